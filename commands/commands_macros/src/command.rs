@@ -13,6 +13,7 @@ struct CommandInfo {
     return_type: ReturnType,
     command_name: String,
     help_text: String,
+    aliases: Vec<String>,
     completers: std::collections::HashMap<String, Ident>, // arg_name -> completer_method_name
 }
 
@@ -52,10 +53,18 @@ fn parse_completer_attribute(attrs: &[syn::Attribute]) -> Result<Option<(Option<
     Ok(None)
 }
 
-/// Parse #[command(name = "...", help = "...")] attributes
-fn parse_command_attribute(attrs: &[syn::Attribute]) -> Result<Option<(String, String)>> {
+/// A parsed #[command(...)] attribute
+struct CommandAttr {
+    name: syn::LitStr,
+    help: String,
+    aliases: Vec<syn::LitStr>,
+}
+
+/// Parse #[command(name = "...", help = "...", aliases = ["..."])] attributes
+fn parse_command_attribute(attrs: &[syn::Attribute]) -> Result<Option<CommandAttr>> {
     let mut command_name = None;
     let mut help_text = String::new();
+    let mut aliases = Vec::new();
 
     for attr in attrs {
         if attr.path().is_ident("command") {
@@ -63,22 +72,32 @@ fn parse_command_attribute(attrs: &[syn::Attribute]) -> Result<Option<(String, S
                 if meta.path.is_ident("name") {
                     let value = meta.value()?;
                     let s: syn::LitStr = value.parse()?;
-                    command_name = Some(s.value());
+                    command_name = Some(s);
                 } else if meta.path.is_ident("help") {
                     let value = meta.value()?;
                     let s: syn::LitStr = value.parse()?;
                     help_text = s.value();
+                } else if meta.path.is_ident("aliases") {
+                    let value = meta.value()?;
+                    let content;
+                    syn::bracketed!(content in value);
+                    let list = syn::punctuated::Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated(&content)?;
+                    aliases.extend(list);
                 }
                 Ok(())
             })?;
         }
     }
 
-    Ok(command_name.map(|name| (name, help_text)))
+    Ok(command_name.map(|name| CommandAttr {
+        name,
+        help: help_text,
+        aliases,
+    }))
 }
 
-/// Parse command attributes to extract name and help text from all methods
-fn parse_command_attrs(impl_block: &ItemImpl) -> Result<Vec<(String, String)>> {
+/// Parse command attributes to extract name, help text and aliases from all methods
+fn parse_command_attrs(impl_block: &ItemImpl) -> Result<Vec<CommandAttr>> {
     impl_block.items.iter()
         .filter_map(|item| {
             if let ImplItem::Fn(method) = item {
@@ -90,6 +109,22 @@ fn parse_command_attrs(impl_block: &ItemImpl) -> Result<Vec<(String, String)>> {
         .collect()
 }
 
+/// Reject names and aliases used more than once within the impl block
+fn check_unique_names(commands: &[CommandAttr]) -> Result<()> {
+    let mut seen = std::collections::HashMap::new();
+    for command in commands {
+        for key in std::iter::once(&command.name).chain(&command.aliases) {
+            if let Some(owner) = seen.insert(key.value(), command.name.value()) {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("\"{}\" is already used by command \"{}\"", key.value(), owner),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Parse argument information from PatType
 fn parse_arg_info(pat_type: &PatType) -> Result<ArgInfo> {
     let name = if let Pat::Ident(pat_ident) = &*pat_type.pat {
@@ -252,6 +287,7 @@ fn generate_command_wrapper(info: &CommandInfo) -> TokenStream {
     let method_name = &info.method_name;
     let command_name = &info.command_name;
     let help_text = &info.help_text;
+    let aliases = &info.aliases;
 
     // Generate wrapper struct name using DRY helper
     let wrapper_name = generate_wrapper_name(self_type, method_name);
@@ -278,7 +314,7 @@ fn generate_command_wrapper(info: &CommandInfo) -> TokenStream {
     });
 
     // Generate metadata method using DRY helper
-    let metadata_impl = generate_metadata_impl(command_name, help_text);
+    let metadata_impl = generate_metadata_impl(command_name, help_text, aliases);
 
     quote! {
         // Zero-sized command struct (no state!) - private, users don't need to know about it
@@ -443,12 +479,13 @@ fn generate_wrapper_name(self_type: &Type, method_name: &Ident) -> Ident {
 }
 
 /// Generate metadata() method implementation
-fn generate_metadata_impl(command_name: &str, help_text: &str) -> TokenStream {
+fn generate_metadata_impl(command_name: &str, help_text: &str, aliases: &[String]) -> TokenStream {
     quote! {
         fn metadata(&self) -> &::commands::CommandMetadata {
             static METADATA: ::commands::CommandMetadata = ::commands::CommandMetadata {
                 name: #command_name,
                 help: #help_text,
+                aliases: &[#(#aliases),*],
             };
             &METADATA
         }
@@ -500,6 +537,7 @@ fn extract_command_info_by_name(
     impl_block: &ItemImpl,
     command_name: &str,
     help_text: &str,
+    aliases: &[String],
 ) -> Result<CommandInfo> {
     let self_type = if let Type::Path(type_path) = &*impl_block.self_ty {
         Type::Path(type_path.clone())
@@ -535,6 +573,7 @@ fn extract_command_info_by_name(
         return_type,
         command_name: command_name.to_string(),
         help_text: help_text.to_string(),
+        aliases: aliases.to_vec(),
         completers,
     })
 }
@@ -586,6 +625,7 @@ fn strip_command_attrs(mut input: ItemImpl) -> ItemImpl {
 pub fn impl_command(input: ItemImpl) -> Result<TokenStream> {
     // Parse command attributes
     let commands = parse_command_attrs(&input)?;
+    check_unique_names(&commands)?;
 
     if commands.is_empty() {
         // No commands found, return original impl block
@@ -602,8 +642,9 @@ pub fn impl_command(input: ItemImpl) -> Result<TokenStream> {
     let mut wrappers = Vec::new();
     let mut command_struct_names = Vec::new();
 
-    for (command_name, help_text) in &commands {
-        let info = extract_command_info_by_name(&input, command_name, help_text)?;
+    for command in &commands {
+        let aliases: Vec<String> = command.aliases.iter().map(syn::LitStr::value).collect();
+        let info = extract_command_info_by_name(&input, &command.name.value(), &command.help, &aliases)?;
         let wrapper = generate_command_wrapper(&info);
         wrappers.push(wrapper);
 
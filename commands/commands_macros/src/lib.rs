@@ -44,8 +44,14 @@ pub fn completable(_args: TokenStream, input: TokenStream) -> TokenStream {
 ///     async fn set_model(&mut self, provider: Provider) -> Result<String, anyhow::Error> {
 ///         // implementation
 ///     }
+///
+///     #[command(name = "quit", help = "Exit", aliases = ["q", "exit"])]
+///     async fn quit(&mut self) {}
 /// }
 /// ```
+///
+/// A name or alias used twice within the block is a compile error; clashes with
+/// commands registered from elsewhere panic in `CommandRegistry::register`.
 #[proc_macro_attribute]
 pub fn commandable(_args: TokenStream, input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemImpl);
@@ -21,6 +21,9 @@ pub struct CommandMetadata {
 
     /// Help text describing the command
     pub help: &'static str,
+
+    /// Alternative names that invoke the same command (e.g. "q" for "quit")
+    pub aliases: &'static [&'static str],
 }
 
 /// Trait for executable commands that support completion
@@ -14,6 +14,8 @@ pub trait Registrable<R> {
 /// Registry for managing and dispatching commands for type T
 pub struct CommandRegistry<T> {
     commands: HashMap<String, Box<dyn Command<T>>>,
+    /// Alias -> canonical command name
+    aliases: HashMap<String, String>,
 }
 
 impl<T> CommandRegistry<T> {
@@ -21,26 +23,58 @@ impl<T> CommandRegistry<T> {
     pub fn new() -> Self {
         Self {
             commands: HashMap::new(),
+            aliases: HashMap::new(),
         }
     }
 
-    /// Register a command instance
+    /// Register a command instance under its name and aliases
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name or an alias is already taken by another command.
     pub fn register<C>(&mut self, command: C)
     where
         C: Command<T> + 'static,
     {
-        let name = command.metadata().name.to_string();
+        let metadata = command.metadata();
+        let name = metadata.name.to_string();
+
+        for key in std::iter::once(metadata.name).chain(metadata.aliases.iter().copied()) {
+            if let Some(existing) = self.canonical_name(key) {
+                panic!(
+                    "Cannot register /{}: \"{}\" is already used by /{}",
+                    name, key, existing
+                );
+            }
+        }
+
+        for alias in metadata.aliases {
+            self.aliases.insert(alias.to_string(), name.clone());
+        }
         self.commands.insert(name, Box::new(command));
     }
 
+    /// Canonical name of the command registered under `name` or as an alias
+    fn canonical_name<'s>(&'s self, name: &'s str) -> Option<&'s str> {
+        if self.commands.contains_key(name) {
+            return Some(name);
+        }
+        self.aliases.get(name).map(String::as_str)
+    }
+
+    /// Look up a command by name or alias
+    fn resolve(&self, name: &str) -> Option<&dyn Command<T>> {
+        let name = self.canonical_name(name)?;
+        self.commands.get(name).map(|cmd| cmd.as_ref())
+    }
+
     /// Execute a command with mutable context
     pub async fn execute<'a>(&self, mut context: ContextMut<'a, T>) -> Result<CommandResult, CommandError> {
         let cmd_name = context.stream().command_name()
             .ok_or_else(|| CommandError::InvalidArgs("Commands must start with /".to_string()))?;
 
         let command = self
-            .commands
-            .get(cmd_name)
+            .resolve(cmd_name)
             .ok_or_else(|| CommandError::UnknownCommand(cmd_name.to_string()))?;
 
         // Extract just the arguments and create a new TokenStream with quoted parsing
@@ -56,31 +90,37 @@ impl<T> CommandRegistry<T> {
         &self, ctx: &Context<'a, T>,
     ) -> Result<Vec<Completion>, CompletionError> {
         let command_name = ctx.stream().command_name();
+        // Still typing the command name if nothing follows it yet
+        let typing_name = !ctx.stream().input().trim_start().contains(char::is_whitespace);
         // Try to complete command arguments if we have a valid command
-        if let Some(cmd_name) = command_name {
-            if let Some(command) = self.commands.get(cmd_name) {
+        if let Some(cmd_name) = command_name.filter(|_| !typing_name) {
+            if let Some(command) = self.resolve(cmd_name) {
                 return command.complete(ctx).await;
             }
         }
 
-        // Fall through: complete command names
+        // Fall through: complete command names, offering the canonical name
+        // when only an alias matches
         let partial = command_name.unwrap_or("");
         Ok(self
             .commands
-            .keys()
-            .filter(|name| name.starts_with(partial))
-            .map(|name| Completion::simple(name.as_str()))
+            .iter()
+            .filter(|(name, command)| {
+                name.starts_with(partial)
+                    || command.metadata().aliases.iter().any(|alias| alias.starts_with(partial))
+            })
+            .map(|(name, _)| Completion::simple(name.as_str()))
             .collect())
     }
 
-    /// Get list of all registered command names
+    /// Get list of all registered command names (without aliases)
     pub fn command_names(&self) -> Vec<&str> {
         self.commands.keys().map(|s| s.as_str()).collect()
     }
 
-    /// Get metadata for a command
+    /// Get metadata for a command by name or alias
     pub fn get_metadata(&self, name: &str) -> Option<&crate::command::CommandMetadata> {
-        self.commands.get(name).map(|cmd| cmd.metadata())
+        self.resolve(name).map(|cmd| cmd.metadata())
     }
 }
 
@@ -166,7 +166,7 @@ impl CompleterTestApp {
         Ok(models
             .into_iter()
             .filter(|m| m.starts_with(partial))
-            .map(|m| commands::Completion::simple(m))
+            .map(commands::Completion::simple)
             .collect())
     }
 }
@@ -318,4 +318,62 @@ async fn test_register_all_commands_helper() {
     let ctx = ContextMut::new(TokenStream::new("/set provider1".to_string()), &mut app);
     let result = registry.execute(ctx).await.unwrap();
     assert!(matches!(result, commands::CommandResult::Success(_)));
-}
\ No newline at end of file
+}
+struct AliasApp {
+    cleared: bool,
+}
+
+#[commandable]
+impl AliasApp {
+    #[command(name = "quit", help = "Exit the app", aliases = ["q", "exit"])]
+    async fn quit(&mut self) -> Result<String, anyhow::Error> {
+        Ok("bye".to_string())
+    }
+
+    #[command(name = "clear", help = "Clear the screen", aliases = ["cls"])]
+    async fn clear_screen(&mut self) {
+        self.cleared = true;
+    }
+}
+
+#[tokio::test]
+async fn test_alias_executes_command() {
+    let mut app = AliasApp { cleared: false };
+    let mut registry = CommandRegistry::new();
+    AliasApp::register(&mut registry);
+
+    let ctx = ContextMut::new(TokenStream::new("/cls".to_string()), &mut app);
+    registry.execute(ctx).await.unwrap();
+    assert!(app.cleared);
+
+    let ctx = ContextMut::new(TokenStream::new("/exit".to_string()), &mut app);
+    let result = registry.execute(ctx).await.unwrap();
+    assert!(matches!(result, commands::CommandResult::Success(msg) if msg == "bye"));
+
+    // Aliases resolve to the command's metadata but are not listed as commands
+    assert_eq!(registry.get_metadata("q").unwrap().name, "quit");
+    assert_eq!(registry.command_names().len(), 2);
+}
+
+#[tokio::test]
+async fn test_alias_completes_to_canonical_name() {
+    let app = AliasApp { cleared: false };
+    let mut registry = CommandRegistry::new();
+    AliasApp::register(&mut registry);
+
+    let completions = registry.complete(&commands::Context::new("/q", &app)).await.unwrap();
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].value, "quit");
+
+    let completions = registry.complete(&commands::Context::new("/c", &app)).await.unwrap();
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].value, "clear");
+}
+
+#[test]
+#[should_panic(expected = "is already used by /quit")]
+fn test_alias_collision_panics_on_registration() {
+    let mut registry: CommandRegistry<AliasApp> = CommandRegistry::new();
+    AliasApp::register(&mut registry);
+    commands::register_commands!(registry, AliasApp => [quit]);
+}
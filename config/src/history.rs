@@ -0,0 +1,165 @@
+//! Input history persisted across sessions
+
+use crate::PathManager;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// How many entries are kept by default
+pub const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Previously submitted chat messages and commands, oldest first
+///
+/// Stored one entry per line, with newlines and backslashes escaped.
+#[derive(Debug, Clone)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+    max_entries: usize,
+}
+
+impl InputHistory {
+    /// Load history from the default history file
+    pub fn load() -> Self {
+        Self::open(PathManager::input_history_path(), MAX_HISTORY_ENTRIES)
+    }
+
+    /// Load history from `path`, keeping at most `max_entries`.
+    /// Without a path, history is kept in memory only.
+    pub fn open(path: Option<PathBuf>, max_entries: usize) -> Self {
+        let entries = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .map(|content| content.lines().map(unescape).collect())
+            .unwrap_or_default();
+
+        let mut history = Self {
+            entries,
+            path,
+            max_entries,
+        };
+        if history.entries.len() > max_entries {
+            history.trim();
+            // Best effort: the file is rewritten again on the next push
+            let _ = history.rewrite();
+        }
+        history
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Add an entry and append it to the history file.
+    /// Blank entries and repeats of the latest entry are ignored.
+    pub fn push(&mut self, entry: &str) -> Result<(), String> {
+        if entry.trim().is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return Ok(());
+        }
+
+        self.entries.push(entry.to_string());
+        if self.entries.len() > self.max_entries {
+            self.trim();
+            return self.rewrite();
+        }
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create history dir: {}", e))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open history file: {}", e))?;
+        writeln!(file, "{}", escape(entry)).map_err(|e| format!("Failed to write history: {}", e))
+    }
+
+    /// Drop the oldest entries beyond the cap
+    fn trim(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.max_entries);
+        self.entries.drain(..excess);
+    }
+
+    /// Replace the history file with the current entries
+    fn rewrite(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let content: String = self
+            .entries
+            .iter()
+            .map(|entry| format!("{}\n", escape(entry)))
+            .collect();
+        fs::write(path, content).map_err(|e| format!("Failed to write history: {}", e))
+    }
+}
+
+fn escape(entry: &str) -> String {
+    entry.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                result.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                result.push('\\');
+                chars.next();
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_history_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("noema-history-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("history")
+    }
+
+    #[test]
+    fn test_history_survives_reload() {
+        let path = temp_history_path("reload");
+        let mut history = InputHistory::open(Some(path.clone()), 10);
+        history.push("first").unwrap();
+        history.push("first").unwrap();
+        history.push("   ").unwrap();
+        history.push("two\nlines with a \\n literal").unwrap();
+        history.push("/model gpt-4o").unwrap();
+        history.push("first").unwrap();
+
+        let reloaded = InputHistory::open(Some(path), 10);
+        assert_eq!(
+            reloaded.entries(),
+            ["first", "two\nlines with a \\n literal", "/model gpt-4o", "first"]
+        );
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let path = temp_history_path("capped");
+        let mut history = InputHistory::open(Some(path.clone()), 3);
+        for entry in ["a", "b", "c", "d", "e"] {
+            history.push(entry).unwrap();
+        }
+        assert_eq!(history.entries(), ["c", "d", "e"]);
+        assert_eq!(InputHistory::open(Some(path.clone()), 3).entries(), ["c", "d", "e"]);
+
+        // A smaller cap trims an existing file on load
+        assert_eq!(InputHistory::open(Some(path), 2).entries(), ["d", "e"]);
+    }
+}
@@ -0,0 +1,112 @@
+//! API keys and the storage encryption key in the OS keychain
+//!
+//! Keys are stored under the "noema" service with the provider name as the
+//! account: Keychain on macOS, Credential Manager on Windows and the Secret
+//! Service (GNOME Keyring, KWallet) on Linux. When no keychain is
+//! available, `Settings::set_api_key` falls back to encrypting the key into
+//! settings.toml.
+//!
+//! Errors never include the key itself.
+
+/// Keychain account holding the hex-encoded storage encryption key
+const STORAGE_KEY_ACCOUNT: &str = "storage-encryption-key";
+
+/// Read a provider's API key from the keychain
+///
+/// Returns None when there is no entry or no keychain.
+pub fn get_api_key(provider: &str) -> Option<String> {
+    store::get(provider)
+}
+
+/// Store a provider's API key in the keychain, replacing any previous one
+pub fn set_api_key(provider: &str, api_key: &str) -> Result<(), String> {
+    store::set(provider, api_key)
+        .map_err(|e| format!("Failed to store the {} API key in the system keychain: {}", provider, e))
+}
+
+/// Remove a provider's API key from the keychain (Ok if there was none, or
+/// no keychain)
+pub fn remove_api_key(provider: &str) -> Result<(), String> {
+    store::remove(provider)
+        .map_err(|e| format!("Failed to remove the {} API key from the system keychain: {}", provider, e))
+}
+
+/// Read the hex-encoded storage encryption key from the keychain
+pub fn get_storage_key() -> Option<String> {
+    store::get(STORAGE_KEY_ACCOUNT)
+}
+
+/// Store the hex-encoded storage encryption key in the keychain
+///
+/// There is no settings.toml fallback: a key stored next to the data it
+/// protects would defeat the encryption.
+pub fn set_storage_key(hex_key: &str) -> Result<(), String> {
+    store::set(STORAGE_KEY_ACCOUNT, hex_key)
+        .map_err(|e| format!("Failed to store the storage encryption key in the system keychain: {}", e))
+}
+
+#[cfg(not(test))]
+mod store {
+    use keyring::{Entry, Error};
+
+    const SERVICE: &str = "noema";
+
+    pub(super) fn get(provider: &str) -> Option<String> {
+        Entry::new(SERVICE, provider).ok()?.get_password().ok()
+    }
+
+    pub(super) fn set(provider: &str, api_key: &str) -> Result<(), Error> {
+        Entry::new(SERVICE, provider)?.set_password(api_key)
+    }
+
+    /// Without a reachable keychain there is no entry to remove
+    pub(super) fn remove(provider: &str) -> Result<(), Error> {
+        let entry = match Entry::new(SERVICE, provider) {
+            Ok(entry) => entry,
+            Err(Error::NoStorageAccess(_) | Error::PlatformFailure(_)) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        match entry.delete_credential() {
+            Ok(())
+            | Err(Error::NoEntry | Error::NoStorageAccess(_) | Error::PlatformFailure(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// In-memory keychain so tests never touch the real one
+#[cfg(test)]
+pub(crate) mod store {
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+
+    thread_local! {
+        static ENTRIES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+        static AVAILABLE: Cell<bool> = const { Cell::new(true) };
+    }
+
+    /// Simulate a machine with or without a keychain
+    pub(crate) fn set_available(available: bool) {
+        AVAILABLE.with(|a| a.set(available));
+    }
+
+    pub(super) fn get(provider: &str) -> Option<String> {
+        ENTRIES.with(|e| e.borrow().get(provider).cloned())
+    }
+
+    pub(super) fn set(provider: &str, api_key: &str) -> Result<(), String> {
+        if !AVAILABLE.with(Cell::get) {
+            return Err("no secret service".to_string());
+        }
+        ENTRIES.with(|e| e.borrow_mut().insert(provider.to_string(), api_key.to_string()));
+        Ok(())
+    }
+
+    pub(super) fn remove(provider: &str) -> Result<(), String> {
+        if !AVAILABLE.with(Cell::get) {
+            return Err("no secret service".to_string());
+        }
+        ENTRIES.with(|e| e.borrow_mut().remove(provider));
+        Ok(())
+    }
+}
@@ -1,10 +1,15 @@
 pub mod crypto;
+pub mod history;
+pub mod keychain;
 pub mod paths;
+pub mod redact;
 pub mod settings;
 
 pub use crypto::{decrypt_string, encrypt_string};
+pub use history::InputHistory;
 pub use paths::PathManager;
-pub use settings::Settings;
+pub use redact::redact;
+pub use settings::{ApiKeyStorage, CompatibleProvider, ProviderRateLimit, Settings};
 
 /// Load environment variables from .env files.
 /// First loads from ~/.env (home directory), then from ./.env (project directory).
@@ -4,6 +4,9 @@ use std::sync::OnceLock;
 static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
 static LOG_FILE_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
 
+/// Whisper model used when none is configured (English-only)
+pub const DEFAULT_WHISPER_MODEL: &str = "ggml-base.en.bin";
+
 pub struct PathManager;
 
 impl PathManager {
@@ -74,6 +77,16 @@ impl PathManager {
         Self::config_subdir().map(|d| d.join("settings.toml"))
     }
 
+    /// Path to the per-provider base URL and API key overrides
+    pub fn providers_config_path() -> Option<PathBuf> {
+        Self::config_subdir().map(|d| d.join("providers.toml"))
+    }
+
+    /// Path to the user's conversation templates
+    pub fn templates_path() -> Option<PathBuf> {
+        Self::config_subdir().map(|d| d.join("templates.toml"))
+    }
+
     /// Path to the secrets environment file
     pub fn env_path() -> Option<PathBuf> {
         Self::config_subdir().map(|d| d.join(".env"))
@@ -91,12 +104,44 @@ impl PathManager {
         Self::logs_dir().map(|d| d.join("noema.log"))
     }
 
+    /// JSONL file of raw provider requests and responses (see `NOEMA_TRAFFIC_LOG`)
+    pub fn traffic_log_path() -> Option<PathBuf> {
+        Self::logs_dir().map(|d| d.join("traffic.jsonl"))
+    }
+
+    /// JSONL audit log of MCP tool calls (when enabled in mcp.toml)
+    pub fn tool_audit_log_path() -> Option<PathBuf> {
+        Self::logs_dir().map(|d| d.join("tool_calls.jsonl"))
+    }
+
+    /// Cached model lists of each provider (see `llm::catalog`)
+    pub fn model_catalog_path() -> Option<PathBuf> {
+        Self::cache_dir().map(|d| d.join("models.json"))
+    }
+
     pub fn models_dir() -> Option<PathBuf> {
         Self::data_dir().map(|d| d.join("models"))
     }
 
     pub fn whisper_model_path() -> Option<PathBuf> {
-        Self::models_dir().map(|d| d.join("ggml-base.en.bin"))
+        Self::whisper_model_path_for(DEFAULT_WHISPER_MODEL)
+    }
+
+    /// Path of a Whisper model file (e.g. "ggml-base.bin") in the models dir
+    ///
+    /// None for names that aren't a plain file name (empty, or containing a
+    /// path separator or `..`), so a configured model can't point outside
+    /// the models dir.
+    pub fn whisper_model_path_for(filename: &str) -> Option<PathBuf> {
+        if filename.is_empty() || filename.contains(['/', '\\']) || filename.contains("..") {
+            return None;
+        }
+        Self::models_dir().map(|d| d.join(filename))
+    }
+
+    /// Path to the chat input history file
+    pub fn input_history_path() -> Option<PathBuf> {
+        Self::data_dir().map(|d| d.join("history"))
     }
 
     pub fn mcp_config_path() -> Option<PathBuf> {
@@ -128,3 +173,21 @@ impl PathManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whisper_model_path_rejects_paths() {
+        for name in ["", "../ggml-base.bin", "models/ggml-base.bin", "..\\ggml-base.bin", ".."] {
+            assert_eq!(PathManager::whisper_model_path_for(name), None, "{}", name);
+        }
+        if let Some(models) = PathManager::models_dir() {
+            assert_eq!(
+                PathManager::whisper_model_path_for("ggml-base.en.bin"),
+                Some(models.join("ggml-base.en.bin"))
+            );
+        }
+    }
+}
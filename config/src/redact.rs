@@ -0,0 +1,70 @@
+//! Masking of credentials in log lines and error messages
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Replacement for masked values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// `Bearer <token>`, as in authorization headers
+static BEARER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(bearer\s+)[A-Za-z0-9\-._~+/]{8,}=*").unwrap());
+
+/// Secret-named fields in JSON, form/query strings and Debug output:
+/// `"access_token":"x"`, `client_secret=x`, `api_key: Some("x")`
+static SECRET_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)((?:client_secret|access_token|refresh_token|id_token|code_verifier|api[_-]?key|x-api-key|password)"?\s*[:=]\s*(?:Some\()?"?)[^"&,;\s})]+"#,
+    )
+    .unwrap()
+});
+
+/// Mask bearer tokens and secret-named values in `s`
+pub fn redact(s: &str) -> String {
+    let s = BEARER.replace_all(s, format!("${{1}}{}", REDACTED));
+    SECRET_FIELD
+        .replace_all(&s, |caps: &regex::Captures| match &caps[0][caps[1].len()..] {
+            // Absent values (`access_token: None`) reveal nothing
+            "None" | "null" => caps[0].to_string(),
+            _ => format!("{}{}", &caps[1], REDACTED),
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_log_lines() {
+        let cases = [
+            (
+                "Authorization: Bearer eyJhbGciOi.J9-x_y==",
+                "Authorization: Bearer [REDACTED]",
+            ),
+            (
+                r#"Token exchange failed: {"error":"invalid_grant","access_token":"abc","refresh_token": "def"}"#,
+                r#"Token exchange failed: {"error":"invalid_grant","access_token":"[REDACTED]","refresh_token": "[REDACTED]"}"#,
+            ),
+            (
+                "POST /token grant_type=refresh_token&client_secret=s3cr3t&client_id=noema",
+                "POST /token grant_type=refresh_token&client_secret=[REDACTED]&client_id=noema",
+            ),
+            (
+                r#"OAuth { client_id: "noema", client_secret: Some("s3cr3t"), access_token: None }"#,
+                r#"OAuth { client_id: "noema", client_secret: Some("[REDACTED]"), access_token: None }"#,
+            ),
+            ("OPENAI_API_KEY=sk-123 started", "OPENAI_API_KEY=[REDACTED] started"),
+            ("x-api-key: sk-ant-123", "x-api-key: [REDACTED]"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(redact(input), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_redact_leaves_plain_text() {
+        let line = "OAuth complete for 'github', bearer of good news, refresh_token grant";
+        assert_eq!(redact(line), line);
+    }
+}
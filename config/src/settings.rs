@@ -1,6 +1,7 @@
 //! Application settings management
 
-use crate::{crypto, PathManager};
+use crate::paths::DEFAULT_WHISPER_MODEL;
+use crate::{crypto, keychain, PathManager};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -12,12 +13,102 @@ pub struct Settings {
     pub user_email: Option<String>,
     /// Default model ID (e.g., "claude/models/claude-sonnet-4-5-20250929")
     pub default_model: Option<String>,
-    /// Encrypted API keys (provider name -> encrypted key)
+    /// Encrypted API keys (provider name -> encrypted key), used when no
+    /// system keychain is available
     #[serde(default)]
     pub api_keys: HashMap<String, String>,
     /// Favorite model IDs for quick access (e.g., ["claude/claude-sonnet-4-5", "openai/gpt-4o"])
     #[serde(default)]
     pub favorite_models: Vec<String>,
+    /// User-defined OpenAI-compatible endpoints (provider name -> endpoint config),
+    /// e.g. `[compatible_providers.deepseek]`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub compatible_providers: HashMap<String, CompatibleProvider>,
+    /// Client-side request limits (provider name -> limit), e.g. `[rate_limits.claude]`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rate_limits: HashMap<String, ProviderRateLimit>,
+    /// Whisper model filename in the models dir (defaults to the English-only base model)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub whisper_model: Option<String>,
+    /// Spoken language for transcription (e.g. "de"); auto-detected when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub whisper_language: Option<String>,
+    /// Only send voice input that starts with this phrase (e.g. "hey noema")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice_wake_word: Option<String>,
+    /// Milliseconds without speech before an utterance is sent anyway (0 waits
+    /// for the end of speech); the voice default when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice_silence_timeout_ms: Option<u64>,
+    /// Name of the microphone to capture voice from; the system default when
+    /// unset or no longer connected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice_input_device: Option<String>,
+    /// Speech detection preset for the room: "quiet", "normal" (default) or "noisy"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice_sensitivity: Option<String>,
+    /// How strictly a speech onset must stay above the threshold (0-3),
+    /// overriding the preset's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice_vad_aggressiveness: Option<u8>,
+    /// Download Ollama models on first use instead of failing when the
+    /// server doesn't have them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ollama_auto_pull: Option<bool>,
+    /// Seconds a provider request may take before it is abandoned (0 waits
+    /// indefinitely); the client default when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_timeout_secs: Option<u64>,
+    /// Seconds a streamed response may go without data before it is
+    /// abandoned (0 waits indefinitely); the client default when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_idle_timeout_secs: Option<u64>,
+    /// Have the model title untitled conversations after their first reply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_name_conversations: Option<bool>,
+    /// Summarize old history once a conversation is estimated above this
+    /// many tokens; only on request when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compaction_threshold_tokens: Option<usize>,
+    /// Model (provider/model) that writes history summaries; the
+    /// conversation's own model when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compaction_summary_model: Option<String>,
+    /// Models (provider/model) MCP servers may pick by hint when sampling;
+    /// other hints get the current model
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sampling_models: Vec<String>,
+}
+
+/// Where `Settings::set_api_key` put a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyStorage {
+    /// The OS keychain
+    Keychain,
+    /// Encrypted in settings.toml, because no keychain was available
+    EncryptedFile,
+}
+
+/// An OpenAI-compatible chat endpoint (DeepSeek, Together, vLLM, ...)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompatibleProvider {
+    /// Base URL including the version path, e.g. "https://api.deepseek.com/v1"
+    pub base_url: String,
+    /// Environment variable holding the API key (omit for unauthenticated endpoints)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// Models to offer when the endpoint has no `/models` listing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+}
+
+/// Request rate allowed for one provider; calls beyond it are delayed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderRateLimit {
+    pub requests_per_minute: u32,
+    /// Requests that may be sent back to back after a quiet period (default 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>,
 }
 
 impl Settings {
@@ -49,32 +140,51 @@ impl Settings {
         Ok(())
     }
 
-    /// Get a decrypted API key for a provider.
+    /// Get an API key for a provider: from the system keychain, else the
+    /// encrypted copy in settings.toml.
     /// Returns None if not set or decryption fails.
     pub fn get_api_key(&self, provider: &str) -> Option<String> {
-        self.api_keys
-            .get(provider)
-            .and_then(|encrypted| crypto::decrypt_string(encrypted).ok())
+        keychain::get_api_key(provider).or_else(|| {
+            self.api_keys
+                .get(provider)
+                .and_then(|encrypted| crypto::decrypt_string(encrypted).ok())
+        })
     }
 
-    /// Set an API key for a provider (encrypts before storing).
-    pub fn set_api_key(&mut self, provider: &str, api_key: &str) -> Result<(), String> {
+    /// Set an API key for a provider.
+    ///
+    /// The key goes into the system keychain when one is available (and any
+    /// copy in settings.toml is dropped); otherwise it is encrypted into
+    /// settings.toml. Returns where it was stored; call `save` afterwards.
+    pub fn set_api_key(&mut self, provider: &str, api_key: &str) -> Result<ApiKeyStorage, String> {
+        if keychain::set_api_key(provider, api_key).is_ok() {
+            self.api_keys.remove(provider);
+            return Ok(ApiKeyStorage::Keychain);
+        }
         let encrypted = crypto::encrypt_string(api_key)?;
         self.api_keys.insert(provider.to_string(), encrypted);
-        Ok(())
+        Ok(ApiKeyStorage::EncryptedFile)
     }
 
-    /// Remove an API key for a provider.
-    pub fn remove_api_key(&mut self, provider: &str) {
-        self.api_keys.remove(provider);
+    /// Remove an API key for a provider from both the keychain and settings.toml.
+    ///
+    /// A keychain error doesn't count when the key was in settings.toml: a
+    /// key only lands there when the keychain couldn't take it.
+    pub fn remove_api_key(&mut self, provider: &str) -> Result<(), String> {
+        let in_file = self.api_keys.remove(provider).is_some();
+        match keychain::remove_api_key(provider) {
+            Err(_) if in_file => Ok(()),
+            result => result,
+        }
     }
 
     /// Check if an API key is set for a provider.
     pub fn has_api_key(&self, provider: &str) -> bool {
-        self.api_keys.contains_key(provider)
+        self.api_keys.contains_key(provider) || keychain::get_api_key(provider).is_some()
     }
 
-    /// Get the list of providers with configured API keys.
+    /// Get the list of providers with API keys in settings.toml.
+    /// Keys in the system keychain can't be listed; use `has_api_key`.
     pub fn configured_providers(&self) -> Vec<String> {
         self.api_keys.keys().cloned().collect()
     }
@@ -99,4 +209,135 @@ impl Settings {
     pub fn is_favorite_model(&self, model_id: &str) -> bool {
         self.favorite_models.iter().any(|m| m == model_id)
     }
+
+    /// Get the configured Whisper model filename, or the default one.
+    pub fn whisper_model(&self) -> &str {
+        self.whisper_model.as_deref().unwrap_or(DEFAULT_WHISPER_MODEL)
+    }
+
+    /// Whether missing Ollama models are pulled automatically (off by default).
+    pub fn ollama_auto_pull(&self) -> bool {
+        self.ollama_auto_pull.unwrap_or(false)
+    }
+
+    /// Whether untitled conversations are named automatically (off by default).
+    pub fn auto_name_conversations(&self) -> bool {
+        self.auto_name_conversations.unwrap_or(false)
+    }
+
+    /// Get a user-defined OpenAI-compatible provider by name.
+    pub fn get_compatible_provider(&self, name: &str) -> Option<&CompatibleProvider> {
+        self.compatible_providers.get(name)
+    }
+
+    /// Get the rate limit configured for a provider, if any.
+    pub fn get_rate_limit(&self, provider: &str) -> Option<&ProviderRateLimit> {
+        self.rate_limits
+            .get(provider)
+            .filter(|limit| limit.requests_per_minute > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compatible_providers_parse() {
+        let settings: Settings = toml::from_str(
+            r#"
+            [compatible_providers.deepseek]
+            base_url = "https://api.deepseek.com/v1"
+            api_key_env = "DEEPSEEK_API_KEY"
+            models = ["deepseek-chat", "deepseek-reasoner"]
+
+            [compatible_providers.vllm]
+            base_url = "http://localhost:8000/v1"
+            "#,
+        )
+        .unwrap();
+
+        let deepseek = settings.get_compatible_provider("deepseek").unwrap();
+        assert_eq!(deepseek.api_key_env.as_deref(), Some("DEEPSEEK_API_KEY"));
+        assert_eq!(deepseek.models, vec!["deepseek-chat", "deepseek-reasoner"]);
+
+        let vllm = settings.get_compatible_provider("vllm").unwrap();
+        assert_eq!(vllm.api_key_env, None);
+        assert!(vllm.models.is_empty());
+        assert!(settings.get_compatible_provider("together").is_none());
+    }
+
+    #[test]
+    fn test_rate_limits_parse() {
+        let settings: Settings = toml::from_str(
+            r#"
+            [rate_limits.claude]
+            requests_per_minute = 50
+            burst = 5
+
+            [rate_limits.openai]
+            requests_per_minute = 0
+            "#,
+        )
+        .unwrap();
+
+        let claude = settings.get_rate_limit("claude").unwrap();
+        assert_eq!(claude.requests_per_minute, 50);
+        assert_eq!(claude.burst, Some(5));
+        // A zero limit disables limiting rather than blocking every request
+        assert!(settings.get_rate_limit("openai").is_none());
+        assert!(settings.get_rate_limit("gemini").is_none());
+    }
+
+    #[test]
+    fn test_whisper_settings_default() {
+        let settings = Settings::default();
+        assert_eq!(settings.whisper_model(), DEFAULT_WHISPER_MODEL);
+        assert_eq!(settings.whisper_language, None);
+
+        let settings: Settings = toml::from_str(
+            r#"
+            whisper_model = "ggml-small.bin"
+            whisper_language = "de"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(settings.whisper_model(), "ggml-small.bin");
+        assert_eq!(settings.whisper_language.as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn test_api_key_prefers_keychain() {
+        keychain::store::set_available(true);
+        let mut settings = Settings::default();
+        assert_eq!(settings.set_api_key("claude", "sk-ant-123").unwrap(), ApiKeyStorage::Keychain);
+        assert!(settings.api_keys.is_empty());
+        assert!(settings.has_api_key("claude"));
+        assert_eq!(settings.get_api_key("claude").as_deref(), Some("sk-ant-123"));
+
+        settings.remove_api_key("claude").unwrap();
+        assert!(!settings.has_api_key("claude"));
+        assert_eq!(settings.get_api_key("claude"), None);
+    }
+
+    #[test]
+    fn test_api_key_falls_back_to_encrypted_file() {
+        keychain::store::set_available(false);
+        let mut settings = Settings::default();
+        assert_eq!(settings.set_api_key("openai", "sk-456").unwrap(), ApiKeyStorage::EncryptedFile);
+        let stored = settings.api_keys.get("openai").unwrap();
+        assert_ne!(stored, "sk-456");
+        assert_eq!(settings.get_api_key("openai").as_deref(), Some("sk-456"));
+
+        // Removing it doesn't need the keychain
+        settings.remove_api_key("openai").unwrap();
+        assert!(!settings.has_api_key("openai"));
+        settings.set_api_key("openai", "sk-456").unwrap();
+
+        // A key moved into the keychain later replaces the file copy
+        keychain::store::set_available(true);
+        assert_eq!(settings.set_api_key("openai", "sk-789").unwrap(), ApiKeyStorage::Keychain);
+        assert!(settings.api_keys.is_empty());
+        assert_eq!(settings.get_api_key("openai").as_deref(), Some("sk-789"));
+    }
 }
@@ -6,7 +6,7 @@
 use crate::traits::AudioStreamer;
 use crate::types::SpeechEvent;
 use crate::utils::resample_to_16khz;
-use crate::vad::VoiceActivityDetector;
+use crate::vad::{VadConfig, VoiceActivityDetector};
 use anyhow::Result;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
@@ -22,9 +22,15 @@ pub struct BrowserAudioController {
 
 impl BrowserAudioController {
     pub fn new(sample_rate: u32) -> Self {
+        Self::with_vad_config(sample_rate, VadConfig::default())
+    }
+
+    /// Controller detecting speech with the given sensitivity
+    pub fn with_vad_config(sample_rate: u32, config: VadConfig) -> Self {
         Self {
             sender: Arc::new(Mutex::new(None)),
-            vad: Arc::new(Mutex::new(VoiceActivityDetector::new(16000))), // Always processing at 16kHz
+            // Always processing at 16kHz
+            vad: Arc::new(Mutex::new(VoiceActivityDetector::with_config(16000, config))),
             sample_rate,
         }
     }
@@ -84,7 +90,15 @@ impl AudioStreamer for BrowserAudioStreamer {
 
 /// Create a paired Controller and Streamer
 pub fn create_browser_backend(sample_rate: u32) -> (BrowserAudioController, BrowserAudioStreamer) {
-    let controller = BrowserAudioController::new(sample_rate);
+    create_browser_backend_with_vad(sample_rate, VadConfig::default())
+}
+
+/// Create a paired Controller and Streamer detecting speech with `config`
+pub fn create_browser_backend_with_vad(
+    sample_rate: u32,
+    config: VadConfig,
+) -> (BrowserAudioController, BrowserAudioStreamer) {
+    let controller = BrowserAudioController::with_vad_config(sample_rate, config);
     let streamer = BrowserAudioStreamer::new(controller.clone());
     (controller, streamer)
 }
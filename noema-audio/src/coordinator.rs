@@ -1,21 +1,187 @@
+use std::time::{Duration, Instant};
+
+use crate::tts::{speakable_text, Speaker, Tts};
 use crate::{VoiceAgent, VoiceEvent};
 
+/// How long the wake word stays open for follow-up utterances by default
+pub const DEFAULT_WAKE_WINDOW: Duration = Duration::from_secs(10);
+
+/// How long an utterance may go without speech before it is sent anyway
+pub const DEFAULT_SILENCE_TIMEOUT: Duration = Duration::from_millis(800);
+
 pub struct VoiceCoordinator {
     agent: VoiceAgent,
     pending_messages: Vec<String>,
     is_listening: bool,
     is_transcribing: bool,
     is_buffering: bool,
+    partial: Option<String>,
+    wake_gate: Option<WakeWordGate>,
+    speaker: Option<Speaker>,
+    speak_responses: bool,
+}
+
+/// Forwards only utterances that start with the wake phrase (stripped), plus
+/// follow-ups within a window after the last forwarded utterance
+struct WakeWordGate {
+    /// Normalized words of the wake phrase
+    phrase: Vec<String>,
+    window: Duration,
+    open_until: Option<Instant>,
+}
+
+impl WakeWordGate {
+    fn new(phrase: &str, window: Duration) -> Self {
+        Self {
+            phrase: phrase.split_whitespace().map(normalize_word).collect(),
+            window,
+            open_until: None,
+        }
+    }
+
+    /// Return the text to forward, if any
+    fn filter(&mut self, text: &str, now: Instant) -> Option<String> {
+        let message = match self.strip_phrase(text) {
+            Some(rest) => rest,
+            None if self.open_until.is_some_and(|until| now < until) => text.trim().to_string(),
+            None => return None,
+        };
+
+        // Saying just the wake phrase opens the window without sending anything
+        self.open_until = Some(now + self.window);
+        (!message.is_empty()).then_some(message)
+    }
+
+    /// Like `filter`, but for provisional text: never opens the window
+    fn preview(&self, text: &str, now: Instant) -> Option<String> {
+        match self.strip_phrase(text) {
+            Some(rest) => Some(rest),
+            None if self.open_until.is_some_and(|until| now < until) => Some(text.trim().to_string()),
+            None => None,
+        }
+    }
+
+    /// Text after the wake phrase if the utterance starts with it
+    fn strip_phrase(&self, text: &str) -> Option<String> {
+        if self.phrase.is_empty() {
+            return Some(text.trim().to_string());
+        }
+
+        let mut rest = text;
+        for expected in &self.phrase {
+            let trimmed = rest.trim_start_matches(|c: char| !c.is_alphanumeric());
+            let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            let (word, tail) = trimmed.split_at(end);
+            if normalize_word(word) != *expected {
+                return None;
+            }
+            rest = tail;
+        }
+
+        // Drop punctuation Whisper puts after the phrase ("Hey Noema, ...")
+        let rest = rest.trim_start_matches(|c: char| !c.is_alphanumeric());
+        Some(rest.trim_end().to_string())
+    }
+}
+
+/// Lowercase a word and drop surrounding punctuation
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
 }
 
 impl VoiceCoordinator {
     pub fn new(agent: VoiceAgent) -> Self {
+        agent.set_silence_timeout(Some(DEFAULT_SILENCE_TIMEOUT));
         Self {
             agent,
             pending_messages: Vec::new(),
             is_listening: false,
             is_transcribing: false,
             is_buffering: false,
+            partial: None,
+            wake_gate: None,
+            speaker: None,
+            speak_responses: false,
+        }
+    }
+
+    /// Speech engine for reading responses aloud (see `set_speak_responses`)
+    pub fn with_tts(mut self, tts: impl Tts + 'static) -> Self {
+        self.speaker = Some(Speaker::new(tts));
+        self
+    }
+
+    /// Read completed assistant responses aloud via `speak_response`
+    ///
+    /// Speech is cut off as soon as the user starts talking.
+    pub fn set_speak_responses(&mut self, enabled: bool) {
+        self.speak_responses = enabled;
+        if !enabled {
+            self.stop_speaking();
+        }
+    }
+
+    pub fn speaks_responses(&self) -> bool {
+        self.speak_responses && self.speaker.is_some()
+    }
+
+    /// Speak a completed assistant response if responses are read aloud,
+    /// without code blocks and markdown
+    pub fn speak_response(&mut self, markdown: &str) {
+        if !self.speak_responses {
+            return;
+        }
+        let text = speakable_text(markdown);
+        if let Some(speaker) = self.speaker.as_mut().filter(|_| !text.is_empty()) {
+            speaker.speak(text);
+        }
+    }
+
+    pub fn is_speaking(&self) -> bool {
+        self.speaker.as_ref().is_some_and(Speaker::is_speaking)
+    }
+
+    /// Stop reading a response aloud
+    pub fn stop_speaking(&mut self) {
+        if let Some(speaker) = &mut self.speaker {
+            speaker.stop();
+        }
+    }
+
+    /// Only forward utterances that begin with `word` (e.g. "hey noema")
+    ///
+    /// The phrase is stripped before sending. Matching ignores case and
+    /// punctuation. After a wake, follow-ups are forwarded without the phrase
+    /// for [`DEFAULT_WAKE_WINDOW`] (see `with_wake_window`).
+    pub fn with_wake_word(mut self, word: &str) -> Self {
+        self.wake_gate = Some(WakeWordGate::new(word, DEFAULT_WAKE_WINDOW));
+        self
+    }
+
+    /// How long follow-ups are accepted after the last forwarded utterance
+    pub fn with_wake_window(mut self, window: Duration) -> Self {
+        if let Some(gate) = &mut self.wake_gate {
+            gate.window = window;
+        }
+        self
+    }
+
+    /// Send an utterance once no speech has been detected for `timeout`, even
+    /// if voice activity detection hasn't ended it ([`DEFAULT_SILENCE_TIMEOUT`]
+    /// unless set; `None` waits for the end of speech)
+    ///
+    /// The text is handled like any other transcription: `process` queues it
+    /// while buffering. Speech after the timeout becomes a new message.
+    pub fn with_silence_timeout(self, timeout: Option<Duration>) -> Self {
+        self.agent.set_silence_timeout(timeout);
+        self
+    }
+
+    /// Whether follow-ups are currently accepted without the wake word
+    pub fn is_awake(&self) -> bool {
+        match &self.wake_gate {
+            Some(gate) => gate.open_until.is_some_and(|until| Instant::now() < until),
+            None => true,
         }
     }
 
@@ -35,6 +201,31 @@ impl VoiceCoordinator {
         self.pending_messages.len()
     }
 
+    /// Take the newest partial transcription seen by `process`, if any
+    ///
+    /// Partials are provisional text for the utterance in progress; each one
+    /// replaces the previous. Cleared once the final transcription arrives.
+    pub fn take_partial(&mut self) -> Option<String> {
+        self.partial.take()
+    }
+
+    /// Stop the voice agent, aborting any in-progress transcription
+    ///
+    /// Buffered messages are discarded and `process` returns nothing afterwards.
+    /// Dropping the coordinator does the same.
+    pub fn shutdown(&mut self) {
+        self.agent.shutdown();
+        self.stop_speaking();
+        self.pending_messages.clear();
+        self.is_listening = false;
+        self.is_transcribing = false;
+        self.is_buffering = false;
+        self.partial = None;
+        if let Some(gate) = &mut self.wake_gate {
+            gate.open_until = None;
+        }
+    }
+
     /// Poll for voice events and return messages to send.
     /// If `buffering` is true, transcriptions are queued instead of returned.
     /// Returns (message_to_send, errors) - buffered messages are concatenated into one
@@ -46,6 +237,8 @@ impl VoiceCoordinator {
         while let Some(event) = self.agent.try_recv() {
             match event {
                 VoiceEvent::ListeningStarted => {
+                    // The user talking over a response interrupts it
+                    self.stop_speaking();
                     self.is_listening = true;
                     self.is_transcribing = false;
                 }
@@ -53,16 +246,31 @@ impl VoiceCoordinator {
                     self.is_listening = false;
                     self.is_transcribing = true;
                 }
+                VoiceEvent::PartialTranscription(text) => {
+                    let text = match &self.wake_gate {
+                        Some(gate) => gate.preview(&text, Instant::now()),
+                        None => Some(text),
+                    };
+                    if let Some(text) = text.filter(|t| !t.trim().is_empty()) {
+                        self.partial = Some(text);
+                    }
+                }
                 VoiceEvent::Transcription(text) => {
                     self.is_listening = false;
                     self.is_transcribing = false;
-                    if !text.trim().is_empty() {
+                    self.partial = None;
+                    let text = match &mut self.wake_gate {
+                        Some(gate) => gate.filter(&text, Instant::now()),
+                        None => Some(text),
+                    };
+                    if let Some(text) = text.filter(|t| !t.trim().is_empty()) {
                         self.pending_messages.push(text);
                     }
                 }
                 VoiceEvent::Error(e) => {
                     self.is_listening = false;
                     self.is_transcribing = false;
+                    self.partial = None;
                     errors.push(e);
                 }
                 _ => {}
@@ -81,3 +289,293 @@ impl VoiceCoordinator {
         (message, errors)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::SpeechToText;
+    use crate::tts::Tts;
+    use crate::types::{AudioSegment, SpeechEvent};
+    use crate::AudioStreamer;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    /// Streamer whose speech events are pushed by the test
+    struct FakeStreamer(Mutex<Option<Receiver<SpeechEvent>>>);
+
+    impl AudioStreamer for FakeStreamer {
+        fn start_streaming(&mut self) -> anyhow::Result<Receiver<SpeechEvent>> {
+            self.0.lock().unwrap().take().ok_or_else(|| anyhow::anyhow!("already streaming"))
+        }
+    }
+
+    /// Transcriber that takes a long time unless cancelled
+    struct SlowTranscriber {
+        cancelled: Arc<AtomicBool>,
+    }
+
+    impl SpeechToText for SlowTranscriber {
+        fn transcribe(&self, _samples: &[f32], cancel: &Arc<AtomicBool>) -> anyhow::Result<Option<String>> {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while Instant::now() < deadline {
+                if cancel.load(Ordering::SeqCst) {
+                    self.cancelled.store(true, Ordering::SeqCst);
+                    return Ok(None);
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Ok(Some("stray transcription".to_string()))
+        }
+    }
+
+    /// Transcriber that reports how many seconds of audio it was given
+    struct SecondsTranscriber;
+
+    impl SpeechToText for SecondsTranscriber {
+        fn transcribe(&self, samples: &[f32], _cancel: &Arc<AtomicBool>) -> anyhow::Result<Option<String>> {
+            Ok(Some(format!("{} seconds", samples.len() / 16000)))
+        }
+    }
+
+    /// Speech engine that "speaks" until cancelled, recording what it was given
+    struct EndlessTts {
+        spoken: Arc<Mutex<Vec<String>>>,
+        cancelled: Arc<AtomicBool>,
+    }
+
+    impl Tts for EndlessTts {
+        fn speak(&self, text: &str, cancel: &AtomicBool) -> anyhow::Result<()> {
+            self.spoken.lock().unwrap().push(text.to_string());
+            while !cancel.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            self.cancelled.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn speak(speech_tx: &Sender<SpeechEvent>) {
+        let now = Instant::now();
+        speech_tx.send(SpeechEvent::SpeechStart { timestamp: now }).unwrap();
+        speech_tx
+            .send(SpeechEvent::SpeechEnd(AudioSegment::new(now, vec![0.0; 16000])))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_wake_word_is_required_and_stripped() {
+        let mut gate = WakeWordGate::new("Hey Noema", Duration::ZERO);
+        let now = Instant::now();
+
+        assert_eq!(gate.filter("What's the weather?", now), None);
+        assert_eq!(
+            gate.filter("Hey, Noema. What's the weather?", now),
+            Some("What's the weather?".to_string())
+        );
+        assert_eq!(gate.filter("hey noema open the docs", now), Some("open the docs".to_string()));
+        assert_eq!(gate.filter("Hey Noemi, what's up?", now), None);
+
+        // Window of zero closes immediately
+        assert_eq!(gate.filter("And tomorrow?", now), None);
+    }
+
+    #[test]
+    fn test_wake_window_allows_follow_ups() {
+        let mut gate = WakeWordGate::new("hey noema", Duration::from_secs(10));
+        let start = Instant::now();
+
+        // The bare phrase opens the window without sending anything
+        assert_eq!(gate.filter("Hey Noema.", start), None);
+        assert_eq!(
+            gate.filter("What's on my calendar?", start + Duration::from_secs(5)),
+            Some("What's on my calendar?".to_string())
+        );
+        // Each forwarded utterance extends the window
+        assert_eq!(
+            gate.filter("And tomorrow?", start + Duration::from_secs(14)),
+            Some("And tomorrow?".to_string())
+        );
+        assert_eq!(gate.filter("Thanks", start + Duration::from_secs(30)), None);
+    }
+
+    #[test]
+    fn test_shutdown_aborts_transcription() {
+        let (speech_tx, speech_rx) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let transcriber = SlowTranscriber { cancelled: Arc::clone(&cancelled) };
+        let agent = VoiceAgent::with_transcriber(
+            Box::new(FakeStreamer(Mutex::new(Some(speech_rx)))),
+            transcriber,
+        )
+        .unwrap();
+        let mut coordinator = VoiceCoordinator::new(agent);
+
+        speak(&speech_tx);
+
+        // Wait until the agent is transcribing
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !coordinator.is_transcribing() {
+            assert!(Instant::now() < deadline, "agent never started transcribing");
+            coordinator.process(false);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let started = Instant::now();
+        coordinator.shutdown();
+        assert!(started.elapsed() < Duration::from_secs(5), "shutdown waited for transcription");
+        assert!(cancelled.load(Ordering::SeqCst));
+
+        // Nothing arrives after shutdown, even with more speech
+        let _ = speech_tx.send(SpeechEvent::SpeechStart { timestamp: Instant::now() });
+        std::thread::sleep(Duration::from_millis(50));
+        let (message, errors) = coordinator.process(false);
+        assert_eq!(message, None);
+        assert!(errors.is_empty());
+        assert!(!coordinator.is_transcribing());
+    }
+
+    #[test]
+    fn test_silence_timeout_sends_unfinished_utterance_once() {
+        let (speech_tx, speech_rx) = mpsc::channel();
+        let agent = VoiceAgent::with_transcriber(
+            Box::new(FakeStreamer(Mutex::new(Some(speech_rx)))),
+            SecondsTranscriber,
+        )
+        .unwrap();
+        let mut coordinator =
+            VoiceCoordinator::new(agent).with_silence_timeout(Some(Duration::from_millis(50)));
+
+        // Two seconds of speech, then nothing: the source never ends the utterance
+        let now = Instant::now();
+        speech_tx.send(SpeechEvent::SpeechStart { timestamp: now }).unwrap();
+        speech_tx
+            .send(SpeechEvent::SpeechChunk(AudioSegment::new(now, vec![0.0; 32000])))
+            .unwrap();
+
+        // Buffered while the conversation is busy, sent once it isn't
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while coordinator.buffered_count() == 0 {
+            assert!(Instant::now() < deadline, "silence timeout never fired");
+            assert_eq!(coordinator.process(true).0, None);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(coordinator.process(false).0, Some("2 seconds".to_string()));
+
+        // The late end of the same utterance doesn't send it again
+        speech_tx
+            .send(SpeechEvent::SpeechEnd(AudioSegment::new(now, vec![0.0; 48000])))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(coordinator.process(false).0, None);
+    }
+
+    #[test]
+    fn test_capture_error_is_reported() {
+        let (speech_tx, speech_rx) = mpsc::channel();
+        let agent = VoiceAgent::with_transcriber(
+            Box::new(FakeStreamer(Mutex::new(Some(speech_rx)))),
+            SecondsTranscriber,
+        )
+        .unwrap();
+        let mut coordinator = VoiceCoordinator::new(agent);
+
+        // The mic goes away mid-utterance
+        speech_tx.send(SpeechEvent::SpeechStart { timestamp: Instant::now() }).unwrap();
+        speech_tx
+            .send(SpeechEvent::Error("Audio input device disconnected".to_string()))
+            .unwrap();
+        drop(speech_tx);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut errors = Vec::new();
+        while errors.is_empty() {
+            assert!(Instant::now() < deadline, "capture error never reported");
+            errors = coordinator.process(false).1;
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(errors, vec!["Audio input device disconnected".to_string()]);
+        assert!(!coordinator.is_listening());
+    }
+
+    #[test]
+    fn test_user_speech_interrupts_spoken_response() {
+        let (speech_tx, speech_rx) = mpsc::channel();
+        let agent = VoiceAgent::with_transcriber(
+            Box::new(FakeStreamer(Mutex::new(Some(speech_rx)))),
+            SecondsTranscriber,
+        )
+        .unwrap();
+        let spoken = Arc::new(Mutex::new(Vec::new()));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let mut coordinator = VoiceCoordinator::new(agent).with_tts(EndlessTts {
+            spoken: Arc::clone(&spoken),
+            cancelled: Arc::clone(&cancelled),
+        });
+
+        // Off by default
+        coordinator.speak_response("Hello");
+        assert!(!coordinator.is_speaking());
+
+        coordinator.set_speak_responses(true);
+        coordinator.speak_response("**Done**:\n```\nls\n```");
+        assert!(coordinator.is_speaking());
+
+        speech_tx.send(SpeechEvent::SpeechStart { timestamp: Instant::now() }).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !coordinator.is_listening() {
+            assert!(Instant::now() < deadline, "speech never started");
+            coordinator.process(false);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!coordinator.is_speaking());
+        assert!(cancelled.load(Ordering::SeqCst));
+        assert_eq!(*spoken.lock().unwrap(), vec!["Done:".to_string()]);
+    }
+
+    #[test]
+    fn test_partials_cover_utterance_so_far() {
+        let (speech_tx, speech_rx) = mpsc::channel();
+        let agent = VoiceAgent::with_transcriber(
+            Box::new(FakeStreamer(Mutex::new(Some(speech_rx)))),
+            SecondsTranscriber,
+        )
+        .unwrap();
+        let mut coordinator = VoiceCoordinator::new(agent);
+
+        let now = Instant::now();
+        let chunk = || SpeechEvent::SpeechChunk(AudioSegment::new(now, vec![0.0; 8000]));
+        speech_tx.send(SpeechEvent::SpeechStart { timestamp: now }).unwrap();
+
+        // Each further second of speech re-decodes everything heard so far
+        for expected in ["1 seconds", "2 seconds"] {
+            speech_tx.send(chunk()).unwrap();
+            speech_tx.send(chunk()).unwrap();
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                assert!(Instant::now() < deadline, "no partial {:?}", expected);
+                coordinator.process(false);
+                if let Some(partial) = coordinator.take_partial() {
+                    assert_eq!(partial, expected);
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+
+        speech_tx
+            .send(SpeechEvent::SpeechEnd(AudioSegment::new(now, vec![0.0; 48000])))
+            .unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            assert!(Instant::now() < deadline, "no final transcription");
+            if let (Some(message), _) = coordinator.process(false) {
+                assert_eq!(message, "3 seconds");
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(coordinator.take_partial(), None);
+    }
+}
@@ -9,8 +9,8 @@ use std::thread::JoinHandle;
 use tracing::{error, info, warn};
 
 use crate::traits::{AudioPlayer, AudioStreamer};
-use crate::types::SpeechEvent;
-use crate::vad::VoiceActivityDetector;
+use crate::types::{DeviceInfo, SpeechEvent};
+use crate::vad::{VadConfig, VoiceActivityDetector};
 
 /// Convert samples to f32 format
 fn convert_samples<T, F>(data: &[T], convert_fn: F) -> Vec<f32>
@@ -21,17 +21,23 @@ where
     data.iter().map(|&sample| convert_fn(sample)).collect()
 }
 
+/// Build and start an input stream feeding `vad`
+///
+/// Stream errors are reported as `SpeechEvent::Error`; once the device is
+/// gone `stop_tx` is signalled so the capture thread shuts down.
 fn build_and_run_stream<T, F>(
     device: &Device,
     config: &StreamConfig,
     sender: Arc<Mutex<Sender<SpeechEvent>>>,
     vad: Arc<Mutex<VoiceActivityDetector>>,
+    stop_tx: Sender<()>,
     convert_fn: F,
 ) -> Result<cpal::Stream>
 where
     T: cpal::Sample + cpal::SizedSample + Send + 'static,
     F: Fn(T) -> f32 + Send + 'static,
 {
+    let error_sender = sender.clone();
     let stream = device.build_input_stream(
         config,
         move |data: &[T], _: &cpal::InputCallbackInfo| {
@@ -46,7 +52,19 @@ where
                 }
             }
         },
-        |err| error!("Audio stream error: {}", err),
+        move |err| {
+            error!("Audio stream error: {}", err);
+            let message = match err {
+                cpal::StreamError::DeviceNotAvailable => {
+                    let _ = stop_tx.send(());
+                    "Audio input device disconnected".to_string()
+                }
+                err => format!("Audio stream error: {}", err),
+            };
+            if let Ok(sender) = error_sender.lock() {
+                let _ = sender.send(SpeechEvent::Error(message));
+            }
+        },
         None,
     )?;
 
@@ -55,7 +73,27 @@ where
     Ok(stream)
 }
 
-/// Audio capture from default input device
+/// Input devices of the default host, for `CpalAudioCapture::with_device`
+pub fn list_input_devices() -> Vec<DeviceInfo> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    let devices = match host.input_devices() {
+        Ok(devices) => devices,
+        Err(e) => {
+            warn!("Failed to list audio input devices: {}", e);
+            return Vec::new();
+        }
+    };
+    devices
+        .filter_map(|device| device.name().ok())
+        .map(|name| DeviceInfo {
+            is_default: default_name.as_ref() == Some(&name),
+            name,
+        })
+        .collect()
+}
+
+/// Audio capture from an input device (the default one unless chosen)
 pub struct CpalAudioCapture {
     #[allow(dead_code)]
     host: Host,
@@ -69,7 +107,27 @@ impl CpalAudioCapture {
         let input_device = host
             .default_input_device()
             .ok_or_else(|| anyhow::anyhow!("No input device available"))?;
+        Self::from_device(host, input_device)
+    }
 
+    /// Capture from the input device called `name`, or the default device
+    /// if there is no longer one by that name
+    pub fn with_device(name: &str) -> Result<Self> {
+        let host = cpal::default_host();
+        let device = host
+            .input_devices()?
+            .find(|device| device.name().is_ok_and(|n| n == name));
+        match device {
+            Some(device) => Self::from_device(host, device),
+            None => {
+                warn!("Audio input device '{}' not found, using the default", name);
+                Self::new()
+            }
+        }
+    }
+
+    fn from_device(host: Host, input_device: Device) -> Result<Self> {
+        info!("Using audio input device '{}'", input_device.name().unwrap_or_default());
         let supported_configs: Vec<_> = input_device.supported_input_configs()?.collect();
 
         let supported_config = supported_configs
@@ -108,7 +166,6 @@ pub struct CpalAudioPlayer {
     #[allow(dead_code)]
     host: Host,
     output_device: Device,
-    config: StreamConfig,
 }
 
 impl CpalAudioPlayer {
@@ -125,35 +182,33 @@ impl CpalAudioPlayer {
             .next()
             .ok_or_else(|| anyhow::anyhow!("No supported audio output config found"))?;
 
-        let sample_rate = SampleRate(16000);
-        let config = StreamConfig {
-            channels: 1,
-            sample_rate,
-            buffer_size: cpal::BufferSize::Default,
-        };
-
         Ok(Self {
             host,
             output_device,
-            config,
         })
     }
 }
 
 impl AudioPlayer for CpalAudioPlayer {
-    /// Play audio samples (expected to be 16kHz mono f32)
-    fn play(&self, samples: &[f32]) -> Result<()> {
+    /// Play mono f32 samples at `sample_rate`
+    fn play_samples(&self, samples: &[f32], sample_rate: u32) -> Result<()> {
         if samples.is_empty() {
             return Ok(());
         }
 
+        let config = StreamConfig {
+            channels: 1,
+            sample_rate: SampleRate(sample_rate),
+            buffer_size: cpal::BufferSize::Default,
+        };
+
         let samples = Arc::new(samples.to_vec());
         let samples_clone = samples.clone();
         let sample_index = Arc::new(Mutex::new(0));
         let sample_index_clone = sample_index.clone();
 
         let stream = self.output_device.build_output_stream(
-            &self.config,
+            &config,
             move |output: &mut [f32], _: &cpal::OutputCallbackInfo| {
                 let mut index = sample_index_clone.lock().unwrap();
                 for sample in output.iter_mut() {
@@ -171,7 +226,7 @@ impl AudioPlayer for CpalAudioPlayer {
 
         stream.play()?;
 
-        let duration_secs = samples.len() as f32 / self.config.sample_rate.0 as f32;
+        let duration_secs = samples.len() as f32 / sample_rate as f32;
         std::thread::sleep(std::time::Duration::from_secs_f32(duration_secs + 0.1));
 
         Ok(())
@@ -196,18 +251,33 @@ impl Drop for StreamHandle {
 /// Streaming audio capture with voice activity detection
 pub struct CpalAudioStreamer {
     audio_capture: CpalAudioCapture,
+    vad_config: VadConfig,
     /// Handle to control stream lifetime - drop this to stop capture
     stream_handle: Option<StreamHandle>,
 }
 
 impl CpalAudioStreamer {
     pub fn new() -> Result<Self> {
-        let audio_capture = CpalAudioCapture::new()?;
+        Ok(Self::from_capture(CpalAudioCapture::new()?))
+    }
 
-        Ok(Self {
+    /// Stream from the input device called `name` (see `CpalAudioCapture::with_device`)
+    pub fn with_device(name: &str) -> Result<Self> {
+        Ok(Self::from_capture(CpalAudioCapture::with_device(name)?))
+    }
+
+    fn from_capture(audio_capture: CpalAudioCapture) -> Self {
+        Self {
             audio_capture,
+            vad_config: VadConfig::default(),
             stream_handle: None,
-        })
+        }
+    }
+
+    /// Detect speech with the given sensitivity (applies from the next `start_streaming`)
+    pub fn with_vad_config(mut self, config: VadConfig) -> Self {
+        self.vad_config = config;
+        self
     }
 }
 
@@ -223,12 +293,14 @@ impl AudioStreamer for CpalAudioStreamer {
 
         let sample_rate = self.audio_capture.config.sample_rate.0;
         let config = self.audio_capture.config.clone();
+        let vad_config = self.vad_config;
 
         // Get device name to find it again in the thread
         let device_name = self.audio_capture.input_device
             .name()
             .unwrap_or_default();
 
+        let disconnect_tx = stop_tx.clone();
         let thread = std::thread::spawn(move || {
             info!("Audio capture thread started");
             // Re-acquire device in this thread
@@ -267,7 +339,10 @@ impl AudioStreamer for CpalAudioStreamer {
             };
 
             let sender = Arc::new(Mutex::new(event_sender));
-            let vad = Arc::new(Mutex::new(VoiceActivityDetector::new(sample_rate)));
+            let vad = Arc::new(Mutex::new(VoiceActivityDetector::with_config(
+                sample_rate,
+                vad_config,
+            )));
 
             let sample_format = supported_config.sample_format();
 
@@ -278,6 +353,7 @@ impl AudioStreamer for CpalAudioStreamer {
                         &config,
                         sender.clone(),
                         vad.clone(),
+                        disconnect_tx.clone(),
                         $converter,
                     )
                 };
@@ -0,0 +1,242 @@
+//! Decoding audio content blocks (WAV, MP3, OGG, FLAC, raw PCM) for playback
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::Cursor;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Sample rate assumed for raw PCM whose mime type doesn't give one
+const DEFAULT_PCM_RATE: u32 = 24000;
+
+/// Mono audio ready for an `AudioPlayer`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedAudio {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+/// Decode audio bytes of the given mime type to mono f32 samples
+///
+/// WAV is parsed directly; raw PCM (`audio/L16`, `audio/pcm`, as returned by
+/// Gemini) is read as 16-bit little-endian at the `rate` parameter; anything
+/// else (MP3, OGG/Vorbis, FLAC, ...) goes through symphonia.
+pub fn decode_audio(bytes: &[u8], mime_type: &str) -> Result<DecodedAudio> {
+    let mut parts = mime_type.split(';').map(str::trim);
+    let essence = parts.next().unwrap_or_default().to_ascii_lowercase();
+    let params: Vec<(&str, &str)> = parts.filter_map(|p| p.split_once('=')).collect();
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim_matches('"'))
+    };
+
+    match essence.as_str() {
+        "audio/wav" | "audio/x-wav" | "audio/wave" | "audio/vnd.wave" => decode_wav(bytes),
+        "audio/l16" | "audio/pcm" | "audio/x-pcm" => {
+            let sample_rate = param("rate")
+                .and_then(|rate| rate.parse().ok())
+                .unwrap_or(DEFAULT_PCM_RATE);
+            let channels = param("channels")
+                .and_then(|channels| channels.parse().ok())
+                .unwrap_or(1);
+            Ok(DecodedAudio {
+                samples: downmix(pcm_samples(bytes, 16)?, channels),
+                sample_rate,
+            })
+        }
+        _ => decode_compressed(bytes, &essence),
+    }
+}
+
+/// Parse a RIFF/WAVE file with PCM (8/16/24/32-bit) or 32-bit float samples
+fn decode_wav(bytes: &[u8]) -> Result<DecodedAudio> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        bail!("Not a WAV file");
+    }
+
+    let mut format = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into()?) as usize;
+        let body = &bytes[offset + 8..(offset + 8 + size).min(bytes.len())];
+        match id {
+            b"fmt " => {
+                if body.len() < 16 {
+                    bail!("WAV fmt chunk is too short");
+                }
+                let mut tag = u16::from_le_bytes([body[0], body[1]]);
+                let channels = u16::from_le_bytes([body[2], body[3]]);
+                let sample_rate = u32::from_le_bytes(body[4..8].try_into()?);
+                let bits = u16::from_le_bytes([body[14], body[15]]);
+                // WAVE_FORMAT_EXTENSIBLE keeps the real format in the sub-format GUID
+                if tag == 0xFFFE && body.len() >= 26 {
+                    tag = u16::from_le_bytes([body[24], body[25]]);
+                }
+                format = Some((tag, channels, sample_rate, bits));
+            }
+            b"data" => {
+                let (tag, channels, sample_rate, bits) =
+                    format.ok_or_else(|| anyhow!("WAV data chunk before fmt chunk"))?;
+                let samples = match (tag, bits) {
+                    (1, _) => pcm_samples(body, bits)?,
+                    (3, 32) => body
+                        .chunks_exact(4)
+                        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                        .collect(),
+                    _ => bail!("Unsupported WAV format {} with {} bits", tag, bits),
+                };
+                return Ok(DecodedAudio {
+                    samples: downmix(samples, channels.max(1) as usize),
+                    sample_rate,
+                });
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even size
+        offset += 8 + size + (size & 1);
+    }
+    bail!("WAV file has no data chunk")
+}
+
+/// Little-endian integer PCM to f32 in [-1, 1]
+fn pcm_samples(bytes: &[u8], bits: u16) -> Result<Vec<f32>> {
+    Ok(match bits {
+        8 => bytes.iter().map(|&b| (b as f32 - 128.0) / 128.0).collect(),
+        16 => bytes
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+            .collect(),
+        24 => bytes
+            .chunks_exact(3)
+            .map(|b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0)
+            .collect(),
+        32 => bytes
+            .chunks_exact(4)
+            .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0)
+            .collect(),
+        _ => bail!("Unsupported PCM sample size: {} bits", bits),
+    })
+}
+
+/// Average interleaved channels into one
+fn downmix(samples: Vec<f32>, channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return samples;
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// Decode the first audio track of a compressed file with symphonia
+fn decode_compressed(bytes: &[u8], mime_type: &str) -> Result<DecodedAudio> {
+    let mut hint = Hint::new();
+    hint.mime_type(mime_type);
+    let source = MediaSourceStream::new(Box::new(Cursor::new(bytes.to_vec())), Default::default());
+    let probed = symphonia::default::get_probe()
+        .format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())
+        .with_context(|| format!("Unrecognized audio format ({})", mime_type))?;
+    let mut format = probed.format;
+
+    let track = format
+        .default_track()
+        .ok_or_else(|| anyhow!("Audio has no playable track"))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .context("Unsupported audio codec")?;
+
+    let mut samples = Vec::new();
+    let mut sample_rate = track.codec_params.sample_rate;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt frame is skipped rather than failing the whole clip
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let spec = *decoded.spec();
+        sample_rate.get_or_insert(spec.rate);
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend(downmix(buffer.samples().to_vec(), spec.channels.count()));
+    }
+
+    Ok(DecodedAudio {
+        samples,
+        sample_rate: sample_rate.ok_or_else(|| anyhow!("Audio has no sample rate"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stereo 16-bit WAV file, optionally with an odd-sized chunk before
+    /// the data
+    fn stereo_wav(rate: u32, frames: &[(i16, i16)], extra_chunk: bool) -> Vec<u8> {
+        let data: Vec<u8> = frames
+            .iter()
+            .flat_map(|(l, r)| l.to_le_bytes().into_iter().chain(r.to_le_bytes()))
+            .collect();
+        let mut wav = b"RIFF\0\0\0\0WAVE".to_vec();
+        wav.extend(b"fmt ");
+        wav.extend(16u32.to_le_bytes());
+        wav.extend(1u16.to_le_bytes());
+        wav.extend(2u16.to_le_bytes());
+        wav.extend(rate.to_le_bytes());
+        wav.extend((rate * 4).to_le_bytes());
+        wav.extend(4u16.to_le_bytes());
+        wav.extend(16u16.to_le_bytes());
+        if extra_chunk {
+            wav.extend(b"junk");
+            wav.extend(3u32.to_le_bytes());
+            wav.extend(b"abc\0");
+        }
+        wav.extend(b"data");
+        wav.extend((data.len() as u32).to_le_bytes());
+        wav.extend(data);
+        let riff_size = (wav.len() as u32 - 8).to_le_bytes();
+        wav[4..8].copy_from_slice(&riff_size);
+        wav
+    }
+
+    #[test]
+    fn test_decode_wav_downmixes_to_mono() {
+        let wav = stereo_wav(44100, &[(16384, 16384), (16384, -16384)], true);
+        let audio = decode_audio(&wav, "audio/wav").unwrap();
+        assert_eq!(audio.sample_rate, 44100);
+        assert_eq!(audio.samples, vec![0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_decode_raw_pcm_uses_rate_parameter() {
+        let pcm: Vec<u8> = [0i16, -32768].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let audio = decode_audio(&pcm, "audio/L16;codec=pcm;rate=24000").unwrap();
+        assert_eq!(audio.sample_rate, 24000);
+        assert_eq!(audio.samples, vec![0.0, -1.0]);
+    }
+
+    #[test]
+    fn test_symphonia_decodes_wav_like_the_parser() {
+        let wav = stereo_wav(8000, &[(16384, 16384), (-16384, -16384)], false);
+        let audio = decode_compressed(&wav, "audio/x-unknown").unwrap();
+        assert_eq!(audio, decode_audio(&wav, "audio/wav").unwrap());
+    }
+}
@@ -0,0 +1,218 @@
+//! Downloading Whisper models from the whisper.cpp Hugging Face repository
+
+use anyhow::{bail, Context, Result};
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// Where the GGML builds of the Whisper models are published
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// Model sizes published in the whisper.cpp repository
+pub const WHISPER_MODEL_SIZES: &[&str] = &[
+    "tiny",
+    "tiny.en",
+    "base",
+    "base.en",
+    "small",
+    "small.en",
+    "medium",
+    "medium.en",
+    "large-v1",
+    "large-v2",
+    "large-v3",
+    "large-v3-turbo",
+];
+
+/// Progress of a model download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    /// Size of the model, if the server reported it
+    pub total: Option<u64>,
+}
+
+impl DownloadProgress {
+    /// Percentage downloaded, if the size is known
+    pub fn percent(&self) -> Option<u8> {
+        match self.total {
+            Some(total) if total > 0 => {
+                Some((self.downloaded.min(total) as f64 / total as f64 * 100.0) as u8)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Model filename for a size, e.g. "ggml-base.en.bin" for "base.en"
+pub fn whisper_model_filename(size: &str) -> String {
+    format!("ggml-{}.bin", size)
+}
+
+/// Size of a model filename such as "ggml-base.en.bin", if it is a known one
+pub fn whisper_model_size(filename: &str) -> Option<&'static str> {
+    let size = filename.strip_prefix("ggml-")?.strip_suffix(".bin")?;
+    WHISPER_MODEL_SIZES.iter().copied().find(|known| *known == size)
+}
+
+/// Download URL of a model size
+pub fn whisper_model_url(size: &str) -> String {
+    format!("{}/{}", MODEL_BASE_URL, whisper_model_filename(size))
+}
+
+/// What the repository says the file should be
+#[derive(Debug, Default)]
+struct ExpectedFile {
+    size: Option<u64>,
+    /// Hex SHA-256 of the file
+    sha256: Option<String>,
+}
+
+/// Read the size and hash Hugging Face reports for an LFS file.
+///
+/// They are only on the redirect response of the `resolve` URL, so it is
+/// requested without following redirects.
+async fn expected_file(url: &str) -> Result<ExpectedFile> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let response = client.head(url).send().await.context("Failed to fetch model info")?;
+    if response.status().is_client_error() || response.status().is_server_error() {
+        bail!("Model not available at {} ({})", url, response.status());
+    }
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim_matches('"').to_string())
+    };
+    Ok(ExpectedFile {
+        size: header("x-linked-size").and_then(|size| size.parse().ok()),
+        sha256: header("x-linked-etag").filter(|etag| is_sha256(etag)),
+    })
+}
+
+fn is_sha256(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Download the Whisper model of `size` (e.g. "base.en") to `dest`.
+///
+/// The file is written next to `dest` with a `.part` suffix and only renamed
+/// into place once its size and SHA-256 match what the repository reports,
+/// so an interrupted download never leaves a truncated model behind.
+/// Does nothing if `dest` already exists.
+pub async fn download_whisper_model(
+    size: &str,
+    dest: &Path,
+    progress: impl Fn(DownloadProgress),
+) -> Result<()> {
+    if !WHISPER_MODEL_SIZES.contains(&size) {
+        bail!(
+            "Unknown Whisper model size '{}' (expected one of {})",
+            size,
+            WHISPER_MODEL_SIZES.join(", ")
+        );
+    }
+    if dest.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .context("Failed to create model directory")?;
+    }
+
+    let url = whisper_model_url(size);
+    let expected = expected_file(&url).await?;
+    tracing::info!(url, "Downloading Whisper model");
+
+    let partial = partial_path(dest);
+    let result = download_to(&url, &partial, &expected, &progress).await;
+    if result.is_err() {
+        tokio::fs::remove_file(&partial).await.ok();
+    }
+    result?;
+
+    tokio::fs::rename(&partial, dest)
+        .await
+        .context("Failed to move the downloaded model into place")?;
+    tracing::info!(path = %dest.display(), "Whisper model download complete");
+    Ok(())
+}
+
+/// Path the model is downloaded to before it is verified
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+async fn download_to(
+    url: &str,
+    path: &Path,
+    expected: &ExpectedFile,
+    progress: &impl Fn(DownloadProgress),
+) -> Result<()> {
+    let response = reqwest::get(url).await.context("Failed to fetch model")?;
+    if !response.status().is_success() {
+        bail!("Failed to fetch model: {}", response.status());
+    }
+
+    let total = expected.size.or(response.content_length());
+    let mut stream = response.bytes_stream();
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .context("Failed to create model file")?;
+    let mut hasher = Sha256::new();
+    let mut downloaded: u64 = 0;
+
+    progress(DownloadProgress { downloaded, total });
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.context("Download error")?;
+        file.write_all(&chunk).await.context("Write error")?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        progress(DownloadProgress { downloaded, total });
+    }
+    file.flush().await.context("Write error")?;
+
+    if let Some(size) = total {
+        if downloaded != size {
+            bail!("Download incomplete: got {} of {} bytes", downloaded, size);
+        }
+    }
+    if let Some(sha256) = &expected.sha256 {
+        let actual = hex::encode(hasher.finalize());
+        if !actual.eq_ignore_ascii_case(sha256) {
+            bail!("Downloaded model is corrupt: SHA-256 {} != {}", actual, sha256);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_filenames() {
+        assert_eq!(whisper_model_filename("base.en"), "ggml-base.en.bin");
+        assert_eq!(whisper_model_size("ggml-base.en.bin"), Some("base.en"));
+        assert_eq!(whisper_model_size("ggml-large-v3-turbo.bin"), Some("large-v3-turbo"));
+        assert_eq!(whisper_model_size("ggml-huge.bin"), None);
+        assert_eq!(whisper_model_size("base.en"), None);
+        assert_eq!(
+            whisper_model_url("tiny"),
+            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin"
+        );
+    }
+
+    #[test]
+    fn test_partial_path_keeps_extension() {
+        let dest = Path::new("/models/ggml-base.bin");
+        assert_eq!(partial_path(dest), Path::new("/models/ggml-base.bin.part"));
+    }
+}
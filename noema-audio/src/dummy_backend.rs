@@ -1,8 +1,14 @@
 use anyhow::{anyhow, Result};
 use std::sync::mpsc::Receiver;
 
-use crate::traits::AudioStreamer;
-use crate::types::SpeechEvent;
+use crate::traits::{AudioPlayer, AudioStreamer};
+use crate::types::{DeviceInfo, SpeechEvent};
+use crate::vad::VadConfig;
+
+/// No input devices without a capture backend
+pub fn list_input_devices() -> Vec<DeviceInfo> {
+    Vec::new()
+}
 
 pub struct DummyAudioCapture;
 
@@ -18,8 +24,10 @@ impl DummyAudioPlayer {
     pub fn new() -> Result<Self> {
         Err(anyhow!("Audio playback is not available in this build (missing 'backend-cpal' feature)"))
     }
-    
-    pub fn play(&self, _samples: &[f32]) -> Result<()> {
+}
+
+impl AudioPlayer for DummyAudioPlayer {
+    fn play_samples(&self, _samples: &[f32], _sample_rate: u32) -> Result<()> {
         Err(anyhow!("Audio playback is not available"))
     }
 }
@@ -30,6 +38,14 @@ impl DummyAudioStreamer {
     pub fn new() -> Result<Self> {
          Err(anyhow!("Audio streaming is not available in this build (missing 'backend-cpal' feature)"))
     }
+
+    pub fn with_device(_name: &str) -> Result<Self> {
+        Self::new()
+    }
+
+    pub fn with_vad_config(self, _config: VadConfig) -> Self {
+        self
+    }
 }
 
 impl AudioStreamer for DummyAudioStreamer {
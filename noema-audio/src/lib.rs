@@ -4,6 +4,9 @@
 //! - Audio capture and playback via `cpal` (feature: `backend-cpal`)
 //! - Voice activity detection (VAD)
 //! - Speech-to-text transcription via Whisper
+//! - Whisper model downloads
+//! - Decoding audio content (WAV, MP3, OGG, FLAC, raw PCM) for playback
+//! - Text-to-speech of responses (espeak-ng backend with feature: `tts-espeak`)
 //! - Voice-enabled agent wrapper
 //! - Browser audio streaming support (feature: `browser`)
 
@@ -22,23 +25,38 @@ pub mod dummy_backend;
 pub mod browser_backend;
 
 pub mod coordinator;
+pub mod decode;
+pub mod download;
 pub mod transcription;
+pub mod tts;
 pub mod voice_agent;
 
 // Re-export types
-pub use types::{AudioSegment, SpeechEvent};
+pub use types::{AudioSegment, DeviceInfo, SpeechEvent};
+pub use vad::{VadConfig, VoiceActivityDetector};
 pub use traits::{AudioPlayer, AudioStreamer};
 
 // Default backend exports
 #[cfg(feature = "backend-cpal")]
-pub use cpal_backend::{CpalAudioCapture as AudioCapture, CpalAudioPlayer as AudioPlayback, CpalAudioStreamer as StreamingAudioCapture};
+pub use cpal_backend::{list_input_devices, CpalAudioCapture as AudioCapture, CpalAudioPlayer as AudioPlayback, CpalAudioStreamer as StreamingAudioCapture};
 
 #[cfg(not(feature = "backend-cpal"))]
-pub use dummy_backend::{DummyAudioCapture as AudioCapture, DummyAudioPlayer as AudioPlayback, DummyAudioStreamer as StreamingAudioCapture};
+pub use dummy_backend::{list_input_devices, DummyAudioCapture as AudioCapture, DummyAudioPlayer as AudioPlayback, DummyAudioStreamer as StreamingAudioCapture};
 
 #[cfg(feature = "browser")]
-pub use browser_backend::{create_browser_backend, BrowserAudioController, BrowserAudioStreamer};
-
-pub use coordinator::VoiceCoordinator;
-pub use transcription::Transcriber;
+pub use browser_backend::{
+    create_browser_backend, create_browser_backend_with_vad, BrowserAudioController,
+    BrowserAudioStreamer,
+};
+
+pub use coordinator::{VoiceCoordinator, DEFAULT_SILENCE_TIMEOUT, DEFAULT_WAKE_WINDOW};
+pub use decode::{decode_audio, DecodedAudio};
+pub use download::{
+    download_whisper_model, whisper_model_filename, whisper_model_size, DownloadProgress,
+    WHISPER_MODEL_SIZES,
+};
+pub use transcription::{SpeechToText, Transcriber, WhisperConfig};
+#[cfg(feature = "tts-espeak")]
+pub use tts::EspeakTts;
+pub use tts::{speakable_text, Speaker, SystemTts, Tts};
 pub use voice_agent::{VoiceAgent, VoiceEvent};
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use std::sync::mpsc::Receiver;
 
+use crate::decode::decode_audio;
 use crate::types::SpeechEvent;
 
 /// Trait for audio capture streaming with VAD
@@ -11,6 +12,17 @@ pub trait AudioStreamer: Send + Sync {
 
 /// Trait for audio playback
 pub trait AudioPlayer: Send + Sync {
+    /// Play mono f32 samples at `sample_rate`
+    fn play_samples(&self, samples: &[f32], sample_rate: u32) -> Result<()>;
+
     /// Play audio samples (16kHz mono f32)
-    fn play(&self, samples: &[f32]) -> Result<()>;
+    fn play(&self, samples: &[f32]) -> Result<()> {
+        self.play_samples(samples, 16000)
+    }
+
+    /// Decode and play an audio content block (WAV, MP3, OGG, raw PCM, ...)
+    fn play_encoded(&self, bytes: &[u8], mime_type: &str) -> Result<()> {
+        let audio = decode_audio(bytes, mime_type)?;
+        self.play_samples(&audio.samples, audio.sample_rate)
+    }
 }
@@ -1,14 +1,58 @@
 //! Speech-to-text transcription using Whisper
 
 use anyhow::Result;
-use std::path::Path;
-use std::sync::Once;
+use std::ffi::c_void;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Once};
 
 static INIT_LOGGING: Once = Once::new();
 
+/// Which Whisper model to load and which language to transcribe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhisperConfig {
+    /// Path to the Whisper GGML model file (e.g., "ggml-base.bin")
+    pub model_path: PathBuf,
+    /// Spoken language code (e.g., "de"); auto-detected when None
+    pub language: Option<String>,
+}
+
+impl WhisperConfig {
+    /// Config for a model with language auto-detection
+    pub fn new(model_path: impl Into<PathBuf>) -> Self {
+        Self {
+            model_path: model_path.into(),
+            language: None,
+        }
+    }
+
+    /// Transcribe in a fixed language instead of auto-detecting
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+}
+
+/// Speech-to-text engine used by the voice agent
+pub trait SpeechToText: Send {
+    /// Transcribe 16kHz mono f32 samples
+    ///
+    /// Returns `Ok(None)` if `cancel` was set before the transcription finished.
+    fn transcribe(&self, samples: &[f32], cancel: &Arc<AtomicBool>) -> Result<Option<String>>;
+}
+
+/// Whisper abort callback: aborts once the flag passed as user data is set
+unsafe extern "C" fn abort_if_cancelled(user_data: *mut c_void) -> bool {
+    // SAFETY: user_data comes from `Arc::into_raw` in `transcribe_cancellable`,
+    // which keeps that reference alive until `full` has returned.
+    unsafe { (*(user_data as *const AtomicBool)).load(Ordering::SeqCst) }
+}
+
 /// Whisper-based speech transcriber
 pub struct Transcriber {
     context: whisper_rs::WhisperContext,
+    /// Language passed to Whisper ("auto" to detect)
+    language: String,
 }
 
 impl Transcriber {
@@ -17,6 +61,13 @@ impl Transcriber {
     /// # Arguments
     /// * `model_path` - Path to the Whisper GGML model file (e.g., "ggml-base.en.bin")
     pub fn new(model_path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_config(&WhisperConfig::new(model_path.as_ref()))
+    }
+
+    /// Create a transcriber from a model path and language
+    ///
+    /// English-only models (`*.en.bin`) always transcribe English.
+    pub fn from_config(config: &WhisperConfig) -> Result<Self> {
         // Suppress whisper.cpp logging output (only runs once)
         INIT_LOGGING.call_once(|| {
             whisper_rs::install_logging_hooks();
@@ -24,10 +75,15 @@ impl Transcriber {
 
         let params = whisper_rs::WhisperContextParameters::default();
         let context = whisper_rs::WhisperContext::new_with_params(
-            model_path.as_ref().to_str().unwrap(),
+            config.model_path.to_str().unwrap(),
             params,
         )?;
-        Ok(Self { context })
+        let language = if context.is_multilingual() {
+            config.language.clone().unwrap_or_else(|| "auto".to_string())
+        } else {
+            "en".to_string()
+        };
+        Ok(Self { context, language })
     }
 
     /// Transcribe audio samples to text
@@ -35,6 +91,19 @@ impl Transcriber {
     /// # Arguments
     /// * `samples` - Audio samples at 16kHz mono f32 format
     pub fn transcribe(&self, samples: &[f32]) -> Result<String> {
+        let text = self.transcribe_cancellable(samples, &Arc::new(AtomicBool::new(false)))?;
+        Ok(text.unwrap_or_default())
+    }
+
+    /// Transcribe audio samples, stopping early once `cancel` is set
+    ///
+    /// Whisper checks the flag between the chunks it decodes, and it is checked
+    /// again between segments. Returns `Ok(None)` if cancelled.
+    pub fn transcribe_cancellable(&self, samples: &[f32], cancel: &Arc<AtomicBool>) -> Result<Option<String>> {
+        if cancel.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+
         let mut state = self.context.create_state()?;
         let mut params = whisper_rs::FullParams::new(whisper_rs::SamplingStrategy::Greedy { best_of: 1 });
 
@@ -43,19 +112,45 @@ impl Transcriber {
         params.set_print_realtime(false);
         params.set_print_special(false);
         params.set_print_timestamps(false);
+        params.set_language(Some(&self.language));
 
-        state.full(params, samples)?;
+        // Whisper gets its own reference to the flag, released once `full`
+        // returns. whisper-rs 0.15's `set_abort_callback_safe` calls its boxed
+        // closure through the wrong type, so the reference is passed as user data.
+        let abort = Arc::into_raw(Arc::clone(cancel));
+        // SAFETY: `abort` stays valid until it is reclaimed after `full`
+        unsafe {
+            params.set_abort_callback(Some(abort_if_cancelled));
+            params.set_abort_callback_user_data(abort as *mut c_void);
+        }
+
+        let result = state.full(params, samples);
+        // SAFETY: `full` has returned, so whisper no longer uses the pointer
+        drop(unsafe { Arc::from_raw(abort) });
+        if cancel.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+        result?;
 
         let num_segments = state.full_n_segments();
         let mut result = String::new();
 
         for i in 0..num_segments {
+            if cancel.load(Ordering::SeqCst) {
+                return Ok(None);
+            }
             if let Some(segment) = state.get_segment(i) {
                 result.push_str(&segment.to_string());
                 result.push(' ');
             }
         }
 
-        Ok(result.trim().to_string())
+        Ok(Some(result.trim().to_string()))
+    }
+}
+
+impl SpeechToText for Transcriber {
+    fn transcribe(&self, samples: &[f32], cancel: &Arc<AtomicBool>) -> Result<Option<String>> {
+        self.transcribe_cancellable(samples, cancel)
     }
 }
@@ -0,0 +1,237 @@
+//! Text-to-speech for reading assistant responses aloud
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// How often a speaking process is checked for completion or cancellation
+const SPEAK_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Text-to-speech engine
+pub trait Tts: Send + Sync {
+    /// Speak `text`, blocking until it has been said
+    ///
+    /// Returns early, cutting speech off, once `cancel` is set.
+    fn speak(&self, text: &str, cancel: &AtomicBool) -> Result<()>;
+}
+
+/// The operating system's speech synthesizer: `say` on macOS,
+/// speech-dispatcher's `spd-say` on Linux
+pub struct SystemTts {
+    program: &'static str,
+    args: &'static [&'static str],
+}
+
+impl SystemTts {
+    pub fn new() -> Result<Self> {
+        if cfg!(target_os = "macos") {
+            Ok(Self { program: "say", args: &[] })
+        } else if cfg!(target_os = "linux") {
+            Ok(Self { program: "spd-say", args: &["--wait"] })
+        } else {
+            Err(anyhow!("No system text-to-speech on this platform"))
+        }
+    }
+}
+
+impl Tts for SystemTts {
+    fn speak(&self, text: &str, cancel: &AtomicBool) -> Result<()> {
+        let mut command = Command::new(self.program);
+        command.args(self.args).arg("--").arg(text);
+        run_cancellable(command, cancel)
+    }
+}
+
+/// Speech via the `espeak-ng` command-line synthesizer
+#[cfg(feature = "tts-espeak")]
+pub struct EspeakTts {
+    /// Voice name such as "en-us"; espeak-ng's default when None
+    voice: Option<String>,
+}
+
+#[cfg(feature = "tts-espeak")]
+impl EspeakTts {
+    pub fn new(voice: Option<String>) -> Self {
+        Self { voice }
+    }
+}
+
+#[cfg(feature = "tts-espeak")]
+impl Tts for EspeakTts {
+    fn speak(&self, text: &str, cancel: &AtomicBool) -> Result<()> {
+        let mut command = Command::new("espeak-ng");
+        if let Some(voice) = &self.voice {
+            command.arg("-v").arg(voice);
+        }
+        command.arg("--").arg(text);
+        run_cancellable(command, cancel)
+    }
+}
+
+/// Run a speech command to completion, killing it once `cancel` is set
+fn run_cancellable(mut command: Command, cancel: &AtomicBool) -> Result<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to start {}", program))?;
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            child.kill().ok();
+            child.wait().ok();
+            return Ok(());
+        }
+        if let Some(status) = child.try_wait()? {
+            if !status.success() {
+                bail!("{} exited with {}", program, status);
+            }
+            return Ok(());
+        }
+        std::thread::sleep(SPEAK_POLL_INTERVAL);
+    }
+}
+
+/// Plain text to read aloud from a markdown response
+///
+/// Code blocks are dropped entirely; inline code, emphasis, headings, list
+/// markers and link targets are reduced to their text.
+pub fn speakable_text(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block || trimmed.is_empty() {
+            continue;
+        }
+        let text = trimmed.trim_start_matches('#').trim_start_matches('>').trim_start();
+        let text = strip_list_marker(text);
+        let text = strip_inline(text);
+        if !text.trim().is_empty() {
+            lines.push(text.trim().to_string());
+        }
+    }
+    lines.join("\n")
+}
+
+/// Drop a leading "-", "*", "+" or "1." list marker
+fn strip_list_marker(line: &str) -> &str {
+    if let Some(rest) = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+    {
+        return rest;
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    match line[digits..].strip_prefix(". ") {
+        Some(rest) if digits > 0 => rest,
+        _ => line,
+    }
+}
+
+/// Reduce links and images to their text and drop emphasis and code markers
+fn strip_inline(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' | '_' | '`' | '~' => {}
+            '!' if chars.peek() == Some(&'[') => {}
+            ']' if chars.peek() == Some(&'(') => {
+                // Skip the link target
+                for c in chars.by_ref() {
+                    if c == ')' {
+                        break;
+                    }
+                }
+            }
+            '[' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Speaks one text at a time on a background thread
+pub struct Speaker {
+    tts: Arc<dyn Tts>,
+    current: Option<(Arc<AtomicBool>, JoinHandle<()>)>,
+}
+
+impl Speaker {
+    pub fn new(tts: impl Tts + 'static) -> Self {
+        Self {
+            tts: Arc::new(tts),
+            current: None,
+        }
+    }
+
+    /// Start speaking `text`, cutting off whatever is being said
+    pub fn speak(&mut self, text: String) {
+        self.stop();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let thread_cancel = Arc::clone(&cancel);
+        let tts = Arc::clone(&self.tts);
+        let handle = std::thread::spawn(move || {
+            if let Err(e) = tts.speak(&text, &thread_cancel) {
+                warn!("Text-to-speech failed: {}", e);
+            }
+        });
+        self.current = Some((cancel, handle));
+    }
+
+    /// Whether speech is still playing
+    pub fn is_speaking(&self) -> bool {
+        self.current
+            .as_ref()
+            .is_some_and(|(_, handle)| !handle.is_finished())
+    }
+
+    /// Stop speaking and wait for the engine to let go of the audio device
+    pub fn stop(&mut self) {
+        if let Some((cancel, handle)) = self.current.take() {
+            if !handle.is_finished() {
+                debug!("Interrupting speech");
+            }
+            cancel.store(true, Ordering::SeqCst);
+            if handle.join().is_err() {
+                warn!("Text-to-speech thread panicked");
+            }
+        }
+    }
+}
+
+impl Drop for Speaker {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speakable_text_drops_markdown() {
+        let markdown = "## Result\n\
+            Use **`cargo build`** to compile, see [the docs](https://example.com).\n\
+            \n\
+            ```rust\n\
+            fn main() {}\n\
+            ```\n\
+            - first *point*\n\
+            2. second point\n\
+            > quoted";
+        assert_eq!(
+            speakable_text(markdown),
+            "Result\nUse cargo build to compile, see the docs.\nfirst point\nsecond point\nquoted"
+        );
+    }
+}
@@ -20,6 +20,15 @@ impl AudioSegment {
     }
 }
 
+/// An audio input device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// Name to select the device by
+    pub name: String,
+    /// Whether this is the system's default input
+    pub is_default: bool,
+}
+
 /// Events emitted during speech detection
 #[derive(Debug, Clone)]
 pub enum SpeechEvent {
@@ -27,6 +36,9 @@ pub enum SpeechEvent {
     SpeechStart { timestamp: Instant },
     /// Speech has ended with complete audio segment
     SpeechEnd(AudioSegment),
-    /// Intermediate speech chunk during active speech
+    /// New audio received during active speech (not the whole utterance)
     SpeechChunk(AudioSegment),
+    /// Capture failed, e.g. because the device was disconnected; no further
+    /// audio follows
+    Error(String),
 }
@@ -4,13 +4,87 @@ use tracing::{debug, info};
 use crate::types::{AudioSegment, SpeechEvent};
 use crate::utils::resample_to_16khz;
 
+/// How eagerly the detector treats sound as speech
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VadConfig {
+    /// RMS energy above which a frame counts as speech
+    pub energy_threshold: f32,
+    /// How long sound must last before it is reported as speech
+    pub min_speech_ms: u64,
+    /// How long silence must last before the utterance ends
+    pub min_silence_ms: u64,
+    /// 0-3, like WebRTC's VAD modes: how strictly a speech onset must stay
+    /// above the threshold. At 3 a single quiet frame discards it; each step
+    /// down tolerates one more.
+    pub aggressiveness: u8,
+}
+
+impl VadConfig {
+    /// Quiet room with a close mic: picks up soft speech
+    pub fn quiet() -> Self {
+        Self {
+            energy_threshold: 0.005,
+            min_speech_ms: 150,
+            min_silence_ms: 600,
+            aggressiveness: 1,
+        }
+    }
+
+    /// The default
+    pub fn normal() -> Self {
+        Self {
+            energy_threshold: 0.01,
+            min_speech_ms: 200,
+            min_silence_ms: 500,
+            aggressiveness: 3,
+        }
+    }
+
+    /// Background noise (fans, open office): ignores sound below raised-voice level
+    pub fn noisy() -> Self {
+        Self {
+            energy_threshold: 0.03,
+            min_speech_ms: 300,
+            min_silence_ms: 400,
+            aggressiveness: 3,
+        }
+    }
+
+    /// Preset by name: "quiet", "normal" or "noisy"
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "quiet" => Some(Self::quiet()),
+            "normal" => Some(Self::normal()),
+            "noisy" => Some(Self::noisy()),
+            _ => None,
+        }
+    }
+
+    /// Use the given aggressiveness (clamped to 0-3)
+    pub fn with_aggressiveness(mut self, aggressiveness: u8) -> Self {
+        self.aggressiveness = aggressiveness.min(3);
+        self
+    }
+
+    /// Quiet frames tolerated while speech is being confirmed
+    fn onset_tolerance(&self) -> u32 {
+        3 - u32::from(self.aggressiveness.min(3))
+    }
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self::normal()
+    }
+}
+
 #[derive(Clone)]
 pub struct VoiceActivityDetector {
-    energy_threshold: f32,
-    silence_duration_ms: u64,
-    speech_duration_ms: u64,
+    config: VadConfig,
     current_state: VadState,
     state_start_time: Instant,
+    /// Quiet frames seen since speech onset began
+    quiet_frames: u32,
     accumulated_audio: Vec<f32>,
     sample_rate_hz: u32,
 }
@@ -25,21 +99,28 @@ enum VadState {
 
 impl VoiceActivityDetector {
     pub fn new(sample_rate_hz: u32) -> Self {
+        Self::with_config(sample_rate_hz, VadConfig::default())
+    }
+
+    pub fn with_config(sample_rate_hz: u32, config: VadConfig) -> Self {
         Self {
-            energy_threshold: 0.01,
-            silence_duration_ms: 500,
-            speech_duration_ms: 200,
+            config,
             current_state: VadState::Silence,
             state_start_time: Instant::now(),
+            quiet_frames: 0,
             accumulated_audio: Vec::new(),
             sample_rate_hz,
         }
     }
 
     pub fn process_samples(&mut self, samples: &[f32]) -> Option<SpeechEvent> {
+        self.process_samples_at(samples, Instant::now())
+    }
+
+    /// Process samples that arrived at `now`
+    fn process_samples_at(&mut self, samples: &[f32], now: Instant) -> Option<SpeechEvent> {
         let energy = self.calculate_energy(samples);
-        let is_speech = energy > self.energy_threshold;
-        let now = Instant::now();
+        let is_speech = energy > self.config.energy_threshold;
         let elapsed = now.duration_since(self.state_start_time);
 
         match self.current_state {
@@ -47,6 +128,7 @@ impl VoiceActivityDetector {
                 if is_speech {
                     debug!("VAD: Silence -> PossibleSpeech (energy: {:.4})", energy);
                     self.transition_to(VadState::PossibleSpeech, now);
+                    self.quiet_frames = 0;
                     self.accumulated_audio.clear();
                     self.accumulated_audio.extend_from_slice(samples);
                 }
@@ -55,11 +137,14 @@ impl VoiceActivityDetector {
             VadState::PossibleSpeech => {
                 self.accumulated_audio.extend_from_slice(samples);
 
-                if is_speech && elapsed.as_millis() >= self.speech_duration_ms as u128 {
+                if !is_speech {
+                    self.quiet_frames += 1;
+                }
+                if is_speech && elapsed.as_millis() >= self.config.min_speech_ms as u128 {
                     info!("VAD: PossibleSpeech -> Speech (confirmed speech start)");
                     self.transition_to(VadState::Speech, now);
                     Some(SpeechEvent::SpeechStart { timestamp: now })
-                } else if !is_speech {
+                } else if !is_speech && self.quiet_frames > self.config.onset_tolerance() {
                     debug!("VAD: PossibleSpeech -> Silence (false positive)");
                     self.transition_to(VadState::Silence, now);
                     None
@@ -76,7 +161,7 @@ impl VoiceActivityDetector {
                 }
                 Some(SpeechEvent::SpeechChunk(AudioSegment::new(
                     now,
-                    samples.to_vec(),
+                    resample_to_16khz(samples, self.sample_rate_hz),
                 )))
             }
             VadState::PossibleSilence => {
@@ -87,9 +172,9 @@ impl VoiceActivityDetector {
                     self.transition_to(VadState::Speech, now);
                     Some(SpeechEvent::SpeechChunk(AudioSegment::new(
                         now,
-                        samples.to_vec(),
+                        resample_to_16khz(samples, self.sample_rate_hz),
                     )))
-                } else if elapsed.as_millis() >= self.silence_duration_ms as u128 {
+                } else if elapsed.as_millis() >= self.config.min_silence_ms as u128 {
                     let raw_audio = self.accumulated_audio.clone();
                     self.transition_to(VadState::Silence, now);
                     self.accumulated_audio.clear();
@@ -119,3 +204,91 @@ impl VoiceActivityDetector {
         self.state_start_time = timestamp;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// 20ms frames at 16kHz
+    const FRAME: usize = 320;
+
+    fn tone(amplitude: f32) -> Vec<f32> {
+        (0..FRAME).map(|i| amplitude * (i as f32 * 0.3).sin()).collect()
+    }
+
+    /// Feed frames 20ms apart, returning the kinds of events produced
+    fn feed(vad: &mut VoiceActivityDetector, start: &mut Instant, frames: &[Vec<f32>]) -> Vec<&'static str> {
+        frames
+            .iter()
+            .filter_map(|frame| {
+                *start += Duration::from_millis(20);
+                vad.process_samples_at(frame, *start)
+            })
+            .map(|event| match event {
+                SpeechEvent::SpeechStart { .. } => "start",
+                SpeechEvent::SpeechChunk(_) => "chunk",
+                SpeechEvent::SpeechEnd(_) => "end",
+                SpeechEvent::Error(_) => "error",
+            })
+            .collect()
+    }
+
+    fn detector(config: VadConfig) -> (VoiceActivityDetector, Instant) {
+        let vad = VoiceActivityDetector::with_config(16000, config);
+        let start = vad.state_start_time;
+        (vad, start)
+    }
+
+    #[test]
+    fn test_speech_then_silence_produces_start_chunks_end() {
+        let (mut vad, mut now) = detector(VadConfig::normal());
+
+        let events = feed(&mut vad, &mut now, &vec![tone(0.0); 20]);
+        assert!(events.is_empty());
+
+        // 200ms to confirm, then speech continues
+        let events = feed(&mut vad, &mut now, &vec![tone(0.2); 20]);
+        assert_eq!(events.first(), Some(&"start"));
+        assert!(events[1..].iter().all(|e| *e == "chunk"));
+
+        // Ends after 500ms of silence, not before
+        let events = feed(&mut vad, &mut now, &vec![tone(0.0); 20]);
+        assert_eq!(events, vec!["chunk"]);
+        let events = feed(&mut vad, &mut now, &vec![tone(0.0); 10]);
+        assert_eq!(events, vec!["end"]);
+    }
+
+    #[test]
+    fn test_noisy_preset_ignores_background_noise() {
+        let noise = vec![tone(0.03); 30];
+
+        let (mut vad, mut now) = detector(VadConfig::normal());
+        assert_eq!(feed(&mut vad, &mut now, &noise).first(), Some(&"start"));
+
+        let (mut vad, mut now) = detector(VadConfig::noisy());
+        assert!(feed(&mut vad, &mut now, &noise).is_empty());
+    }
+
+    #[test]
+    fn test_aggressiveness_sets_onset_tolerance() {
+        // Speech with a quiet frame every few frames
+        let choppy: Vec<Vec<f32>> = (0..20)
+            .map(|i| if i % 4 == 3 { tone(0.0) } else { tone(0.2) })
+            .collect();
+
+        let (mut vad, mut now) = detector(VadConfig::normal());
+        assert!(feed(&mut vad, &mut now, &choppy).is_empty());
+
+        let (mut vad, mut now) = detector(VadConfig::normal().with_aggressiveness(0));
+        assert_eq!(feed(&mut vad, &mut now, &choppy).first(), Some(&"start"));
+    }
+
+    #[test]
+    fn test_presets_by_name() {
+        assert_eq!(VadConfig::preset("Noisy"), Some(VadConfig::noisy()));
+        assert_eq!(VadConfig::preset("quiet"), Some(VadConfig::quiet()));
+        assert_eq!(VadConfig::preset("loud"), None);
+        assert_eq!(VadConfig::default(), VadConfig::normal());
+    }
+}
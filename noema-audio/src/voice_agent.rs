@@ -5,14 +5,27 @@
 
 use crate::traits::AudioStreamer;
 use crate::types::SpeechEvent;
-use crate::transcription::Transcriber;
+use crate::transcription::{SpeechToText, Transcriber, WhisperConfig};
 use anyhow::Result;
-use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc as std_mpsc;
+use std::sync::Arc;
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
+/// How often the transcription thread checks for shutdown while idle
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How much new speech (at 16kHz) to collect before re-decoding for a partial
+const PARTIAL_INTERVAL_SAMPLES: usize = 16000;
+
+/// Most speech (at 16kHz) a partial decodes. Once the window is full its
+/// text is kept and later partials only decode the speech after it, so long
+/// utterances don't make every partial slower.
+const PARTIAL_WINDOW_SAMPLES: usize = 8 * 16000;
+
 /// Events from the voice agent
 #[derive(Debug, Clone)]
 pub enum VoiceEvent {
@@ -20,6 +33,9 @@ pub enum VoiceEvent {
     ListeningStarted,
     /// Speech ended, now transcribing
     Transcribing,
+    /// Provisional text for the utterance so far; superseded by the next
+    /// partial or the final `Transcription`
+    PartialTranscription(String),
     /// Transcription is available
     Transcription(String),
     /// Agent response text
@@ -36,8 +52,25 @@ pub struct VoiceAgent {
     #[allow(dead_code)]
     streamer: Box<dyn AudioStreamer>,
     event_rx: Option<mpsc::UnboundedReceiver<VoiceEvent>>,
-    #[allow(dead_code)]
     transcription_thread: Option<JoinHandle<()>>,
+    shutdown: Arc<AtomicBool>,
+    /// Milliseconds without speech after which an open utterance is
+    /// transcribed anyway; 0 disables
+    silence_timeout_ms: Arc<AtomicU64>,
+}
+
+/// Audio of the utterance being spoken, as seen by the transcription thread
+#[derive(Default)]
+struct Utterance {
+    /// 16kHz speech collected from chunks since the start (or the last flush)
+    audio: Vec<f32>,
+    /// When the last chunk of speech arrived, while the utterance is open
+    last_speech: Option<Instant>,
+    /// Whether part of the utterance was already sent by the silence timeout
+    flushed: bool,
+    /// Partial text of `audio[..partial_start]`, which is not decoded again
+    partial_prefix: String,
+    partial_start: usize,
 }
 
 impl VoiceAgent {
@@ -45,112 +78,301 @@ impl VoiceAgent {
     ///
     /// # Arguments
     /// * `streamer` - The audio streamer to use (e.g. CpalAudioStreamer or BrowserAudioStreamer)
-    /// * `model_path` - Path to the Whisper GGML model file
-    pub fn new(mut streamer: Box<dyn AudioStreamer>, model_path: impl AsRef<Path>) -> Result<Self> {
+    /// * `config` - Whisper model path and transcription language
+    pub fn new(streamer: Box<dyn AudioStreamer>, config: WhisperConfig) -> Result<Self> {
         // Validate the model exists by creating a transcriber
-        let _ = Transcriber::new(model_path.as_ref())?;
+        let _ = Transcriber::from_config(&config)?;
+
+        Self::spawn(streamer, move || {
+            let transcriber = Transcriber::from_config(&config);
+            if transcriber.is_ok() {
+                info!("Transcriber initialized successfully");
+            }
+            transcriber
+        })
+    }
 
+    /// Create a voice agent using the given speech-to-text engine
+    pub fn with_transcriber(
+        streamer: Box<dyn AudioStreamer>,
+        transcriber: impl SpeechToText + 'static,
+    ) -> Result<Self> {
+        Self::spawn(streamer, move || Ok(transcriber))
+    }
+
+    /// Start streaming and run transcription on a background thread.
+    /// The transcriber is built on that thread.
+    fn spawn<T, F>(mut streamer: Box<dyn AudioStreamer>, make_transcriber: F) -> Result<Self>
+    where
+        T: SpeechToText,
+        F: FnOnce() -> Result<T> + Send + 'static,
+    {
         let speech_rx = streamer.start_streaming()?;
         let (event_tx, event_rx) = mpsc::unbounded_channel();
-
-        let model_path = model_path.as_ref().to_string_lossy().to_string();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let thread_shutdown = Arc::clone(&shutdown);
+        let silence_timeout_ms = Arc::new(AtomicU64::new(0));
+        let thread_silence_timeout = Arc::clone(&silence_timeout_ms);
 
         let handle = std::thread::spawn(move || {
-            Self::transcription_loop(model_path, speech_rx, event_tx);
+            info!("Voice transcription thread started");
+
+            let transcriber = match make_transcriber() {
+                Ok(t) => t,
+                Err(e) => {
+                    error!("Failed to initialize transcriber: {}", e);
+                    let _ = event_tx.send(VoiceEvent::Error(format!(
+                        "Failed to initialize transcriber: {}",
+                        e
+                    )));
+                    return;
+                }
+            };
+
+            Self::transcription_loop(
+                &transcriber,
+                speech_rx,
+                event_tx,
+                &thread_shutdown,
+                &thread_silence_timeout,
+            );
         });
 
         Ok(Self {
             streamer,
             event_rx: Some(event_rx),
             transcription_thread: Some(handle),
+            shutdown,
+            silence_timeout_ms,
         })
     }
 
+    /// Transcribe an utterance once no speech has arrived for `timeout`, even
+    /// if the audio source hasn't ended it (e.g. the browser stopped sending
+    /// samples mid-utterance). `None` waits for the end of speech.
+    pub fn set_silence_timeout(&self, timeout: Option<Duration>) {
+        let millis = timeout.map_or(0, |t| (t.as_millis() as u64).max(1));
+        self.silence_timeout_ms.store(millis, Ordering::SeqCst);
+    }
+
     /// Background transcription loop that processes speech events
     fn transcription_loop(
-        model_path: String,
+        transcriber: &dyn SpeechToText,
         speech_rx: std_mpsc::Receiver<SpeechEvent>,
         event_tx: mpsc::UnboundedSender<VoiceEvent>,
+        shutdown: &Arc<AtomicBool>,
+        silence_timeout_ms: &AtomicU64,
     ) {
-        info!("Voice transcription thread started");
-
-        let transcriber = match Transcriber::new(&model_path) {
-            Ok(t) => {
-                info!("Transcriber initialized successfully");
-                t
-            }
-            Err(e) => {
-                error!("Failed to initialize transcriber: {}", e);
-                let _ = event_tx.send(VoiceEvent::Error(format!(
-                    "Failed to initialize transcriber: {}",
-                    e
-                )));
-                return;
-            }
-        };
-
         info!("Waiting for speech events...");
-        for event in speech_rx {
-            if !Self::handle_speech_event(&transcriber, event, &event_tx) {
+        let mut utterance = Utterance::default();
+        while !shutdown.load(Ordering::SeqCst) {
+            let silence_timeout = match silence_timeout_ms.load(Ordering::SeqCst) {
+                0 => None,
+                millis => Some(Duration::from_millis(millis)),
+            };
+            let silence_deadline = utterance.last_speech.zip(silence_timeout).map(|(at, t)| at + t);
+            let wait = silence_deadline.map_or(SHUTDOWN_POLL_INTERVAL, |deadline| {
+                deadline.saturating_duration_since(Instant::now()).min(SHUTDOWN_POLL_INTERVAL)
+            });
+
+            let event = match speech_rx.recv_timeout(wait) {
+                Ok(event) => event,
+                Err(std_mpsc::RecvTimeoutError::Timeout) => {
+                    if silence_deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                        && !Self::flush_utterance(transcriber, &mut utterance, &event_tx, shutdown)
+                    {
+                        break;
+                    }
+                    continue;
+                }
+                Err(std_mpsc::RecvTimeoutError::Disconnected) => {
+                    info!("Voice transcription thread exiting - speech_rx channel closed");
+                    return;
+                }
+            };
+            if !Self::handle_speech_event(transcriber, event, &mut utterance, &event_tx, shutdown) {
                 break;
             }
         }
-        info!("Voice transcription thread exiting - speech_rx channel closed");
+        info!("Voice transcription thread exiting");
     }
 
     /// Handle a single speech event. Returns false if the loop should exit.
+    ///
+    /// `utterance` collects the audio of the current utterance for partials
+    /// and the silence timeout.
     fn handle_speech_event(
-        transcriber: &Transcriber,
+        transcriber: &dyn SpeechToText,
         event: SpeechEvent,
+        utterance: &mut Utterance,
         event_tx: &mpsc::UnboundedSender<VoiceEvent>,
+        shutdown: &Arc<AtomicBool>,
     ) -> bool {
         match event {
-            SpeechEvent::SpeechStart { .. } => {
+            SpeechEvent::SpeechStart { timestamp } => {
                 debug!("Speech started");
+                *utterance = Utterance {
+                    last_speech: Some(timestamp),
+                    ..Utterance::default()
+                };
                 if event_tx.send(VoiceEvent::ListeningStarted).is_err() {
                     warn!("Failed to send ListeningStarted event - receiver dropped");
                     return false;
                 }
             }
-            SpeechEvent::SpeechChunk(_) => {
-                // Intermediate chunks - could be used for streaming transcription
+            SpeechEvent::SpeechChunk(chunk) => {
+                utterance.last_speech = Some(Instant::now());
+                let previous = utterance.audio.len() / PARTIAL_INTERVAL_SAMPLES;
+                utterance.audio.extend_from_slice(&chunk.audio_data);
+                if utterance.audio.len() / PARTIAL_INTERVAL_SAMPLES > previous {
+                    return Self::send_partial(transcriber, utterance, event_tx, shutdown);
+                }
             }
             SpeechEvent::SpeechEnd(segment) => {
+                let utterance = std::mem::take(utterance);
                 let duration_ms = segment.duration_ms();
                 debug!("Speech ended, duration: {:.0}ms, samples: {}", duration_ms, segment.audio_data.len());
 
-                if event_tx.send(VoiceEvent::Transcribing).is_err() {
-                    warn!("Failed to send Transcribing event - receiver dropped");
+                // The segment covers the whole utterance; after a flush only
+                // the speech since then is still unsent
+                let audio = if utterance.flushed {
+                    utterance.audio
+                } else {
+                    segment.audio_data
+                };
+                if utterance.flushed && audio.is_empty() {
+                    debug!("Utterance already sent by the silence timeout");
+                    return true;
+                }
+                return Self::transcribe_utterance(transcriber, &audio, event_tx, shutdown);
+            }
+            SpeechEvent::Error(message) => {
+                warn!("Audio capture error: {}", message);
+                *utterance = Utterance::default();
+                if event_tx.send(VoiceEvent::Error(message)).is_err() {
+                    warn!("Failed to send Error event - receiver dropped");
                     return false;
                 }
+            }
+        }
+        true
+    }
 
-                match transcriber.transcribe(&segment.audio_data) {
-                    Ok(text) if !text.trim().is_empty() => {
-                        info!("Transcription: {:?}", text);
-                        if event_tx.send(VoiceEvent::Transcription(text)).is_err() {
-                            warn!("Failed to send Transcription event - receiver dropped");
-                            return false;
-                        }
-                    }
-                    Ok(_) => {
-                        debug!("Empty transcription, ignoring");
-                    }
-                    Err(e) => {
-                        error!("Transcription failed: {}", e);
-                        if event_tx.send(VoiceEvent::Error(format!(
-                            "Transcription failed: {}",
-                            e
-                        ))).is_err() {
-                            warn!("Failed to send Error event - receiver dropped");
-                            return false;
-                        }
-                    }
+    /// Transcribe what was said of an utterance the audio source hasn't ended
+    /// after the silence timeout. Returns false if the loop should exit.
+    fn flush_utterance(
+        transcriber: &dyn SpeechToText,
+        utterance: &mut Utterance,
+        event_tx: &mpsc::UnboundedSender<VoiceEvent>,
+        shutdown: &Arc<AtomicBool>,
+    ) -> bool {
+        utterance.last_speech = None;
+        if utterance.audio.is_empty() {
+            return true;
+        }
+        debug!("Silence timeout, transcribing {} samples", utterance.audio.len());
+        let audio = std::mem::take(&mut utterance.audio);
+        utterance.flushed = true;
+        utterance.partial_prefix.clear();
+        utterance.partial_start = 0;
+        Self::transcribe_utterance(transcriber, &audio, event_tx, shutdown)
+    }
+
+    /// Transcribe a finished utterance and send the text. Returns false if
+    /// the loop should exit.
+    fn transcribe_utterance(
+        transcriber: &dyn SpeechToText,
+        audio: &[f32],
+        event_tx: &mpsc::UnboundedSender<VoiceEvent>,
+        shutdown: &Arc<AtomicBool>,
+    ) -> bool {
+        if event_tx.send(VoiceEvent::Transcribing).is_err() {
+            warn!("Failed to send Transcribing event - receiver dropped");
+            return false;
+        }
+
+        match transcriber.transcribe(audio, shutdown) {
+            Ok(None) => {
+                debug!("Transcription cancelled by shutdown");
+                return false;
+            }
+            Ok(Some(text)) if !text.trim().is_empty() => {
+                info!("Transcription: {:?}", text);
+                if event_tx.send(VoiceEvent::Transcription(text)).is_err() {
+                    warn!("Failed to send Transcription event - receiver dropped");
+                    return false;
+                }
+            }
+            Ok(Some(_)) => {
+                debug!("Empty transcription, ignoring");
+            }
+            Err(e) => {
+                error!("Transcription failed: {}", e);
+                if event_tx.send(VoiceEvent::Error(format!(
+                    "Transcription failed: {}",
+                    e
+                ))).is_err() {
+                    warn!("Failed to send Error event - receiver dropped");
+                    return false;
                 }
             }
         }
         true
     }
 
+    /// Decode the utterance so far and send it as a partial. Returns false if
+    /// the loop should exit. Failed partials are only logged; the final
+    /// transcription reports errors.
+    ///
+    /// Only the speech after `partial_start` is decoded, see
+    /// `PARTIAL_WINDOW_SAMPLES`.
+    fn send_partial(
+        transcriber: &dyn SpeechToText,
+        utterance: &mut Utterance,
+        event_tx: &mpsc::UnboundedSender<VoiceEvent>,
+        shutdown: &Arc<AtomicBool>,
+    ) -> bool {
+        let window = &utterance.audio[utterance.partial_start..];
+        let window_full = window.len() >= PARTIAL_WINDOW_SAMPLES;
+        match transcriber.transcribe(window, shutdown) {
+            Ok(None) => false,
+            Ok(Some(text)) => {
+                let text = join_transcripts(&utterance.partial_prefix, text.trim());
+                if window_full {
+                    utterance.partial_prefix = text.clone();
+                    utterance.partial_start = utterance.audio.len();
+                }
+                if text.is_empty() {
+                    return true;
+                }
+                debug!("Partial transcription: {:?}", text);
+                if event_tx.send(VoiceEvent::PartialTranscription(text)).is_err() {
+                    warn!("Failed to send PartialTranscription event - receiver dropped");
+                    return false;
+                }
+                true
+            }
+            Err(e) => {
+                debug!("Partial transcription failed: {}", e);
+                true
+            }
+        }
+    }
+
+    /// Stop the transcription thread and wait for it to exit
+    ///
+    /// An in-progress transcription is aborted and its result discarded, and
+    /// no further events are delivered. Safe to call more than once.
+    pub fn shutdown(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        self.event_rx = None;
+        if let Some(handle) = self.transcription_thread.take() {
+            if handle.join().is_err() {
+                warn!("Voice transcription thread panicked");
+            }
+        }
+    }
+
     /// Try to receive a voice event without blocking
     pub fn try_recv(&mut self) -> Option<VoiceEvent> {
         match self.event_rx.as_mut()?.try_recv() {
@@ -163,3 +385,18 @@ impl VoiceAgent {
         }
     }
 }
+
+impl Drop for VoiceAgent {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Append a transcript to the text before it, with a space between
+fn join_transcripts(prefix: &str, text: &str) -> String {
+    match (prefix.is_empty(), text.is_empty()) {
+        (true, _) => text.to_string(),
+        (false, true) => prefix.to_string(),
+        (false, false) => format!("{} {}", prefix, text),
+    }
+}
@@ -95,6 +95,14 @@ pub fn delegate_provider_enum_impl(_attr: TokenStream, item: TokenStream) -> Tok
         }
     });
 
+    // Generate match arms for create_embedding_model
+    let create_embedding_model_arms = variant_info.iter().map(|info| {
+        let variant_name = &info.variant_name;
+        quote! {
+            #enum_name::#variant_name(provider) => provider.create_embedding_model(model_name)
+        }
+    });
+
     // Generate match arms for provider_name
     let provider_name_arms = variant_info.iter().map(|info| {
         let variant_name = &info.variant_name;
@@ -162,13 +170,13 @@ pub fn delegate_provider_enum_impl(_attr: TokenStream, item: TokenStream) -> Tok
         }
     });
 
-    // Generate from_name_with_key match arms (settings key takes priority, falls back to env var)
-    let from_name_with_key_arms = variant_info.iter().map(|info| {
+    // Generate from_name_with_config match arms (given key takes priority, falls back to env var;
+    // the base URL is used as given)
+    let from_name_with_config_arms = variant_info.iter().map(|info| {
         let variant_name = &info.variant_name;
         let name = &info.name;
         let inner_type = &info.inner_type;
         let api_key_env = &info.api_key_env;
-        let base_url_env = &info.base_url_env;
 
         let create_provider = if api_key_env.is_some() {
             let api_key_env_str = api_key_env.as_ref().unwrap();
@@ -179,8 +187,8 @@ pub fn delegate_provider_enum_impl(_attr: TokenStream, item: TokenStream) -> Tok
                     None => ::std::env::var(#api_key_env_str)
                         .map_err(|_| ::anyhow::anyhow!("{} not configured in settings and {} environment variable not set", #name, #api_key_env_str))?,
                 };
-                let provider = match ::std::env::var(#base_url_env).ok() {
-                    Some(url) => <#inner_type>::new(&url, &api_key),
+                let provider = match base_url {
+                    Some(url) => <#inner_type>::new(url, &api_key),
                     None => <#inner_type>::default(&api_key),
                 };
                 Ok(#enum_name::#variant_name(provider))
@@ -188,8 +196,8 @@ pub fn delegate_provider_enum_impl(_attr: TokenStream, item: TokenStream) -> Tok
         } else {
             // Providers without API keys (like Ollama)
             quote! {
-                let provider = match ::std::env::var(#base_url_env).ok() {
-                    Some(url) => <#inner_type>::new(&url),
+                let provider = match base_url {
+                    Some(url) => <#inner_type>::new(url),
                     None => <#inner_type>::default(),
                 };
                 Ok(#enum_name::#variant_name(provider))
@@ -249,6 +257,12 @@ pub fn delegate_provider_enum_impl(_attr: TokenStream, item: TokenStream) -> Tok
                     #(#create_chat_model_arms),*
                 }
             }
+
+            fn create_embedding_model(&self, model_name: &str) -> Option<::std::sync::Arc<dyn crate::EmbeddingModel + Send + Sync>> {
+                match self {
+                    #(#create_embedding_model_arms),*
+                }
+            }
         }
 
         impl #enum_name {
@@ -289,8 +303,19 @@ pub fn delegate_provider_enum_impl(_attr: TokenStream, item: TokenStream) -> Tok
             /// Create a provider from its name with an optional API key.
             /// If api_key is Some, it takes priority. Otherwise falls back to env var.
             pub fn from_name_with_key(name: &str, api_key: Option<&str>) -> ::anyhow::Result<Self> {
+                let base_url = Self::all_provider_info()
+                    .iter()
+                    .find(|info| info.name == name)
+                    .and_then(|info| ::std::env::var(info.base_url_env).ok());
+                Self::from_name_with_config(name, api_key, base_url.as_deref())
+            }
+
+            /// Create a provider from its name with an optional API key and base URL.
+            /// If api_key is Some, it takes priority. Otherwise falls back to env var.
+            /// Without a base URL the provider's default endpoint is used.
+            pub fn from_name_with_config(name: &str, api_key: Option<&str>, base_url: Option<&str>) -> ::anyhow::Result<Self> {
                 match name {
-                    #(#from_name_with_key_arms),*
+                    #(#from_name_with_config_arms),*
                     _ => Err(::anyhow::anyhow!("Unknown provider: {}", name))
                 }
             }
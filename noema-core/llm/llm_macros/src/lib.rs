@@ -1,6 +1,7 @@
 use proc_macro::TokenStream;
 
 mod delegate_provider;
+mod params;
 mod tool;
 mod tool_methods;
 
@@ -42,6 +43,23 @@ pub fn delegate_provider_enum(attr: TokenStream, item: TokenStream) -> TokenStre
 /// // - AddNumbersArgs::call() wrapper
 /// ```
 ///
+/// # Optional parameters
+///
+/// `Option<T>` parameters may be omitted by the caller. Use
+/// `#[tool_param(default)]` or `#[tool_param(default = expr)]` to make any
+/// other parameter optional; both are left out of the schema's `required` list.
+///
+/// ```ignore
+/// #[tool]
+/// fn search(
+///     query: String,
+///     #[tool_param(default = 10)] limit: usize,
+///     language: Option<String>,
+/// ) -> Vec<String> {
+///     todo!()
+/// }
+/// ```
+///
 /// # Example - Method (with `#[tool_methods]`)
 ///
 /// ```ignore
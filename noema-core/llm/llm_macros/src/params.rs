@@ -0,0 +1,84 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Attribute, Expr, Ident, Type};
+
+/// A tool parameter turned into an Args struct field
+pub struct ParamField {
+    /// The field declaration, including serde attributes
+    pub field: TokenStream,
+    /// Associated function supplying the default value, if one was given
+    pub default_fn: Option<TokenStream>,
+}
+
+/// Build the Args struct field for a parameter.
+///
+/// `#[tool_param(default)]` and `#[tool_param(default = expr)]` make the parameter
+/// optional, filled with `Default::default()` or `expr` when missing. `Option<T>`
+/// parameters are optional too (missing means `None`). Other attributes are kept.
+pub fn param_field(
+    struct_name: &Ident,
+    name: &Ident,
+    ty: &Type,
+    attrs: &[Attribute],
+) -> syn::Result<ParamField> {
+    let mut default: Option<Option<Expr>> = None;
+    let mut kept = Vec::new();
+
+    for attr in attrs {
+        if !attr.path().is_ident("tool_param") {
+            kept.push(attr);
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("default") {
+                default = Some(if meta.input.peek(syn::Token![=]) {
+                    Some(meta.value()?.parse()?)
+                } else {
+                    None
+                });
+                Ok(())
+            } else {
+                Err(meta.error("unsupported tool_param attribute, expected `default`"))
+            }
+        })?;
+    }
+
+    let (serde_default, default_fn) = match default {
+        Some(Some(expr)) => {
+            let fn_name = format_ident!("default_{}", name);
+            let path = format!("{}::{}", struct_name, fn_name);
+            (
+                Some(quote! { #[serde(default = #path)] }),
+                Some(quote! {
+                    fn #fn_name() -> #ty {
+                        #expr
+                    }
+                }),
+            )
+        }
+        Some(None) => (Some(quote! { #[serde(default)] }), None),
+        None if is_option(ty) => (Some(quote! { #[serde(default)] }), None),
+        None => (None, None),
+    };
+
+    Ok(ParamField {
+        field: quote! {
+            #(#kept)*
+            #serde_default
+            pub #name: #ty
+        },
+        default_fn,
+    })
+}
+
+/// Whether the type is written as `Option<T>` (or a path ending in it)
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
@@ -2,6 +2,8 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, FnArg, ItemFn, Pat, PatType};
 
+use crate::params::param_field;
+
 pub fn tool_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as ItemFn);
 
@@ -73,12 +75,17 @@ pub fn tool_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 
     // Generate the struct fields with their attributes
-    let struct_fields = params.iter().map(|(name, ty, attrs)| {
-        quote! {
-            #(#attrs)*
-            pub #name: #ty
+    let mut struct_fields = Vec::new();
+    let mut default_fns = Vec::new();
+    for (name, ty, attrs) in &params {
+        match param_field(&struct_name, name, ty, attrs) {
+            Ok(param) => {
+                struct_fields.push(param.field);
+                default_fns.extend(param.default_fn);
+            }
+            Err(e) => return e.to_compile_error().into(),
         }
-    });
+    }
 
     // Determine if wrapper should be async
     let wrapper_async = if fn_asyncness.is_some() {
@@ -139,6 +146,8 @@ pub fn tool_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
         }
 
         impl #struct_name {
+            #(#default_fns)*
+
             pub fn tool_def() -> ::llm::ToolDefinition {
                 use ::schemars::schema_for;
                 let schema = schema_for!(#struct_name);
@@ -150,6 +159,16 @@ pub fn tool_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
             }
 
             pub #wrapper_async fn call(args_json: ::serde_json::Value) -> ::anyhow::Result<String> {
+                // The schema is compiled on the first call and kept for the rest
+                static VALIDATOR: ::std::sync::OnceLock<::llm::ToolArgsValidator> = ::std::sync::OnceLock::new();
+                let validator = match VALIDATOR.get() {
+                    Some(validator) => validator,
+                    None => {
+                        let validator = Self::tool_def().args_validator()?;
+                        VALIDATOR.get_or_init(|| validator)
+                    }
+                };
+                validator.validate(&args_json)?;
                 let args: #struct_name = ::serde_json::from_value(args_json)?;
                 let result = #fn_name(#(args.#param_names),*) #wrapper_await;
                 Ok(::serde_json::to_string(&result)?)
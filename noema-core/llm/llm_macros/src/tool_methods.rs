@@ -2,6 +2,8 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{FnArg, ImplItem, ItemImpl, Pat, PatType};
 
+use crate::params::param_field;
+
 pub fn tool_methods_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
     // Early return on parse error to help rust-analyzer
     let input = match syn::parse::<ItemImpl>(item.clone()) {
@@ -92,12 +94,17 @@ pub fn tool_methods_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
         }
 
         // Generate the struct fields with their attributes
-        let struct_fields = params.iter().map(|(name, ty, attrs)| {
-            quote! {
-                #(#attrs)*
-                pub #name: #ty
+        let mut struct_fields = Vec::new();
+        let mut default_fns = Vec::new();
+        for (name, ty, attrs) in &params {
+            match param_field(&struct_name, name, ty, attrs) {
+                Ok(param) => {
+                    struct_fields.push(param.field);
+                    default_fns.extend(param.default_fn);
+                }
+                Err(e) => return e.to_compile_error().into(),
             }
-        });
+        }
 
         // Generate the wrapper function name
         let wrapper_name = syn::Ident::new(&format!("{}_wrapper", fn_name), fn_name.span());
@@ -167,6 +174,8 @@ pub fn tool_methods_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
             }
 
             impl #struct_name {
+                #(#default_fns)*
+
                 pub fn #tool_def_method_name() -> ::llm::ToolDefinition {
                     use ::schemars::schema_for;
                     let schema = schema_for!(#struct_name);
@@ -178,6 +187,16 @@ pub fn tool_methods_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 }
 
                 pub #wrapper_async fn #wrapper_name(&self, instance: &#self_ty, args_json: ::serde_json::Value) -> ::anyhow::Result<String> {
+                    // The schema is compiled on the first call and kept for the rest
+                    static VALIDATOR: ::std::sync::OnceLock<::llm::ToolArgsValidator> = ::std::sync::OnceLock::new();
+                    let validator = match VALIDATOR.get() {
+                        Some(validator) => validator,
+                        None => {
+                            let validator = Self::#tool_def_method_name().args_validator()?;
+                            VALIDATOR.get_or_init(|| validator)
+                        }
+                    };
+                    validator.validate(&args_json)?;
                     let args: #struct_name = ::serde_json::from_value(args_json)?;
                     let result = #fn_call #wrapper_await;
                     Ok(::serde_json::to_string(&result)?)
@@ -32,6 +32,11 @@ impl Calculator {
         self.base_value + x + y
     }
 
+    #[tool]
+    fn offset(&self, #[tool_param(default = 1)] step: i32, times: Option<i32>) -> i32 {
+        self.base_value + step * times.unwrap_or(1)
+    }
+
     #[tool]
     fn complex_operation(&self, a: i32, b: String, c: Vec<i32>) -> String {
         format!("base={}, a={}, b={}, c={:?}", self.base_value, a, b, c)
@@ -228,3 +233,33 @@ fn test_method_args_serialization() {
     let deserialized: AddArgs = serde_json::from_str(&json).unwrap();
     assert_eq!(deserialized.amount, 42);
 }
+
+#[test]
+fn test_method_wrapper_validates_args() {
+    let calc = Calculator::new(1);
+    let args = ComplexOperationArgs { a: 1, b: String::new(), c: vec![] };
+
+    let err = args
+        .complex_operation_wrapper(&calc, serde_json::json!({ "a": 1, "c": [1, "two"] }))
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("Invalid arguments for tool 'complex_operation'"), "{}", err);
+    assert!(err.contains("missing required field 'b'"), "{}", err);
+    assert!(err.contains("field 'c.1'"), "{}", err);
+}
+
+#[test]
+fn test_method_default_params() {
+    let calc = Calculator::new(10);
+    let args = OffsetArgs { step: 0, times: None };
+
+    assert_eq!(args.offset_wrapper(&calc, serde_json::json!({})).unwrap(), "11");
+    assert_eq!(
+        args.offset_wrapper(&calc, serde_json::json!({ "step": 2, "times": 3 })).unwrap(),
+        "16"
+    );
+
+    let schema = OffsetArgs::offset_tool_def().input_schema;
+    assert!(schema.schema.object.unwrap().required.is_empty());
+}
@@ -427,6 +427,78 @@ fn test_visibility_modifiers() {
     assert_eq!(args.value, 42);
 }
 
+// Test 25: Missing required fields are reported by name before deserializing
+#[test]
+fn test_call_wrapper_reports_missing_fields() {
+    let err = ComplexFunctionArgs::call(serde_json::json!({ "number": 1, "flag": true }))
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("Invalid arguments for tool 'complex_function'"), "{}", err);
+    assert!(err.contains("missing required field 'text'"), "{}", err);
+    assert!(err.contains("missing required field 'decimal'"), "{}", err);
+    assert!(!err.contains("'number'"), "{}", err);
+}
+
+// Test 26: Wrong types are reported with the offending field
+#[test]
+fn test_call_wrapper_reports_wrong_types() {
+    let err = AddNumbersArgs::call(serde_json::json!({ "a": "ten", "b": 2.5 }))
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("field 'a': \"ten\" is not of type \"integer\""), "{}", err);
+    assert!(err.contains("field 'b'"), "{}", err);
+}
+
+// Test 27: Nested fields use dotted paths
+#[test]
+fn test_call_wrapper_reports_nested_paths() {
+    let err = ProcessListArgs::call(serde_json::json!({ "items": ["a", 1] }))
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("field 'items.1': 1 is not of type \"string\""), "{}", err);
+}
+
+// Test 28: Optional and defaulted parameters may be omitted
+#[tool]
+fn search(
+    query: String,
+    #[tool_param(default = 10)] limit: usize,
+    #[tool_param(default)] exact: bool,
+    language: Option<String>,
+) -> String {
+    format!("{} {} {} {:?}", query, limit, exact, language)
+}
+
+#[test]
+fn test_optional_and_default_params_can_be_omitted() {
+    let result = SearchArgs::call(serde_json::json!({ "query": "rust" })).unwrap();
+    assert_eq!(result, "\"rust 10 false None\"");
+
+    let result = SearchArgs::call(serde_json::json!({
+        "query": "rust",
+        "limit": 3,
+        "exact": true,
+        "language": "en"
+    }))
+    .unwrap();
+    assert_eq!(result, "\"rust 3 true Some(\\\"en\\\")\"");
+}
+
+#[test]
+fn test_schema_required_omits_optional_params() {
+    let schema = SearchArgs::tool_def().input_schema;
+    let object = schema.schema.object.expect("object schema");
+    let required: Vec<_> = object.required.iter().map(String::as_str).collect();
+    assert_eq!(required, vec!["query"]);
+
+    // The default value is advertised to the model
+    let limit = serde_json::to_value(&object.properties["limit"]).unwrap();
+    assert_eq!(limit["default"], 10);
+}
+
 // NOTE: Tests for methods with &self have been moved to tool_macro_method_tests.rs
 // because the macro currently has a limitation where it generates Args structs
 // inside impl blocks, which is invalid Rust syntax. Those tests are marked as
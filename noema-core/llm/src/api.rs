@@ -46,6 +46,75 @@ pub struct ToolDefinition {
     pub input_schema: schemars::schema::RootSchema,
 }
 
+impl ToolDefinition {
+    /// Compile `input_schema` for checking tool-call arguments
+    pub fn args_validator(&self) -> anyhow::Result<ToolArgsValidator> {
+        let schema = serde_json::to_value(&self.input_schema)?;
+        let validator = jsonschema::validator_for(&schema)
+            .map_err(|e| anyhow::anyhow!("Invalid input schema for tool '{}': {}", self.name, e))?;
+        Ok(ToolArgsValidator {
+            tool: self.name.clone(),
+            validator,
+        })
+    }
+
+    /// Check tool-call arguments against `input_schema`
+    ///
+    /// This compiles the schema on every call; code checking a tool's
+    /// arguments repeatedly should keep its [`args_validator`](Self::args_validator).
+    pub fn validate_args(&self, args: &serde_json::Value) -> anyhow::Result<()> {
+        self.args_validator()?.validate(args)
+    }
+}
+
+/// A tool's input schema, compiled once for checking any number of calls
+pub struct ToolArgsValidator {
+    tool: String,
+    validator: jsonschema::Validator,
+}
+
+impl ToolArgsValidator {
+    /// Check tool-call arguments against the schema
+    ///
+    /// The error lists every problem on its own line (missing fields, wrong
+    /// types, ...) so a model can read it and correct the call.
+    pub fn validate(&self, args: &serde_json::Value) -> anyhow::Result<()> {
+        let problems: Vec<String> = self
+            .validator
+            .iter_errors(args)
+            .map(|error| {
+                let path = error.instance_path.to_string();
+                let field = path.trim_start_matches('/').replace('/', ".");
+                match &error.kind {
+                    jsonschema::error::ValidationErrorKind::Required { property } => {
+                        let property = property
+                            .as_str()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| property.to_string());
+                        if field.is_empty() {
+                            format!("- missing required field '{}'", property)
+                        } else {
+                            format!("- missing required field '{}.{}'", field, property)
+                        }
+                    }
+                    _ if field.is_empty() => format!("- {}", error),
+                    _ => format!("- field '{}': {}", field, error),
+                }
+            })
+            .collect();
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "Invalid arguments for tool '{}':\n{}",
+                self.tool,
+                problems.join("\n")
+            ))
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ToolCall {
     pub id: String,
@@ -285,17 +354,135 @@ impl ChatPayload {
     }
 }
 
+/// Token counts reported by a provider for a single model call.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    /// Prompt tokens read from the provider's prompt cache (included in `prompt_tokens`)
+    #[serde(default)]
+    pub cached_tokens: u32,
+}
+
+impl TokenUsage {
+    pub fn new(prompt_tokens: u32, completion_tokens: u32) -> Self {
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+            cached_tokens: 0,
+        }
+    }
+
+    /// Set how many of the prompt tokens were served from the provider's cache
+    pub fn with_cached_tokens(mut self, cached_tokens: u32) -> Self {
+        self.cached_tokens = cached_tokens;
+        self
+    }
+}
+
+impl std::ops::Add for TokenUsage {
+    type Output = TokenUsage;
+
+    fn add(self, other: TokenUsage) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: self.prompt_tokens + other.prompt_tokens,
+            completion_tokens: self.completion_tokens + other.completion_tokens,
+            total_tokens: self.total_tokens + other.total_tokens,
+            cached_tokens: self.cached_tokens + other.cached_tokens,
+        }
+    }
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: TokenUsage) {
+        *self = *self + other;
+    }
+}
+
+/// Why the model stopped generating
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    /// The answer is complete, or a stop sequence was produced
+    Stop,
+    /// The output token limit (`max_tokens`) cut the answer off
+    Length,
+    /// The model stopped to call tools
+    ToolUse,
+    /// The provider withheld or cut off the answer (safety filters, refusals),
+    /// with the filter category when the provider names one
+    ContentFilter { category: Option<String> },
+    /// Any other reason the provider reported
+    Other,
+}
+
+/// Error for a response the provider's safety filter blocked before the
+/// model produced anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentFiltered {
+    pub category: Option<String>,
+}
+
+impl std::fmt::Display for ContentFiltered {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.category {
+            Some(category) => write!(f, "Response blocked by the provider's safety filter ({})", category),
+            None => write!(f, "Response blocked by the provider's safety filter"),
+        }
+    }
+}
+
+impl std::error::Error for ContentFiltered {}
+
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
 pub struct ChatMessage {
     #[serde(default)]
     pub role: Role,
     #[serde(flatten)]
     pub payload: ChatPayload,
+    /// Token usage for the call that produced this message (None if the provider didn't report it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    /// Why the model stopped (None if the provider didn't say)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<FinishReason>,
 }
 
 impl ChatMessage {
     pub fn new(role: Role, payload: ChatPayload) -> Self {
-        Self { role, payload }
+        Self { role, payload, usage: None, finish_reason: None }
+    }
+
+    pub fn with_usage(mut self, usage: Option<TokenUsage>) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    pub fn with_finish_reason(mut self, finish_reason: Option<FinishReason>) -> Self {
+        self.finish_reason = finish_reason;
+        self
+    }
+
+    /// Whether the output token limit cut this message off
+    pub fn is_truncated(&self) -> bool {
+        self.finish_reason == Some(FinishReason::Length)
+    }
+
+    /// The filter that blocked this message, if the provider filtered it
+    /// before any text or tool call was produced
+    ///
+    /// Refusals that come with an explanation are ordinary messages.
+    pub fn blocked(&self) -> Option<ContentFiltered> {
+        match &self.finish_reason {
+            Some(FinishReason::ContentFilter { category })
+                if self.get_text().trim().is_empty() && self.get_tool_calls().is_empty() =>
+            {
+                Some(ContentFiltered { category: category.clone() })
+            }
+            _ => None,
+        }
     }
 
     pub fn user(payload: ChatPayload) -> Self {
@@ -327,16 +514,58 @@ impl ChatMessage {
     }
 }
 
+/// Part of a tool call's arguments, streamed before the call is complete
+///
+/// The first delta for a call may have an empty fragment; it announces the
+/// call as soon as the model starts it. The assembled call still arrives as
+/// a `ContentBlock::ToolCall` once its arguments are complete.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ToolCallDelta {
+    pub id: String,
+    pub name: String,
+    /// Next piece of the JSON arguments (not valid JSON on its own)
+    pub args_fragment: String,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ChatChunk {
     pub role: Role,
     #[serde(flatten)]
     pub payload: ChatPayload,
+    /// Token usage, typically only present on the final chunk of a stream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    /// Arguments of a tool call still being generated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_delta: Option<ToolCallDelta>,
+    /// Why the model stopped, on the chunk that ends the stream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<FinishReason>,
 }
 
 impl ChatChunk {
     pub fn new(role: Role, payload: ChatPayload) -> Self {
-        Self { role, payload }
+        Self { role, payload, usage: None, tool_call_delta: None, finish_reason: None }
+    }
+
+    /// Create an empty assistant chunk that only carries usage
+    pub fn usage(usage: TokenUsage) -> Self {
+        Self::assistant(ChatPayload::default()).with_usage(Some(usage))
+    }
+
+    /// Create an empty assistant chunk that only carries a tool-call delta
+    pub fn tool_call_delta(delta: ToolCallDelta) -> Self {
+        Self { tool_call_delta: Some(delta), ..Self::assistant(ChatPayload::default()) }
+    }
+
+    pub fn with_usage(mut self, usage: Option<TokenUsage>) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    pub fn with_finish_reason(mut self, finish_reason: Option<FinishReason>) -> Self {
+        self.finish_reason = finish_reason;
+        self
     }
 
     pub fn user(payload: ChatPayload) -> Self {
@@ -361,6 +590,93 @@ impl From<ChatChunk> for ChatMessage {
         ChatMessage {
             role: chunk.role,
             payload: chunk.payload,
+            usage: chunk.usage,
+            finish_reason: chunk.finish_reason,
+        }
+    }
+}
+
+/// Sampling settings for a request; unset fields use the provider's defaults
+///
+/// Providers send the fields their API supports and ignore the rest.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct GenerationParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Maximum number of tokens to generate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Sequences that end generation when produced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    /// Sampling seed for reproducible runs
+    ///
+    /// Determinism is best-effort: providers may still vary between runs
+    /// (OpenAI reports this through `system_fingerprint`), and providers
+    /// without seed support ignore it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+/// Shape the model's reply must take
+///
+/// Providers with a native JSON mode use it; Claude is made to answer by
+/// calling a tool whose input schema is the requested shape.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", content = "schema", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// Free-form text
+    #[default]
+    Text,
+    /// Any JSON object
+    JsonObject,
+    /// JSON matching this schema
+    JsonSchema(serde_json::Value),
+}
+
+impl ResponseFormat {
+    /// Schema a JSON reply must match (None for free-form text)
+    pub fn schema(&self) -> Option<serde_json::Value> {
+        match self {
+            ResponseFormat::Text => None,
+            ResponseFormat::JsonObject => Some(serde_json::json!({ "type": "object" })),
+            ResponseFormat::JsonSchema(schema) => Some(schema.clone()),
+        }
+    }
+
+    /// Check that a reply's text has this format
+    ///
+    /// Like `ToolArgsValidator::validate`, the error lists every schema
+    /// violation on its own line.
+    pub fn validate(&self, text: &str) -> anyhow::Result<()> {
+        let Some(schema) = self.schema() else {
+            return Ok(());
+        };
+        let value: serde_json::Value = serde_json::from_str(text.trim())
+            .map_err(|e| anyhow::anyhow!("Response is not valid JSON: {}", e))?;
+        let validator = jsonschema::validator_for(&schema)
+            .map_err(|e| anyhow::anyhow!("Invalid response schema: {}", e))?;
+
+        let problems: Vec<String> = validator
+            .iter_errors(&value)
+            .map(|error| {
+                let path = error.instance_path.to_string();
+                match path.trim_start_matches('/').replace('/', ".") {
+                    field if field.is_empty() => format!("- {}", error),
+                    field => format!("- field '{}': {}", field, error),
+                }
+            })
+            .collect();
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "Response does not match the requested schema:\n{}",
+                problems.join("\n")
+            ))
         }
     }
 }
@@ -369,6 +685,12 @@ impl From<ChatChunk> for ChatMessage {
 pub struct ChatRequest {
     pub(crate) messages: Vec<ChatMessage>,
     pub(crate) tools: Option<Vec<ToolDefinition>>,
+    #[serde(default)]
+    pub(crate) params: GenerationParams,
+    #[serde(default)]
+    pub(crate) cache_system_prompt: bool,
+    #[serde(default)]
+    pub(crate) response_format: ResponseFormat,
 }
 
 impl ChatRequest {
@@ -384,6 +706,9 @@ impl ChatRequest {
         ChatRequest {
             messages: messages.into_iter().cloned().collect(),
             tools: None,
+            params: GenerationParams::default(),
+            cache_system_prompt: false,
+            response_format: ResponseFormat::Text,
         }
     }
 
@@ -395,6 +720,9 @@ impl ChatRequest {
         ChatRequest {
             messages: messages.into_iter().cloned().collect(),
             tools: Some(tools),
+            params: GenerationParams::default(),
+            cache_system_prompt: false,
+            response_format: ResponseFormat::Text,
         }
     }
 
@@ -403,6 +731,49 @@ impl ChatRequest {
         &self.messages
     }
 
+    /// Get the tools offered to the model, if any
+    pub fn tools(&self) -> Option<&[ToolDefinition]> {
+        self.tools.as_deref()
+    }
+
+    /// Set the sampling settings for this request
+    pub fn with_params(mut self, params: GenerationParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Get the sampling settings for this request
+    pub fn params(&self) -> &GenerationParams {
+        &self.params
+    }
+
+    /// Ask the provider to cache the system prompt (and the tools before it)
+    ///
+    /// Claude marks the end of the system prompt as a cache breakpoint, so
+    /// later requests with the same prefix are billed at the cached rate.
+    /// Gemini caches repeated prefixes implicitly and ignores this flag.
+    /// Cache hits are reported in `TokenUsage::cached_tokens`.
+    pub fn with_cache_system_prompt(mut self, cache: bool) -> Self {
+        self.cache_system_prompt = cache;
+        self
+    }
+
+    /// Whether the provider should cache the system prompt
+    pub fn cache_system_prompt(&self) -> bool {
+        self.cache_system_prompt
+    }
+
+    /// Require the reply to be JSON (see `ResponseFormat`)
+    pub fn with_response_format(mut self, format: ResponseFormat) -> Self {
+        self.response_format = format;
+        self
+    }
+
+    /// Get the shape the reply must take
+    pub fn response_format(&self) -> &ResponseFormat {
+        &self.response_format
+    }
+
     /// Get a mutable reference to the messages (for external resolution)
     pub fn messages_mut(&mut self) -> &mut Vec<ChatMessage> {
         &mut self.messages
@@ -446,6 +817,7 @@ mod tests {
             id: "call_123".to_string(),
             name: "search".to_string(),
             arguments: serde_json::json!({"query": "test"}),
+            extra: serde_json::Value::Null,
         };
 
         let payload = ChatPayload::with_tool_calls(
@@ -495,6 +867,7 @@ mod tests {
             id: "call_456".to_string(),
             name: "calculator".to_string(),
             arguments: serde_json::json!({"a": 5, "b": 3}),
+            extra: serde_json::Value::Null,
         };
 
         let payload = ChatPayload::with_tool_calls(
@@ -564,6 +937,7 @@ mod tests {
             id: "call_abc".to_string(),
             name: "test_tool".to_string(),
             arguments: serde_json::json!({"key": "value"}),
+            extra: serde_json::Value::Null,
         };
 
         let block = ContentBlock::ToolCall(tool_call);
@@ -611,6 +985,7 @@ mod tests {
                 id: "call_1".to_string(),
                 name: "tool1".to_string(),
                 arguments: serde_json::json!({}),
+                extra: serde_json::Value::Null,
             }),
             ContentBlock::Text {
                 text: "Text after tool".to_string(),
@@ -621,4 +996,37 @@ mod tests {
         assert_eq!(payload.get_tool_calls().len(), 1);
         assert_eq!(payload.content.len(), 3);
     }
+
+    #[test]
+    fn test_token_usage_add() {
+        let total = TokenUsage::new(10, 5) + TokenUsage::new(3, 2);
+        assert_eq!(total, TokenUsage::new(13, 7));
+        assert_eq!(total.total_tokens, 20);
+    }
+
+    #[test]
+    fn test_chat_message_usage_skipped_when_none() {
+        let message = ChatMessage::assistant(ChatPayload::text("hi"));
+        let json = serde_json::to_value(&message).unwrap();
+        assert!(json.get("usage").is_none());
+
+        let message = message.with_usage(Some(TokenUsage::new(1, 2)));
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["usage"]["total_tokens"], 3);
+    }
+
+    #[test]
+    fn test_response_format_validation() {
+        let format = ResponseFormat::JsonSchema(serde_json::to_value(schemars::schema_for!(TestInput)).unwrap());
+        assert!(format.validate(r#"{"query": "rust"}"#).is_ok());
+
+        let err = format.validate(r#"{"query": 3}"#).unwrap_err().to_string();
+        assert!(err.contains("field 'query'"), "{}", err);
+        let err = format.validate("Sure! Here you go").unwrap_err().to_string();
+        assert!(err.starts_with("Response is not valid JSON"), "{}", err);
+
+        assert!(ResponseFormat::JsonObject.validate("[1, 2]").is_err());
+        assert!(ResponseFormat::JsonObject.validate(r#"{"any": "thing"}"#).is_ok());
+        assert!(ResponseFormat::Text.validate("anything").is_ok());
+    }
 }
@@ -0,0 +1,337 @@
+//! Cached model lists
+//!
+//! Listing models asks every provider over the network. A [`ModelCatalog`]
+//! keeps the last list each provider returned, on disk at
+//! [`PathManager::model_catalog_path`], and answers from it:
+//!
+//! - within the TTL the cached list is returned as is;
+//! - past it the cached list is still returned while a background task
+//!   fetches a fresh one, so listing works offline;
+//! - a list cached under another configuration of the provider (API key,
+//!   base URL, compatible-provider entry) is ignored and fetched again.
+//!
+//! [`ModelCatalog::refresh`] fetches regardless of age.
+
+use crate::provider_urls::ProviderUrls;
+use crate::registry::{provider_fingerprint, provider_names, resolve_provider, ModelId, ModelInfo};
+use crate::ModelDefinition;
+use async_trait::async_trait;
+use config::{PathManager, Settings};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A provider to list and the fingerprint of its configuration, a value
+/// that changes whenever the configuration does
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceProvider {
+    pub name: String,
+    pub fingerprint: String,
+}
+
+/// Where a catalog gets its providers and their model lists
+#[async_trait]
+pub trait ModelSource: Send + Sync {
+    /// Providers to list, in order
+    fn providers(&self) -> Vec<SourceProvider>;
+
+    /// The fingerprint of one provider's configuration
+    fn fingerprint(&self, provider: &str) -> String;
+
+    async fn list_models(&self, provider: &str) -> anyhow::Result<Vec<ModelDefinition>>;
+}
+
+/// The providers configured in settings.toml and providers.toml
+///
+/// Each call reads the configuration once, however many providers it covers.
+pub struct ConfiguredProviders;
+
+#[async_trait]
+impl ModelSource for ConfiguredProviders {
+    fn providers(&self) -> Vec<SourceProvider> {
+        let settings = Settings::load();
+        let urls = ProviderUrls::from_config_file();
+        provider_names(&settings)
+            .into_iter()
+            .map(|name| SourceProvider {
+                fingerprint: provider_fingerprint(&name, &settings, &urls),
+                name,
+            })
+            .collect()
+    }
+
+    fn fingerprint(&self, provider: &str) -> String {
+        provider_fingerprint(provider, &Settings::load(), &ProviderUrls::from_config_file())
+    }
+
+    async fn list_models(&self, provider: &str) -> anyhow::Result<Vec<ModelDefinition>> {
+        resolve_provider(provider, &Settings::load(), &ProviderUrls::from_config_file())?
+            .list_models()
+            .await
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CatalogEntry {
+    fingerprint: String,
+    /// Unix millis
+    fetched_at: i64,
+    models: Vec<ModelDefinition>,
+}
+
+/// Model lists per provider, cached with a TTL
+pub struct ModelCatalog {
+    source: Arc<dyn ModelSource>,
+    path: Option<PathBuf>,
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CatalogEntry>>,
+    /// Providers with a background refresh running
+    refreshing: Mutex<HashSet<String>>,
+}
+
+impl ModelCatalog {
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+    /// Catalog of the configured providers, cached at the default path
+    pub fn open_default() -> Self {
+        Self::new(Arc::new(ConfiguredProviders), PathManager::model_catalog_path(), Self::DEFAULT_TTL)
+    }
+
+    /// Catalog of `source`'s providers, cached at `path` (in memory only
+    /// without one). A missing or unreadable cache file starts it empty.
+    pub fn new(source: Arc<dyn ModelSource>, path: Option<PathBuf>, ttl: Duration) -> Self {
+        let entries = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self {
+            source,
+            path,
+            ttl,
+            entries: Mutex::new(entries),
+            refreshing: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Models of one provider, from the cache when it has them
+    pub async fn list_models(self: &Arc<Self>, provider: &str) -> anyhow::Result<Vec<ModelInfo>> {
+        self.list_cached(provider, self.source.fingerprint(provider)).await
+    }
+
+    /// Models of every provider, from the cache when it has them; providers
+    /// without a cached list are asked concurrently
+    pub async fn list_all_models(self: &Arc<Self>) -> Vec<(String, anyhow::Result<Vec<ModelInfo>>)> {
+        let providers = self.source.providers();
+        let results = futures::future::join_all(
+            providers.iter().map(|provider| self.list_cached(&provider.name, provider.fingerprint.clone())),
+        )
+        .await;
+        providers.into_iter().map(|provider| provider.name).zip(results).collect()
+    }
+
+    /// Fetch the models of `provider`, or of every provider, now
+    ///
+    /// A failed fetch leaves the provider's cached list in place.
+    pub async fn refresh(&self, provider: Option<&str>) -> Vec<(String, anyhow::Result<Vec<ModelInfo>>)> {
+        let providers = match provider {
+            Some(provider) => vec![SourceProvider {
+                name: provider.to_string(),
+                fingerprint: self.source.fingerprint(provider),
+            }],
+            None => self.source.providers(),
+        };
+        let results = futures::future::join_all(
+            providers.iter().map(|provider| self.fetch(&provider.name, provider.fingerprint.clone())),
+        )
+        .await;
+        providers.into_iter().map(|provider| provider.name).zip(results).collect()
+    }
+
+    /// The cached definition of the model `id`, if its provider's list was
+    /// cached under `fingerprint`. Never fetches.
+    pub fn cached_definition(&self, id: &ModelId, fingerprint: &str) -> Option<ModelDefinition> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(&id.provider)
+            .filter(|entry| entry.fingerprint == fingerprint)
+            .and_then(|entry| entry.models.iter().find(|model| model.id == id.model).cloned())
+    }
+
+    /// Forget the cached list of `provider`, so the next listing fetches it
+    pub fn invalidate(&self, provider: &str) {
+        if self.entries.lock().unwrap().remove(provider).is_some() {
+            self.save();
+        }
+    }
+
+    /// The cached list of `provider` if it was fetched under `fingerprint`,
+    /// otherwise a fresh one
+    async fn list_cached(self: &Arc<Self>, provider: &str, fingerprint: String) -> anyhow::Result<Vec<ModelInfo>> {
+        let cached = self
+            .entries
+            .lock()
+            .unwrap()
+            .get(provider)
+            .filter(|entry| entry.fingerprint == fingerprint)
+            .cloned();
+        match cached {
+            Some(entry) => {
+                if self.is_stale(&entry) {
+                    self.refresh_in_background(provider, fingerprint);
+                }
+                Ok(model_infos(provider, entry.models))
+            }
+            None => self.fetch(provider, fingerprint).await,
+        }
+    }
+
+    fn is_stale(&self, entry: &CatalogEntry) -> bool {
+        let age = chrono::Utc::now().timestamp_millis() - entry.fetched_at;
+        age < 0 || age as u128 >= self.ttl.as_millis()
+    }
+
+    async fn fetch(&self, provider: &str, fingerprint: String) -> anyhow::Result<Vec<ModelInfo>> {
+        let models = self.source.list_models(provider).await?;
+        let entry = CatalogEntry {
+            fingerprint,
+            fetched_at: chrono::Utc::now().timestamp_millis(),
+            models: models.clone(),
+        };
+        self.entries.lock().unwrap().insert(provider.to_string(), entry);
+        self.save();
+        Ok(model_infos(provider, models))
+    }
+
+    fn refresh_in_background(self: &Arc<Self>, provider: &str, fingerprint: String) {
+        if !self.refreshing.lock().unwrap().insert(provider.to_string()) {
+            return;
+        }
+        let catalog = Arc::clone(self);
+        let provider = provider.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = catalog.fetch(&provider, fingerprint).await {
+                tracing::debug!("Keeping the cached models of {}: {}", provider, e);
+            }
+            catalog.refreshing.lock().unwrap().remove(&provider);
+        });
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let Ok(json) = serde_json::to_string(&*self.entries.lock().unwrap()) else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = std::fs::write(path, json) {
+            tracing::warn!("Failed to write model catalog {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn model_infos(provider: &str, models: Vec<ModelDefinition>) -> Vec<ModelInfo> {
+    models
+        .into_iter()
+        .map(|definition| ModelInfo { id: ModelId::new(provider, &definition.id), definition })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// One provider whose list has as many models as it has been asked for
+    #[derive(Default)]
+    struct CountingSource {
+        calls: AtomicUsize,
+        offline: AtomicBool,
+        fingerprint: Mutex<String>,
+    }
+
+    #[async_trait]
+    impl ModelSource for CountingSource {
+        fn providers(&self) -> Vec<SourceProvider> {
+            vec![SourceProvider {
+                name: "local".to_string(),
+                fingerprint: self.fingerprint("local"),
+            }]
+        }
+
+        fn fingerprint(&self, _provider: &str) -> String {
+            self.fingerprint.lock().unwrap().clone()
+        }
+
+        async fn list_models(&self, _provider: &str) -> anyhow::Result<Vec<ModelDefinition>> {
+            if self.offline.load(Ordering::SeqCst) {
+                anyhow::bail!("offline");
+            }
+            let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok((0..calls).map(|i| ModelDefinition::text_model(format!("model-{}", i))).collect())
+        }
+    }
+
+    fn cache_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("noema-catalog-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("models.json")
+    }
+
+    #[tokio::test]
+    async fn test_fresh_list_comes_from_the_cache_file() {
+        let path = cache_path("fresh");
+        let source = Arc::new(CountingSource::default());
+        let catalog = Arc::new(ModelCatalog::new(source.clone(), Some(path.clone()), ModelCatalog::DEFAULT_TTL));
+
+        assert_eq!(catalog.list_models("local").await.unwrap().len(), 1);
+        assert_eq!(catalog.list_models("local").await.unwrap().len(), 1);
+        assert_eq!(source.calls.load(Ordering::SeqCst), 1);
+
+        // A new catalog reads what the first one saved
+        let reopened = Arc::new(ModelCatalog::new(source.clone(), Some(path), ModelCatalog::DEFAULT_TTL));
+        let models = reopened.list_all_models().await;
+        assert_eq!(models[0].1.as_ref().unwrap()[0].id.to_string(), "local/model-0");
+        assert_eq!(source.calls.load(Ordering::SeqCst), 1);
+
+        // Refreshing asks again even though the list is fresh
+        assert_eq!(reopened.refresh(None).await[0].1.as_ref().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_list_is_served_while_refreshing() {
+        let source = Arc::new(CountingSource::default());
+        let catalog = Arc::new(ModelCatalog::new(source.clone(), None, Duration::ZERO));
+        catalog.list_models("local").await.unwrap();
+
+        // Still answered while the provider is unreachable
+        source.offline.store(true, Ordering::SeqCst);
+        assert_eq!(catalog.list_models("local").await.unwrap().len(), 1);
+        assert!(catalog.refresh(Some("local")).await[0].1.is_err());
+
+        // Back online, the background refresh replaces the stale list
+        source.offline.store(false, Ordering::SeqCst);
+        assert_eq!(catalog.list_models("local").await.unwrap().len(), 1);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(catalog.list_models("local").await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_config_change_invalidates_the_list() {
+        let source = Arc::new(CountingSource::default());
+        let catalog = Arc::new(ModelCatalog::new(source.clone(), None, ModelCatalog::DEFAULT_TTL));
+        catalog.list_models("local").await.unwrap();
+
+        *source.fingerprint.lock().unwrap() = "new key".to_string();
+        assert_eq!(catalog.list_models("local").await.unwrap().len(), 2);
+
+        catalog.invalidate("local");
+        assert_eq!(catalog.list_models("local").await.unwrap().len(), 3);
+    }
+}
@@ -3,22 +3,244 @@ use futures::{
     StreamExt,
     stream::{self},
 };
-use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
 use serde::{Serialize, de::DeserializeOwned};
-use std::{fmt::Debug, pin::Pin};
+use serde_json::Value;
+use std::time::Duration;
+use std::{fmt::Debug, pin::Pin, sync::Arc};
 use tracing::{Level, event, instrument};
 
+use crate::replay::{recorded_error, RecordedOutcome, TrafficReplay};
+use crate::traffic_log::{self, TrafficKind, TrafficLogger, TrafficRecord};
+
 #[derive(Clone)]
 pub struct Client {
     client: reqwest::Client,
+    traffic_logger: Option<Arc<dyn TrafficLogger>>,
+    /// Recorded traffic answering posts in place of the network
+    replay: Option<Arc<TrafficReplay>>,
+    /// Model id that traffic records are tagged with
+    model: String,
+    timeouts: Timeouts,
+}
+
+/// How long a provider call may hang before it fails with a `Timeout`
+/// `ProviderError`; `None` waits indefinitely
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Time allowed for a whole response, or for a stream to start
+    pub request_timeout: Option<Duration>,
+    /// Longest gap allowed between two pieces of a streamed response
+    pub stream_idle_timeout: Option<Duration>,
+}
+
+impl Timeouts {
+    pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+    pub const DEFAULT_STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            request_timeout: Some(Self::DEFAULT_REQUEST_TIMEOUT),
+            stream_idle_timeout: Some(Self::DEFAULT_STREAM_IDLE_TIMEOUT),
+        }
+    }
 }
 
 pub type BoxedStream<T> = Pin<Box<dyn Stream<Item = T> + Send>>;
 
+/// What a provider's error response says went wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderErrorKind {
+    /// The request was malformed or rejected (400 and other 4xx)
+    InvalidRequest,
+    /// The API key is missing, invalid or lacks permission (401, 403)
+    Auth,
+    /// The model or endpoint doesn't exist (404)
+    NotFound,
+    /// The request timed out on the provider's side (408)
+    Timeout,
+    /// Too many requests (429)
+    RateLimited,
+    /// The provider is temporarily overloaded (503, 529)
+    Overloaded,
+    /// Any other server error (5xx)
+    Server,
+}
+
+impl ProviderErrorKind {
+    fn from_status(status: StatusCode, error_type: Option<&str>) -> Self {
+        if error_type == Some("overloaded_error") {
+            return Self::Overloaded;
+        }
+        match status.as_u16() {
+            401 | 403 => Self::Auth,
+            404 => Self::NotFound,
+            408 => Self::Timeout,
+            429 => Self::RateLimited,
+            503 | 529 => Self::Overloaded,
+            _ if status.is_server_error() => Self::Server,
+            _ => Self::InvalidRequest,
+        }
+    }
+}
+
+/// Non-success HTTP response from a provider.
+///
+/// Returned inside `anyhow::Error` so callers can downcast to inspect the
+/// status, kind and how long the provider asked to wait before retrying.
+#[derive(Debug, Clone)]
+pub struct ProviderError {
+    pub status: StatusCode,
+    pub kind: ProviderErrorKind,
+    /// The provider's error message, or the whole body if it had none
+    pub message: String,
+    pub body: String,
+    /// Delay requested by a `Retry-After` (or `retry-after-ms`) header
+    pub retry_after: Option<Duration>,
+}
+
+impl ProviderError {
+    /// Build an error from a status and response body, without headers
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self::from_response(status, &HeaderMap::new(), body.into())
+    }
+
+    /// Build an error from a non-success response.
+    ///
+    /// Understands the error bodies of Claude and Gemini
+    /// (`{"error": {"type"|"status": ..., "message": ...}}`), OpenAI-style
+    /// APIs (`{"error": {"message": ..., "code": ...}}`) and Ollama
+    /// (`{"error": "..."}`).
+    pub fn from_response(status: StatusCode, headers: &HeaderMap, body: String) -> Self {
+        let json: Option<Value> = serde_json::from_str(&body).ok();
+        let error = json.as_ref().and_then(|json| json.get("error"));
+        let message = error
+            .and_then(|error| match error {
+                Value::String(message) => Some(message.as_str()),
+                error => error.get("message").and_then(Value::as_str),
+            })
+            .or_else(|| json.as_ref()?.get("message")?.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| body.clone());
+        let error_type = error.and_then(|error| error.get("type")?.as_str());
+
+        Self {
+            status,
+            kind: ProviderErrorKind::from_status(status, error_type),
+            message,
+            body,
+            retry_after: parse_retry_after(headers),
+        }
+    }
+
+    /// Build the error for a call that got no response in time
+    pub fn timeout(message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self {
+            status: StatusCode::REQUEST_TIMEOUT,
+            kind: ProviderErrorKind::Timeout,
+            body: message.clone(),
+            message,
+            retry_after: None,
+        }
+    }
+
+    /// Whether retrying the same request later may succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self.kind,
+            ProviderErrorKind::Timeout
+                | ProviderErrorKind::RateLimited
+                | ProviderErrorKind::Overloaded
+                | ProviderErrorKind::Server
+        )
+    }
+}
+
+impl std::fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Request failed with status {}: {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+/// Read the delay a provider asked for, from `retry-after-ms` (OpenAI) or
+/// `Retry-After` in seconds or as an HTTP date
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok()).map(str::trim);
+
+    if let Some(millis) = header("retry-after-ms").and_then(|v| v.parse::<f64>().ok()) {
+        return Duration::try_from_secs_f64(millis / 1000.0).ok();
+    }
+    let value = header(RETRY_AFTER.as_str())?;
+    if let Ok(secs) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
+/// Fail with a `Timeout` error if `future` takes longer than `limit`
+async fn within<T>(
+    limit: Option<Duration>,
+    future: impl std::future::Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    let Some(limit) = limit else {
+        return future.await;
+    };
+    tokio::time::timeout(limit, future).await.unwrap_or_else(|_| {
+        Err(ProviderError::timeout(format!("No response from the provider within {}s", limit.as_secs())).into())
+    })
+}
+
+/// End `stream` with a `Timeout` error when nothing arrives for `idle`
+fn with_idle_timeout<B, E>(
+    stream: impl Stream<Item = Result<B, E>> + Send + 'static,
+    idle: Option<Duration>,
+) -> BoxedStream<anyhow::Result<B>>
+where
+    B: Send + 'static,
+    E: Into<anyhow::Error> + Send + 'static,
+{
+    let stream: BoxedStream<Result<B, E>> = Box::pin(stream);
+    Box::pin(stream::unfold(Some(stream), move |stream| async move {
+        let mut stream = stream?;
+        let next = match idle {
+            Some(idle) => match tokio::time::timeout(idle, stream.next()).await {
+                Ok(next) => next,
+                Err(_) => {
+                    let message = format!("The provider stopped responding: nothing received for {}s", idle.as_secs());
+                    return Some((Err(ProviderError::timeout(message).into()), None));
+                }
+            },
+            None => stream.next().await,
+        };
+        next.map(|item| (item.map_err(Into::into), Some(stream)))
+    }))
+}
+
+/// Turn a non-success response into a `ProviderError`
+async fn error_response(response: reqwest::Response) -> ProviderError {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
+    ProviderError::from_response(status, &headers, body)
+}
+
 impl Client {
     pub fn default() -> Self {
         Client {
             client: reqwest::Client::new(),
+            traffic_logger: traffic_log::env_logger(),
+            replay: None,
+            model: String::new(),
+            timeouts: Timeouts::default(),
         }
     }
 
@@ -28,6 +250,46 @@ impl Client {
                 .default_headers(headers)
                 .build()
                 .expect("Failed to build headers"),
+            traffic_logger: traffic_log::env_logger(),
+            replay: None,
+            model: String::new(),
+            timeouts: Timeouts::default(),
+        }
+    }
+
+    /// Send the raw traffic of this client to `logger`
+    pub fn set_traffic_logger(&mut self, logger: Arc<dyn TrafficLogger>) {
+        self.traffic_logger = Some(logger);
+    }
+
+    pub fn set_timeouts(&mut self, timeouts: Timeouts) {
+        self.timeouts = timeouts;
+    }
+
+    /// Answer posts from `replay` instead of sending them
+    pub fn set_replay(&mut self, replay: Arc<TrafficReplay>) {
+        self.replay = Some(replay);
+    }
+
+    /// A client sharing this connection pool whose traffic is tagged with `model`
+    pub fn for_model(&self, model: &str) -> Client {
+        Client {
+            model: model.to_string(),
+            ..self.clone()
+        }
+    }
+
+    fn log_traffic(&self, kind: TrafficKind, body: Value) {
+        if let Some(logger) = &self.traffic_logger {
+            logger.log(&TrafficRecord::new(&self.model, kind, body));
+        }
+    }
+
+    /// Log an error response with its status, so a replay fails the same way
+    fn log_error_response(&self, error: &ProviderError) {
+        if let Some(logger) = &self.traffic_logger {
+            let record = TrafficRecord::new(&self.model, TrafficKind::Error, parse_body(&error.body));
+            logger.log(&record.with_status(error.status.as_u16()));
         }
     }
 
@@ -37,15 +299,14 @@ impl Client {
         U: reqwest::IntoUrl + std::fmt::Debug,
         T: DeserializeOwned,
     {
-        let response = self.client.get(url).send().await?;
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Request failed with status: {} - {:?}",
-                response.status(),
-                response.error_for_status()
-            ));
-        }
-        let text = response.text().await?;
+        let text = within(self.timeouts.request_timeout, async {
+            let response = self.client.get(url).send().await?;
+            if !response.status().is_success() {
+                return Err(error_response(response).await.into());
+            }
+            Ok(response.text().await?)
+        })
+        .await?;
         event!(Level::TRACE, response = text);
 
         Ok(serde_json::from_str::<T>(&text)?)
@@ -58,18 +319,30 @@ impl Client {
         S: Serialize + Sized,
         T: DeserializeOwned,
     {
-        let response = self.client.post(url).json(request).send().await?;
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
-            return Err(anyhow::anyhow!(
-                "Request failed with status {}: {}",
-                status,
-                error_body
-            ));
+        if let Some(replay) = &self.replay {
+            return match replay.next(serde_json::to_value(request)?)? {
+                RecordedOutcome::Response(body) => Ok(serde_json::from_value(body)?),
+                RecordedOutcome::Error { status, body } => Err(recorded_error(status, body)),
+                RecordedOutcome::Stream(_) => {
+                    Err(anyhow::anyhow!("The recorded response to this request was streamed"))
+                }
+            };
+        }
+        if self.traffic_logger.is_some() {
+            self.log_traffic(TrafficKind::Request, serde_json::to_value(request)?);
         }
-        let text = response.text().await?;
+        let text = within(self.timeouts.request_timeout, async {
+            let response = self.client.post(url).json(request).send().await?;
+            if !response.status().is_success() {
+                let error = error_response(response).await;
+                self.log_error_response(&error);
+                return Err(error.into());
+            }
+            Ok(response.text().await?)
+        })
+        .await?;
         event!(Level::TRACE, response = text);
+        self.log_traffic(TrafficKind::Response, parse_body(&text));
 
         Ok(serde_json::from_str::<T>(&text)?)
     }
@@ -80,41 +353,54 @@ impl Client {
         url: U,
         request: &S,
         process: F,
-    ) -> anyhow::Result<BoxedStream<T>>
+    ) -> anyhow::Result<BoxedStream<anyhow::Result<T>>>
     where
         U: reqwest::IntoUrl + Debug,
         S: Serialize + Sized,
         T: DeserializeOwned + Send + 'static,
         F: Fn(&str) -> Option<&str> + 'static + Send,
     {
-        let response = self.client.post(url).json(&request).send().await?;
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
-            return Err(anyhow::anyhow!(
-                "Request failed with status {}: {}",
-                status,
-                error_body
-            ));
+        if let Some(replay) = &self.replay {
+            return match replay.next(serde_json::to_value(request)?)? {
+                RecordedOutcome::Stream(chunks) => Ok(Box::pin(stream::iter(
+                    chunks.into_iter().map(|chunk| serde_json::from_value(chunk).map_err(Into::into)),
+                ))),
+                RecordedOutcome::Error { status, body } => Err(recorded_error(status, body)),
+                RecordedOutcome::Response(_) => {
+                    Err(anyhow::anyhow!("The recorded response to this request wasn't streamed"))
+                }
+            };
         }
+        if self.traffic_logger.is_some() {
+            self.log_traffic(TrafficKind::Request, serde_json::to_value(request)?);
+        }
+        let response = within(self.timeouts.request_timeout, async {
+            let response = self.client.post(url).json(&request).send().await?;
+            if !response.status().is_success() {
+                let error = error_response(response).await;
+                self.log_error_response(&error);
+                return Err(error.into());
+            }
+            Ok(response)
+        })
+        .await?;
 
-        let bytes = response.bytes_stream();
+        let bytes = with_idle_timeout(response.bytes_stream(), self.timeouts.stream_idle_timeout);
+        let tap = self.clone();
 
         // Use scan to maintain state (buffer) across chunks
         let buffered_stream = bytes.scan(String::new(), move |buffer, chunk| {
+            // Pass read errors and timeouts on to the caller
             let chunk = match chunk {
                 Ok(c) => c,
-                Err(e) => {
-                    eprintln!("Error reading chunk: {}", e);
-                    return futures::future::ready(Some(vec![]));
-                }
+                Err(e) => return futures::future::ready(Some(vec![Err(e)])),
             };
 
             // Append new chunk data to buffer
             buffer.push_str(&String::from_utf8_lossy(&chunk));
 
             // Process complete lines (ending with \n)
-            let mut messages: Vec<T> = vec![];
+            let mut messages: Vec<anyhow::Result<T>> = vec![];
             let mut last_newline_pos = 0;
 
             for (idx, _) in buffer.match_indices('\n') {
@@ -123,8 +409,9 @@ impl Client {
 
                 if let Some(processed) = process(line) {
                     if !processed.trim().is_empty() {
+                        tap.log_traffic(TrafficKind::StreamChunk, parse_body(processed));
                         match serde_json::from_str::<T>(processed) {
-                            Ok(chat_response) => messages.push(chat_response),
+                            Ok(chat_response) => messages.push(Ok(chat_response)),
                             Err(e) => {
                                 eprintln!("Failed to parse line: {}: {}", processed, e);
                             }
@@ -143,12 +430,86 @@ impl Client {
     }
 }
 
+/// Parse a body as JSON for the traffic log, keeping it as a string otherwise
+fn parse_body(text: &str) -> Value {
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use futures::StreamExt;
+    use reqwest::header::HeaderValue;
     use serde::{Deserialize, Serialize};
 
+    #[test]
+    fn test_provider_error_parses_claude_body() {
+        let body = r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        let error = ProviderError::new(StatusCode::from_u16(529).unwrap(), body);
+        assert_eq!(error.kind, ProviderErrorKind::Overloaded);
+        assert_eq!(error.message, "Overloaded");
+        assert!(error.is_transient());
+
+        let body = r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#;
+        let error = ProviderError::new(StatusCode::UNAUTHORIZED, body);
+        assert_eq!(error.kind, ProviderErrorKind::Auth);
+        assert_eq!(error.message, "invalid x-api-key");
+        assert!(!error.is_transient());
+    }
+
+    #[test]
+    fn test_provider_error_parses_openai_body() {
+        let body = r#"{"error":{"message":"Rate limit reached for gpt-4o","type":"requests","param":null,"code":"rate_limit_exceeded"}}"#;
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("20"));
+        let error = ProviderError::from_response(StatusCode::TOO_MANY_REQUESTS, &headers, body.to_string());
+        assert_eq!(error.kind, ProviderErrorKind::RateLimited);
+        assert_eq!(error.message, "Rate limit reached for gpt-4o");
+        assert_eq!(error.retry_after, Some(Duration::from_secs(20)));
+
+        // retry-after-ms is more precise and wins
+        headers.insert("retry-after-ms", HeaderValue::from_static("1500"));
+        let error = ProviderError::from_response(StatusCode::TOO_MANY_REQUESTS, &headers, body.to_string());
+        assert_eq!(error.retry_after, Some(Duration::from_millis(1500)));
+
+        let body = r#"{"error":{"message":"The model `gpt-9` does not exist","type":"invalid_request_error","param":null,"code":"model_not_found"}}"#;
+        let error = ProviderError::new(StatusCode::NOT_FOUND, body);
+        assert_eq!(error.kind, ProviderErrorKind::NotFound);
+        assert_eq!(error.retry_after, None);
+    }
+
+    #[test]
+    fn test_provider_error_keeps_unstructured_body() {
+        let error = ProviderError::new(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>");
+        assert_eq!(error.kind, ProviderErrorKind::Server);
+        assert_eq!(error.message, "<html>Bad Gateway</html>");
+
+        let error = ProviderError::new(StatusCode::NOT_FOUND, r#"{"error":"model 'llama9' not found"}"#);
+        assert_eq!(error.message, "model 'llama9' not found");
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let limit = Some(Duration::from_millis(20));
+        assert_eq!(within(limit, async { Ok(1) }).await.unwrap(), 1);
+
+        let err = within(limit, futures::future::pending::<anyhow::Result<()>>()).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<ProviderError>().unwrap().kind, ProviderErrorKind::Timeout);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_stream_times_out() {
+        let bytes = stream::iter([Ok::<_, std::io::Error>("data")]).chain(stream::pending());
+        let mut stream = with_idle_timeout(bytes, Some(Duration::from_millis(20)));
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), "data");
+        let err = stream.next().await.unwrap().unwrap_err();
+        let error = err.downcast_ref::<ProviderError>().unwrap();
+        assert_eq!(error.kind, ProviderErrorKind::Timeout);
+        assert!(error.is_transient());
+        assert!(stream.next().await.is_none());
+    }
+
     #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
     struct TestEvent {
         id: u32,
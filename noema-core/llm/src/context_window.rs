@@ -0,0 +1,236 @@
+//! Trimming conversation history to fit a model's context window
+
+use crate::{ChatMessage, ChatModel, ChatRequest, ChatStream, ContentBlock, Role, ToolResultContent};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Rough token count charged for an image, audio clip or unresolved document
+const MEDIA_TOKENS: usize = 1000;
+
+/// Estimate the number of tokens in a message (about four characters per token)
+pub fn estimate_tokens(message: &ChatMessage) -> usize {
+    let chars: usize = message
+        .payload
+        .content
+        .iter()
+        .map(|block| match block {
+            ContentBlock::Text { text } => text.len(),
+            ContentBlock::ToolCall(call) => call.name.len() + call.arguments.to_string().len(),
+            ContentBlock::ToolResult(result) => result
+                .content
+                .iter()
+                .map(|content| match content {
+                    ToolResultContent::Text { text } => text.len(),
+                    _ => MEDIA_TOKENS * 4,
+                })
+                .sum(),
+            ContentBlock::Image { .. } | ContentBlock::Audio { .. } | ContentBlock::DocumentRef { .. } => {
+                MEDIA_TOKENS * 4
+            }
+        })
+        .sum();
+    chars.div_ceil(4)
+}
+
+/// How history is trimmed when a request would overflow the context window.
+///
+/// System messages and the most recent turn are always kept. Older turns
+/// are dropped whole, oldest first, so a tool call is never sent without
+/// its result.
+#[derive(Clone, Debug)]
+pub struct ContextWindowPolicy {
+    /// Tokens kept free for the response when the request doesn't set `max_tokens`
+    pub reserved_output_tokens: u32,
+}
+
+impl Default for ContextWindowPolicy {
+    fn default() -> Self {
+        Self {
+            reserved_output_tokens: 8192,
+        }
+    }
+}
+
+impl ContextWindowPolicy {
+    /// Drop the oldest turns from `request` until its estimated size fits
+    /// `context_window`. Returns the number of messages dropped.
+    pub fn trim(&self, request: &mut ChatRequest, context_window: u32) -> usize {
+        let reserved = request
+            .params()
+            .max_tokens
+            .unwrap_or(self.reserved_output_tokens);
+        let budget = context_window.saturating_sub(reserved) as usize;
+
+        let messages = request.messages_mut();
+        let mut total: usize = messages.iter().map(estimate_tokens).sum();
+        if total <= budget {
+            return 0;
+        }
+
+        let turn_starts: Vec<usize> = messages
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| starts_turn(message))
+            .map(|(index, _)| index)
+            .collect();
+
+        // Drop whole turns, never the last one, until the rest fits
+        let mut cut = None;
+        for window in turn_starts.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            total -= messages[start..end]
+                .iter()
+                .filter(|message| message.role != Role::System)
+                .map(estimate_tokens)
+                .sum::<usize>();
+            cut = Some((turn_starts[0], end));
+            if total <= budget {
+                break;
+            }
+        }
+
+        let Some((start, end)) = cut else {
+            return 0;
+        };
+        let before = messages.len();
+        let mut index = 0;
+        messages.retain(|message| {
+            let keep = message.role == Role::System || !(start..end).contains(&index);
+            index += 1;
+            keep
+        });
+        before - messages.len()
+    }
+}
+
+/// Whether a message begins a new turn: a user message that isn't just
+/// returning tool results
+fn starts_turn(message: &ChatMessage) -> bool {
+    message.role == Role::User && message.get_tool_results().is_empty()
+}
+
+/// ChatModel wrapper reporting a context window the inner model doesn't
+/// know, such as one from the provider's cached model list
+pub struct ContextWindowChatModel {
+    inner: Arc<dyn ChatModel + Send + Sync>,
+    context_window: u32,
+}
+
+impl ContextWindowChatModel {
+    /// Wrap `inner` unless it already knows its context window
+    pub fn wrap(
+        inner: Arc<dyn ChatModel + Send + Sync>,
+        context_window: Option<u32>,
+    ) -> Arc<dyn ChatModel + Send + Sync> {
+        match (inner.context_window(), context_window) {
+            (None, Some(context_window)) => Arc::new(Self { inner, context_window }),
+            _ => inner,
+        }
+    }
+}
+
+#[async_trait]
+impl ChatModel for ContextWindowChatModel {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.inner.supports_tools()
+    }
+
+    fn context_window(&self) -> Option<u32> {
+        Some(self.context_window)
+    }
+
+    async fn chat(&self, messages: &ChatRequest) -> anyhow::Result<ChatMessage> {
+        self.inner.chat(messages).await
+    }
+
+    async fn stream_chat(&self, messages: &ChatRequest) -> anyhow::Result<ChatStream> {
+        self.inner.stream_chat(messages).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChatPayload, ToolCall, ToolResult};
+
+    fn text(role: Role, chars: usize) -> ChatMessage {
+        ChatMessage::new(role, ChatPayload::text("x".repeat(chars)))
+    }
+
+    fn roles(request: &ChatRequest) -> Vec<Role> {
+        request.messages().iter().map(|m| m.role).collect()
+    }
+
+    #[test]
+    fn test_fitting_request_is_unchanged() {
+        let messages = vec![text(Role::User, 400), text(Role::Assistant, 400)];
+        let mut request = ChatRequest::new(&messages);
+        assert_eq!(ContextWindowPolicy::default().trim(&mut request, 100_000), 0);
+        assert_eq!(request.messages().len(), 2);
+    }
+
+    #[test]
+    fn test_drops_oldest_turns_but_keeps_system_and_latest() {
+        let messages = vec![
+            text(Role::System, 40),
+            text(Role::User, 400),
+            text(Role::Assistant, 400),
+            text(Role::User, 400),
+            text(Role::Assistant, 400),
+            text(Role::User, 400),
+        ];
+        let mut request = ChatRequest::new(&messages);
+        let policy = ContextWindowPolicy { reserved_output_tokens: 0 };
+
+        // 10 + 5 * 100 tokens; a budget of 350 leaves room for one older turn
+        assert_eq!(policy.trim(&mut request, 350), 2);
+        assert_eq!(
+            roles(&request),
+            vec![Role::System, Role::User, Role::Assistant, Role::User]
+        );
+
+        // The latest turn is kept even when it alone is too large
+        let mut request = ChatRequest::new(&messages);
+        assert_eq!(policy.trim(&mut request, 50), 4);
+        assert_eq!(roles(&request), vec![Role::System, Role::User]);
+    }
+
+    #[test]
+    fn test_tool_results_stay_with_their_calls() {
+        let call = ChatMessage::assistant(ChatPayload::with_tool_calls(
+            String::new(),
+            vec![ToolCall {
+                id: "call_1".to_string(),
+                name: "search".to_string(),
+                arguments: serde_json::json!({"q": "x".repeat(400)}),
+                extra: serde_json::Value::Null,
+            }],
+        ));
+        let result = ChatMessage::user(ChatPayload::new(vec![ContentBlock::ToolResult(
+            ToolResult {
+                tool_call_id: "call_1".to_string(),
+                content: vec![ToolResultContent::text("y".repeat(400))],
+            },
+        )]));
+        let messages = vec![
+            text(Role::User, 40),
+            call,
+            result,
+            text(Role::Assistant, 40),
+            text(Role::User, 40),
+        ];
+        let mut request = ChatRequest::new(&messages);
+        let policy = ContextWindowPolicy { reserved_output_tokens: 0 };
+
+        assert_eq!(policy.trim(&mut request, 100), 4);
+        assert_eq!(roles(&request), vec![Role::User]);
+    }
+}
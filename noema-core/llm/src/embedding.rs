@@ -0,0 +1,78 @@
+//! Text embeddings, the basis for semantic search
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Embedding vectors, one per input and in input order
+#[derive(Clone, Debug, PartialEq)]
+pub struct Embeddings {
+    pub vectors: Vec<Vec<f32>>,
+    /// Length of every vector
+    pub dimensions: usize,
+}
+
+impl Embeddings {
+    /// Collect the vectors returned for `input_count` inputs, checking that
+    /// none are missing and that they all have the same length
+    pub fn from_vectors(input_count: usize, vectors: Vec<Vec<f32>>) -> anyhow::Result<Self> {
+        if vectors.len() != input_count {
+            anyhow::bail!(
+                "Expected {} embeddings, got {}",
+                input_count,
+                vectors.len()
+            );
+        }
+        let dimensions = vectors.first().map_or(0, Vec::len);
+        if let Some(other) = vectors.iter().find(|v| v.len() != dimensions) {
+            anyhow::bail!(
+                "Embeddings have mixed dimensions ({} and {})",
+                dimensions,
+                other.len()
+            );
+        }
+        Ok(Self {
+            vectors,
+            dimensions,
+        })
+    }
+}
+
+#[async_trait]
+pub trait EmbeddingModel {
+    /// Model ID (e.g., "text-embedding-3-small")
+    fn id(&self) -> &str;
+
+    /// Embed each input. Large inputs are split into as many requests as
+    /// the provider's batch limit requires.
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Embeddings>;
+}
+
+#[async_trait]
+impl EmbeddingModel for Arc<dyn EmbeddingModel + Send + Sync> {
+    fn id(&self) -> &str {
+        (**self).id()
+    }
+
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Embeddings> {
+        (**self).embed(inputs).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_vectors_checks_shape() {
+        let embeddings = Embeddings::from_vectors(2, vec![vec![0.1, 0.2], vec![0.3, 0.4]]).unwrap();
+        assert_eq!(embeddings.dimensions, 2);
+
+        let err = Embeddings::from_vectors(3, vec![vec![0.1], vec![0.2]]).unwrap_err();
+        assert_eq!(err.to_string(), "Expected 3 embeddings, got 2");
+
+        let err = Embeddings::from_vectors(2, vec![vec![0.1, 0.2], vec![0.3]]).unwrap_err();
+        assert_eq!(err.to_string(), "Embeddings have mixed dimensions (2 and 1)");
+
+        assert_eq!(Embeddings::from_vectors(0, Vec::new()).unwrap().dimensions, 0);
+    }
+}
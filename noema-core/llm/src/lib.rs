@@ -1,23 +1,46 @@
 use async_trait::async_trait;
 use futures::stream::Stream;
+use serde::{Deserialize, Serialize};
 use std::pin::Pin;
 use std::sync::Arc;
 
 pub mod api;
+pub mod catalog;
 mod client;
+pub mod context_window;
+pub mod embedding;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod provider_urls;
 pub mod providers;
+pub mod rate_limit;
 pub mod registry;
+pub mod replay;
+pub mod retry;
+pub mod stop;
+pub mod stream;
 pub mod tools;
 pub mod traffic_log;
 pub use api::*;
-pub use providers::GeneralModelProvider;
+pub use catalog::{ModelCatalog, ModelSource, SourceProvider};
+pub use client::{ProviderError, ProviderErrorKind, Timeouts};
+pub use context_window::{estimate_tokens, ContextWindowChatModel, ContextWindowPolicy};
+pub use embedding::{EmbeddingModel, Embeddings};
+pub use provider_urls::{ProviderEndpoint, ProviderUrls};
+pub use providers::{set_pull_progress_handler, GeneralModelProvider, PullProgress, PullProgressFn};
 pub use registry::{
-    create_model, get_provider_info, list_all_models, list_models, list_providers, ModelId,
-    ModelInfo, ProviderInfo,
+    create_embedding_model, create_model, get_provider_info, list_all_models,
+    list_compatible_providers, list_models, list_providers, ModelId, ModelInfo, ProviderInfo,
 };
+pub use rate_limit::{RateLimit, RateLimitedChatModel, RateLimitedEmbeddingModel, RateLimiter};
+pub use replay::{ReplayMatch, ReplayProvider, TrafficReplay};
+pub use retry::{RetryPolicy, RetryingChatModel, RetryingEmbeddingModel};
+pub use stop::StopSequenceFilter;
+pub use stream::{collect_stream, fake_stream, StreamCollector};
 pub use tools::ToolRegistry;
 
-pub type ChatStream = Pin<Box<dyn Stream<Item = ChatChunk> + Send>>;
+/// Streamed response; an error item (a read failure or timeout) ends the stream
+pub type ChatStream = Pin<Box<dyn Stream<Item = anyhow::Result<ChatChunk>> + Send>>;
 
 /// Capabilities and characteristics of a model.
 ///
@@ -25,7 +48,7 @@ pub type ChatStream = Pin<Box<dyn Stream<Item = ChatChunk> + Send>>;
 /// - Filter models by what they can do (e.g., only show models that support vision)
 /// - Display capability indicators in the UI
 /// - Enforce privacy rules (e.g., block cloud models for private content)
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ModelCapability {
     // === Content Processing ===
     /// Can process and generate text (chat/completion)
@@ -56,12 +79,15 @@ pub enum ModelCapability {
     Private,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ModelDefinition {
     pub id: String,
     pub display_name: Option<String>,
     pub capabilities: Vec<ModelCapability>,
+    /// Maximum input tokens (prompt plus history), if known
     pub context_window: Option<u32>,
+    /// Maximum tokens the model can generate in one response, if known
+    pub max_output_tokens: Option<u32>,
 }
 
 impl ModelDefinition {
@@ -71,6 +97,7 @@ impl ModelDefinition {
             display_name: None,
             capabilities,
             context_window: None,
+            max_output_tokens: None,
         }
     }
 
@@ -84,6 +111,7 @@ impl ModelDefinition {
             display_name: Some(display_name.into()),
             capabilities,
             context_window: None,
+            max_output_tokens: None,
         }
     }
 
@@ -92,6 +120,18 @@ impl ModelDefinition {
         self
     }
 
+    pub fn with_max_output_tokens(mut self, max_output_tokens: u32) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+
+    /// Set whichever of the context window and output limit are known
+    pub fn with_limits(mut self, context_window: Option<u32>, max_output_tokens: Option<u32>) -> Self {
+        self.context_window = context_window;
+        self.max_output_tokens = max_output_tokens;
+        self
+    }
+
     pub fn text_model(id: impl Into<String>) -> Self {
         Self::new(id, vec![ModelCapability::Text])
     }
@@ -114,6 +154,18 @@ pub trait ChatModel {
     /// Display name for the model
     fn name(&self) -> &str;
 
+    /// Whether the model accepts tool definitions and can make tool calls.
+    /// Providers override this for models without function calling.
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    /// Maximum input tokens the model accepts, if known. Used to trim
+    /// history that would otherwise be rejected.
+    fn context_window(&self) -> Option<u32> {
+        None
+    }
+
     async fn chat(&self, messages: &ChatRequest) -> anyhow::Result<ChatMessage>;
 
     async fn stream_chat(&self, messages: &ChatRequest) -> anyhow::Result<ChatStream>;
@@ -130,6 +182,14 @@ impl ChatModel for Arc<dyn ChatModel + Send + Sync> {
         (**self).name()
     }
 
+    fn supports_tools(&self) -> bool {
+        (**self).supports_tools()
+    }
+
+    fn context_window(&self) -> Option<u32> {
+        (**self).context_window()
+    }
+
     async fn chat(&self, messages: &ChatRequest) -> anyhow::Result<ChatMessage> {
         (**self).chat(messages).await
     }
@@ -146,5 +206,13 @@ pub trait ModelProvider {
 
     /// Create a chat model by name, returned as Arc for sharing across threads
     fn create_chat_model(&self, model_name: &str) -> Option<Arc<dyn ChatModel + Send + Sync>>;
+
+    /// Create an embedding model by name, if the provider offers embeddings
+    fn create_embedding_model(
+        &self,
+        _model_name: &str,
+    ) -> Option<Arc<dyn EmbeddingModel + Send + Sync>> {
+        None
+    }
 }
 
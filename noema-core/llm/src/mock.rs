@@ -0,0 +1,450 @@
+//! Scripted models for tests and offline demos
+//!
+//! A [`MockChatModel`] answers each request with the next response of its
+//! script: a whole message, a list of chunks to stream, or an error. Requests
+//! are recorded so tests can check what was sent. [`MockProvider`] serves a
+//! set of mock models through the [`ModelProvider`] interface.
+//!
+//! Available in this crate's tests and, for other crates, with the `mock`
+//! feature.
+
+use crate::{
+    ChatChunk, ChatMessage, ChatModel, ChatPayload, ChatRequest, ChatStream, ContentBlock,
+    FinishReason, ModelCapability, ModelDefinition, ModelProvider, ProviderError, StreamCollector,
+    ToolCall,
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// One scripted answer
+#[derive(Debug)]
+pub enum MockResponse {
+    /// Reply with a whole message; streamed as a single chunk
+    Message(ChatMessage),
+    /// Stream these chunks, then end with `error` if set; `chat` merges them
+    Chunks {
+        chunks: Vec<ChatChunk>,
+        error: Option<String>,
+    },
+    /// Fail the request
+    Error(anyhow::Error),
+}
+
+/// A model that replays a script of responses
+///
+/// Once the script runs out, requests fail, or with
+/// [`MockChatModelBuilder::repeat_last`] get the last message again.
+pub struct MockChatModel {
+    id: String,
+    script: Mutex<VecDeque<MockResponse>>,
+    repeat_last: bool,
+    last: Mutex<Option<ChatMessage>>,
+    delay: Option<Duration>,
+    chunk_delay: Option<Duration>,
+    supports_tools: bool,
+    requests: Mutex<Vec<ChatRequest>>,
+}
+
+impl MockChatModel {
+    pub fn builder(id: impl Into<String>) -> MockChatModelBuilder {
+        MockChatModelBuilder {
+            id: id.into(),
+            script: VecDeque::new(),
+            repeat_last: false,
+            delay: None,
+            chunk_delay: None,
+            supports_tools: true,
+        }
+    }
+
+    /// A model that answers every request with `text`
+    pub fn echo_text(id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::builder(id).text(text).repeat_last().build()
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<ChatRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Number of requests received so far
+    pub fn request_count(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+
+    /// Record the request and take the next scripted response
+    async fn next_response(&self, request: &ChatRequest) -> anyhow::Result<MockResponse> {
+        self.requests.lock().unwrap().push(request.clone());
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        let next = self.script.lock().unwrap().pop_front();
+        match next {
+            Some(MockResponse::Message(message)) => {
+                if self.repeat_last {
+                    *self.last.lock().unwrap() = Some(message.clone());
+                }
+                Ok(MockResponse::Message(message))
+            }
+            Some(response) => Ok(response),
+            None => match self.last.lock().unwrap().clone() {
+                Some(message) => Ok(MockResponse::Message(message)),
+                None => Err(anyhow::anyhow!(
+                    "Mock model '{}' has no more scripted responses",
+                    self.id
+                )),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl ChatModel for MockChatModel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.supports_tools
+    }
+
+    async fn chat(&self, request: &ChatRequest) -> anyhow::Result<ChatMessage> {
+        match self.next_response(request).await? {
+            MockResponse::Message(message) => Ok(message),
+            MockResponse::Chunks { chunks, error } => {
+                if let Some(error) = error {
+                    anyhow::bail!(error);
+                }
+                let mut collector = StreamCollector::new();
+                for chunk in chunks {
+                    collector.push(chunk);
+                }
+                collector.flush();
+                Ok(collector.into_message())
+            }
+            MockResponse::Error(error) => Err(error),
+        }
+    }
+
+    async fn stream_chat(&self, request: &ChatRequest) -> anyhow::Result<ChatStream> {
+        let (chunks, error) = match self.next_response(request).await? {
+            MockResponse::Message(message) => return Ok(crate::fake_stream(message)),
+            MockResponse::Chunks { chunks, error } => (chunks, error),
+            MockResponse::Error(error) => return Err(error),
+        };
+
+        let items = chunks
+            .into_iter()
+            .map(Ok)
+            .chain(error.map(|e| Err(anyhow::anyhow!(e))));
+        let stream = futures::stream::iter(items);
+        match self.chunk_delay {
+            Some(delay) => Ok(Box::pin(stream.then(move |item| async move {
+                tokio::time::sleep(delay).await;
+                item
+            }))),
+            None => Ok(Box::pin(stream)),
+        }
+    }
+}
+
+/// Builds a [`MockChatModel`]'s script, one response per call
+pub struct MockChatModelBuilder {
+    id: String,
+    script: VecDeque<MockResponse>,
+    repeat_last: bool,
+    delay: Option<Duration>,
+    chunk_delay: Option<Duration>,
+    supports_tools: bool,
+}
+
+impl MockChatModelBuilder {
+    /// Reply with `message` as is
+    pub fn message(mut self, message: ChatMessage) -> Self {
+        self.script.push_back(MockResponse::Message(message));
+        self
+    }
+
+    /// Reply with `text`, finishing normally
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.message(
+            ChatMessage::assistant(ChatPayload::text(text))
+                .with_finish_reason(Some(FinishReason::Stop)),
+        )
+    }
+
+    /// Reply with a call to tool `name`; calls are numbered "call_1", "call_2", ...
+    pub fn tool_call(self, name: &str, arguments: serde_json::Value) -> Self {
+        self.tool_calls(&[(name, arguments)])
+    }
+
+    /// Reply with several tool calls at once
+    pub fn tool_calls(mut self, calls: &[(&str, serde_json::Value)]) -> Self {
+        let numbered = self.tool_call_count();
+        let mut content = Vec::with_capacity(calls.len());
+        for (name, arguments) in calls {
+            content.push(ContentBlock::ToolCall(ToolCall {
+                id: format!("call_{}", numbered + content.len() + 1),
+                name: name.to_string(),
+                arguments: arguments.clone(),
+                extra: serde_json::Value::Null,
+            }));
+        }
+        self.script.push_back(MockResponse::Message(
+            ChatMessage::assistant(ChatPayload::new(content))
+                .with_finish_reason(Some(FinishReason::ToolUse)),
+        ));
+        self
+    }
+
+    /// Stream `pieces` of text as separate chunks
+    pub fn chunks(mut self, pieces: &[&str]) -> Self {
+        self.script.push_back(MockResponse::Chunks {
+            chunks: text_chunks(pieces),
+            error: None,
+        });
+        self
+    }
+
+    /// Stream `pieces` of text, then fail the stream with `error`
+    pub fn chunks_then_error(mut self, pieces: &[&str], error: impl Into<String>) -> Self {
+        self.script.push_back(MockResponse::Chunks {
+            chunks: text_chunks(pieces),
+            error: Some(error.into()),
+        });
+        self
+    }
+
+    /// Stream these chunks as they are
+    pub fn raw_chunks(mut self, chunks: Vec<ChatChunk>) -> Self {
+        self.script.push_back(MockResponse::Chunks {
+            chunks,
+            error: None,
+        });
+        self
+    }
+
+    /// Fail the request with `message`
+    pub fn error(mut self, message: impl Into<String>) -> Self {
+        self.script
+            .push_back(MockResponse::Error(anyhow::anyhow!(message.into())));
+        self
+    }
+
+    /// Fail the request as a provider would with HTTP `status`, so retry and
+    /// error classification see a [`ProviderError`]
+    pub fn provider_error(mut self, status: u16) -> Self {
+        let status = reqwest::StatusCode::from_u16(status).expect("valid HTTP status");
+        self.script
+            .push_back(MockResponse::Error(ProviderError::new(status, "").into()));
+        self
+    }
+
+    /// Once the script runs out, answer with the last message it replied with
+    pub fn repeat_last(mut self) -> Self {
+        self.repeat_last = true;
+        self
+    }
+
+    /// Wait this long before answering each request
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Wait this long before each streamed chunk
+    pub fn with_chunk_delay(mut self, delay: Duration) -> Self {
+        self.chunk_delay = Some(delay);
+        self
+    }
+
+    /// Act like a model without function calling
+    pub fn without_tool_support(mut self) -> Self {
+        self.supports_tools = false;
+        self
+    }
+
+    pub fn build(self) -> MockChatModel {
+        MockChatModel {
+            id: self.id,
+            script: Mutex::new(self.script),
+            repeat_last: self.repeat_last,
+            last: Mutex::new(None),
+            delay: self.delay,
+            chunk_delay: self.chunk_delay,
+            supports_tools: self.supports_tools,
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn tool_call_count(&self) -> usize {
+        self.script
+            .iter()
+            .filter_map(|response| match response {
+                MockResponse::Message(message) => Some(message.get_tool_calls().len()),
+                _ => None,
+            })
+            .sum()
+    }
+}
+
+fn text_chunks(pieces: &[&str]) -> Vec<ChatChunk> {
+    let mut chunks: Vec<ChatChunk> = pieces
+        .iter()
+        .map(|piece| ChatChunk::assistant(ChatPayload::text(*piece)))
+        .collect();
+    if let Some(last) = chunks.pop() {
+        chunks.push(last.with_finish_reason(Some(FinishReason::Stop)));
+    }
+    chunks
+}
+
+/// A provider serving mock models, e.g. for demos without network access
+#[derive(Default)]
+pub struct MockProvider {
+    models: Vec<Arc<MockChatModel>>,
+}
+
+impl MockProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_model(mut self, model: MockChatModel) -> Self {
+        self.models.push(Arc::new(model));
+        self
+    }
+
+    /// The model with this ID, to inspect the requests it received
+    pub fn model(&self, id: &str) -> Option<Arc<MockChatModel>> {
+        self.models.iter().find(|model| model.id == id).cloned()
+    }
+}
+
+#[async_trait]
+impl ModelProvider for MockProvider {
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelDefinition>> {
+        Ok(self
+            .models
+            .iter()
+            .map(|model| {
+                ModelDefinition::new(
+                    model.id.clone(),
+                    vec![
+                        ModelCapability::Text,
+                        ModelCapability::Tools,
+                        ModelCapability::Streaming,
+                    ],
+                )
+            })
+            .collect())
+    }
+
+    fn create_chat_model(&self, model_name: &str) -> Option<Arc<dyn ChatModel + Send + Sync>> {
+        self.model(model_name)
+            .map(|model| model as Arc<dyn ChatModel + Send + Sync>)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collect_stream;
+
+    fn request() -> ChatRequest {
+        let messages = [ChatMessage::user(ChatPayload::text("hi"))];
+        ChatRequest::new(messages.iter())
+    }
+
+    #[tokio::test]
+    async fn test_script_is_answered_in_order() {
+        let model = MockChatModel::builder("mock")
+            .tool_call("search", serde_json::json!({"q": "rust"}))
+            .text("Found it")
+            .build();
+
+        let first = model.chat(&request()).await.unwrap();
+        let calls = first.get_tool_calls();
+        assert_eq!(
+            (calls[0].id.as_str(), calls[0].name.as_str()),
+            ("call_1", "search")
+        );
+        assert_eq!(first.finish_reason, Some(FinishReason::ToolUse));
+
+        let second = collect_stream(model.stream_chat(&request()).await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(second.get_text(), "Found it");
+
+        let err = model.chat(&request()).await.unwrap_err().to_string();
+        assert!(err.contains("no more scripted responses"), "{}", err);
+        assert_eq!(model.request_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_chunks_stream_separately_and_merge_for_chat() {
+        let model = MockChatModel::builder("mock")
+            .chunks(&["Hel", "lo"])
+            .chunks(&["Hel", "lo"])
+            .build();
+
+        let stream = model.stream_chat(&request()).await.unwrap();
+        let chunks: Vec<_> = stream.collect().await;
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            chunks[1].as_ref().unwrap().finish_reason,
+            Some(FinishReason::Stop)
+        );
+
+        assert_eq!(model.chat(&request()).await.unwrap().get_text(), "Hello");
+    }
+
+    #[tokio::test]
+    async fn test_stream_error_follows_chunks() {
+        let model = MockChatModel::builder("mock")
+            .chunks_then_error(&["partial"], "connection reset")
+            .build();
+
+        let mut stream = model.stream_chat(&request()).await.unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap().payload.get_text(),
+            "partial"
+        );
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_provider_error_is_classified() {
+        let model = MockChatModel::builder("mock").provider_error(429).build();
+        let err = model.chat(&request()).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ProviderError>().unwrap().status.as_u16(),
+            429
+        );
+    }
+
+    #[tokio::test]
+    async fn test_provider_lists_and_creates_its_models() {
+        let provider = MockProvider::new().with_model(MockChatModel::echo_text("echo", "hi there"));
+
+        let models = provider.list_models().await.unwrap();
+        assert_eq!(models.len(), 1);
+        assert!(models[0].has_capability(&ModelCapability::Tools));
+
+        let model = provider.create_chat_model("echo").unwrap();
+        for _ in 0..2 {
+            assert_eq!(model.chat(&request()).await.unwrap().get_text(), "hi there");
+        }
+        assert_eq!(provider.model("echo").unwrap().request_count(), 2);
+        assert!(provider.create_chat_model("other").is_none());
+    }
+}
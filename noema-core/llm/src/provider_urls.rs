@@ -0,0 +1,199 @@
+//! Base URL and API key overrides for the built-in providers
+//!
+//! Endpoints can be pointed at a proxy or self-hosted gateway either with
+//! environment variables (`CLAUDE_BASE_URL`, ...) or persistently in
+//! providers.toml (see `PathManager::providers_config_path`):
+//!
+//! ```toml
+//! [claude]
+//! base_url = "https://gateway.example.com/anthropic/v1"
+//! api_key_env = "GATEWAY_KEY"
+//! ```
+//!
+//! Precedence, highest first:
+//! - base URL: the provider's `*_BASE_URL` variable, then providers.toml,
+//!   then the provider's default endpoint
+//! - API key: the key saved in settings (system keychain, else the
+//!   encrypted copy in settings.toml), then the variable named by
+//!   `api_key_env` in providers.toml, then the provider's own `*_API_KEY`
+//!   variable
+
+use crate::registry::{get_provider_info, list_providers};
+use config::PathManager;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Endpoint settings for one provider
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProviderEndpoint {
+    /// Base URL including the version path, e.g. "https://api.anthropic.com/v1"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+}
+
+/// Endpoint overrides keyed by provider name
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(transparent)]
+pub struct ProviderUrls {
+    providers: BTreeMap<String, ProviderEndpoint>,
+}
+
+impl ProviderUrls {
+    /// Base URLs set in the environment for the built-in providers
+    pub fn from_env() -> Self {
+        let providers = list_providers()
+            .iter()
+            .filter_map(|info| {
+                let base_url = std::env::var(info.base_url_env).ok()?;
+                let endpoint = ProviderEndpoint { base_url: Some(base_url), api_key_env: None };
+                Some((info.name.to_string(), endpoint))
+            })
+            .collect();
+        Self { providers }
+    }
+
+    /// Overrides from providers.toml, with environment variables taking
+    /// precedence
+    ///
+    /// A missing or unreadable file contributes nothing.
+    pub fn from_config_file() -> Self {
+        let from_file = PathManager::providers_config_path()
+            .and_then(|path| match Self::load(&path) {
+                Ok(urls) => {
+                    for name in urls.unknown_providers() {
+                        tracing::warn!("Ignoring unknown provider '{}' in {}", name, path.display());
+                    }
+                    Some(urls)
+                }
+                Err(e) => {
+                    if path.exists() {
+                        tracing::warn!("Ignoring {}: {}", path.display(), e);
+                    }
+                    None
+                }
+            })
+            .unwrap_or_default();
+        from_file.merge(Self::from_env())
+    }
+
+    /// Read overrides from a providers.toml file
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Write these overrides to a providers.toml file
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Combine with `other`, whose fields win where both are set
+    pub fn merge(mut self, other: Self) -> Self {
+        for (name, endpoint) in other.providers {
+            let current = self.providers.entry(name).or_default();
+            if endpoint.base_url.is_some() {
+                current.base_url = endpoint.base_url;
+            }
+            if endpoint.api_key_env.is_some() {
+                current.api_key_env = endpoint.api_key_env;
+            }
+        }
+        self
+    }
+
+    /// Set the endpoint for a provider
+    pub fn set(&mut self, provider: &str, endpoint: ProviderEndpoint) {
+        self.providers.insert(provider.to_string(), endpoint);
+    }
+
+    /// Get the endpoint configured for a provider, if any
+    pub fn get(&self, provider: &str) -> Option<&ProviderEndpoint> {
+        self.providers.get(provider)
+    }
+
+    /// Base URL to use for a provider (None = its default endpoint)
+    pub fn base_url(&self, provider: &str) -> Option<&str> {
+        self.get(provider)?.base_url.as_deref()
+    }
+
+    /// API key from the variable named in providers.toml, if that is set
+    pub fn api_key(&self, provider: &str) -> Option<String> {
+        let env = self.get(provider)?.api_key_env.as_deref()?;
+        std::env::var(env).ok()
+    }
+
+    /// Names in the overrides that aren't built-in providers
+    fn unknown_providers(&self) -> Vec<&str> {
+        self.providers
+            .keys()
+            .filter(|name| get_provider_info(name).is_none())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut urls = ProviderUrls::default();
+        urls.set(
+            "claude",
+            ProviderEndpoint {
+                base_url: Some("https://gateway.example.com/anthropic/v1".to_string()),
+                api_key_env: Some("GATEWAY_KEY".to_string()),
+            },
+        );
+        urls.set(
+            "ollama",
+            ProviderEndpoint { base_url: Some("http://gpu-box:11434".to_string()), api_key_env: None },
+        );
+
+        let path = std::env::temp_dir().join(format!("providers_{}.toml", std::process::id()));
+        urls.save(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("[claude]"), "{}", content);
+        assert_eq!(ProviderUrls::load(&path).unwrap(), urls);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_merge_prefers_other() {
+        let from_file: ProviderUrls = toml::from_str(
+            r#"
+            [claude]
+            base_url = "https://file.example.com/v1"
+            api_key_env = "GATEWAY_KEY"
+
+            [openai]
+            base_url = "https://openai-proxy.example.com/v1"
+
+            [typo]
+            base_url = "http://nowhere"
+            "#,
+        )
+        .unwrap();
+        let mut from_env = ProviderUrls::default();
+        from_env.set(
+            "claude",
+            ProviderEndpoint { base_url: Some("https://env.example.com/v1".to_string()), api_key_env: None },
+        );
+
+        let urls = from_file.merge(from_env);
+        assert_eq!(urls.base_url("claude"), Some("https://env.example.com/v1"));
+        assert_eq!(urls.get("claude").unwrap().api_key_env.as_deref(), Some("GATEWAY_KEY"));
+        assert_eq!(urls.base_url("openai"), Some("https://openai-proxy.example.com/v1"));
+        assert_eq!(urls.base_url("gemini"), None);
+        assert_eq!(urls.unknown_providers(), vec!["typo"]);
+    }
+}
@@ -111,6 +111,9 @@ impl TryFrom<&Content> for crate::api::ContentBlock {
                     })
                 }
             },
+            Content::ToolUse { name, input, .. } if name == RESPONSE_TOOL_NAME => {
+                Ok(crate::api::ContentBlock::Text { text: input.to_string() })
+            }
             Content::ToolUse { id, name, input } => {
                 Ok(crate::api::ContentBlock::ToolCall(crate::api::ToolCall {
                     id: id.clone(),
@@ -258,17 +261,30 @@ impl From<&crate::ChatMessage> for InputMessage {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub(crate) enum SystemPrompt {
-    Text { text: String },
+    Text {
+        text: String,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
 }
 
 impl SystemPrompt {
-    fn new(text: &str) -> Self {
+    fn new(text: &str, cache: bool) -> Self {
         SystemPrompt::Text {
             text: text.to_string(),
+            cache_control: cache.then_some(CacheControl::Ephemeral),
         }
     }
 }
 
+/// Prompt cache breakpoint; everything up to and including the marked block is cached
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub(crate) enum CacheControl {
+    Ephemeral,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct Tool {
     pub(crate) name: String,
@@ -290,6 +306,30 @@ impl From<&crate::api::ToolDefinition> for Tool {
     }
 }
 
+/// Tool Claude is made to call when the request asks for JSON; its input is
+/// returned as the reply's text
+pub(crate) const RESPONSE_TOOL_NAME: &str = "json_response";
+
+impl Tool {
+    /// Tool taking the requested reply format as its input
+    ///
+    /// Claude requires tool input to be an object, so a schema with another
+    /// root type is rejected by the API.
+    fn response(schema: serde_json::Value) -> Self {
+        Tool {
+            name: RESPONSE_TOOL_NAME.to_string(),
+            description: Some("Give your reply to the user in this format.".to_string()),
+            input_schema: schema,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ToolChoice {
+    Tool { name: String },
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct MessagesRequest {
     pub(crate) model: String,
@@ -302,10 +342,33 @@ pub(crate) struct MessagesRequest {
     pub(crate) stream: Option<bool>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub(crate) system: Option<SystemPrompt>,
+    pub(crate) system: Option<Vec<SystemPrompt>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) tools: Option<Vec<Tool>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tool_choice: Option<ToolChoice>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) temperature: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) top_p: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) stop_sequences: Option<Vec<String>>,
+}
+
+/// Output limit used when the request doesn't set one; the API requires it.
+/// Capped at the model's own limit when that is known.
+const DEFAULT_MAX_TOKENS: u32 = 32000;
+
+fn default_max_tokens(model_name: &str) -> u32 {
+    match super::super::provider::model_limits(model_name) {
+        Some((_, max_output_tokens)) => max_output_tokens.min(DEFAULT_MAX_TOKENS),
+        None => DEFAULT_MAX_TOKENS,
+    }
 }
 
 impl MessagesRequest {
@@ -330,23 +393,35 @@ impl MessagesRequest {
             .map(|msg: &crate::ChatMessage| msg.into())
             .collect::<Vec<InputMessage>>();
 
-        let tools = request
+        let mut tools: Option<Vec<Tool>> = request
             .tools
             .as_ref()
             .map(|tools| tools.iter().map(|t| t.into()).collect());
 
+        // Claude has no JSON mode; force a call to a tool whose input is the reply
+        let tool_choice = request.response_format.schema().map(|schema| {
+            tools.get_or_insert_with(Vec::new).push(Tool::response(schema));
+            ToolChoice::Tool { name: RESPONSE_TOOL_NAME.to_string() }
+        });
+
         MessagesRequest {
             model: model_name.to_string(),
             messages: messages,
-            // TODO: Don't hardcode
-            max_tokens: 32000,
+            max_tokens: request
+                .params
+                .max_tokens
+                .unwrap_or_else(|| default_max_tokens(model_name)),
             stream: Some(stream),
             system: if system_instruction.len() == 0 {
                 None
             } else {
-                Some(SystemPrompt::new(&system_instruction))
+                Some(vec![SystemPrompt::new(&system_instruction, request.cache_system_prompt)])
             },
             tools,
+            tool_choice,
+            temperature: request.params.temperature,
+            top_p: request.params.top_p,
+            stop_sequences: request.params.stop.clone(),
         }
     }
 }
@@ -361,26 +436,52 @@ pub(crate) struct MessagesResponse {
 
     pub(crate) model: String,
 
-    // Turn into an enum later.
     pub(crate) stop_reason: Option<String>,
 
     pub(crate) stop_sequence: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) usage: Option<Usage>,
+
     #[serde(flatten)]
     pub(crate) extra: serde_json::Value,
 }
 
+/// Map Claude's `stop_reason`
+///
+/// `response_tool_used` marks a reply forced through the JSON response tool:
+/// that tool call is the answer itself, so it ends the reply like `end_turn`.
+pub(crate) fn finish_reason(stop_reason: &str, response_tool_used: bool) -> crate::FinishReason {
+    match stop_reason {
+        "end_turn" | "stop_sequence" => crate::FinishReason::Stop,
+        "tool_use" if response_tool_used => crate::FinishReason::Stop,
+        "max_tokens" => crate::FinishReason::Length,
+        "tool_use" => crate::FinishReason::ToolUse,
+        "refusal" => crate::FinishReason::ContentFilter { category: None },
+        _ => crate::FinishReason::Other,
+    }
+}
+
 impl From<MessagesResponse> for crate::ChatMessage {
     fn from(response: MessagesResponse) -> Self {
+        let usage = response.usage.as_ref().map(|u| u.to_token_usage(None));
+        let response_tool_used = response.content.iter().any(
+            |content| matches!(content, Content::ToolUse { name, .. } if name == RESPONSE_TOOL_NAME),
+        );
+        let finish_reason = response
+            .stop_reason
+            .as_deref()
+            .map(|reason| finish_reason(reason, response_tool_used));
         let payload: crate::api::ChatPayload = response
             .content
             .try_into()
             .expect("Failed to convert Claude response");
 
-        match response.role {
+        let message = match response.role {
             Role::User => crate::ChatMessage::user(payload),
             Role::Assistant => crate::ChatMessage::assistant(payload),
-        }
+        };
+        message.with_usage(usage).with_finish_reason(finish_reason)
     }
 }
 
@@ -444,6 +545,32 @@ pub(crate) struct MessageStartPayload {
 pub(crate) struct Usage {
     pub input_tokens: Option<u32>,
     pub output_tokens: Option<u32>,
+
+    /// Prompt tokens written to the cache (not included in `input_tokens`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input_tokens: Option<u32>,
+
+    /// Prompt tokens read from the cache (not included in `input_tokens`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<u32>,
+}
+
+impl Usage {
+    /// Convert to TokenUsage, falling back to `start` for input counts this event doesn't carry.
+    ///
+    /// Claude reports input tokens in `message_start` and the final output count in `message_delta`.
+    /// Cached tokens are counted separately from `input_tokens`, so they're added back into the
+    /// prompt total.
+    pub(crate) fn to_token_usage(&self, start: Option<&Usage>) -> crate::TokenUsage {
+        let input = |field: fn(&Usage) -> Option<u32>| {
+            field(self).or_else(|| start.and_then(field)).unwrap_or(0)
+        };
+        let cache_read = input(|u| u.cache_read_input_tokens);
+        let prompt_tokens =
+            input(|u| u.input_tokens) + input(|u| u.cache_creation_input_tokens) + cache_read;
+        crate::TokenUsage::new(prompt_tokens, self.output_tokens.unwrap_or(0))
+            .with_cached_tokens(cache_read)
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -488,3 +615,87 @@ pub(crate) struct ErrorPayload {
     pub error_type: String,
     pub message: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(cache_system_prompt: bool) -> MessagesRequest {
+        let messages = [
+            crate::ChatMessage::system(crate::ChatPayload::text("You are terse.")),
+            crate::ChatMessage::user(crate::ChatPayload::text("Hello")),
+        ];
+        let request = crate::ChatRequest::new(&messages).with_cache_system_prompt(cache_system_prompt);
+        MessagesRequest::from_chat_request("claude-sonnet-4-5", &request, true)
+    }
+
+    #[test]
+    fn test_cache_system_prompt_marks_last_system_block() {
+        let json = serde_json::to_value(request(true)).unwrap();
+        assert_eq!(
+            json["system"],
+            serde_json::json!([{"type": "text", "text": "You are terse.", "cache_control": {"type": "ephemeral"}}])
+        );
+
+        let json = serde_json::to_value(request(false)).unwrap();
+        assert_eq!(json["system"], serde_json::json!([{"type": "text", "text": "You are terse."}]));
+    }
+
+    #[test]
+    fn test_json_response_format_forces_response_tool() {
+        let message = crate::ChatMessage::user(crate::ChatPayload::text("List two colors"));
+        let schema = serde_json::json!({"type": "object", "properties": {"colors": {"type": "array"}}});
+        let request = crate::ChatRequest::new([&message])
+            .with_response_format(crate::ResponseFormat::JsonSchema(schema.clone()));
+        let json = serde_json::to_value(MessagesRequest::from_chat_request("claude-sonnet-4-5", &request, false)).unwrap();
+        assert_eq!(json["tool_choice"], serde_json::json!({"type": "tool", "name": RESPONSE_TOOL_NAME}));
+        assert_eq!(json["tools"][0]["input_schema"], schema);
+
+        let response = Content::ToolUse {
+            id: "toolu_1".to_string(),
+            name: RESPONSE_TOOL_NAME.to_string(),
+            input: serde_json::json!({"colors": ["red", "blue"]}),
+        };
+        let block = crate::api::ContentBlock::try_from(&response).unwrap();
+        assert!(matches!(block, crate::api::ContentBlock::Text { text } if text == r#"{"colors":["red","blue"]}"#));
+    }
+
+    #[test]
+    fn test_usage_counts_cached_prompt_tokens() {
+        let start: Usage = serde_json::from_str(
+            r#"{"input_tokens":20,"output_tokens":1,"cache_creation_input_tokens":0,"cache_read_input_tokens":1800}"#,
+        )
+        .unwrap();
+        let delta: Usage = serde_json::from_str(r#"{"output_tokens":42}"#).unwrap();
+
+        let usage = delta.to_token_usage(Some(&start));
+        assert_eq!(usage.prompt_tokens, 1820);
+        assert_eq!(usage.cached_tokens, 1800);
+        assert_eq!(usage.completion_tokens, 42);
+        assert_eq!(usage.total_tokens, 1862);
+    }
+
+    #[test]
+    fn test_stop_reasons_map_to_finish_reasons() {
+        assert_eq!(finish_reason("end_turn", false), crate::FinishReason::Stop);
+        assert_eq!(finish_reason("max_tokens", false), crate::FinishReason::Length);
+        assert_eq!(finish_reason("tool_use", false), crate::FinishReason::ToolUse);
+        // Answering through the response tool is a finished answer
+        assert_eq!(finish_reason("tool_use", true), crate::FinishReason::Stop);
+        assert_eq!(finish_reason("pause_turn", false), crate::FinishReason::Other);
+    }
+
+    #[test]
+    fn test_response_stop_reason_sets_finish_reason() {
+        let response = |stop_reason: &str| -> crate::ChatMessage {
+            let json = format!(
+                r#"{{"id":"msg_1","type":"message","role":"assistant","model":"claude","content":[{{"type":"text","text":"Once upon"}}],"stop_reason":"{}","stop_sequence":null}}"#,
+                stop_reason
+            );
+            serde_json::from_str::<MessagesResponse>(&json).unwrap().into()
+        };
+        assert!(response("max_tokens").is_truncated());
+        assert_eq!(response("end_turn").finish_reason, Some(crate::FinishReason::Stop));
+        assert_eq!(response("refusal").finish_reason, Some(crate::FinishReason::ContentFilter { category: None }));
+    }
+}
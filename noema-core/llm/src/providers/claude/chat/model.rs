@@ -1,7 +1,10 @@
 use crate::client::Client;
 use crate::traffic_log;
 
-use super::api::{ContentBlock, Delta, MessagesRequest, MessagesResponse, StreamEvent};
+use super::api::{
+    finish_reason, ContentBlock, Delta, MessagesRequest, MessagesResponse, StreamEvent, Usage,
+    RESPONSE_TOOL_NAME,
+};
 use crate::{ChatMessage, ChatModel, ChatRequest, ChatStream};
 use async_trait::async_trait;
 use futures::StreamExt;
@@ -18,7 +21,7 @@ pub struct ClaudeChatModel {
 impl ClaudeChatModel {
     pub fn new(client: Client, base_url: String, model_name: String) -> Self {
         ClaudeChatModel {
-            client,
+            client: client.for_model(&model_name),
             base_url,
             model_name,
         }
@@ -35,15 +38,18 @@ impl ChatModel for ClaudeChatModel {
         &self.model_name
     }
 
+    fn context_window(&self) -> Option<u32> {
+        super::super::provider::model_limits(&self.model_name)
+            .map(|(context_window, _)| context_window)
+    }
+
     async fn chat(&self, request: &ChatRequest) -> anyhow::Result<ChatMessage> {
         let url = format!("{}/messages", self.base_url);
 
         let api_request = MessagesRequest::from_chat_request(&self.model_name, request, false);
-        traffic_log::log_request(&self.model_name, &api_request);
 
         match self.client.post(url, &api_request).await {
             Ok(response) => {
-                traffic_log::log_response(&self.model_name, &response);
                 let response: MessagesResponse = response;
                 Ok(response.into())
             }
@@ -58,7 +64,6 @@ impl ChatModel for ClaudeChatModel {
         let url = format!("{}/messages", self.base_url);
 
         let api_request = MessagesRequest::from_chat_request(&self.model_name, request, true);
-        traffic_log::log_stream_start(&self.model_name, &api_request);
 
         let streamed_response = self
             .client
@@ -71,63 +76,120 @@ impl ChatModel for ClaudeChatModel {
             Arc::new(Mutex::new(HashMap::new()));
         let tool_calls_clone = Arc::clone(&tool_calls);
 
+        // Input of the forced JSON response tool streams as the reply's text
+        let response_block: Arc<Mutex<Option<usize>>> = Arc::new(Mutex::new(None));
+
+        // Input tokens arrive in message_start, output tokens in the final message_delta
+        let start_usage: Arc<Mutex<Option<Usage>>> = Arc::new(Mutex::new(None));
+
         // Process Claude's streaming events and extract text deltas + tool calls
-        let chunk_stream = streamed_response.filter_map(move |event: StreamEvent| {
+        let chunk_stream = streamed_response.filter_map(move |event: anyhow::Result<StreamEvent>| {
             let tool_calls = Arc::clone(&tool_calls_clone);
+            let start_usage = Arc::clone(&start_usage);
+            let response_block = Arc::clone(&response_block);
             async move {
-                match event {
-                    StreamEvent::ContentBlockStart { index, content_block } => {
-                        // When a tool use block starts, record it
-                        if let ContentBlock::ToolUse { id, name, .. } = content_block {
-                            let mut calls = tool_calls.lock().unwrap();
-                            calls.insert(index, (id, name, String::new()));
+                // A read error or timeout ends the stream
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => return Some(Err(e)),
+                };
+                let chunk = async move {
+                    match event {
+                        StreamEvent::MessageStart { message } => {
+                            if let Some(usage) = message.usage {
+                                *start_usage.lock().unwrap() = Some(usage);
+                            }
+                            None
                         }
-                        None
-                    }
-                    StreamEvent::ContentBlockDelta { index, delta } => match delta {
-                        Delta::TextDelta { text } => {
-                            Some(crate::ChatChunk::assistant(crate::ChatPayload::text(text)))
+                        StreamEvent::MessageDelta { delta, usage } => {
+                            let response_tool_used = response_block.lock().unwrap().is_some();
+                            let finish_reason = delta
+                                .stop_reason
+                                .as_deref()
+                                .map(|reason| finish_reason(reason, response_tool_used));
+                            let chunk = match usage {
+                                Some(usage) => {
+                                    let start = start_usage.lock().unwrap();
+                                    crate::ChatChunk::usage(usage.to_token_usage(start.as_ref()))
+                                }
+                                None if finish_reason.is_some() => {
+                                    crate::ChatChunk::assistant(crate::ChatPayload::default())
+                                }
+                                None => return None,
+                            };
+                            Some(chunk.with_finish_reason(finish_reason))
                         }
-                        Delta::ThinkingDelta { thinking } => {
-                            Some(crate::ChatChunk::assistant(crate::ChatPayload::text(thinking)))
+                        StreamEvent::ContentBlockStart { index, content_block } => {
+                            // When a tool use block starts, record it
+                            if let ContentBlock::ToolUse { id, name, .. } = content_block {
+                                if name == RESPONSE_TOOL_NAME {
+                                    *response_block.lock().unwrap() = Some(index);
+                                } else {
+                                    let mut calls = tool_calls.lock().unwrap();
+                                    calls.insert(index, (id.clone(), name.clone(), String::new()));
+                                    // Announce the call before its arguments arrive
+                                    return Some(crate::ChatChunk::tool_call_delta(crate::ToolCallDelta {
+                                        id,
+                                        name,
+                                        args_fragment: String::new(),
+                                    }));
+                                }
+                            }
+                            None
                         }
-                        Delta::InputJsonDelta { partial_json } => {
-                            // Accumulate the JSON for this tool call
-                            let mut calls = tool_calls.lock().unwrap();
-                            if let Some((_, _, json)) = calls.get_mut(&index) {
+                        StreamEvent::ContentBlockDelta { index, delta } => match delta {
+                            Delta::TextDelta { text } => {
+                                Some(crate::ChatChunk::assistant(crate::ChatPayload::text(text)))
+                            }
+                            Delta::ThinkingDelta { thinking } => {
+                                Some(crate::ChatChunk::assistant(crate::ChatPayload::text(thinking)))
+                            }
+                            Delta::InputJsonDelta { partial_json } if *response_block.lock().unwrap() == Some(index) => {
+                                Some(crate::ChatChunk::assistant(crate::ChatPayload::text(partial_json)))
+                            }
+                            Delta::InputJsonDelta { partial_json } => {
+                                // Accumulate the JSON for this tool call, forwarding each piece
+                                let mut calls = tool_calls.lock().unwrap();
+                                let (id, name, json) = calls.get_mut(&index)?;
                                 json.push_str(&partial_json);
+                                Some(crate::ChatChunk::tool_call_delta(crate::ToolCallDelta {
+                                    id: id.clone(),
+                                    name: name.clone(),
+                                    args_fragment: partial_json,
+                                }))
+                            }
+                        },
+                        StreamEvent::ContentBlockStop { index } => {
+                            // When a tool use block ends, emit the complete tool call
+                            let mut calls = tool_calls.lock().unwrap();
+                            if let Some((id, name, json)) = calls.remove(&index) {
+                                let arguments: serde_json::Value =
+                                    serde_json::from_str(&json).unwrap_or(serde_json::Value::Null);
+                                let tool_call = crate::api::ToolCall {
+                                    id,
+                                    name,
+                                    arguments,
+                                    extra: serde_json::Value::Null,
+                                };
+                                return Some(crate::ChatChunk::assistant(crate::ChatPayload::tool_call(
+                                    tool_call,
+                                )));
                             }
                             None
                         }
-                    },
-                    StreamEvent::ContentBlockStop { index } => {
-                        // When a tool use block ends, emit the complete tool call
-                        let mut calls = tool_calls.lock().unwrap();
-                        if let Some((id, name, json)) = calls.remove(&index) {
-                            let arguments: serde_json::Value =
-                                serde_json::from_str(&json).unwrap_or(serde_json::Value::Null);
-                            let tool_call = crate::api::ToolCall {
-                                id,
-                                name,
-                                arguments,
-                                extra: serde_json::Value::Null,
-                            };
-                            return Some(crate::ChatChunk::assistant(crate::ChatPayload::tool_call(
-                                tool_call,
-                            )));
+                        StreamEvent::Error { error } => {
+                            warn!(
+                                "Received error event: {} - {}",
+                                error.error_type, error.message
+                            );
+                            None
                         }
-                        None
-                    }
-                    StreamEvent::Error { error } => {
-                        warn!(
-                            "Received error event: {} - {}",
-                            error.error_type, error.message
-                        );
-                        None
+                        // Ignore other event types (MessageStop, Ping)
+                        _ => None,
                     }
-                    // Ignore other event types (MessageStart, MessageDelta, MessageStop, Ping)
-                    _ => None,
                 }
+                .await;
+                chunk.map(Ok)
             }
         });
 
@@ -1,6 +1,8 @@
 use super::chat::model::ClaudeChatModel;
 use crate::{ChatModel, ModelProvider};
-use crate::client::Client;
+use crate::client::{Client, Timeouts};
+use crate::replay::TrafficReplay;
+use crate::traffic_log::TrafficLogger;
 use async_trait::async_trait;
 use reqwest::header;
 use serde::{Deserialize, Serialize};
@@ -26,13 +28,43 @@ impl From<ModelInfo> for crate::ModelDefinition {
         // - Vision capabilities (all Claude 3+ models support vision, but this isn't indicated in the API)
         // - Embedding capabilities (Anthropic doesn't offer embedding models)
         //
-        // All Claude models support text/chat as their primary capability.
-        let capabilities = vec![crate::ModelCapability::Text];
-
+        // All Claude models support text/chat as their primary capability, and
+        // every model served by the Messages API supports tool use.
+        let capabilities = vec![crate::ModelCapability::Text, crate::ModelCapability::Tools];
+
+        let (context_window, max_output_tokens) = match model_limits(&model.id) {
+            Some((context_window, max_output_tokens)) => {
+                (Some(context_window), Some(max_output_tokens))
+            }
+            None => (None, None),
+        };
         crate::ModelDefinition::with_display_name(model.id, model.display_name, capabilities)
+            .with_limits(context_window, max_output_tokens)
     }
 }
 
+/// Context window and output limit by model ID prefix; more specific
+/// prefixes come first. The models endpoint doesn't report limits.
+const MODEL_LIMITS: &[(&str, u32, u32)] = &[
+    ("claude-opus-4-5", 200_000, 64_000),
+    ("claude-opus-4", 200_000, 32_000),
+    ("claude-sonnet-4", 200_000, 64_000),
+    ("claude-haiku-4", 200_000, 64_000),
+    ("claude-3-7-sonnet", 200_000, 64_000),
+    ("claude-3-5-sonnet", 200_000, 8_192),
+    ("claude-3-5-haiku", 200_000, 8_192),
+    ("claude-3-opus", 200_000, 4_096),
+    ("claude-3-haiku", 200_000, 4_096),
+];
+
+/// Known (context window, max output tokens) for a model
+pub(crate) fn model_limits(model_id: &str) -> Option<(u32, u32)> {
+    MODEL_LIMITS
+        .iter()
+        .find(|(prefix, _, _)| model_id.starts_with(prefix))
+        .map(|&(_, context_window, max_output_tokens)| (context_window, max_output_tokens))
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 struct ListModelsResponse {
     data: Vec<ModelInfo>,
@@ -73,6 +105,24 @@ impl ClaudeProvider {
             base_url: format!("{}/{}", base_url, API_VERSION),
         }
     }
+
+    /// Record the raw traffic of every model created by this provider
+    pub fn with_traffic_logger(mut self, logger: Arc<dyn TrafficLogger>) -> Self {
+        self.client.set_traffic_logger(logger);
+        self
+    }
+
+    /// Bound how long every model created by this provider may wait on a request
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.client.set_timeouts(timeouts);
+        self
+    }
+
+    /// Answer every model's requests from recorded traffic instead of the network
+    pub fn with_replay(mut self, replay: Arc<TrafficReplay>) -> Self {
+        self.client.set_replay(replay);
+        self
+    }
 }
 
 #[async_trait]
@@ -58,18 +58,31 @@ impl From<ModelDefinition> for crate::ModelDefinition {
             }
         }
 
+        if capabilities.contains(&crate::ModelCapability::Text) && supports_tools(&model.name) {
+            capabilities.push(crate::ModelCapability::Tools);
+        }
+
         // No fallback - if no supported methods found, capabilities will be empty
         // and the model will be filtered out by list_models
 
-        match model.display_name {
+        let definition = match model.display_name {
             Some(display_name) => {
                 crate::ModelDefinition::with_display_name(model.name, display_name, capabilities)
             }
             None => crate::ModelDefinition::new(model.name, capabilities),
-        }
+        };
+        definition.with_limits(model.input_token_limit, model.output_token_limit)
     }
 }
 
+/// Whether a model supports function calling. Gemma and the embedding,
+/// speech and image generation variants do not.
+pub(crate) fn supports_tools(model_name: &str) -> bool {
+    const WITHOUT_TOOLS: &[&str] = &["aqa", "embedding", "gemma", "image", "tts"];
+    let name = model_name.to_ascii_lowercase();
+    !WITHOUT_TOOLS.iter().any(|marker| name.contains(marker))
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct ListModelsResponse {
@@ -78,10 +91,11 @@ pub(crate) struct ListModelsResponse {
     pub(crate) next_page_token: Option<String>,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Role {
     User,
+    #[default]
     Model,
 }
 
@@ -320,9 +334,10 @@ impl From<&crate::api::ContentBlock> for Part {
 }
 
 // Gemini representation of messages.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub(crate) struct Content {
     pub(crate) role: Role,
+    #[serde(default)]
     pub(crate) parts: Vec<Part>,
 }
 
@@ -610,7 +625,61 @@ pub(crate) struct GenerateContentRequest {
     pub(crate) system_instruction: Option<Content>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub(crate) generation_config: Option<serde_json::Value>,
+    pub(crate) generation_config: Option<GenerationConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) temperature: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) top_p: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_output_tokens: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) stop_sequences: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) seed: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) response_mime_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) response_schema: Option<serde_json::Value>,
+}
+
+impl GenerationConfig {
+    /// Config for the request's sampling settings and response format, or
+    /// `None` if neither is set
+    fn from_request(request: &ChatRequest) -> Option<Self> {
+        let params = &request.params;
+        let format = &request.response_format;
+        if *params == crate::GenerationParams::default() && *format == crate::ResponseFormat::Text {
+            return None;
+        }
+        let (response_mime_type, response_schema) = match format {
+            crate::ResponseFormat::Text => (None, None),
+            crate::ResponseFormat::JsonObject => (Some("application/json".to_string()), None),
+            crate::ResponseFormat::JsonSchema(schema) => (
+                Some("application/json".to_string()),
+                Some(sanitize_schema_for_gemini(schema.clone())),
+            ),
+        };
+        Some(GenerationConfig {
+            temperature: params.temperature,
+            top_p: params.top_p,
+            max_output_tokens: params.max_tokens,
+            stop_sequences: params.stop.clone(),
+            seed: params.seed,
+            response_mime_type,
+            response_schema,
+        })
+    }
 }
 
 impl GenerateContentRequest {
@@ -650,37 +719,158 @@ impl From<&ChatRequest> for GenerateContentRequest {
 
         let mut req = GenerateContentRequest::new(contents, Some(system_instruction));
         req.tools = tools;
+        req.generation_config = GenerationConfig::from_request(request);
         req
     }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct Candidate {
+    /// Missing when the candidate was blocked before producing anything
+    #[serde(default)]
     pub(crate) content: Content,
 
+    #[serde(rename = "finishReason", skip_serializing_if = "Option::is_none")]
+    pub(crate) finish_reason: Option<String>,
+
+    #[serde(rename = "safetyRatings", default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) safety_ratings: Vec<SafetyRating>,
+
     #[serde(flatten)]
     pub(crate) extra: Option<serde_json::Value>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UsageMetadata {
+    #[serde(default)]
+    pub(crate) prompt_token_count: u32,
+
+    #[serde(default)]
+    pub(crate) candidates_token_count: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) total_token_count: Option<u32>,
+
+    /// Prompt tokens served from Gemini's implicit cache (included in `prompt_token_count`)
+    #[serde(default)]
+    pub(crate) cached_content_token_count: u32,
+}
+
+impl From<&UsageMetadata> for crate::TokenUsage {
+    fn from(usage: &UsageMetadata) -> Self {
+        let mut token_usage =
+            crate::TokenUsage::new(usage.prompt_token_count, usage.candidates_token_count)
+                .with_cached_tokens(usage.cached_content_token_count);
+        // Total includes thinking tokens, which aren't part of candidates_token_count
+        if let Some(total) = usage.total_token_count {
+            token_usage.total_tokens = total;
+        }
+        token_usage
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct SafetyRating {
+    pub(crate) category: String,
+
+    #[serde(default)]
+    pub(crate) blocked: bool,
+}
+
+/// The first category that caused a block, if any is named
+fn blocked_category(ratings: &[SafetyRating]) -> Option<String> {
+    ratings.iter().find(|rating| rating.blocked).map(|rating| rating.category.clone())
+}
+
+/// Set instead of candidates when the prompt itself was blocked
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PromptFeedback {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) block_reason: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) safety_ratings: Vec<SafetyRating>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct GenerateContentResponse {
+    #[serde(default)]
     pub(crate) candidates: Vec<Candidate>,
 
+    #[serde(rename = "promptFeedback", skip_serializing_if = "Option::is_none")]
+    pub(crate) prompt_feedback: Option<PromptFeedback>,
+
+    #[serde(rename = "usageMetadata", skip_serializing_if = "Option::is_none")]
+    pub(crate) usage_metadata: Option<UsageMetadata>,
+
     #[serde(flatten)]
     pub(crate) extra: Option<serde_json::Value>,
 }
 
+impl Candidate {
+    /// Map Gemini's `finishReason`, which is only set on the last chunk
+    fn finish_reason(&self) -> Option<crate::FinishReason> {
+        let reason = self.finish_reason.as_deref()?;
+        Some(match reason {
+            // Gemini reports STOP for function calls too
+            "STOP" if self.content.parts.iter().any(|p| matches!(p.data, PartType::FunctionCall(_))) => {
+                crate::FinishReason::ToolUse
+            }
+            "STOP" => crate::FinishReason::Stop,
+            "MAX_TOKENS" => crate::FinishReason::Length,
+            // SAFETY names the category in the ratings; the other reasons
+            // are their own category
+            "SAFETY" => crate::FinishReason::ContentFilter {
+                category: blocked_category(&self.safety_ratings),
+            },
+            "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" => crate::FinishReason::ContentFilter {
+                category: Some(reason.to_string()),
+            },
+            _ => crate::FinishReason::Other,
+        })
+    }
+}
+
+impl GenerateContentResponse {
+    /// The first candidate's content and finish reason
+    ///
+    /// A blocked prompt has no candidates, only `promptFeedback`; it reads
+    /// as an empty answer stopped by the content filter.
+    fn first_candidate(&self) -> (Content, Option<crate::FinishReason>) {
+        if let Some(candidate) = self.candidates.first() {
+            // TODO: Move out of candidates instead of cloning.
+            return (candidate.content.clone(), candidate.finish_reason());
+        }
+        let finish_reason = self.prompt_feedback.as_ref().and_then(|feedback| {
+            let reason = feedback.block_reason.as_ref()?;
+            Some(crate::FinishReason::ContentFilter {
+                category: blocked_category(&feedback.safety_ratings).or_else(|| Some(reason.clone())),
+            })
+        });
+        (Content::default(), finish_reason)
+    }
+}
+
 impl From<GenerateContentResponse> for crate::ChatMessage {
     fn from(response: GenerateContentResponse) -> Self {
-        // TODO: Move out of candidates instead of cloning.
-        response.candidates.first().unwrap().content.clone().into()
+        let usage = response.usage_metadata.as_ref().map(crate::TokenUsage::from);
+        let (content, finish_reason) = response.first_candidate();
+        crate::ChatMessage::from(content)
+            .with_usage(usage)
+            .with_finish_reason(finish_reason)
     }
 }
 
 impl From<GenerateContentResponse> for crate::ChatChunk {
     fn from(response: GenerateContentResponse) -> Self {
-        // TODO: Move out of candidates instead of cloning.
-        response.candidates.first().unwrap().content.clone().into()
+        // Gemini reports cumulative usage on every chunk, so the last one seen is the total
+        let usage = response.usage_metadata.as_ref().map(crate::TokenUsage::from);
+        let (content, finish_reason) = response.first_candidate();
+        crate::ChatChunk::from(content)
+            .with_usage(usage)
+            .with_finish_reason(finish_reason)
     }
 }
 
@@ -704,4 +894,152 @@ mod tests {
             r#"{"role":"user","parts":[{"thought":true,"text":"Hello, world!","foo":"bar"}]}"#
         );
     }
+
+    #[test]
+    fn test_model_capabilities_include_tools() {
+        let model = |name: &str, method: &str| {
+            crate::ModelDefinition::from(ModelDefinition {
+                name: name.to_string(),
+                version: "001".to_string(),
+                display_name: None,
+                description: None,
+                input_token_limit: None,
+                output_token_limit: None,
+                thinking: None,
+                supported_generation_methods: Some(vec![method.to_string()]),
+            })
+        };
+        let tools = |def: crate::ModelDefinition| {
+            def.capabilities.contains(&crate::ModelCapability::Tools)
+        };
+
+        assert!(tools(model("models/gemini-2.5-flash", "generateContent")));
+        assert!(!tools(model("models/gemma-3-27b-it", "generateContent")));
+        assert!(!tools(model("models/gemini-embedding-001", "embedContent")));
+    }
+
+    #[test]
+    fn test_model_limits_from_token_limits() {
+        let definition = crate::ModelDefinition::from(ModelDefinition {
+            name: "models/gemini-2.5-flash".to_string(),
+            version: "001".to_string(),
+            display_name: None,
+            description: None,
+            input_token_limit: Some(1_048_576),
+            output_token_limit: Some(65_536),
+            thinking: None,
+            supported_generation_methods: Some(vec!["generateContent".to_string()]),
+        });
+        assert_eq!(definition.context_window, Some(1_048_576));
+        assert_eq!(definition.max_output_tokens, Some(65_536));
+    }
+
+    #[test]
+    fn test_generation_config_from_params() {
+        let message = crate::ChatMessage::user(crate::ChatPayload::text("Hello"));
+        let request = ChatRequest::new([&message]).with_params(crate::GenerationParams {
+            temperature: Some(0.2),
+            stop: Some(vec!["END".to_string()]),
+            ..Default::default()
+        });
+        let json = serde_json::to_string(&GenerateContentRequest::from(&request)).unwrap();
+        assert!(json.contains(r#""generationConfig":{"temperature":0.2,"stopSequences":["END"]}"#));
+
+        let request = ChatRequest::new([&message]);
+        let json = serde_json::to_string(&GenerateContentRequest::from(&request)).unwrap();
+        assert!(!json.contains("generationConfig"));
+    }
+
+    #[test]
+    fn test_json_schema_response_format() {
+        let message = crate::ChatMessage::user(crate::ChatPayload::text("Hello"));
+        let request = ChatRequest::new([&message]).with_response_format(crate::ResponseFormat::JsonSchema(
+            serde_json::json!({"$schema": "http://json-schema.org/draft-07/schema#", "type": "object"}),
+        ));
+        let json = serde_json::to_value(GenerateContentRequest::from(&request)).unwrap();
+        assert_eq!(
+            json["generationConfig"],
+            serde_json::json!({"responseMimeType": "application/json", "responseSchema": {"type": "OBJECT"}})
+        );
+    }
+
+    #[test]
+    fn test_usage_reports_cached_tokens() {
+        let usage: UsageMetadata = serde_json::from_str(
+            r#"{"promptTokenCount":2000,"candidatesTokenCount":50,"totalTokenCount":2050,"cachedContentTokenCount":1536}"#,
+        )
+        .unwrap();
+        let usage = crate::TokenUsage::from(&usage);
+        assert_eq!(usage.prompt_tokens, 2000);
+        assert_eq!(usage.cached_tokens, 1536);
+        assert_eq!(usage.total_tokens, 2050);
+    }
+
+    #[test]
+    fn test_max_tokens_marks_message_truncated() {
+        let response: GenerateContentResponse = serde_json::from_str(
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Once upon"}]},"finishReason":"MAX_TOKENS"}]}"#,
+        )
+        .unwrap();
+        let message = crate::ChatMessage::from(response);
+        assert_eq!(message.get_text(), "Once upon");
+        assert!(message.is_truncated());
+
+        let response: GenerateContentResponse = serde_json::from_str(
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"functionCall":{"name":"f","args":{}}}]},"finishReason":"STOP"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(crate::ChatChunk::from(response).finish_reason, Some(crate::FinishReason::ToolUse));
+    }
+
+    #[test]
+    fn test_blocked_candidate_reports_category() {
+        let response: GenerateContentResponse = serde_json::from_str(
+            r#"{"candidates":[{"finishReason":"SAFETY","index":0,"safetyRatings":[
+                {"category":"HARM_CATEGORY_HATE_SPEECH","probability":"NEGLIGIBLE"},
+                {"category":"HARM_CATEGORY_DANGEROUS_CONTENT","probability":"HIGH","blocked":true}]}]}"#,
+        )
+        .unwrap();
+        let message = crate::ChatMessage::from(response);
+        assert_eq!(
+            message.blocked(),
+            Some(crate::ContentFiltered {
+                category: Some("HARM_CATEGORY_DANGEROUS_CONTENT".to_string())
+            })
+        );
+
+        let response: GenerateContentResponse = serde_json::from_str(
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"The lyrics go"}]},"finishReason":"RECITATION"}]}"#,
+        )
+        .unwrap();
+        let message = crate::ChatMessage::from(response);
+        assert_eq!(
+            message.finish_reason,
+            Some(crate::FinishReason::ContentFilter { category: Some("RECITATION".to_string()) })
+        );
+        // Cut off partway, so there is text to show
+        assert!(message.blocked().is_none());
+    }
+
+    #[test]
+    fn test_blocked_prompt_has_no_candidates() {
+        let response: GenerateContentResponse = serde_json::from_str(
+            r#"{"promptFeedback":{"blockReason":"SAFETY","safetyRatings":[
+                {"category":"HARM_CATEGORY_HARASSMENT","probability":"HIGH","blocked":true}]},
+                "usageMetadata":{"promptTokenCount":8,"totalTokenCount":8}}"#,
+        )
+        .unwrap();
+        let chunk = crate::ChatChunk::from(response);
+        assert_eq!(
+            crate::ChatMessage::from(chunk).blocked(),
+            Some(crate::ContentFiltered { category: Some("HARM_CATEGORY_HARASSMENT".to_string()) })
+        );
+
+        let response: GenerateContentResponse =
+            serde_json::from_str(r#"{"promptFeedback":{"blockReason":"OTHER"}}"#).unwrap();
+        assert_eq!(
+            crate::ChatMessage::from(response).blocked(),
+            Some(crate::ContentFiltered { category: Some("OTHER".to_string()) })
+        );
+    }
 }
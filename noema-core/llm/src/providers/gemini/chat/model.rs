@@ -14,7 +14,7 @@ pub struct GeminiChatModel {
 impl GeminiChatModel {
     pub fn new(client: Client, base_url: String, model_name: String) -> Self {
         GeminiChatModel {
-            client,
+            client: client.for_model(&model_name),
             base_url,
             model_name,
         }
@@ -31,15 +31,17 @@ impl ChatModel for GeminiChatModel {
         &self.model_name
     }
 
+    fn supports_tools(&self) -> bool {
+        super::api::supports_tools(&self.model_name)
+    }
+
     async fn chat(&self, request: &ChatRequest) -> anyhow::Result<ChatMessage> {
         let url = format!("{}/{}:generateContent", self.base_url, self.model_name);
 
         let api_request: GenerateContentRequest = GenerateContentRequest::from(request);
-        traffic_log::log_request(&self.model_name, &api_request);
 
         match self.client.post(url, &api_request).await {
             Ok(response) => {
-                traffic_log::log_response(&self.model_name, &response);
                 let response: GenerateContentResponse = response;
                 Ok(response.into())
             }
@@ -57,14 +59,13 @@ impl ChatModel for GeminiChatModel {
         );
 
         let api_request: GenerateContentRequest = GenerateContentRequest::from(request);
-        traffic_log::log_stream_start(&self.model_name, &api_request);
 
         let streamed_response = self
             .client
             .post_stream(url, &api_request, |line: &str| line.strip_prefix("data: "))
             .await?;
         Ok(Box::pin(
-            streamed_response.map(|chunk: GenerateContentResponse| chunk.into()),
+            streamed_response.map(|chunk: anyhow::Result<GenerateContentResponse>| chunk.map(Into::into)),
         ))
     }
 }
@@ -0,0 +1,118 @@
+use crate::client::Client;
+use crate::traffic_log;
+use crate::{EmbeddingModel, Embeddings};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Requests per `batchEmbedContents` call
+const MAX_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Serialize)]
+struct TextPart<'a> {
+    text: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct EmbedContent<'a> {
+    parts: [TextPart<'a>; 1],
+}
+
+#[derive(Debug, Serialize)]
+struct EmbedContentRequest<'a> {
+    model: &'a str,
+    content: EmbedContent<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchEmbedContentsRequest<'a> {
+    requests: Vec<EmbedContentRequest<'a>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentEmbedding {
+    values: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchEmbedContentsResponse {
+    embeddings: Vec<ContentEmbedding>,
+}
+
+/// Embedding model such as `models/gemini-embedding-001`
+pub struct GeminiEmbeddingModel {
+    client: Client,
+    base_url: String,
+    /// Resource name, always with the `models/` prefix
+    model_name: String,
+}
+
+impl GeminiEmbeddingModel {
+    pub fn new(client: Client, base_url: String, model_name: String) -> Self {
+        let model_name = if model_name.starts_with("models/") {
+            model_name
+        } else {
+            format!("models/{}", model_name)
+        };
+        GeminiEmbeddingModel {
+            client: client.for_model(&model_name),
+            base_url,
+            model_name,
+        }
+    }
+
+    async fn embed_batch(&self, inputs: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let request = BatchEmbedContentsRequest {
+            requests: inputs
+                .iter()
+                .map(|text| EmbedContentRequest {
+                    model: &self.model_name,
+                    content: EmbedContent {
+                        parts: [TextPart { text }],
+                    },
+                })
+                .collect(),
+        };
+        let url = format!("{}/{}:batchEmbedContents", self.base_url, self.model_name);
+        let response: BatchEmbedContentsResponse =
+            self.client.post(url, &request).await.inspect_err(|e| {
+                traffic_log::log_error(&self.model_name, &e.to_string());
+            })?;
+        Ok(response.embeddings.into_iter().map(|e| e.values).collect())
+    }
+}
+
+#[async_trait]
+impl EmbeddingModel for GeminiEmbeddingModel {
+    fn id(&self) -> &str {
+        &self.model_name
+    }
+
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Embeddings> {
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(MAX_BATCH_SIZE) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        Embeddings::from_vectors(inputs.len(), vectors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_request_serialization() {
+        let request = BatchEmbedContentsRequest {
+            requests: vec![EmbedContentRequest {
+                model: "models/gemini-embedding-001",
+                content: EmbedContent {
+                    parts: [TextPart { text: "hello" }],
+                },
+            }],
+        };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"requests":[{"model":"models/gemini-embedding-001","content":{"parts":[{"text":"hello"}]}}]}"#
+        );
+    }
+}
@@ -1,6 +1,8 @@
 pub mod chat;
+pub mod embedding;
 mod provider;
 
 pub use chat::model::GeminiChatModel;
 
+pub use embedding::GeminiEmbeddingModel;
 pub use provider::GeminiProvider;
@@ -1,7 +1,10 @@
 use super::chat::api::ListModelsResponse;
 use super::chat::model::GeminiChatModel;
-use crate::{ChatModel, ModelProvider};
-use crate::client::Client;
+use super::embedding::GeminiEmbeddingModel;
+use crate::{ChatModel, EmbeddingModel, ModelProvider};
+use crate::client::{Client, Timeouts};
+use crate::replay::TrafficReplay;
+use crate::traffic_log::TrafficLogger;
 use async_trait::async_trait;
 use reqwest::header;
 use std::sync::Arc;
@@ -34,6 +37,24 @@ impl GeminiProvider {
             base_url: format!("{}/{}", base_url, API_VERSION),
         }
     }
+
+    /// Record the raw traffic of every model created by this provider
+    pub fn with_traffic_logger(mut self, logger: Arc<dyn TrafficLogger>) -> Self {
+        self.client.set_traffic_logger(logger);
+        self
+    }
+
+    /// Bound how long every model created by this provider may wait on a request
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.client.set_timeouts(timeouts);
+        self
+    }
+
+    /// Answer every model's requests from recorded traffic instead of the network
+    pub fn with_replay(mut self, replay: Arc<TrafficReplay>) -> Self {
+        self.client.set_replay(replay);
+        self
+    }
 }
 
 #[async_trait]
@@ -51,4 +72,15 @@ impl ModelProvider for GeminiProvider {
             model_name.to_string(),
         )))
     }
+
+    fn create_embedding_model(
+        &self,
+        model_name: &str,
+    ) -> Option<Arc<dyn EmbeddingModel + Send + Sync>> {
+        Some(Arc::new(GeminiEmbeddingModel::new(
+            self.client.clone(),
+            self.base_url.clone(),
+            model_name.to_string(),
+        )))
+    }
 }
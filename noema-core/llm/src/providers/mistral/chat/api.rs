@@ -1,6 +1,13 @@
 use crate::api::{ChatMessage, ChatRequest, Role};
 use serde::{Deserialize, Serialize};
 
+/// Whether a model supports function calling; the embedding, moderation
+/// and OCR models do not.
+pub(crate) fn supports_tools(model_id: &str) -> bool {
+    const WITHOUT_TOOLS: &[&str] = &["embed", "moderation", "ocr"];
+    !WITHOUT_TOOLS.iter().any(|marker| model_id.contains(marker))
+}
+
 /// Mistral content part for multimodal messages
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -179,6 +186,48 @@ pub struct ChatCompletionRequest {
     pub stream: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub random_seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    JsonObject,
+    JsonSchema { json_schema: JsonSchemaFormat },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct JsonSchemaFormat {
+    pub name: String,
+    pub schema: serde_json::Value,
+    pub strict: bool,
+}
+
+impl ResponseFormat {
+    fn from_format(format: &crate::ResponseFormat) -> Option<Self> {
+        match format {
+            crate::ResponseFormat::Text => None,
+            crate::ResponseFormat::JsonObject => Some(ResponseFormat::JsonObject),
+            crate::ResponseFormat::JsonSchema(schema) => Some(ResponseFormat::JsonSchema {
+                json_schema: JsonSchemaFormat {
+                    name: "response".to_string(),
+                    schema: schema.clone(),
+                    strict: false,
+                },
+            }),
+        }
+    }
 }
 
 impl ChatCompletionRequest {
@@ -193,6 +242,12 @@ impl ChatCompletionRequest {
             messages: request.messages.iter().map(|m| m.into()).collect(),
             stream: if stream { Some(true) } else { None },
             tools,
+            temperature: request.params.temperature,
+            top_p: request.params.top_p,
+            max_tokens: request.params.max_tokens,
+            stop: request.params.stop.clone(),
+            random_seed: request.params.seed,
+            response_format: ResponseFormat::from_format(&request.response_format),
         }
     }
 }
@@ -211,6 +266,26 @@ pub struct ChatCompletionResponse {
     pub created: u64,
     pub model: String,
     pub choices: Vec<ChatCompletionChoice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Usage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+impl From<&Usage> for crate::api::TokenUsage {
+    fn from(usage: &Usage) -> Self {
+        crate::api::TokenUsage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+            cached_tokens: 0,
+        }
+    }
 }
 
 impl From<ChatCompletionResponse> for ChatMessage {
@@ -263,6 +338,18 @@ impl From<ChatCompletionResponse> for ChatMessage {
         }
 
         ChatMessage::assistant(crate::ChatPayload::new(content))
+            .with_usage(response.usage.as_ref().map(crate::api::TokenUsage::from))
+            .with_finish_reason(choice.finish_reason.as_deref().map(finish_reason))
+    }
+}
+
+/// Map Mistral's `finish_reason`
+pub fn finish_reason(reason: &str) -> crate::FinishReason {
+    match reason {
+        "stop" => crate::FinishReason::Stop,
+        "length" | "model_length" => crate::FinishReason::Length,
+        "tool_calls" => crate::FinishReason::ToolUse,
+        _ => crate::FinishReason::Other,
     }
 }
 
@@ -290,4 +377,27 @@ pub struct ChatCompletionChunk {
     pub created: u64,
     pub model: String,
     pub choices: Vec<ChatCompletionChunkChoice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_finish_reason_is_mapped() {
+        let response = |finish_reason: &str| -> ChatMessage {
+            let json = format!(
+                r#"{{"id":"cmpl-1","object":"chat.completion","created":0,"model":"mistral","choices":[{{"index":0,"message":{{"role":"assistant","content":"Once upon"}},"finish_reason":"{}"}}]}}"#,
+                finish_reason
+            );
+            serde_json::from_str::<ChatCompletionResponse>(&json).unwrap().into()
+        };
+        assert!(response("length").is_truncated());
+        assert!(response("model_length").is_truncated());
+        assert_eq!(response("stop").finish_reason, Some(crate::FinishReason::Stop));
+        assert_eq!(response("tool_calls").finish_reason, Some(crate::FinishReason::ToolUse));
+        assert_eq!(response("error").finish_reason, Some(crate::FinishReason::Other));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::api::{ChatChunk, ChatMessage, ChatRequest, Role};
+use crate::api::{ChatChunk, ChatMessage, ChatRequest, Role, TokenUsage};
 use crate::client::Client;
 use crate::traffic_log;
 use crate::ChatModel;
@@ -6,7 +6,7 @@ use crate::ChatStream;
 use async_trait::async_trait;
 use futures::StreamExt;
 
-use super::api::{ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse};
+use super::api::{finish_reason, ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse};
 
 #[derive(Clone)]
 pub struct MistralChatModel {
@@ -18,7 +18,7 @@ pub struct MistralChatModel {
 impl MistralChatModel {
     pub fn new(client: Client, base_url: String, model_name: String) -> Self {
         MistralChatModel {
-            client,
+            client: client.for_model(&model_name),
             base_url,
             model_name,
         }
@@ -39,14 +39,16 @@ impl ChatModel for MistralChatModel {
         &self.model_name
     }
 
+    fn supports_tools(&self) -> bool {
+        super::api::supports_tools(&self.model_name)
+    }
+
     async fn chat(&self, request: &ChatRequest) -> anyhow::Result<ChatMessage> {
         let mistral_request =
             ChatCompletionRequest::from_request(self.model_name.clone(), request, false);
-        traffic_log::log_request(&self.model_name, &mistral_request);
 
         match self.client.post(self.chat_url(), &mistral_request).await {
             Ok(response) => {
-                traffic_log::log_response(&self.model_name, &response);
                 let response: ChatCompletionResponse = response;
                 Ok(response.into())
             }
@@ -60,7 +62,6 @@ impl ChatModel for MistralChatModel {
     async fn stream_chat(&self, request: &ChatRequest) -> anyhow::Result<ChatStream> {
         let mistral_request =
             ChatCompletionRequest::from_request(self.model_name.clone(), request, true);
-        traffic_log::log_stream_start(&self.model_name, &mistral_request);
 
         let stream = self
             .client
@@ -80,12 +81,20 @@ impl ChatModel for MistralChatModel {
             })
             .await?;
 
-        let chat_stream = stream.map(|chunk| {
-            let choice = &chunk.choices[0];
+        let chat_stream = stream.map(|chunk| -> anyhow::Result<ChatChunk> {
+            let chunk = chunk?;
+            let usage = chunk.usage.as_ref().map(TokenUsage::from);
+            // The usage-only chunk at the end of the stream has no choices
+            let Some(choice) = chunk.choices.first() else {
+                return Ok(ChatChunk::new(Role::Assistant, crate::ChatPayload::default())
+                    .with_usage(usage));
+            };
             let role = choice.delta.role.unwrap_or(Role::Assistant);
             let content = choice.delta.content.clone().unwrap_or_default();
 
-            ChatChunk::new(role, crate::ChatPayload::text(content))
+            Ok(ChatChunk::new(role, crate::ChatPayload::text(content))
+                .with_usage(usage)
+                .with_finish_reason(choice.finish_reason.as_deref().map(finish_reason)))
         });
 
         Ok(Box::pin(chat_stream))
@@ -1,10 +1,13 @@
-use crate::client::Client;
+use crate::client::{Client, Timeouts};
+use crate::replay::TrafficReplay;
+use crate::traffic_log::TrafficLogger;
 use crate::{ChatModel, ModelProvider};
 use async_trait::async_trait;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use super::chat::api::supports_tools;
 use super::chat::MistralChatModel;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -13,13 +16,21 @@ pub struct ModelInfo {
     pub object: String,
     pub created: u64,
     pub owned_by: String,
+    /// Context window in tokens
+    #[serde(default)]
+    pub max_context_length: Option<u32>,
 }
 
 impl From<ModelInfo> for crate::ModelDefinition {
     fn from(model: ModelInfo) -> Self {
         // Mistral models support text/chat as their primary capability
-        let capabilities = vec![crate::ModelCapability::Text];
+        let mut capabilities = vec![crate::ModelCapability::Text];
+        if supports_tools(&model.id) {
+            capabilities.push(crate::ModelCapability::Tools);
+        }
+        // The API reports the context window but not an output limit
         crate::ModelDefinition::new(model.id, capabilities)
+            .with_limits(model.max_context_length, None)
     }
 }
 
@@ -67,6 +78,24 @@ impl MistralProvider {
     fn models_url(&self) -> String {
         format!("{}/models", self.base_url)
     }
+
+    /// Record the raw traffic of every model created by this provider
+    pub fn with_traffic_logger(mut self, logger: Arc<dyn TrafficLogger>) -> Self {
+        self.client.set_traffic_logger(logger);
+        self
+    }
+
+    /// Bound how long every model created by this provider may wait on a request
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.client.set_timeouts(timeouts);
+        self
+    }
+
+    /// Answer every model's requests from recorded traffic instead of the network
+    pub fn with_replay(mut self, replay: Arc<TrafficReplay>) -> Self {
+        self.client.set_replay(replay);
+        self
+    }
 }
 
 #[async_trait]
@@ -3,14 +3,21 @@ pub(crate) mod gemini;
 pub(crate) mod mistral;
 pub(crate) mod ollama;
 pub(crate) mod openai;
+pub(crate) mod openai_compatible;
 
 pub use claude::{ClaudeChatModel, ClaudeProvider};
-pub use gemini::{GeminiChatModel, GeminiProvider};
+pub use gemini::{GeminiChatModel, GeminiEmbeddingModel, GeminiProvider};
 pub use mistral::{MistralChatModel, MistralProvider};
-pub use ollama::{OllamaChatModel, OllamaProvider};
-pub use openai::{OpenAIChatModel, OpenAIProvider};
+pub use ollama::{
+    set_pull_progress_handler, OllamaChatModel, OllamaEmbeddingModel, OllamaProvider, PullProgress,
+    PullProgressFn,
+};
+pub use openai::{OpenAIChatModel, OpenAIEmbeddingModel, OpenAIProvider};
+pub use openai_compatible::OpenAICompatibleProvider;
 
+use crate::replay::TrafficReplay;
 use llm_macros::delegate_provider_enum;
+use std::sync::Arc;
 
 #[delegate_provider_enum]
 pub enum GeneralModelProvider {
@@ -29,3 +36,27 @@ pub enum GeneralModelProvider {
     #[provider(name = "mistral", api_key_env = "MISTRAL_API_KEY", base_url_env = "MISTRAL_BASE_URL")]
     Mistral(MistralProvider),
 }
+
+impl GeneralModelProvider {
+    /// Bound how long every model created by this provider may wait on a request
+    pub fn with_timeouts(self, timeouts: crate::Timeouts) -> Self {
+        match self {
+            Self::Ollama(provider) => Self::Ollama(provider.with_timeouts(timeouts)),
+            Self::Gemini(provider) => Self::Gemini(provider.with_timeouts(timeouts)),
+            Self::Claude(provider) => Self::Claude(provider.with_timeouts(timeouts)),
+            Self::OpenAI(provider) => Self::OpenAI(provider.with_timeouts(timeouts)),
+            Self::Mistral(provider) => Self::Mistral(provider.with_timeouts(timeouts)),
+        }
+    }
+
+    /// Answer every model's requests from recorded traffic instead of the network
+    pub fn with_replay(self, replay: Arc<TrafficReplay>) -> Self {
+        match self {
+            Self::Ollama(provider) => Self::Ollama(provider.with_replay(replay)),
+            Self::Gemini(provider) => Self::Gemini(provider.with_replay(replay)),
+            Self::Claude(provider) => Self::Claude(provider.with_replay(replay)),
+            Self::OpenAI(provider) => Self::OpenAI(provider.with_replay(replay)),
+            Self::Mistral(provider) => Self::Mistral(provider.with_replay(replay)),
+        }
+    }
+}
@@ -51,6 +51,9 @@ impl From<ModelDefinition> for crate::ModelDefinition {
             if has_vision {
                 capabilities.push(crate::ModelCapability::Vision);
             }
+            if supports_tools(&model.name) {
+                capabilities.push(crate::ModelCapability::Tools);
+            }
         }
 
         // Fallback: if we couldn't determine anything, assume text
@@ -65,6 +68,21 @@ impl From<ModelDefinition> for crate::ModelDefinition {
     }
 }
 
+/// Model families whose Ollama templates support tool calling
+const TOOL_FAMILIES: &[&str] = &[
+    "command-r", "devstral", "firefunction", "gpt-oss", "granite3", "hermes3", "llama3.1",
+    "llama3.2", "llama3.3", "llama4", "mistral", "mixtral", "qwen2", "qwen3", "smollm2",
+];
+
+/// Whether a local model can be sent tools. `/api/tags` does not report
+/// this, so it is decided from the model name (e.g. `qwen3:8b`), without
+/// any namespace (`library/qwen3`) or tag.
+pub(crate) fn supports_tools(model_name: &str) -> bool {
+    let base = model_name.split(':').next().unwrap_or(model_name);
+    let base = base.rsplit('/').next().unwrap_or(base).to_ascii_lowercase();
+    TOOL_FAMILIES.iter().any(|family| base.starts_with(family))
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct ListModelsResponse {
     pub(crate) models: Vec<ModelDefinition>,
@@ -246,6 +264,47 @@ pub(crate) struct OllamaRequest {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) tools: Option<Vec<OllamaTool>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) options: Option<OllamaOptions>,
+
+    /// `"json"` for any JSON, or a JSON schema the reply must match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) format: Option<serde_json::Value>,
+}
+
+/// Model options overriding the Modelfile defaults
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct OllamaOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) temperature: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) top_p: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) num_predict: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) stop: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) seed: Option<u64>,
+}
+
+impl OllamaOptions {
+    fn from_params(params: &crate::GenerationParams) -> Option<Self> {
+        if *params == crate::GenerationParams::default() {
+            return None;
+        }
+        Some(OllamaOptions {
+            temperature: params.temperature,
+            top_p: params.top_p,
+            num_predict: params.max_tokens,
+            stop: params.stop.clone(),
+            seed: params.seed,
+        })
+    }
 }
 
 impl OllamaRequest {
@@ -262,6 +321,12 @@ impl OllamaRequest {
             messages: ollama_messages,
             stream: Some(stream),
             tools,
+            options: OllamaOptions::from_params(&value.params),
+            format: match &value.response_format {
+                crate::ResponseFormat::Text => None,
+                crate::ResponseFormat::JsonObject => Some(serde_json::json!("json")),
+                crate::ResponseFormat::JsonSchema(schema) => Some(schema.clone()),
+            },
         }
     }
 }
@@ -270,19 +335,61 @@ impl OllamaRequest {
 pub(crate) struct OllamaResponse {
     pub(crate) message: Message,
 
+    /// Prompt token count (only present on the final response)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) prompt_eval_count: Option<u32>,
+
+    /// Generated token count (only present on the final response)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) eval_count: Option<u32>,
+
+    /// Why generation stopped (only present on the final response)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) done_reason: Option<String>,
+
     #[serde(flatten)]
     pub(crate) extra: serde_json::Value,
 }
 
+impl OllamaResponse {
+    fn usage(&self) -> Option<crate::TokenUsage> {
+        if self.prompt_eval_count.is_none() && self.eval_count.is_none() {
+            return None;
+        }
+        Some(crate::TokenUsage::new(
+            self.prompt_eval_count.unwrap_or(0),
+            self.eval_count.unwrap_or(0),
+        ))
+    }
+
+    fn finish_reason(&self) -> Option<crate::FinishReason> {
+        let reason = self.done_reason.as_deref()?;
+        Some(match reason {
+            "stop" if self.message.tool_calls.as_ref().is_some_and(|calls| !calls.is_empty()) => {
+                crate::FinishReason::ToolUse
+            }
+            "stop" => crate::FinishReason::Stop,
+            "length" => crate::FinishReason::Length,
+            _ => crate::FinishReason::Other,
+        })
+    }
+}
+
 impl From<OllamaResponse> for crate::ChatMessage {
     fn from(response: OllamaResponse) -> Self {
-        response.message.into()
+        let usage = response.usage();
+        let finish_reason = response.finish_reason();
+        let message: crate::ChatMessage = response.message.into();
+        message.with_usage(usage).with_finish_reason(finish_reason)
     }
 }
 
 impl From<OllamaResponse> for crate::ChatChunk {
     fn from(response: OllamaResponse) -> Self {
-        response.message.into()
+        let usage = response.usage();
+        let finish_reason = response.finish_reason();
+        let chunk: crate::ChatChunk = response.message.into();
+        chunk.with_usage(usage).with_finish_reason(finish_reason)
     }
 }
 
@@ -309,6 +416,8 @@ mod tests {
             messages,
             stream: None,
             tools: None,
+            options: None,
+            format: None,
         };
         let json = serde_json::to_string(&request).unwrap();
         assert_eq!(
@@ -336,6 +445,8 @@ mod tests {
             messages,
             stream: Some(false),
             tools: None,
+            options: None,
+            format: None,
         };
         let json = serde_json::to_string(&request).unwrap();
         assert_eq!(
@@ -343,4 +454,46 @@ mod tests {
             r#"{"model":"test-model","messages":[{"role":"user","content":"Hello"},{"role":"assistant","content":"Hi there!"}],"stream":false}"#
         );
     }
+
+    #[test]
+    fn test_generation_params_become_options() {
+        let message = crate::ChatMessage::user(crate::ChatPayload::text("Hello"));
+        let request = crate::ChatRequest::new([&message]).with_params(crate::GenerationParams {
+            temperature: Some(0.5),
+            max_tokens: Some(64),
+            seed: Some(7),
+            ..Default::default()
+        });
+        let json = serde_json::to_value(OllamaRequest::from_chat_request("m", &request, false)).unwrap();
+        assert_eq!(json["options"], serde_json::json!({"temperature": 0.5, "num_predict": 64, "seed": 7}));
+
+        let request = crate::ChatRequest::new([&message]);
+        let json = serde_json::to_value(OllamaRequest::from_chat_request("m", &request, false)).unwrap();
+        assert!(json.get("options").is_none());
+    }
+
+    #[test]
+    fn test_supports_tools_by_model_name() {
+        assert!(supports_tools("qwen3:8b"));
+        assert!(supports_tools("llama3.1"));
+        assert!(supports_tools("registry.ollama.ai/library/mistral-nemo:latest"));
+        assert!(!supports_tools("gemma2:9b"));
+        assert!(!supports_tools("llama2:7b"));
+    }
+
+    #[test]
+    fn test_done_reason_sets_finish_reason() {
+        let response: OllamaResponse = serde_json::from_str(
+            r#"{"model":"llama3","message":{"role":"assistant","content":"Once upon"},"done":true,"done_reason":"length","eval_count":8}"#,
+        )
+        .unwrap();
+        assert!(crate::ChatMessage::from(response).is_truncated());
+
+        // Chunks before the last one carry no reason
+        let response: OllamaResponse = serde_json::from_str(
+            r#"{"model":"llama3","message":{"role":"assistant","content":"Once"},"done":false}"#,
+        )
+        .unwrap();
+        assert_eq!(crate::ChatChunk::from(response).finish_reason, None);
+    }
 }
@@ -1,4 +1,5 @@
 use super::api::{OllamaRequest, OllamaResponse};
+use super::super::pull::{is_model_not_found, pull_model, PullProgressFn};
 use crate::client::Client;
 use crate::traffic_log;
 use crate::{ChatMessage, ChatModel, ChatRequest, ChatStream};
@@ -9,16 +10,38 @@ pub struct OllamaChatModel {
     client: Client,
     base_url: String,
     model_name: String,
+    /// Pull the model and retry when the server doesn't have it
+    auto_pull: bool,
+    pull_progress: Option<PullProgressFn>,
 }
 
 impl OllamaChatModel {
     pub fn new(client: Client, base_url: String, model_name: String) -> Self {
         OllamaChatModel {
-            client,
+            client: client.for_model(&model_name),
             base_url,
             model_name,
+            auto_pull: false,
+            pull_progress: None,
         }
     }
+
+    /// Pull the model when a request finds it missing, then retry the request
+    pub fn with_auto_pull(mut self, auto_pull: bool, progress: Option<PullProgressFn>) -> Self {
+        self.auto_pull = auto_pull;
+        self.pull_progress = progress;
+        self
+    }
+
+    /// Pull the model if `error` says it's missing and auto-pull is on.
+    /// Returns whether the request should be retried.
+    async fn pull_if_missing(&self, error: &anyhow::Error) -> anyhow::Result<bool> {
+        if !self.auto_pull || !is_model_not_found(error) {
+            return Ok(false);
+        }
+        pull_model(&self.client, &self.base_url, &self.model_name, self.pull_progress.as_ref()).await?;
+        Ok(true)
+    }
 }
 
 #[async_trait]
@@ -31,15 +54,21 @@ impl ChatModel for OllamaChatModel {
         &self.model_name
     }
 
+    fn supports_tools(&self) -> bool {
+        super::api::supports_tools(&self.model_name)
+    }
+
     async fn chat(&self, request: &ChatRequest) -> anyhow::Result<ChatMessage> {
         let url = format!("{}/api/chat", self.base_url);
 
         let api_request = OllamaRequest::from_chat_request(&self.model_name, request, false);
-        traffic_log::log_request(&self.model_name, &api_request);
 
-        match self.client.post(url, &api_request).await {
+        let result = match self.client.post(&url, &api_request).await {
+            Err(e) if self.pull_if_missing(&e).await? => self.client.post(&url, &api_request).await,
+            result => result,
+        };
+        match result {
             Ok(response) => {
-                traffic_log::log_response(&self.model_name, &response);
                 let response: OllamaResponse = response;
                 Ok(response.into())
             }
@@ -54,11 +83,15 @@ impl ChatModel for OllamaChatModel {
         let url = format!("{}/api/chat", self.base_url);
 
         let api_request = OllamaRequest::from_chat_request(&self.model_name, request, true);
-        traffic_log::log_stream_start(&self.model_name, &api_request);
 
-        let streamed_response = self.client.post_stream(url, &api_request, |m| Some(m)).await?;
+        let streamed_response = match self.client.post_stream(&url, &api_request, |m| Some(m)).await {
+            Err(e) if self.pull_if_missing(&e).await? => {
+                self.client.post_stream(&url, &api_request, |m| Some(m)).await?
+            }
+            result => result?,
+        };
         Ok(Box::pin(
-            streamed_response.map(|chunk: OllamaResponse| chunk.into()),
+            streamed_response.map(|chunk: anyhow::Result<OllamaResponse>| chunk.map(Into::into)),
         ))
     }
 }
@@ -0,0 +1,64 @@
+use crate::client::Client;
+use crate::traffic_log;
+use crate::{EmbeddingModel, Embeddings};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Inputs per `/api/embed` call; the server has no hard limit, but large
+/// batches of a local model can run into the request timeout
+const MAX_BATCH_SIZE: usize = 64;
+
+#[derive(Debug, Serialize)]
+struct EmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Local embedding model such as `nomic-embed-text`
+pub struct OllamaEmbeddingModel {
+    client: Client,
+    base_url: String,
+    model_name: String,
+}
+
+impl OllamaEmbeddingModel {
+    pub fn new(client: Client, base_url: String, model_name: String) -> Self {
+        OllamaEmbeddingModel {
+            client: client.for_model(&model_name),
+            base_url,
+            model_name,
+        }
+    }
+
+    async fn embed_batch(&self, inputs: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let request = EmbedRequest {
+            model: &self.model_name,
+            input: inputs,
+        };
+        let url = format!("{}/api/embed", self.base_url);
+        let response: EmbedResponse = self.client.post(url, &request).await.inspect_err(|e| {
+            traffic_log::log_error(&self.model_name, &e.to_string());
+        })?;
+        Ok(response.embeddings)
+    }
+}
+
+#[async_trait]
+impl EmbeddingModel for OllamaEmbeddingModel {
+    fn id(&self) -> &str {
+        &self.model_name
+    }
+
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Embeddings> {
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(MAX_BATCH_SIZE) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        Embeddings::from_vectors(inputs.len(), vectors)
+    }
+}
@@ -1,6 +1,11 @@
 pub mod chat;
+pub mod embedding;
 mod provider;
+mod pull;
 
 pub use chat::model::OllamaChatModel;
 
+pub use embedding::OllamaEmbeddingModel;
 pub use provider::OllamaProvider;
+pub(crate) use pull::pull_progress_handler;
+pub use pull::{set_pull_progress_handler, PullProgress, PullProgressFn};
@@ -1,13 +1,19 @@
 use super::chat::api::ListModelsResponse;
 use super::chat::model::OllamaChatModel;
-use crate::{ChatModel, ModelProvider};
-use crate::client::Client;
+use super::embedding::OllamaEmbeddingModel;
+use super::pull::PullProgressFn;
+use crate::{ChatModel, EmbeddingModel, ModelProvider};
+use crate::client::{Client, Timeouts};
+use crate::replay::TrafficReplay;
+use crate::traffic_log::TrafficLogger;
 use async_trait::async_trait;
 use std::sync::Arc;
 
 pub struct OllamaProvider {
     client: Client,
     base_url: String,
+    auto_pull: bool,
+    pull_progress: Option<PullProgressFn>,
 }
 
 impl OllamaProvider {
@@ -19,8 +25,41 @@ impl OllamaProvider {
         OllamaProvider {
             client: Client::default(),
             base_url: base_url.to_string(),
+            auto_pull: false,
+            pull_progress: None,
         }
     }
+
+    /// Record the raw traffic of every model created by this provider
+    pub fn with_traffic_logger(mut self, logger: Arc<dyn TrafficLogger>) -> Self {
+        self.client.set_traffic_logger(logger);
+        self
+    }
+
+    /// Bound how long every model created by this provider may wait on a request
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.client.set_timeouts(timeouts);
+        self
+    }
+
+    /// Answer every model's requests from recorded traffic instead of the network
+    pub fn with_replay(mut self, replay: Arc<TrafficReplay>) -> Self {
+        self.client.set_replay(replay);
+        self
+    }
+
+    /// Have models pull themselves from the Ollama library when the server
+    /// doesn't have them yet, instead of failing the request
+    pub fn with_auto_pull(mut self, auto_pull: bool) -> Self {
+        self.auto_pull = auto_pull;
+        self
+    }
+
+    /// Report the progress of automatic pulls to `progress`
+    pub fn with_pull_progress(mut self, progress: PullProgressFn) -> Self {
+        self.pull_progress = Some(progress);
+        self
+    }
 }
 
 #[async_trait]
@@ -32,7 +71,17 @@ impl ModelProvider for OllamaProvider {
     }
 
     fn create_chat_model(&self, model_name: &str) -> Option<Arc<dyn ChatModel + Send + Sync>> {
-        Some(Arc::new(OllamaChatModel::new(
+        Some(Arc::new(
+            OllamaChatModel::new(self.client.clone(), self.base_url.clone(), model_name.to_string())
+                .with_auto_pull(self.auto_pull, self.pull_progress.clone()),
+        ))
+    }
+
+    fn create_embedding_model(
+        &self,
+        model_name: &str,
+    ) -> Option<Arc<dyn EmbeddingModel + Send + Sync>> {
+        Some(Arc::new(OllamaEmbeddingModel::new(
             self.client.clone(),
             self.base_url.clone(),
             model_name.to_string(),
@@ -0,0 +1,149 @@
+//! Downloading models the local Ollama server doesn't have yet (`/api/pull`)
+
+use crate::client::{Client, ProviderError, ProviderErrorKind};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// One progress update of a model pull
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PullProgress {
+    pub model: String,
+    /// Ollama's description of the current step, e.g. "pulling manifest"
+    pub status: String,
+    /// Bytes downloaded of the current layer, when downloading
+    pub completed: Option<u64>,
+    /// Size of the current layer, when downloading
+    pub total: Option<u64>,
+}
+
+impl PullProgress {
+    /// Percentage of the current layer downloaded, if known
+    pub fn percent(&self) -> Option<u8> {
+        match (self.completed, self.total) {
+            (Some(completed), Some(total)) if total > 0 => {
+                Some((completed.min(total) as f64 / total as f64 * 100.0) as u8)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Callback receiving the progress of model pulls
+pub type PullProgressFn = Arc<dyn Fn(&PullProgress) + Send + Sync>;
+
+static PULL_PROGRESS_HANDLER: LazyLock<RwLock<Option<PullProgressFn>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+/// Report the progress of pulls by models created through the registry to
+/// `handler` (they are only logged otherwise)
+pub fn set_pull_progress_handler(handler: PullProgressFn) {
+    *PULL_PROGRESS_HANDLER.write().unwrap() = Some(handler);
+}
+
+/// The handler installed with [`set_pull_progress_handler`], if any
+pub(crate) fn pull_progress_handler() -> Option<PullProgressFn> {
+    PULL_PROGRESS_HANDLER.read().unwrap().clone()
+}
+
+#[derive(Debug, Serialize)]
+struct PullRequest<'a> {
+    model: &'a str,
+    stream: bool,
+}
+
+/// A line of the streamed `/api/pull` response
+#[derive(Debug, Deserialize)]
+struct PullResponse {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    completed: Option<u64>,
+    #[serde(default)]
+    total: Option<u64>,
+}
+
+/// Whether `error` is Ollama saying the requested model isn't installed
+pub(crate) fn is_model_not_found(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<ProviderError>().is_some_and(|error| {
+        error.kind == ProviderErrorKind::NotFound && error.message.contains("not found")
+    })
+}
+
+/// Pull `model`, reporting each step to `progress`, and return once the
+/// server has it
+pub(crate) async fn pull_model(
+    client: &Client,
+    base_url: &str,
+    model: &str,
+    progress: Option<&PullProgressFn>,
+) -> anyhow::Result<()> {
+    let url = format!("{}/api/pull", base_url);
+    let request = PullRequest { model, stream: true };
+    tracing::info!(model, "Pulling Ollama model");
+
+    let mut stream = client.post_stream(url, &request, |line| Some(line)).await?;
+    while let Some(response) = stream.next().await {
+        let response: PullResponse = response?;
+        if let Some(error) = response.error {
+            anyhow::bail!("Failed to pull '{}': {}", model, error);
+        }
+        let Some(status) = response.status else {
+            continue;
+        };
+        if status == "success" {
+            tracing::info!(model, "Pulled Ollama model");
+            return Ok(());
+        }
+        let update = PullProgress {
+            model: model.to_string(),
+            status,
+            completed: response.completed,
+            total: response.total,
+        };
+        tracing::debug!(model, status = update.status, percent = update.percent(), "Pull progress");
+        if let Some(progress) = progress {
+            progress(&update);
+        }
+    }
+    anyhow::bail!("Pull of '{}' ended before it completed", model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_detects_missing_model() {
+        let body = r#"{"error":"model \"llama3.2\" not found, try pulling it first"}"#;
+        let error = anyhow::Error::from(ProviderError::new(StatusCode::NOT_FOUND, body));
+        assert!(is_model_not_found(&error));
+
+        let body = r#"{"error":"unknown endpoint"}"#;
+        let error = anyhow::Error::from(ProviderError::new(StatusCode::NOT_FOUND, body));
+        assert!(!is_model_not_found(&error));
+
+        let body = r#"{"error":"model \"x\" not found"}"#;
+        let error = anyhow::Error::from(ProviderError::new(StatusCode::BAD_REQUEST, body));
+        assert!(!is_model_not_found(&error));
+    }
+
+    #[test]
+    fn test_pull_progress_percent() {
+        let line = r#"{"status":"pulling 6a0746a1ec1a","digest":"sha256:6a07","total":400,"completed":100}"#;
+        let response: PullResponse = serde_json::from_str(line).unwrap();
+        let progress = PullProgress {
+            model: "llama3.2".to_string(),
+            status: response.status.unwrap(),
+            completed: response.completed,
+            total: response.total,
+        };
+        assert_eq!(progress.percent(), Some(25));
+
+        let response: PullResponse = serde_json::from_str(r#"{"status":"pulling manifest"}"#).unwrap();
+        assert_eq!(response.total, None);
+    }
+}
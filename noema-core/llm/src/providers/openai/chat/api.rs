@@ -61,6 +61,9 @@ pub struct Message {
     pub tool_calls: Option<Vec<ToolCall>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_call_id: Option<String>,
+    /// The model's explanation when it declines to answer, in place of `content`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refusal: Option<String>,
 }
 
 impl From<&ChatMessage> for Message {
@@ -118,6 +121,7 @@ impl From<&ChatMessage> for Message {
                     content: Some(MessageContent::Text(result_text)),
                     tool_calls: None,
                     tool_call_id: Some(result.tool_call_id.clone()),
+                    refusal: None,
                 };
             }
         }
@@ -186,6 +190,7 @@ impl From<&ChatMessage> for Message {
                 Some(tool_calls)
             },
             tool_call_id: None,
+            refusal: None,
         }
     }
 }
@@ -226,7 +231,61 @@ pub struct ChatCompletionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Replaces the deprecated `max_tokens`, which reasoning models reject
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_completion_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+}
+
+/// Native JSON mode
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    JsonObject,
+    JsonSchema { json_schema: JsonSchemaFormat },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct JsonSchemaFormat {
+    pub name: String,
+    pub schema: serde_json::Value,
+    /// Strict mode rejects schemas that don't meet its extra rules, so any
+    /// schema is accepted and replies are validated afterwards instead
+    pub strict: bool,
+}
+
+impl ResponseFormat {
+    fn from_format(format: &crate::ResponseFormat) -> Option<Self> {
+        match format {
+            crate::ResponseFormat::Text => None,
+            crate::ResponseFormat::JsonObject => Some(ResponseFormat::JsonObject),
+            crate::ResponseFormat::JsonSchema(schema) => Some(ResponseFormat::JsonSchema {
+                json_schema: JsonSchemaFormat {
+                    name: "response".to_string(),
+                    schema: schema.clone(),
+                    strict: false,
+                },
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StreamOptions {
+    /// Ask for a final chunk carrying token usage (with empty choices)
+    pub include_usage: bool,
 }
 
 impl ChatCompletionRequest {
@@ -240,7 +299,18 @@ impl ChatCompletionRequest {
             model,
             messages: request.messages.iter().map(|m| m.into()).collect(),
             stream: if stream { Some(true) } else { None },
+            stream_options: if stream {
+                Some(StreamOptions { include_usage: true })
+            } else {
+                None
+            },
             tools,
+            temperature: request.params.temperature,
+            top_p: request.params.top_p,
+            max_completion_tokens: request.params.max_tokens,
+            stop: request.params.stop.clone(),
+            seed: request.params.seed,
+            response_format: ResponseFormat::from_format(&request.response_format),
         }
     }
 }
@@ -261,6 +331,26 @@ pub struct ChatCompletionResponse {
     pub created: u64,
     pub model: String,
     pub choices: Vec<ChatCompletionChoice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Usage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+impl From<&Usage> for crate::api::TokenUsage {
+    fn from(usage: &Usage) -> Self {
+        crate::api::TokenUsage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+            cached_tokens: 0,
+        }
+    }
 }
 
 impl From<ChatCompletionResponse> for ChatMessage {
@@ -322,7 +412,27 @@ impl From<ChatCompletionResponse> for ChatMessage {
             }
         }
 
+        // A refusal is the whole answer, and OpenAI still reports it as "stop"
+        let mut reason = choice.finish_reason.as_deref().map(finish_reason);
+        if let Some(refusal) = &choice.message.refusal {
+            content.push(crate::api::ContentBlock::Text { text: refusal.clone() });
+            reason = Some(crate::FinishReason::ContentFilter { category: None });
+        }
+
         ChatMessage::assistant(crate::ChatPayload::new(content))
+            .with_usage(response.usage.as_ref().map(crate::api::TokenUsage::from))
+            .with_finish_reason(reason)
+    }
+}
+
+/// Map OpenAI's `finish_reason`
+pub fn finish_reason(reason: &str) -> crate::FinishReason {
+    match reason {
+        "stop" => crate::FinishReason::Stop,
+        "length" => crate::FinishReason::Length,
+        "tool_calls" | "function_call" => crate::FinishReason::ToolUse,
+        "content_filter" => crate::FinishReason::ContentFilter { category: None },
+        _ => crate::FinishReason::Other,
     }
 }
 
@@ -333,7 +443,72 @@ pub struct ChatCompletionChunkDelta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tool_calls: Option<Vec<ToolCall>>,
+    pub tool_calls: Option<Vec<ToolCallChunk>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refusal: Option<String>,
+}
+
+/// A fragment of a streamed tool call; only the first for each `index`
+/// carries the id and function name
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ToolCallChunk {
+    pub index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<FunctionCallChunk>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FunctionCallChunk {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
+}
+
+/// Assembles tool calls from streamed fragments
+#[derive(Debug, Default)]
+pub(crate) struct StreamedToolCalls {
+    /// (id, name, arguments so far), in `index` order
+    calls: std::collections::BTreeMap<usize, (String, String, String)>,
+}
+
+impl StreamedToolCalls {
+    /// Add fragments, returning a delta for each
+    pub(crate) fn push(&mut self, chunks: &[ToolCallChunk]) -> Vec<crate::ToolCallDelta> {
+        chunks
+            .iter()
+            .map(|chunk| {
+                let (id, name, arguments) = self.calls.entry(chunk.index).or_default();
+                if let Some(chunk_id) = &chunk.id {
+                    id.clone_from(chunk_id);
+                }
+                let function = chunk.function.as_ref();
+                if let Some(chunk_name) = function.and_then(|f| f.name.as_ref()) {
+                    name.push_str(chunk_name);
+                }
+                let fragment = function.and_then(|f| f.arguments.clone()).unwrap_or_default();
+                arguments.push_str(&fragment);
+                crate::ToolCallDelta { id: id.clone(), name: name.clone(), args_fragment: fragment }
+            })
+            .collect()
+    }
+
+    /// The assembled calls, leaving none pending
+    pub(crate) fn finish(&mut self) -> Vec<crate::api::ContentBlock> {
+        std::mem::take(&mut self.calls)
+            .into_values()
+            .map(|(id, name, arguments)| {
+                crate::api::ContentBlock::ToolCall(crate::api::ToolCall {
+                    id,
+                    name,
+                    arguments: serde_json::from_str(&arguments).unwrap_or(serde_json::Value::Null),
+                    extra: serde_json::Value::Null,
+                })
+            })
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -350,6 +525,8 @@ pub struct ChatCompletionChunk {
     pub created: u64,
     pub model: String,
     pub choices: Vec<ChatCompletionChunkChoice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -393,12 +570,143 @@ impl From<Model> for crate::ModelDefinition {
             capabilities.push(crate::ModelCapability::Text);
         }
 
+        if capabilities.contains(&crate::ModelCapability::Text) && supports_tools(&model.id) {
+            capabilities.push(crate::ModelCapability::Tools);
+        }
+
+        let (context_window, max_output_tokens) = match model_limits(&model.id) {
+            Some((context_window, max_output_tokens)) => {
+                (Some(context_window), Some(max_output_tokens))
+            }
+            None => (None, None),
+        };
         crate::ModelDefinition::new(model.id, capabilities)
+            .with_limits(context_window, max_output_tokens)
     }
 }
 
+/// Context window and output limit by model ID prefix; more specific
+/// prefixes come first. The models endpoint doesn't report limits.
+const MODEL_LIMITS: &[(&str, u32, u32)] = &[
+    ("gpt-5", 400_000, 128_000),
+    ("gpt-4.1", 1_047_576, 32_768),
+    ("gpt-4o", 128_000, 16_384),
+    ("gpt-4-turbo", 128_000, 4_096),
+    ("gpt-4", 8_192, 8_192),
+    ("gpt-3.5-turbo", 16_385, 4_096),
+    ("o1-mini", 128_000, 65_536),
+    ("o1", 200_000, 100_000),
+    ("o3", 200_000, 100_000),
+    ("o4-mini", 200_000, 100_000),
+];
+
+/// Known (context window, max output tokens) for a model
+pub(crate) fn model_limits(model_id: &str) -> Option<(u32, u32)> {
+    MODEL_LIMITS
+        .iter()
+        .find(|(prefix, _, _)| model_id.starts_with(prefix))
+        .map(|&(_, context_window, max_output_tokens)| (context_window, max_output_tokens))
+}
+
+/// Whether a model accepts `tools`. The model list has no capability
+/// metadata, so only models known to lack function calling are excluded;
+/// this also keeps tools enabled for OpenAI-compatible servers.
+pub(crate) fn supports_tools(model_id: &str) -> bool {
+    const WITHOUT_TOOLS: &[&str] = &[
+        "babbage", "dall-e", "davinci", "embedding", "image", "instruct", "moderation",
+        "search", "transcribe", "tts", "whisper",
+    ];
+    let id = model_id.to_ascii_lowercase();
+    !WITHOUT_TOOLS.iter().any(|marker| id.contains(marker))
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ListModelsResponse {
     pub object: String,
     pub data: Vec<Model>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_is_sent_only_when_set() {
+        let message = crate::ChatMessage::user(crate::ChatPayload::text("Hello"));
+        let request = ChatRequest::new([&message]);
+        let json = serde_json::to_value(ChatCompletionRequest::from_request("m".to_string(), &request, false)).unwrap();
+        assert!(json.get("seed").is_none());
+
+        let request = request.with_params(crate::GenerationParams { seed: Some(42), ..Default::default() });
+        let json = serde_json::to_value(ChatCompletionRequest::from_request("m".to_string(), &request, false)).unwrap();
+        assert_eq!(json["seed"], 42);
+    }
+
+    #[test]
+    fn test_streamed_tool_calls_are_assembled() {
+        let deltas: Vec<ChatCompletionChunkDelta> = [
+            r#"{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"search_web","arguments":""}}]}"#,
+            r#"{"tool_calls":[{"index":0,"function":{"arguments":"{\"query\":"}}]}"#,
+            r#"{"tool_calls":[{"index":0,"function":{"arguments":"\"rust\"}"}}]}"#,
+            r#"{"tool_calls":[{"index":1,"id":"call_2","type":"function","function":{"name":"get_time","arguments":"{}"}}]}"#,
+        ]
+        .iter()
+        .map(|json| serde_json::from_str(json).unwrap())
+        .collect();
+
+        let mut calls = StreamedToolCalls::default();
+        let fragments: Vec<crate::ToolCallDelta> =
+            deltas.iter().flat_map(|delta| calls.push(delta.tool_calls.as_deref().unwrap())).collect();
+        assert_eq!(fragments.len(), 4);
+        assert_eq!(fragments[0].name, "search_web");
+        assert_eq!(fragments[0].args_fragment, "");
+        assert_eq!(fragments[2].id, "call_1");
+        assert_eq!(fragments[2].args_fragment, "\"rust\"}");
+
+        let assembled = calls.finish();
+        let tool_calls: Vec<&crate::ToolCall> = assembled
+            .iter()
+            .filter_map(|block| match block {
+                crate::ContentBlock::ToolCall(call) => Some(call),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(tool_calls.len(), 2);
+        assert_eq!(tool_calls[0].id, "call_1");
+        assert_eq!(tool_calls[0].arguments, serde_json::json!({"query": "rust"}));
+        assert_eq!(tool_calls[1].name, "get_time");
+        assert!(calls.finish().is_empty());
+    }
+
+    #[test]
+    fn test_response_finish_reason_is_mapped() {
+        let response = |finish_reason: &str| -> ChatMessage {
+            let json = format!(
+                r#"{{"id":"chatcmpl-1","object":"chat.completion","created":0,"model":"gpt","choices":[{{"index":0,"message":{{"role":"assistant","content":"Once upon"}},"finish_reason":"{}"}}]}}"#,
+                finish_reason
+            );
+            serde_json::from_str::<ChatCompletionResponse>(&json).unwrap().into()
+        };
+        assert!(response("length").is_truncated());
+        assert_eq!(response("stop").finish_reason, Some(crate::FinishReason::Stop));
+        assert_eq!(response("tool_calls").finish_reason, Some(crate::FinishReason::ToolUse));
+        assert_eq!(
+            response("content_filter").finish_reason,
+            Some(crate::FinishReason::ContentFilter { category: None })
+        );
+    }
+
+    #[test]
+    fn test_refusal_becomes_filtered_text() {
+        let json = r#"{"id":"chatcmpl-1","object":"chat.completion","created":0,"model":"gpt","choices":[{"index":0,"message":{"role":"assistant","content":null,"refusal":"I can't help with that."},"finish_reason":"stop"}]}"#;
+        let message: ChatMessage = serde_json::from_str::<ChatCompletionResponse>(json).unwrap().into();
+        assert_eq!(message.get_text(), "I can't help with that.");
+        assert_eq!(message.finish_reason, Some(crate::FinishReason::ContentFilter { category: None }));
+        // The refusal explains itself, so it isn't reported as blocked
+        assert!(message.blocked().is_none());
+
+        let json = r#"{"id":"chatcmpl-1","object":"chat.completion","created":0,"model":"gpt","choices":[{"index":0,"message":{"role":"assistant","content":null},"finish_reason":"content_filter"}]}"#;
+        let message: ChatMessage = serde_json::from_str::<ChatCompletionResponse>(json).unwrap().into();
+        assert_eq!(message.blocked(), Some(crate::ContentFiltered { category: None }));
+    }
+}
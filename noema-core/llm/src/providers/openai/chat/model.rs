@@ -1,12 +1,14 @@
-use crate::api::{ChatChunk, ChatMessage, ChatRequest, Role};
+use crate::api::{ChatChunk, ChatMessage, ChatRequest, Role, TokenUsage};
 use crate::client::Client;
 use crate::traffic_log;
 use crate::ChatModel;
 use crate::ChatStream;
 use async_trait::async_trait;
-use futures::StreamExt;
+use futures::{stream, StreamExt};
 
-use super::api::{ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse};
+use super::api::{
+    finish_reason, ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse, StreamedToolCalls,
+};
 
 #[derive(Clone)]
 pub struct OpenAIChatModel {
@@ -18,7 +20,7 @@ pub struct OpenAIChatModel {
 impl OpenAIChatModel {
     pub fn new(client: Client, base_url: String, model_name: String) -> Self {
         OpenAIChatModel {
-            client,
+            client: client.for_model(&model_name),
             base_url,
             model_name,
         }
@@ -39,14 +41,20 @@ impl ChatModel for OpenAIChatModel {
         &self.model_name
     }
 
+    fn supports_tools(&self) -> bool {
+        super::api::supports_tools(&self.model_name)
+    }
+
+    fn context_window(&self) -> Option<u32> {
+        super::api::model_limits(&self.model_name).map(|(context_window, _)| context_window)
+    }
+
     async fn chat(&self, request: &ChatRequest) -> anyhow::Result<ChatMessage> {
         let openai_request =
             ChatCompletionRequest::from_request(self.model_name.clone(), request, false);
-        traffic_log::log_request(&self.model_name, &openai_request);
 
         match self.client.post(self.chat_url(), &openai_request).await {
             Ok(response) => {
-                traffic_log::log_response(&self.model_name, &response);
                 let response: ChatCompletionResponse = response;
                 Ok(response.into())
             }
@@ -60,7 +68,6 @@ impl ChatModel for OpenAIChatModel {
     async fn stream_chat(&self, request: &ChatRequest) -> anyhow::Result<ChatStream> {
         let openai_request =
             ChatCompletionRequest::from_request(self.model_name.clone(), request, true);
-        traffic_log::log_stream_start(&self.model_name, &openai_request);
 
         let stream = self
             .client
@@ -80,12 +87,49 @@ impl ChatModel for OpenAIChatModel {
             })
             .await?;
 
-        let chat_stream = stream.map(|chunk| {
-            let choice = &chunk.choices[0];
+        // Tool calls arrive as argument fragments; each is forwarded as a
+        // delta and the assembled calls are emitted when the choice finishes
+        let mut tool_calls = StreamedToolCalls::default();
+        let mut refused = false;
+        let chat_stream = stream.flat_map(move |chunk| {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => return stream::iter(vec![Err(e)]),
+            };
+            let usage = chunk.usage.as_ref().map(TokenUsage::from);
+            // The usage-only chunk at the end of the stream has no choices
+            let Some(choice) = chunk.choices.first() else {
+                return stream::iter(vec![Ok(
+                    ChatChunk::new(Role::Assistant, crate::ChatPayload::default()).with_usage(usage),
+                )]);
+            };
             let role = choice.delta.role.unwrap_or(Role::Assistant);
-            let content = choice.delta.content.clone().unwrap_or_default();
+            let mut content = choice.delta.content.clone().unwrap_or_default();
+            // Refusals stream as text in their own field
+            if let Some(refusal) = &choice.delta.refusal {
+                refused = true;
+                content.push_str(refusal);
+            }
 
-            ChatChunk::new(role, crate::ChatPayload::text(content))
+            let mut chunks = vec![ChatChunk::new(role, crate::ChatPayload::text(content)).with_usage(usage)];
+            if let Some(fragments) = &choice.delta.tool_calls {
+                chunks.extend(tool_calls.push(fragments).into_iter().map(ChatChunk::tool_call_delta));
+            }
+            if let Some(reason) = choice.finish_reason.as_deref() {
+                let calls = tool_calls.finish();
+                if !calls.is_empty() {
+                    chunks.push(ChatChunk::assistant(crate::ChatPayload::new(calls)));
+                }
+                chunks.push(
+                    ChatChunk::assistant(crate::ChatPayload::default())
+                        .with_finish_reason(Some(if refused {
+                            crate::FinishReason::ContentFilter { category: None }
+                        } else {
+                            finish_reason(reason)
+                        })),
+                );
+            }
+            stream::iter(chunks.into_iter().map(Ok).collect::<Vec<_>>())
         });
 
         Ok(Box::pin(chat_stream))
@@ -0,0 +1,99 @@
+use crate::client::Client;
+use crate::traffic_log;
+use crate::{EmbeddingModel, Embeddings};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Inputs per request accepted by the embeddings endpoint
+const MAX_BATCH_SIZE: usize = 2048;
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+impl EmbeddingResponse {
+    /// The vectors in input order; the endpoint tags each with its index
+    /// rather than promising to return them in order
+    fn into_vectors(mut self) -> Vec<Vec<f32>> {
+        self.data.sort_by_key(|data| data.index);
+        self.data.into_iter().map(|data| data.embedding).collect()
+    }
+}
+
+/// Embedding model served by `/v1/embeddings`, e.g. `text-embedding-3-small`
+pub struct OpenAIEmbeddingModel {
+    client: Client,
+    base_url: String,
+    model_name: String,
+}
+
+impl OpenAIEmbeddingModel {
+    pub fn new(client: Client, base_url: String, model_name: String) -> Self {
+        OpenAIEmbeddingModel {
+            client: client.for_model(&model_name),
+            base_url,
+            model_name,
+        }
+    }
+
+    async fn embed_batch(&self, inputs: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let request = EmbeddingRequest {
+            model: &self.model_name,
+            input: inputs,
+        };
+        let url = format!("{}/embeddings", self.base_url);
+        let response: EmbeddingResponse =
+            self.client.post(url, &request).await.inspect_err(|e| {
+                traffic_log::log_error(&self.model_name, &e.to_string());
+            })?;
+        Ok(response.into_vectors())
+    }
+}
+
+#[async_trait]
+impl EmbeddingModel for OpenAIEmbeddingModel {
+    fn id(&self) -> &str {
+        &self.model_name
+    }
+
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Embeddings> {
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(MAX_BATCH_SIZE) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        Embeddings::from_vectors(inputs.len(), vectors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_is_ordered_by_index() {
+        let response: EmbeddingResponse = serde_json::from_str(
+            r#"{"object":"list","model":"text-embedding-3-small","data":[
+                {"object":"embedding","index":1,"embedding":[0.3,0.4]},
+                {"object":"embedding","index":0,"embedding":[0.1,0.2]}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response.into_vectors(),
+            vec![vec![0.1, 0.2], vec![0.3, 0.4]]
+        );
+    }
+}
@@ -1,5 +1,7 @@
 pub mod chat;
+pub mod embedding;
 pub mod provider;
 
 pub use chat::OpenAIChatModel;
+pub use embedding::OpenAIEmbeddingModel;
 pub use provider::OpenAIProvider;
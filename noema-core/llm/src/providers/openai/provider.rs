@@ -1,11 +1,14 @@
-use crate::client::Client;
-use crate::{ChatModel, ModelProvider};
+use crate::client::{Client, Timeouts};
+use crate::replay::TrafficReplay;
+use crate::traffic_log::TrafficLogger;
+use crate::{ChatModel, EmbeddingModel, ModelProvider};
 use async_trait::async_trait;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use std::sync::Arc;
 
 use super::chat::api::ListModelsResponse;
 use super::chat::OpenAIChatModel;
+use super::embedding::OpenAIEmbeddingModel;
 
 #[derive(Clone)]
 pub struct OpenAIProvider {
@@ -45,6 +48,24 @@ impl OpenAIProvider {
     fn models_url(&self) -> String {
         format!("{}/models", self.base_url)
     }
+
+    /// Record the raw traffic of every model created by this provider
+    pub fn with_traffic_logger(mut self, logger: Arc<dyn TrafficLogger>) -> Self {
+        self.client.set_traffic_logger(logger);
+        self
+    }
+
+    /// Bound how long every model created by this provider may wait on a request
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.client.set_timeouts(timeouts);
+        self
+    }
+
+    /// Answer every model's requests from recorded traffic instead of the network
+    pub fn with_replay(mut self, replay: Arc<TrafficReplay>) -> Self {
+        self.client.set_replay(replay);
+        self
+    }
 }
 
 #[async_trait]
@@ -61,4 +82,15 @@ impl ModelProvider for OpenAIProvider {
             model_name.to_string(),
         )))
     }
+
+    fn create_embedding_model(
+        &self,
+        model_name: &str,
+    ) -> Option<Arc<dyn EmbeddingModel + Send + Sync>> {
+        Some(Arc::new(OpenAIEmbeddingModel::new(
+            self.client.clone(),
+            self.base_url.clone(),
+            model_name.to_string(),
+        )))
+    }
 }
@@ -0,0 +1,3 @@
+pub mod provider;
+
+pub use provider::OpenAICompatibleProvider;
@@ -0,0 +1,132 @@
+use crate::client::{Client, Timeouts};
+use crate::replay::TrafficReplay;
+use crate::traffic_log::TrafficLogger;
+use crate::providers::openai::chat::api::ListModelsResponse;
+use crate::providers::openai::OpenAIChatModel;
+use crate::{ChatModel, ModelCapability, ModelDefinition, ModelProvider};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use std::sync::Arc;
+
+/// Provider for any endpoint speaking the OpenAI chat completions API
+/// (DeepSeek, Together, vLLM, ...).
+///
+/// Requests are serialized exactly as for OpenAI. Unlike [`OpenAIProvider`],
+/// the base URL is used as-is, so it must include the version path
+/// (e.g. "https://api.deepseek.com/v1").
+///
+/// [`OpenAIProvider`]: crate::providers::OpenAIProvider
+#[derive(Clone)]
+pub struct OpenAICompatibleProvider {
+    client: Client,
+    base_url: String,
+    models: Vec<String>,
+}
+
+impl OpenAICompatibleProvider {
+    /// Create a provider for `base_url`.
+    ///
+    /// `api_key` is sent as a bearer token when present. `models` is offered
+    /// when the endpoint doesn't implement `/models`.
+    pub fn new(base_url: &str, api_key: Option<&str>, models: Vec<String>) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if let Some(api_key) = api_key {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", api_key))
+                    .expect("Invalid API key format"),
+            );
+        }
+
+        OpenAICompatibleProvider {
+            client: Client::with_headers(headers),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            models,
+        }
+    }
+
+    fn models_url(&self) -> String {
+        format!("{}/models", self.base_url)
+    }
+
+    fn static_models(&self) -> Vec<ModelDefinition> {
+        self.models
+            .iter()
+            .map(|id| {
+                ModelDefinition::new(
+                    id,
+                    vec![ModelCapability::Text, ModelCapability::Tools, ModelCapability::Streaming],
+                )
+            })
+            .collect()
+    }
+
+    /// Record the raw traffic of every model created by this provider
+    pub fn with_traffic_logger(mut self, logger: Arc<dyn TrafficLogger>) -> Self {
+        self.client.set_traffic_logger(logger);
+        self
+    }
+
+    /// Bound how long every model created by this provider may wait on a request
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.client.set_timeouts(timeouts);
+        self
+    }
+
+    /// Answer every model's requests from recorded traffic instead of the network
+    pub fn with_replay(mut self, replay: Arc<TrafficReplay>) -> Self {
+        self.client.set_replay(replay);
+        self
+    }
+}
+
+#[async_trait]
+impl ModelProvider for OpenAICompatibleProvider {
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelDefinition>> {
+        match self.client.get::<_, ListModelsResponse>(self.models_url()).await {
+            Ok(response) => Ok(response.data.into_iter().map(|m| m.into()).collect()),
+            // Many compatible servers don't implement /models
+            Err(e) if !self.models.is_empty() => {
+                tracing::debug!("{}: falling back to configured models: {}", self.base_url, e);
+                Ok(self.static_models())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn create_chat_model(&self, model_name: &str) -> Option<Arc<dyn ChatModel + Send + Sync>> {
+        Some(Arc::new(OpenAIChatModel::new(
+            self.client.clone(),
+            self.base_url.clone(),
+            model_name.to_string(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_models_falls_back_to_static_list() {
+        // Nothing listens on port 1, so the /models request fails
+        let provider = OpenAICompatibleProvider::new(
+            "http://127.0.0.1:1/v1/",
+            None,
+            vec!["deepseek-chat".to_string()],
+        );
+        let models = provider.list_models().await.unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].id, "deepseek-chat");
+
+        let model = provider.create_chat_model("deepseek-chat").unwrap();
+        assert_eq!(model.id(), "deepseek-chat");
+    }
+
+    #[tokio::test]
+    async fn test_list_models_errors_without_static_list() {
+        let provider = OpenAICompatibleProvider::new("http://127.0.0.1:1/v1", None, Vec::new());
+        assert!(provider.list_models().await.is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! Client-side rate limiting so bursts of requests are delayed instead of
+//! running into provider 429s
+
+use crate::{ChatMessage, ChatModel, ChatRequest, ChatStream, EmbeddingModel, Embeddings};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Token bucket allowing `requests_per_minute` on average, with up to
+/// `burst` requests back to back after a quiet period.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests_per_minute: u32,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Limit without bursts: requests are spaced evenly over the minute
+    pub fn per_minute(requests_per_minute: u32) -> Self {
+        Self {
+            requests_per_minute,
+            burst: 1,
+        }
+    }
+
+    /// Time it takes to earn back one request
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(60) / self.requests_per_minute.max(1)
+    }
+}
+
+struct BucketState {
+    /// Requests that may start right now; negative while callers are queued
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Shared token bucket. Callers reserve a token up front and then sleep
+/// until it is earned, so waiting callers are served in arrival order.
+pub struct RateLimiter {
+    limit: RateLimit,
+    state: Mutex<BucketState>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        let tokens = limit.burst.max(1) as f64;
+        Self {
+            limit,
+            state: Mutex::new(BucketState {
+                tokens,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn limit(&self) -> &RateLimit {
+        &self.limit
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take a token, returning how long to wait until it is available
+    fn reserve(&self) -> Duration {
+        let interval = self.limit.interval().as_secs_f64();
+        let mut state = self.state.lock().unwrap();
+
+        let now = Instant::now();
+        let earned = now.duration_since(state.last_refill).as_secs_f64() / interval;
+        state.tokens = (state.tokens + earned).min(self.limit.burst.max(1) as f64);
+        state.last_refill = now;
+
+        state.tokens -= 1.0;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens * interval)
+        }
+    }
+}
+
+/// Limiters by provider name, so every model of a provider draws from the
+/// same bucket
+static SHARED_LIMITERS: LazyLock<Mutex<HashMap<String, Arc<RateLimiter>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Get the limiter shared by all models of `provider`, replacing it if the
+/// configured limit changed
+pub fn shared_limiter(provider: &str, limit: RateLimit) -> Arc<RateLimiter> {
+    let mut limiters = SHARED_LIMITERS.lock().unwrap();
+    match limiters.get(provider) {
+        Some(limiter) if limiter.limit == limit => Arc::clone(limiter),
+        _ => {
+            let limiter = Arc::new(RateLimiter::new(limit));
+            limiters.insert(provider.to_string(), Arc::clone(&limiter));
+            limiter
+        }
+    }
+}
+
+/// ChatModel wrapper that delays calls to stay within a rate limit.
+///
+/// Both `chat` and `stream_chat` take a token before calling the inner
+/// model; a stream counts as one request.
+pub struct RateLimitedChatModel {
+    inner: Arc<dyn ChatModel + Send + Sync>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitedChatModel {
+    pub fn new(inner: Arc<dyn ChatModel + Send + Sync>, limiter: Arc<RateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+
+    /// Wrap a model, returning it in the shared form used throughout the app
+    pub fn wrap(
+        inner: Arc<dyn ChatModel + Send + Sync>,
+        limiter: Arc<RateLimiter>,
+    ) -> Arc<dyn ChatModel + Send + Sync> {
+        Arc::new(Self::new(inner, limiter))
+    }
+}
+
+#[async_trait]
+impl ChatModel for RateLimitedChatModel {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.inner.supports_tools()
+    }
+
+    fn context_window(&self) -> Option<u32> {
+        self.inner.context_window()
+    }
+
+    async fn chat(&self, messages: &ChatRequest) -> anyhow::Result<ChatMessage> {
+        self.limiter.acquire().await;
+        self.inner.chat(messages).await
+    }
+
+    async fn stream_chat(&self, messages: &ChatRequest) -> anyhow::Result<ChatStream> {
+        self.limiter.acquire().await;
+        self.inner.stream_chat(messages).await
+    }
+}
+
+/// EmbeddingModel wrapper that delays calls to stay within a rate limit
+pub struct RateLimitedEmbeddingModel {
+    inner: Arc<dyn EmbeddingModel + Send + Sync>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitedEmbeddingModel {
+    pub fn new(inner: Arc<dyn EmbeddingModel + Send + Sync>, limiter: Arc<RateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+
+    /// Wrap a model, returning it in the shared form used throughout the app
+    pub fn wrap(
+        inner: Arc<dyn EmbeddingModel + Send + Sync>,
+        limiter: Arc<RateLimiter>,
+    ) -> Arc<dyn EmbeddingModel + Send + Sync> {
+        Arc::new(Self::new(inner, limiter))
+    }
+}
+
+#[async_trait]
+impl EmbeddingModel for RateLimitedEmbeddingModel {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Embeddings> {
+        self.limiter.acquire().await;
+        self.inner.embed(inputs).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockChatModel;
+    use crate::ChatPayload;
+
+    fn request() -> ChatRequest {
+        let messages = [ChatMessage::user(ChatPayload::text("hi"))];
+        ChatRequest::new(messages.iter())
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_spaced() {
+        // 1200 per minute = one request every 50ms
+        let limiter = Arc::new(RateLimiter::new(RateLimit::per_minute(1200)));
+        let model = RateLimitedChatModel::new(Arc::new(MockChatModel::echo_text("echo", "ok")), limiter);
+
+        let start = Instant::now();
+        let mut finished = Vec::new();
+        for i in 0..4 {
+            if i % 2 == 0 {
+                model.chat(&request()).await.unwrap();
+            } else {
+                let _stream = model.stream_chat(&request()).await.unwrap();
+            }
+            finished.push(start.elapsed());
+        }
+
+        assert!(finished[0] < Duration::from_millis(50));
+        for pair in finished.windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::from_millis(45), "{:?}", finished);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_burst_passes_without_delay() {
+        let limiter = RateLimiter::new(RateLimit {
+            requests_per_minute: 60,
+            burst: 3,
+        });
+        for _ in 0..3 {
+            assert_eq!(limiter.reserve(), Duration::ZERO);
+        }
+        // The fourth waits for a token to be earned, the fifth queues behind it
+        let fourth = limiter.reserve();
+        assert!(fourth > Duration::from_millis(900) && fourth <= Duration::from_secs(1));
+        assert!(limiter.reserve() > Duration::from_millis(1900));
+    }
+
+    #[test]
+    fn test_shared_limiter_per_provider() {
+        let a = shared_limiter("test-provider", RateLimit::per_minute(30));
+        let b = shared_limiter("test-provider", RateLimit::per_minute(30));
+        assert!(Arc::ptr_eq(&a, &b));
+
+        let changed = shared_limiter("test-provider", RateLimit::per_minute(10));
+        assert!(!Arc::ptr_eq(&a, &changed));
+    }
+}
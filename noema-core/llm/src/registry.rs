@@ -5,12 +5,45 @@
 //!
 //! API Key Priority:
 //! 1. Settings file (encrypted API keys in settings.toml)
-//! 2. Environment variables (CLAUDE_API_KEY, OPENAI_API_KEY, etc.)
+//! 2. The variable named by `api_key_env` in providers.toml
+//! 3. Environment variables (CLAUDE_API_KEY, OPENAI_API_KEY, etc.)
+//!
+//! Base URLs come from `*_BASE_URL` variables, then providers.toml (see
+//! [`ProviderUrls`]), then each provider's default.
+//!
+//! Besides the built-in providers, settings.toml may define any number of
+//! OpenAI-compatible endpoints under `[compatible_providers.<name>]`; their
+//! models are addressed as "<name>/<model>" like any other.
+//!
+//! Providers listed under `[rate_limits.<name>]` get their models wrapped in a
+//! [`RateLimitedChatModel`] sharing one bucket per provider; embedding models
+//! draw from the same bucket.
+//!
+//! Models that don't know their context window (Gemini, Mistral, Ollama and
+//! compatible endpoints) take the one their provider reported when listing
+//! models, from the [`ModelCatalog`] cache.
+//!
+//! With `ollama_auto_pull = true`, Ollama models the server doesn't have are
+//! pulled on first use; progress goes to the handler installed with
+//! [`set_pull_progress_handler`](crate::providers::set_pull_progress_handler).
+//!
+//! `request_timeout_secs` and `stream_idle_timeout_secs` override the
+//! client's [`Timeouts`]; 0 turns a limit off.
 
-use crate::providers::GeneralModelProvider;
-use crate::{ChatModel, ModelDefinition, ModelProvider};
+use crate::catalog::ModelCatalog;
+use crate::context_window::ContextWindowChatModel;
+use crate::provider_urls::ProviderUrls;
+use crate::providers::ollama::pull_progress_handler;
+use crate::providers::{GeneralModelProvider, OpenAICompatibleProvider};
+use crate::rate_limit::{
+    shared_limiter, RateLimit, RateLimitedChatModel, RateLimitedEmbeddingModel, RateLimiter,
+};
+use crate::retry::{RetryPolicy, RetryingEmbeddingModel};
+use crate::{ChatModel, EmbeddingModel, ModelDefinition, ModelProvider, Timeouts};
 use config::Settings;
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// A model identifier in the format "provider/model-name"
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -68,21 +101,181 @@ pub fn get_provider_info(name: &str) -> Option<&'static ProviderInfo> {
     list_providers().iter().find(|p| p.name == name)
 }
 
+/// Names of the user-defined OpenAI-compatible providers, sorted
+pub fn list_compatible_providers(settings: &Settings) -> Vec<String> {
+    let mut names: Vec<String> = settings
+        .compatible_providers
+        .keys()
+        .filter(|name| get_provider_info(name).is_none())
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+/// The request limits from settings, falling back to the defaults
+fn timeouts(settings: &Settings) -> Timeouts {
+    let limit = |secs: Option<u64>, default: Option<Duration>| match secs {
+        None => default,
+        Some(0) => None,
+        Some(secs) => Some(Duration::from_secs(secs)),
+    };
+    let defaults = Timeouts::default();
+    Timeouts {
+        request_timeout: limit(settings.request_timeout_secs, defaults.request_timeout),
+        stream_idle_timeout: limit(settings.stream_idle_timeout_secs, defaults.stream_idle_timeout),
+    }
+}
+
+/// Names of every built-in provider, then the compatible providers
+pub(crate) fn provider_names(settings: &Settings) -> Vec<String> {
+    list_providers()
+        .iter()
+        .map(|info| info.name.to_string())
+        .chain(list_compatible_providers(settings))
+        .collect()
+}
+
+/// A digest of the configuration `resolve_provider` uses for `name`: API
+/// key, base URL and compatible-provider entry. It changes when any of them
+/// does; the key itself can't be recovered from it.
+pub(crate) fn provider_fingerprint(name: &str, settings: &Settings, urls: &ProviderUrls) -> String {
+    let env_key = get_provider_info(name)
+        .and_then(|info| info.api_key_env)
+        .or_else(|| settings.get_compatible_provider(name)?.api_key_env.as_deref())
+        .and_then(|env| std::env::var(env).ok());
+    // Serialized together so no two configurations feed the same bytes
+    let configuration = serde_json::json!([
+        settings.get_api_key(name).or_else(|| urls.api_key(name)).or(env_key),
+        urls.base_url(name),
+        settings.get_compatible_provider(name).map(|config| format!("{:?}", config)),
+    ]);
+    format!("{:x}", Sha256::digest(configuration.to_string()))
+}
+
+/// Resolve a provider by name. Built-in providers take precedence over
+/// compatible providers of the same name.
+pub(crate) fn resolve_provider(
+    name: &str,
+    settings: &Settings,
+    urls: &ProviderUrls,
+) -> anyhow::Result<Box<dyn ModelProvider + Send + Sync>> {
+    let api_key = settings.get_api_key(name);
+
+    if get_provider_info(name).is_none() {
+        if let Some(config) = settings.get_compatible_provider(name) {
+            // Settings API key takes priority, then the configured env var
+            let api_key = api_key.or_else(|| {
+                config.api_key_env.as_ref().and_then(|env| std::env::var(env).ok())
+            });
+            return Ok(Box::new(
+                OpenAICompatibleProvider::new(&config.base_url, api_key.as_deref(), config.models.clone())
+                    .with_timeouts(timeouts(settings)),
+            ));
+        }
+    }
+
+    let api_key = api_key.or_else(|| urls.api_key(name));
+    let provider =
+        GeneralModelProvider::from_name_with_config(name, api_key.as_deref(), urls.base_url(name))?
+            .with_timeouts(timeouts(settings));
+    Ok(Box::new(match provider {
+        GeneralModelProvider::Ollama(ollama) if settings.ollama_auto_pull() => {
+            let ollama = ollama.with_auto_pull(true);
+            GeneralModelProvider::Ollama(match pull_progress_handler() {
+                Some(progress) => ollama.with_pull_progress(progress),
+                None => ollama,
+            })
+        }
+        provider => provider,
+    }))
+}
+
 /// Create a chat model from a model ID string like "claude/claude-sonnet-4-5-20250929"
 ///
 /// API keys are loaded with settings taking priority over environment variables.
+/// A model that doesn't know its context window takes the one in the
+/// provider's cached model list, if any.
 pub fn create_model(model_id: &str) -> anyhow::Result<Arc<dyn ChatModel + Send + Sync>> {
+    let settings = Settings::load();
+    let urls = ProviderUrls::from_config_file();
+    let model = create_model_with_settings(model_id, &settings, &urls)?;
+    Ok(with_catalog_context_window(model, model_id, &ModelCatalog::open_default(), &settings, &urls))
+}
+
+/// Give `model` the context window its cached definition reports when it
+/// doesn't know its own
+fn with_catalog_context_window(
+    model: Arc<dyn ChatModel + Send + Sync>,
+    model_id: &str,
+    catalog: &ModelCatalog,
+    settings: &Settings,
+    urls: &ProviderUrls,
+) -> Arc<dyn ChatModel + Send + Sync> {
+    if model.context_window().is_some() {
+        return model;
+    }
+    let Some(id) = ModelId::parse(model_id) else {
+        return model;
+    };
+    let fingerprint = provider_fingerprint(&id.provider, settings, urls);
+    let context_window = catalog
+        .cached_definition(&id, &fingerprint)
+        .and_then(|definition| definition.context_window);
+    ContextWindowChatModel::wrap(model, context_window)
+}
+
+fn create_model_with_settings(
+    model_id: &str,
+    settings: &Settings,
+    urls: &ProviderUrls,
+) -> anyhow::Result<Arc<dyn ChatModel + Send + Sync>> {
+    let id = ModelId::parse(model_id)
+        .ok_or_else(|| anyhow::anyhow!("Invalid model ID '{}': expected 'provider/model'", model_id))?;
+
+    let provider = resolve_provider(&id.provider, settings, urls)?;
+    let model = provider
+        .create_chat_model(&id.model)
+        .ok_or_else(|| anyhow::anyhow!("Failed to create model '{}' from provider '{}'", id.model, id.provider))?;
+
+    Ok(match provider_limiter(&id.provider, settings) {
+        Some(limiter) => RateLimitedChatModel::wrap(model, limiter),
+        None => model,
+    })
+}
+
+/// The bucket shared by every model of a rate-limited provider
+fn provider_limiter(provider: &str, settings: &Settings) -> Option<Arc<RateLimiter>> {
+    let limit = settings.get_rate_limit(provider)?;
+    let limit = RateLimit {
+        requests_per_minute: limit.requests_per_minute,
+        burst: limit.burst.unwrap_or(1),
+    };
+    Some(shared_limiter(provider, limit))
+}
+
+/// Create an embedding model from a model ID string like "openai/text-embedding-3-small"
+///
+/// API keys are loaded with settings taking priority over environment variables.
+/// The model shares its provider's rate limit with the chat models and
+/// retries transient failures with the default [`RetryPolicy`].
+pub fn create_embedding_model(
+    model_id: &str,
+) -> anyhow::Result<Arc<dyn EmbeddingModel + Send + Sync>> {
     let id = ModelId::parse(model_id)
         .ok_or_else(|| anyhow::anyhow!("Invalid model ID '{}': expected 'provider/model'", model_id))?;
 
-    // Load API key from settings (takes priority over env vars)
     let settings = Settings::load();
-    let api_key = settings.get_api_key(&id.provider);
+    let provider = resolve_provider(&id.provider, &settings, &ProviderUrls::from_config_file())?;
+    let model = provider
+        .create_embedding_model(&id.model)
+        .ok_or_else(|| anyhow::anyhow!("Provider '{}' does not offer embedding models", id.provider))?;
 
-    let provider = GeneralModelProvider::from_name_with_key(&id.provider, api_key.as_deref())?;
-    provider
-        .create_chat_model(&id.model)
-        .ok_or_else(|| anyhow::anyhow!("Failed to create model '{}' from provider '{}'", id.model, id.provider))
+    let model = match provider_limiter(&id.provider, &settings) {
+        Some(limiter) => RateLimitedEmbeddingModel::wrap(model, limiter),
+        None => model,
+    };
+    Ok(RetryingEmbeddingModel::wrap(model, RetryPolicy::default()))
 }
 
 /// Model info with its full ID
@@ -98,16 +291,16 @@ pub struct ModelInfo {
 pub async fn list_all_models() -> Vec<(String, anyhow::Result<Vec<ModelInfo>>)> {
     let mut results = Vec::new();
     let settings = Settings::load();
+    let urls = ProviderUrls::from_config_file();
 
-    for info in list_providers() {
-        let api_key = settings.get_api_key(info.name);
-        let provider_result = GeneralModelProvider::from_name_with_key(info.name, api_key.as_deref());
+    for name in provider_names(&settings) {
+        let provider_result = resolve_provider(&name, &settings, &urls);
         let models_result = match provider_result {
             Ok(provider) => match provider.list_models().await {
                 Ok(models) => Ok(models
                     .into_iter()
                     .map(|def| ModelInfo {
-                        id: ModelId::new(info.name, &def.id),
+                        id: ModelId::new(&name, &def.id),
                         definition: def,
                     })
                     .collect()),
@@ -115,7 +308,7 @@ pub async fn list_all_models() -> Vec<(String, anyhow::Result<Vec<ModelInfo>>)>
             },
             Err(e) => Err(e),
         };
-        results.push((info.name.to_string(), models_result));
+        results.push((name, models_result));
     }
 
     results
@@ -125,9 +318,7 @@ pub async fn list_all_models() -> Vec<(String, anyhow::Result<Vec<ModelInfo>>)>
 ///
 /// API keys are loaded with settings taking priority over environment variables.
 pub async fn list_models(provider_name: &str) -> anyhow::Result<Vec<ModelInfo>> {
-    let settings = Settings::load();
-    let api_key = settings.get_api_key(provider_name);
-    let provider = GeneralModelProvider::from_name_with_key(provider_name, api_key.as_deref())?;
+    let provider = resolve_provider(provider_name, &Settings::load(), &ProviderUrls::from_config_file())?;
     let models = provider.list_models().await?;
 
     Ok(models
@@ -174,4 +365,108 @@ mod tests {
         assert!(providers.iter().any(|p| p.name == "ollama"));
         assert!(providers.iter().any(|p| p.name == "mistral"));
     }
+
+    #[test]
+    fn test_compatible_provider_resolution() {
+        let mut settings = Settings::default();
+        for name in ["together", "openai"] {
+            settings.compatible_providers.insert(
+                name.to_string(),
+                config::CompatibleProvider {
+                    base_url: "http://localhost:8000/v1".to_string(),
+                    api_key_env: None,
+                    models: vec!["llama-3".to_string()],
+                },
+            );
+        }
+
+        // Built-in names can't be shadowed
+        assert_eq!(list_compatible_providers(&settings), vec!["together"]);
+
+        let urls = ProviderUrls::default();
+        let model = create_model_with_settings("together/llama-3", &settings, &urls).unwrap();
+        assert_eq!(model.id(), "llama-3");
+        assert!(create_model_with_settings("deepseek/deepseek-chat", &settings, &urls).is_err());
+    }
+
+    /// One provider listing one model with a known context window
+    struct FixedSource {
+        fingerprint: String,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::ModelSource for FixedSource {
+        fn providers(&self) -> Vec<crate::SourceProvider> {
+            Vec::new()
+        }
+
+        fn fingerprint(&self, _provider: &str) -> String {
+            self.fingerprint.clone()
+        }
+
+        async fn list_models(&self, _provider: &str) -> anyhow::Result<Vec<ModelDefinition>> {
+            Ok(vec![ModelDefinition::new("llama-3".to_string(), Vec::new()).with_context_window(32_768)])
+        }
+    }
+
+    #[test]
+    fn test_context_window_falls_back_to_catalog() {
+        let mut settings = Settings::default();
+        settings.compatible_providers.insert(
+            "together".to_string(),
+            config::CompatibleProvider {
+                base_url: "http://localhost:8000/v1".to_string(),
+                api_key_env: None,
+                models: vec!["llama-3".to_string()],
+            },
+        );
+        let urls = ProviderUrls::default();
+        let model = create_model_with_settings("together/llama-3", &settings, &urls).unwrap();
+        assert_eq!(model.context_window(), None);
+
+        let fingerprint = provider_fingerprint("together", &settings, &urls);
+        let source = Arc::new(FixedSource { fingerprint });
+        let catalog = ModelCatalog::new(source, None, ModelCatalog::DEFAULT_TTL);
+        let model = with_catalog_context_window(Arc::clone(&model), "together/llama-3", &catalog, &settings, &urls);
+        // Nothing cached yet
+        assert_eq!(model.context_window(), None);
+
+        // Settings look up keys synchronously, so only the fetch runs async
+        tokio::runtime::Runtime::new().unwrap().block_on(catalog.refresh(Some("together")));
+        let model = with_catalog_context_window(model, "together/llama-3", &catalog, &settings, &urls);
+        assert_eq!(model.context_window(), Some(32_768));
+        assert_eq!(model.id(), "llama-3");
+    }
+
+    #[test]
+    fn test_provider_fingerprint_follows_configuration() {
+        let mut settings = Settings::default();
+        let mut provider = config::CompatibleProvider {
+            base_url: "http://localhost:8000/v1".to_string(),
+            api_key_env: None,
+            models: Vec::new(),
+        };
+        settings.compatible_providers.insert("together".to_string(), provider.clone());
+        let urls = ProviderUrls::default();
+
+        let fingerprint = provider_fingerprint("together", &settings, &urls);
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(provider_fingerprint("together", &settings, &urls), fingerprint);
+
+        provider.base_url = "http://localhost:9000/v1".to_string();
+        settings.compatible_providers.insert("together".to_string(), provider);
+        assert_ne!(provider_fingerprint("together", &settings, &urls), fingerprint);
+    }
+
+    #[test]
+    fn test_timeouts_from_settings() {
+        let mut settings = Settings::default();
+        assert_eq!(timeouts(&settings), Timeouts::default());
+
+        settings.request_timeout_secs = Some(30);
+        settings.stream_idle_timeout_secs = Some(0);
+        let timeouts = timeouts(&settings);
+        assert_eq!(timeouts.request_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(timeouts.stream_idle_timeout, None);
+    }
 }
@@ -0,0 +1,339 @@
+//! Replaying recorded provider traffic
+//!
+//! A [`TrafficReplay`] reads the JSONL written by a
+//! [`JsonlTrafficLogger`](crate::traffic_log::JsonlTrafficLogger) and hands
+//! back the recorded responses instead of sending requests. A
+//! [`ReplayProvider`] runs a built-in provider on it, so recorded bodies go
+//! through the same parsing as live ones and a session can be reproduced
+//! without network access.
+//!
+//! Each request record starts an exchange; the response, stream chunk and
+//! error records after it belong to it. Logs of concurrent requests, whose
+//! records interleave, can't be replayed.
+
+use crate::providers::GeneralModelProvider;
+use crate::traffic_log::{redact, TrafficKind, TrafficRecord};
+use crate::{ChatModel, ModelCapability, ModelDefinition, ModelProvider, ProviderError};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// How requests are paired with recorded exchanges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayMatch {
+    /// The n-th request gets the n-th recorded exchange
+    #[default]
+    ByIndex,
+    /// A request gets the first unused exchange whose recorded request body
+    /// is the same, after redaction
+    ByRequest,
+}
+
+/// What a recorded request got back
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RecordedOutcome {
+    Response(Value),
+    Stream(Vec<Value>),
+    Error { status: Option<u16>, body: Value },
+}
+
+#[derive(Debug)]
+struct Exchange {
+    model: String,
+    request: Value,
+    outcome: Option<RecordedOutcome>,
+    used: bool,
+}
+
+/// Recorded exchanges, handed out once each
+#[derive(Debug)]
+pub struct TrafficReplay {
+    exchanges: Mutex<Vec<Exchange>>,
+    mode: ReplayMatch,
+}
+
+impl TrafficReplay {
+    /// Read a JSONL traffic log
+    pub fn open(path: &Path, mode: ReplayMatch) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let mut records = Vec::new();
+        for (number, line) in text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+        {
+            let record: TrafficRecord = serde_json::from_str(line).map_err(|e| {
+                anyhow::anyhow!(
+                    "{}:{}: not a traffic record: {}",
+                    path.display(),
+                    number + 1,
+                    e
+                )
+            })?;
+            records.push(record);
+        }
+        Ok(Self::from_records(records, mode))
+    }
+
+    pub fn from_records(
+        records: impl IntoIterator<Item = TrafficRecord>,
+        mode: ReplayMatch,
+    ) -> Self {
+        let mut exchanges: Vec<Exchange> = Vec::new();
+        for record in records {
+            if record.kind == TrafficKind::Request {
+                exchanges.push(Exchange {
+                    model: record.model,
+                    request: record.body,
+                    outcome: None,
+                    used: false,
+                });
+                continue;
+            }
+            // Errors logged without a request (e.g. by `log_error`) have nothing to attach to
+            let Some(exchange) = exchanges.last_mut() else {
+                continue;
+            };
+            match (record.kind, &mut exchange.outcome) {
+                (TrafficKind::StreamChunk, Some(RecordedOutcome::Stream(chunks))) => {
+                    chunks.push(record.body)
+                }
+                (TrafficKind::StreamChunk, outcome @ None) => {
+                    *outcome = Some(RecordedOutcome::Stream(vec![record.body]))
+                }
+                (TrafficKind::Response, outcome @ None) => {
+                    *outcome = Some(RecordedOutcome::Response(record.body))
+                }
+                (TrafficKind::Error, outcome @ None) => {
+                    *outcome = Some(RecordedOutcome::Error {
+                        status: record.status,
+                        body: record.body,
+                    })
+                }
+                _ => {}
+            }
+        }
+        Self {
+            exchanges: Mutex::new(exchanges),
+            mode,
+        }
+    }
+
+    /// Models the recorded requests were sent to, in order of first use
+    pub fn models(&self) -> Vec<String> {
+        let mut models: Vec<String> = Vec::new();
+        for exchange in self.exchanges.lock().unwrap().iter() {
+            if !models.contains(&exchange.model) {
+                models.push(exchange.model.clone());
+            }
+        }
+        models
+    }
+
+    /// Number of recorded exchanges not yet replayed
+    pub fn remaining(&self) -> usize {
+        self.exchanges
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|exchange| !exchange.used)
+            .count()
+    }
+
+    /// Take the recorded outcome for `request`
+    pub(crate) fn next(&self, request: Value) -> anyhow::Result<RecordedOutcome> {
+        let mut exchanges = self.exchanges.lock().unwrap();
+        let exchange = match self.mode {
+            ReplayMatch::ByIndex => exchanges
+                .iter_mut()
+                .find(|exchange| !exchange.used)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Traffic log exhausted: no recorded exchange left to replay")
+                })?,
+            ReplayMatch::ByRequest => {
+                let request = redact(request);
+                exchanges
+                    .iter_mut()
+                    .find(|exchange| !exchange.used && exchange.request == request)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Traffic log exhausted: no recorded exchange matches this request"
+                        )
+                    })?
+            }
+        };
+        exchange.used = true;
+        exchange.outcome.clone().ok_or_else(|| {
+            anyhow::anyhow!(
+                "The recorded request to '{}' got no response",
+                exchange.model
+            )
+        })
+    }
+}
+
+/// Turn a recorded error back into the error the provider returned
+pub(crate) fn recorded_error(status: Option<u16>, body: Value) -> anyhow::Error {
+    let body = match body {
+        Value::String(text) => text,
+        other => other.to_string(),
+    };
+    // Logs written before the status was recorded get one that isn't
+    // retried, so a replay doesn't back off on an error it can't recover from
+    let status = status
+        .and_then(|status| reqwest::StatusCode::from_u16(status).ok())
+        .unwrap_or(reqwest::StatusCode::BAD_REQUEST);
+    ProviderError::new(status, body).into()
+}
+
+/// A built-in provider answering from a traffic log instead of the network
+pub struct ReplayProvider {
+    provider: GeneralModelProvider,
+    replay: Arc<TrafficReplay>,
+}
+
+impl ReplayProvider {
+    /// Replay the traffic of the built-in provider `provider_name` (e.g. "claude")
+    pub fn new(provider_name: &str, replay: TrafficReplay) -> anyhow::Result<Self> {
+        let replay = Arc::new(replay);
+        // Nothing is sent, so no real key is needed
+        let provider =
+            GeneralModelProvider::from_name_with_config(provider_name, Some("replay"), None)?
+                .with_replay(Arc::clone(&replay));
+        Ok(Self { provider, replay })
+    }
+
+    /// The recorded exchanges, e.g. to check they were all replayed
+    pub fn replay(&self) -> &Arc<TrafficReplay> {
+        &self.replay
+    }
+}
+
+#[async_trait]
+impl ModelProvider for ReplayProvider {
+    /// The models the log has requests for; their capabilities aren't recorded
+    async fn list_models(&self) -> anyhow::Result<Vec<ModelDefinition>> {
+        Ok(self
+            .replay
+            .models()
+            .into_iter()
+            .map(|model| {
+                ModelDefinition::new(model, vec![ModelCapability::Text, ModelCapability::Tools])
+            })
+            .collect())
+    }
+
+    fn create_chat_model(&self, model_name: &str) -> Option<Arc<dyn ChatModel + Send + Sync>> {
+        self.provider.create_chat_model(model_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ollama::chat::api::OllamaRequest;
+    use crate::{collect_stream, ChatMessage, ChatPayload, ChatRequest};
+    use serde_json::json;
+
+    fn request() -> ChatRequest {
+        let messages = [ChatMessage::user(ChatPayload::text("hi"))];
+        ChatRequest::new(messages.iter())
+    }
+
+    fn reply(text: &str, done: bool) -> Value {
+        let mut body = json!({"message": {"role": "assistant", "content": text}});
+        if done {
+            body["done_reason"] = json!("stop");
+        }
+        body
+    }
+
+    fn record(kind: TrafficKind, body: Value) -> TrafficRecord {
+        TrafficRecord::new("llama3", kind, body)
+    }
+
+    #[tokio::test]
+    async fn test_replays_responses_in_order() {
+        let records = vec![
+            record(TrafficKind::Request, json!({})),
+            record(TrafficKind::Response, reply("Hello", true)),
+            record(TrafficKind::Request, json!({})),
+            record(TrafficKind::StreamChunk, reply("Hel", false)),
+            record(TrafficKind::StreamChunk, reply("lo again", true)),
+            record(TrafficKind::Request, json!({})),
+            record(
+                TrafficKind::Error,
+                json!({"error": "model 'llama3' not found"}),
+            )
+            .with_status(404),
+        ];
+        let provider = ReplayProvider::new(
+            "ollama",
+            TrafficReplay::from_records(records, ReplayMatch::ByIndex),
+        )
+        .unwrap();
+        assert_eq!(provider.list_models().await.unwrap()[0].id, "llama3");
+        let model = provider.create_chat_model("llama3").unwrap();
+
+        assert_eq!(model.chat(&request()).await.unwrap().get_text(), "Hello");
+        let streamed = collect_stream(model.stream_chat(&request()).await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(streamed.get_text(), "Hello again");
+
+        let err = model.chat(&request()).await.unwrap_err();
+        let err = err.downcast_ref::<ProviderError>().unwrap();
+        assert_eq!(err.status, reqwest::StatusCode::NOT_FOUND);
+        assert!(err.message.contains("not found"));
+
+        let err = model.chat(&request()).await.unwrap_err().to_string();
+        assert!(err.contains("exhausted"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_matches_by_request_body() {
+        let body = |text: &str| {
+            let messages = [ChatMessage::user(ChatPayload::text(text))];
+            serde_json::to_value(OllamaRequest::from_chat_request(
+                "llama3",
+                &ChatRequest::new(messages.iter()),
+                false,
+            ))
+            .unwrap()
+        };
+        let records = vec![
+            record(TrafficKind::Request, body("first")),
+            record(TrafficKind::Response, reply("one", true)),
+            record(TrafficKind::Request, body("hi")),
+            record(TrafficKind::Response, reply("two", true)),
+        ];
+        let replay = TrafficReplay::from_records(records, ReplayMatch::ByRequest);
+        let provider = ReplayProvider::new("ollama", replay).unwrap();
+        let model = provider.create_chat_model("llama3").unwrap();
+
+        assert_eq!(model.chat(&request()).await.unwrap().get_text(), "two");
+        assert_eq!(provider.replay().remaining(), 1);
+        assert!(model.chat(&request()).await.is_err());
+    }
+
+    #[test]
+    fn test_reads_jsonl_log() {
+        use crate::traffic_log::{JsonlTrafficLogger, TrafficLogger};
+
+        let dir = std::env::temp_dir().join(format!("noema-replay-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("traffic.jsonl");
+        let logger = JsonlTrafficLogger::open(&path).unwrap();
+        logger.log(&record(TrafficKind::Request, json!({"api_key": "secret"})));
+        logger.log(&record(TrafficKind::Response, reply("Hello", true)));
+
+        let replay = TrafficReplay::open(&path, ReplayMatch::ByRequest).unwrap();
+        assert_eq!(replay.models(), vec!["llama3"]);
+        // The live request is redacted the same way before matching
+        assert_eq!(
+            replay.next(json!({"api_key": "other"})).unwrap(),
+            RecordedOutcome::Response(reply("Hello", true))
+        );
+    }
+}
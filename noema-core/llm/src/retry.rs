@@ -0,0 +1,339 @@
+//! Retry with exponential backoff for transient provider errors
+
+use crate::{
+    ChatMessage, ChatModel, ChatRequest, ChatStream, EmbeddingModel, Embeddings, ProviderError,
+};
+use async_trait::async_trait;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often and how long to wait between retries.
+///
+/// The delay before retry `n` (0-based) is `base_delay * 2^n`, capped at
+/// `max_delay`. With `jitter` enabled the delay is drawn uniformly from
+/// `[delay / 2, delay]` so concurrent clients don't retry in lockstep.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before the given retry attempt (0-based)
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        if !self.jitter {
+            return delay;
+        }
+        let fraction = (random_u64() % 1000) as f64 / 1000.0;
+        delay.mul_f64(0.5 + fraction / 2.0)
+    }
+}
+
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// Whether an error from a provider call is worth retrying.
+///
+/// Provider errors are retryable for 408, 429 and 5xx; other statuses are
+/// not. Transport errors (timeouts, connection failures) are retryable.
+/// Anything else, e.g. a response that failed to parse, is not.
+pub fn is_retryable(error: &anyhow::Error) -> bool {
+    if let Some(provider) = error.downcast_ref::<ProviderError>() {
+        return provider.is_transient();
+    }
+    if let Some(err) = error.downcast_ref::<reqwest::Error>() {
+        return err.is_timeout() || err.is_connect() || err.is_request();
+    }
+    false
+}
+
+/// Wait before the next attempt. A delay the provider asked for via
+/// `Retry-After` replaces the policy's, still capped at `max_delay`.
+async fn backoff(model_id: &str, policy: &RetryPolicy, attempt: u32, error: &anyhow::Error) {
+    let delay = error
+        .downcast_ref::<ProviderError>()
+        .and_then(|provider| provider.retry_after)
+        .map(|delay| delay.min(policy.max_delay))
+        .unwrap_or_else(|| policy.delay_for(attempt));
+    tracing::warn!(
+        "{}: retrying in {:?} (attempt {}/{}): {}",
+        model_id,
+        delay,
+        attempt + 1,
+        policy.max_retries,
+        error
+    );
+    tokio::time::sleep(delay).await;
+}
+
+/// ChatModel wrapper that retries transient failures of the inner model.
+///
+/// `stream_chat` only retries while opening the stream; once a stream has
+/// been returned its chunks are passed through untouched.
+pub struct RetryingChatModel {
+    inner: Arc<dyn ChatModel + Send + Sync>,
+    policy: RetryPolicy,
+}
+
+impl RetryingChatModel {
+    pub fn new(inner: Arc<dyn ChatModel + Send + Sync>, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// Wrap a model, returning it in the shared form used throughout the app
+    pub fn wrap(
+        inner: Arc<dyn ChatModel + Send + Sync>,
+        policy: RetryPolicy,
+    ) -> Arc<dyn ChatModel + Send + Sync> {
+        Arc::new(Self::new(inner, policy))
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    async fn backoff(&self, attempt: u32, error: &anyhow::Error) {
+        backoff(self.inner.id(), &self.policy, attempt, error).await;
+    }
+}
+
+#[async_trait]
+impl ChatModel for RetryingChatModel {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.inner.supports_tools()
+    }
+
+    fn context_window(&self) -> Option<u32> {
+        self.inner.context_window()
+    }
+
+    async fn chat(&self, messages: &ChatRequest) -> anyhow::Result<ChatMessage> {
+        let mut attempt = 0;
+        loop {
+            match self.inner.chat(messages).await {
+                Ok(response) => return Ok(response),
+                Err(e) if attempt < self.policy.max_retries && is_retryable(&e) => {
+                    self.backoff(attempt, &e).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn stream_chat(&self, messages: &ChatRequest) -> anyhow::Result<ChatStream> {
+        let mut attempt = 0;
+        loop {
+            match self.inner.stream_chat(messages).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) if attempt < self.policy.max_retries && is_retryable(&e) => {
+                    self.backoff(attempt, &e).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// EmbeddingModel wrapper that retries transient failures of the inner model
+pub struct RetryingEmbeddingModel {
+    inner: Arc<dyn EmbeddingModel + Send + Sync>,
+    policy: RetryPolicy,
+}
+
+impl RetryingEmbeddingModel {
+    pub fn new(inner: Arc<dyn EmbeddingModel + Send + Sync>, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// Wrap a model, returning it in the shared form used throughout the app
+    pub fn wrap(
+        inner: Arc<dyn EmbeddingModel + Send + Sync>,
+        policy: RetryPolicy,
+    ) -> Arc<dyn EmbeddingModel + Send + Sync> {
+        Arc::new(Self::new(inner, policy))
+    }
+}
+
+#[async_trait]
+impl EmbeddingModel for RetryingEmbeddingModel {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    async fn embed(&self, inputs: &[String]) -> anyhow::Result<Embeddings> {
+        let mut attempt = 0;
+        loop {
+            match self.inner.embed(inputs).await {
+                Ok(embeddings) => return Ok(embeddings),
+                Err(e) if attempt < self.policy.max_retries && is_retryable(&e) => {
+                    backoff(self.inner.id(), &self.policy, attempt, &e).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockChatModel;
+    use crate::ChatPayload;
+
+    /// Model that fails with the given status a fixed number of times
+    fn flaky_model(failures: u32, status: u16) -> MockChatModel {
+        let mut builder = MockChatModel::builder("flaky");
+        for _ in 0..failures {
+            builder = builder.provider_error(status);
+        }
+        builder.text("ok").build()
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            jitter: false,
+        }
+    }
+
+    fn request() -> ChatRequest {
+        let messages = [ChatMessage::user(ChatPayload::text("hi"))];
+        ChatRequest::new(messages.iter())
+    }
+
+    #[test]
+    fn test_delay_is_exponential_and_capped() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+            jitter: false,
+        };
+        assert_eq!(policy.delay_for(0), Duration::from_millis(100));
+        assert_eq!(policy.delay_for(1), Duration::from_millis(200));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let policy = RetryPolicy { jitter: true, ..fast_policy() };
+        for _ in 0..20 {
+            let delay = policy.delay_for(10);
+            assert!(delay >= Duration::from_micros(2500) && delay <= Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_is_retryable_status_classification() {
+        let err = |status: u16| -> anyhow::Error {
+            ProviderError::new(reqwest::StatusCode::from_u16(status).unwrap(), "").into()
+        };
+        assert!(is_retryable(&err(429)));
+        assert!(is_retryable(&err(408)));
+        assert!(is_retryable(&err(503)));
+        assert!(!is_retryable(&err(400)));
+        assert!(!is_retryable(&err(401)));
+        assert!(!is_retryable(&anyhow::anyhow!("parse error")));
+    }
+
+    #[tokio::test]
+    async fn test_chat_retries_transient_errors() {
+        let inner = Arc::new(flaky_model(2, 429));
+        let model = RetryingChatModel::new(inner.clone(), fast_policy());
+        let response = model.chat(&request()).await.unwrap();
+        assert_eq!(response.get_text(), "ok");
+        assert_eq!(inner.request_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_chat_gives_up_after_max_retries() {
+        let inner = Arc::new(flaky_model(5, 503));
+        let model = RetryingChatModel::new(inner.clone(), fast_policy());
+        assert!(model.chat(&request()).await.is_err());
+        assert_eq!(inner.request_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_stream_chat_does_not_retry_client_errors() {
+        let inner = Arc::new(flaky_model(1, 400));
+        let model = RetryingChatModel::new(inner.clone(), fast_policy());
+        assert!(model.stream_chat(&request()).await.is_err());
+        assert_eq!(inner.request_count(), 1);
+    }
+
+    /// Embedding model that fails with the given status a fixed number of times
+    struct FlakyEmbeddingModel {
+        failures: u32,
+        status: u16,
+        calls: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait]
+    impl EmbeddingModel for FlakyEmbeddingModel {
+        fn id(&self) -> &str {
+            "flaky"
+        }
+
+        async fn embed(&self, inputs: &[String]) -> anyhow::Result<Embeddings> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if call < self.failures {
+                let status = reqwest::StatusCode::from_u16(self.status).unwrap();
+                return Err(ProviderError::new(status, "").into());
+            }
+            Embeddings::from_vectors(inputs.len(), vec![vec![1.0]; inputs.len()])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_embed_retries_transient_errors() {
+        let inner = Arc::new(FlakyEmbeddingModel {
+            failures: 2,
+            status: 429,
+            calls: Default::default(),
+        });
+        let model = RetryingEmbeddingModel::new(inner.clone(), fast_policy());
+        assert!(model.embed(&["hi".to_string()]).await.is_ok());
+        assert_eq!(inner.calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+}
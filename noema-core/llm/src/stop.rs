@@ -0,0 +1,114 @@
+//! Enforcing stop sequences on streamed text
+//!
+//! Providers are asked to stop at `GenerationParams::stop`, but some
+//! (OpenAI-compatible local servers in particular) include the stop
+//! sequence in the stream or keep generating past it. `StopSequenceFilter`
+//! cuts streamed text at the first stop sequence so it never reaches the
+//! user, holding back any trailing text that could be the start of one.
+
+/// Cuts streamed text at the first stop sequence
+#[derive(Clone, Debug, Default)]
+pub struct StopSequenceFilter {
+    stops: Vec<String>,
+    /// Text that may be the start of a stop sequence, not yet released
+    held: String,
+    stopped: bool,
+}
+
+impl StopSequenceFilter {
+    /// Create a filter for `stops`; empty sequences are ignored
+    pub fn new(stops: &[String]) -> Self {
+        Self {
+            stops: stops.iter().filter(|s| !s.is_empty()).cloned().collect(),
+            held: String::new(),
+            stopped: false,
+        }
+    }
+
+    /// Add a streamed delta and return the text that is safe to show
+    ///
+    /// Returns an empty string once a stop sequence has been seen.
+    pub fn push(&mut self, text: &str) -> String {
+        if self.stopped {
+            return String::new();
+        }
+        if self.stops.is_empty() {
+            return text.to_string();
+        }
+        self.held.push_str(text);
+
+        if let Some(at) = self.stops.iter().filter_map(|stop| self.held.find(stop.as_str())).min() {
+            self.stopped = true;
+            self.held.truncate(at);
+            return std::mem::take(&mut self.held);
+        }
+
+        let keep = self.partial_stop_len();
+        self.held.drain(..self.held.len() - keep).collect()
+    }
+
+    /// Release text held back at the end of the stream
+    pub fn finish(&mut self) -> String {
+        std::mem::take(&mut self.held)
+    }
+
+    /// Whether a stop sequence has been seen
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Length of the longest suffix of the held text that starts a stop sequence
+    fn partial_stop_len(&self) -> usize {
+        self.stops
+            .iter()
+            .filter_map(|stop| {
+                (1..stop.len())
+                    .rev()
+                    .filter(|&len| stop.is_char_boundary(len))
+                    .find(|&len| self.held.ends_with(&stop[..len]))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(stops: &[&str], deltas: &[&str]) -> (Vec<String>, bool) {
+        let stops: Vec<String> = stops.iter().map(|s| s.to_string()).collect();
+        let mut filter = StopSequenceFilter::new(&stops);
+        let mut out: Vec<String> = deltas.iter().map(|d| filter.push(d)).collect();
+        out.push(filter.finish());
+        (out, filter.is_stopped())
+    }
+
+    #[test]
+    fn test_stop_split_across_deltas_is_not_leaked() {
+        let (out, stopped) = run(&["END"], &["{\"a\": 1}", "E", "N", "D", " more"]);
+        assert_eq!(out, vec!["{\"a\": 1}", "", "", "", "", ""]);
+        assert!(stopped);
+    }
+
+    #[test]
+    fn test_false_start_is_released() {
+        let (out, stopped) = run(&["END"], &["The E", "nigma", " ends"]);
+        assert_eq!(out.concat(), "The Enigma ends");
+        assert_eq!(out[0], "The ");
+        assert!(!stopped);
+    }
+
+    #[test]
+    fn test_earliest_of_several_stops_wins() {
+        let (out, _) = run(&["\n\n", "###"], &["one ### two\n\nthree"]);
+        assert_eq!(out.concat(), "one ");
+    }
+
+    #[test]
+    fn test_no_stops_passes_text_through() {
+        let (out, stopped) = run(&[""], &["a", "b"]);
+        assert_eq!(out, vec!["a", "b", ""]);
+        assert!(!stopped);
+    }
+}
@@ -0,0 +1,193 @@
+//! Converting between streamed and whole responses
+//!
+//! `collect_stream` turns a `ChatStream` into the message it adds up to, for
+//! callers that want one reply from a streaming model. `fake_stream` goes
+//! the other way, for models that only produce whole replies.
+//! `StreamCollector` does the merging chunk by chunk, for callers that also
+//! show text as it arrives.
+
+use futures::StreamExt;
+
+use crate::api::{ChatChunk, ChatMessage, ChatPayload, ContentBlock, FinishReason, Role, TokenUsage};
+use crate::stop::StopSequenceFilter;
+use crate::ChatStream;
+
+/// Merges streamed chunks into one message
+///
+/// Text deltas are joined into a single text block, cut at any stop
+/// sequences. Other blocks (tool calls, images) are kept in arrival order;
+/// providers send each tool call whole once its arguments are complete, so
+/// `tool_call_delta` fragments are ignored.
+#[derive(Debug, Default)]
+pub struct StreamCollector {
+    stop_filter: StopSequenceFilter,
+    role: Role,
+    text: String,
+    blocks: Vec<ContentBlock>,
+    usage: Option<TokenUsage>,
+    finish_reason: Option<FinishReason>,
+}
+
+impl StreamCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a collector that cuts text at the first of `stops`
+    pub fn with_stop_sequences(stops: &[String]) -> Self {
+        Self { stop_filter: StopSequenceFilter::new(stops), ..Self::default() }
+    }
+
+    /// Add a chunk, returning the text it makes visible
+    pub fn push(&mut self, chunk: ChatChunk) -> String {
+        self.role = chunk.role;
+        if chunk.usage.is_some() {
+            self.usage = chunk.usage;
+        }
+        if chunk.finish_reason.is_some() {
+            self.finish_reason = chunk.finish_reason;
+        }
+        let mut visible = String::new();
+        for block in chunk.payload.content {
+            match block {
+                ContentBlock::Text { text } => visible.push_str(&self.stop_filter.push(&text)),
+                other => self.blocks.push(other),
+            }
+        }
+        self.text.push_str(&visible);
+        visible
+    }
+
+    /// Release text held back as a possible stop sequence, at the end of the stream
+    pub fn flush(&mut self) -> String {
+        let rest = self.stop_filter.finish();
+        self.text.push_str(&rest);
+        rest
+    }
+
+    /// The text collected so far
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The merged message: the text, then the other blocks
+    pub fn into_message(self) -> ChatMessage {
+        self.build(true)
+    }
+
+    /// The merged message without its tool calls and other blocks, for a
+    /// response cut short while they may be incomplete
+    pub fn into_text_message(self) -> ChatMessage {
+        self.build(false)
+    }
+
+    fn build(self, keep_blocks: bool) -> ChatMessage {
+        let mut content = Vec::new();
+        if !self.text.is_empty() {
+            content.push(ContentBlock::Text { text: self.text });
+        }
+        if keep_blocks {
+            content.extend(self.blocks);
+        }
+        // Text after a stop sequence is dropped, so the answer ended there
+        let finish_reason = if self.stop_filter.is_stopped() {
+            Some(FinishReason::Stop)
+        } else {
+            self.finish_reason
+        };
+        ChatMessage::new(self.role, ChatPayload::new(content))
+            .with_usage(self.usage)
+            .with_finish_reason(finish_reason)
+    }
+}
+
+/// Read a stream to the end and merge it into one message, failing if
+/// the stream does
+pub async fn collect_stream(mut stream: ChatStream) -> anyhow::Result<ChatMessage> {
+    let mut collector = StreamCollector::new();
+    while let Some(chunk) = stream.next().await {
+        collector.push(chunk?);
+    }
+    collector.flush();
+    Ok(collector.into_message())
+}
+
+/// A stream that yields `message` as a single chunk
+pub fn fake_stream(message: ChatMessage) -> ChatStream {
+    let chunk = ChatChunk::new(message.role, message.payload)
+        .with_usage(message.usage)
+        .with_finish_reason(message.finish_reason);
+    Box::pin(futures::stream::iter([Ok(chunk)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ToolCall;
+
+    fn tool_call() -> ContentBlock {
+        ContentBlock::ToolCall(ToolCall {
+            id: "call_1".to_string(),
+            name: "search".to_string(),
+            arguments: serde_json::json!({"query": "rust"}),
+            extra: serde_json::Value::Null,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_collect_stream_merges_chunks() {
+        let chunks = vec![
+            ChatChunk::assistant(ChatPayload::text("Let me ")),
+            ChatChunk::assistant(ChatPayload::text("look that up.")),
+            ChatChunk::tool_call_delta(crate::ToolCallDelta {
+                id: "call_1".to_string(),
+                name: "search".to_string(),
+                args_fragment: "{\"query\"".to_string(),
+            }),
+            ChatChunk::assistant(ChatPayload::new(vec![tool_call()])),
+            ChatChunk::usage(TokenUsage::new(10, 5)),
+            ChatChunk::assistant(ChatPayload::default()).with_finish_reason(Some(FinishReason::ToolUse)),
+        ];
+        let message = collect_stream(Box::pin(futures::stream::iter(chunks.into_iter().map(Ok)))).await.unwrap();
+
+        assert_eq!(message.role, Role::Assistant);
+        assert_eq!(message.payload.content.len(), 2);
+        assert_eq!(message.get_text(), "Let me look that up.");
+        assert_eq!(message.get_tool_calls().len(), 1);
+        assert_eq!(message.usage, Some(TokenUsage::new(10, 5)));
+        assert_eq!(message.finish_reason, Some(FinishReason::ToolUse));
+    }
+
+    #[tokio::test]
+    async fn test_fake_stream_round_trips() {
+        let message = ChatMessage::assistant(ChatPayload::new(vec![
+            ContentBlock::Text { text: "Searching".to_string() },
+            tool_call(),
+        ]))
+        .with_usage(Some(TokenUsage::new(3, 4)))
+        .with_finish_reason(Some(FinishReason::ToolUse));
+
+        let chunks: Vec<anyhow::Result<ChatChunk>> = fake_stream(message.clone()).collect().await;
+        assert_eq!(chunks.len(), 1);
+
+        let collected = collect_stream(fake_stream(message.clone())).await.unwrap();
+        assert_eq!(collected.get_text(), message.get_text());
+        assert_eq!(collected.get_tool_calls()[0].arguments, serde_json::json!({"query": "rust"}));
+        assert_eq!(collected.usage, message.usage);
+        assert_eq!(collected.finish_reason, message.finish_reason);
+    }
+
+    #[test]
+    fn test_collector_cuts_at_stop_sequence() {
+        let mut collector = StreamCollector::with_stop_sequences(&["END".to_string()]);
+        assert_eq!(collector.push(ChatChunk::assistant(ChatPayload::text("done E"))), "done ");
+        assert_eq!(collector.push(ChatChunk::assistant(ChatPayload::text("ND ignored"))), "");
+        assert_eq!(collector.flush(), "");
+        collector.push(ChatChunk::assistant(ChatPayload::new(vec![tool_call()])));
+
+        let message = collector.into_text_message();
+        assert_eq!(message.get_text(), "done ");
+        assert!(message.get_tool_calls().is_empty());
+        assert_eq!(message.finish_reason, Some(FinishReason::Stop));
+    }
+}
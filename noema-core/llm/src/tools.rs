@@ -1,4 +1,4 @@
-use crate::api::ToolDefinition;
+use crate::api::{ToolArgsValidator, ToolDefinition};
 use anyhow::Result;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -7,8 +7,21 @@ use std::pin::Pin;
 
 type ToolFn = Box<dyn Fn(Value) -> Pin<Box<dyn Future<Output = Result<String>> + Send>> + Send + Sync>;
 
+/// A registered tool: its definition, its schema compiled once at
+/// registration, and its handler
+struct RegisteredTool {
+    definition: ToolDefinition,
+    validator: Result<ToolArgsValidator>,
+    handler: ToolFn,
+}
+
+/// Tools implemented in-process, each an async handler taking the call's
+/// JSON arguments and returning the result text
+///
+/// `call` checks the arguments against the tool's input schema before
+/// running the handler, so handlers only see arguments of the right shape.
 pub struct ToolRegistry {
-    tools: HashMap<String, (ToolDefinition, ToolFn)>,
+    tools: HashMap<String, RegisteredTool>,
 }
 
 impl ToolRegistry {
@@ -18,34 +31,52 @@ impl ToolRegistry {
         }
     }
 
+    /// Add a tool, replacing any registered under the same name
     pub fn register<F, Fut>(&mut self, definition: ToolDefinition, handler: F)
     where
         F: Fn(Value) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Result<String>> + Send + 'static,
     {
         let name = definition.name.clone();
-        let wrapped: ToolFn = Box::new(move |args| Box::pin(handler(args)));
-        self.tools.insert(name, (definition, wrapped));
+        let tool = RegisteredTool {
+            validator: definition.args_validator(),
+            definition,
+            handler: Box::new(move |args| Box::pin(handler(args))),
+        };
+        self.tools.insert(name, tool);
     }
 
     pub fn get_definition(&self, name: &str) -> Option<&ToolDefinition> {
-        self.tools.get(name).map(|(def, _)| def)
+        self.tools.get(name).map(|tool| &tool.definition)
     }
 
     pub fn get_all_definitions(&self) -> Vec<ToolDefinition> {
-        self.tools.values().map(|(def, _)| def.clone()).collect()
+        self.tools.values().map(|tool| tool.definition.clone()).collect()
     }
 
     pub async fn call(&self, name: &str, args: Value) -> Result<String> {
-        match self.tools.get(name) {
-            Some((_, handler)) => handler(args).await,
-            None => Err(anyhow::anyhow!("Tool '{}' not found", name)),
+        let tool = self
+            .tools
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Tool '{}' not found", name))?;
+        match &tool.validator {
+            Ok(validator) => validator.validate(&args)?,
+            Err(e) => anyhow::bail!("{}", e),
         }
+        (tool.handler)(args).await
     }
 
     pub fn has_tool(&self, name: &str) -> bool {
         self.tools.contains_key(name)
     }
+
+    pub fn len(&self) -> usize {
+        self.tools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
 }
 
 impl Default for ToolRegistry {
@@ -241,4 +272,18 @@ mod tests {
         let missing = registry.get_definition("missing");
         assert!(missing.is_none());
     }
+
+    #[tokio::test]
+    async fn test_arguments_not_matching_the_schema_are_rejected() {
+        let mut registry = ToolRegistry::new();
+        registry.register(create_calculator_definition(), |_| async { panic!("handler must not run") });
+
+        let err = registry
+            .call("calculator", serde_json::json!({"operation": "add", "a": "five"}))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("missing required field 'b'"), "{}", err);
+        assert!(err.contains("field 'a'"), "{}", err);
+    }
 }
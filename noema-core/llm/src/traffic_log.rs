@@ -1,28 +1,166 @@
 //! Traffic logging for LLM API calls
 //!
-//! Logs LLM errors only. Request/response content is not logged
-//! to protect user privacy (may contain blobs, personal data).
+//! Errors always go to noema.log. Raw request/response bodies are only
+//! recorded when a [`TrafficLogger`] is installed on a provider, or when the
+//! `NOEMA_TRAFFIC_LOG` environment variable is set, since they may contain
+//! blobs and personal data. A recorded log can be played back with a
+//! [`ReplayProvider`](crate::replay::ReplayProvider).
 
 use config::PathManager;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{File, OpenOptions};
 use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
 
-/// Log an LLM request (no-op, content not logged for privacy)
-pub fn log_request(_model: &str, _request: &impl serde::Serialize) {}
+/// Environment variable enabling the JSONL traffic log.
+///
+/// `1` or `true` logs to [`PathManager::traffic_log_path`], any other
+/// non-empty value (except `0`/`false`) is used as the log file path.
+pub const TRAFFIC_LOG_ENV: &str = "NOEMA_TRAFFIC_LOG";
 
-/// Log an LLM response (no-op, content not logged for privacy)
-pub fn log_response(_model: &str, _response: &impl serde::Serialize) {}
+/// Replacement for redacted credential values
+const REDACTED: &str = "[REDACTED]";
+
+/// Object keys whose values are never written to the traffic log
+const SECRET_KEYS: &[&str] = &[
+    "access_token",
+    "api_key",
+    "apikey",
+    "authorization",
+    "password",
+    "secret",
+    "x-api-key",
+    "x-goog-api-key",
+];
+
+/// What a traffic record holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrafficKind {
+    Request,
+    Response,
+    StreamChunk,
+    Error,
+}
+
+/// One request, response or stream chunk exchanged with a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficRecord {
+    /// RFC 3339 timestamp
+    pub timestamp: String,
+    pub model: String,
+    pub kind: TrafficKind,
+    /// Serialized body, with credentials redacted
+    pub body: Value,
+    /// HTTP status of an error response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+}
+
+impl TrafficRecord {
+    pub fn new(model: &str, kind: TrafficKind, body: Value) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            model: model.to_string(),
+            kind,
+            body: redact(body),
+            status: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+}
+
+/// Receives the raw traffic of a provider
+pub trait TrafficLogger: Send + Sync {
+    fn log(&self, record: &TrafficRecord);
+}
+
+/// Appends each record as one JSON line to a file
+pub struct JsonlTrafficLogger {
+    file: Mutex<File>,
+}
+
+impl JsonlTrafficLogger {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl TrafficLogger for JsonlTrafficLogger {
+    fn log(&self, record: &TrafficRecord) {
+        let Ok(line) = serde_json::to_string(record) else {
+            return;
+        };
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// Logger configured by `NOEMA_TRAFFIC_LOG`, shared by every client
+pub(crate) fn env_logger() -> Option<Arc<dyn TrafficLogger>> {
+    static LOGGER: OnceLock<Option<Arc<dyn TrafficLogger>>> = OnceLock::new();
+    LOGGER
+        .get_or_init(|| {
+            let value = std::env::var(TRAFFIC_LOG_ENV).ok()?;
+            let path = match value.trim() {
+                "" | "0" | "false" => return None,
+                "1" | "true" => PathManager::traffic_log_path()?,
+                path => path.into(),
+            };
+            match JsonlTrafficLogger::open(&path) {
+                Ok(logger) => Some(Arc::new(logger) as Arc<dyn TrafficLogger>),
+                Err(e) => {
+                    log_error("traffic", &format!("Cannot open {}: {}", path.display(), e));
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
+/// Replace credential values anywhere in a JSON body
+pub fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    if SECRET_KEYS.contains(&key.to_ascii_lowercase().as_str()) {
+                        (key, Value::String(REDACTED.to_string()))
+                    } else {
+                        (key, redact(value))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        Value::String(s) if is_bearer_token(&s) => Value::String(REDACTED.to_string()),
+        other => other,
+    }
+}
+
+fn is_bearer_token(s: &str) -> bool {
+    s.strip_prefix("Bearer ")
+        .is_some_and(|token| !token.is_empty() && !token.contains(char::is_whitespace))
+}
 
 /// Log an LLM error
 pub fn log_error(model: &str, error: &str) {
     log_traffic("ERROR", &format!("[{}] {}", model, error));
 }
 
-/// Log an LLM streaming start (no-op, content not logged for privacy)
-pub fn log_stream_start(_model: &str, _request: &impl serde::Serialize) {}
-
-/// Log an LLM streaming response (no-op, content not logged for privacy)
-pub fn log_stream_response(_model: &str, _response: &impl serde::Serialize) {}
-
 /// Internal function to write to the log file
 fn log_traffic(event_type: &str, message: &str) {
     if let Some(log_path) = PathManager::log_file_path() {
@@ -35,7 +173,57 @@ fn log_traffic(event_type: &str, message: &str) {
             .open(&log_path)
         {
             let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-            let _ = writeln!(file, "[{}] [TRAFFIC] [LLM] [{}] {}", timestamp, event_type, message);
+            let _ = writeln!(file, "[{}] [TRAFFIC] [LLM] [{}] {}", timestamp, event_type, config::redact(message));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_credentials() {
+        let body = json!({
+            "model": "gpt-4o",
+            "api_key": "sk-123",
+            "headers": {"Authorization": "Bearer sk-123", "X-Api-Key": "sk-123"},
+            "messages": [{"role": "user", "content": "Bearer of bad news"}],
+            "auth": "Bearer sk-123",
+        });
+        assert_eq!(
+            redact(body),
+            json!({
+                "model": "gpt-4o",
+                "api_key": REDACTED,
+                "headers": {"Authorization": REDACTED, "X-Api-Key": REDACTED},
+                "messages": [{"role": "user", "content": "Bearer of bad news"}],
+                "auth": REDACTED,
+            })
+        );
+    }
+
+    #[test]
+    fn test_jsonl_logger_appends_records() {
+        let dir = std::env::temp_dir().join(format!("noema-traffic-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("traffic.jsonl");
+
+        let logger = JsonlTrafficLogger::open(&path).unwrap();
+        logger.log(&TrafficRecord::new("m", TrafficKind::Request, json!({"access_token": "t"})));
+        logger.log(&TrafficRecord::new("m", TrafficKind::Response, json!("ok")));
+
+        let lines: Vec<Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["model"], "m");
+        assert_eq!(lines[0]["kind"], "request");
+        assert_eq!(lines[0]["body"], json!({"access_token": REDACTED}));
+        assert_eq!(lines[1]["kind"], "response");
+        assert!(lines[1]["timestamp"].is_string());
+    }
+}
@@ -9,14 +9,87 @@ use crate::Agent;
 use crate::ConversationContext;
 use anyhow::Result;
 use async_trait::async_trait;
-use llm::{ChatMessage, ChatModel, ChatPayload, ChatRequest, ContentBlock, ToolResultContent};
+use llm::{
+    ChatChunk, ChatMessage, ChatModel, ChatPayload, ChatRequest, ChatStream, ContextWindowPolicy,
+    GenerationParams, ResponseFormat, StreamCollector, ToolCallDelta, ToolDefinition,
+    ToolResultContent,
+};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 /// Function that enriches tool call arguments before execution.
 /// Takes (tool_name, arguments, execution_context) and returns enriched arguments.
 pub type ToolEnricher =
     Arc<dyn Fn(&str, serde_json::Value, &ExecutionContext) -> serde_json::Value + Send + Sync>;
 
+/// Function receiving progress of a running tool call.
+/// Takes (tool_call_id, message), e.g. ("call_1", "pages (2/5)").
+pub type ToolProgressFn = Arc<dyn Fn(&str, String) + Send + Sync>;
+
+/// Function receiving each piece of assistant text as it streams in.
+/// Only the newly produced text is passed, never the accumulated message.
+pub type TextDeltaFn = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Function receiving each fragment of a tool call's arguments while the
+/// model is still generating them, e.g. to show "preparing to call search_web"
+pub type ToolCallDeltaFn = Arc<dyn Fn(&ToolCallDelta) + Send + Sync>;
+
+/// Function told how many history messages were dropped to fit a request
+/// into the model's context window.
+pub type HistoryTrimmedFn = Arc<dyn Fn(usize) + Send + Sync>;
+
+/// Answer to a request to run a tool call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolApproval {
+    Approve,
+    Deny,
+    /// Deny, telling the model why
+    DenyWithMessage(String),
+}
+
+/// Decides whether each tool call may run, e.g. by asking the user.
+///
+/// Without an approver every tool call runs.
+#[async_trait]
+pub trait ToolApprover: Send + Sync {
+    async fn approve(&self, tool_call: &llm::ToolCall) -> ToolApproval;
+}
+
+/// How long a tool call may run before it is abandoned, unless the
+/// providing server configures an override for the tool
+pub const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How much text from a single tool call is passed back to the model, unless
+/// the providing server configures an override
+pub const DEFAULT_MAX_TOOL_RESULT_BYTES: usize = 16 * 1024;
+
+/// Default number of model/tool-call rounds an agent runs within one turn
+pub const DEFAULT_MAX_ITERATIONS: usize = 10;
+
+/// Default number of tool calls from one model response that run at once.
+/// Tools may have side effects that depend on the order the model chose, so
+/// calls run one after another unless more are allowed.
+pub const DEFAULT_MAX_CONCURRENT_TOOLS: usize = 1;
+
+/// Error returned when the model is still calling tools after the agent's
+/// last allowed round. The transcript ends with a note saying so.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxIterationsExceeded(pub usize);
+
+impl std::fmt::Display for MaxIterationsExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Stopped after {} tool-call rounds: the model kept calling tools without answering",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for MaxIterationsExceeded {}
+
 /// Agent that dynamically uses tools from connected MCP servers.
 ///
 /// All tools (including spawn_agent) come from MCP servers registered
@@ -31,6 +104,19 @@ pub struct McpAgent {
     document_formatter: DocumentFormatter,
     execution_context: ExecutionContext,
     enricher: Option<ToolEnricher>,
+    cancel_token: Option<CancellationToken>,
+    on_progress: Option<ToolProgressFn>,
+    on_text_delta: Option<TextDeltaFn>,
+    on_tool_call_delta: Option<ToolCallDeltaFn>,
+    tool_timeout: Duration,
+    max_tool_result_bytes: usize,
+    max_concurrent_tools: usize,
+    generation_params: GenerationParams,
+    cache_system_prompt: bool,
+    response_format: ResponseFormat,
+    context_window_policy: Option<ContextWindowPolicy>,
+    on_history_trimmed: Option<HistoryTrimmedFn>,
+    approver: Option<Arc<dyn ToolApprover>>,
 }
 
 impl McpAgent {
@@ -47,6 +133,19 @@ impl McpAgent {
             document_formatter: DocumentFormatter,
             execution_context,
             enricher: None,
+            cancel_token: None,
+            on_progress: None,
+            on_text_delta: None,
+            on_tool_call_delta: None,
+            tool_timeout: DEFAULT_TOOL_TIMEOUT,
+            max_tool_result_bytes: DEFAULT_MAX_TOOL_RESULT_BYTES,
+            max_concurrent_tools: DEFAULT_MAX_CONCURRENT_TOOLS,
+            generation_params: GenerationParams::default(),
+            cache_system_prompt: false,
+            response_format: ResponseFormat::Text,
+            context_window_policy: Some(ContextWindowPolicy::default()),
+            on_history_trimmed: None,
+            approver: None,
         }
     }
 
@@ -68,6 +167,208 @@ impl McpAgent {
             document_formatter: DocumentFormatter,
             execution_context,
             enricher: Some(enricher),
+            cancel_token: None,
+            on_progress: None,
+            on_text_delta: None,
+            on_tool_call_delta: None,
+            tool_timeout: DEFAULT_TOOL_TIMEOUT,
+            max_tool_result_bytes: DEFAULT_MAX_TOOL_RESULT_BYTES,
+            max_concurrent_tools: DEFAULT_MAX_CONCURRENT_TOOLS,
+            generation_params: GenerationParams::default(),
+            cache_system_prompt: false,
+            response_format: ResponseFormat::Text,
+            context_window_policy: Some(ContextWindowPolicy::default()),
+            on_history_trimmed: None,
+            approver: None,
+        }
+    }
+
+    /// Stop streaming when the token is cancelled.
+    ///
+    /// On cancellation the text streamed so far is added to the context as a
+    /// partial assistant message and no further tool calls are made.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Report progress notifications from MCP servers while tools run.
+    pub fn with_progress(mut self, on_progress: ToolProgressFn) -> Self {
+        self.on_progress = Some(on_progress);
+        self
+    }
+
+    /// Report assistant text incrementally while the model streams.
+    pub fn with_text_delta(mut self, on_text_delta: TextDeltaFn) -> Self {
+        self.on_text_delta = Some(on_text_delta);
+        self
+    }
+
+    /// Report tool-call arguments incrementally while the model streams them.
+    ///
+    /// The call only runs once it is complete; this is for showing it early.
+    pub fn with_tool_call_delta(mut self, on_tool_call_delta: ToolCallDeltaFn) -> Self {
+        self.on_tool_call_delta = Some(on_tool_call_delta);
+        self
+    }
+
+    /// Default timeout for each tool call (see [`DEFAULT_TOOL_TIMEOUT`]).
+    ///
+    /// A timed-out call produces an error tool result so the model can react.
+    pub fn with_tool_timeout(mut self, timeout: Duration) -> Self {
+        self.tool_timeout = timeout;
+        self
+    }
+
+    /// Default limit on tool-result text (see [`DEFAULT_MAX_TOOL_RESULT_BYTES`]).
+    ///
+    /// Longer text is cut off with a "[truncated N bytes]" marker; images and
+    /// audio are passed through unchanged.
+    pub fn with_max_tool_result_bytes(mut self, limit: usize) -> Self {
+        self.max_tool_result_bytes = limit;
+        self
+    }
+
+    /// How many tool calls from one model response may run at once (see
+    /// [`DEFAULT_MAX_CONCURRENT_TOOLS`]); 1 runs them one after another.
+    ///
+    /// Each call is still approved and timed out on its own, and results are
+    /// added in the order the model made the calls.
+    pub fn with_max_concurrent_tools(mut self, limit: usize) -> Self {
+        self.max_concurrent_tools = limit.max(1);
+        self
+    }
+
+    /// Sampling settings sent with every model request.
+    pub fn with_generation_params(mut self, params: GenerationParams) -> Self {
+        self.generation_params = params;
+        self
+    }
+
+    /// Ask providers that support it to cache the system prompt across
+    /// requests (see `ChatRequest::with_cache_system_prompt`).
+    pub fn with_cache_system_prompt(mut self, cache: bool) -> Self {
+        self.cache_system_prompt = cache;
+        self
+    }
+
+    /// Require the final reply to have this format (free-form text by default).
+    ///
+    /// A reply that doesn't parse, or doesn't match the schema, ends the run
+    /// with an error after it is added to the context.
+    pub fn with_response_format(mut self, format: ResponseFormat) -> Self {
+        self.response_format = format;
+        self
+    }
+
+    /// How history is trimmed when a request exceeds the model's context
+    /// window. `None` sends the full history regardless.
+    pub fn with_context_window_policy(mut self, policy: Option<ContextWindowPolicy>) -> Self {
+        self.context_window_policy = policy;
+        self
+    }
+
+    /// Report when older history is dropped to fit the context window.
+    pub fn with_history_trimmed(mut self, on_history_trimmed: HistoryTrimmedFn) -> Self {
+        self.on_history_trimmed = Some(on_history_trimmed);
+        self
+    }
+
+    /// Ask `approver` before running each tool call.
+    ///
+    /// A denied call isn't run; its result is an error telling the model the
+    /// user denied it. Cancelling while waiting for an answer denies the call.
+    pub fn with_tool_approval(mut self, approver: Arc<dyn ToolApprover>) -> Self {
+        self.approver = Some(approver);
+        self
+    }
+
+    /// Ask the approver (if any) whether a tool call may run
+    async fn approval_for(&self, tool_call: &llm::ToolCall) -> ToolApproval {
+        let Some(approver) = &self.approver else {
+            return ToolApproval::Approve;
+        };
+        match &self.cancel_token {
+            Some(token) => tokio::select! {
+                approval = approver.approve(tool_call) => approval,
+                _ = token.cancelled() => ToolApproval::Deny,
+            },
+            None => approver.approve(tool_call).await,
+        }
+    }
+
+    /// Note the iteration limit in the transcript and build the error to return
+    fn iteration_limit_reached(&self, context: &mut dyn ConversationContext) -> anyhow::Error {
+        tracing::warn!(
+            "McpAgent reached max iterations ({}), stopping",
+            self.max_iterations
+        );
+        context.add(ChatMessage::assistant(ChatPayload::text(format!(
+            "[Stopped after {} tool-call rounds]",
+            self.max_iterations
+        ))));
+        MaxIterationsExceeded(self.max_iterations).into()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel_token.as_ref().is_some_and(|t| t.is_cancelled())
+    }
+
+    /// Next chunk from the stream, or None once the stream ends or is cancelled
+    async fn next_chunk(&self, stream: &mut ChatStream) -> Option<Result<ChatChunk>> {
+        use futures::StreamExt;
+
+        match &self.cancel_token {
+            Some(token) => tokio::select! {
+                chunk = stream.next() => chunk,
+                _ = token.cancelled() => None,
+            },
+            None => stream.next().await,
+        }
+    }
+
+    /// Apply the agent's request settings
+    fn configure(&self, request: ChatRequest) -> ChatRequest {
+        request
+            .with_params(self.generation_params.clone())
+            .with_cache_system_prompt(self.cache_system_prompt)
+            .with_response_format(self.response_format.clone())
+    }
+
+    /// Collector for a streamed response, cutting text at the request's stop
+    /// sequences for providers that stream past them
+    fn stream_collector(&self) -> StreamCollector {
+        StreamCollector::with_stop_sequences(self.generation_params.stop.as_deref().unwrap_or_default())
+    }
+
+    /// Stream `model`'s response to `request`, reporting text and tool-call
+    /// deltas as they arrive; stops early on cancellation
+    async fn stream_response(
+        &self,
+        model: &(dyn ChatModel + Send + Sync),
+        request: &ChatRequest,
+    ) -> Result<StreamCollector> {
+        let mut stream = model.stream_chat(request).await?;
+
+        let mut collector = self.stream_collector();
+        while let Some(chunk) = self.next_chunk(&mut stream).await {
+            let chunk = chunk?;
+            if let (Some(delta), Some(on_tool_call_delta)) = (&chunk.tool_call_delta, &self.on_tool_call_delta) {
+                on_tool_call_delta(delta);
+            }
+            self.emit_text(&collector.push(chunk));
+        }
+        self.emit_text(&collector.flush());
+        Ok(collector)
+    }
+
+    /// Report streamed text
+    fn emit_text(&self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if let Some(on_text_delta) = &self.on_text_delta {
+            on_text_delta(text);
         }
     }
 
@@ -90,46 +391,64 @@ impl McpAgent {
         context: &mut dyn ConversationContext,
         model: Arc<dyn ChatModel + Send + Sync>,
     ) -> Result<()> {
-        use futures::StreamExt;
-
         let messages = context.messages().await?;
-        let mut request = ChatRequest::new(messages.iter());
+        let mut request = self.configure(ChatRequest::new(messages.iter()));
 
         self.resolve_documents(&mut request).await;
+        self.fit_context_window(&mut request, model.as_ref());
 
-        let mut stream = model.stream_chat(&request).await?;
-
-        let mut accumulated_text = String::new();
-        let mut other_blocks: Vec<ContentBlock> = Vec::new();
-        let mut role = llm::api::Role::default();
-
-        while let Some(chunk) = stream.next().await {
-            role = chunk.role;
-            for block in chunk.payload.content {
-                match block {
-                    ContentBlock::Text { text } => {
-                        accumulated_text.push_str(&text);
-                    }
-                    other => {
-                        other_blocks.push(other);
-                    }
-                }
-            }
-        }
+        let collector = self
+            .stream_response(model.as_ref(), &request)
+            .instrument(tracing::debug_span!("model_call", iteration = 0))
+            .await?;
 
-        let mut content = Vec::new();
-        if !accumulated_text.is_empty() {
-            content.push(ContentBlock::Text { text: accumulated_text });
+        let cancelled = self.is_cancelled();
+        if cancelled && collector.text().is_empty() {
+            return Ok(());
         }
-        content.extend(other_blocks);
-
-        let accumulated = ChatMessage::new(role, ChatPayload::new(content));
+        let accumulated = if cancelled { collector.into_text_message() } else { collector.into_message() };
 
         traffic_log::log_llm_response(model.name(), &accumulated);
+        if let Some(blocked) = accumulated.blocked() {
+            return Err(blocked.into());
+        }
 
+        let text = accumulated.get_text();
         context.add(accumulated);
 
-        Ok(())
+        if cancelled {
+            return Ok(());
+        }
+        self.response_format.validate(&text)
+    }
+
+    /// Tool definitions to send to `model`; none if it cannot call tools
+    async fn tool_definitions_for(&self, model: &(dyn ChatModel + Send + Sync)) -> Vec<ToolDefinition> {
+        let definitions = self.tools.get_all_definitions().await;
+        if definitions.is_empty() || model.supports_tools() {
+            return definitions;
+        }
+        tracing::warn!(
+            "Model {} does not support tools, sending the request without {} tool(s)",
+            model.id(),
+            definitions.len()
+        );
+        Vec::new()
+    }
+
+    /// Drop the oldest history if the request would overflow the model's
+    /// context window, reporting how many messages were removed
+    fn fit_context_window(&self, request: &mut ChatRequest, model: &dyn ChatModel) {
+        let (Some(policy), Some(window)) = (&self.context_window_policy, model.context_window())
+        else {
+            return;
+        };
+        let dropped = policy.trim(request, window);
+        if dropped > 0 {
+            if let Some(on_history_trimmed) = &self.on_history_trimmed {
+                on_history_trimmed(dropped);
+            }
+        }
     }
 
     async fn resolve_documents(&self, request: &mut ChatRequest) {
@@ -148,10 +467,29 @@ impl McpAgent {
     }
 
     /// Process a single tool call via MCP registry
+    #[tracing::instrument(
+        level = "debug",
+        name = "tool_call",
+        skip_all,
+        fields(tool = %tool_call.name, id = %tool_call.id)
+    )]
     async fn process_single_tool_call(
         &self,
         tool_call: &llm::ToolCall,
     ) -> Vec<ToolResultContent> {
+        match self.approval_for(tool_call).await {
+            ToolApproval::Approve => {}
+            ToolApproval::Deny => {
+                return vec![ToolResultContent::text("Error: User denied the tool call")];
+            }
+            ToolApproval::DenyWithMessage(message) => {
+                return vec![ToolResultContent::text(format!(
+                    "Error: User denied the tool call: {}",
+                    message
+                ))];
+            }
+        }
+
         // Apply enricher if present
         let args = match &self.enricher {
             Some(enricher) => {
@@ -160,26 +498,100 @@ impl McpAgent {
             None => tool_call.arguments.clone(),
         };
 
-        self.tools
-            .call(&tool_call.name, args)
+        let timeout = self
+            .tools
+            .tool_timeout(&tool_call.name)
+            .await
+            .unwrap_or(self.tool_timeout);
+        let report = |message: String| {
+            if let Some(on_progress) = &self.on_progress {
+                on_progress(&tool_call.id, message);
+            }
+        };
+        let call = self.tools.call_with_progress(&tool_call.name, args, &report);
+
+        let result = match tokio::time::timeout(timeout, call).await {
+            Ok(result) => result,
+            Err(_) => {
+                traffic_log::log_mcp_error(&tool_call.name, "timed out");
+                Err(anyhow::anyhow!(
+                    "Tool '{}' timed out after {}s",
+                    tool_call.name,
+                    timeout.as_secs_f64()
+                ))
+            }
+        };
+
+        let content =
+            result.unwrap_or_else(|e| vec![ToolResultContent::text(format!("Error: {}", e))]);
+
+        let limit = self
+            .tools
+            .max_tool_result_bytes(&tool_call.name)
             .await
-            .unwrap_or_else(|e| vec![ToolResultContent::text(format!("Error: {}", e))])
+            .unwrap_or(self.max_tool_result_bytes);
+        truncate_tool_result(content, limit)
     }
 
+    #[tracing::instrument(level = "debug", name = "tool_calls", skip_all, fields(count = tool_calls.len()))]
     async fn process_tool_calls(
         &self,
         context: &mut dyn ConversationContext,
         tool_calls: Vec<&llm::ToolCall>,
     ) {
+        use futures::StreamExt;
+
+        // `buffered` yields results in call order however the calls finish;
+        // a failed call is an error result and doesn't affect the others
+        let mut calls = Vec::with_capacity(tool_calls.len());
         for tool_call in tool_calls {
-            let result_content = self.process_single_tool_call(tool_call).await;
+            calls.push(async move { (tool_call.id.clone(), self.process_single_tool_call(tool_call).await) });
+        }
+        let mut results = futures::stream::iter(calls).buffered(self.max_concurrent_tools);
+
+        while let Some((id, result_content)) = results.next().await {
+            context.add(ChatMessage::user(ChatPayload::tool_result(id, result_content)));
+        }
+    }
+}
 
-            let result_msg =
-                ChatMessage::user(ChatPayload::tool_result(tool_call.id.clone(), result_content));
+/// Keep at most `limit` bytes of text across a tool result's text blocks
+///
+/// Text past the limit is dropped and a "[truncated N bytes]" block is appended.
+/// Non-text content does not count towards the limit and is always kept.
+fn truncate_tool_result(content: Vec<ToolResultContent>, limit: usize) -> Vec<ToolResultContent> {
+    let mut remaining = limit;
+    let mut truncated = 0;
+    let mut result = Vec::with_capacity(content.len() + 1);
 
-            context.add(result_msg);
+    for block in content {
+        match block {
+            ToolResultContent::Text { mut text } if text.len() > remaining => {
+                let mut keep = remaining;
+                while !text.is_char_boundary(keep) {
+                    keep -= 1;
+                }
+                truncated += text.len() - keep;
+                // Nothing after a cut is kept, even if it would fit
+                remaining = 0;
+                if keep > 0 {
+                    text.truncate(keep);
+                    result.push(ToolResultContent::Text { text });
+                }
+            }
+            ToolResultContent::Text { text } => {
+                remaining -= text.len();
+                result.push(ToolResultContent::Text { text });
+            }
+            other => result.push(other),
         }
     }
+
+    if truncated > 0 {
+        tracing::debug!("Truncated {} bytes of tool result text", truncated);
+        result.push(ToolResultContent::text(format!("\n[truncated {} bytes]", truncated)));
+    }
+    result
 }
 
 #[async_trait]
@@ -190,33 +602,34 @@ impl Agent for McpAgent {
         model: Arc<dyn ChatModel + Send + Sync>,
     ) -> Result<()> {
         for iteration in 0..self.max_iterations {
-            let tool_definitions = self.tools.get_all_definitions().await;
+            let tool_definitions = self.tool_definitions_for(model.as_ref()).await;
 
             let messages = context.messages().await?;
-            let mut request = if tool_definitions.is_empty() {
+            let mut request = self.configure(if tool_definitions.is_empty() {
                 ChatRequest::new(messages.iter())
             } else {
                 ChatRequest::with_tools(messages.iter(), tool_definitions)
-            };
+            });
 
             self.resolve_documents(&mut request).await;
+            self.fit_context_window(&mut request, model.as_ref());
 
-            let response = model.chat(&request).await?;
+            let response = model
+                .chat(&request)
+                .instrument(tracing::debug_span!("model_call", iteration))
+                .await?;
             let tool_calls = response.get_tool_calls();
 
             context.add(response.clone());
 
             if tool_calls.is_empty() {
-                break;
+                return self.response_format.validate(&response.get_text());
             }
 
             self.process_tool_calls(context, tool_calls).await;
 
             if iteration == self.max_iterations - 1 {
-                tracing::warn!(
-                    "McpAgent reached max iterations ({}), stopping",
-                    self.max_iterations
-                );
+                return Err(self.iteration_limit_reached(context));
             }
         }
 
@@ -228,68 +641,544 @@ impl Agent for McpAgent {
         context: &mut dyn ConversationContext,
         model: Arc<dyn ChatModel + Send + Sync>,
     ) -> Result<()> {
-        use futures::StreamExt;
-
         for iteration in 0..self.max_iterations {
-            let tool_definitions = self.tools.get_all_definitions().await;
+            let tool_definitions = self.tool_definitions_for(model.as_ref()).await;
 
             let messages = context.messages().await?;
-            let mut request = if tool_definitions.is_empty() {
+            let mut request = self.configure(if tool_definitions.is_empty() {
                 ChatRequest::new(messages.iter())
             } else {
                 ChatRequest::with_tools(messages.iter(), tool_definitions)
-            };
+            });
 
             self.resolve_documents(&mut request).await;
+            self.fit_context_window(&mut request, model.as_ref());
 
-            let mut stream = model.stream_chat(&request).await?;
-
-            let mut accumulated_text = String::new();
-            let mut other_blocks: Vec<ContentBlock> = Vec::new();
-            let mut role = llm::api::Role::default();
-
-            while let Some(chunk) = stream.next().await {
-                role = chunk.role;
-                for block in chunk.payload.content {
-                    match block {
-                        ContentBlock::Text { text } => {
-                            accumulated_text.push_str(&text);
-                        }
-                        other => {
-                            other_blocks.push(other);
-                        }
-                    }
-                }
-            }
+            let collector = self
+                .stream_response(model.as_ref(), &request)
+                .instrument(tracing::debug_span!("model_call", iteration))
+                .await?;
 
-            let mut content = Vec::new();
-            if !accumulated_text.is_empty() {
-                content.push(ContentBlock::Text { text: accumulated_text });
+            // A cancelled response keeps only its text: tool calls may be incomplete
+            let cancelled = self.is_cancelled();
+            if cancelled && collector.text().is_empty() {
+                break;
             }
-            content.extend(other_blocks);
-
-            let accumulated = ChatMessage::new(role, ChatPayload::new(content));
+            let accumulated = if cancelled { collector.into_text_message() } else { collector.into_message() };
 
             traffic_log::log_llm_response(model.name(), &accumulated);
+            // Nothing to keep: the empty answer would only read as silence
+            if let Some(blocked) = accumulated.blocked() {
+                return Err(blocked.into());
+            }
 
             context.add(accumulated.clone());
 
             let tool_calls = accumulated.get_tool_calls();
 
-            if tool_calls.is_empty() {
+            if self.is_cancelled() {
                 break;
             }
+            if tool_calls.is_empty() {
+                return self.response_format.validate(&accumulated.get_text());
+            }
 
             self.process_tool_calls(context, tool_calls).await;
 
             if iteration == self.max_iterations - 1 {
-                tracing::warn!(
-                    "McpAgent reached max iterations ({}), stopping",
-                    self.max_iterations
-                );
+                return Err(self.iteration_limit_reached(context));
             }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::{McpConfig, McpRegistry, ServerConfig, Transport};
+    use crate::storage::implementations::memory::{MemoryDocumentStore, MemoryStorage};
+    use crate::storage::session::Session;
+    use llm::mock::MockChatModel;
+    use llm::{ContentBlock, FinishReason};
+    use rmcp::model::{
+        CallToolRequestParam, CallToolResult, Content, ListToolsResult, PaginatedRequestParam,
+        ServerCapabilities, ServerInfo, Tool,
+    };
+    use rmcp::service::RequestContext;
+    use rmcp::{ErrorData, RoleServer, ServerHandler, ServiceExt};
+    use std::collections::HashMap;
+    use tokio::sync::Mutex;
+
+    /// MCP server with a tool that never finishes in time ("slow"), one
+    /// returning a megabyte of text plus an image ("huge") and one that only
+    /// returns once two calls to it are running ("meet")
+    struct SlowServer {
+        meeting: Arc<tokio::sync::Barrier>,
+    }
+
+    impl ServerHandler for SlowServer {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo {
+                capabilities: ServerCapabilities::builder().enable_tools().build(),
+                ..Default::default()
+            }
+        }
+
+        async fn list_tools(
+            &self,
+            _request: Option<PaginatedRequestParam>,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ListToolsResult, ErrorData> {
+            let schema = serde_json::json!({ "type": "object" });
+            let schema = Arc::new(schema.as_object().unwrap().clone());
+            let tools = vec![
+                Tool::new("slow", "Sleeps forever", schema.clone()),
+                Tool::new("huge", "Returns a lot of text", schema.clone()),
+                Tool::new("meet", "Waits for a second call", schema),
+            ];
+            Ok(ListToolsResult::with_all_items(tools))
+        }
+
+        async fn call_tool(
+            &self,
+            request: CallToolRequestParam,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, ErrorData> {
+            if request.name == "huge" {
+                return Ok(CallToolResult::success(vec![
+                    Content::text("é".repeat(512 * 1024)),
+                    Content::image("aW1hZ2U=", "image/png"),
+                    Content::text("tail"),
+                ]));
+            }
+            if request.name == "meet" {
+                self.meeting.wait().await;
+                return Ok(CallToolResult::success(vec![Content::text("met")]));
+            }
+            tokio::time::sleep(Duration::from_secs(300)).await;
+            Ok(CallToolResult::success(vec![Content::text("done")]))
+        }
+    }
+
+    /// Agent whose registry holds a connected in-process SlowServer
+    async fn agent_with_slow_server(tool_timeouts: HashMap<String, u64>) -> McpAgent {
+        agent_with_server_config(|config| config.tool_timeouts = tool_timeouts).await
+    }
+
+    async fn agent_with_server_config(configure: impl FnOnce(&mut ServerConfig)) -> McpAgent {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            let server = SlowServer { meeting: Arc::new(tokio::sync::Barrier::new(2)) };
+            if let Ok(server) = server.serve(server_io).await {
+                let _ = server.waiting().await;
+            }
+        });
+
+        let mut config = ServerConfig {
+            name: "slow".to_string(),
+            transport: Transport::Stdio {
+                command: "unused".to_string(),
+                args: Vec::new(),
+                env: HashMap::new(),
+            },
+            auth: Default::default(),
+            use_well_known: false,
+            auth_token: None,
+            auto_connect: false,
+            auto_retry: false,
+            tool_filter: None,
+            tool_timeouts: HashMap::new(),
+            max_tool_result_bytes: None,
+            allow_sampling: false,
+        };
+        configure(&mut config);
+        let server = McpRegistry::connect_with_transport(&config, None, client_io).await.unwrap();
+        let mut registry = McpRegistry::new(McpConfig::default());
+        registry.store_connection("slow", server);
+
+        let tools = Arc::new(McpToolRegistry::new(Arc::new(Mutex::new(registry))));
+        McpAgent::new(
+            tools,
+            10,
+            Arc::new(MemoryDocumentStore::new()),
+            ExecutionContext::new(),
+        )
+    }
+
+    fn slow_call() -> llm::ToolCall {
+        tool_call("slow")
+    }
+
+    fn tool_call(name: &str) -> llm::ToolCall {
+        llm::ToolCall {
+            id: "call_1".to_string(),
+            name: name.to_string(),
+            arguments: serde_json::json!({}),
+            extra: serde_json::Value::Null,
+        }
+    }
+
+    fn result_text(content: &[ToolResultContent]) -> String {
+        content
+            .iter()
+            .filter_map(|c| match c {
+                ToolResultContent::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_slow_tool_times_out_with_error_result() {
+        let agent = agent_with_slow_server(HashMap::new())
+            .await
+            .with_tool_timeout(Duration::from_millis(100));
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            agent.process_single_tool_call(&slow_call()),
+        )
+        .await
+        .expect("tool call should not hang");
+
+        assert!(result_text(&result).contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_server_tool_timeout_overrides_agent_default() {
+        let agent = agent_with_slow_server(HashMap::from([("slow".to_string(), 1)])).await;
+        assert_eq!(agent.tool_timeout, DEFAULT_TOOL_TIMEOUT);
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            agent.process_single_tool_call(&slow_call()),
+        )
+        .await
+        .expect("server override should apply");
+
+        assert!(result_text(&result).contains("timed out after 1s"));
+    }
+
+    #[tokio::test]
+    async fn test_huge_tool_result_is_truncated() {
+        let agent = agent_with_slow_server(HashMap::new()).await;
+        let result = agent.process_single_tool_call(&tool_call("huge")).await;
+
+        // One megabyte of text in, 16KB out; the image survives
+        let text = result_text(&result);
+        let kept = DEFAULT_MAX_TOOL_RESULT_BYTES;
+        assert!(text.starts_with(&"é".repeat(kept / 2)));
+        assert!(text.ends_with(&format!("\n[truncated {} bytes]", 1024 * 1024 + 4 - kept)));
+        assert!(!text.contains("tail"));
+        assert!(result.iter().any(|c| matches!(c, ToolResultContent::Image { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_server_result_limit_overrides_agent_default() {
+        let agent = agent_with_server_config(|config| config.max_tool_result_bytes = Some(5)).await;
+        let result = agent.process_single_tool_call(&tool_call("huge")).await;
+
+        // 5 bytes would split a two-byte character, so only 4 are kept
+        assert_eq!(
+            result_text(&result),
+            format!("éé\n[truncated {} bytes]", 1024 * 1024 + 4 - 4)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_run_concurrently_in_order() {
+        let mut session = memory_session().await;
+        let agent = agent_with_slow_server(HashMap::new())
+            .await
+            .with_tool_timeout(Duration::from_secs(5))
+            .with_max_concurrent_tools(3);
+        let calls: Vec<llm::ToolCall> = [("a", "meet"), ("b", "huge"), ("c", "meet")]
+            .into_iter()
+            .map(|(id, name)| llm::ToolCall { id: id.to_string(), ..tool_call(name) })
+            .collect();
+
+        // Run one after another, the first "meet" would wait alone until it timed out
+        agent.process_tool_calls(&mut session, calls.iter().collect()).await;
+
+        let results: Vec<(String, String)> = session
+            .pending()
+            .iter()
+            .flat_map(|message| message.payload.content.iter())
+            .filter_map(|block| match block {
+                ContentBlock::ToolResult(result) => Some((result.tool_call_id.clone(), result.get_text())),
+                _ => None,
+            })
+            .collect();
+        let ids: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(results[0].1, "met");
+        assert!(results[1].1.contains("[truncated"));
+        assert_eq!(results[2].1, "met");
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_run_one_at_a_time_by_default() {
+        let mut session = memory_session().await;
+        let agent = agent_with_slow_server(HashMap::new())
+            .await
+            .with_tool_timeout(Duration::from_millis(200));
+        let calls: Vec<llm::ToolCall> = ["a", "b"]
+            .into_iter()
+            .map(|id| llm::ToolCall { id: id.to_string(), ..tool_call("meet") })
+            .collect();
+
+        // The first "meet" waits alone, since the second only starts after it
+        agent.process_tool_calls(&mut session, calls.iter().collect()).await;
+
+        let first = session
+            .pending()
+            .iter()
+            .flat_map(|message| message.payload.content.iter())
+            .find_map(|block| match block {
+                ContentBlock::ToolResult(result) => Some(result.get_text()),
+                _ => None,
+            })
+            .unwrap();
+        assert!(first.contains("timed out"), "{}", first);
+    }
+
+    #[test]
+    fn test_small_tool_result_is_untouched() {
+        let content = vec![ToolResultContent::text("short"), ToolResultContent::image("aW1n", "image/png")];
+        let result = truncate_tool_result(content, 5);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result_text(&result), "short");
+    }
+
+    /// Approver that gives the same answer for every call
+    struct FixedApprover(ToolApproval);
+
+    #[async_trait]
+    impl ToolApprover for FixedApprover {
+        async fn approve(&self, _tool_call: &llm::ToolCall) -> ToolApproval {
+            self.0.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_denied_tool_call_is_not_run() {
+        let agent = agent_with_server_config(|config| config.max_tool_result_bytes = Some(5))
+            .await
+            .with_tool_approval(Arc::new(FixedApprover(ToolApproval::DenyWithMessage(
+                "not now".to_string(),
+            ))));
+        let result = agent.process_single_tool_call(&tool_call("huge")).await;
+        assert_eq!(result_text(&result), "Error: User denied the tool call: not now");
+
+        let agent = agent.with_tool_approval(Arc::new(FixedApprover(ToolApproval::Approve)));
+        let result = agent.process_single_tool_call(&tool_call("huge")).await;
+        assert!(result_text(&result).starts_with("éé"));
+    }
+
+    /// Session for a new in-memory conversation holding one user message
+    async fn memory_session() -> Session<MemoryStorage> {
+        use crate::storage::coordinator::memory_coordinator;
+        use crate::storage::ids::UserId;
+
+        let coordinator = Arc::new(memory_coordinator());
+        let conversation_id = coordinator.create_conversation(&UserId::new(), None).await.unwrap();
+        let mut session = Session::new(coordinator, conversation_id);
+        session.add(ChatMessage::user(ChatPayload::text("hi")));
+        session
+    }
+
+    /// Agent without tools recording the text deltas it reports
+    fn delta_recording_agent(deltas: &Arc<std::sync::Mutex<Vec<String>>>) -> McpAgent {
+        let registry = McpRegistry::new(McpConfig::default());
+        McpAgent::new(
+            Arc::new(McpToolRegistry::new(Arc::new(Mutex::new(registry)))),
+            10,
+            Arc::new(MemoryDocumentStore::new()),
+            ExecutionContext::new(),
+        )
+        .with_text_delta({
+            let deltas = Arc::clone(deltas);
+            Arc::new(move |text: &str| deltas.lock().unwrap().push(text.to_string()))
+        })
+    }
+
+    #[tokio::test]
+    async fn test_text_deltas_carry_only_new_text() {
+        let mut session = memory_session().await;
+        let deltas = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let agent = delta_recording_agent(&deltas);
+
+        let model = Arc::new(MockChatModel::builder("scripted").chunks(&["Hel", "", "lo"]).build());
+        agent.execute_stream_no_tools(&mut session, model).await.unwrap();
+
+        assert_eq!(*deltas.lock().unwrap(), vec!["Hel", "lo"]);
+        assert_eq!(session.pending().last().unwrap().get_text(), "Hello");
+    }
+
+    #[tokio::test]
+    async fn test_stop_sequence_truncates_streamed_text() {
+        let mut session = memory_session().await;
+        let deltas = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let agent = delta_recording_agent(&deltas).with_generation_params(GenerationParams {
+            stop: Some(vec!["</json>".to_string()]),
+            ..Default::default()
+        });
+
+        // The model streams the stop sequence, split across chunks, and keeps going
+        let model = Arc::new(
+            MockChatModel::builder("scripted")
+                .chunks(&["{\"a\": 1}", "</js", "on>", " trailing"])
+                .build(),
+        );
+        agent.execute_stream(&mut session, model).await.unwrap();
+
+        assert_eq!(deltas.lock().unwrap().concat(), "{\"a\": 1}");
+        assert_eq!(session.pending().last().unwrap().get_text(), "{\"a\": 1}");
+        assert_eq!(session.pending().last().unwrap().finish_reason, Some(FinishReason::Stop));
+    }
+
+    #[tokio::test]
+    async fn test_finish_reason_is_kept_on_the_response() {
+        let mut session = memory_session().await;
+        let deltas = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let agent = delta_recording_agent(&deltas);
+
+        // Cut off by the output token limit
+        let model = MockChatModel::builder("cut-off")
+            .raw_chunks(vec![
+                ChatChunk::assistant(ChatPayload::text("Once upon")),
+                ChatChunk::assistant(ChatPayload::new(Vec::new())).with_finish_reason(Some(FinishReason::Length)),
+            ])
+            .build();
+        agent.execute_stream_no_tools(&mut session, Arc::new(model)).await.unwrap();
+
+        let response = session.pending().last().unwrap();
+        assert_eq!(response.get_text(), "Once upon");
+        assert!(response.is_truncated());
+    }
+
+    #[tokio::test]
+    async fn test_blocked_response_is_an_error() {
+        let mut session = memory_session().await;
+        let deltas = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let agent = delta_recording_agent(&deltas);
+
+        // Withheld by a safety filter
+        let reason = FinishReason::ContentFilter { category: Some("HARM_CATEGORY_HARASSMENT".to_string()) };
+        let model = MockChatModel::builder("blocked")
+            .message(ChatMessage::assistant(ChatPayload::new(Vec::new())).with_finish_reason(Some(reason)))
+            .build();
+        let err = agent.execute_stream(&mut session, Arc::new(model)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<llm::ContentFiltered>(),
+            Some(&llm::ContentFiltered { category: Some("HARM_CATEGORY_HARASSMENT".to_string()) })
+        );
+        assert!(session.pending().iter().all(|msg| msg.role != llm::api::Role::Assistant));
+    }
+
+    #[tokio::test]
+    async fn test_reply_not_matching_response_format_is_an_error() {
+        let mut session = memory_session().await;
+        let deltas = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let agent = delta_recording_agent(&deltas).with_response_format(ResponseFormat::JsonSchema(
+            serde_json::json!({"type": "object", "required": ["answer"]}),
+        ));
+
+        let model = Arc::new(
+            MockChatModel::builder("scripted")
+                .chunks(&["{\"answer\": ", "42}"])
+                .chunks(&["{\"reply\": 42}"])
+                .build(),
+        );
+        agent.execute_stream(&mut session, model.clone()).await.unwrap();
+
+        let err = agent.execute_stream(&mut session, model).await.unwrap_err().to_string();
+        assert!(err.contains("does not match the requested schema"), "{}", err);
+        assert!(err.contains("answer"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_stops_after_max_iterations() {
+        use crate::storage::coordinator::memory_coordinator;
+        use crate::storage::ids::UserId;
+        use crate::storage::session::Session;
+
+        let coordinator = Arc::new(memory_coordinator());
+        let conversation_id = coordinator.create_conversation(&UserId::new(), None).await.unwrap();
+
+        let registry = McpRegistry::new(McpConfig::default());
+        let agent = McpAgent::new(
+            Arc::new(McpToolRegistry::new(Arc::new(Mutex::new(registry)))),
+            3,
+            Arc::new(MemoryDocumentStore::new()),
+            ExecutionContext::new(),
+        );
+        // Answers every request with another tool call
+        let model: Arc<dyn ChatModel + Send + Sync> = Arc::new(
+            MockChatModel::builder("looping")
+                .message(ChatMessage::assistant(ChatPayload::new(vec![ContentBlock::ToolCall(slow_call())])))
+                .repeat_last()
+                .build(),
+        );
+
+        for streaming in [false, true] {
+            let mut session = Session::new(Arc::clone(&coordinator), conversation_id.clone());
+            session.add(ChatMessage::user(ChatPayload::text("search forever")));
+
+            let result = tokio::time::timeout(Duration::from_secs(5), async {
+                if streaming {
+                    agent.execute_stream(&mut session, Arc::clone(&model)).await
+                } else {
+                    agent.execute(&mut session, Arc::clone(&model)).await
+                }
+            })
+            .await
+            .expect("agent should terminate");
+
+            let err = result.unwrap_err();
+            assert_eq!(err.downcast_ref::<MaxIterationsExceeded>(), Some(&MaxIterationsExceeded(3)));
+
+            // user + 3 x (tool call, tool result) + note
+            let pending = session.pending();
+            assert_eq!(pending.len(), 8);
+            assert_eq!(pending.last().unwrap().get_text(), "[Stopped after 3 tool-call rounds]");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tools_not_sent_to_model_without_tool_support() {
+        use crate::storage::coordinator::memory_coordinator;
+        use crate::storage::ids::UserId;
+        use crate::storage::session::Session;
+
+        let coordinator = Arc::new(memory_coordinator());
+        let conversation_id = coordinator.create_conversation(&UserId::new(), None).await.unwrap();
+
+        let agent = agent_with_slow_server(HashMap::new()).await;
+        assert_eq!(agent.tools.get_all_definitions().await.len(), 3);
+
+        let model = Arc::new(
+            MockChatModel::builder("no-tools")
+                .text("no tools here")
+                .repeat_last()
+                .without_tool_support()
+                .build(),
+        );
+        for streaming in [false, true] {
+            let mut session = Session::new(Arc::clone(&coordinator), conversation_id.clone());
+            session.add(ChatMessage::user(ChatPayload::text("hi")));
+            let dyn_model: Arc<dyn ChatModel + Send + Sync> = model.clone();
+            if streaming {
+                agent.execute_stream(&mut session, dyn_model).await.unwrap();
+            } else {
+                agent.execute(&mut session, dyn_model).await.unwrap();
+            }
+            assert_eq!(session.pending().last().unwrap().get_text(), "no tools here");
+        }
+
+        assert!(model.requests().iter().all(|request| request.tools().is_none_or(|tools| tools.is_empty())));
+        assert_eq!(model.request_count(), 2);
+    }
+}
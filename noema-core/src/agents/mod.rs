@@ -4,4 +4,8 @@ mod execution_context;
 pub mod mcp_agent;
 
 pub use execution_context::ExecutionContext;
-pub use mcp_agent::{McpAgent, ToolEnricher};
+pub use mcp_agent::{
+    HistoryTrimmedFn, McpAgent, MaxIterationsExceeded, TextDeltaFn, ToolApproval, ToolApprover, ToolCallDeltaFn,
+    ToolEnricher, ToolProgressFn, DEFAULT_MAX_CONCURRENT_TOOLS, DEFAULT_MAX_ITERATIONS, DEFAULT_MAX_TOOL_RESULT_BYTES,
+    DEFAULT_TOOL_TIMEOUT,
+};
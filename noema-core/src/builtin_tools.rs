@@ -0,0 +1,300 @@
+//! Built-in tools served in-process, without an MCP server
+//!
+//! `current_time` and `calculator` cover two things models are reliably bad
+//! at on their own. They are offered when `builtin_tools = true` is set in
+//! mcp.toml, and double as examples of registering local tools:
+//!
+//! ```ignore
+//! let mut tools = ToolRegistry::new();
+//! builtin_tools::register(&mut tools);
+//! mcp_registry.set_local_tools(Some(Arc::new(tools)));
+//! ```
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Local, Utc};
+use llm::{ToolDefinition, ToolRegistry};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Add `current_time` and `calculator` to a tool registry
+pub fn register(registry: &mut ToolRegistry) {
+    registry.register(current_time_def(), |args: Value| async move {
+        let args: CurrentTimeArgs = serde_json::from_value(args)?;
+        Ok(current_time(args.utc.unwrap_or(false)))
+    });
+    registry.register(calculator_def(), |args: Value| async move {
+        let args: CalculatorArgs = serde_json::from_value(args)?;
+        let value = evaluate(&args.expression)?;
+        Ok(format_number(value))
+    });
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct CurrentTimeArgs {
+    /// Give the time in UTC instead of the user's local time zone
+    utc: Option<bool>,
+}
+
+fn current_time_def() -> ToolDefinition {
+    ToolDefinition {
+        name: "current_time".to_string(),
+        description: Some(
+            "Get the current date and time (RFC 3339, with the weekday). \
+             Use this whenever the answer depends on today's date or the time."
+                .to_string(),
+        ),
+        input_schema: schemars::schema_for!(CurrentTimeArgs),
+    }
+}
+
+fn current_time(utc: bool) -> String {
+    if utc {
+        Utc::now().format("%Y-%m-%dT%H:%M:%SZ (%A)").to_string()
+    } else {
+        Local::now().format("%Y-%m-%dT%H:%M:%S%:z (%A)").to_string()
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct CalculatorArgs {
+    /// Arithmetic expression such as "(3 + 4.5) * 2^10 / sqrt(2)"
+    expression: String,
+}
+
+fn calculator_def() -> ToolDefinition {
+    ToolDefinition {
+        name: "calculator".to_string(),
+        description: Some(
+            "Evaluate an arithmetic expression exactly instead of estimating it. \
+             Supports + - * / % ^, parentheses, pi, e and the functions sqrt, abs, \
+             ln, log10, exp, sin, cos, tan, floor, ceil and round."
+                .to_string(),
+        ),
+        input_schema: schemars::schema_for!(CalculatorArgs),
+    }
+}
+
+/// Print whole numbers without a fractional part
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Evaluate an arithmetic expression
+///
+/// Usual precedence: `^` (right-associative) binds tighter than unary minus,
+/// which binds tighter than `* / %`, then `+ -`.
+fn evaluate(expression: &str) -> Result<f64> {
+    let mut parser = Parser {
+        chars: expression.chars().filter(|c| !c.is_whitespace()).collect(),
+        pos: 0,
+    };
+    let value = parser.sum()?;
+    if let Some(c) = parser.peek() {
+        bail!("Unexpected '{}' at position {}", c, parser.pos + 1);
+    }
+    if !value.is_finite() {
+        bail!("Result is not a finite number");
+    }
+    Ok(value)
+}
+
+/// Recursive-descent parser over an expression with whitespace removed
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sum(&mut self) -> Result<f64> {
+        let mut value = self.product()?;
+        loop {
+            if self.eat('+') {
+                value += self.product()?;
+            } else if self.eat('-') {
+                value -= self.product()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<f64> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    bail!("Division by zero");
+                }
+                value /= divisor;
+            } else if self.eat('%') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    bail!("Division by zero");
+                }
+                value %= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64> {
+        if self.eat('-') {
+            Ok(-self.unary()?)
+        } else if self.eat('+') {
+            self.unary()
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<f64> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            // Right-associative, and -2 in 2^-2 is the exponent's sign
+            Ok(base.powf(self.unary()?))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<f64> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let value = self.sum()?;
+                if !self.eat(')') {
+                    bail!("Missing ')'");
+                }
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if c.is_ascii_alphabetic() => self.name(),
+            Some(c) => bail!("Unexpected '{}' at position {}", c, self.pos + 1),
+            None => bail!("Expression ended early"),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+        }
+        // Exponent, as in 1.5e3 or 2E-4
+        if self.peek().is_some_and(|c| c == 'e' || c == 'E') {
+            let sign = usize::from(matches!(self.chars.get(self.pos + 1), Some('-' | '+')));
+            if self.chars.get(self.pos + 1 + sign).is_some_and(char::is_ascii_digit) {
+                self.pos += 1 + sign;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse().map_err(|_| anyhow!("Invalid number '{}'", text))
+    }
+
+    fn name(&mut self) -> Result<f64> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphanumeric()) {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+        match name.as_str() {
+            "pi" => return Ok(std::f64::consts::PI),
+            "e" => return Ok(std::f64::consts::E),
+            _ => {}
+        }
+        let function: fn(f64) -> f64 = match name.as_str() {
+            "sqrt" => f64::sqrt,
+            "abs" => f64::abs,
+            "ln" => f64::ln,
+            "log10" | "log" => f64::log10,
+            "exp" => f64::exp,
+            "sin" => f64::sin,
+            "cos" => f64::cos,
+            "tan" => f64::tan,
+            "floor" => f64::floor,
+            "ceil" => f64::ceil,
+            "round" => f64::round,
+            _ => bail!("Unknown name '{}'", name),
+        };
+        if !self.eat('(') {
+            bail!("Expected '(' after {}", name);
+        }
+        let argument = self.sum()?;
+        if !self.eat(')') {
+            bail!("Missing ')'");
+        }
+        Ok(function(argument))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_evaluate_precedence() {
+        assert_eq!(evaluate("1 + 2 * 3").unwrap(), 7.0);
+        assert_eq!(evaluate("(1 + 2) * 3").unwrap(), 9.0);
+        assert_eq!(evaluate("2 ^ 3 ^ 2").unwrap(), 512.0);
+        assert_eq!(evaluate("-2^2").unwrap(), -4.0);
+        assert_eq!(evaluate("2^-1").unwrap(), 0.5);
+        assert_eq!(evaluate("10 - 4 - 3").unwrap(), 3.0);
+        assert_eq!(evaluate("7 % 4 * 2").unwrap(), 6.0);
+        assert_eq!(evaluate("1.5e3 / 3").unwrap(), 500.0);
+        assert_eq!(evaluate("sqrt(16) + abs(-2) * e^0").unwrap(), 6.0);
+        assert!((evaluate("sin(pi / 2)").unwrap() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_evaluate_errors() {
+        assert_eq!(evaluate("1 / 0").unwrap_err().to_string(), "Division by zero");
+        assert_eq!(evaluate("(1 + 2").unwrap_err().to_string(), "Missing ')'");
+        assert_eq!(evaluate("2 +").unwrap_err().to_string(), "Expression ended early");
+        assert_eq!(evaluate("2 $ 3").unwrap_err().to_string(), "Unexpected '$' at position 2");
+        assert_eq!(evaluate("foo(1)").unwrap_err().to_string(), "Unknown name 'foo'");
+        assert!(evaluate("sqrt(-1)").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_registered_tools() {
+        let mut registry = ToolRegistry::new();
+        register(&mut registry);
+
+        let result = registry.call("calculator", json!({"expression": "6 * 7"})).await.unwrap();
+        assert_eq!(result, "42");
+        let result = registry.call("calculator", json!({"expression": "1 / 4"})).await.unwrap();
+        assert_eq!(result, "0.25");
+        assert!(registry.call("calculator", json!({})).await.is_err());
+
+        let now = registry.call("current_time", json!({"utc": true})).await.unwrap();
+        let (timestamp, weekday) = now.split_once(' ').unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(timestamp).is_ok());
+        assert!(weekday.starts_with('(') && weekday.ends_with("day)"));
+        assert!(registry.call("current_time", json!({})).await.is_ok());
+    }
+}
@@ -0,0 +1,230 @@
+//! Bounded channel carrying manager events to the UI
+//!
+//! Sending never waits for the consumer, so a stalled UI can't stop a
+//! model stream. While the channel is full, events are held in a backlog
+//! that one background task forwards, in order, as the consumer catches
+//! up. To keep that backlog small:
+//! - consecutive `TextDelta`s for a conversation are merged into one, as
+//!   are consecutive `ToolCallDelta`s for the same tool call
+//! - `ToolProgress` updates are dropped
+//! - everything else (including `Complete`, `Cancelled` and `Error`) is
+//!   always delivered
+//!
+//! The tradeoff is that a slow consumer sees text in fewer, larger chunks
+//! and may miss tool progress messages, while memory stays bounded by the
+//! text of the response rather than by the number of tokens streamed.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, error::SendError, error::TrySendError};
+
+use crate::manager::ManagerEvent;
+use crate::storage::ids::ConversationId;
+
+/// Default number of events buffered before backpressure applies
+pub const DEFAULT_EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// An event tagged with the conversation it belongs to
+pub type TaggedEvent = (ConversationId, ManagerEvent);
+
+/// Receiving half of an event channel
+pub type EventReceiver = mpsc::Receiver<TaggedEvent>;
+
+/// Create a bounded event channel holding up to `capacity` events
+pub fn event_channel(capacity: usize) -> (SharedEventSender, EventReceiver) {
+    let (tx, rx) = mpsc::channel(capacity);
+    let sender = SharedEventSender {
+        tx,
+        overflow: Arc::new(Mutex::new(Overflow::default())),
+    };
+    (sender, rx)
+}
+
+/// Sending half of an event channel, shared by all managers
+///
+/// Cloning is cheap; clones share the same backlog so ordering is kept
+/// across them.
+#[derive(Clone)]
+pub struct SharedEventSender {
+    tx: mpsc::Sender<TaggedEvent>,
+    overflow: Arc<Mutex<Overflow>>,
+}
+
+/// Events waiting for room in the channel
+#[derive(Default)]
+struct Overflow {
+    backlog: VecDeque<TaggedEvent>,
+    /// Whether a task is forwarding the backlog
+    draining: bool,
+}
+
+impl SharedEventSender {
+    /// Send an event without waiting for the consumer
+    ///
+    /// Fails only when the receiver has been dropped. The error hands the
+    /// event back, like `mpsc::Sender::send`.
+    #[allow(clippy::result_large_err)]
+    pub fn send(&self, event: TaggedEvent) -> Result<(), SendError<TaggedEvent>> {
+        let mut overflow = self.overflow.lock().unwrap();
+
+        if !overflow.draining {
+            match self.tx.try_send(event) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Closed(event)) => return Err(SendError(event)),
+                Err(TrySendError::Full(event)) => {
+                    overflow.push(event);
+                    overflow.draining = true;
+                    self.spawn_drain();
+                    return Ok(());
+                }
+            }
+        }
+
+        if self.tx.is_closed() {
+            return Err(SendError(event));
+        }
+        overflow.push(event);
+        Ok(())
+    }
+
+    /// Forward the backlog as the consumer makes room
+    fn spawn_drain(&self) {
+        let tx = self.tx.clone();
+        let overflow = self.overflow.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok(permit) = tx.reserve().await else {
+                    let mut overflow = overflow.lock().unwrap();
+                    overflow.backlog.clear();
+                    overflow.draining = false;
+                    return;
+                };
+                let mut overflow = overflow.lock().unwrap();
+                match overflow.backlog.pop_front() {
+                    Some(event) => permit.send(event),
+                    None => {
+                        overflow.draining = false;
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Number of events waiting for room in the channel
+    #[cfg(test)]
+    fn backlog_len(&self) -> usize {
+        self.overflow.lock().unwrap().backlog.len()
+    }
+}
+
+impl Overflow {
+    fn push(&mut self, (conversation_id, event): TaggedEvent) {
+        match event {
+            ManagerEvent::ToolProgress { .. } => {}
+            ManagerEvent::TextDelta(text) => match self.backlog.back_mut() {
+                Some((last_id, ManagerEvent::TextDelta(pending))) if *last_id == conversation_id => {
+                    pending.push_str(&text);
+                }
+                _ => self.backlog.push_back((conversation_id, ManagerEvent::TextDelta(text))),
+            },
+            ManagerEvent::ToolCallDelta { tool_call_id, name, args_fragment } => match self.backlog.back_mut() {
+                Some((last_id, ManagerEvent::ToolCallDelta { tool_call_id: pending_id, args_fragment: pending, .. }))
+                    if *last_id == conversation_id && *pending_id == tool_call_id =>
+                {
+                    pending.push_str(&args_fragment);
+                }
+                _ => self.backlog.push_back((
+                    conversation_id,
+                    ManagerEvent::ToolCallDelta { tool_call_id, name, args_fragment },
+                )),
+            },
+            event => self.backlog.push_back((conversation_id, event)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_slow_consumer_keeps_backlog_small() {
+        let (tx, mut rx) = event_channel(4);
+        let conversation_id = ConversationId::from_string("c1");
+
+        // Stream far more deltas than the channel holds without reading any
+        for i in 0..100_000 {
+            tx.send((conversation_id.clone(), ManagerEvent::TextDelta(format!("{} ", i)))).unwrap();
+            if i % 1000 == 0 {
+                tx.send((
+                    conversation_id.clone(),
+                    ManagerEvent::ToolProgress { tool_call_id: "t".to_string(), message: "working".to_string() },
+                ))
+                .unwrap();
+            }
+        }
+        tx.send((conversation_id.clone(), ManagerEvent::Complete(Vec::new()))).unwrap();
+
+        // The overflow is one merged delta plus the completion
+        assert_eq!(tx.backlog_len(), 2);
+
+        let mut text = String::new();
+        let mut completed = false;
+        while let Some((_, event)) = rx.recv().await {
+            match event {
+                ManagerEvent::TextDelta(delta) => {
+                    assert!(!completed, "text arrived after completion");
+                    text.push_str(&delta);
+                }
+                ManagerEvent::Complete(_) => {
+                    completed = true;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let expected: String = (0..100_000).map(|i| format!("{} ", i)).collect();
+        assert!(completed);
+        assert_eq!(text, expected);
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_deltas_merge_per_call() {
+        let (tx, mut rx) = event_channel(1);
+        let conversation_id = ConversationId::from_string("c1");
+        let delta = |id: &str, fragment: &str| {
+            (
+                conversation_id.clone(),
+                ManagerEvent::ToolCallDelta {
+                    tool_call_id: id.to_string(),
+                    name: "search".to_string(),
+                    args_fragment: fragment.to_string(),
+                },
+            )
+        };
+
+        // The first fills the channel, the rest wait in the backlog
+        for event in [delta("a", ""), delta("a", "{\"q\":"), delta("a", "\"x\"}"), delta("b", "{}")] {
+            tx.send(event).unwrap();
+        }
+        assert_eq!(tx.backlog_len(), 2);
+
+        let mut received = Vec::new();
+        for _ in 0..3 {
+            if let Some((_, ManagerEvent::ToolCallDelta { tool_call_id, args_fragment, .. })) = rx.recv().await {
+                received.push((tool_call_id, args_fragment));
+            }
+        }
+        let expected = [("a", ""), ("a", "{\"q\":\"x\"}"), ("b", "{}")];
+        assert_eq!(received, expected.map(|(id, fragment)| (id.to_string(), fragment.to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_once_receiver_is_dropped() {
+        let (tx, rx) = event_channel(1);
+        drop(rx);
+        assert!(tx.send((ConversationId::from_string("c1"), ManagerEvent::TextDelta("x".to_string()))).is_err());
+    }
+}
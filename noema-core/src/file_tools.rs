@@ -0,0 +1,246 @@
+//! Built-in file tools confined to a sandbox directory
+//!
+//! `read_file`, `write_file` and `list_dir` let the model work with files
+//! without an MCP server. Every path is taken relative to the sandbox root
+//! and resolved with symlinks followed; anything that ends up outside the
+//! root (`..`, absolute paths, links pointing out) is rejected. Set the root
+//! for a conversation with `ConversationManager::set_file_tools_root`.
+
+use anyhow::{bail, Context, Result};
+use llm::ToolRegistry;
+use llm_macros::tool_methods;
+use serde_json::Value;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Largest file `read_file` returns
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+
+/// File access limited to one directory tree
+pub struct FileTools {
+    /// Canonical sandbox root
+    root: PathBuf,
+}
+
+#[tool_methods]
+impl FileTools {
+    /// Sandbox rooted at `root`, which must be an existing directory
+    pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let root = std::fs::canonicalize(root)
+            .with_context(|| format!("File tools root {} doesn't exist", root.display()))?;
+        if !root.is_dir() {
+            bail!("File tools root {} is not a directory", root.display());
+        }
+        Ok(Self { root })
+    }
+
+    /// The canonical sandbox root
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Canonical path of an existing file or directory inside the sandbox
+    async fn resolve_existing(&self, path: &str) -> Result<PathBuf, String> {
+        let joined = self.root.join(path);
+        let resolved = tokio::fs::canonicalize(&joined)
+            .await
+            .map_err(|e| format!("{}: {}", path, e))?;
+        self.check_inside(path, resolved)
+    }
+
+    /// Path to write `path` to, which need not exist yet
+    ///
+    /// The deepest existing ancestor is canonicalized and checked; the
+    /// missing components below it must be plain names, since ".." can't be
+    /// resolved through a directory that doesn't exist.
+    async fn resolve_for_write(&self, path: &str) -> Result<PathBuf, String> {
+        let mut existing = self.root.join(path);
+        let mut missing = Vec::new();
+        while tokio::fs::symlink_metadata(&existing).await.is_err() {
+            match existing.components().next_back() {
+                Some(Component::Normal(name)) => missing.push(name.to_os_string()),
+                _ => return Err(format!("{}: can't go up from a directory that doesn't exist", path)),
+            }
+            existing.pop();
+        }
+        let base = tokio::fs::canonicalize(&existing)
+            .await
+            .map_err(|e| format!("{}: {}", path, e))?;
+        let resolved = missing.iter().rev().fold(base, |dir, name| dir.join(name));
+        self.check_inside(path, resolved)
+    }
+
+    fn check_inside(&self, path: &str, resolved: PathBuf) -> Result<PathBuf, String> {
+        if resolved.starts_with(&self.root) {
+            Ok(resolved)
+        } else {
+            Err(format!("{}: is outside the sandbox", path))
+        }
+    }
+
+    /// Read a UTF-8 text file from the sandbox directory.
+    /// Paths are relative to the sandbox root.
+    #[tool]
+    async fn read_file(&self, path: String) -> Result<String, String> {
+        let resolved = self.resolve_existing(&path).await?;
+        let metadata = tokio::fs::metadata(&resolved).await.map_err(|e| format!("{}: {}", path, e))?;
+        if metadata.is_dir() {
+            return Err(format!("{}: is a directory", path));
+        }
+        if metadata.len() > MAX_READ_BYTES {
+            return Err(format!("{}: is larger than {} bytes", path, MAX_READ_BYTES));
+        }
+        let bytes = tokio::fs::read(&resolved).await.map_err(|e| format!("{}: {}", path, e))?;
+        String::from_utf8(bytes).map_err(|_| format!("{}: is not a UTF-8 text file", path))
+    }
+
+    /// Write a text file in the sandbox directory, replacing it if it exists
+    /// and creating missing parent directories. Paths are relative to the sandbox root.
+    #[tool]
+    async fn write_file(&self, path: String, content: String) -> Result<String, String> {
+        let resolved = self.resolve_for_write(&path).await?;
+        if resolved == self.root || resolved.is_dir() {
+            return Err(format!("{}: is a directory", path));
+        }
+        if let Some(parent) = resolved.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("{}: {}", path, e))?;
+        }
+        tokio::fs::write(&resolved, content.as_bytes())
+            .await
+            .map_err(|e| format!("{}: {}", path, e))?;
+        Ok(format!("Wrote {} bytes to {}", content.len(), path))
+    }
+
+    /// List a directory in the sandbox (the sandbox root when no path is given).
+    /// Subdirectories end with "/".
+    #[tool]
+    async fn list_dir(&self, path: Option<String>) -> Result<Vec<String>, String> {
+        let path = path.unwrap_or_else(|| ".".to_string());
+        let resolved = self.resolve_existing(&path).await?;
+        let mut entries = tokio::fs::read_dir(&resolved)
+            .await
+            .map_err(|e| format!("{}: {}", path, e))?;
+        let mut names = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(|e| format!("{}: {}", path, e))? {
+            let mut name = entry.file_name().to_string_lossy().into_owned();
+            if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+                name.push('/');
+            }
+            names.push(name);
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Add `read_file`, `write_file` and `list_dir` to a tool registry
+    pub fn register(self: Arc<Self>, registry: &mut ToolRegistry) {
+        let tools = Arc::clone(&self);
+        registry.register(ReadFileArgs::read_file_tool_def(), move |args: Value| {
+            let tools = Arc::clone(&tools);
+            async move {
+                let args: ReadFileArgs = serde_json::from_value(args)?;
+                tools.read_file(args.path).await.map_err(anyhow::Error::msg)
+            }
+        });
+        let tools = Arc::clone(&self);
+        registry.register(WriteFileArgs::write_file_tool_def(), move |args: Value| {
+            let tools = Arc::clone(&tools);
+            async move {
+                let args: WriteFileArgs = serde_json::from_value(args)?;
+                tools.write_file(args.path, args.content).await.map_err(anyhow::Error::msg)
+            }
+        });
+        registry.register(ListDirArgs::list_dir_tool_def(), move |args: Value| {
+            let tools = Arc::clone(&self);
+            async move {
+                let args: ListDirArgs = serde_json::from_value(args)?;
+                let names = tools.list_dir(args.path).await.map_err(anyhow::Error::msg)?;
+                if names.is_empty() {
+                    return Ok("(empty directory)".to_string());
+                }
+                Ok(names.join("\n"))
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A sandbox in a fresh temp directory, with a sibling file outside it
+    fn sandbox(name: &str) -> (PathBuf, ToolRegistry) {
+        let base = std::env::temp_dir().join(format!("noema-file-tools-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let root = base.join("root");
+        std::fs::create_dir_all(root.join("notes")).unwrap();
+        std::fs::write(root.join("notes/todo.txt"), "buy milk").unwrap();
+        std::fs::write(base.join("secret.txt"), "hunter2").unwrap();
+
+        let mut registry = ToolRegistry::new();
+        Arc::new(FileTools::new(&root).unwrap()).register(&mut registry);
+        (base, registry)
+    }
+
+    #[tokio::test]
+    async fn test_reads_and_writes_inside_root() {
+        let (base, registry) = sandbox("rw");
+
+        let text = registry.call("read_file", json!({"path": "notes/todo.txt"})).await.unwrap();
+        assert_eq!(text, "buy milk");
+
+        registry
+            .call("write_file", json!({"path": "drafts/plan.md", "content": "# Plan"}))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(base.join("root/drafts/plan.md")).unwrap(), "# Plan");
+        let text = registry.call("read_file", json!({"path": "./drafts/../drafts/plan.md"})).await.unwrap();
+        assert_eq!(text, "# Plan");
+
+        let listing = registry.call("list_dir", json!({})).await.unwrap();
+        assert_eq!(listing, "drafts/\nnotes/");
+        let listing = registry.call("list_dir", json!({"path": "notes"})).await.unwrap();
+        assert_eq!(listing, "todo.txt");
+
+        assert!(registry.call("read_file", json!({"path": "notes"})).await.is_err());
+        assert!(registry.call("read_file", json!({"path": "missing.txt"})).await.is_err());
+        std::fs::remove_dir_all(base).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rejects_paths_outside_root() {
+        let (base, registry) = sandbox("traversal");
+        let secret = base.join("secret.txt");
+
+        for path in ["../secret.txt", "notes/../../secret.txt", secret.to_str().unwrap()] {
+            let error = registry.call("read_file", json!({"path": path})).await.unwrap_err();
+            assert!(error.to_string().contains("outside the sandbox"), "{}: {}", path, error);
+        }
+        let outside = base.join("escaped.txt");
+        for path in ["../escaped.txt", outside.to_str().unwrap()] {
+            let error = registry
+                .call("write_file", json!({"path": path, "content": "x"}))
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("outside the sandbox"), "{}: {}", path, error);
+        }
+        let write = json!({"path": "new/../../escaped.txt", "content": "x"});
+        assert!(registry.call("write_file", write).await.is_err());
+        assert!(!outside.exists());
+        assert!(registry.call("list_dir", json!({"path": ".."})).await.is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&base, base.join("root/link")).unwrap();
+            assert!(registry.call("read_file", json!({"path": "link/secret.txt"})).await.is_err());
+            let write = json!({"path": "link/escaped.txt", "content": "x"});
+            assert!(registry.call("write_file", write).await.is_err());
+            assert!(!base.join("escaped.txt").exists());
+        }
+        std::fs::remove_dir_all(base).unwrap();
+    }
+}
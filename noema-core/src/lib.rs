@@ -4,7 +4,12 @@
 //! - **Traits**: `ConversationContext`, `Agent`
 //! - **Implementations**: `SimpleAgent`, `ToolAgent`, `McpAgent`
 //! - **MCP Support**: `McpRegistry`, `McpToolRegistry` for Model Context Protocol
+//! - **Built-in tools**: `current_time` and `calculator`, served without an MCP server
+//! - **File tools**: `read_file`, `write_file`, `list_dir` confined to a sandbox root
+//! - **Web search**: built-in `search_web` tool (`web-search` feature)
 //! - **Manager**: `ConversationManager` for orchestrating conversations
+//! - **Templates**: `Template` presets for new conversations
+//! - **Subconversations**: `SubconversationManager` for running sub-agents concurrently
 //! - **Storage**: `Session<S: StorageTypes>` for DB-agnostic session management
 //!
 //! # Example
@@ -17,17 +22,30 @@
 //! ```
 pub mod agent;
 pub mod agents;
+pub mod builtin_tools;
 pub mod context;
+pub mod event_channel;
+pub mod file_tools;
 pub mod manager;
 pub mod mcp;
 pub mod storage;
+pub mod subconversation;
+pub mod templates;
 pub mod traffic_log;
+#[cfg(feature = "web-search")]
+pub mod web_search;
 
 pub use agent::Agent;
 pub use agents::{McpAgent};
 pub use context::{ConversationContext, MessagesGuard};
+pub use event_channel::{event_channel, EventReceiver, SharedEventSender, TaggedEvent, DEFAULT_EVENT_CHANNEL_CAPACITY};
 
 // New manager API
-pub use manager::{CommitMode, ConversationManager, ManagerCommand, ManagerEvent, SharedEventSender, ToolConfig};
+pub use manager::{
+    AutoNameConfig, CommitMode, CompactionConfig, ConversationManager, ManagerCommand, ManagerError, ManagerEvent, RunSettings,
+    ToolConfig, DEFAULT_TEXT_DELTA_INTERVAL,
+};
+pub use subconversation::{SubconversationManager, SubconversationStatus};
+pub use templates::Template;
 
-pub use mcp::{AuthMethod, McpConfig, McpRegistry, McpToolRegistry, ServerConfig};
\ No newline at end of file
+pub use mcp::{AuthMethod, McpConfig, McpRegistry, McpToolRegistry, ServerConfig, Transport};
\ No newline at end of file
@@ -7,19 +7,34 @@
 //! - Event streaming to UI
 
 use anyhow::Result;
-use llm::{ChatMessage, ChatModel, ChatPayload};
+use llm::{
+    estimate_tokens, ChatMessage, ChatModel, ChatPayload, ChatRequest, ContentBlock, ContentFiltered,
+    FinishReason, GenerationParams, ProviderError, ProviderErrorKind, Role, StreamCollector,
+    TokenUsage, ToolRegistry,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
-use crate::agents::{ExecutionContext, ToolEnricher};
+use crate::agents::{
+    ExecutionContext, MaxIterationsExceeded, ToolApproval, ToolApprover, ToolEnricher, DEFAULT_MAX_CONCURRENT_TOOLS,
+    DEFAULT_MAX_ITERATIONS, DEFAULT_MAX_TOOL_RESULT_BYTES,
+};
 use crate::context::ConversationContext;
+use crate::event_channel::SharedEventSender;
+use crate::file_tools::FileTools;
 use crate::storage::content::InputContent;
 use crate::storage::coordinator::StorageCoordinator;
 use crate::storage::ids::{ConversationId, SpanId, TurnId, UserId};
-use crate::storage::session::{ResolvedMessage, Session};
+use crate::storage::session::{ResolvedContent, ResolvedMessage, Session};
 use crate::storage::traits::StorageTypes;
-use crate::storage::types::OriginKind;
+use crate::storage::types::{ContextSummary, Incognito, OriginKind};
 use crate::storage::DocumentResolver;
 use crate::{Agent, McpAgent, McpRegistry, McpToolRegistry};
 
@@ -29,7 +44,10 @@ use crate::{Agent, McpAgent, McpRegistry, McpToolRegistry};
 fn create_noema_core_enricher() -> ToolEnricher {
     Arc::new(|tool_name, args, context| {
         // Inject context for noema-core tools (spawn_agent needs it)
-        if tool_name == "spawn_agent" {
+        if matches!(
+            tool_name,
+            "spawn_agent" | "spawn_agent_streaming" | "spawn_agents" | "list_conversations" | "get_conversation"
+        ) {
             match args {
                 serde_json::Value::Object(map) => serde_json::Value::Object(context.inject_into(map)),
                 other => other,
@@ -40,9 +58,6 @@ fn create_noema_core_enricher() -> ToolEnricher {
     })
 }
 
-/// Type alias for the shared event sender - sends (ConversationId, ManagerEvent) tuples
-pub type SharedEventSender = mpsc::UnboundedSender<(ConversationId, ManagerEvent)>;
-
 /// Configuration for which tools to enable during a request.
 #[derive(Debug, Clone, Default)]
 pub struct ToolConfig {
@@ -61,6 +76,158 @@ impl ToolConfig {
     }
 }
 
+/// Cancellation state shared between the manager and its background task.
+///
+/// Every command carries the token that was current when it was sent, so a
+/// cancel reaches requests still waiting in the queue as well as the one
+/// running. Cancelling replaces the token, so it has no effect on requests
+/// sent afterwards, and cancelling while idle has no effect at all.
+#[derive(Default)]
+struct CancelState {
+    /// Token given to commands sent from now on
+    token: std::sync::Mutex<CancellationToken>,
+    keep_partial: AtomicBool,
+    /// Set while an agent run is in progress
+    running: AtomicBool,
+}
+
+impl CancelState {
+    /// Token for a command being sent now
+    fn current(&self) -> CancellationToken {
+        self.token.lock().unwrap().clone()
+    }
+
+    /// Cancel every command sent so far
+    fn cancel(&self, keep_partial: bool) {
+        self.keep_partial.store(keep_partial, Ordering::SeqCst);
+        let sent = std::mem::take(&mut *self.token.lock().unwrap());
+        sent.cancel();
+    }
+
+    fn keep_partial(&self) -> bool {
+        self.keep_partial.load(Ordering::SeqCst)
+    }
+
+    fn set_running(&self, running: bool) {
+        self.running.store(running, Ordering::SeqCst);
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
+
+/// Tool calls waiting for the user's answer, keyed by tool call ID
+type PendingApprovals = std::sync::Mutex<HashMap<String, oneshot::Sender<ToolApproval>>>;
+
+/// Approver that asks the UI with a `ToolApprovalRequest` event and waits
+/// for `ConversationManager::respond_to_tool_approval`
+struct EventApprover {
+    conversation_id: ConversationId,
+    event_tx: SharedEventSender,
+    pending: Arc<PendingApprovals>,
+}
+
+#[async_trait::async_trait]
+impl ToolApprover for EventApprover {
+    async fn approve(&self, tool_call: &llm::ToolCall) -> ToolApproval {
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(tool_call.id.clone(), tx);
+        let _ = self.event_tx.send((
+            self.conversation_id.clone(),
+            ManagerEvent::ToolApprovalRequest(tool_call.clone()),
+        ));
+        // Dropped unanswered (e.g. on cancel) counts as denied
+        rx.await.unwrap_or(ToolApproval::Deny)
+    }
+}
+
+/// Suggested interval for coalescing streamed text into `TextDelta` events
+pub const DEFAULT_TEXT_DELTA_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Collects streamed text and sends it as one `TextDelta` per flush, so
+/// fast token streams don't cause a UI update per token
+struct TextDeltaBuffer {
+    pending: std::sync::Mutex<String>,
+    conversation_id: ConversationId,
+    event_tx: SharedEventSender,
+}
+
+impl TextDeltaBuffer {
+    fn new(conversation_id: ConversationId, event_tx: SharedEventSender) -> Self {
+        Self {
+            pending: std::sync::Mutex::new(String::new()),
+            conversation_id,
+            event_tx,
+        }
+    }
+
+    fn push(&self, text: &str) {
+        self.pending.lock().unwrap().push_str(text);
+    }
+
+    /// Send the buffered text, if any
+    fn flush(&self) {
+        let text = std::mem::take(&mut *self.pending.lock().unwrap());
+        if !text.is_empty() {
+            let _ = self.event_tx.send((self.conversation_id.clone(), ManagerEvent::TextDelta(text)));
+        }
+    }
+}
+
+/// Instructions given to the model that writes context summaries
+const SUMMARY_PROMPT: &str = "Summarize the conversation transcript you are given so it can replace \
+the transcript as context for continuing the conversation. Keep facts, decisions, open questions \
+and anything the user asked to remember. Be concise and reply with the summary only.";
+
+/// When and how old history is summarized to keep the context small.
+///
+/// The summary replaces the summarized turns only in what is sent to the
+/// model; the turns themselves stay in storage.
+#[derive(Clone)]
+pub struct CompactionConfig {
+    /// Summarize automatically after a request once the history is estimated
+    /// above this many tokens (None = only when asked)
+    pub threshold_tokens: Option<usize>,
+    /// Number of most recent exchanges always kept verbatim
+    pub keep_turns: usize,
+    /// Model that writes the summary (None = the conversation's model)
+    pub summary_model: Option<Arc<dyn ChatModel + Send + Sync>>,
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self {
+            threshold_tokens: None,
+            keep_turns: 4,
+            summary_model: None,
+        }
+    }
+}
+
+/// Instructions given to the model that names conversations
+const TITLE_PROMPT: &str = "Write a short title of at most six words for a conversation that starts \
+with the exchange you are given. Reply with the title only, without quotes or a final period.";
+
+/// Longest title kept from the naming model's reply, in characters
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Sent (but not stored) after a response the output token limit cut off,
+/// to have the model finish it
+const CONTINUE_PROMPT: &str = "Your last response was cut off. Continue exactly where it stopped, \
+without repeating anything or adding an introduction.";
+
+/// Naming untitled conversations after their first exchange
+///
+/// Off by default, since it costs an extra (small) model call.
+#[derive(Clone, Default)]
+pub struct AutoNameConfig {
+    /// Name a conversation that has no name once its first reply completes
+    pub enabled: bool,
+    /// Model that writes the title (None = the conversation's model)
+    pub title_model: Option<Arc<dyn ChatModel + Send + Sync>>,
+}
+
 /// How to commit messages after LLM execution.
 #[derive(Debug, Clone, Default)]
 pub enum CommitMode {
@@ -71,6 +238,61 @@ pub enum CommitMode {
     AtTurn(TurnId),
 }
 
+/// Settings applied to each request, changed through the manager's setters
+#[derive(Clone)]
+pub struct RunSettings {
+    /// Model/tool-call rounds a single request may run
+    pub max_tool_iterations: usize,
+    /// Text from one tool call passed back to the model, in bytes
+    pub max_tool_result_bytes: usize,
+    /// Tool calls from one model response run at once
+    pub max_concurrent_tools: usize,
+    /// Sampling settings sent with each model request
+    pub generation_params: GenerationParams,
+    /// Ask providers to cache the system prompt
+    pub cache_system_prompt: bool,
+    /// When and how old history is summarized
+    pub compaction: CompactionConfig,
+    /// How often streamed text is sent (None = every delta as it arrives)
+    pub text_delta_interval: Option<Duration>,
+    /// Make each tool call wait for the user's approval
+    pub require_tool_approval: bool,
+    /// Whether and with which model untitled conversations are named
+    pub auto_name: AutoNameConfig,
+    /// Tools offered to this conversation only (None = just the shared ones)
+    pub local_tools: Option<Arc<ToolRegistry>>,
+}
+
+impl Default for RunSettings {
+    fn default() -> Self {
+        Self {
+            max_tool_iterations: DEFAULT_MAX_ITERATIONS,
+            max_tool_result_bytes: DEFAULT_MAX_TOOL_RESULT_BYTES,
+            max_concurrent_tools: DEFAULT_MAX_CONCURRENT_TOOLS,
+            generation_params: GenerationParams::default(),
+            cache_system_prompt: false,
+            compaction: CompactionConfig::default(),
+            text_delta_interval: None,
+            require_tool_approval: false,
+            auto_name: AutoNameConfig::default(),
+            local_tools: None,
+        }
+    }
+}
+
+/// What the background task works with, fixed for the manager's lifetime
+struct TaskContext<S: StorageTypes> {
+    conversation_id: ConversationId,
+    session: Arc<Mutex<Session<S>>>,
+    coordinator: Arc<StorageCoordinator<S>>,
+    mcp_registry: Arc<Mutex<McpRegistry>>,
+    document_resolver: Arc<dyn DocumentResolver>,
+    user_id: UserId,
+    cancel: Arc<CancelState>,
+    approver: Arc<dyn ToolApprover>,
+    event_tx: SharedEventSender,
+}
+
 // ============================================================================
 // Commands and Events
 // ============================================================================
@@ -87,6 +309,16 @@ pub enum ManagerCommand {
         tool_config: ToolConfig,
         commit_mode: CommitMode,
     },
+    /// Replace the text of the last user message, drop everything after it
+    /// and run the agent again
+    EditAndResend {
+        text: String,
+        tool_config: ToolConfig,
+    },
+    /// Have the model finish the last response and append what it writes
+    ContinueResponse,
+    /// Append messages to the conversation as they are, without running the agent
+    AddMessages(Vec<ChatMessage>),
     /// Truncate context to before a specific turn (None = clear all)
     Truncate(Option<TurnId>),
     /// Change the model (model_id should be in provider/model format)
@@ -94,6 +326,10 @@ pub enum ManagerCommand {
         model: Arc<dyn ChatModel + Send + Sync>,
         model_id: String,
     },
+    /// Replace the settings applied to later requests
+    SetSettings(RunSettings),
+    /// Summarize old history into the conversation's context summary now
+    Compact,
 }
 
 /// Events emitted from the background task
@@ -101,16 +337,157 @@ pub enum ManagerCommand {
 pub enum ManagerEvent {
     /// User message was added (for immediate UI feedback)
     UserMessageAdded(ChatMessage),
+    /// Newly produced text of the in-progress assistant message (append to what came before)
+    TextDelta(String),
     /// Streaming message from agent
     StreamingMessage(ChatMessage),
     /// Agent execution and commit completed - includes all committed messages with turn_ids
     Complete(Vec<ResolvedMessage>),
+    /// Next fragment of a tool call's arguments while the model is still
+    /// generating them (the first may be empty, announcing the call)
+    ToolCallDelta { tool_call_id: String, name: String, args_fragment: String },
+    /// Progress reported by an MCP server for a running tool call
+    ToolProgress { tool_call_id: String, message: String },
+    /// A tool call is waiting for the user's approval; answer with
+    /// `respond_to_tool_approval`
+    ToolApprovalRequest(llm::ToolCall),
+    /// Token usage summed over all model calls of the completed request (sent after Complete)
+    Usage(TokenUsage),
+    /// Why the model stopped the last response, if the provider said (sent
+    /// after Complete). `FinishReason::Length` means the output token limit
+    /// cut it off, and `continue_response` can pick it up.
+    Finished(FinishReason),
+    /// Oldest history messages were left out of a request to fit the model's context window
+    HistoryTrimmed(usize),
+    /// Old history was summarized - includes how many messages the new summary replaced
+    /// (0 if there was nothing old enough to summarize)
+    Compacted(usize),
+    /// The conversation was given a generated name (see `AutoNameConfig`)
+    Renamed(String),
     /// Error occurred
-    Error(String),
+    Error(ManagerError),
     /// Model was changed
     ModelChanged(String),
     /// Context was truncated
     Truncated(Option<TurnId>),
+    /// Request was cancelled - includes committed messages (with the partial
+    /// response if it was kept)
+    Cancelled(Vec<ResolvedMessage>),
+}
+
+/// Why a request failed, so a UI can tell a rate limit from a bad API key
+/// and offer "retry" or "check API key" accordingly
+#[derive(Debug, Clone, PartialEq)]
+pub enum ManagerError {
+    /// The provider couldn't be reached (connection failure, timeout)
+    Network(String),
+    /// The provider rejected the request for exceeding its rate limit
+    RateLimited { retry_after: Option<Duration> },
+    /// The provider rejected the credentials
+    Auth(String),
+    /// The provider doesn't know the requested model
+    ModelNotFound(String),
+    /// The provider's safety filter blocked the response, with the filter
+    /// category when the provider names one
+    ContentFiltered { category: Option<String> },
+    /// Any other provider failure
+    Provider(String),
+    /// Reading or writing the conversation failed
+    Storage(String),
+    /// The request stopped for another reason (e.g. the tool-call limit was reached)
+    Other(String),
+}
+
+impl ManagerError {
+    /// Classify an error returned by a model or agent call
+    pub fn from_provider(error: &anyhow::Error) -> Self {
+        if let Some(limit) = error.downcast_ref::<MaxIterationsExceeded>() {
+            return Self::Other(limit.to_string());
+        }
+        if let Some(filtered) = error.downcast_ref::<ContentFiltered>() {
+            return Self::ContentFiltered { category: filtered.category.clone() };
+        }
+        if let Some(provider) = error.downcast_ref::<ProviderError>() {
+            return match provider.kind {
+                ProviderErrorKind::Auth => Self::Auth(provider.message.clone()),
+                ProviderErrorKind::NotFound => Self::ModelNotFound(provider.message.clone()),
+                ProviderErrorKind::Timeout => Self::Network(provider.message.clone()),
+                ProviderErrorKind::RateLimited => Self::RateLimited {
+                    retry_after: provider.retry_after,
+                },
+                _ => Self::Provider(provider.to_string()),
+            };
+        }
+        if let Some(err) = error.downcast_ref::<reqwest::Error>() {
+            if err.is_timeout() || err.is_connect() || err.is_request() {
+                return Self::Network(err.to_string());
+            }
+        }
+        Self::Provider(error.to_string())
+    }
+}
+
+impl fmt::Display for ManagerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(message) => write!(f, "Network error: {}", message),
+            Self::RateLimited { retry_after: Some(delay) } => {
+                write!(f, "Rate limited by the provider, retry after {}s", delay.as_secs())
+            }
+            Self::RateLimited { retry_after: None } => write!(f, "Rate limited by the provider"),
+            Self::Auth(message) => write!(f, "Authentication failed: {}", message),
+            Self::ModelNotFound(message) => write!(f, "Model not found: {}", message),
+            Self::ContentFiltered { category } => {
+                write!(f, "{}", ContentFiltered { category: category.clone() })
+            }
+            Self::Provider(message) | Self::Storage(message) | Self::Other(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManagerError {}
+
+/// Render messages as a plain-text transcript for the summary model
+fn transcript(messages: &[ChatMessage]) -> String {
+    let mut out = String::new();
+    for message in messages {
+        let speaker = match message.role {
+            Role::System => "System",
+            Role::User => "User",
+            Role::Assistant => "Assistant",
+        };
+        for block in &message.payload.content {
+            let text = match block {
+                ContentBlock::Text { text } => text.clone(),
+                ContentBlock::ToolCall(call) => format!("[called {} with {}]", call.name, call.arguments),
+                ContentBlock::ToolResult(result) => format!("[tool result: {}]", result.get_text()),
+                ContentBlock::Image { .. } => "[image]".to_string(),
+                ContentBlock::Audio { .. } => "[audio]".to_string(),
+                ContentBlock::DocumentRef { id } => format!("[document {}]", id),
+            };
+            out.push_str(speaker);
+            out.push_str(": ");
+            out.push_str(&text);
+            out.push_str("\n\n");
+        }
+    }
+    out
+}
+
+/// The first line of a model's title reply, without quotes, markdown or a
+/// trailing period, cut to `MAX_TITLE_CHARS`
+fn clean_title(reply: &str) -> String {
+    let line = reply.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+    let decoration = |c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '*' | '#' | '`');
+    let line = line.trim_matches(decoration);
+    let line = line.strip_prefix("Title:").unwrap_or(line);
+    let title = line.trim_matches(decoration).trim_end_matches('.').trim();
+    match title.char_indices().nth(MAX_TITLE_CHARS) {
+        Some((end, _)) => title[..end].trim_end().to_string(),
+        None => title.to_string(),
+    }
 }
 
 // ============================================================================
@@ -127,10 +504,15 @@ pub struct ConversationManager<S: StorageTypes> {
     session: Arc<Mutex<Session<S>>>,
     coordinator: Arc<StorageCoordinator<S>>,
     mcp_registry: Arc<Mutex<McpRegistry>>,
-    cmd_tx: mpsc::UnboundedSender<ManagerCommand>,
+    cmd_tx: mpsc::UnboundedSender<(ManagerCommand, CancellationToken)>,
+    cancel: Arc<CancelState>,
     model: Arc<dyn ChatModel + Send + Sync>,
     /// Full model ID in provider/model format (e.g., "gemini/gemini-3-flash-preview")
     model_id: String,
+    /// The settings last sent to the background task
+    settings: RunSettings,
+    file_tools_root: Option<PathBuf>,
+    pending_approvals: Arc<PendingApprovals>,
     #[allow(dead_code)]
     task_handle: JoinHandle<()>,
 }
@@ -139,9 +521,10 @@ impl<S: StorageTypes> ConversationManager<S> {
     /// Create a new ConversationManager for a conversation
     ///
     /// The `event_tx` is a shared channel sender - events are sent as `(ConversationId, ManagerEvent)`
-    /// tuples to allow centralized dispatch to UI.
+    /// tuples to allow centralized dispatch to UI. See `event_channel` for how a slow UI is handled.
     ///
     /// `model_id` should be the full model ID in `provider/model` format (e.g., "gemini/gemini-3-flash-preview")
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         session: Session<S>,
         coordinator: Arc<StorageCoordinator<S>>,
@@ -154,29 +537,31 @@ impl<S: StorageTypes> ConversationManager<S> {
     ) -> Self {
         let conversation_id = session.conversation_id().clone();
         let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+        let cancel = Arc::new(CancelState::default());
+        let pending_approvals = Arc::new(PendingApprovals::default());
+        let approver: Arc<dyn ToolApprover> = Arc::new(EventApprover {
+            conversation_id: conversation_id.clone(),
+            event_tx: event_tx.clone(),
+            pending: Arc::clone(&pending_approvals),
+        });
 
         let session = Arc::new(Mutex::new(session));
-        let session_clone = Arc::clone(&session);
-        let coordinator_clone = Arc::clone(&coordinator);
-        let mcp_registry_clone = Arc::clone(&mcp_registry);
+        let task = TaskContext {
+            conversation_id: conversation_id.clone(),
+            session: Arc::clone(&session),
+            coordinator: Arc::clone(&coordinator),
+            mcp_registry: Arc::clone(&mcp_registry),
+            document_resolver,
+            user_id,
+            cancel: Arc::clone(&cancel),
+            approver,
+            event_tx,
+        };
         let initial_model = Arc::clone(&model);
-        let conversation_id_clone = conversation_id.clone();
         let model_id_clone = model_id.clone();
 
         let task_handle = tokio::spawn(async move {
-            Self::background_loop(
-                conversation_id_clone,
-                session_clone,
-                coordinator_clone,
-                initial_model,
-                model_id_clone,
-                mcp_registry_clone,
-                document_resolver,
-                user_id,
-                cmd_rx,
-                event_tx,
-            )
-            .await;
+            Self::background_loop(task, initial_model, model_id_clone, cmd_rx).await;
         });
 
         Self {
@@ -185,31 +570,53 @@ impl<S: StorageTypes> ConversationManager<S> {
             coordinator,
             mcp_registry,
             cmd_tx,
+            cancel,
             model,
             model_id,
+            settings: RunSettings::default(),
+            file_tools_root: None,
+            pending_approvals,
             task_handle,
         }
     }
 
     async fn background_loop(
-        conversation_id: ConversationId,
-        session: Arc<Mutex<Session<S>>>,
-        coordinator: Arc<StorageCoordinator<S>>,
+        task: TaskContext<S>,
         mut model: Arc<dyn ChatModel + Send + Sync>,
         mut model_id: String,
-        mcp_registry: Arc<Mutex<McpRegistry>>,
-        document_resolver: Arc<dyn DocumentResolver>,
-        user_id: UserId,
-        mut cmd_rx: mpsc::UnboundedReceiver<ManagerCommand>,
-        event_tx: SharedEventSender,
+        mut cmd_rx: mpsc::UnboundedReceiver<(ManagerCommand, CancellationToken)>,
     ) {
-        while let Some(cmd) = cmd_rx.recv().await {
+        let TaskContext { conversation_id, session, coordinator, user_id, cancel, event_tx, .. } = &task;
+        let mut settings = RunSettings::default();
+        // Whether naming is settled: a name was generated or already existed
+        let mut named = false;
+
+        while let Some((cmd, token)) = cmd_rx.recv().await {
+            let runs_agent = matches!(
+                cmd,
+                ManagerCommand::SendMessage { .. }
+                    | ManagerCommand::RunAgent { .. }
+                    | ManagerCommand::EditAndResend { .. }
+                    | ManagerCommand::ContinueResponse
+            );
+
+            // Drop commands sent before a cancel that hadn't started yet;
+            // setting changes still apply
+            if token.is_cancelled() && !matches!(cmd, ManagerCommand::SetModel { .. } | ManagerCommand::SetSettings(_)) {
+                if runs_agent {
+                    let messages = session.lock().await.messages_for_display().to_vec();
+                    let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Cancelled(messages)));
+                }
+                continue;
+            }
+            cancel.set_running(runs_agent);
+
             match cmd {
                 ManagerCommand::SendMessage { content, tool_config } => {
                     // Step 1: Store user input and add to pending
                     let add_result = Self::store_and_add_user_message(
-                        &session,
-                        &coordinator,
+                        session,
+                        coordinator,
                         content,
                     ).await;
 
@@ -221,48 +628,52 @@ impl<S: StorageTypes> ConversationManager<S> {
                             // Step 2: Commit user message first (creates the user turn)
                             // This is needed so spawn_agent has a valid parent turn
                             let commit_result = Self::commit_pending(
-                                &conversation_id,
-                                &session,
-                                &coordinator,
+                                session,
                                 Some(model.id()),
                                 &CommitMode::NewTurns,
                             ).await;
 
                             match commit_result {
                                 Ok(Some((turn_id, span_id))) => {
+                                    Self::record_last_model(conversation_id, coordinator, &model_id).await;
+
                                     // Build execution context and run agent
                                     let exec_ctx = ExecutionContext::with_all(
                                         user_id.clone(),
                                         conversation_id.clone(),
                                         turn_id,
-                                        Some(span_id),
+                                        span_id,
                                         model_id.clone(),
                                     );
 
-                                    Self::run_agent_and_commit(
-                                        &conversation_id,
-                                        &session,
-                                        &coordinator,
-                                        &mcp_registry,
-                                        &document_resolver,
-                                        exec_ctx,
-                                        &model,
-                                        tool_config,
-                                        CommitMode::NewTurns,
-                                        &event_tx,
-                                    ).await;
+                                    Self::run_agent_and_commit(&task, exec_ctx, &model, tool_config, CommitMode::NewTurns, &settings, &token).await;
+
+                                    if settings.auto_name.enabled && !named {
+                                        named = Self::name_conversation(
+                                            conversation_id,
+                                            session,
+                                            coordinator,
+                                            &model,
+                                            &settings.auto_name,
+                                            event_tx,
+                                        ).await;
+                                    }
+
+                                    if Self::exceeds_compaction_threshold(session, &settings.compaction).await {
+                                        Self::compact_history(conversation_id, session, &model, &settings.compaction, event_tx).await;
+                                    }
                                 }
                                 Ok(None) => {
                                     // No pending messages to commit (shouldn't happen)
-                                    let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error("No user message to commit".to_string())));
+                                    let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(ManagerError::Storage("No user message to commit".to_string()))));
                                 }
                                 Err(e) => {
-                                    let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(format!("Failed to commit user message: {}", e))));
+                                    let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(ManagerError::Storage(format!("Failed to commit user message: {}", e)))));
                                 }
                             }
                         }
                         Err(e) => {
-                            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(format!("Failed to add message: {}", e))));
+                            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(ManagerError::Storage(format!("Failed to add message: {}", e)))));
                         }
                     }
                 }
@@ -271,13 +682,19 @@ impl<S: StorageTypes> ConversationManager<S> {
                     // For regeneration, the turn already exists
                     // Get the turn_id from commit_mode if it's AtTurn
                     let exec_ctx = if let CommitMode::AtTurn(ref turn_id) = commit_mode {
-                        // Create span for the regeneration
-                        if let Ok(span_id) = coordinator.create_and_select_span(&conversation_id, turn_id, Some(model.id())).await {
+                        // Create span for the regeneration; ephemeral conversations store none
+                        let ephemeral = session.lock().await.incognito() == Incognito::Ephemeral;
+                        let span_id = if ephemeral {
+                            Ok(None)
+                        } else {
+                            coordinator.create_and_select_span(conversation_id, turn_id, Some(model.id())).await.map(Some)
+                        };
+                        if let Ok(span_id) = span_id {
                             ExecutionContext::with_all(
                                 user_id.clone(),
                                 conversation_id.clone(),
                                 turn_id.clone(),
-                                Some(span_id),
+                                span_id,
                                 model_id.clone(),
                             )
                         } else {
@@ -289,18 +706,53 @@ impl<S: StorageTypes> ConversationManager<S> {
                         ExecutionContext::default()
                     };
 
-                    Self::run_agent_and_commit(
-                        &conversation_id,
-                        &session,
-                        &coordinator,
-                        &mcp_registry,
-                        &document_resolver,
-                        exec_ctx,
-                        &model,
-                        tool_config,
-                        commit_mode,
-                        &event_tx,
-                    ).await;
+                    Self::run_agent_and_commit(&task, exec_ctx, &model, tool_config, commit_mode, &settings, &token).await;
+                }
+
+                ManagerCommand::EditAndResend { text, tool_config } => {
+                    match Self::replace_last_user_message(conversation_id, session, coordinator, text).await {
+                        Ok((user_msg, turn_id, span_id)) => {
+                            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::UserMessageAdded(user_msg)));
+
+                            let exec_ctx = ExecutionContext::with_all(
+                                user_id.clone(),
+                                conversation_id.clone(),
+                                turn_id,
+                                span_id,
+                                model_id.clone(),
+                            );
+
+                            Self::run_agent_and_commit(&task, exec_ctx, &model, tool_config, CommitMode::NewTurns, &settings, &token).await;
+                        }
+                        Err(e) => {
+                            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(ManagerError::Storage(format!("Failed to edit message: {}", e)))));
+                        }
+                    }
+                }
+
+                ManagerCommand::ContinueResponse => {
+                    Self::continue_last_response(&task, &model, &settings, &token).await;
+                }
+
+                ManagerCommand::AddMessages(messages) => {
+                    {
+                        let mut sess = session.lock().await;
+                        for message in messages {
+                            sess.add(message);
+                        }
+                    }
+                    match Self::commit_pending(session, None, &CommitMode::NewTurns).await {
+                        Ok(_) => {
+                            let messages = {
+                                let sess = session.lock().await;
+                                sess.messages_for_display().to_vec()
+                            };
+                            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Complete(messages)));
+                        }
+                        Err(e) => {
+                            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(ManagerError::Storage(format!("Failed to add messages: {}", e)))));
+                        }
+                    }
                 }
 
                 ManagerCommand::Truncate(turn_id) => {
@@ -313,12 +765,203 @@ impl<S: StorageTypes> ConversationManager<S> {
                     let name = new_model.name().to_string();
                     model = new_model;
                     model_id = new_model_id;
+                    Self::record_last_model(conversation_id, coordinator, &model_id).await;
                     let _ = event_tx.send((conversation_id.clone(), ManagerEvent::ModelChanged(name)));
                 }
+
+                ManagerCommand::SetSettings(new_settings) => {
+                    settings = new_settings;
+                }
+
+                ManagerCommand::Compact => {
+                    Self::compact_history(conversation_id, session, &model, &settings.compaction, event_tx).await;
+                }
+            }
+            cancel.set_running(false);
+        }
+    }
+
+    /// Whether the history sent to the model is estimated above the automatic
+    /// compaction threshold
+    async fn exceeds_compaction_threshold(
+        session: &Arc<Mutex<Session<S>>>,
+        compaction: &CompactionConfig,
+    ) -> bool {
+        let Some(threshold) = compaction.threshold_tokens else {
+            return false;
+        };
+        let mut sess = session.lock().await;
+        match sess.messages().await {
+            Ok(messages) => messages.iter().map(estimate_tokens).sum::<usize>() > threshold,
+            Err(_) => false,
+        }
+    }
+
+    /// Summarize old history and report the outcome
+    async fn compact_history(
+        conversation_id: &ConversationId,
+        session: &Arc<Mutex<Session<S>>>,
+        model: &Arc<dyn ChatModel + Send + Sync>,
+        compaction: &CompactionConfig,
+        event_tx: &SharedEventSender,
+    ) {
+        let event = match Self::summarize_old_context(session, model, compaction).await {
+            Ok(summarized) => ManagerEvent::Compacted(summarized),
+            Err(e) => ManagerEvent::Error(ManagerError::from_provider(&e)),
+        };
+        let _ = event_tx.send((conversation_id.clone(), event));
+    }
+
+    /// Ask the summary model to fold everything but the most recent
+    /// exchanges into the conversation's context summary, and persist it.
+    ///
+    /// Returns the number of messages the summary newly covers.
+    async fn summarize_old_context(
+        session: &Arc<Mutex<Session<S>>>,
+        model: &Arc<dyn ChatModel + Send + Sync>,
+        compaction: &CompactionConfig,
+    ) -> Result<usize> {
+        let Some((messages, through_turn_id)) =
+            session.lock().await.messages_to_summarize(compaction.keep_turns)
+        else {
+            return Ok(0);
+        };
+
+        let model = compaction.summary_model.as_ref().unwrap_or(model);
+        let request = ChatRequest::new(&[
+            ChatMessage::system(ChatPayload::text(SUMMARY_PROMPT)),
+            ChatMessage::user(ChatPayload::text(transcript(&messages))),
+        ]);
+        let text = model.chat(&request).await?.get_text();
+        if text.trim().is_empty() {
+            anyhow::bail!("the model returned an empty summary");
+        }
+
+        let summary = ContextSummary {
+            text: text.trim().to_string(),
+            through_turn_id,
+        };
+        session.lock().await.set_context_summary(Some(summary)).await?;
+        Ok(messages.iter().filter(|m| m.role != Role::System).count())
+    }
+
+    /// Name an unnamed conversation after its first exchange and report it
+    ///
+    /// Returns whether naming is settled (named now or already), so it is
+    /// tried again after the next reply if the model failed or there was no
+    /// reply yet.
+    async fn name_conversation(
+        conversation_id: &ConversationId,
+        session: &Arc<Mutex<Session<S>>>,
+        coordinator: &Arc<StorageCoordinator<S>>,
+        model: &Arc<dyn ChatModel + Send + Sync>,
+        auto_name: &AutoNameConfig,
+        event_tx: &SharedEventSender,
+    ) -> bool {
+        match coordinator.get_conversation(conversation_id).await {
+            Ok(Some(entity)) if entity.name.is_some() => return true,
+            Ok(Some(_)) => {}
+            Ok(None) => return true,
+            Err(e) => {
+                tracing::warn!("Failed to look up the name of {}: {}", conversation_id, e);
+                return false;
+            }
+        }
+
+        let model = auto_name.title_model.as_ref().unwrap_or(model);
+        let title = match Self::generate_title(session, model).await {
+            Ok(Some(title)) => title,
+            Ok(None) => return false,
+            Err(e) => {
+                tracing::warn!("Failed to name {}: {}", conversation_id, e);
+                return false;
             }
+        };
+        if let Err(e) = coordinator.rename_conversation(conversation_id, Some(&title)).await {
+            tracing::warn!("Failed to rename {}: {}", conversation_id, e);
+            return false;
+        }
+        let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Renamed(title)));
+        true
+    }
+
+    /// Ask `model` for a title for the first user message and the reply to
+    /// it (None if there is no reply yet)
+    async fn generate_title(
+        session: &Arc<Mutex<Session<S>>>,
+        model: &Arc<dyn ChatModel + Send + Sync>,
+    ) -> Result<Option<String>> {
+        let exchange: Vec<ChatMessage> = {
+            let mut sess = session.lock().await;
+            let messages = sess.messages().await?;
+            let mut exchange = messages
+                .iter()
+                .skip_while(|m| m.role != Role::User)
+                .filter(|m| m.role != Role::System && !m.get_text().trim().is_empty());
+            match (exchange.next(), exchange.find(|m| m.role == Role::Assistant)) {
+                (Some(user), Some(assistant)) => vec![user.clone(), assistant.clone()],
+                _ => return Ok(None),
+            }
+        };
+
+        let request = ChatRequest::new(&[
+            ChatMessage::system(ChatPayload::text(TITLE_PROMPT)),
+            ChatMessage::user(ChatPayload::text(transcript(&exchange))),
+        ]);
+        let reply = model.chat(&request).await?.get_text();
+        let title = clean_title(&reply);
+        if title.is_empty() {
+            anyhow::bail!("the model returned an empty title");
+        }
+        Ok(Some(title))
+    }
+
+    /// Remember the conversation's model so it is restored on reopen
+    async fn record_last_model(
+        conversation_id: &ConversationId,
+        coordinator: &Arc<StorageCoordinator<S>>,
+        model_id: &str,
+    ) {
+        if let Err(e) = coordinator.set_last_model(conversation_id, model_id).await {
+            tracing::warn!("Failed to record last model for {}: {}", conversation_id, e);
         }
     }
 
+    /// Replace the last user-written message with `text` in storage and in
+    /// the session, dropping everything after it
+    ///
+    /// Returns the new message and the turn and span it was stored at.
+    /// Ephemeral conversations replace it in memory, at a new turn without a span.
+    async fn replace_last_user_message(
+        conversation_id: &ConversationId,
+        session: &Arc<Mutex<Session<S>>>,
+        coordinator: &Arc<StorageCoordinator<S>>,
+        text: String,
+    ) -> Result<(ChatMessage, TurnId, Option<SpanId>)> {
+        let mut sess = session.lock().await;
+        let turn_id = last_user_turn(sess.messages_for_display())
+            .ok_or_else(|| anyhow::anyhow!("No user message to edit"))?;
+
+        let message = ChatMessage::user(ChatPayload::text(text));
+        if sess.incognito() == Incognito::Ephemeral {
+            sess.truncate(Some(&turn_id));
+            sess.add(message.clone());
+            let (turn_id, _) = sess
+                .commit(None, &CommitMode::NewTurns)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No user message to commit"))?;
+            return Ok((message, turn_id, None));
+        }
+
+        let (span_id, resolved) = coordinator
+            .replace_user_message(conversation_id, &turn_id, message.payload.content.clone())
+            .await?;
+
+        sess.truncate(Some(&turn_id));
+        sess.add_resolved(resolved);
+        Ok((message, turn_id, Some(span_id)))
+    }
+
     /// Store user input content and add to session pending
     async fn store_and_add_user_message(
         session: &Arc<Mutex<Session<S>>>,
@@ -329,6 +972,19 @@ impl<S: StorageTypes> ConversationManager<S> {
             anyhow::bail!("Empty content");
         }
 
+        // Ephemeral conversations keep the input in memory only
+        {
+            let mut sess = session.lock().await;
+            if sess.incognito() == Incognito::Ephemeral {
+                sess.add_user_message(content).await?;
+                return sess
+                    .pending_messages()
+                    .last()
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("Empty content"));
+            }
+        }
+
         // Store content and get refs
         let stored = coordinator
             .store_input_content(content, OriginKind::User)
@@ -352,27 +1008,111 @@ impl<S: StorageTypes> ConversationManager<S> {
     }
 
     /// Run agent and commit results
+    ///
+    /// Runs in a debug-level "turn" span; the agent's model calls and tool
+    /// calls get spans inside it.
+    #[tracing::instrument(
+        level = "debug",
+        name = "turn",
+        skip_all,
+        fields(conversation_id = %task.conversation_id, model = model.id(), turn = tracing::field::Empty)
+    )]
     async fn run_agent_and_commit(
-        conversation_id: &ConversationId,
-        session: &Arc<Mutex<Session<S>>>,
-        coordinator: &Arc<StorageCoordinator<S>>,
-        mcp_registry: &Arc<Mutex<McpRegistry>>,
-        document_resolver: &Arc<dyn DocumentResolver>,
+        task: &TaskContext<S>,
         execution_context: ExecutionContext,
         model: &Arc<dyn ChatModel + Send + Sync>,
         tool_config: ToolConfig,
         commit_mode: CommitMode,
-        event_tx: &SharedEventSender,
+        settings: &RunSettings,
+        token: &CancellationToken,
     ) {
+        let TaskContext { conversation_id, session, mcp_registry, document_resolver, cancel, event_tx, .. } = task;
+        let started = Instant::now();
+        // The span is disabled unless debug logging is on; skip the lookup then
+        if !tracing::Span::current().is_disabled() {
+            let turn = turn_index(session.lock().await.messages_for_display());
+            tracing::Span::current().record("turn", turn);
+        }
+
+        // With an interval, text deltas are buffered and flushed on a timer
+        let text_delta_interval = settings.text_delta_interval;
+        let delta_buffer = text_delta_interval
+            .map(|_| Arc::new(TextDeltaBuffer::new(conversation_id.clone(), event_tx.clone())));
+        let delta_flusher = delta_buffer.clone().zip(text_delta_interval).map(|(buffer, interval)| {
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                loop {
+                    ticker.tick().await;
+                    buffer.flush();
+                }
+            })
+        });
+
         // Create agent with enricher for noema-core tools
-        let tool_registry = McpToolRegistry::new(Arc::clone(mcp_registry));
-        let agent = McpAgent::with_enricher(
+        let tool_registry =
+            McpToolRegistry::new(Arc::clone(mcp_registry)).with_local_tools(settings.local_tools.clone());
+        let mut agent = McpAgent::with_enricher(
             Arc::new(tool_registry),
-            10,
+            settings.max_tool_iterations,
             Arc::clone(document_resolver),
             execution_context,
             create_noema_core_enricher(),
-        );
+        )
+        .with_max_tool_result_bytes(settings.max_tool_result_bytes)
+        .with_max_concurrent_tools(settings.max_concurrent_tools)
+        .with_generation_params(settings.generation_params.clone())
+        .with_cache_system_prompt(settings.cache_system_prompt)
+        .with_cancellation(token.clone())
+        .with_progress({
+            let event_tx = event_tx.clone();
+            let conversation_id = conversation_id.clone();
+            Arc::new(move |tool_call_id: &str, message: String| {
+                let _ = event_tx.send((
+                    conversation_id.clone(),
+                    ManagerEvent::ToolProgress { tool_call_id: tool_call_id.to_string(), message },
+                ));
+            })
+        })
+        .with_tool_call_delta({
+            let event_tx = event_tx.clone();
+            let conversation_id = conversation_id.clone();
+            let delta_buffer = delta_buffer.clone();
+            Arc::new(move |delta: &llm::ToolCallDelta| {
+                // Text buffered before the call goes out first
+                if let Some(buffer) = &delta_buffer {
+                    buffer.flush();
+                }
+                let _ = event_tx.send((
+                    conversation_id.clone(),
+                    ManagerEvent::ToolCallDelta {
+                        tool_call_id: delta.id.clone(),
+                        name: delta.name.clone(),
+                        args_fragment: delta.args_fragment.clone(),
+                    },
+                ));
+            })
+        })
+        .with_text_delta({
+            let event_tx = event_tx.clone();
+            let conversation_id = conversation_id.clone();
+            let delta_buffer = delta_buffer.clone();
+            Arc::new(move |text: &str| match &delta_buffer {
+                Some(buffer) => buffer.push(text),
+                None => {
+                    let _ = event_tx.send((conversation_id.clone(), ManagerEvent::TextDelta(text.to_string())));
+                }
+            })
+        })
+        .with_history_trimmed({
+            let event_tx = event_tx.clone();
+            let conversation_id = conversation_id.clone();
+            Arc::new(move |dropped_messages: usize| {
+                let _ = event_tx.send((conversation_id.clone(), ManagerEvent::HistoryTrimmed(dropped_messages)));
+            })
+        });
+        if settings.require_tool_approval {
+            agent = agent.with_tool_approval(Arc::clone(&task.approver));
+        }
 
         // Run agent
         let execute_result = {
@@ -384,10 +1124,30 @@ impl<S: StorageTypes> ConversationManager<S> {
             }
         };
 
+        // Send the last buffered text before the message completes
+        if let Some(flusher) = delta_flusher {
+            flusher.abort();
+        }
+        if let Some(buffer) = &delta_buffer {
+            buffer.flush();
+        }
+
+        if token.is_cancelled() {
+            tracing::debug!(elapsed_ms = started.elapsed().as_millis() as u64, "Turn cancelled");
+            Self::finish_cancelled(conversation_id, session, model, &commit_mode, cancel.keep_partial(), event_tx).await;
+            return;
+        }
+
+        // Hitting the tool-call limit still commits the transcript, then reports the error
+        let execute_result = match execute_result {
+            Err(e) if e.downcast_ref::<MaxIterationsExceeded>().is_some() => Ok(Some(ManagerError::from_provider(&e))),
+            other => other.map(|_| None),
+        };
+
         match execute_result {
-            Ok(_) => {
-                // Send streaming messages
-                {
+            Ok(limit_error) => {
+                // Send streaming messages and total up usage before pending is committed
+                let (usage, finish_reason) = {
                     let sess = session.lock().await;
                     for msg in sess.pending() {
                         // Skip user messages (already sent)
@@ -395,13 +1155,21 @@ impl<S: StorageTypes> ConversationManager<S> {
                             let _ = event_tx.send((conversation_id.clone(), ManagerEvent::StreamingMessage(msg.clone())));
                         }
                     }
-                }
+                    (
+                        sess.pending().iter().filter_map(|msg| msg.usage).reduce(|a, b| a + b),
+                        sess.pending().last().and_then(|msg| msg.finish_reason.clone()),
+                    )
+                };
+                tracing::debug!(
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    prompt_tokens = usage.map(|u| u.prompt_tokens),
+                    completion_tokens = usage.map(|u| u.completion_tokens),
+                    "Turn complete"
+                );
 
                 // Commit pending messages (assistant messages)
                 let commit_result = Self::commit_pending(
-                    conversation_id,
                     session,
-                    coordinator,
                     Some(model.id()),
                     &commit_mode,
                 ).await;
@@ -414,84 +1182,190 @@ impl<S: StorageTypes> ConversationManager<S> {
                             sess.messages_for_display().to_vec()
                         };
                         let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Complete(messages)));
+                        if let Some(usage) = usage {
+                            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Usage(usage)));
+                        }
+                        if let Some(finish_reason) = finish_reason {
+                            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Finished(finish_reason)));
+                        }
+                        if let Some(err) = limit_error {
+                            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(err)));
+                        }
                     }
                     Err(e) => {
-                        let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(format!("Failed to commit: {}", e))));
+                        let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(ManagerError::Storage(format!("Failed to commit: {}", e)))));
                     }
                 }
             }
             Err(e) => {
-                let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(e.to_string())));
+                tracing::debug!(elapsed_ms = started.elapsed().as_millis() as u64, error = %e, "Turn failed");
+                let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(ManagerError::from_provider(&e))));
             }
         }
     }
 
-    /// Commit pending messages to storage
-    /// Returns the (turn_id, span_id) of the first turn committed, if any
-    async fn commit_pending(
-        conversation_id: &ConversationId,
-        session: &Arc<Mutex<Session<S>>>,
-        coordinator: &Arc<StorageCoordinator<S>>,
-        model_id: Option<&str>,
-        commit_mode: &CommitMode,
-    ) -> Result<Option<(TurnId, SpanId)>> {
-        let mut sess = session.lock().await;
+    /// Have the model finish the last response, append what it writes, and
+    /// emit Complete (or Cancelled)
+    async fn continue_last_response(
+        task: &TaskContext<S>,
+        model: &Arc<dyn ChatModel + Send + Sync>,
+        settings: &RunSettings,
+        token: &CancellationToken,
+    ) {
+        let TaskContext { conversation_id, session, coordinator, cancel, event_tx, .. } = task;
+
+        let continuation = match Self::stream_continuation(
+            conversation_id,
+            session,
+            model,
+            &settings.generation_params,
+            settings.cache_system_prompt,
+            token,
+            event_tx,
+        ).await {
+            Ok(continuation) => continuation,
+            Err(e) => {
+                let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(ManagerError::from_provider(&e))));
+                return;
+            }
+        };
 
-        if sess.pending().is_empty() {
-            return Ok(None);
+        let cancelled = token.is_cancelled();
+        let text = continuation.get_text();
+        if !text.is_empty() && (!cancelled || cancel.keep_partial()) {
+            if let Err(e) = Self::append_to_last_response(conversation_id, session, coordinator, &text).await {
+                let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(ManagerError::Storage(format!("Failed to commit: {}", e)))));
+                return;
+            }
         }
 
-        let pending: Vec<ChatMessage> = sess.pending().to_vec();
+        let messages = {
+            let sess = session.lock().await;
+            sess.messages_for_display().to_vec()
+        };
+        if cancelled {
+            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Cancelled(messages)));
+            return;
+        }
+        let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Complete(messages)));
+        if let Some(usage) = continuation.usage {
+            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Usage(usage)));
+        }
+        if let Some(finish_reason) = continuation.finish_reason {
+            let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Finished(finish_reason)));
+        }
+    }
 
-        // Track current turn and span for adding messages
-        let mut current_turn: Option<TurnId> = None;
-        let mut current_span: Option<SpanId> = None;
-        let mut current_role = None;
-        let mut first_turn_info: Option<(TurnId, SpanId)> = None;
+    /// Ask the model to carry on from the last response, streaming its text
+    /// as TextDelta events
+    ///
+    /// The request is the conversation followed by `CONTINUE_PROMPT`, without
+    /// tools. Stops early, keeping the text so far, when `token` is cancelled.
+    async fn stream_continuation(
+        conversation_id: &ConversationId,
+        session: &Arc<Mutex<Session<S>>>,
+        model: &Arc<dyn ChatModel + Send + Sync>,
+        generation_params: &GenerationParams,
+        cache_system_prompt: bool,
+        token: &CancellationToken,
+        event_tx: &SharedEventSender,
+    ) -> Result<ChatMessage> {
+        use futures::StreamExt;
 
-        for msg in pending {
-            let origin = OriginKind::from(msg.role);
-            let span_role = msg.role;
+        let request = {
+            let mut sess = session.lock().await;
+            let mut messages: Vec<ChatMessage> = sess.messages().await?.iter().cloned().collect();
+            if messages.last().is_none_or(|msg| msg.role != Role::Assistant) {
+                anyhow::bail!("No response to continue");
+            }
+            messages.push(ChatMessage::user(ChatPayload::text(CONTINUE_PROMPT)));
+            ChatRequest::new(&messages)
+                .with_params(generation_params.clone())
+                .with_cache_system_prompt(cache_system_prompt)
+        };
 
-            // Get or create turn and span based on commit mode
-            let (turn_id, span_id) = match commit_mode {
-                CommitMode::AtTurn(tid) => {
-                    if current_span.is_none() {
-                        let span = coordinator
-                            .create_and_select_span(conversation_id, tid, model_id)
-                            .await?;
-                        current_turn = Some(tid.clone());
-                        current_span = Some(span);
-                    }
-                    (current_turn.as_ref().unwrap().clone(), current_span.as_ref().unwrap().clone())
-                }
-                CommitMode::NewTurns => {
-                    if current_role != Some(span_role) {
-                        let tid = coordinator.create_turn(span_role).await?;
-                        let span = coordinator
-                            .create_and_select_span(conversation_id, &tid, model_id)
-                            .await?;
-                        current_turn = Some(tid);
-                        current_span = Some(span);
-                        current_role = Some(span_role);
-                    }
-                    (current_turn.as_ref().unwrap().clone(), current_span.as_ref().unwrap().clone())
-                }
+        let mut stream = model.stream_chat(&request).await?;
+        let mut collector = StreamCollector::with_stop_sequences(generation_params.stop.as_deref().unwrap_or_default());
+        let emit = |delta: String| {
+            if !delta.is_empty() {
+                let _ = event_tx.send((conversation_id.clone(), ManagerEvent::TextDelta(delta)));
+            }
+        };
+
+        loop {
+            let chunk = tokio::select! {
+                chunk = stream.next() => chunk,
+                _ = token.cancelled() => None,
             };
+            let Some(chunk) = chunk else { break };
+            emit(collector.push(chunk?));
+        }
+        emit(collector.flush());
 
-            // Track first turn info for return value
-            if first_turn_info.is_none() {
-                first_turn_info = Some((turn_id.clone(), span_id.clone()));
-            }
+        let continuation = collector.into_text_message();
+        if let Some(blocked) = continuation.blocked() {
+            return Err(blocked.into());
+        }
+        Ok(continuation)
+    }
+
+    /// Append text to the last assistant message, in storage and in the session
+    async fn append_to_last_response(
+        conversation_id: &ConversationId,
+        session: &Arc<Mutex<Session<S>>>,
+        coordinator: &Arc<StorageCoordinator<S>>,
+        text: &str,
+    ) -> Result<()> {
+        let mut sess = session.lock().await;
+        let turn_id = sess
+            .messages_for_display()
+            .last()
+            .filter(|msg| msg.role == Role::Assistant)
+            .map(|msg| msg.turn_id.clone())
+            .ok_or_else(|| anyhow::anyhow!("No response to continue"))?;
+
+        let messages = coordinator.append_to_last_message(conversation_id, &turn_id, text).await?;
+        sess.truncate(Some(&turn_id));
+        for msg in messages {
+            sess.add_resolved(msg);
+        }
+        Ok(())
+    }
 
-            let resolved = coordinator
-                .add_message(&span_id, &turn_id, msg.role, msg.payload.content, origin)
-                .await?;
-            sess.add_resolved(resolved);
+    /// Keep or discard the partial response of a cancelled run, then emit Cancelled
+    async fn finish_cancelled(
+        conversation_id: &ConversationId,
+        session: &Arc<Mutex<Session<S>>>,
+        model: &Arc<dyn ChatModel + Send + Sync>,
+        commit_mode: &CommitMode,
+        keep_partial: bool,
+        event_tx: &SharedEventSender,
+    ) {
+        if keep_partial {
+            if let Err(e) = Self::commit_pending(session, Some(model.id()), commit_mode).await {
+                let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Error(ManagerError::Storage(format!("Failed to commit: {}", e)))));
+                return;
+            }
         }
 
-        sess.clear_pending();
-        Ok(first_turn_info)
+        let messages = {
+            let mut sess = session.lock().await;
+            sess.clear_pending();
+            sess.messages_for_display().to_vec()
+        };
+        let _ = event_tx.send((conversation_id.clone(), ManagerEvent::Cancelled(messages)));
+    }
+
+    /// Commit pending messages to storage
+    ///
+    /// Returns the turn and span of the first message committed, if any.
+    /// Ephemeral conversations are only committed in memory and have no span.
+    async fn commit_pending(
+        session: &Arc<Mutex<Session<S>>>,
+        model_id: Option<&str>,
+        commit_mode: &CommitMode,
+    ) -> Result<Option<(TurnId, Option<SpanId>)>> {
+        session.lock().await.commit(model_id, commit_mode).await
     }
 
     // ========================================================================
@@ -500,36 +1374,297 @@ impl<S: StorageTypes> ConversationManager<S> {
 
     /// Send a user message
     pub fn send_message(&self, content: Vec<InputContent>, tool_config: ToolConfig) {
-        let _ = self.cmd_tx.send(ManagerCommand::SendMessage { content, tool_config });
+        self.send(ManagerCommand::SendMessage { content, tool_config });
+    }
+
+    /// Append messages as they are (e.g. an MCP prompt's), without a response
+    ///
+    /// Emits `Complete` once they are stored. Messages sent after this are
+    /// answered with these in the history.
+    pub fn add_messages(&self, messages: Vec<ChatMessage>) {
+        if !messages.is_empty() {
+            self.send(ManagerCommand::AddMessages(messages));
+        }
     }
 
     /// Regenerate response at a turn
     pub fn regenerate(&self, turn_id: TurnId, tool_config: ToolConfig) {
-        let _ = self.cmd_tx.send(ManagerCommand::Truncate(Some(turn_id.clone())));
-        let _ = self.cmd_tx.send(ManagerCommand::RunAgent {
+        self.send(ManagerCommand::Truncate(Some(turn_id.clone())));
+        self.send(ManagerCommand::RunAgent {
             tool_config,
             commit_mode: CommitMode::AtTurn(turn_id),
         });
     }
 
+    /// Regenerate the response to the last user message
+    ///
+    /// Drops the last response, including any tool exchanges, and runs the
+    /// agent again from that user message, emitting the usual events. The new
+    /// response is stored as an alternate at the same turn. Fails if the
+    /// conversation does not end in a response to a user message, or while a
+    /// response is being generated.
+    pub async fn regenerate_last(&self, tool_config: ToolConfig) -> Result<()> {
+        if self.is_busy() {
+            anyhow::bail!("Cannot regenerate while a response is being generated");
+        }
+        let turn_id = {
+            let session = self.session.lock().await;
+            last_response_turn(session.messages_for_display())
+        };
+        let turn_id = turn_id.ok_or_else(|| anyhow::anyhow!("No response to regenerate"))?;
+        self.regenerate(turn_id, tool_config);
+        Ok(())
+    }
+
+    /// Replace the text of the last user message and generate a new response
+    ///
+    /// Everything after that message is dropped from the conversation; the
+    /// previous text stays available as an alternate of its turn. Fails while
+    /// a response is being generated.
+    pub fn edit_and_resend(&self, new_text: String, tool_config: ToolConfig) -> Result<()> {
+        if self.is_busy() {
+            anyhow::bail!("Cannot edit while a response is being generated");
+        }
+        self.send(ManagerCommand::EditAndResend { text: new_text, tool_config });
+        Ok(())
+    }
+
+    /// Continue the last response where the output token limit cut it off
+    ///
+    /// Sends the conversation with a request to carry on, streams the reply
+    /// as TextDelta events and appends it to the last assistant message. The
+    /// longer message is stored as an alternate at the same turn, and
+    /// Finished reports whether the continuation was cut off too. Fails if
+    /// the conversation does not end in an assistant message, or while a
+    /// response is being generated.
+    pub async fn continue_response(&self) -> Result<()> {
+        if self.is_busy() {
+            anyhow::bail!("Cannot continue while a response is being generated");
+        }
+        let ends_in_response = {
+            let session = self.session.lock().await;
+            session.messages_for_display().last().is_some_and(|msg| msg.role == Role::Assistant)
+        };
+        if !ends_in_response {
+            anyhow::bail!("No response to continue");
+        }
+        self.send(ManagerCommand::ContinueResponse);
+        Ok(())
+    }
+
+    /// Queue a command for the background task, cancelled by any later `cancel`
+    fn send(&self, command: ManagerCommand) {
+        let _ = self.cmd_tx.send((command, self.cancel.current()));
+    }
+
+    /// Whether a response is being generated
+    pub fn is_busy(&self) -> bool {
+        self.cancel.is_running()
+    }
+
     /// Run agent on current pending messages (for edit flow where session already has pending)
     pub fn run_agent(&self, tool_config: ToolConfig) {
-        let _ = self.cmd_tx.send(ManagerCommand::RunAgent {
+        self.send(ManagerCommand::RunAgent {
             tool_config,
             commit_mode: CommitMode::NewTurns,
         });
     }
 
+    /// Cancel the in-flight request, if any, and drop requests queued behind it
+    ///
+    /// With `keep_partial` the text streamed so far is committed as the
+    /// assistant response; otherwise it is discarded.
+    pub fn cancel(&self, keep_partial: bool) {
+        self.cancel.cancel(keep_partial);
+        // Unanswered approval requests are denied
+        self.pending_approvals.lock().unwrap().clear();
+    }
+
     /// Clear all history
     pub fn clear_history(&self) {
-        let _ = self.cmd_tx.send(ManagerCommand::Truncate(None));
+        self.send(ManagerCommand::Truncate(None));
     }
 
     /// Set the model (model_id should be in provider/model format)
     pub fn set_model(&mut self, model: Arc<dyn ChatModel + Send + Sync>, model_id: String) {
         self.model = Arc::clone(&model);
         self.model_id = model_id.clone();
-        let _ = self.cmd_tx.send(ManagerCommand::SetModel { model, model_id });
+        self.send(ManagerCommand::SetModel { model, model_id });
+    }
+
+    /// Set how many model/tool-call rounds a single request may run
+    ///
+    /// A request still calling tools after the last round is stopped with an
+    /// error event; the transcript so far is kept. Defaults to
+    /// [`DEFAULT_MAX_ITERATIONS`].
+    pub fn set_max_tool_iterations(&mut self, limit: usize) {
+        self.settings.max_tool_iterations = limit.max(1);
+        self.send_settings();
+    }
+
+    /// Get how many model/tool-call rounds a single request may run
+    pub fn max_tool_iterations(&self) -> usize {
+        self.settings.max_tool_iterations
+    }
+
+    /// Set how many bytes of text from one tool call are passed back to the model
+    ///
+    /// Longer results are truncated with a marker. MCP servers may override this
+    /// per server. Defaults to [`DEFAULT_MAX_TOOL_RESULT_BYTES`].
+    pub fn set_max_tool_result_bytes(&mut self, limit: usize) {
+        self.settings.max_tool_result_bytes = limit;
+        self.send_settings();
+    }
+
+    /// Get how many bytes of text from one tool call are passed back to the model
+    pub fn max_tool_result_bytes(&self) -> usize {
+        self.settings.max_tool_result_bytes
+    }
+
+    /// Set how many tool calls from one model response run at once
+    ///
+    /// Results are still added in the order the model made the calls; 1 runs
+    /// them one after another. Defaults to [`DEFAULT_MAX_CONCURRENT_TOOLS`].
+    pub fn set_max_concurrent_tools(&mut self, limit: usize) {
+        self.settings.max_concurrent_tools = limit.max(1);
+        self.send_settings();
+    }
+
+    /// Get how many tool calls from one model response run at once
+    pub fn max_concurrent_tools(&self) -> usize {
+        self.settings.max_concurrent_tools
+    }
+
+    /// Set the sampling settings (temperature, top_p, ...) for later requests
+    ///
+    /// Unset fields use the provider's defaults.
+    pub fn set_generation_params(&mut self, params: GenerationParams) {
+        self.settings.generation_params = params;
+        self.send_settings();
+    }
+
+    /// Get the sampling settings for later requests
+    pub fn generation_params(&self) -> &GenerationParams {
+        &self.settings.generation_params
+    }
+
+    /// Ask providers that support prompt caching to cache the system prompt
+    /// (off by default; see `ChatRequest::with_cache_system_prompt`)
+    ///
+    /// Cache hits show up in `TokenUsage::cached_tokens`.
+    pub fn set_cache_system_prompt(&mut self, cache: bool) {
+        self.settings.cache_system_prompt = cache;
+        self.send_settings();
+    }
+
+    /// Get whether providers are asked to cache the system prompt
+    pub fn cache_system_prompt(&self) -> bool {
+        self.settings.cache_system_prompt
+    }
+
+    /// Summarize old history now, keeping the most recent exchanges verbatim.
+    ///
+    /// Emits `ManagerEvent::Compacted` when done.
+    pub fn compact(&self) {
+        self.send(ManagerCommand::Compact);
+    }
+
+    /// Change when and how old history is summarized
+    pub fn set_compaction(&mut self, config: CompactionConfig) {
+        self.settings.compaction = config;
+        self.send_settings();
+    }
+
+    /// Get the history summarization settings
+    pub fn compaction(&self) -> &CompactionConfig {
+        &self.settings.compaction
+    }
+
+    /// Coalesce streamed text into at most one `TextDelta` event per
+    /// `interval` (None = send every delta as it arrives, the default)
+    ///
+    /// Buffered text is always sent before the message completes.
+    pub fn set_text_delta_interval(&mut self, interval: Option<Duration>) {
+        self.settings.text_delta_interval = interval;
+        self.send_settings();
+    }
+
+    /// Get how often streamed text is sent
+    pub fn text_delta_interval(&self) -> Option<Duration> {
+        self.settings.text_delta_interval
+    }
+
+    /// Make each tool call wait for the user's approval (off by default)
+    ///
+    /// Every call emits `ManagerEvent::ToolApprovalRequest`; the agent waits
+    /// until `respond_to_tool_approval` answers it.
+    pub fn set_require_tool_approval(&mut self, required: bool) {
+        self.settings.require_tool_approval = required;
+        self.send_settings();
+    }
+
+    /// Get whether tool calls wait for the user's approval
+    pub fn requires_tool_approval(&self) -> bool {
+        self.settings.require_tool_approval
+    }
+
+    /// Name the conversation from its first exchange if it has no name
+    /// (off by default)
+    ///
+    /// Emits `ManagerEvent::Renamed` with the title.
+    pub fn set_auto_name(&mut self, config: AutoNameConfig) {
+        self.settings.auto_name = config;
+        self.send_settings();
+    }
+
+    /// Get the conversation naming settings
+    pub fn auto_name(&self) -> &AutoNameConfig {
+        &self.settings.auto_name
+    }
+
+    /// Offer `read_file`, `write_file` and `list_dir` confined to `root`
+    /// (None removes them; see `file_tools`)
+    ///
+    /// Fails if `root` isn't an existing directory.
+    pub fn set_file_tools_root(&mut self, root: Option<PathBuf>) -> Result<()> {
+        let tools = match &root {
+            Some(root) => {
+                let mut tools = ToolRegistry::new();
+                Arc::new(FileTools::new(root)?).register(&mut tools);
+                Some(Arc::new(tools))
+            }
+            None => None,
+        };
+        self.file_tools_root = root;
+        self.settings.local_tools = tools;
+        self.send_settings();
+        Ok(())
+    }
+
+    /// Get the directory the file tools are confined to, if enabled
+    pub fn file_tools_root(&self) -> Option<&Path> {
+        self.file_tools_root.as_deref()
+    }
+
+    /// Hand the current settings to the background task for later requests
+    fn send_settings(&self) {
+        self.send(ManagerCommand::SetSettings(self.settings.clone()));
+    }
+
+    /// Answer a `ToolApprovalRequest`
+    ///
+    /// Answers bypass the command queue, which is blocked while the agent
+    /// waits for them.
+    pub fn respond_to_tool_approval(&self, tool_call_id: &str, approval: ToolApproval) -> Result<()> {
+        let sender = self
+            .pending_approvals
+            .lock()
+            .unwrap()
+            .remove(tool_call_id)
+            .ok_or_else(|| anyhow::anyhow!("No tool call {} is waiting for approval", tool_call_id))?;
+        sender
+            .send(approval)
+            .map_err(|_| anyhow::anyhow!("Tool call {} is no longer waiting for approval", tool_call_id))
     }
 
     /// Get conversation ID
@@ -542,6 +1677,24 @@ impl<S: StorageTypes> ConversationManager<S> {
         self.model.name()
     }
 
+    /// Get current model ID (provider/model format)
+    pub fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    /// Get the conversation's system prompt
+    pub async fn system_message(&self) -> Option<String> {
+        self.session.lock().await.system_message().map(str::to_string)
+    }
+
+    /// Set or clear the conversation's system prompt (persisted)
+    ///
+    /// Waits for any in-flight request to finish; the new prompt applies to
+    /// the next request.
+    pub async fn set_system_message(&self, text: Option<String>) -> Result<()> {
+        self.session.lock().await.set_system_message(text).await
+    }
+
     /// Get all messages (committed + pending) for display
     pub async fn all_messages(&self) -> Vec<ChatMessage> {
         self.session.lock().await.all_messages()
@@ -562,3 +1715,337 @@ impl<S: StorageTypes> ConversationManager<S> {
         self.session.lock().await.reload().await
     }
 }
+
+/// Whether a message was written by the user rather than returning tool results
+fn is_user_input(msg: &ResolvedMessage) -> bool {
+    msg.role == Role::User
+        && !msg
+            .content
+            .iter()
+            .all(|c| matches!(c, ResolvedContent::ToolResult(_)))
+}
+
+/// Index of the turn after `messages`: the number of user-written messages
+/// already committed
+fn turn_index(messages: &[ResolvedMessage]) -> usize {
+    messages.iter().filter(|msg| is_user_input(msg)).count()
+}
+
+/// Turn of the last user-written message
+fn last_user_turn(messages: &[ResolvedMessage]) -> Option<TurnId> {
+    messages
+        .iter()
+        .rfind(|msg| is_user_input(msg))
+        .map(|msg| msg.turn_id.clone())
+}
+
+/// First turn of the response following the last user-written message
+///
+/// Tool results are also user-role messages, so they are skipped when looking
+/// for the user message.
+fn last_response_turn(messages: &[ResolvedMessage]) -> Option<TurnId> {
+    let last_input = messages.iter().rposition(is_user_input)?;
+    let input_turn = &messages[last_input].turn_id;
+    messages[last_input + 1..]
+        .iter()
+        .find(|msg| &msg.turn_id != input_turn)
+        .map(|msg| msg.turn_id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_channel::event_channel;
+    use crate::mcp::McpConfig;
+    use crate::storage::implementations::memory::{
+        MemoryAssetStore, MemoryBlobStore, MemoryDocumentStore, MemoryEntityStore, MemoryStorage,
+        MemoryTextStore, MemoryTurnStore,
+    };
+    use crate::storage::traits::TurnStore;
+    use crate::storage::types::ConversationListOptions;
+    use llm::mock::MockChatModel;
+    use llm::ToolResult;
+
+    fn message(role: Role, content: ResolvedContent, turn: &str) -> ResolvedMessage {
+        ResolvedMessage::new(role, vec![content], TurnId::from(turn))
+    }
+
+    fn tool_output() -> ResolvedContent {
+        ResolvedContent::tool_result(ToolResult {
+            tool_call_id: "call_1".to_string(),
+            content: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_last_response_turn_skips_tool_exchanges() {
+        let messages = vec![
+            message(Role::User, ResolvedContent::text("hi"), "t1"),
+            message(Role::Assistant, ResolvedContent::text("hello"), "t2"),
+            message(Role::User, ResolvedContent::text("weather?"), "t3"),
+            message(Role::Assistant, ResolvedContent::text("checking"), "t4"),
+            message(Role::User, tool_output(), "t5"),
+            message(Role::Assistant, ResolvedContent::text("sunny"), "t6"),
+        ];
+        assert_eq!(last_response_turn(&messages), Some(TurnId::from("t4")));
+
+        // Nothing to regenerate without a response after the user message
+        assert_eq!(last_response_turn(&messages[..3]), None);
+        assert_eq!(last_response_turn(&[]), None);
+
+        // Editing targets the user's message, not the tool output
+        assert_eq!(last_user_turn(&messages), Some(TurnId::from("t3")));
+        assert_eq!(last_user_turn(&[]), None);
+
+        // Tool output doesn't start a turn
+        assert_eq!(turn_index(&messages), 2);
+    }
+
+    #[test]
+    fn test_provider_errors_are_classified() {
+        let http = |status: u16| -> anyhow::Error {
+            ProviderError::new(reqwest::StatusCode::from_u16(status).unwrap(), r#"{"error":{"message":"nope"}}"#).into()
+        };
+
+        assert_eq!(ManagerError::from_provider(&http(401)), ManagerError::Auth("nope".to_string()));
+        assert_eq!(ManagerError::from_provider(&http(404)), ManagerError::ModelNotFound("nope".to_string()));
+        assert_eq!(
+            ManagerError::from_provider(&http(429)),
+            ManagerError::RateLimited { retry_after: None }
+        );
+        assert!(matches!(ManagerError::from_provider(&http(500)), ManagerError::Provider(_)));
+        assert_eq!(
+            ManagerError::from_provider(&ProviderError::timeout("stalled").into()),
+            ManagerError::Network("stalled".to_string())
+        );
+        assert!(matches!(
+            ManagerError::from_provider(&MaxIterationsExceeded(3).into()),
+            ManagerError::Other(_)
+        ));
+        let blocked = ContentFiltered { category: Some("HARM_CATEGORY_HARASSMENT".to_string()) };
+        assert_eq!(
+            ManagerError::from_provider(&blocked.into()),
+            ManagerError::ContentFiltered { category: Some("HARM_CATEGORY_HARASSMENT".to_string()) }
+        );
+
+        // Display keeps the provider's message for existing string formatting
+        assert_eq!(
+            ManagerError::from_provider(&anyhow::anyhow!("bad response")).to_string(),
+            "bad response"
+        );
+    }
+
+    #[test]
+    fn test_text_delta_buffer_coalesces_until_flushed() {
+        let (event_tx, mut event_rx) = event_channel(16);
+        let buffer = TextDeltaBuffer::new(ConversationId::from_string("c1"), event_tx);
+
+        buffer.push("Hel");
+        buffer.push("lo");
+        assert!(event_rx.try_recv().is_err());
+
+        buffer.flush();
+        match event_rx.try_recv() {
+            Ok((_, ManagerEvent::TextDelta(text))) => assert_eq!(text, "Hello"),
+            other => panic!("expected one TextDelta, got {:?}", other),
+        }
+
+        // Nothing buffered, nothing sent
+        buffer.flush();
+        assert!(event_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_event_approver_waits_for_answer() {
+        let (event_tx, mut event_rx) = event_channel(16);
+        let pending = Arc::new(PendingApprovals::default());
+        let approver = EventApprover {
+            conversation_id: ConversationId::from_string("c1"),
+            event_tx,
+            pending: Arc::clone(&pending),
+        };
+        let call = llm::ToolCall {
+            id: "call_1".to_string(),
+            name: "delete_file".to_string(),
+            arguments: serde_json::json!({}),
+            extra: serde_json::Value::Null,
+        };
+        let waiting = tokio::spawn(async move { approver.approve(&call).await });
+
+        match event_rx.recv().await {
+            Some((_, ManagerEvent::ToolApprovalRequest(call))) => assert_eq!(call.id, "call_1"),
+            other => panic!("expected an approval request, got {:?}", other),
+        }
+        let answer = pending.lock().unwrap().remove("call_1").unwrap();
+        answer.send(ToolApproval::DenyWithMessage("not that file".to_string())).unwrap();
+        assert_eq!(
+            waiting.await.unwrap(),
+            ToolApproval::DenyWithMessage("not that file".to_string())
+        );
+    }
+
+    #[test]
+    fn test_clean_title() {
+        assert_eq!(clean_title("\"Planning a Trip to Kyoto.\"\n"), "Planning a Trip to Kyoto");
+        assert_eq!(clean_title("\n**Title:** Rust Lifetimes\nBecause..."), "Rust Lifetimes");
+        assert_eq!(clean_title("   "), "");
+        let long = "word ".repeat(40);
+        assert!(clean_title(&long).chars().count() <= MAX_TITLE_CHARS);
+    }
+
+    #[tokio::test]
+    async fn test_ephemeral_conversation_is_unlisted_and_unstored() {
+        let turn_store = Arc::new(MemoryTurnStore::new());
+        let coordinator = Arc::new(StorageCoordinator::<MemoryStorage>::new(
+            Arc::new(MemoryBlobStore::new()),
+            Arc::new(MemoryAssetStore::new()),
+            Arc::new(MemoryTextStore::new()),
+            Arc::new(MemoryEntityStore::new()),
+            Arc::clone(&turn_store),
+        ));
+        let user_id = UserId::new();
+        let listed = coordinator.create_conversation(&user_id, None).await.unwrap();
+        let conversation_id = coordinator.create_conversation(&user_id, None).await.unwrap();
+        coordinator.set_incognito(&conversation_id, Incognito::Ephemeral).await.unwrap();
+
+        let session = Session::open(Arc::clone(&coordinator), conversation_id.clone()).await.unwrap();
+        let (event_tx, mut event_rx) = event_channel(64);
+        let manager = ConversationManager::new(
+            session,
+            Arc::clone(&coordinator),
+            Arc::new(MockChatModel::builder("mock").text("hi").repeat_last().build()),
+            "mock/mock".to_string(),
+            Arc::new(Mutex::new(McpRegistry::new(McpConfig::default()))),
+            Arc::new(MemoryDocumentStore::new()),
+            user_id.clone(),
+            event_tx,
+        );
+        manager.send_message(vec![InputContent::Text { text: "hello".to_string() }], ToolConfig::default());
+
+        let display = loop {
+            match event_rx.recv().await {
+                Some((_, ManagerEvent::Complete(messages))) => break messages,
+                Some((_, ManagerEvent::Error(e))) => panic!("unexpected error: {}", e),
+                Some(_) => {}
+                None => panic!("event channel closed"),
+            }
+        };
+
+        // The session remembers the exchange, storage does not
+        assert_eq!(display.len(), 2);
+        assert_ne!(display[0].turn_id, display[1].turn_id);
+        assert!(coordinator.open_session(&conversation_id).await.unwrap().is_empty());
+        for message in &display {
+            assert!(turn_store.get_spans(&message.turn_id).await.unwrap().is_empty());
+        }
+
+        let default_list = coordinator
+            .list_conversations(&user_id, &ConversationListOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(default_list.iter().map(|c| &c.id).collect::<Vec<_>>(), vec![&listed]);
+        let all = coordinator
+            .list_conversations(&user_id, &ConversationListOptions::default().with_incognito())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_regenerate_last_fails_while_busy() {
+        let coordinator = Arc::new(StorageCoordinator::<MemoryStorage>::new(
+            Arc::new(MemoryBlobStore::new()),
+            Arc::new(MemoryAssetStore::new()),
+            Arc::new(MemoryTextStore::new()),
+            Arc::new(MemoryEntityStore::new()),
+            Arc::new(MemoryTurnStore::new()),
+        ));
+        let user_id = UserId::new();
+        let conversation_id = coordinator.create_conversation(&user_id, None).await.unwrap();
+        let session = Session::open(Arc::clone(&coordinator), conversation_id).await.unwrap();
+        let (event_tx, mut event_rx) = event_channel(64);
+        let model = MockChatModel::builder("mock")
+            .text("hi")
+            .repeat_last()
+            .with_delay(Duration::from_millis(100))
+            .build();
+        let manager = ConversationManager::new(
+            session,
+            Arc::clone(&coordinator),
+            Arc::new(model),
+            "mock/mock".to_string(),
+            Arc::new(Mutex::new(McpRegistry::new(McpConfig::default()))),
+            Arc::new(MemoryDocumentStore::new()),
+            user_id,
+            event_tx,
+        );
+        let hello = || vec![InputContent::Text { text: "hello".to_string() }];
+
+        manager.send_message(hello(), ToolConfig::default());
+        while !manager.is_busy() {
+            tokio::task::yield_now().await;
+        }
+        let err = manager.regenerate_last(ToolConfig::default()).await.unwrap_err();
+        assert!(err.to_string().contains("being generated"), "{}", err);
+
+        loop {
+            match event_rx.recv().await {
+                Some((_, ManagerEvent::Complete(_))) => break,
+                Some((_, ManagerEvent::Error(e))) => panic!("unexpected error: {}", e),
+                Some(_) => {}
+                None => panic!("event channel closed"),
+            }
+        }
+        while manager.is_busy() {
+            tokio::task::yield_now().await;
+        }
+        manager.regenerate_last(ToolConfig::default()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_before_the_request_starts_is_not_lost() {
+        let coordinator = Arc::new(StorageCoordinator::<MemoryStorage>::new(
+            Arc::new(MemoryBlobStore::new()),
+            Arc::new(MemoryAssetStore::new()),
+            Arc::new(MemoryTextStore::new()),
+            Arc::new(MemoryEntityStore::new()),
+            Arc::new(MemoryTurnStore::new()),
+        ));
+        let user_id = UserId::new();
+        let conversation_id = coordinator.create_conversation(&user_id, None).await.unwrap();
+        let session = Session::open(Arc::clone(&coordinator), conversation_id).await.unwrap();
+        let (event_tx, mut event_rx) = event_channel(64);
+        let model = Arc::new(MockChatModel::builder("mock").text("hi").repeat_last().build());
+        let manager = ConversationManager::new(
+            session,
+            Arc::clone(&coordinator),
+            model.clone(),
+            "mock/mock".to_string(),
+            Arc::new(Mutex::new(McpRegistry::new(McpConfig::default()))),
+            Arc::new(MemoryDocumentStore::new()),
+            user_id,
+            event_tx,
+        );
+        let hello = || vec![InputContent::Text { text: "hello".to_string() }];
+        let mut next_outcome = async || loop {
+            match event_rx.recv().await {
+                Some((_, event @ (ManagerEvent::Complete(_) | ManagerEvent::Cancelled(_)))) => break event,
+                Some((_, ManagerEvent::Error(e))) => panic!("unexpected error: {}", e),
+                Some(_) => {}
+                None => panic!("event channel closed"),
+            }
+        };
+
+        // Cancelling while idle leaves the next request alone
+        manager.cancel(false);
+        manager.send_message(hello(), ToolConfig::default());
+        assert!(matches!(next_outcome().await, ManagerEvent::Complete(_)));
+        assert_eq!(model.request_count(), 1);
+
+        // The background task hasn't picked the request up before the cancel
+        manager.send_message(hello(), ToolConfig::default());
+        manager.cancel(false);
+        assert!(matches!(next_outcome().await, ManagerEvent::Cancelled(_)));
+        assert_eq!(model.request_count(), 1);
+    }
+}
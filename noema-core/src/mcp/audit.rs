@@ -0,0 +1,242 @@
+//! Audit log of MCP tool calls
+//!
+//! When enabled (`audit_log = true` in mcp.toml, or a sink set with
+//! `McpRegistry::set_audit_sink`), every tool call made through
+//! `McpToolRegistry` is recorded: which server and tool, the arguments,
+//! how long it took and whether it succeeded. Argument values under
+//! secret-looking keys are masked before they are recorded.
+
+use anyhow::Result;
+use config::PathManager;
+use llm::ToolResultContent;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Argument keys whose values are never recorded (matched case-insensitively
+/// anywhere in the key, e.g. `github_token`)
+const SECRET_KEYS: &[&str] = &[
+    "secret",
+    "password",
+    "passwd",
+    "token",
+    "api_key",
+    "apikey",
+    "api-key",
+    "authorization",
+    "credential",
+    "private_key",
+];
+
+/// One tool call, as written to the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    /// When the call finished (RFC 3339)
+    pub timestamp: String,
+    /// Server that provided the tool (None for built-in tools, or if no
+    /// connected server had it)
+    pub server_id: Option<String>,
+    pub tool: String,
+    /// Arguments as sent, with secret values masked
+    pub arguments: serde_json::Value,
+    /// Size of the result: text bytes plus encoded media bytes
+    pub result_bytes: usize,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl ToolCallRecord {
+    pub fn new(
+        server_id: Option<String>,
+        tool: &str,
+        arguments: &serde_json::Value,
+        result: &Result<Vec<ToolResultContent>>,
+        duration: Duration,
+    ) -> Self {
+        let (result_bytes, error) = match result {
+            Ok(content) => (content.iter().map(content_bytes).sum(), None),
+            Err(e) => (0, Some(config::redact(&e.to_string()))),
+        };
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            server_id,
+            tool: tool.to_string(),
+            arguments: redact_arguments(arguments),
+            result_bytes,
+            duration_ms: duration.as_millis() as u64,
+            success: error.is_none(),
+            error,
+        }
+    }
+}
+
+fn content_bytes(content: &ToolResultContent) -> usize {
+    match content {
+        ToolResultContent::Text { text } => text.len(),
+        ToolResultContent::Image { data, .. } | ToolResultContent::Audio { data, .. } => data.len(),
+    }
+}
+
+/// Copy of `arguments` with values under secret-looking keys replaced and
+/// credentials inside other strings (e.g. bearer tokens) masked
+pub fn redact_arguments(arguments: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match arguments {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let key_lower = key.to_lowercase();
+                    let value = if SECRET_KEYS.iter().any(|secret| key_lower.contains(secret)) {
+                        Value::String(config::redact::REDACTED.to_string())
+                    } else {
+                        redact_arguments(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_arguments).collect()),
+        Value::String(s) => Value::String(config::redact(s)),
+        other => other.clone(),
+    }
+}
+
+/// Destination for tool call records
+pub trait AuditSink: Send + Sync {
+    fn record(&self, record: &ToolCallRecord);
+}
+
+/// Audit sink appending one JSON record per line to a file
+pub struct JsonlAuditSink {
+    path: PathBuf,
+    /// Serializes appends so concurrent calls don't interleave lines
+    write_lock: Mutex<()>,
+}
+
+impl JsonlAuditSink {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Sink writing to the default audit log (see `PathManager::tool_audit_log_path`)
+    pub fn open_default() -> Option<Self> {
+        PathManager::tool_audit_log_path().map(Self::new)
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Read the most recent `limit` records, newest first
+    pub fn recent(&self, limit: usize) -> Result<Vec<ToolCallRecord>> {
+        read_recent(&self.path, limit)
+    }
+}
+
+impl AuditSink for JsonlAuditSink {
+    fn record(&self, record: &ToolCallRecord) {
+        let Ok(line) = serde_json::to_string(record) else {
+            return;
+        };
+        let _guard = self.write_lock.lock().unwrap();
+        if let Some(parent) = self.path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match std::fs::OpenOptions::new().create(true).append(true).open(&self.path) {
+            Ok(mut file) => {
+                let _ = writeln!(file, "{}", line);
+            }
+            Err(e) => tracing::warn!("Failed to write tool audit log {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+/// Read the most recent `limit` records from an audit log, newest first
+///
+/// A missing log has no records; lines that don't parse are skipped.
+pub fn read_recent(path: &std::path::Path, limit: usize) -> Result<Vec<ToolCallRecord>> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut records: Vec<ToolCallRecord> = BufReader::new(file)
+        .lines()
+        .map_while(|line| line.ok())
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect();
+    records.reverse();
+    records.truncate(limit);
+    Ok(records)
+}
+
+/// Read the most recent `limit` records from the default audit log, newest first
+pub fn recent_tool_calls(limit: usize) -> Result<Vec<ToolCallRecord>> {
+    match PathManager::tool_audit_log_path() {
+        Some(path) => read_recent(&path, limit),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_arguments_masks_secret_keys() {
+        let arguments = json!({
+            "query": "weather",
+            "api_key": "sk-123",
+            "auth": { "GitHub_Token": "ghp_abc", "user": "ada" },
+            "headers": ["Authorization: Bearer abcdefghijkl"],
+        });
+        assert_eq!(
+            redact_arguments(&arguments),
+            json!({
+                "query": "weather",
+                "api_key": "[REDACTED]",
+                "auth": { "GitHub_Token": "[REDACTED]", "user": "ada" },
+                "headers": ["Authorization: Bearer [REDACTED]"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_jsonl_sink_reads_back_newest_first() {
+        let path = std::env::temp_dir().join(format!("tool_audit_{}.jsonl", uuid::Uuid::new_v4()));
+        let sink = JsonlAuditSink::new(path.clone());
+
+        let ok = Ok(vec![ToolResultContent::text("four")]);
+        let failed = Err(anyhow::anyhow!("Tool 'search' timed out after 1s"));
+        sink.record(&ToolCallRecord::new(
+            Some("docs".to_string()),
+            "read",
+            &json!({"password": "hunter2"}),
+            &ok,
+            Duration::from_millis(12),
+        ));
+        sink.record(&ToolCallRecord::new(None, "search", &json!({}), &failed, Duration::from_secs(1)));
+
+        let records = sink.recent(10).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].tool, "search");
+        assert!(!records[0].success);
+        assert_eq!(records[0].error.as_deref(), Some("Tool 'search' timed out after 1s"));
+        assert_eq!(records[1].server_id.as_deref(), Some("docs"));
+        assert_eq!(records[1].result_bytes, 4);
+        assert_eq!(records[1].duration_ms, 12);
+        assert_eq!(records[1].arguments, json!({"password": "[REDACTED]"}));
+        assert_eq!(sink.recent(1).unwrap().len(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(sink.recent(10).unwrap().is_empty());
+    }
+}
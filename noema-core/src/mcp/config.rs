@@ -1,6 +1,8 @@
+use crate::mcp::oauth::OAuthTokens;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Authentication method for an MCP server.
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
@@ -69,6 +71,126 @@ impl AuthMethod {
     pub fn needs_oauth_login(&self) -> bool {
         matches!(self, AuthMethod::OAuth { access_token: None, .. })
     }
+
+    /// Check if an expired OAuth token can be refreshed without logging in again
+    pub fn can_refresh(&self) -> bool {
+        matches!(
+            self,
+            AuthMethod::OAuth {
+                token_url: Some(_),
+                refresh_token: Some(_),
+                ..
+            }
+        )
+    }
+
+    /// Store tokens obtained from `endpoint`, which is remembered for refreshing
+    pub fn set_tokens(&mut self, endpoint: &str, tokens: OAuthTokens) {
+        if let AuthMethod::OAuth {
+            token_url,
+            access_token,
+            refresh_token,
+            expires_at,
+            ..
+        } = self
+        {
+            *token_url = Some(endpoint.to_string());
+            *access_token = Some(tokens.access_token);
+            if tokens.refresh_token.is_some() {
+                *refresh_token = tokens.refresh_token;
+            }
+            *expires_at = tokens
+                .expires_in
+                .map(|secs| chrono::Utc::now().timestamp() + secs);
+        }
+    }
+
+    /// Forget the OAuth tokens, so the server needs a new login
+    pub fn clear_tokens(&mut self) {
+        if let AuthMethod::OAuth {
+            access_token,
+            refresh_token,
+            expires_at,
+            ..
+        } = self
+        {
+            *access_token = None;
+            *refresh_token = None;
+            *expires_at = None;
+        }
+    }
+}
+
+/// How to reach an MCP server.
+///
+/// Flattened into `ServerConfig`, so HTTP servers keep the `url = "..."` form
+/// and stdio servers are configured with `command`, `args` and `env`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Transport {
+    /// Streamable HTTP endpoint
+    Http {
+        url: String,
+    },
+    /// Subprocess speaking JSON-RPC over stdin/stdout
+    Stdio {
+        command: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        env: HashMap<String, String>,
+    },
+}
+
+impl Transport {
+    /// The HTTP endpoint URL, if this is an HTTP transport
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Transport::Http { url } => Some(url),
+            Transport::Stdio { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Transport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Transport::Http { url } => write!(f, "{}", url),
+            Transport::Stdio { command, args, .. } => {
+                write!(f, "{}", command)?;
+                for arg in args {
+                    write!(f, " {}", arg)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Which of a server's tools are exposed to the model.
+///
+/// Entries are glob patterns (`*`, `?`, `[...]`) matched against tool names.
+/// A tool is allowed if `allow` is empty or one of its patterns matches, and
+/// no `deny` pattern matches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ToolFilter {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+impl ToolFilter {
+    /// Check whether a tool passes the filter
+    pub fn is_allowed(&self, tool_name: &str) -> bool {
+        let matches = |pattern: &String| {
+            glob::Pattern::new(pattern)
+                .map(|p| p.matches(tool_name))
+                .unwrap_or_else(|_| pattern == tool_name)
+        };
+        (self.allow.is_empty() || self.allow.iter().any(matches))
+            && !self.deny.iter().any(matches)
+    }
 }
 
 /// Configuration for a single MCP server.
@@ -76,8 +198,9 @@ impl AuthMethod {
 pub struct ServerConfig {
     /// Display name for the server
     pub name: String,
-    /// HTTP endpoint URL for the streamable HTTP server
-    pub url: String,
+    /// How to connect to the server
+    #[serde(flatten)]
+    pub transport: Transport,
     /// Authentication method
     #[serde(default)]
     pub auth: AuthMethod,
@@ -93,17 +216,84 @@ pub struct ServerConfig {
     /// Enable automatic retry with exponential backoff when connection fails
     #[serde(default = "default_true")]
     pub auto_retry: bool,
+    /// Restrict which tools are exposed to the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_filter: Option<ToolFilter>,
+    /// Per-tool execution timeout overrides in seconds, keyed by tool name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tool_timeouts: HashMap<String, u64>,
+    /// Override for how much tool-result text is passed back to the model, in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_result_bytes: Option<usize>,
+    /// Let the server ask for completions from the user's models (see `mcp::sampling`)
+    #[serde(default)]
+    pub allow_sampling: bool,
+}
+
+impl ServerConfig {
+    /// Timeout override for a tool, if one is configured
+    pub fn tool_timeout(&self, tool_name: &str) -> Option<Duration> {
+        self.tool_timeouts.get(tool_name).copied().map(Duration::from_secs)
+    }
+
+    /// Check whether a tool from this server may be exposed and called
+    pub fn is_tool_allowed(&self, tool_name: &str) -> bool {
+        self.tool_filter
+            .as_ref()
+            .is_none_or(|filter| filter.is_allowed(tool_name))
+    }
 }
 
 fn default_true() -> bool {
     true
 }
 
+/// Search service behind the built-in `search_web` tool
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebSearchBackendKind {
+    /// DuckDuckGo's Instant Answer API (no key needed)
+    #[default]
+    DuckDuckGo,
+    /// Google results through SerpAPI (needs an API key)
+    SerpApi,
+}
+
+/// Built-in web search, available without an MCP server
+///
+/// ```toml
+/// [web_search]
+/// backend = "serp_api"
+/// api_key_env = "SERPAPI_API_KEY"
+/// ```
+///
+/// Needs noema-core's `web-search` feature; ignored (with a warning) without it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WebSearchConfig {
+    #[serde(default)]
+    pub backend: WebSearchBackendKind,
+    /// Environment variable holding the backend's API key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// Results returned when the model doesn't ask for a number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_results: Option<usize>,
+}
+
 /// Root configuration containing all MCP servers.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct McpConfig {
     #[serde(default)]
     pub servers: HashMap<String, ServerConfig>,
+    /// Record every tool call in the audit log (see `mcp::audit`)
+    #[serde(default)]
+    pub audit_log: bool,
+    /// Offer the built-in `search_web` tool (see `web_search`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_search: Option<WebSearchConfig>,
+    /// Offer the built-in `current_time` and `calculator` tools (see `builtin_tools`)
+    #[serde(default)]
+    pub builtin_tools: bool,
 }
 
 impl McpConfig {
@@ -161,3 +351,147 @@ impl McpConfig {
         self.servers.get(id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_url_parses_as_http() {
+        let config: McpConfig = toml::from_str(
+            r#"
+            [servers.docs]
+            name = "Docs"
+            url = "http://localhost:3000/mcp"
+            "#,
+        )
+        .unwrap();
+
+        let server = config.get_server("docs").unwrap();
+        assert_eq!(
+            server.transport,
+            Transport::Http { url: "http://localhost:3000/mcp".to_string() }
+        );
+        assert!(server.auto_connect);
+    }
+
+    #[test]
+    fn test_stdio_transport_roundtrip() {
+        let mut config = McpConfig::default();
+        config.add_server(
+            "fs".to_string(),
+            ServerConfig {
+                name: "Filesystem".to_string(),
+                transport: Transport::Stdio {
+                    command: "npx".to_string(),
+                    args: vec!["-y".to_string(), "server-filesystem".to_string()],
+                    env: HashMap::from([("DEBUG".to_string(), "1".to_string())]),
+                },
+                auth: AuthMethod::None,
+                use_well_known: false,
+                auth_token: None,
+                auto_connect: true,
+                auto_retry: false,
+                tool_filter: None,
+                tool_timeouts: HashMap::from([("read_file".to_string(), 5)]),
+                max_tool_result_bytes: Some(4096),
+                allow_sampling: false,
+            },
+        );
+
+        let text = toml::to_string_pretty(&config).unwrap();
+        let parsed: McpConfig = toml::from_str(&text).unwrap();
+        let server = parsed.get_server("fs").unwrap();
+        assert_eq!(server.transport, config.get_server("fs").unwrap().transport);
+        assert_eq!(server.transport.url(), None);
+        assert_eq!(server.transport.to_string(), "npx -y server-filesystem");
+        assert_eq!(server.tool_timeout("read_file"), Some(Duration::from_secs(5)));
+        assert_eq!(server.tool_timeout("write_file"), None);
+        assert_eq!(server.max_tool_result_bytes, Some(4096));
+    }
+
+    #[test]
+    fn test_tool_filter() {
+        let filter = ToolFilter {
+            allow: vec!["read_*".to_string(), "search".to_string()],
+            deny: vec!["read_secret*".to_string()],
+        };
+        assert!(filter.is_allowed("read_file"));
+        assert!(filter.is_allowed("search"));
+        assert!(!filter.is_allowed("write_file"));
+        assert!(!filter.is_allowed("read_secrets"));
+
+        let deny_only = ToolFilter {
+            allow: vec![],
+            deny: vec!["delete_*".to_string()],
+        };
+        assert!(deny_only.is_allowed("list_files"));
+        assert!(!deny_only.is_allowed("delete_file"));
+    }
+
+    #[test]
+    fn test_tool_filter_parses_from_toml() {
+        let config: McpConfig = toml::from_str(
+            r#"
+            [servers.fs]
+            name = "Filesystem"
+            command = "fs-server"
+
+            [servers.fs.tool_filter]
+            deny = ["write_*"]
+            "#,
+        )
+        .unwrap();
+
+        let server = config.get_server("fs").unwrap();
+        assert!(server.is_tool_allowed("read_file"));
+        assert!(!server.is_tool_allowed("write_file"));
+    }
+
+    #[test]
+    fn test_oauth_token_lifecycle() {
+        let mut auth = AuthMethod::OAuth {
+            client_id: "noema".to_string(),
+            client_secret: None,
+            authorization_url: None,
+            token_url: None,
+            scopes: Vec::new(),
+            access_token: None,
+            refresh_token: None,
+            expires_at: None,
+        };
+        assert!(auth.needs_oauth_login());
+
+        auth.set_tokens(
+            "https://auth.example.com/token",
+            OAuthTokens {
+                access_token: "a1".to_string(),
+                refresh_token: Some("r1".to_string()),
+                expires_in: Some(30),
+            },
+        );
+        assert_eq!(auth.bearer_token(), Some("a1"));
+        assert!(auth.can_refresh());
+        // Within the 60 second margin
+        assert!(auth.is_token_expired());
+
+        // A refresh without a new refresh token keeps the old one
+        auth.set_tokens(
+            "https://auth.example.com/token",
+            OAuthTokens {
+                access_token: "a2".to_string(),
+                refresh_token: None,
+                expires_in: Some(3600),
+            },
+        );
+        assert!(!auth.is_token_expired());
+        assert!(matches!(
+            &auth,
+            AuthMethod::OAuth { refresh_token: Some(r), .. } if r == "r1"
+        ));
+
+        auth.clear_tokens();
+        assert!(auth.needs_oauth_login());
+        assert!(!auth.can_refresh());
+    }
+}
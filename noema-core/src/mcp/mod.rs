@@ -1,10 +1,19 @@
 //! MCP (Model Context Protocol) support for connecting to tool servers
 
+pub mod audit;
 mod config;
+mod oauth;
 mod registry;
+mod sampling;
 
-pub use config::{AuthMethod, McpConfig, ServerConfig};
+pub use audit::{AuditSink, JsonlAuditSink, ToolCallRecord};
+pub use config::{
+    AuthMethod, McpConfig, ServerConfig, ToolFilter, Transport, WebSearchBackendKind, WebSearchConfig,
+};
+pub use oauth::{exchange_code, refresh_tokens, OAuthTokens, Pkce};
 pub use registry::{
-    spawn_retry_task, start_auto_connect, ConnectedServer, McpRegistry, McpToolRegistry,
-    ServerStatus,
+    format_progress, spawn_retry_task, start_auto_connect, start_health_monitor, ConnectedServer,
+    McpRegistry, McpToolRegistry, ProgressCallback, PromptArgumentInfo, PromptInfo, ResourceInfo,
+    ServerStatus, StatusCallback,
 };
+pub use sampling::{Sampler, SamplingApprover, SamplingRequest};
@@ -0,0 +1,249 @@
+//! OAuth 2.0 token endpoint requests, shared by the login flow and token refresh
+
+use crate::mcp::config::AuthMethod;
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+/// Tokens returned by a token endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct OAuthTokens {
+    pub access_token: String,
+    /// Omitted by some servers on refresh, in which case the old one stays valid
+    pub refresh_token: Option<String>,
+    /// Lifetime of the access token in seconds
+    pub expires_in: Option<i64>,
+}
+
+/// The token endpoint refused the grant (e.g. `invalid_grant` for a
+/// revoked refresh token), so asking again won't help
+///
+/// Returned inside `anyhow::Error`; transport failures and server errors
+/// are not wrapped in it, since a later attempt may succeed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenRejected {
+    pub status: reqwest::StatusCode,
+    pub body: String,
+}
+
+impl std::fmt::Display for TokenRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Token request failed ({}): {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for TokenRejected {}
+
+/// PKCE (RFC 7636) verifier and its S256 challenge for one authorization request
+#[derive(Debug, Clone)]
+pub struct Pkce {
+    /// Kept until the token exchange, sent as `code_verifier`
+    pub verifier: String,
+    /// Sent with the authorization request as `code_challenge`
+    pub challenge: String,
+}
+
+impl Pkce {
+    /// Value of the `code_challenge_method` parameter
+    pub const METHOD: &'static str = "S256";
+
+    pub fn new() -> Self {
+        let verifier = URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>());
+        let challenge = Self::challenge_for(&verifier);
+        Self { verifier, challenge }
+    }
+
+    fn challenge_for(verifier: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+    }
+}
+
+impl Default for Pkce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Exchange an authorization code for tokens.
+/// `code_verifier` must be given if the authorization request used PKCE.
+pub async fn exchange_code(
+    token_url: &str,
+    code: &str,
+    redirect_uri: &str,
+    client_id: &str,
+    client_secret: Option<&str>,
+    code_verifier: Option<&str>,
+) -> Result<OAuthTokens> {
+    let mut params = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", redirect_uri),
+        ("client_id", client_id),
+    ];
+    if let Some(secret) = client_secret {
+        params.push(("client_secret", secret));
+    }
+    if let Some(verifier) = code_verifier {
+        params.push(("code_verifier", verifier));
+    }
+    request_tokens(token_url, &params).await
+}
+
+/// Obtain a new access token with the refresh token stored in `auth`
+pub async fn refresh_tokens(auth: &AuthMethod) -> Result<OAuthTokens> {
+    let AuthMethod::OAuth {
+        client_id,
+        client_secret,
+        token_url: Some(token_url),
+        refresh_token: Some(refresh_token),
+        ..
+    } = auth
+    else {
+        anyhow::bail!("No refresh token or token URL available");
+    };
+
+    let mut params = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token.as_str()),
+        ("client_id", client_id.as_str()),
+    ];
+    if let Some(secret) = client_secret {
+        params.push(("client_secret", secret.as_str()));
+    }
+    request_tokens(token_url, &params).await
+}
+
+async fn request_tokens(token_url: &str, params: &[(&str, &str)]) -> Result<OAuthTokens> {
+    let resp = reqwest::Client::new()
+        .post(token_url)
+        .form(params)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("Token request failed: {}", config::redact(&e.to_string())))?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = config::redact(&resp.text().await.unwrap_or_default());
+        // Timeouts and rate limits are worth another try; other 4xx answers refuse the grant
+        let rejected = status.is_client_error()
+            && status != reqwest::StatusCode::REQUEST_TIMEOUT
+            && status != reqwest::StatusCode::TOO_MANY_REQUESTS;
+        if rejected {
+            return Err(TokenRejected { status, body }.into());
+        }
+        anyhow::bail!("Token request failed ({}): {}", status, body);
+    }
+
+    let token_response: serde_json::Value =
+        resp.json().await.context("Failed to parse token response")?;
+    parse_tokens(&token_response)
+}
+
+fn parse_tokens(token_response: &serde_json::Value) -> Result<OAuthTokens> {
+    let access_token = token_response["access_token"]
+        .as_str()
+        .context("No access_token in response")?
+        .to_string();
+
+    Ok(OAuthTokens {
+        access_token,
+        refresh_token: token_response["refresh_token"].as_str().map(String::from),
+        expires_in: token_response["expires_in"].as_i64(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_tokens() {
+        let tokens = parse_tokens(&json!({
+            "access_token": "a",
+            "token_type": "Bearer",
+            "expires_in": 3600,
+        }))
+        .unwrap();
+        assert_eq!(
+            tokens,
+            OAuthTokens {
+                access_token: "a".to_string(),
+                refresh_token: None,
+                expires_in: Some(3600),
+            }
+        );
+
+        let err = parse_tokens(&json!({"error": "invalid_grant"})).unwrap_err();
+        assert_eq!(err.to_string(), "No access_token in response");
+    }
+
+    /// Token endpoint answering one request with `status` and `body`
+    async fn token_endpoint(status: u16, body: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).await;
+            let response = format!(
+                "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+        url
+    }
+
+    fn expired_auth(token_url: String) -> AuthMethod {
+        AuthMethod::OAuth {
+            client_id: "noema".to_string(),
+            client_secret: None,
+            authorization_url: None,
+            token_url: Some(token_url),
+            scopes: Vec::new(),
+            access_token: Some("old".to_string()),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: Some(0),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_refused_grants_are_rejections() {
+        let url = token_endpoint(400, r#"{"error": "invalid_grant"}"#).await;
+        let err = refresh_tokens(&expired_auth(url)).await.unwrap_err();
+        let rejected = err.downcast_ref::<TokenRejected>().unwrap();
+        assert_eq!(rejected.status, reqwest::StatusCode::BAD_REQUEST);
+        assert!(rejected.body.contains("invalid_grant"));
+
+        let url = token_endpoint(503, "").await;
+        let err = refresh_tokens(&expired_auth(url)).await.unwrap_err();
+        assert!(err.downcast_ref::<TokenRejected>().is_none(), "{}", err);
+
+        // Nothing listening
+        let port = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+        let err = refresh_tokens(&expired_auth(format!("http://127.0.0.1:{}/token", port)))
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<TokenRejected>().is_none(), "{}", err);
+    }
+
+    #[test]
+    fn test_pkce_challenge() {
+        // Example from RFC 7636, appendix B
+        assert_eq!(
+            Pkce::challenge_for("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let pkce = Pkce::new();
+        assert_eq!(pkce.verifier.len(), 43);
+        assert_eq!(pkce.challenge, Pkce::challenge_for(&pkce.verifier));
+        assert_ne!(pkce.verifier, Pkce::new().verifier);
+    }
+}
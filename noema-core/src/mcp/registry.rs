@@ -1,22 +1,97 @@
-use crate::mcp::config::{McpConfig, ServerConfig};
+use crate::mcp::audit::{AuditSink, JsonlAuditSink, ToolCallRecord};
+use crate::mcp::config::{AuthMethod, McpConfig, ServerConfig, Transport};
+use crate::mcp::oauth;
+use crate::mcp::sampling::Sampler;
 use crate::traffic_log;
 use anyhow::Result;
-use llm::{ToolDefinition, ToolResultContent};
+use llm::{ChatMessage, ChatPayload, ContentBlock, ToolDefinition, ToolRegistry, ToolResultContent};
+use futures::StreamExt;
 use rmcp::{
-    model::{CallToolRequestParam, RawContent, Tool},
-    service::{Peer, RunningService},
-    transport::streamable_http_client::{
-        StreamableHttpClientTransport, StreamableHttpClientTransportConfig,
+    handler::client::progress::ProgressDispatcher,
+    model::{
+        CallToolRequest, CallToolRequestParam, ClientInfo, ClientRequest, CreateMessageRequestMethod,
+        CreateMessageRequestParam, CreateMessageResult, GetPromptRequestParam, Meta, NumberOrString,
+        ProgressNotificationParam, ProgressToken, Prompt, PromptMessage, PromptMessageContent,
+        PromptMessageRole, RawContent, ReadResourceRequestParam, Resource, ResourceContents,
+        ServerResult, Tool,
     },
-    RoleClient, ServiceExt,
+    service::{NotificationContext, Peer, PeerRequestOptions, RequestContext, RunningService},
+    transport::{
+        streamable_http_client::{
+            StreamableHttpClientTransport, StreamableHttpClientTransportConfig,
+        },
+        IntoTransport, TokioChildProcess,
+    },
+    ClientHandler, ErrorData, RoleClient, ServiceExt,
 };
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex};
 use tokio_util::sync::CancellationToken;
 
+/// Callback receiving human-readable progress messages for a running tool call
+pub type ProgressCallback<'a> = &'a (dyn Fn(String) + Send + Sync);
+
+/// Client-side handler for a server connection.
+///
+/// Routes `notifications/progress` to whichever tool call subscribed to the
+/// notification's progress token, and `sampling/createMessage` to the
+/// sampler when the server may sample.
+#[derive(Clone, Default)]
+struct NoemaClient {
+    progress: ProgressDispatcher,
+    /// Display name of the server, for sampling requests
+    server_name: String,
+    /// None unless the server's config sets `allow_sampling`
+    sampler: Option<Arc<Sampler>>,
+}
+
+impl ClientHandler for NoemaClient {
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.progress.handle_notification(params).await;
+    }
+
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        match &self.sampler {
+            Some(sampler) => sampler.create_message(&self.server_name, params).await,
+            None => Err(ErrorData::method_not_found::<CreateMessageRequestMethod>()),
+        }
+    }
+
+    fn get_info(&self) -> ClientInfo {
+        let mut info = ClientInfo::default();
+        if self.sampler.is_some() {
+            info.capabilities.sampling = Some(Default::default());
+        }
+        info
+    }
+}
+
+/// Render a progress notification as e.g. "2/5 pages" or "Fetching (40%)"
+pub fn format_progress(params: &ProgressNotificationParam) -> String {
+    let counter = match params.total {
+        Some(total) if total > 0.0 && total <= 1.0 => {
+            format!("{:.0}%", params.progress / total * 100.0)
+        }
+        Some(total) => format!("{}/{}", params.progress, total),
+        None => format!("{}", params.progress),
+    };
+    match params.message.as_deref().filter(|m| !m.is_empty()) {
+        Some(message) => format!("{} ({})", message, counter),
+        None => counter,
+    }
+}
+
 /// A cloneable handle for calling MCP tools without holding registry locks.
 ///
 /// This is a lightweight wrapper around the rmcp Peer that can be cloned
@@ -24,6 +99,7 @@ use tokio_util::sync::CancellationToken;
 #[derive(Clone)]
 pub struct McpToolCaller {
     peer: Peer<RoleClient>,
+    progress: ProgressDispatcher,
 }
 
 impl McpToolCaller {
@@ -42,22 +118,175 @@ impl McpToolCaller {
             .await?;
         Ok(result)
     }
+
+    /// Call a tool, forwarding the server's progress notifications to `on_progress`.
+    ///
+    /// The request carries a fresh progress token; servers that don't report
+    /// progress simply never invoke the callback.
+    pub async fn call_tool_with_progress(
+        &self,
+        name: String,
+        arguments: Option<serde_json::Map<String, serde_json::Value>>,
+        on_progress: ProgressCallback<'_>,
+    ) -> Result<rmcp::model::CallToolResult> {
+        let token = ProgressToken(NumberOrString::String(
+            format!("noema-{}", uuid::Uuid::new_v4()).into(),
+        ));
+        // Subscribe before sending so early notifications aren't lost
+        let mut subscriber = self.progress.subscribe(token.clone()).await;
+
+        let mut meta = Meta::new();
+        meta.set_progress_token(token);
+        let request = ClientRequest::CallToolRequest(CallToolRequest {
+            method: Default::default(),
+            params: CallToolRequestParam {
+                name: name.into(),
+                arguments,
+            },
+            extensions: Default::default(),
+        });
+        let options = PeerRequestOptions {
+            timeout: None,
+            meta: Some(meta),
+        };
+        let handle = self.peer.send_request_with_option(request, options).await?;
+
+        let response = handle.await_response();
+        tokio::pin!(response);
+        let response = loop {
+            tokio::select! {
+                response = &mut response => break response?,
+                Some(params) = subscriber.next() => on_progress(format_progress(&params)),
+            }
+        };
+
+        match response {
+            ServerResult::CallToolResult(result) => Ok(result),
+            _ => Err(anyhow::anyhow!("Unexpected response to tools/call")),
+        }
+    }
+
+    /// List the server's tools (also used as a liveness check)
+    pub async fn list_tools(&self) -> Result<Vec<Tool>> {
+        let result = self.peer.list_tools(Default::default()).await?;
+        Ok(result.tools)
+    }
 }
 
-/// A connected MCP server with its available tools.
+/// A resource (document, file, ...) offered by a connected server
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceInfo {
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+impl From<Resource> for ResourceInfo {
+    fn from(resource: Resource) -> Self {
+        let raw = resource.raw;
+        Self {
+            uri: raw.uri,
+            name: raw.title.unwrap_or(raw.name),
+            description: raw.description,
+            mime_type: raw.mime_type,
+        }
+    }
+}
+
+/// A prompt template offered by a connected server (e.g. "summarize-pr")
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<PromptArgumentInfo>,
+}
+
+/// A value filled into a prompt template
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptArgumentInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
+}
+
+impl From<Prompt> for PromptInfo {
+    fn from(prompt: Prompt) -> Self {
+        Self {
+            name: prompt.name,
+            description: prompt.description,
+            arguments: prompt
+                .arguments
+                .unwrap_or_default()
+                .into_iter()
+                .map(|argument| PromptArgumentInfo {
+                    name: argument.name,
+                    description: argument.description,
+                    required: argument.required.unwrap_or(false),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Convert an expanded prompt message to a chat message
+///
+/// Embedded text resources become text; resource links and binary
+/// resources other than images and audio are dropped.
+fn prompt_message_to_chat(message: PromptMessage) -> ChatMessage {
+    let block = match message.content {
+        PromptMessageContent::Text { text } => Some(ContentBlock::Text { text }),
+        PromptMessageContent::Image { image } => Some(ContentBlock::Image {
+            data: image.raw.data,
+            mime_type: image.raw.mime_type,
+        }),
+        PromptMessageContent::Resource { resource } => match resource.raw.resource {
+            ResourceContents::TextResourceContents { text, .. } => Some(ContentBlock::Text { text }),
+            ResourceContents::BlobResourceContents { blob, mime_type, .. } => match mime_type {
+                Some(mime_type) if mime_type.starts_with("image/") => {
+                    Some(ContentBlock::Image { data: blob, mime_type })
+                }
+                Some(mime_type) if mime_type.starts_with("audio/") => {
+                    Some(ContentBlock::Audio { data: blob, mime_type })
+                }
+                _ => None,
+            },
+        },
+        PromptMessageContent::ResourceLink { .. } => None,
+    };
+    let payload = ChatPayload::new(block.into_iter().collect());
+    match message.role {
+        PromptMessageRole::User => ChatMessage::user(payload),
+        PromptMessageRole::Assistant => ChatMessage::assistant(payload),
+    }
+}
+
+/// A connected MCP server with its available tools, resources and prompts.
 pub struct ConnectedServer {
     pub config: ServerConfig,
     pub tools: Vec<Tool>,
-    service: RunningService<rmcp::RoleClient, ()>,
+    /// Empty when the server doesn't offer resources
+    pub resources: Vec<ResourceInfo>,
+    /// Empty when the server doesn't offer prompts
+    pub prompts: Vec<PromptInfo>,
+    service: RunningService<rmcp::RoleClient, NoemaClient>,
 }
 
 impl ConnectedServer {
+    /// Tools that pass the server's tool filter
+    pub fn allowed_tools(&self) -> impl Iterator<Item = &Tool> {
+        self.tools
+            .iter()
+            .filter(|tool| self.config.is_tool_allowed(&tool.name))
+    }
+
     /// Get a cloneable tool caller that can be used without holding registry locks.
     ///
     /// Use this when you need to make tool calls while releasing the registry lock.
     pub fn tool_caller(&self) -> McpToolCaller {
         McpToolCaller {
             peer: self.service.deref().clone(),
+            progress: self.service.service().progress.clone(),
         }
     }
 
@@ -77,6 +306,35 @@ impl ConnectedServer {
         Ok(result)
     }
 
+    /// Read a resource's contents by URI
+    pub async fn read_resource(&self, uri: &str) -> Result<Vec<ResourceContents>> {
+        let result = self
+            .service
+            .read_resource(ReadResourceRequestParam { uri: uri.to_string() })
+            .await?;
+        Ok(result.contents)
+    }
+
+    /// Expand a prompt template with `arguments` into the messages it stands for
+    ///
+    /// Messages left empty by the conversion (e.g. a lone resource link) are dropped.
+    pub async fn get_prompt(&self, name: &str, arguments: &HashMap<String, String>) -> Result<Vec<ChatMessage>> {
+        let arguments = arguments
+            .iter()
+            .map(|(key, value)| (key.clone(), serde_json::Value::String(value.clone())))
+            .collect();
+        let result = self
+            .service
+            .get_prompt(GetPromptRequestParam { name: name.to_string(), arguments: Some(arguments) })
+            .await?;
+        Ok(result
+            .messages
+            .into_iter()
+            .map(prompt_message_to_chat)
+            .filter(|message| !message.payload.content.is_empty())
+            .collect())
+    }
+
     /// Disconnect from the server
     pub async fn disconnect(self) -> Result<()> {
         self.service.cancel().await?;
@@ -97,6 +355,13 @@ pub enum ServerStatus {
     RetryStopped { last_error: String },
 }
 
+/// Outcome of an OAuth token request, stored by `McpRegistry::apply_token_refresh`
+struct TokenRefresh {
+    /// The auth method with the new tokens, or without tokens if rejected
+    auth: AuthMethod,
+    rejected: Option<oauth::TokenRejected>,
+}
+
 /// Registry managing MCP server connections.
 pub struct McpRegistry {
     config: McpConfig,
@@ -107,17 +372,64 @@ pub struct McpRegistry {
     server_status: HashMap<String, ServerStatus>,
     /// Ephemeral servers (not persisted to config)
     ephemeral_servers: HashMap<String, ServerConfig>,
+    /// Broadcasts (server_id, status) whenever a server's status changes
+    status_tx: broadcast::Sender<(String, ServerStatus)>,
+    /// Where tool calls are recorded (None = not audited)
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    /// Built-in tools served in-process, alongside the servers' tools
+    local_tools: Option<Arc<ToolRegistry>>,
+    /// Runs completions for servers allowed to sample (None = sampling off)
+    sampler: Option<Arc<Sampler>>,
+}
+
+/// Capacity of the status broadcast channel
+const STATUS_CHANNEL_CAPACITY: usize = 64;
+
+/// Add the built-in `search_web` tool to `tools`, if it can be set up
+#[cfg(feature = "web-search")]
+fn register_web_search(config: &crate::mcp::WebSearchConfig, tools: &mut ToolRegistry) {
+    match crate::web_search::WebSearch::from_config(config) {
+        Ok(search) => Arc::new(search).register(tools),
+        Err(e) => tracing::warn!("Web search disabled: {}", e),
+    }
+}
+
+#[cfg(not(feature = "web-search"))]
+fn register_web_search(_config: &crate::mcp::WebSearchConfig, _tools: &mut ToolRegistry) {
+    tracing::warn!("Ignoring [web_search] in mcp.toml: built without the web-search feature");
+}
+
+/// The built-in tools enabled in the configuration, if any
+fn configured_local_tools(config: &McpConfig) -> Option<Arc<ToolRegistry>> {
+    let mut tools = ToolRegistry::new();
+    if config.builtin_tools {
+        crate::builtin_tools::register(&mut tools);
+    }
+    if let Some(web_search) = &config.web_search {
+        register_web_search(web_search, &mut tools);
+    }
+    (!tools.is_empty()).then(|| Arc::new(tools))
 }
 
 impl McpRegistry {
     /// Create a new registry with the given configuration
     pub fn new(config: McpConfig) -> Self {
+        let audit_sink = if config.audit_log {
+            JsonlAuditSink::open_default().map(|sink| Arc::new(sink) as Arc<dyn AuditSink>)
+        } else {
+            None
+        };
+        let local_tools = configured_local_tools(&config);
         Self {
             config,
             connections: HashMap::new(),
             retry_tokens: HashMap::new(),
             server_status: HashMap::new(),
             ephemeral_servers: HashMap::new(),
+            status_tx: broadcast::channel(STATUS_CHANNEL_CAPACITY).0,
+            audit_sink,
+            local_tools,
+            sampler: None,
         }
     }
 
@@ -137,6 +449,29 @@ impl McpRegistry {
         &mut self.config
     }
 
+    /// Record tool calls to `sink` (None stops auditing)
+    ///
+    /// Replaces the JSONL log enabled by `audit_log` in the configuration.
+    pub fn set_audit_sink(&mut self, sink: Option<Arc<dyn AuditSink>>) {
+        self.audit_sink = sink;
+    }
+
+    /// Offer `tools` to agents alongside the servers' tools (None removes them)
+    ///
+    /// Replaces the built-in tools enabled in the configuration. A local tool
+    /// wins over a server tool with the same name.
+    pub fn set_local_tools(&mut self, tools: Option<Arc<ToolRegistry>>) {
+        self.local_tools = tools;
+    }
+
+    /// Run sampling requests of servers whose config sets `allow_sampling`
+    /// with `sampler` (None turns sampling off)
+    ///
+    /// Applies to connections made afterwards; reconnect a server to change it.
+    pub fn set_sampler(&mut self, sampler: Option<Arc<Sampler>>) {
+        self.sampler = sampler;
+    }
+
     /// List all configured servers (includes ephemeral servers)
     pub fn list_servers(&self) -> Vec<(&str, &ServerConfig)> {
         let mut servers: Vec<_> = self.config
@@ -165,58 +500,236 @@ impl McpRegistry {
         self.connections.get(id)
     }
 
+    /// Read a resource from a connected server
+    pub async fn read_resource(&self, server_id: &str, uri: &str) -> Result<Vec<ResourceContents>> {
+        let connection = self
+            .get_connection(server_id)
+            .ok_or_else(|| anyhow::anyhow!("Server '{}' is not connected", server_id))?;
+        connection.read_resource(uri).await
+    }
+
+    /// Expand a prompt template of a connected server into messages to seed a conversation
+    pub async fn get_prompt(
+        &self,
+        server_id: &str,
+        name: &str,
+        arguments: &HashMap<String, String>,
+    ) -> Result<Vec<ChatMessage>> {
+        let connection = self
+            .get_connection(server_id)
+            .ok_or_else(|| anyhow::anyhow!("Server '{}' is not connected", server_id))?;
+        connection.get_prompt(name, arguments).await
+    }
+
     /// Connect to a configured server (checks both persistent and ephemeral)
+    ///
+    /// An expired OAuth token is refreshed first when a refresh token is available.
     pub async fn connect(&mut self, id: &str) -> Result<&ConnectedServer> {
         if self.connections.contains_key(id) {
             return Ok(self.connections.get(id).unwrap());
         }
 
         // Check persistent config first, then ephemeral
-        let server_config = self
+        let mut server_config = self
             .config
             .get_server(id)
             .or_else(|| self.ephemeral_servers.get(id))
             .ok_or_else(|| anyhow::anyhow!("Server '{}' not found in configuration", id))?
             .clone();
 
-        let connected = Self::connect_to_server(&server_config).await?;
+        if server_config.auth.is_token_expired() && server_config.auth.can_refresh() {
+            server_config.auth = self.refresh_oauth_token(id, &server_config.auth).await?;
+        }
+
+        let connected = Self::connect_to_server(&server_config, self.sampler.clone()).await?;
         self.connections.insert(id.to_string(), connected);
+        self.set_status(id, ServerStatus::Connected);
         Ok(self.connections.get(id).unwrap())
     }
 
-    /// Connect to a server configuration (public for retry task access)
-    pub async fn connect_to_server(config: &ServerConfig) -> Result<ConnectedServer> {
-        // Get bearer token from auth method (new) or legacy auth_token field
-        let bearer_token = config.auth.bearer_token().or(config.auth_token.as_deref());
-
-        let transport = if let Some(token) = bearer_token {
-            let mut transport_config =
-                StreamableHttpClientTransportConfig::with_uri(Arc::from(config.url.as_str()));
-            transport_config = transport_config.auth_header(token.to_string());
-            StreamableHttpClientTransport::from_config(transport_config)
-        } else {
-            StreamableHttpClientTransport::from_uri(config.url.as_str())
+    /// Refresh an expired OAuth token and persist the new tokens.
+    ///
+    /// If the token endpoint rejects the refresh token, the stored tokens are
+    /// cleared so the server shows up as needing a new login, and the error
+    /// holds an [`oauth::TokenRejected`]. Other failures (network errors,
+    /// server errors) keep the tokens for a later attempt.
+    async fn refresh_oauth_token(&mut self, id: &str, auth: &AuthMethod) -> Result<AuthMethod> {
+        let refresh = Self::request_token_refresh(id, auth).await?;
+        self.apply_token_refresh(id, refresh)
+    }
+
+    /// Like `refresh_oauth_token`, but only locks the registry to store the
+    /// result, not while waiting for the token endpoint
+    async fn refresh_oauth_token_unlocked(
+        registry: &Mutex<McpRegistry>,
+        id: &str,
+        auth: &AuthMethod,
+    ) -> Result<AuthMethod> {
+        let refresh = Self::request_token_refresh(id, auth).await?;
+        registry.lock().await.apply_token_refresh(id, refresh)
+    }
+
+    /// Ask the token endpoint for new tokens without touching the registry.
+    ///
+    /// Fails only for errors that keep the current tokens; a rejected
+    /// refresh token comes back as a `TokenRefresh` with cleared tokens.
+    async fn request_token_refresh(id: &str, auth: &AuthMethod) -> Result<TokenRefresh> {
+        let mut auth = auth.clone();
+        match oauth::refresh_tokens(&auth).await {
+            Ok(tokens) => {
+                if let AuthMethod::OAuth { token_url: Some(endpoint), .. } = &auth {
+                    let endpoint = endpoint.clone();
+                    auth.set_tokens(&endpoint, tokens);
+                }
+                Ok(TokenRefresh { auth, rejected: None })
+            }
+            Err(e) => match e.downcast::<oauth::TokenRejected>() {
+                Ok(rejected) => {
+                    tracing::warn!("OAuth token refresh for '{}' was rejected: {}", id, rejected);
+                    auth.clear_tokens();
+                    Ok(TokenRefresh { auth, rejected: Some(rejected) })
+                }
+                Err(e) => {
+                    tracing::warn!("OAuth token refresh for '{}' failed: {}", id, e);
+                    Err(e.context(format!("Could not refresh the OAuth token for '{}'", id)))
+                }
+            },
+        }
+    }
+
+    /// Persist the outcome of `request_token_refresh`, returning the new auth
+    fn apply_token_refresh(&mut self, id: &str, refresh: TokenRefresh) -> Result<AuthMethod> {
+        let TokenRefresh { auth, rejected } = refresh;
+        if let Some(server) = self.config.servers.get_mut(id) {
+            server.auth = auth.clone();
+            self.save_config()?;
+        }
+
+        match rejected {
+            None => Ok(auth),
+            Some(rejected) => {
+                let message = format!("OAuth session for '{}' expired, log in again", id);
+                self.set_status(id, ServerStatus::RetryStopped { last_error: message.clone() });
+                Err(anyhow::Error::new(rejected).context(message))
+            }
+        }
+    }
+
+    /// Reconnect a server whose OAuth token has expired, refreshing the token
+    ///
+    /// Takes the registry's mutex so the lock is only held to take out the
+    /// stale connection and to store the new one, not across the token
+    /// request, the disconnect or the reconnect.
+    pub async fn ensure_fresh_token(registry: &Mutex<McpRegistry>, id: &str) -> Result<()> {
+        let (stale, mut config, sampler) = {
+            let mut reg = registry.lock().await;
+            let expired = reg
+                .connections
+                .get(id)
+                .is_some_and(|c| c.config.auth.is_token_expired() && c.config.auth.can_refresh());
+            let config = reg.config.get_server(id).or_else(|| reg.ephemeral_servers.get(id)).cloned();
+            let Some(config) = config.filter(|_| expired) else {
+                return Ok(());
+            };
+            (reg.take_connection(id), config, reg.sampler.clone())
         };
 
-        let service = ().serve(transport).await?;
+        if let Some(stale) = stale {
+            stale.disconnect().await?;
+        }
+        // Another caller may already have stored fresh tokens in the config
+        if config.auth.is_token_expired() && config.auth.can_refresh() {
+            config.auth = Self::refresh_oauth_token_unlocked(registry, id, &config.auth).await?;
+        }
+        let connected = Self::connect_to_server(&config, sampler).await?;
+        registry.lock().await.store_connection(id, connected);
+        Ok(())
+    }
+
+    /// Connect to a server configuration (public for retry task access)
+    ///
+    /// `sampler` runs the server's sampling requests if its config sets `allow_sampling`.
+    pub async fn connect_to_server(config: &ServerConfig, sampler: Option<Arc<Sampler>>) -> Result<ConnectedServer> {
+        match &config.transport {
+            Transport::Http { url } => {
+                // Get bearer token from auth method (new) or legacy auth_token field
+                let bearer_token = config.auth.bearer_token().or(config.auth_token.as_deref());
+
+                let transport = if let Some(token) = bearer_token {
+                    let mut transport_config =
+                        StreamableHttpClientTransportConfig::with_uri(Arc::from(url.as_str()));
+                    transport_config = transport_config.auth_header(token.to_string());
+                    StreamableHttpClientTransport::from_config(transport_config)
+                } else {
+                    StreamableHttpClientTransport::from_uri(url.as_str())
+                };
+
+                Self::connect_with_transport(config, sampler, transport).await
+            }
+            Transport::Stdio { command, args, env } => {
+                let mut cmd = tokio::process::Command::new(command);
+                cmd.args(args).envs(env);
+                Self::connect_with_transport(config, sampler, TokioChildProcess::new(cmd)?).await
+            }
+        }
+    }
 
+    /// Connect over an already-established transport (e.g. an in-process pipe)
+    pub async fn connect_with_transport<T, E, A>(
+        config: &ServerConfig,
+        sampler: Option<Arc<Sampler>>,
+        transport: T,
+    ) -> Result<ConnectedServer>
+    where
+        T: IntoTransport<RoleClient, E, A>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let client = NoemaClient {
+            progress: ProgressDispatcher::default(),
+            server_name: config.name.clone(),
+            sampler: sampler.filter(|_| config.allow_sampling),
+        };
+        let service = client.serve(transport).await?;
         let tools_result = service.list_tools(Default::default()).await?;
+        // Servers without the capability may reject resources/list and
+        // prompts/list outright
+        let capabilities = service.peer_info().map(|info| info.capabilities.clone()).unwrap_or_default();
+        let resources = if capabilities.resources.is_some() {
+            service.list_all_resources().await?.into_iter().map(ResourceInfo::from).collect()
+        } else {
+            Vec::new()
+        };
+        let prompts = if capabilities.prompts.is_some() {
+            service.list_all_prompts().await?.into_iter().map(PromptInfo::from).collect()
+        } else {
+            Vec::new()
+        };
 
         Ok(ConnectedServer {
             config: config.clone(),
             tools: tools_result.tools,
+            resources,
+            prompts,
             service,
         })
     }
 
     /// Disconnect from a server
     pub async fn disconnect(&mut self, id: &str) -> Result<()> {
-        if let Some(connection) = self.connections.remove(id) {
+        if let Some(connection) = self.take_connection(id) {
             connection.disconnect().await?;
         }
         Ok(())
     }
 
+    /// Remove a server's connection and mark it disconnected, leaving the
+    /// caller to close it (e.g. after releasing the registry lock)
+    fn take_connection(&mut self, id: &str) -> Option<ConnectedServer> {
+        let connection = self.connections.remove(id)?;
+        self.set_status(id, ServerStatus::Disconnected);
+        Some(connection)
+    }
+
     /// Disconnect from all servers
     pub async fn disconnect_all(&mut self) -> Result<()> {
         let ids: Vec<String> = self.connections.keys().cloned().collect();
@@ -236,12 +749,16 @@ impl McpRegistry {
     pub fn register_ephemeral(&mut self, id: String, url: String) {
         let config = ServerConfig {
             name: id.clone(),
-            url,
+            transport: Transport::Http { url },
             auth: crate::mcp::AuthMethod::None,
             auth_token: None,
             auto_connect: true,
             auto_retry: false,
             use_well_known: false,
+            tool_filter: None,
+            tool_timeouts: HashMap::new(),
+            max_tool_result_bytes: None,
+            allow_sampling: false,
         };
         self.ephemeral_servers.insert(id, config);
     }
@@ -294,9 +811,19 @@ impl McpRegistry {
         statuses
     }
 
-    /// Update server status
+    /// Update server status and broadcast the change
     pub fn set_status(&mut self, id: &str, status: ServerStatus) {
-        self.server_status.insert(id.to_string(), status);
+        if self.server_status.get(id) == Some(&status) {
+            return;
+        }
+        self.server_status.insert(id.to_string(), status.clone());
+        // No subscribers is fine - the status map is the source of truth
+        let _ = self.status_tx.send((id.to_string(), status));
+    }
+
+    /// Subscribe to server status changes
+    pub fn subscribe_status(&self) -> broadcast::Receiver<(String, ServerStatus)> {
+        self.status_tx.subscribe()
     }
 
     /// Check if a retry is active for a server
@@ -324,7 +851,7 @@ impl McpRegistry {
     /// Store a successful connection (called from retry task)
     pub fn store_connection(&mut self, id: &str, server: ConnectedServer) {
         self.connections.insert(id.to_string(), server);
-        self.server_status.insert(id.to_string(), ServerStatus::Connected);
+        self.set_status(id, ServerStatus::Connected);
         self.retry_tokens.remove(id);
     }
 
@@ -354,18 +881,38 @@ const INITIAL_BACKOFF_MS: u64 = 1000; // 1 second
 const MAX_BACKOFF_MS: u64 = 60000; // 1 minute
 const BACKOFF_MULTIPLIER: f64 = 2.0;
 
+/// Callback invoked with (server_id, status) when a retry task changes status
+pub type StatusCallback = Box<dyn Fn(&str, &ServerStatus) + Send + Sync>;
+
+/// Reconnect attempts made after a failed health check
+const HEALTH_RECONNECT_MAX_ATTEMPTS: u32 = 5;
+/// How long a health check ping may take before the server is considered dead
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Spawn a background retry task for connecting to an MCP server.
 /// Returns a cancellation token that can be used to stop the retry loop.
 pub fn spawn_retry_task(
     registry: Arc<Mutex<McpRegistry>>,
     server_id: String,
     config: ServerConfig,
-    on_status_change: Option<Box<dyn Fn(&str, &ServerStatus) + Send + Sync>>,
+    on_status_change: Option<StatusCallback>,
+) -> CancellationToken {
+    spawn_bounded_retry_task(registry, server_id, config, None, on_status_change)
+}
+
+/// Like `spawn_retry_task`, but gives up after `max_attempts` if set.
+fn spawn_bounded_retry_task(
+    registry: Arc<Mutex<McpRegistry>>,
+    server_id: String,
+    config: ServerConfig,
+    max_attempts: Option<u32>,
+    on_status_change: Option<StatusCallback>,
 ) -> CancellationToken {
     let token = CancellationToken::new();
     let cancel_token = token.clone();
 
     tokio::spawn(async move {
+        let mut config = config;
         let mut attempt: u32 = 0;
         let mut backoff_ms = INITIAL_BACKOFF_MS;
 
@@ -373,16 +920,42 @@ pub fn spawn_retry_task(
             attempt += 1;
 
             // Update status to retrying
-            {
+            let sampler = {
                 let mut reg = registry.lock().await;
                 reg.set_status(&server_id, ServerStatus::Retrying { attempt });
                 if let Some(ref cb) = on_status_change {
                     cb(&server_id, &ServerStatus::Retrying { attempt });
                 }
-            }
+                reg.sampler.clone()
+            };
+
+            // Refresh an expired OAuth token; retrying cannot help if the
+            // token endpoint rejects it, but can if it was unreachable
+            let refreshed = if config.auth.is_token_expired() && config.auth.can_refresh() {
+                match McpRegistry::refresh_oauth_token_unlocked(&registry, &server_id, &config.auth).await {
+                    Ok(auth) => {
+                        config.auth = auth;
+                        Ok(())
+                    }
+                    Err(e) if e.downcast_ref::<oauth::TokenRejected>().is_some() => {
+                        registry.lock().await.remove_retry_token(&server_id);
+                        if let Some(ref cb) = on_status_change {
+                            cb(&server_id, &ServerStatus::RetryStopped { last_error: e.to_string() });
+                        }
+                        return;
+                    }
+                    Err(e) => Err(e),
+                }
+            } else {
+                Ok(())
+            };
 
             // Try to connect
-            match McpRegistry::connect_to_server(&config).await {
+            let result = match refreshed {
+                Ok(()) => McpRegistry::connect_to_server(&config, sampler).await,
+                Err(e) => Err(e),
+            };
+            match result {
                 Ok(connected) => {
                     // Success! Store connection and exit
                     let mut reg = registry.lock().await;
@@ -401,8 +974,8 @@ pub fn spawn_retry_task(
                         e
                     );
 
-                    // Check if auto_retry is still enabled
-                    let should_retry = {
+                    // Check if auto_retry is still enabled and attempts remain
+                    let should_retry = max_attempts.is_none_or(|max| attempt < max) && {
                         let reg = registry.lock().await;
                         reg.config()
                             .get_server(&server_id)
@@ -474,11 +1047,11 @@ pub async fn start_auto_connect(
         }
 
         // Clone callback for this server's retry task
-        let cb: Option<Box<dyn Fn(&str, &ServerStatus) + Send + Sync>> =
+        let cb: Option<StatusCallback> =
             on_status_change.as_ref().map(|f| {
                 let f = Arc::clone(f);
                 Box::new(move |id: &str, status: &ServerStatus| f(id, status))
-                    as Box<dyn Fn(&str, &ServerStatus) + Send + Sync>
+                    as StatusCallback
             });
 
         // Spawn retry task
@@ -494,6 +1067,73 @@ pub async fn start_auto_connect(
     count
 }
 
+/// Start a background task that periodically checks connected servers.
+///
+/// Each tick pings every connected server. A server that fails to answer
+/// within `HEALTH_CHECK_TIMEOUT` is marked disconnected and, if it has
+/// `auto_retry` enabled, reconnected with a bounded backoff. Reconnects use
+/// the registry's retry tokens, so `cancel_retry` stops them like any other
+/// retry. Returns a cancellation token that stops the monitor.
+pub fn start_health_monitor(registry: Arc<Mutex<McpRegistry>>, interval: Duration) -> CancellationToken {
+    let token = CancellationToken::new();
+    let cancel_token = token.clone();
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = cancel_token.cancelled() => return,
+                _ = tokio::time::sleep(interval) => {}
+            }
+
+            // Collect callers under the lock, ping without it
+            let callers: Vec<(String, McpToolCaller)> = {
+                let reg = registry.lock().await;
+                reg.connected_servers()
+                    .map(|(id, server)| (id.to_string(), server.tool_caller()))
+                    .collect()
+            };
+
+            for (server_id, caller) in callers {
+                let error = match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, caller.list_tools()).await {
+                    Ok(Ok(_)) => continue,
+                    Ok(Err(e)) => e.to_string(),
+                    Err(_) => "Health check timed out".to_string(),
+                };
+                tracing::warn!("MCP server '{}' failed health check: {}", server_id, error);
+
+                // Drop the connection and schedule reconnects under the
+                // lock; close the connection after releasing it
+                let stale = {
+                    let mut reg = registry.lock().await;
+                    let stale = reg.take_connection(&server_id);
+                    let config = reg
+                        .config()
+                        .get_server(&server_id)
+                        .or_else(|| reg.get_ephemeral(&server_id))
+                        .cloned()
+                        .filter(|config| config.auto_retry && !reg.is_retry_active(&server_id));
+                    if let Some(config) = config {
+                        let retry_token = spawn_bounded_retry_task(
+                            Arc::clone(&registry),
+                            server_id.clone(),
+                            config,
+                            Some(HEALTH_RECONNECT_MAX_ATTEMPTS),
+                            None,
+                        );
+                        reg.set_retry_token(&server_id, retry_token);
+                    }
+                    stale
+                };
+                if let Some(stale) = stale {
+                    stale.disconnect().await.ok();
+                }
+            }
+        }
+    });
+
+    token
+}
+
 /// Convert an MCP Tool to an llm ToolDefinition
 fn mcp_tool_to_definition(tool: &Tool) -> ToolDefinition {
     // Convert the MCP JsonObject schema to schemars RootSchema
@@ -625,22 +1265,43 @@ fn mcp_content_to_tool_result(content: &RawContent) -> Option<ToolResultContent>
 /// any changes to connected MCP servers - new connections are immediately available.
 pub struct McpToolRegistry {
     mcp_registry: Arc<Mutex<McpRegistry>>,
+    /// Local tools of this registry only (e.g. a conversation's file tools),
+    /// consulted before the shared registry's
+    own_tools: Option<Arc<ToolRegistry>>,
 }
 
 impl McpToolRegistry {
     /// Create a new dynamic MCP tool registry
     pub fn new(mcp_registry: Arc<Mutex<McpRegistry>>) -> Self {
-        Self { mcp_registry }
+        Self { mcp_registry, own_tools: None }
+    }
+
+    /// Also offer `tools`, ahead of the shared registry's local and server tools
+    pub fn with_local_tools(mut self, tools: Option<Arc<ToolRegistry>>) -> Self {
+        self.own_tools = tools;
+        self
     }
 
     /// Get all tool definitions from all connected MCP servers.
     /// This is called fresh each time to reflect current connections.
     pub async fn get_all_definitions(&self) -> Vec<ToolDefinition> {
         let registry = self.mcp_registry.lock().await;
-        let mut definitions = Vec::new();
+        let mut definitions = self
+            .own_tools
+            .as_ref()
+            .map(|tools| tools.get_all_definitions())
+            .unwrap_or_default();
+        if let Some(tools) = &registry.local_tools {
+            definitions.extend(
+                tools
+                    .get_all_definitions()
+                    .into_iter()
+                    .filter(|def| !self.own_tools.as_ref().is_some_and(|own| own.has_tool(&def.name))),
+            );
+        }
 
         for (_server_id, server) in registry.connected_servers() {
-            for tool in &server.tools {
+            for tool in server.allowed_tools() {
                 definitions.push(mcp_tool_to_definition(tool));
             }
         }
@@ -651,17 +1312,84 @@ impl McpToolRegistry {
     /// Call a tool by name, routing to the appropriate MCP server.
     /// Returns multimodal content (text, images, audio).
     pub async fn call(&self, name: &str, args: serde_json::Value) -> Result<Vec<ToolResultContent>> {
+        self.call_with_progress(name, args, &|_| {}).await
+    }
+
+    /// Like [`call`](Self::call), but reports the server's progress
+    /// notifications for this call through `on_progress`.
+    ///
+    /// The call is recorded if the registry has an audit sink.
+    pub async fn call_with_progress(
+        &self,
+        name: &str,
+        args: serde_json::Value,
+        on_progress: ProgressCallback<'_>,
+    ) -> Result<Vec<ToolResultContent>> {
+        let audit_sink = self.mcp_registry.lock().await.audit_sink.clone();
+        let Some(audit_sink) = audit_sink else {
+            return self.call_unaudited(name, args, on_progress).await;
+        };
+
+        let server_id = self.get_server_for_tool(name).await;
+        let arguments = args.clone();
+        let started = Instant::now();
+        let result = self.call_unaudited(name, args, on_progress).await;
+        audit_sink.record(&ToolCallRecord::new(server_id, name, &arguments, &result, started.elapsed()));
+        result
+    }
+
+    async fn call_unaudited(
+        &self,
+        name: &str,
+        args: serde_json::Value,
+        on_progress: ProgressCallback<'_>,
+    ) -> Result<Vec<ToolResultContent>> {
         traffic_log::log_mcp_request(name, &args);
 
+        if let Some(tools) = self.local_tools_with(name).await {
+            return match tools.call(name, args).await {
+                Ok(text) => {
+                    let content = vec![ToolResultContent::text(text)];
+                    traffic_log::log_mcp_response(name, &content);
+                    Ok(content)
+                }
+                Err(e) => {
+                    traffic_log::log_mcp_error(name, &e.to_string());
+                    Err(e)
+                }
+            };
+        }
+
         // Get the tool caller and coerced arguments under the lock, then release it
         // before making the actual call. This prevents deadlock when tools spawn
         // subconversations that need to use the same registry.
+        // Refresh an expired OAuth token before calling the server
+        let server_id = {
+            let registry = self.mcp_registry.lock().await;
+            let server_id = registry
+                .connected_servers()
+                .find(|(_, server)| server.tools.iter().any(|t| t.name == name))
+                .map(|(id, _)| id.to_string());
+            server_id
+        };
+        if let Some(server_id) = server_id {
+            if let Err(e) = McpRegistry::ensure_fresh_token(&self.mcp_registry, &server_id).await {
+                traffic_log::log_mcp_error(name, &e.to_string());
+                return Err(e);
+            }
+        }
+
         let (tool_caller, arguments) = {
             let registry = self.mcp_registry.lock().await;
 
-            // Find which server has this tool
+            // Find which server has this tool (denied tools are never called)
             let mut found = None;
+            let mut denied = false;
             for (_server_id, server) in registry.connected_servers() {
+                if !server.config.is_tool_allowed(name) {
+                    denied |= server.tools.iter().any(|t| t.name == name);
+                    continue;
+                }
                 if let Some(tool) = server.tools.iter().find(|t| t.name == name) {
                     // Coerce arguments to match the tool's schema
                     let schema = serde_json::to_value(&*tool.input_schema).unwrap_or_default();
@@ -677,7 +1405,11 @@ impl McpToolRegistry {
             match found {
                 Some(f) => f,
                 None => {
-                    let err_msg = format!("Tool '{}' not found in any connected MCP server", name);
+                    let err_msg = if denied {
+                        format!("Tool '{}' is disabled by the server's tool filter", name)
+                    } else {
+                        format!("Tool '{}' not found in any connected MCP server", name)
+                    };
                     traffic_log::log_mcp_error(name, &err_msg);
                     return Err(anyhow::anyhow!(err_msg));
                 }
@@ -685,7 +1417,10 @@ impl McpToolRegistry {
         }; // Lock released here
 
         // Make the call without holding the registry lock
-        match tool_caller.call_tool(name.to_string(), arguments).await {
+        match tool_caller
+            .call_tool_with_progress(name.to_string(), arguments, on_progress)
+            .await
+        {
             Ok(result) => {
                 // Convert MCP content to our ToolResultContent format
                 let content: Vec<ToolResultContent> = result
@@ -704,16 +1439,46 @@ impl McpToolRegistry {
         }
     }
 
-    /// Check if a tool exists in any connected server
+    /// Timeout override configured for a tool by the server that provides it
+    pub async fn tool_timeout(&self, name: &str) -> Option<Duration> {
+        let registry = self.mcp_registry.lock().await;
+        let timeout = registry
+            .connected_servers()
+            .find(|(_, server)| server.allowed_tools().any(|t| t.name == name))
+            .and_then(|(_, server)| server.config.tool_timeout(name));
+        timeout
+    }
+
+    /// Tool-result size limit configured by the server that provides a tool
+    pub async fn max_tool_result_bytes(&self, name: &str) -> Option<usize> {
+        let registry = self.mcp_registry.lock().await;
+        let limit = registry
+            .connected_servers()
+            .find(|(_, server)| server.allowed_tools().any(|t| t.name == name))
+            .and_then(|(_, server)| server.config.max_tool_result_bytes);
+        limit
+    }
+
+    /// Check if a tool is built in or exists in any connected server
     pub async fn has_tool(&self, name: &str) -> bool {
-        self.get_server_for_tool(name).await.is_some()
+        self.local_tools_with(name).await.is_some() || self.get_server_for_tool(name).await.is_some()
+    }
+
+    /// The built-in tools providing `name`, cloned out so calls don't hold
+    /// the registry lock
+    async fn local_tools_with(&self, name: &str) -> Option<Arc<ToolRegistry>> {
+        if let Some(tools) = self.own_tools.as_ref().filter(|tools| tools.has_tool(name)) {
+            return Some(Arc::clone(tools));
+        }
+        let shared = self.mcp_registry.lock().await.local_tools.clone();
+        shared.filter(|tools| tools.has_tool(name))
     }
 
-    /// Get the server ID that provides a tool
+    /// Get the server ID that provides a tool (None for built-in tools)
     pub async fn get_server_for_tool(&self, name: &str) -> Option<String> {
         let registry = self.mcp_registry.lock().await;
         for (server_id, server) in registry.connected_servers() {
-            if server.tools.iter().any(|t| t.name == name) {
+            if server.allowed_tools().any(|t| t.name == name) {
                 return Some(server_id.to_string());
             }
         }
@@ -725,3 +1490,323 @@ impl McpToolRegistry {
         self.get_server_for_tool(tool_name).await.as_deref() == Some(server_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{
+        AnnotateAble, GetPromptResult, ListPromptsResult, ListResourcesResult, PaginatedRequestParam,
+        PromptArgument, RawResource, ReadResourceResult, ServerCapabilities, ServerInfo,
+    };
+    use rmcp::service::RequestContext;
+    use rmcp::{ErrorData, RoleServer, ServerHandler};
+
+    fn progress(progress: f64, total: Option<f64>, message: Option<&str>) -> ProgressNotificationParam {
+        ProgressNotificationParam {
+            progress_token: ProgressToken(NumberOrString::Number(1)),
+            progress,
+            total,
+            message: message.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_format_progress() {
+        assert_eq!(format_progress(&progress(2.0, Some(5.0), Some("pages"))), "pages (2/5)");
+        assert_eq!(format_progress(&progress(0.4, Some(1.0), Some("Fetching"))), "Fetching (40%)");
+        assert_eq!(format_progress(&progress(3.0, None, None)), "3");
+        assert_eq!(format_progress(&progress(1.0, Some(4.0), Some(""))), "1/4");
+    }
+
+    /// Input schema accepting any JSON object
+    fn any_object_schema() -> schemars::schema::RootSchema {
+        schemars::schema_for!(serde_json::Map<String, serde_json::Value>)
+    }
+
+    #[tokio::test]
+    async fn test_local_tools_are_offered_and_called() {
+        let mut local = ToolRegistry::new();
+        local.register(
+            ToolDefinition { name: "echo".to_string(), description: None, input_schema: any_object_schema() },
+            |args| async move { Ok(args["text"].as_str().unwrap_or_default().to_string()) },
+        );
+        let mut registry = McpRegistry::new(McpConfig::default());
+        registry.set_local_tools(Some(Arc::new(local)));
+        let tools = McpToolRegistry::new(Arc::new(Mutex::new(registry)));
+
+        let names: Vec<String> = tools.get_all_definitions().await.into_iter().map(|d| d.name).collect();
+        assert_eq!(names, vec!["echo"]);
+        assert!(tools.has_tool("echo").await);
+        assert_eq!(tools.get_server_for_tool("echo").await, None);
+
+        let content = tools.call("echo", serde_json::json!({"text": "hi"})).await.unwrap();
+        assert!(matches!(content.as_slice(), [ToolResultContent::Text { text }] if text == "hi"));
+        assert!(tools.call("missing", serde_json::json!({})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_own_tools_come_before_shared_ones() {
+        let echo = |reply: &'static str| {
+            let mut tools = ToolRegistry::new();
+            tools.register(
+                ToolDefinition { name: "echo".to_string(), description: None, input_schema: any_object_schema() },
+                move |_| async move { Ok(reply.to_string()) },
+            );
+            Arc::new(tools)
+        };
+        let mut registry = McpRegistry::new(McpConfig::default());
+        registry.set_local_tools(Some(echo("shared")));
+        let registry = Arc::new(Mutex::new(registry));
+
+        let tools = McpToolRegistry::new(Arc::clone(&registry)).with_local_tools(Some(echo("own")));
+        assert_eq!(tools.get_all_definitions().await.len(), 1);
+        let content = tools.call("echo", serde_json::json!({})).await.unwrap();
+        assert!(matches!(content.as_slice(), [ToolResultContent::Text { text }] if text == "own"));
+
+        let content = McpToolRegistry::new(registry).call("echo", serde_json::json!({})).await.unwrap();
+        assert!(matches!(content.as_slice(), [ToolResultContent::Text { text }] if text == "shared"));
+    }
+
+    #[tokio::test]
+    async fn test_builtin_tools_are_opt_in() {
+        let registry = McpRegistry::new(McpConfig::default());
+        let tools = McpToolRegistry::new(Arc::new(Mutex::new(registry)));
+        assert!(tools.get_all_definitions().await.is_empty());
+
+        let config = McpConfig { builtin_tools: true, ..McpConfig::default() };
+        let tools = McpToolRegistry::new(Arc::new(Mutex::new(McpRegistry::new(config))));
+        let mut names: Vec<String> = tools.get_all_definitions().await.into_iter().map(|d| d.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["calculator", "current_time"]);
+
+        let content = tools.call("calculator", serde_json::json!({"expression": "2^10"})).await.unwrap();
+        assert!(matches!(content.as_slice(), [ToolResultContent::Text { text }] if text == "1024"));
+    }
+
+    /// MCP server offering a single text document and a prompt for
+    /// summarizing a topic, when `with_content` is set. Its "draft" tool
+    /// asks the client's model for the text.
+    struct DocsServer {
+        with_content: bool,
+    }
+
+    impl ServerHandler for DocsServer {
+        fn get_info(&self) -> ServerInfo {
+            let capabilities = if self.with_content {
+                ServerCapabilities::builder().enable_tools().enable_resources().enable_prompts().build()
+            } else {
+                ServerCapabilities::builder().enable_tools().build()
+            };
+            ServerInfo { capabilities, ..Default::default() }
+        }
+
+        async fn call_tool(
+            &self,
+            _request: CallToolRequestParam,
+            context: RequestContext<RoleServer>,
+        ) -> Result<rmcp::model::CallToolResult, ErrorData> {
+            let result = context
+                .peer
+                .create_message(CreateMessageRequestParam {
+                    messages: vec![rmcp::model::SamplingMessage {
+                        role: rmcp::model::Role::User,
+                        content: rmcp::model::Content::text("Draft an intro"),
+                    }],
+                    model_preferences: None,
+                    system_prompt: None,
+                    include_context: None,
+                    temperature: None,
+                    max_tokens: 100,
+                    stop_sequences: None,
+                    metadata: None,
+                })
+                .await
+                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+            Ok(rmcp::model::CallToolResult::success(vec![result.message.content]))
+        }
+
+        async fn list_resources(
+            &self,
+            _request: Option<PaginatedRequestParam>,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ListResourcesResult, ErrorData> {
+            let mut readme = RawResource::new("file:///README.md", "README.md");
+            readme.description = Some("Project overview".to_string());
+            readme.mime_type = Some("text/markdown".to_string());
+            Ok(ListResourcesResult::with_all_items(vec![readme.no_annotation()]))
+        }
+
+        async fn read_resource(
+            &self,
+            request: ReadResourceRequestParam,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ReadResourceResult, ErrorData> {
+            if request.uri != "file:///README.md" {
+                return Err(ErrorData::resource_not_found(request.uri, None));
+            }
+            Ok(ReadResourceResult {
+                contents: vec![ResourceContents::text("# Noema", request.uri)],
+            })
+        }
+
+        async fn list_prompts(
+            &self,
+            _request: Option<PaginatedRequestParam>,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ListPromptsResult, ErrorData> {
+            let topic = PromptArgument {
+                name: "topic".to_string(),
+                title: None,
+                description: Some("What to summarize".to_string()),
+                required: Some(true),
+            };
+            let prompt = Prompt::new("summarize", Some("Summarize a topic"), Some(vec![topic]));
+            Ok(ListPromptsResult::with_all_items(vec![prompt]))
+        }
+
+        async fn get_prompt(
+            &self,
+            request: GetPromptRequestParam,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<GetPromptResult, ErrorData> {
+            let topic = request
+                .arguments
+                .as_ref()
+                .and_then(|arguments| arguments.get("topic"))
+                .and_then(|topic| topic.as_str())
+                .ok_or_else(|| ErrorData::invalid_params("topic is required", None))?;
+            Ok(GetPromptResult {
+                description: None,
+                messages: vec![
+                    PromptMessage::new_text(PromptMessageRole::User, "Be brief."),
+                    PromptMessage::new_text(PromptMessageRole::Assistant, "Understood."),
+                    PromptMessage::new_resource(
+                        PromptMessageRole::User,
+                        "file:///README.md".to_string(),
+                        None,
+                        Some(format!("Summarize {}", topic)),
+                        None,
+                        None,
+                        None,
+                    ),
+                    PromptMessage::new_resource_link(
+                        PromptMessageRole::User,
+                        RawResource::new("file:///README.md", "README.md").no_annotation(),
+                    ),
+                ],
+            })
+        }
+    }
+
+    async fn connect_docs_server(with_content: bool) -> ConnectedServer {
+        connect_docs_server_sampling(with_content, false, None).await
+    }
+
+    async fn connect_docs_server_sampling(
+        with_content: bool,
+        allow_sampling: bool,
+        sampler: Option<Arc<Sampler>>,
+    ) -> ConnectedServer {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            if let Ok(server) = (DocsServer { with_content }).serve(server_io).await {
+                let _ = server.waiting().await;
+            }
+        });
+        let config = ServerConfig {
+            name: "docs".to_string(),
+            transport: Transport::Stdio { command: "unused".to_string(), args: Vec::new(), env: HashMap::new() },
+            auth: Default::default(),
+            use_well_known: false,
+            auth_token: None,
+            auto_connect: false,
+            auto_retry: false,
+            tool_filter: None,
+            tool_timeouts: HashMap::new(),
+            max_tool_result_bytes: None,
+            allow_sampling,
+        };
+        McpRegistry::connect_with_transport(&config, sampler, client_io).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_resources_are_listed_and_read() {
+        let server = connect_docs_server(true).await;
+        assert_eq!(
+            server.resources,
+            vec![ResourceInfo {
+                uri: "file:///README.md".to_string(),
+                name: "README.md".to_string(),
+                description: Some("Project overview".to_string()),
+                mime_type: Some("text/markdown".to_string()),
+            }]
+        );
+
+        let mut registry = McpRegistry::new(McpConfig::default());
+        registry.store_connection("docs", server);
+        let contents = registry.read_resource("docs", "file:///README.md").await.unwrap();
+        assert!(matches!(
+            contents.as_slice(),
+            [ResourceContents::TextResourceContents { text, .. }] if text == "# Noema"
+        ));
+        assert!(registry.read_resource("docs", "file:///missing").await.is_err());
+        assert!(registry.read_resource("other", "file:///README.md").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_prompts_expand_into_messages() {
+        let server = connect_docs_server(true).await;
+        assert_eq!(
+            server.prompts,
+            vec![PromptInfo {
+                name: "summarize".to_string(),
+                description: Some("Summarize a topic".to_string()),
+                arguments: vec![PromptArgumentInfo {
+                    name: "topic".to_string(),
+                    description: Some("What to summarize".to_string()),
+                    required: true,
+                }],
+            }]
+        );
+
+        let mut registry = McpRegistry::new(McpConfig::default());
+        registry.store_connection("docs", server);
+        let arguments = HashMap::from([("topic".to_string(), "the README".to_string())]);
+        let messages = registry.get_prompt("docs", "summarize", &arguments).await.unwrap();
+        let turns: Vec<(llm::Role, String)> = messages.iter().map(|m| (m.role, m.get_text())).collect();
+        assert_eq!(
+            turns,
+            vec![
+                (llm::Role::User, "Be brief.".to_string()),
+                (llm::Role::Assistant, "Understood.".to_string()),
+                (llm::Role::User, "Summarize the README".to_string()),
+            ]
+        );
+        assert!(registry.get_prompt("docs", "summarize", &HashMap::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resources_and_prompts_need_the_capability() {
+        let server = connect_docs_server(false).await;
+        assert!(server.resources.is_empty());
+        assert!(server.prompts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sampling_is_opt_in_per_server() {
+        let sampler = Arc::new(Sampler::new(Arc::new(llm::mock::MockChatModel::echo_text(
+            "welcome",
+            "Welcome to Noema.",
+        ))));
+
+        let server = connect_docs_server_sampling(false, true, Some(Arc::clone(&sampler))).await;
+        let result = server.call_tool("draft".to_string(), None).await.unwrap();
+        assert!(matches!(
+            result.content.as_slice(),
+            [content] if matches!(&content.raw, RawContent::Text(text) if text.text == "Welcome to Noema.")
+        ));
+
+        let server = connect_docs_server_sampling(false, false, Some(sampler)).await;
+        assert!(server.call_tool("draft".to_string(), None).await.is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Sampling: completions that MCP servers ask the client to run
+//!
+//! A server sends `sampling/createMessage` with a conversation and model
+//! preferences; the client runs it on one of its models and returns the
+//! reply. Since that spends the user's tokens, servers may only sample when
+//! their configuration sets `allow_sampling`, and a `SamplingApprover` can
+//! ask the user about each request. A server's model hints can only pick
+//! the active model or one of the models the user allowed.
+
+use async_trait::async_trait;
+use llm::{
+    ChatMessage, ChatModel, ChatPayload, ChatRequest, ContentBlock, FinishReason, GenerationParams, RetryPolicy,
+    RetryingChatModel,
+};
+use rmcp::model::{Content, CreateMessageRequestParam, CreateMessageResult, RawContent, Role, SamplingMessage};
+use rmcp::ErrorData;
+use std::sync::{Arc, RwLock};
+
+/// A completion a server asked for, as it will be run
+#[derive(Debug, Clone)]
+pub struct SamplingRequest {
+    /// Name of the server asking
+    pub server: String,
+    /// ID of the model that will answer
+    pub model_id: String,
+    pub system_prompt: Option<String>,
+    pub messages: Vec<ChatMessage>,
+    pub params: GenerationParams,
+}
+
+/// Decides whether a server's completion request may run, e.g. by asking the user.
+///
+/// Without an approver every request from a server allowed to sample runs.
+#[async_trait]
+pub trait SamplingApprover: Send + Sync {
+    async fn approve(&self, request: &SamplingRequest) -> bool;
+}
+
+/// Runs servers' sampling requests on the active model
+pub struct Sampler {
+    model: RwLock<Arc<dyn ChatModel + Send + Sync>>,
+    approver: Option<Arc<dyn SamplingApprover>>,
+    allowed_models: Vec<String>,
+}
+
+impl Sampler {
+    pub fn new(model: Arc<dyn ChatModel + Send + Sync>) -> Self {
+        Self { model: RwLock::new(model), approver: None, allowed_models: Vec::new() }
+    }
+
+    /// Let hints pick these models (provider/model) besides the active one
+    pub fn with_allowed_models(mut self, models: Vec<String>) -> Self {
+        self.allowed_models = models;
+        self
+    }
+
+    /// Ask `approver` before running each request
+    pub fn with_approver(mut self, approver: Arc<dyn SamplingApprover>) -> Self {
+        self.approver = Some(approver);
+        self
+    }
+
+    /// Change the model requests run on when their hints don't name another
+    pub fn set_model(&self, model: Arc<dyn ChatModel + Send + Sync>) {
+        *self.model.write().unwrap() = model;
+    }
+
+    /// Pick the model for a request's hints
+    ///
+    /// Hints are tried in order: one contained in the active model's ID keeps
+    /// that model, one naming an allowed model as "provider/model" creates
+    /// it, retrying transient errors like the active model. Other hints are
+    /// ignored, leaving the active model.
+    fn choose_model(&self, hints: &[String]) -> Arc<dyn ChatModel + Send + Sync> {
+        let active = Arc::clone(&self.model.read().unwrap());
+        for hint in hints {
+            if active.id().contains(hint.as_str()) {
+                break;
+            }
+            if self.allowed_models.contains(hint) {
+                if let Ok(model) = llm::create_model(hint) {
+                    return RetryingChatModel::wrap(model, RetryPolicy::default());
+                }
+            }
+        }
+        active
+    }
+
+    /// Run a `sampling/createMessage` request from the server named `server`
+    ///
+    /// The request's `includeContext` is not honored: the completion sees
+    /// only the messages the server sent.
+    pub async fn create_message(
+        &self,
+        server: &str,
+        params: CreateMessageRequestParam,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        let hints: Vec<String> = params
+            .model_preferences
+            .as_ref()
+            .and_then(|preferences| preferences.hints.as_ref())
+            .into_iter()
+            .flatten()
+            .filter_map(|hint| hint.name.clone())
+            .collect();
+        let model = self.choose_model(&hints);
+
+        let request = SamplingRequest {
+            server: server.to_string(),
+            model_id: model.id().to_string(),
+            system_prompt: params.system_prompt.filter(|prompt| !prompt.is_empty()),
+            messages: params.messages.into_iter().map(sampling_message_to_chat).collect(),
+            params: GenerationParams {
+                temperature: params.temperature,
+                max_tokens: Some(params.max_tokens),
+                stop: params.stop_sequences.filter(|stops| !stops.is_empty()),
+                ..GenerationParams::default()
+            },
+        };
+        if let Some(approver) = &self.approver {
+            if !approver.approve(&request).await {
+                return Err(ErrorData::invalid_request("The user declined the sampling request", None));
+            }
+        }
+
+        let mut messages = Vec::with_capacity(request.messages.len() + 1);
+        if let Some(prompt) = &request.system_prompt {
+            messages.push(ChatMessage::system(ChatPayload::text(prompt.clone())));
+        }
+        messages.extend(request.messages);
+        let chat_request = ChatRequest::new(&messages).with_params(request.params);
+        let reply = model
+            .chat(&chat_request)
+            .await
+            .map_err(|e| ErrorData::internal_error(format!("Sampling failed: {}", e), None))?;
+
+        let stop_reason = match reply.finish_reason {
+            Some(FinishReason::Stop) => Some(CreateMessageResult::STOP_REASON_END_TURN.to_string()),
+            Some(FinishReason::Length) => Some(CreateMessageResult::STOP_REASON_END_MAX_TOKEN.to_string()),
+            _ => None,
+        };
+        Ok(CreateMessageResult {
+            model: request.model_id,
+            stop_reason,
+            message: SamplingMessage { role: Role::Assistant, content: Content::text(reply.get_text()) },
+        })
+    }
+}
+
+/// Convert a message of a sampling request; content other than text,
+/// images and audio is dropped
+fn sampling_message_to_chat(message: SamplingMessage) -> ChatMessage {
+    let block = match message.content.raw {
+        RawContent::Text(text) => Some(ContentBlock::Text { text: text.text }),
+        RawContent::Image(image) => Some(ContentBlock::Image { data: image.data, mime_type: image.mime_type }),
+        RawContent::Audio(audio) => Some(ContentBlock::Audio { data: audio.data, mime_type: audio.mime_type }),
+        RawContent::Resource(_) | RawContent::ResourceLink(_) => None,
+    };
+    let payload = ChatPayload::new(block.into_iter().collect());
+    match message.role {
+        Role::User => ChatMessage::user(payload),
+        Role::Assistant => ChatMessage::assistant(payload),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm::mock::MockChatModel;
+    use rmcp::model::{ModelHint, ModelPreferences};
+
+    struct Decline;
+
+    #[async_trait]
+    impl SamplingApprover for Decline {
+        async fn approve(&self, _request: &SamplingRequest) -> bool {
+            false
+        }
+    }
+
+    fn params(hints: &[&str]) -> CreateMessageRequestParam {
+        CreateMessageRequestParam {
+            messages: vec![SamplingMessage { role: Role::User, content: Content::text("Say hi") }],
+            model_preferences: Some(ModelPreferences {
+                hints: Some(hints.iter().map(|name| ModelHint { name: Some(name.to_string()) }).collect()),
+                cost_priority: None,
+                speed_priority: None,
+                intelligence_priority: None,
+            }),
+            system_prompt: Some("Be terse".to_string()),
+            include_context: None,
+            temperature: Some(0.2),
+            max_tokens: 50,
+            stop_sequences: Some(vec!["END".to_string()]),
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_runs_on_the_active_model() {
+        let model = Arc::new(MockChatModel::echo_text("echo-large", "Say hi"));
+        let sampler = Sampler::new(model.clone());
+
+        let result = sampler.create_message("docs", params(&["unknown-model", "echo"])).await.unwrap();
+        assert_eq!(result.model, "echo-large");
+        assert_eq!(result.stop_reason.as_deref(), Some(CreateMessageResult::STOP_REASON_END_TURN));
+        assert!(matches!(&result.message.content.raw, RawContent::Text(text) if text.text == "Say hi"));
+
+        let requests = model.requests();
+        let request = &requests[0];
+        assert_eq!(request.messages()[0].role, llm::Role::System);
+        assert_eq!(request.messages()[0].get_text(), "Be terse");
+        assert_eq!(request.params().temperature, Some(0.2));
+        assert_eq!(request.params().max_tokens, Some(50));
+        assert_eq!(request.params().stop, Some(vec!["END".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_declined_request_does_not_run() {
+        let model = Arc::new(MockChatModel::echo_text("echo-large", "Say hi"));
+        let sampler = Sampler::new(model.clone()).with_approver(Arc::new(Decline));
+
+        assert!(sampler.create_message("docs", params(&[])).await.is_err());
+        assert_eq!(model.request_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_hint_outside_the_allowed_models_keeps_the_active_model() {
+        let model = Arc::new(MockChatModel::echo_text("echo-large", "Say hi"));
+        let sampler = Sampler::new(model.clone()).with_allowed_models(vec!["ollama/llama3".to_string()]);
+
+        let result = sampler.create_message("docs", params(&["openai/gpt-4o"])).await.unwrap();
+        assert_eq!(result.model, "echo-large");
+        assert_eq!(model.request_count(), 1);
+    }
+}
@@ -11,7 +11,8 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD, Engine};
-use llm::{ContentBlock, Role};
+use llm::{ChatMessage, ChatPayload, ContentBlock, Role};
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
@@ -19,11 +20,12 @@ use crate::storage::content::{ContentResolver, InputContent, StoredContent};
 use crate::storage::ids::{AssetId, ContentBlockId, ConversationId, SpanId, TurnId, UserId};
 use crate::storage::session::{ResolvedContent, ResolvedMessage};
 use crate::storage::traits::{
-    AssetStore, BlobStore, EntityStore, StorageTypes, Stores, TextStore, TurnStore,
+    AssetStore, BlobStore, DocumentStore, EntityStore, StorageTypes, StoredEntity, Stores,
+    TextStore, TurnStore, UserStore,
 };
 use crate::storage::types::{
-    Asset, BlobHash, ContentBlock as ContentBlockData, ContentOrigin, EntityType, OriginKind,
-    TurnWithContent,
+    Asset, BlobHash, ContentBlock as ContentBlockData, ContentOrigin, ContextSummary,
+    ConversationListOptions, EntityType, GcStats, Incognito, OriginKind, TurnWithContent,
 };
 
 /// Coordinates storage across all store types.
@@ -144,10 +146,24 @@ impl<S: StorageTypes> StorageCoordinator<S> {
     /// Decode base64 data, store in blob storage, register in asset storage,
     /// and return the asset ID.
     pub async fn store_asset(&self, base64_data: &str, mime_type: &str) -> Result<AssetId> {
+        let (asset_id, _) = self.store_asset_with_hash(base64_data, mime_type).await?;
+        Ok(asset_id)
+    }
+
+    /// Like `store_asset`, but also returns the content hash of the blob.
+    ///
+    /// Blob storage is content-addressed, so bytes that are already stored
+    /// are not written again and the existing hash is returned.
+    pub async fn store_asset_with_hash(
+        &self,
+        base64_data: &str,
+        mime_type: &str,
+    ) -> Result<(AssetId, BlobHash)> {
         let bytes = STANDARD.decode(base64_data)?;
         let blob_hash = self.blob_store.store(&bytes).await?;
-        let asset = Asset::new(blob_hash, mime_type, bytes.len() as i64);
-        self.asset_store.create_asset(asset).await
+        let asset = Asset::new(blob_hash.clone(), mime_type, bytes.len() as i64);
+        let asset_id = self.asset_store.create_asset(asset).await?;
+        Ok((asset_id, blob_hash))
     }
 
     /// Get blob data by hash
@@ -175,6 +191,85 @@ impl<S: StorageTypes> StorageCoordinator<S> {
         Ok(span.id)
     }
 
+    /// Replace a user message with new content and drop the turns after it.
+    ///
+    /// The new content becomes a new selected span at the message's turn, so
+    /// the previous text stays available as an alternate.
+    pub async fn replace_user_message(
+        &self,
+        conversation_id: &ConversationId,
+        turn_id: &TurnId,
+        content: Vec<ContentBlock>,
+    ) -> Result<(SpanId, ResolvedMessage)> {
+        self.turn_store.truncate_selections(conversation_id, turn_id).await?;
+        let span_id = self.create_and_select_span(conversation_id, turn_id, None).await?;
+        let resolved = self
+            .add_message(&span_id, turn_id, Role::User, content, OriginKind::User)
+            .await?;
+        Ok((span_id, resolved))
+    }
+
+    /// Append text to the last message of a turn.
+    ///
+    /// The turn's messages are copied into a new selected span, with the text
+    /// joined onto the last one, so the original stays available as an
+    /// alternate. Returns the turn's messages as they now read.
+    pub async fn append_to_last_message(
+        &self,
+        conversation_id: &ConversationId,
+        turn_id: &TurnId,
+        text: &str,
+    ) -> Result<Vec<ResolvedMessage>> {
+        let span_id = self
+            .turn_store
+            .get_selected_span(conversation_id, turn_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Turn {} has no selected span", turn_id))?;
+        let model_id = self
+            .turn_store
+            .get_span(&span_id)
+            .await?
+            .and_then(|span| span.model_id.clone());
+
+        let mut messages = self.turn_store.get_messages(&span_id).await?;
+        let last = messages
+            .last_mut()
+            .ok_or_else(|| anyhow::anyhow!("Turn {} has no messages", turn_id))?;
+
+        // Extend the trailing text block, or add one after other content
+        let mut joined = text.to_string();
+        if let Some(StoredContent::TextRef { .. }) = last.content.last() {
+            let previous = last.content.split_off(last.content.len() - 1);
+            match self.resolve_stored_content(&previous).await?.pop() {
+                Some(ResolvedContent::Text { text: before }) => joined = before + text,
+                _ => anyhow::bail!(
+                    "Last message of turn {} ends in a text reference that is not text",
+                    turn_id
+                ),
+            }
+        }
+        let origin = OriginKind::from(last.message.role);
+        last.content
+            .push(self.store_content_block(ContentBlock::Text { text: joined }, origin).await?);
+
+        let new_span_id = self
+            .create_and_select_span(conversation_id, turn_id, model_id.as_deref())
+            .await?;
+        let mut resolved = Vec::with_capacity(messages.len());
+        for msg in messages {
+            let stored = self
+                .turn_store
+                .add_message(&new_span_id, msg.message.role, &msg.content)
+                .await?;
+            let content = self.resolve_stored_content(&msg.content).await?;
+            resolved.push(
+                ResolvedMessage::new(msg.message.role, content, turn_id.clone())
+                    .with_created_at(stored.created_at),
+            );
+        }
+        Ok(resolved)
+    }
+
     // ========== Session Methods ==========
 
     /// Open a session for a conversation.
@@ -227,6 +322,150 @@ impl<S: StorageTypes> StorageCoordinator<S> {
         Ok(conversation_id)
     }
 
+    /// List a user's conversations.
+    pub async fn list_conversations(
+        &self,
+        user_id: &UserId,
+        options: &ConversationListOptions,
+    ) -> Result<Vec<StoredEntity>> {
+        self.entity_store.list_conversations(user_id, options).await
+    }
+
+    /// Get a conversation entity, or None if there is no conversation with this ID.
+    pub async fn get_conversation(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Result<Option<StoredEntity>> {
+        let entity = self.entity_store.get_entity(conversation_id).await?;
+        Ok(entity.filter(|e| e.entity_type == EntityType::conversation()))
+    }
+
+    /// Number of turns on a conversation's selected path.
+    pub async fn get_turn_count(&self, conversation_id: &ConversationId) -> Result<usize> {
+        self.turn_store.get_turn_count(conversation_id).await
+    }
+
+    /// Get a conversation's tags, sorted alphabetically.
+    pub async fn get_conversation_tags(&self, conversation_id: &ConversationId) -> Result<Vec<String>> {
+        self.entity_store.get_conversation_tags(conversation_id).await
+    }
+
+    /// Record the model last used in a conversation.
+    ///
+    /// Stored in the conversation entity's metadata so reopening the
+    /// conversation can restore it.
+    pub async fn set_last_model(
+        &self,
+        conversation_id: &ConversationId,
+        model_id: &str,
+    ) -> Result<()> {
+        let mut entity = self.entity_store
+            .get_entity(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+        if entity.last_model() == Some(model_id) {
+            return Ok(());
+        }
+        entity.set_last_model(model_id);
+        self.entity_store.update_entity(conversation_id, &entity).await
+    }
+
+    /// Set a conversation's name (None removes it).
+    pub async fn rename_conversation(
+        &self,
+        conversation_id: &ConversationId,
+        name: Option<&str>,
+    ) -> Result<()> {
+        let mut entity = self.entity_store
+            .get_entity(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+        entity.name = name.map(str::to_string);
+        self.entity_store.update_entity(conversation_id, &entity).await
+    }
+
+    /// Set a conversation's incognito mode.
+    ///
+    /// Going ephemeral only affects messages committed from then on; earlier
+    /// messages stay in storage.
+    pub async fn set_incognito(
+        &self,
+        conversation_id: &ConversationId,
+        incognito: Incognito,
+    ) -> Result<()> {
+        let mut entity = self.entity_store
+            .get_entity(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+        if entity.incognito == incognito {
+            return Ok(());
+        }
+        entity.incognito = incognito;
+        self.entity_store.update_entity(conversation_id, &entity).await
+    }
+
+    /// Get a conversation's incognito mode (Off if there is no such conversation).
+    pub async fn get_incognito(&self, conversation_id: &ConversationId) -> Result<Incognito> {
+        let entity = self.entity_store.get_entity(conversation_id).await?;
+        Ok(entity.map(|e| e.incognito).unwrap_or_default())
+    }
+
+    /// Get the model last used in a conversation, if one was recorded.
+    pub async fn get_last_model(&self, conversation_id: &ConversationId) -> Result<Option<String>> {
+        let entity = self.entity_store.get_entity(conversation_id).await?;
+        Ok(entity.and_then(|e| e.last_model().map(str::to_string)))
+    }
+
+    /// Set or clear a conversation's system prompt.
+    ///
+    /// Stored in the conversation entity's metadata, so a conversation has at
+    /// most one system prompt and it is never part of the turn history.
+    pub async fn set_system_prompt(
+        &self,
+        conversation_id: &ConversationId,
+        prompt: Option<&str>,
+    ) -> Result<()> {
+        let mut entity = self.entity_store
+            .get_entity(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+        if entity.system_prompt() == prompt {
+            return Ok(());
+        }
+        entity.set_system_prompt(prompt.map(str::to_string));
+        self.entity_store.update_entity(conversation_id, &entity).await
+    }
+
+    /// Get a conversation's system prompt, if one is set.
+    pub async fn get_system_prompt(&self, conversation_id: &ConversationId) -> Result<Option<String>> {
+        let entity = self.entity_store.get_entity(conversation_id).await?;
+        Ok(entity.and_then(|e| e.system_prompt().map(str::to_string)))
+    }
+
+    /// Set or clear the summary that replaces the start of a conversation's
+    /// history when it is sent to the model.
+    pub async fn set_context_summary(
+        &self,
+        conversation_id: &ConversationId,
+        summary: Option<&ContextSummary>,
+    ) -> Result<()> {
+        let mut entity = self.entity_store
+            .get_entity(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+        entity.set_context_summary(summary);
+        self.entity_store.update_entity(conversation_id, &entity).await
+    }
+
+    /// Get a conversation's context summary, if it was compacted.
+    pub async fn get_context_summary(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Result<Option<ContextSummary>> {
+        let entity = self.entity_store.get_entity(conversation_id).await?;
+        Ok(entity.and_then(|e| e.context_summary()))
+    }
+
     /// Fork a conversation at a specific turn.
     ///
     /// Creates a new conversation entity, copies selections up to and including
@@ -288,6 +527,35 @@ impl<S: StorageTypes> StorageCoordinator<S> {
         Ok(new_conversation_id)
     }
 
+    /// Fork a conversation after one of its messages.
+    ///
+    /// `message_index` is the 0-based position of the last message to keep
+    /// on the conversation's selected path; `None` forks at the end. Every
+    /// message of that message's turn is kept. Content is shared with the
+    /// original rather than copied.
+    pub async fn fork_conversation_at_message(
+        &self,
+        conversation_id: &ConversationId,
+        message_index: Option<usize>,
+    ) -> Result<ConversationId> {
+        let messages = self.open_session(conversation_id).await?;
+        let message = match message_index {
+            Some(index) => messages.get(index).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Message {} out of range: conversation has {} messages",
+                    index,
+                    messages.len()
+                )
+            })?,
+            None => messages
+                .last()
+                .ok_or_else(|| anyhow::anyhow!("Cannot fork an empty conversation"))?,
+        };
+        let at_turn_id = message.turn_id.clone();
+
+        self.fork_conversation(conversation_id, &at_turn_id, None).await
+    }
+
     /// Get conversations forked from a given conversation.
     pub async fn get_forked_conversations(
         &self,
@@ -531,7 +799,10 @@ impl<S: StorageTypes> StorageCoordinator<S> {
             let turn_id = turn.turn.id.clone();
             for msg in &turn.messages {
                 let resolved = self.resolve_stored_content(&msg.content).await?;
-                messages.push(ResolvedMessage::new(msg.message.role, resolved, turn_id.clone()));
+                messages.push(
+                    ResolvedMessage::new(msg.message.role, resolved, turn_id.clone())
+                        .with_created_at(msg.message.created_at),
+                );
             }
         }
 
@@ -624,12 +895,38 @@ impl<S: StorageTypes> StorageCoordinator<S> {
         }
 
         // Add message to turn store
-        self.turn_store.add_message(span_id, role, &stored).await?;
+        let message = self.turn_store.add_message(span_id, role, &stored).await?;
 
         // Resolve for caching
         let resolved = self.resolve_stored_content(&stored).await?;
 
-        Ok(ResolvedMessage::new(role, resolved, turn_id.clone()))
+        Ok(ResolvedMessage::new(role, resolved, turn_id.clone()).with_created_at(message.created_at))
+    }
+
+    /// Resolve a message's content without adding it to any conversation.
+    ///
+    /// Used for ephemeral conversations: text, tool calls and tool results
+    /// stay in memory. Images and audio still go to the asset store so they
+    /// can be displayed, but no message references them, so garbage
+    /// collection removes them.
+    pub async fn resolve_unstored(
+        &self,
+        content: Vec<ContentBlock>,
+        origin: OriginKind,
+    ) -> Result<Vec<ResolvedContent>> {
+        let mut resolved = Vec::with_capacity(content.len());
+        for block in content {
+            match block {
+                ContentBlock::Text { text } => resolved.push(ResolvedContent::text(text)),
+                ContentBlock::ToolCall(call) => resolved.push(ResolvedContent::tool_call(call)),
+                ContentBlock::ToolResult(result) => resolved.push(ResolvedContent::tool_result(result)),
+                block => {
+                    let stored = self.store_content_block(block, origin).await?;
+                    resolved.extend(self.resolve_stored_content(&[stored]).await?);
+                }
+            }
+        }
+        Ok(resolved)
     }
 
     /// Get resolved context up to (but not including) a specific turn.
@@ -647,6 +944,289 @@ impl<S: StorageTypes> StorageCoordinator<S> {
 
         self.resolve_path(&context_path).await
     }
+
+    // ========== Import/Export Methods ==========
+
+    /// Export a conversation's current path as JSON (a `Vec<ChatMessage>`).
+    ///
+    /// Images and audio are inlined as base64 so the export is self-contained;
+    /// documents are exported as references.
+    pub async fn export_conversation(&self, conversation_id: &ConversationId) -> Result<String> {
+        let resolved = self.open_session(conversation_id).await?;
+        let messages = resolved
+            .iter()
+            .map(|msg| {
+                let blocks = msg
+                    .content
+                    .iter()
+                    .map(export_content)
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(ChatMessage::new(msg.role, ChatPayload::new(blocks)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(serde_json::to_string_pretty(&messages)?)
+    }
+
+    /// Import a conversation from JSON produced by `export_conversation`.
+    ///
+    /// The whole input is parsed and validated before anything is written, so
+    /// malformed input never leaves a partial conversation behind. Tool results
+    /// without a preceding matching tool call are dropped. Consecutive messages
+    /// with the same role share a turn, as in a live session.
+    ///
+    /// Returns the ID of the new conversation.
+    pub async fn import_conversation(
+        &self,
+        user_id: &UserId,
+        name: Option<&str>,
+        json: &str,
+    ) -> Result<ConversationId> {
+        let messages: Vec<ChatMessage> = serde_json::from_str(json)
+            .map_err(|e| anyhow::anyhow!("Invalid conversation JSON: {}", e))?;
+        let messages = sanitize_imported_messages(messages)?;
+
+        let conversation_id = self.create_conversation(user_id, name).await?;
+
+        let mut current: Option<(Role, TurnId, SpanId)> = None;
+        for msg in messages {
+            let (turn_id, span_id) = match &current {
+                Some((role, turn_id, span_id)) if *role == msg.role => {
+                    (turn_id.clone(), span_id.clone())
+                }
+                _ => {
+                    let turn_id = self.create_turn(msg.role).await?;
+                    let span_id = self
+                        .create_and_select_span(&conversation_id, &turn_id, None)
+                        .await?;
+                    current = Some((msg.role, turn_id.clone(), span_id.clone()));
+                    (turn_id, span_id)
+                }
+            };
+            let origin = OriginKind::from(msg.role);
+            self.add_message(&span_id, &turn_id, msg.role, msg.payload.content, origin)
+                .await?;
+        }
+
+        Ok(conversation_id)
+    }
+
+    // ========== Garbage Collection Methods ==========
+
+    /// Collect the blob hashes referenced anywhere in storage.
+    ///
+    /// Walks every user's conversations, archived and incognito ones included,
+    /// visiting every span at every turn so alternates keep their assets.
+    /// Document tabs and their revisions contribute both their
+    /// `referenced_assets` and any `noema-asset://` links in their markdown.
+    pub async fn referenced_blobs(
+        &self,
+        users: &impl UserStore,
+        documents: &impl DocumentStore,
+    ) -> Result<HashSet<BlobHash>> {
+        let mut seen_turns: HashSet<TurnId> = HashSet::new();
+        let mut seen_assets: HashSet<AssetId> = HashSet::new();
+        let mut referenced = HashSet::new();
+        let everything = ConversationListOptions::default()
+            .with_archived()
+            .with_incognito();
+
+        for user in users.list_users().await? {
+            for conversation in self.entity_store.list_conversations(&user.id, &everything).await? {
+                let path = self.turn_store.get_conversation_path(&conversation.id).await?;
+                for turn in path {
+                    // Forked conversations share turns with their parent
+                    if !seen_turns.insert(turn.turn.id.clone()) {
+                        continue;
+                    }
+                    for span in self.turn_store.get_spans(&turn.turn.id).await? {
+                        for msg in self.turn_store.get_messages(&span.id).await? {
+                            for asset_id in msg.content.iter().filter_map(|c| c.asset_id()) {
+                                self.reference_asset(asset_id, &mut seen_assets, &mut referenced)
+                                    .await?;
+                            }
+                        }
+                    }
+                }
+            }
+
+            for document in documents.list_documents(&user.id).await? {
+                for tab in documents.list_document_tabs(&document.id).await? {
+                    self.reference_document_content(
+                        &tab.referenced_assets,
+                        tab.content_markdown.as_deref().unwrap_or_default(),
+                        &mut seen_assets,
+                        &mut referenced,
+                    )
+                    .await?;
+                    for revision in documents.list_document_revisions(&tab.id).await? {
+                        self.reference_document_content(
+                            &revision.referenced_assets,
+                            &revision.content_markdown,
+                            &mut seen_assets,
+                            &mut referenced,
+                        )
+                        .await?;
+                    }
+                }
+            }
+        }
+
+        Ok(referenced)
+    }
+
+    /// Mark an asset's blob as referenced. Returns false if the asset is unknown.
+    async fn reference_asset(
+        &self,
+        asset_id: &AssetId,
+        seen_assets: &mut HashSet<AssetId>,
+        referenced: &mut HashSet<BlobHash>,
+    ) -> Result<bool> {
+        if !seen_assets.insert(asset_id.clone()) {
+            return Ok(true);
+        }
+        match self.asset_store.get(asset_id).await? {
+            Some(asset) => {
+                referenced.insert(asset.blob_hash.clone());
+                Ok(true)
+            }
+            None => {
+                seen_assets.remove(asset_id);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Mark the blobs used by a document tab or revision as referenced.
+    ///
+    /// `noema-asset://` links carry an asset id when written by the Google
+    /// Docs import, but the asset protocol serves them as blob hashes, so a
+    /// link that doesn't name an asset is kept as a blob hash.
+    async fn reference_document_content(
+        &self,
+        asset_ids: &[AssetId],
+        markdown: &str,
+        seen_assets: &mut HashSet<AssetId>,
+        referenced: &mut HashSet<BlobHash>,
+    ) -> Result<()> {
+        for asset_id in asset_ids {
+            self.reference_asset(asset_id, seen_assets, referenced).await?;
+        }
+        for link in asset_links(markdown) {
+            let asset_id = AssetId::from_string(link);
+            if !self.reference_asset(&asset_id, seen_assets, referenced).await? {
+                referenced.insert(BlobHash::from_string(link));
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete blobs not referenced by any conversation or document.
+    pub async fn collect_garbage(
+        &self,
+        users: &impl UserStore,
+        documents: &impl DocumentStore,
+    ) -> Result<GcStats> {
+        let referenced = self.referenced_blobs(users, documents).await?;
+        self.blob_store.gc(&referenced).await
+    }
+}
+
+/// Extract the ids from `noema-asset://localhost/<id>` links in markdown
+fn asset_links(markdown: &str) -> impl Iterator<Item = &str> {
+    const PREFIX: &str = "noema-asset://localhost/";
+    markdown.split(PREFIX).skip(1).filter_map(|rest| {
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, ')' | '?' | '#' | '"' | '\'' | '>'))
+            .unwrap_or(rest.len());
+        let id = &rest[..end];
+        (!id.is_empty()).then_some(id)
+    })
+}
+
+/// Convert resolved content back to a self-contained ContentBlock for export
+fn export_content(content: &ResolvedContent) -> Result<ContentBlock> {
+    Ok(match content {
+        ResolvedContent::Text { text } => ContentBlock::Text { text: text.clone() },
+        ResolvedContent::Asset {
+            asset_id,
+            resolved,
+            ..
+        } => resolved
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Asset data unavailable for export: {}", asset_id))?,
+        ResolvedContent::Document { document_id, .. } => ContentBlock::DocumentRef {
+            id: document_id.to_string(),
+        },
+        ResolvedContent::ToolCall(call) => ContentBlock::ToolCall(call.clone()),
+        ResolvedContent::ToolResult(result) => ContentBlock::ToolResult(result.clone()),
+    })
+}
+
+/// Validate imported messages and drop dangling tool results.
+///
+/// Fails on malformed blocks (bad media data, missing IDs). Tool results
+/// whose `tool_call_id` matches no earlier tool call are removed, along with
+/// any message left empty by that.
+fn sanitize_imported_messages(messages: Vec<ChatMessage>) -> Result<Vec<ChatMessage>> {
+    let mut seen_calls = HashSet::new();
+    let mut sanitized = Vec::with_capacity(messages.len());
+
+    for (i, mut msg) in messages.into_iter().enumerate() {
+        for (j, block) in msg.payload.content.iter().enumerate() {
+            validate_imported_block(block)
+                .map_err(|e| anyhow::anyhow!("Message {}, block {}: {}", i, j, e))?;
+        }
+
+        let had_content = !msg.payload.content.is_empty();
+        msg.payload.content.retain(|block| match block {
+            ContentBlock::ToolCall(call) => {
+                seen_calls.insert(call.id.clone());
+                true
+            }
+            ContentBlock::ToolResult(result) => seen_calls.contains(&result.tool_call_id),
+            _ => true,
+        });
+        if had_content && msg.payload.content.is_empty() {
+            continue;
+        }
+        sanitized.push(msg);
+    }
+
+    Ok(sanitized)
+}
+
+fn validate_imported_block(block: &ContentBlock) -> Result<()> {
+    match block {
+        ContentBlock::Text { .. } => {}
+        ContentBlock::Image { data, mime_type } => validate_media(data, mime_type, "image/")?,
+        ContentBlock::Audio { data, mime_type } => validate_media(data, mime_type, "audio/")?,
+        ContentBlock::DocumentRef { id } => {
+            if id.is_empty() {
+                anyhow::bail!("document reference has an empty id");
+            }
+        }
+        ContentBlock::ToolCall(call) => {
+            if call.id.is_empty() || call.name.is_empty() {
+                anyhow::bail!("tool call is missing its id or name");
+            }
+        }
+        ContentBlock::ToolResult(result) => {
+            if result.tool_call_id.is_empty() {
+                anyhow::bail!("tool result has an empty tool_call_id");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_media(data: &str, mime_type: &str, prefix: &str) -> Result<()> {
+    if !mime_type.starts_with(prefix) {
+        anyhow::bail!("expected a {}* mime type, got '{}'", prefix, mime_type);
+    }
+    STANDARD
+        .decode(data)
+        .map_err(|e| anyhow::anyhow!("invalid base64 data: {}", e))?;
+    Ok(())
 }
 
 /// Implement ContentResolver for the generic coordinator
@@ -673,6 +1253,22 @@ impl<S: StorageTypes> ContentResolver for StorageCoordinator<S> {
     }
 }
 
+/// Coordinator over fresh in-memory stores, shared by tests across the crate
+#[cfg(test)]
+pub(crate) fn memory_coordinator() -> StorageCoordinator<crate::storage::implementations::memory::MemoryStorage> {
+    use crate::storage::implementations::memory::{
+        MemoryAssetStore, MemoryBlobStore, MemoryEntityStore, MemoryTextStore, MemoryTurnStore,
+    };
+
+    StorageCoordinator::new(
+        Arc::new(MemoryBlobStore::new()),
+        Arc::new(MemoryAssetStore::new()),
+        Arc::new(MemoryTextStore::new()),
+        Arc::new(MemoryEntityStore::new()),
+        Arc::new(MemoryTurnStore::new()),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -681,6 +1277,11 @@ mod tests {
         MockAssetStore, MockBlobStore, MockEntityStore, MockStorage, MockTextStore,
         MockTurnStore,
     };
+    use crate::storage::implementations::memory::{
+        MemoryAssetStore, MemoryBlobStore, MemoryDocumentStore, MemoryEntityStore, MemoryStorage,
+        MemoryTextStore, MemoryTurnStore, MemoryUserStore,
+    };
+    use crate::storage::types::DocumentSource;
     use crate::storage::traits::AssetStore;
 
     fn make_coordinator(content_block_store: Arc<MockTextStore>) -> StorageCoordinator<MockStorage> {
@@ -767,6 +1368,21 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_store_asset_deduplicates_blobs() {
+        let coordinator = memory_coordinator();
+        let data = STANDARD.encode(b"same image");
+
+        let (first_id, first_hash) =
+            coordinator.store_asset_with_hash(&data, "image/png").await.unwrap();
+        let (second_id, second_hash) =
+            coordinator.store_asset_with_hash(&data, "image/png").await.unwrap();
+
+        assert_eq!(first_hash, second_hash);
+        assert_eq!(first_hash, BlobHash::from_data(b"same image"));
+        assert_ne!(first_id, second_id);
+    }
+
     #[tokio::test]
     async fn test_resolve_text() {
         let content_block_store = Arc::new(MockTextStore::new());
@@ -837,6 +1453,7 @@ mod tests {
             id: "call-1".to_string(),
             name: "test_tool".to_string(),
             arguments: serde_json::json!({"key": "value"}),
+            extra: serde_json::Value::Null,
         };
         let block = ContentBlock::ToolCall(tool_call.clone());
 
@@ -852,4 +1469,229 @@ mod tests {
             other => panic!("Expected ToolCall, got {:?}", other),
         }
     }
+
+    fn tool_call(id: &str) -> ContentBlock {
+        ContentBlock::ToolCall(llm::ToolCall {
+            id: id.to_string(),
+            name: "search".to_string(),
+            arguments: serde_json::json!({"q": "rust"}),
+            extra: serde_json::Value::Null,
+        })
+    }
+
+    fn tool_result(id: &str) -> ContentBlock {
+        ContentBlock::ToolResult(llm::ToolResult {
+            tool_call_id: id.to_string(),
+            content: vec![llm::ToolResultContent::text("found")],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let coordinator = memory_coordinator();
+        let user_id = UserId::new();
+
+        let messages = vec![
+            ChatMessage::user(ChatPayload::new(vec![
+                ContentBlock::Text { text: "What is this?".to_string() },
+                ContentBlock::Image {
+                    data: STANDARD.encode(b"png bytes"),
+                    mime_type: "image/png".to_string(),
+                },
+            ])),
+            ChatMessage::assistant(ChatPayload::new(vec![tool_call("call-1")])),
+            ChatMessage::user(ChatPayload::new(vec![tool_result("call-1")])),
+            ChatMessage::assistant(ChatPayload::text("A picture.")),
+        ];
+        let json = serde_json::to_string(&messages).unwrap();
+
+        let imported = coordinator.import_conversation(&user_id, None, &json).await.unwrap();
+        let exported = coordinator.export_conversation(&imported).await.unwrap();
+
+        let original: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let round_tripped: serde_json::Value = serde_json::from_str(&exported).unwrap();
+        assert_eq!(original, round_tripped);
+
+        // Exporting and re-importing is stable too
+        let again = coordinator.import_conversation(&user_id, None, &exported).await.unwrap();
+        assert_ne!(again, imported);
+        assert_eq!(coordinator.export_conversation(&again).await.unwrap(), exported);
+    }
+
+    #[test]
+    fn test_sanitize_drops_dangling_tool_results() {
+        let messages = vec![
+            ChatMessage::assistant(ChatPayload::new(vec![tool_call("call-1")])),
+            ChatMessage::user(ChatPayload::new(vec![tool_result("call-1"), tool_result("call-2")])),
+            ChatMessage::user(ChatPayload::new(vec![tool_result("call-3")])),
+        ];
+
+        let sanitized = sanitize_imported_messages(messages).unwrap();
+        assert_eq!(sanitized.len(), 2);
+        assert_eq!(sanitized[1].payload.content.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_malformed_blocks() {
+        let coordinator = memory_coordinator();
+        let user_id = UserId::new();
+
+        let err = coordinator
+            .import_conversation(&user_id, None, "[{\"role\": \"user\"}]")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Invalid conversation JSON"));
+
+        let bad_image = vec![ChatMessage::user(ChatPayload::new(vec![ContentBlock::Image {
+            data: "not base64!".to_string(),
+            mime_type: "image/png".to_string(),
+        }]))];
+        let json = serde_json::to_string(&bad_image).unwrap();
+        let err = coordinator.import_conversation(&user_id, None, &json).await.unwrap_err();
+        assert!(err.to_string().contains("Message 0, block 0: invalid base64"));
+    }
+
+    fn image(bytes: &[u8]) -> ContentBlock {
+        ContentBlock::Image {
+            data: STANDARD.encode(bytes),
+            mime_type: "image/png".to_string(),
+        }
+    }
+
+    /// A memory coordinator that shares its blob store with the caller
+    fn gc_coordinator() -> (StorageCoordinator<MemoryStorage>, Arc<MemoryBlobStore>) {
+        let blob_store = Arc::new(MemoryBlobStore::new());
+        let coordinator = StorageCoordinator::new(
+            blob_store.clone(),
+            Arc::new(MemoryAssetStore::new()),
+            Arc::new(MemoryTextStore::new()),
+            Arc::new(MemoryEntityStore::new()),
+            Arc::new(MemoryTurnStore::new()),
+        );
+        (coordinator, blob_store)
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage_keeps_alternate_spans() {
+        let (coordinator, blob_store) = gc_coordinator();
+        let users = MemoryUserStore::new();
+        let documents = MemoryDocumentStore::new();
+        let user_id = users.get_or_create_default_user().await.unwrap().id;
+        let conversation_id = coordinator.create_conversation(&user_id, None).await.unwrap();
+
+        // Two spans at the same turn; only the second ends up selected
+        let turn_id = coordinator.create_turn(Role::User).await.unwrap();
+        for bytes in [b"first".as_slice(), b"second".as_slice()] {
+            let span_id = coordinator
+                .create_and_select_span(&conversation_id, &turn_id, None)
+                .await
+                .unwrap();
+            coordinator
+                .add_message(&span_id, &turn_id, Role::User, vec![image(bytes)], OriginKind::User)
+                .await
+                .unwrap();
+        }
+
+        let orphan = blob_store.store(b"orphan").await.unwrap();
+
+        let referenced = coordinator.referenced_blobs(&users, &documents).await.unwrap();
+        assert_eq!(referenced.len(), 2);
+        assert!(!referenced.contains(&orphan));
+
+        let stats = coordinator.collect_garbage(&users, &documents).await.unwrap();
+        assert_eq!(stats.deleted, 1);
+        assert_eq!(stats.bytes_reclaimed, 6);
+        assert!(!blob_store.exists(&orphan).await);
+        assert!(blob_store.exists(&BlobHash::from_data(b"first")).await);
+        assert!(blob_store.exists(&BlobHash::from_data(b"second")).await);
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage_keeps_documents_and_archived_conversations() {
+        let (coordinator, blob_store) = gc_coordinator();
+        let users = MemoryUserStore::new();
+        let documents = MemoryDocumentStore::new();
+        let user_id = users.get_or_create_default_user().await.unwrap().id;
+
+        // An attachment in a conversation that is then archived
+        let conversation_id = coordinator.create_conversation(&user_id, None).await.unwrap();
+        let turn_id = coordinator.create_turn(Role::User).await.unwrap();
+        let span_id = coordinator
+            .create_and_select_span(&conversation_id, &turn_id, None)
+            .await
+            .unwrap();
+        coordinator
+            .add_message(&span_id, &turn_id, Role::User, vec![image(b"attachment")], OriginKind::User)
+            .await
+            .unwrap();
+        coordinator.entity_store.archive_entity(&conversation_id).await.unwrap();
+
+        // Document images, one listed as a referenced asset and one only linked
+        let listed = coordinator
+            .store_asset(&STANDARD.encode(b"listed"), "image/png")
+            .await
+            .unwrap();
+        let linked = coordinator
+            .store_asset(&STANDARD.encode(b"linked"), "image/png")
+            .await
+            .unwrap();
+        let document_id = documents
+            .create_document(&user_id, "Doc", DocumentSource::GoogleDrive, None)
+            .await
+            .unwrap();
+        let markdown = format!("![chart](noema-asset://localhost/{})", linked.as_str());
+        documents
+            .create_document_tab(&document_id, None, 0, "Tab", None, Some(&markdown), &[listed], None)
+            .await
+            .unwrap();
+
+        let orphan = blob_store.store(b"orphan").await.unwrap();
+
+        let stats = coordinator.collect_garbage(&users, &documents).await.unwrap();
+        assert_eq!(stats.deleted, 1);
+        assert!(!blob_store.exists(&orphan).await);
+        for bytes in [b"attachment".as_slice(), b"listed", b"linked"] {
+            assert!(blob_store.exists(&BlobHash::from_data(bytes)).await);
+        }
+    }
+
+    #[test]
+    fn test_asset_links() {
+        let markdown = "![a](noema-asset://localhost/abc) and \
+                        <img src=\"noema-asset://localhost/def?mime_type=image/png\">";
+        assert_eq!(asset_links(markdown).collect::<Vec<_>>(), vec!["abc", "def"]);
+        assert_eq!(asset_links("no links here").count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_append_to_last_message_keeps_original_as_alternate() {
+        let coordinator = memory_coordinator();
+        let conversation_id = coordinator.create_conversation(&UserId::new(), None).await.unwrap();
+        let turn_id = coordinator.create_turn(Role::Assistant).await.unwrap();
+        let span_id = coordinator
+            .create_and_select_span(&conversation_id, &turn_id, Some("model-a"))
+            .await
+            .unwrap();
+        let text = ContentBlock::Text { text: "The answer is".to_string() };
+        coordinator
+            .add_message(&span_id, &turn_id, Role::Assistant, vec![text], OriginKind::Assistant)
+            .await
+            .unwrap();
+
+        let messages = coordinator
+            .append_to_last_message(&conversation_id, &turn_id, " 42.")
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content[0].as_text(), Some("The answer is 42."));
+
+        let path = coordinator.open_session(&conversation_id).await.unwrap();
+        assert_eq!(path.len(), 1);
+        assert_eq!(path[0].content.len(), 1);
+        assert_eq!(path[0].content[0].as_text(), Some("The answer is 42."));
+
+        let spans = coordinator.turn_store.get_spans(&turn_id).await.unwrap();
+        assert_eq!(spans.len(), 2);
+        assert!(spans.iter().all(|span| span.model_id.as_deref() == Some("model-a")));
+    }
 }
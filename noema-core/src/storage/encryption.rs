@@ -0,0 +1,86 @@
+//! At-rest encryption keys
+//!
+//! A `StorageKey` is a random 256-bit key shared by the encrypted SQLite
+//! database (`SqliteStore::open_encrypted`, `sqlcipher` feature) and
+//! `EncryptedBlobStore`. Callers decide where it lives: pass one in
+//! directly, or keep it in the OS keychain with `StorageKey::from_keychain`
+//! and `StorageKey::load_or_create`.
+
+use anyhow::{Context, Result};
+use rand::Rng;
+use std::fmt;
+
+/// 256-bit key for encrypting stored data
+#[derive(Clone, PartialEq, Eq)]
+pub struct StorageKey([u8; 32]);
+
+impl StorageKey {
+    /// Generate a new random key
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 32];
+        rand::rng().fill(&mut bytes);
+        Self(bytes)
+    }
+
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parse a key from 64 hex characters
+    pub fn from_hex(hex_key: &str) -> Result<Self> {
+        let bytes = hex::decode(hex_key.trim()).context("Storage key is not valid hex")?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Storage key must be 32 bytes (64 hex characters)"))?;
+        Ok(Self(bytes))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Read the key kept in the OS keychain, if there is one
+    pub fn from_keychain() -> Result<Option<Self>> {
+        config::keychain::get_storage_key()
+            .map(|hex_key| Self::from_hex(&hex_key))
+            .transpose()
+    }
+
+    /// Read the key kept in the OS keychain, generating and storing one on
+    /// first use
+    ///
+    /// Fails when no keychain is available, rather than creating a key that
+    /// would be lost on exit.
+    pub fn load_or_create() -> Result<Self> {
+        if let Some(key) = Self::from_keychain()? {
+            return Ok(key);
+        }
+        let key = Self::generate();
+        config::keychain::set_storage_key(&key.to_hex()).map_err(anyhow::Error::msg)?;
+        Ok(key)
+    }
+}
+
+impl fmt::Debug for StorageKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StorageKey(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_round_trip() {
+        let key = StorageKey::generate();
+        assert_eq!(StorageKey::from_hex(&key.to_hex()).unwrap(), key);
+        assert_ne!(StorageKey::generate(), key);
+        assert!(StorageKey::from_hex("abcd").is_err());
+        assert_eq!(format!("{:?}", key), "StorageKey(..)");
+    }
+}
@@ -6,12 +6,21 @@
 //! - Efficient storage (no Base64 overhead)
 
 use crate::storage::traits::BlobStore;
-use crate::storage::types::BlobHash;
+use crate::storage::types::{BlobHash, GcStats};
 use async_trait::async_trait;
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
+/// Blobs modified more recently than this are never garbage collected
+///
+/// A blob is written before the message referencing it is committed, so a
+/// sweep racing with a store could otherwise delete a blob that is about to
+/// become referenced.
+pub const GC_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
 /// Content-addressable blob storage on filesystem
 ///
 /// Files are stored in a sharded directory structure based on the first 2 characters
@@ -19,12 +28,22 @@ use tokio::io::AsyncWriteExt;
 #[derive(Debug, Clone)]
 pub struct FsBlobStore {
     root: PathBuf,
+    gc_grace_period: Duration,
 }
 
 impl FsBlobStore {
     /// Create a new FsBlobStore with the given root directory
     pub fn new(root: PathBuf) -> Self {
-        Self { root }
+        Self {
+            root,
+            gc_grace_period: GC_GRACE_PERIOD,
+        }
+    }
+
+    /// Override how recently modified a blob may be and still be collected
+    pub fn with_gc_grace_period(mut self, period: Duration) -> Self {
+        self.gc_grace_period = period;
+        self
     }
 
     /// Get the filesystem path for a blob
@@ -129,6 +148,58 @@ impl BlobStore for FsBlobStore {
             Ok(false)
         }
     }
+
+    async fn gc(&self, referenced: &HashSet<BlobHash>) -> anyhow::Result<GcStats> {
+        let mut stats = GcStats::default();
+
+        if !fs::try_exists(&self.root).await? {
+            return Ok(stats);
+        }
+
+        let now = SystemTime::now();
+        let mut shard_entries = fs::read_dir(&self.root).await?;
+        while let Some(shard_entry) = shard_entries.next_entry().await? {
+            let shard_path = shard_entry.path();
+
+            if !shard_path.is_dir() {
+                continue;
+            }
+
+            let mut blob_entries = fs::read_dir(&shard_path).await?;
+            while let Some(blob_entry) = blob_entries.next_entry().await? {
+                let blob_path = blob_entry.path();
+
+                // In-flight writes are left to cleanup_temp_files
+                if blob_path.extension().is_some_and(|ext| ext == "tmp") {
+                    continue;
+                }
+
+                let Some(name) = blob_path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                if referenced.contains(&BlobHash::from_string(name)) {
+                    continue;
+                }
+
+                let metadata = blob_entry.metadata().await?;
+                if !metadata.is_file() {
+                    continue;
+                }
+                let age = now
+                    .duration_since(metadata.modified()?)
+                    .unwrap_or(Duration::ZERO);
+                if age < self.gc_grace_period {
+                    continue;
+                }
+
+                fs::remove_file(&blob_path).await?;
+                stats.deleted += 1;
+                stats.bytes_reclaimed += metadata.len();
+            }
+        }
+
+        Ok(stats)
+    }
 }
 
 #[cfg(test)]
@@ -164,6 +235,15 @@ mod tests {
         let second = store.store(&data).await.unwrap();
         assert_eq!(first, second);
 
+        // Only one file was written
+        let shard = store.path_for(&first).parent().unwrap().to_path_buf();
+        let mut entries = fs::read_dir(&shard).await.unwrap();
+        let mut count = 0;
+        while entries.next_entry().await.unwrap().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 1);
+
         // Clean up
         fs::remove_dir_all(&store.root).await.ok();
     }
@@ -194,4 +274,38 @@ mod tests {
         // Clean up
         fs::remove_dir_all(&store.root).await.ok();
     }
+
+    #[tokio::test]
+    async fn test_gc_removes_unreferenced() {
+        let store = temp_blob_store().with_gc_grace_period(Duration::ZERO);
+
+        let keep = store.store(b"keep").await.unwrap();
+        let drop = store.store(b"drop me").await.unwrap();
+
+        let referenced = HashSet::from([keep.clone()]);
+        let stats = store.gc(&referenced).await.unwrap();
+
+        assert_eq!(stats.deleted, 1);
+        assert_eq!(stats.bytes_reclaimed, 7);
+        assert!(store.exists(&keep).await);
+        assert!(!store.exists(&drop).await);
+
+        // Clean up
+        fs::remove_dir_all(&store.root).await.ok();
+    }
+
+    #[tokio::test]
+    async fn test_gc_skips_recent_blobs() {
+        let store = temp_blob_store();
+
+        let fresh = store.store(b"just written").await.unwrap();
+
+        let stats = store.gc(&HashSet::new()).await.unwrap();
+
+        assert_eq!(stats, GcStats::default());
+        assert!(store.exists(&fresh).await);
+
+        // Clean up
+        fs::remove_dir_all(&store.root).await.ok();
+    }
 }
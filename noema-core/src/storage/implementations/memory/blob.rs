@@ -2,11 +2,11 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 
 use crate::storage::traits::BlobStore;
-use crate::storage::types::BlobHash;
+use crate::storage::types::{BlobHash, GcStats};
 
 /// In-memory blob store for testing
 #[derive(Debug, Default)]
@@ -25,7 +25,7 @@ impl BlobStore for MemoryBlobStore {
     async fn store(&self, data: &[u8]) -> Result<BlobHash> {
         let hash = BlobHash::from_data(data);
         let mut blobs = self.blobs.lock().unwrap();
-        blobs.insert(hash.clone(), data.to_vec());
+        blobs.entry(hash.clone()).or_insert_with(|| data.to_vec());
 
         Ok(hash)
     }
@@ -45,6 +45,19 @@ impl BlobStore for MemoryBlobStore {
     async fn delete(&self, hash: &BlobHash) -> Result<bool> {
         Ok(self.blobs.lock().unwrap().remove(hash).is_some())
     }
+
+    async fn gc(&self, referenced: &HashSet<BlobHash>) -> Result<GcStats> {
+        let mut stats = GcStats::default();
+        self.blobs.lock().unwrap().retain(|hash, data| {
+            if referenced.contains(hash) {
+                return true;
+            }
+            stats.deleted += 1;
+            stats.bytes_reclaimed += data.len() as u64;
+            false
+        });
+        Ok(stats)
+    }
 }
 
 #[cfg(test)]
@@ -100,4 +113,20 @@ mod tests {
         assert!(!store.exists(&blob_hash).await);
         assert!(!store.delete(&blob_hash).await.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_gc_removes_unreferenced() {
+        let store = MemoryBlobStore::new();
+
+        let keep = store.store(b"keep").await.unwrap();
+        let drop = store.store(b"drop me").await.unwrap();
+
+        let referenced = HashSet::from([keep.clone()]);
+        let stats = store.gc(&referenced).await.unwrap();
+
+        assert_eq!(stats.deleted, 1);
+        assert_eq!(stats.bytes_reclaimed, 7);
+        assert!(store.exists(&keep).await);
+        assert!(!store.exists(&drop).await);
+    }
 }
@@ -2,12 +2,13 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Mutex;
 
-use crate::storage::ids::{EntityId, UserId};
+use crate::storage::ids::{ConversationId, EntityId, UserId};
+use crate::storage::traits::normalize_tag;
 use crate::storage::traits::{EntityStore, StoredEntity};
-use crate::storage::types::entity::{Entity, EntityRangeQuery, EntityRelation, EntityType, RelationType};
+use crate::storage::types::entity::{ConversationListOptions, Entity, EntityRangeQuery, EntityRelation, EntityType, Incognito, RelationType};
 use crate::storage::types::stored_editable;
 
 fn now() -> i64 {
@@ -27,6 +28,7 @@ struct EntityEntry {
     slug: Option<String>,
     is_private: bool,
     is_archived: bool,
+    incognito: Incognito,
     metadata: Option<serde_json::Value>,
     created_at: i64,
     updated_at: i64,
@@ -41,6 +43,7 @@ impl EntityEntry {
             slug: self.slug.clone(),
             is_private: self.is_private,
             is_archived: self.is_archived,
+            incognito: self.incognito,
             metadata: self.metadata.clone(),
         };
         stored_editable(self.id.clone(), entity, self.created_at, self.updated_at)
@@ -71,6 +74,7 @@ pub struct MemoryEntityStore {
     entities: Mutex<HashMap<String, EntityEntry>>,
     slugs: Mutex<HashMap<String, String>>, // slug -> entity_id
     relations: Mutex<HashMap<RelationKey, RelationEntry>>,
+    tags: Mutex<HashMap<String, BTreeSet<String>>>, // conversation_id -> tags
 }
 
 impl MemoryEntityStore {
@@ -96,6 +100,7 @@ impl EntityStore for MemoryEntityStore {
             slug: None,
             is_private: true,
             is_archived: false,
+            incognito: Incognito::Off,
             metadata: None,
             created_at: now,
             updated_at: now,
@@ -150,7 +155,7 @@ impl EntityStore for MemoryEntityStore {
         let mut result: Vec<_> = entities
             .values()
             .filter(|e| e.user_id.as_ref() == Some(user_id))
-            .filter(|e| !e.is_archived)
+            .filter(|e| !e.is_archived && !e.incognito.is_unlisted())
             .filter(|e| {
                 query.entity_types.as_ref().map_or(true, |types| {
                     types.iter().any(|t| &e.entity_type == t)
@@ -166,6 +171,24 @@ impl EntityStore for MemoryEntityStore {
         Ok(result)
     }
 
+    async fn list_conversations(
+        &self,
+        user_id: &UserId,
+        options: &ConversationListOptions,
+    ) -> Result<Vec<StoredEntity>> {
+        let entities = self.entities.lock().unwrap();
+        let mut result: Vec<_> = entities
+            .values()
+            .filter(|e| e.user_id.as_ref() == Some(user_id))
+            .filter(|e| options.include_archived || !e.is_archived)
+            .filter(|e| options.include_incognito || !e.incognito.is_unlisted())
+            .filter(|e| e.entity_type == EntityType::conversation())
+            .map(|e| e.to_stored())
+            .collect();
+        result.sort_by_key(|e| (!e.is_pinned(), std::cmp::Reverse(e.updated_at)));
+        Ok(result)
+    }
+
     async fn update_entity(&self, id: &EntityId, entity: &Entity) -> Result<()> {
         let mut entities = self.entities.lock().unwrap();
         if let Some(entry) = entities.get_mut(id.as_str()) {
@@ -184,6 +207,7 @@ impl EntityStore for MemoryEntityStore {
             entry.slug = entity.slug.clone();
             entry.is_private = entity.is_private;
             entry.is_archived = entity.is_archived;
+            entry.incognito = entity.incognito;
             entry.metadata = entity.metadata.clone();
             entry.updated_at = now();
         }
@@ -199,6 +223,15 @@ impl EntityStore for MemoryEntityStore {
         Ok(())
     }
 
+    async fn unarchive_entity(&self, id: &EntityId) -> Result<()> {
+        let mut entities = self.entities.lock().unwrap();
+        if let Some(entry) = entities.get_mut(id.as_str()) {
+            entry.is_archived = false;
+            entry.updated_at = now();
+        }
+        Ok(())
+    }
+
     async fn delete_entity(&self, id: &EntityId) -> Result<()> {
         // Remove slug index
         {
@@ -216,6 +249,9 @@ impl EntityStore for MemoryEntityStore {
             relations.retain(|k, _| k.from_id != id.as_str() && k.to_id != id.as_str());
         }
 
+        // Remove tags
+        self.tags.lock().unwrap().remove(id.as_str());
+
         // Remove entity
         self.entities.lock().unwrap().remove(id.as_str());
         Ok(())
@@ -308,6 +344,52 @@ impl EntityStore for MemoryEntityStore {
         self.relations.lock().unwrap().remove(&key);
         Ok(())
     }
+
+    async fn add_conversation_tag(&self, conversation_id: &ConversationId, tag: &str) -> Result<()> {
+        let tag = normalize_tag(tag)?;
+        self.tags
+            .lock()
+            .unwrap()
+            .entry(conversation_id.as_str().to_string())
+            .or_default()
+            .insert(tag.to_string());
+        Ok(())
+    }
+
+    async fn remove_conversation_tag(&self, conversation_id: &ConversationId, tag: &str) -> Result<()> {
+        if let Some(tags) = self.tags.lock().unwrap().get_mut(conversation_id.as_str()) {
+            tags.remove(tag.trim());
+        }
+        Ok(())
+    }
+
+    async fn get_conversation_tags(&self, conversation_id: &ConversationId) -> Result<Vec<String>> {
+        let tags = self.tags.lock().unwrap();
+        Ok(tags
+            .get(conversation_id.as_str())
+            .map(|t| t.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn list_conversations_by_tag(
+        &self,
+        user_id: &UserId,
+        tag: &str,
+    ) -> Result<Vec<StoredEntity>> {
+        let tag = tag.trim();
+        let tags = self.tags.lock().unwrap();
+        let entities = self.entities.lock().unwrap();
+        let mut result: Vec<_> = entities
+            .values()
+            .filter(|e| e.user_id.as_ref() == Some(user_id))
+            .filter(|e| !e.is_archived && !e.incognito.is_unlisted())
+            .filter(|e| e.entity_type == EntityType::conversation())
+            .filter(|e| tags.get(e.id.as_str()).is_some_and(|t| t.contains(tag)))
+            .map(|e| e.to_stored())
+            .collect();
+        result.sort_by_key(|e| std::cmp::Reverse(e.updated_at));
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
@@ -347,4 +429,59 @@ mod tests {
         let all = store.list_entities(&user_id, None).await.unwrap();
         assert_eq!(all.len(), 3);
     }
+
+    #[tokio::test]
+    async fn test_list_conversations_pinned_first() {
+        let store = MemoryEntityStore::new();
+        let user_id = UserId::new();
+        let old = store.create_entity(EntityType::conversation(), Some(&user_id)).await.unwrap();
+        let archived = store.create_entity(EntityType::conversation(), Some(&user_id)).await.unwrap();
+        let recent = store.create_entity(EntityType::conversation(), Some(&user_id)).await.unwrap();
+        store.create_entity(EntityType::document(), Some(&user_id)).await.unwrap();
+
+        // Pin the oldest conversation, then make the others more recent
+        let mut entity = store.get_entity(&old).await.unwrap().unwrap();
+        entity.set_pinned(true);
+        store.update_entity(&old, &entity).await.unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        store.archive_entity(&archived).await.unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let entity = store.get_entity(&recent).await.unwrap().unwrap();
+        store.update_entity(&recent, &entity).await.unwrap();
+
+        let ids = |list: Vec<StoredEntity>| list.into_iter().map(|e| e.id).collect::<Vec<_>>();
+        let default = ConversationListOptions::default();
+        assert_eq!(
+            ids(store.list_conversations(&user_id, &default).await.unwrap()),
+            vec![old.clone(), recent.clone()]
+        );
+        assert_eq!(
+            ids(store.list_conversations(&user_id, &default.clone().with_archived()).await.unwrap()),
+            vec![old.clone(), recent.clone(), archived.clone()]
+        );
+
+        store.unarchive_entity(&archived).await.unwrap();
+        assert_eq!(store.list_conversations(&user_id, &default).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_conversation_tags() {
+        let store = MemoryEntityStore::new();
+        let user_id = UserId::new();
+        let work = store.create_entity(EntityType::conversation(), Some(&user_id)).await.unwrap();
+        let other = store.create_entity(EntityType::conversation(), Some(&user_id)).await.unwrap();
+
+        store.add_conversation_tag(&work, " work ").await.unwrap();
+        store.add_conversation_tag(&work, "rust").await.unwrap();
+        store.add_conversation_tag(&other, "rust").await.unwrap();
+        assert!(store.add_conversation_tag(&work, "").await.is_err());
+        assert_eq!(store.get_conversation_tags(&work).await.unwrap(), vec!["rust", "work"]);
+
+        let tagged = store.list_conversations_by_tag(&user_id, "work").await.unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].id, work);
+
+        store.archive_entity(&other).await.unwrap();
+        assert_eq!(store.list_conversations_by_tag(&user_id, "rust").await.unwrap().len(), 1);
+    }
 }
@@ -362,6 +362,22 @@ impl TurnStore for MemoryTurnStore {
         Ok(count)
     }
 
+    async fn truncate_selections(
+        &self,
+        conversation_id: &ConversationId,
+        after_turn_id: &TurnId,
+    ) -> Result<usize> {
+        let mut selections = self.conversation_selections.lock().unwrap();
+        let cutoff_seq = selections
+            .get(&(conversation_id.clone(), after_turn_id.clone()))
+            .map(|sel| sel.sequence_number)
+            .ok_or_else(|| anyhow::anyhow!("Turn not in conversation"))?;
+
+        let before = selections.len();
+        selections.retain(|(cid, _), sel| cid != conversation_id || sel.sequence_number <= cutoff_seq);
+        Ok(before - selections.len())
+    }
+
     async fn get_turn_count(&self, conversation_id: &ConversationId) -> Result<usize> {
         let selections = self.conversation_selections.lock().unwrap();
         let count = selections
@@ -1,12 +1,12 @@
 //! Mock blob store for testing
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 
 use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::storage::{traits::BlobStore, types::blob::BlobHash};
+use crate::storage::{traits::BlobStore, types::blob::{BlobHash, GcStats}};
 
 /// Mock blob store with in-memory storage
 pub struct MockBlobStore {
@@ -32,7 +32,7 @@ impl BlobStore for MockBlobStore {
     async fn store(&self, data: &[u8]) -> Result<BlobHash> {
         let mut blobs = self.blobs.lock().unwrap();
         let hash = BlobHash::from_data(data);
-        blobs.insert(hash.clone(), data.to_vec());
+        blobs.entry(hash.clone()).or_insert_with(|| data.to_vec());
 
         Ok(hash)
     }
@@ -52,4 +52,17 @@ impl BlobStore for MockBlobStore {
     async fn delete(&self, hash: &BlobHash) -> Result<bool> {
         Ok(self.blobs.lock().unwrap().remove(hash).is_some())
     }
+
+    async fn gc(&self, referenced: &HashSet<BlobHash>) -> Result<GcStats> {
+        let mut stats = GcStats::default();
+        self.blobs.lock().unwrap().retain(|hash, data| {
+            if referenced.contains(hash) {
+                return true;
+            }
+            stats.deleted += 1;
+            stats.bytes_reclaimed += data.len() as u64;
+            false
+        });
+        Ok(stats)
+    }
 }
@@ -3,9 +3,9 @@
 use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::storage::ids::{EntityId, UserId};
+use crate::storage::ids::{ConversationId, EntityId, UserId};
 use crate::storage::traits::{EntityStore, StoredEntity};
-use crate::storage::types::entity::{EntityRangeQuery, EntityRelation, EntityType, RelationType};
+use crate::storage::types::entity::{ConversationListOptions, EntityRangeQuery, EntityRelation, EntityType, RelationType};
 
 /// Mock entity store that returns unimplemented for all operations
 pub struct MockEntityStore;
@@ -27,12 +27,18 @@ impl EntityStore for MockEntityStore {
     async fn list_entities_in_range(&self, _: &UserId, _: &EntityRangeQuery) -> Result<Vec<StoredEntity>> {
         unimplemented!()
     }
+    async fn list_conversations(&self, _: &UserId, _: &ConversationListOptions) -> Result<Vec<StoredEntity>> {
+        unimplemented!()
+    }
     async fn update_entity(&self, _: &EntityId, _: &crate::storage::types::Entity) -> Result<()> {
         unimplemented!()
     }
     async fn archive_entity(&self, _: &EntityId) -> Result<()> {
         unimplemented!()
     }
+    async fn unarchive_entity(&self, _: &EntityId) -> Result<()> {
+        unimplemented!()
+    }
     async fn delete_entity(&self, _: &EntityId) -> Result<()> {
         unimplemented!()
     }
@@ -62,4 +68,16 @@ impl EntityStore for MockEntityStore {
     async fn remove_relation(&self, _: &EntityId, _: &EntityId, _: &RelationType) -> Result<()> {
         unimplemented!()
     }
+    async fn add_conversation_tag(&self, _: &ConversationId, _: &str) -> Result<()> {
+        unimplemented!()
+    }
+    async fn remove_conversation_tag(&self, _: &ConversationId, _: &str) -> Result<()> {
+        unimplemented!()
+    }
+    async fn get_conversation_tags(&self, _: &ConversationId) -> Result<Vec<String>> {
+        unimplemented!()
+    }
+    async fn list_conversations_by_tag(&self, _: &UserId, _: &str) -> Result<Vec<StoredEntity>> {
+        unimplemented!()
+    }
 }
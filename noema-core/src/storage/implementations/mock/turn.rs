@@ -64,6 +64,9 @@ impl TurnStore for MockTurnStore {
     ) -> Result<usize> {
         unimplemented!()
     }
+    async fn truncate_selections(&self, _: &ConversationId, _: &TurnId) -> Result<usize> {
+        unimplemented!()
+    }
     async fn get_turn_count(&self, _: &ConversationId) -> Result<usize> {
         unimplemented!()
     }
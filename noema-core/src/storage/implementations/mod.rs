@@ -5,13 +5,19 @@
 //! ## Available Implementations
 //!
 //! - `sqlite/` - SQLite-based storage (requires `sqlite` feature)
+//! - `postgres/` - PostgreSQL storage (requires `postgres` feature)
 //! - `memory/` - In-memory storage for testing
 //! - `fs/` - Filesystem-based blob storage
+//! - `encrypted` - Encrypting wrapper for any blob store
 //! - `mock/` - Minimal mock stores for coordinator testing
 
 #[cfg(feature = "sqlite")]
 pub mod sqlite;
 
+#[cfg(feature = "postgres")]
+pub mod postgres;
+
+pub mod encrypted;
 pub mod fs;
 pub mod memory;
 pub mod mock;
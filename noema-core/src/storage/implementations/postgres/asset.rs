@@ -0,0 +1,89 @@
+//! PostgreSQL implementation of AssetStore
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::Row;
+
+use super::PostgresStore;
+use crate::storage::helper::unix_timestamp;
+use crate::storage::ids::AssetId;
+use crate::storage::traits::AssetStore;
+use crate::storage::types::{stored, Asset, Stored};
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS assets (
+        id TEXT PRIMARY KEY,
+        blob_hash TEXT NOT NULL,
+        mime_type TEXT NOT NULL,
+        size_bytes BIGINT NOT NULL,
+        is_private BOOLEAN NOT NULL DEFAULT FALSE,
+        created_at BIGINT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_assets_blob_hash ON assets(blob_hash);
+    CREATE INDEX IF NOT EXISTS idx_assets_private ON assets(is_private);
+    CREATE INDEX IF NOT EXISTS idx_assets_created ON assets(created_at);
+"#;
+
+#[async_trait]
+impl AssetStore for PostgresStore {
+    async fn create_asset(&self, asset: Asset) -> Result<AssetId> {
+        let id = AssetId::new();
+
+        sqlx::query(
+            "INSERT INTO assets (id, blob_hash, mime_type, size_bytes, is_private, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&id)
+        .bind(asset.blob_hash.as_str())
+        .bind(&asset.mime_type)
+        .bind(asset.size_bytes)
+        .bind(asset.is_private)
+        .bind(unix_timestamp())
+        .execute(self.pool())
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn get(&self, id: &AssetId) -> Result<Option<Stored<AssetId, Asset>>> {
+        let row = sqlx::query(
+            "SELECT blob_hash, mime_type, size_bytes, is_private, created_at
+             FROM assets WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let blob_hash: String = row.try_get(0)?;
+        Ok(Some(stored(
+            id.clone(),
+            Asset {
+                blob_hash: blob_hash.parse()?,
+                mime_type: row.try_get(1)?,
+                size_bytes: row.try_get(2)?,
+                is_private: row.try_get(3)?,
+            },
+            row.try_get(4)?,
+        )))
+    }
+
+    async fn exists(&self, id: &AssetId) -> Result<bool> {
+        let exists = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM assets WHERE id = $1)")
+            .bind(id)
+            .fetch_one(self.pool())
+            .await?;
+        Ok(exists)
+    }
+
+    async fn delete(&self, id: &AssetId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM assets WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
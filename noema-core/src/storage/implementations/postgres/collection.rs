@@ -0,0 +1,648 @@
+//! PostgreSQL implementation of CollectionStore
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+use super::PostgresStore;
+use crate::storage::helper::unix_timestamp;
+use crate::storage::ids::{
+    CollectionId, CollectionItemId, CollectionViewId, EntityId, ItemFieldId, UserId,
+};
+use crate::storage::traits::{
+    CollectionStore, ItemField, StoredCollection, StoredCollectionItem, StoredCollectionView,
+    StoredItemField,
+};
+use crate::storage::types::{
+    stored_editable, Collection, CollectionItem, CollectionView, FieldType, ItemTarget, ViewConfig,
+    ViewType,
+};
+
+/// Collections schema
+pub(crate) const SCHEMA: &str = r#"
+    -- Collections (tree organization of entities)
+    CREATE TABLE IF NOT EXISTS collections (
+        id TEXT PRIMARY KEY,
+        user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        description TEXT,
+        icon TEXT,
+        schema_hint TEXT,
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL
+    );
+
+    -- Index for user's collections
+    CREATE INDEX IF NOT EXISTS idx_collections_user ON collections(user_id);
+
+    -- Collection items (entities organized in tree structure)
+    CREATE TABLE IF NOT EXISTS collection_items (
+        id TEXT PRIMARY KEY,
+        collection_id TEXT NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+        target_type TEXT NOT NULL,
+        target_id TEXT NOT NULL,
+        parent_item_id TEXT REFERENCES collection_items(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        name_override TEXT,
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL
+    );
+
+    -- Indexes for collection items
+    CREATE INDEX IF NOT EXISTS idx_collection_items_collection ON collection_items(collection_id);
+    CREATE INDEX IF NOT EXISTS idx_collection_items_parent ON collection_items(parent_item_id);
+    CREATE INDEX IF NOT EXISTS idx_collection_items_target ON collection_items(target_type, target_id);
+
+    -- Item fields (typed metadata on collection items)
+    CREATE TABLE IF NOT EXISTS item_fields (
+        id TEXT PRIMARY KEY,
+        item_id TEXT NOT NULL REFERENCES collection_items(id) ON DELETE CASCADE,
+        field_name TEXT NOT NULL,
+        field_type TEXT NOT NULL,
+        value_text TEXT,
+        value_number DOUBLE PRECISION,
+        value_boolean BOOLEAN,
+        value_json TEXT,
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL,
+        UNIQUE(item_id, field_name)
+    );
+
+    -- Indexes for item fields
+    CREATE INDEX IF NOT EXISTS idx_item_fields_item ON item_fields(item_id);
+    CREATE INDEX IF NOT EXISTS idx_item_fields_name ON item_fields(field_name);
+
+    -- Item tags (cross-cutting organization)
+    CREATE TABLE IF NOT EXISTS item_tags (
+        item_id TEXT NOT NULL REFERENCES collection_items(id) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        created_at BIGINT NOT NULL,
+        PRIMARY KEY(item_id, tag)
+    );
+
+    -- Index for finding items by tag
+    CREATE INDEX IF NOT EXISTS idx_item_tags_tag ON item_tags(tag);
+
+    -- Collection views (saved view configurations)
+    CREATE TABLE IF NOT EXISTS collection_views (
+        id TEXT PRIMARY KEY,
+        collection_id TEXT NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        view_type TEXT NOT NULL,
+        config TEXT NOT NULL,
+        is_default BOOLEAN NOT NULL DEFAULT FALSE,
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL
+    );
+
+    -- Index for collection's views
+    CREATE INDEX IF NOT EXISTS idx_collection_views_collection ON collection_views(collection_id);
+"#;
+
+const COLLECTION_COLUMNS: &str =
+    "id, user_id, name, description, icon, schema_hint, created_at, updated_at";
+
+const ITEM_COLUMNS: &str =
+    "ci.id, ci.collection_id, ci.target_type, ci.target_id, ci.parent_item_id, ci.position, ci.name_override, ci.created_at, ci.updated_at";
+
+const VIEW_COLUMNS: &str =
+    "id, collection_id, name, view_type, config, is_default, created_at, updated_at";
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn parse_collection(row: &PgRow) -> sqlx::Result<StoredCollection> {
+    let schema_hint: Option<String> = row.try_get(5)?;
+    let collection = Collection {
+        user_id: row.try_get(1)?,
+        name: row.try_get(2)?,
+        description: row.try_get(3)?,
+        icon: row.try_get(4)?,
+        schema_hint: schema_hint.and_then(|j| serde_json::from_str(&j).ok()),
+    };
+    Ok(stored_editable(row.try_get(0)?, collection, row.try_get(6)?, row.try_get(7)?))
+}
+
+fn parse_collection_item(row: &PgRow) -> sqlx::Result<StoredCollectionItem> {
+    let target_type: String = row.try_get(2)?;
+    let target_id: String = row.try_get(3)?;
+    let target = match target_type.as_str() {
+        "collection" => ItemTarget::Collection(CollectionId::from_string(target_id)),
+        _ => ItemTarget::Entity(EntityId::from_string(target_id)),
+    };
+
+    let item = CollectionItem {
+        collection_id: row.try_get(1)?,
+        target,
+        parent_item_id: row.try_get(4)?,
+        position: row.try_get(5)?,
+        name_override: row.try_get(6)?,
+    };
+    Ok(stored_editable(row.try_get(0)?, item, row.try_get(7)?, row.try_get(8)?))
+}
+
+fn parse_collection_view(row: &PgRow) -> sqlx::Result<StoredCollectionView> {
+    let config: String = row.try_get(4)?;
+    let view = CollectionView {
+        collection_id: row.try_get(1)?,
+        name: row.try_get(2)?,
+        config: serde_json::from_str::<ViewConfig>(&config).unwrap_or_default(),
+        is_default: row.try_get(5)?,
+    };
+    Ok(stored_editable(row.try_get(0)?, view, row.try_get(6)?, row.try_get(7)?))
+}
+
+fn parse_item_field(row: &PgRow) -> sqlx::Result<StoredItemField> {
+    let field_type: String = row.try_get(2)?;
+    let value_json: Option<String> = row.try_get(6)?;
+    let field = ItemField {
+        field_name: row.try_get(1)?,
+        field_type: parse_field_type(&field_type),
+        value_text: row.try_get(3)?,
+        value_number: row.try_get(4)?,
+        value_boolean: row.try_get(5)?,
+        value_json: value_json.and_then(|j| serde_json::from_str(&j).ok()),
+    };
+    Ok(stored_editable(row.try_get(0)?, field, row.try_get(7)?, row.try_get(8)?))
+}
+
+fn parse_field_type(s: &str) -> FieldType {
+    match s {
+        "number" => FieldType::Number,
+        "boolean" => FieldType::Boolean,
+        "date" => FieldType::Date,
+        "select" => FieldType::Select,
+        "multi_select" => FieldType::MultiSelect,
+        "url" => FieldType::Url,
+        _ => FieldType::Text,
+    }
+}
+
+fn field_type_to_str(ft: &FieldType) -> &'static str {
+    match ft {
+        FieldType::Text => "text",
+        FieldType::Number => "number",
+        FieldType::Boolean => "boolean",
+        FieldType::Date => "date",
+        FieldType::Select => "select",
+        FieldType::MultiSelect => "multi_select",
+        FieldType::Url => "url",
+    }
+}
+
+fn view_type_to_str(vt: &ViewType) -> &'static str {
+    match vt {
+        ViewType::List => "list",
+        ViewType::Table => "table",
+        ViewType::Board => "board",
+        ViewType::Calendar => "calendar",
+        ViewType::Gallery => "gallery",
+    }
+}
+
+impl PostgresStore {
+    /// Collection items matching a condition on `ci` with one parameter
+    async fn items_where(&self, condition: &str, param: &str) -> Result<Vec<StoredCollectionItem>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM collection_items ci WHERE {} ORDER BY ci.position",
+            ITEM_COLUMNS, condition
+        ))
+        .bind(param)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.iter().map(parse_collection_item).collect::<sqlx::Result<_>>()?)
+    }
+}
+
+// ============================================================================
+// CollectionStore Implementation
+// ============================================================================
+
+#[async_trait]
+impl CollectionStore for PostgresStore {
+    // ========================================================================
+    // Collection CRUD
+    // ========================================================================
+
+    async fn create_collection(
+        &self,
+        user_id: &UserId,
+        name: &str,
+        description: Option<&str>,
+        icon: Option<&str>,
+    ) -> Result<CollectionId> {
+        let id = CollectionId::new();
+        let now = unix_timestamp();
+
+        sqlx::query(
+            "INSERT INTO collections (id, user_id, name, description, icon, schema_hint, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, NULL, $6, $7)",
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(name)
+        .bind(description)
+        .bind(icon)
+        .bind(now)
+        .bind(now)
+        .execute(self.pool())
+        .await
+        .context("Failed to create collection")?;
+
+        Ok(id)
+    }
+
+    async fn get_collection(&self, id: &CollectionId) -> Result<Option<StoredCollection>> {
+        let row = sqlx::query(&format!("SELECT {} FROM collections WHERE id = $1", COLLECTION_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.pool())
+            .await?;
+        Ok(row.as_ref().map(parse_collection).transpose()?)
+    }
+
+    async fn list_collections(&self, user_id: &UserId) -> Result<Vec<StoredCollection>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM collections WHERE user_id = $1 ORDER BY name",
+            COLLECTION_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows.iter().map(parse_collection).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn update_collection(
+        &self,
+        id: &CollectionId,
+        name: Option<&str>,
+        description: Option<&str>,
+        icon: Option<&str>,
+    ) -> Result<bool> {
+        // Fields passed as None keep their current value
+        let result = sqlx::query(
+            "UPDATE collections
+             SET name = COALESCE($1, name), description = COALESCE($2, description), icon = COALESCE($3, icon), updated_at = $4
+             WHERE id = $5",
+        )
+        .bind(name)
+        .bind(description)
+        .bind(icon)
+        .bind(unix_timestamp())
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_collection(&self, id: &CollectionId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM collections WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Item Management
+    // ========================================================================
+
+    async fn add_item(
+        &self,
+        collection_id: &CollectionId,
+        target: &ItemTarget,
+        parent_item_id: Option<&CollectionItemId>,
+        position: i32,
+        name_override: Option<&str>,
+    ) -> Result<CollectionItemId> {
+        let id = CollectionItemId::new();
+        let now = unix_timestamp();
+
+        let (target_type, target_id) = match target {
+            ItemTarget::Entity(eid) => ("entity", eid.as_str()),
+            ItemTarget::Collection(cid) => ("collection", cid.as_str()),
+        };
+
+        sqlx::query(
+            "INSERT INTO collection_items (id, collection_id, target_type, target_id, parent_item_id, position, name_override, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(&id)
+        .bind(collection_id)
+        .bind(target_type)
+        .bind(target_id)
+        .bind(parent_item_id)
+        .bind(position)
+        .bind(name_override)
+        .bind(now)
+        .bind(now)
+        .execute(self.pool())
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn get_item(&self, id: &CollectionItemId) -> Result<Option<StoredCollectionItem>> {
+        Ok(self.items_where("ci.id = $1", id.as_str()).await?.into_iter().next())
+    }
+
+    async fn get_items(&self, collection_id: &CollectionId) -> Result<Vec<StoredCollectionItem>> {
+        self.items_where("ci.collection_id = $1", collection_id.as_str()).await
+    }
+
+    async fn get_root_items(&self, collection_id: &CollectionId) -> Result<Vec<StoredCollectionItem>> {
+        self.items_where("ci.collection_id = $1 AND ci.parent_item_id IS NULL", collection_id.as_str())
+            .await
+    }
+
+    async fn get_children(&self, item_id: &CollectionItemId) -> Result<Vec<StoredCollectionItem>> {
+        self.items_where("ci.parent_item_id = $1", item_id.as_str()).await
+    }
+
+    async fn move_item(
+        &self,
+        item_id: &CollectionItemId,
+        new_parent_id: Option<&CollectionItemId>,
+        new_position: i32,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE collection_items SET parent_item_id = $1, position = $2, updated_at = $3 WHERE id = $4",
+        )
+        .bind(new_parent_id)
+        .bind(new_position)
+        .bind(unix_timestamp())
+        .bind(item_id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove_item(&self, id: &CollectionItemId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM collection_items WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Field Operations
+    // ========================================================================
+
+    async fn set_field(
+        &self,
+        item_id: &CollectionItemId,
+        field: &ItemField,
+    ) -> Result<ItemFieldId> {
+        let now = unix_timestamp();
+        let value_json = field.value_json.as_ref().map(|v| v.to_string());
+
+        // Updating keeps the existing field's ID
+        let id = sqlx::query_scalar(
+            "INSERT INTO item_fields (id, item_id, field_name, field_type, value_text, value_number, value_boolean, value_json, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9)
+             ON CONFLICT (item_id, field_name) DO UPDATE SET
+                 field_type = EXCLUDED.field_type,
+                 value_text = EXCLUDED.value_text,
+                 value_number = EXCLUDED.value_number,
+                 value_boolean = EXCLUDED.value_boolean,
+                 value_json = EXCLUDED.value_json,
+                 updated_at = EXCLUDED.updated_at
+             RETURNING id",
+        )
+        .bind(ItemFieldId::new())
+        .bind(item_id)
+        .bind(&field.field_name)
+        .bind(field_type_to_str(&field.field_type))
+        .bind(&field.value_text)
+        .bind(field.value_number)
+        .bind(field.value_boolean)
+        .bind(value_json)
+        .bind(now)
+        .fetch_one(self.pool())
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn get_fields(&self, item_id: &CollectionItemId) -> Result<Vec<StoredItemField>> {
+        let rows = sqlx::query(
+            "SELECT id, field_name, field_type, value_text, value_number, value_boolean, value_json, created_at, updated_at
+             FROM item_fields WHERE item_id = $1",
+        )
+        .bind(item_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows.iter().map(parse_item_field).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn remove_field(&self, item_id: &CollectionItemId, field_name: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM item_fields WHERE item_id = $1 AND field_name = $2")
+            .bind(item_id)
+            .bind(field_name)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Tag Operations
+    // ========================================================================
+
+    async fn add_tag(&self, item_id: &CollectionItemId, tag: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO item_tags (item_id, tag, created_at) VALUES ($1, $2, $3)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(item_id)
+        .bind(tag)
+        .bind(unix_timestamp())
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_tag(&self, item_id: &CollectionItemId, tag: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM item_tags WHERE item_id = $1 AND tag = $2")
+            .bind(item_id)
+            .bind(tag)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_tags(&self, item_id: &CollectionItemId) -> Result<Vec<String>> {
+        let tags = sqlx::query_scalar("SELECT tag FROM item_tags WHERE item_id = $1 ORDER BY tag")
+            .bind(item_id)
+            .fetch_all(self.pool())
+            .await?;
+        Ok(tags)
+    }
+
+    async fn find_by_tag(
+        &self,
+        collection_id: &CollectionId,
+        tag: &str,
+    ) -> Result<Vec<StoredCollectionItem>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM collection_items ci
+             JOIN item_tags it ON ci.id = it.item_id
+             WHERE ci.collection_id = $1 AND it.tag = $2
+             ORDER BY ci.position",
+            ITEM_COLUMNS
+        ))
+        .bind(collection_id)
+        .bind(tag)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows.iter().map(parse_collection_item).collect::<sqlx::Result<_>>()?)
+    }
+
+    // ========================================================================
+    // View Operations
+    // ========================================================================
+
+    async fn create_view(
+        &self,
+        collection_id: &CollectionId,
+        name: &str,
+        view: &CollectionView,
+    ) -> Result<CollectionViewId> {
+        let id = CollectionViewId::new();
+        let now = unix_timestamp();
+
+        sqlx::query(
+            "INSERT INTO collection_views (id, collection_id, name, view_type, config, is_default, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(&id)
+        .bind(collection_id)
+        .bind(name)
+        .bind(view_type_to_str(&view.config.view_type))
+        .bind(serde_json::to_string(&view.config)?)
+        .bind(view.is_default)
+        .bind(now)
+        .bind(now)
+        .execute(self.pool())
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn get_view(&self, id: &CollectionViewId) -> Result<Option<StoredCollectionView>> {
+        let row = sqlx::query(&format!("SELECT {} FROM collection_views WHERE id = $1", VIEW_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.pool())
+            .await?;
+        Ok(row.as_ref().map(parse_collection_view).transpose()?)
+    }
+
+    async fn list_views(&self, collection_id: &CollectionId) -> Result<Vec<StoredCollectionView>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM collection_views WHERE collection_id = $1 ORDER BY name",
+            VIEW_COLUMNS
+        ))
+        .bind(collection_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows.iter().map(parse_collection_view).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn get_default_view(&self, collection_id: &CollectionId) -> Result<Option<StoredCollectionView>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM collection_views WHERE collection_id = $1 AND is_default LIMIT 1",
+            VIEW_COLUMNS
+        ))
+        .bind(collection_id)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(row.as_ref().map(parse_collection_view).transpose()?)
+    }
+
+    async fn update_view(&self, id: &CollectionViewId, view: &CollectionView) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE collection_views SET name = $1, view_type = $2, config = $3, is_default = $4, updated_at = $5 WHERE id = $6",
+        )
+        .bind(&view.name)
+        .bind(view_type_to_str(&view.config.view_type))
+        .bind(serde_json::to_string(&view.config)?)
+        .bind(view.is_default)
+        .bind(unix_timestamp())
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_view(&self, id: &CollectionViewId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM collection_views WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Query Operations
+    // ========================================================================
+
+    async fn find_items_by_entity(&self, entity_id: &EntityId) -> Result<Vec<StoredCollectionItem>> {
+        self.items_where("ci.target_type = 'entity' AND ci.target_id = $1", entity_id.as_str())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::test_store;
+    use super::*;
+    use crate::storage::traits::{EntityStore, UserStore};
+    use crate::storage::types::EntityType;
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_collection_items_fields_and_tags() {
+        let store = test_store().await;
+        let user = store.get_or_create_default_user().await.unwrap();
+        let collection = store.create_collection(&user.id, "Reading", None, None).await.unwrap();
+        store.update_collection(&collection, None, Some("Papers"), None).await.unwrap();
+        let stored = store.get_collection(&collection).await.unwrap().unwrap();
+        assert_eq!(stored.name, "Reading");
+        assert_eq!(stored.description.as_deref(), Some("Papers"));
+
+        let doc = store.create_entity(EntityType::document(), Some(&user.id)).await.unwrap();
+        let folder = store
+            .add_item(&collection, &ItemTarget::Entity(doc.clone()), None, 0, Some("Folder"))
+            .await
+            .unwrap();
+        let child = store
+            .add_item(&collection, &ItemTarget::Entity(doc.clone()), Some(&folder), 0, None)
+            .await
+            .unwrap();
+        assert_eq!(store.get_root_items(&collection).await.unwrap().len(), 1);
+        assert_eq!(store.get_children(&folder).await.unwrap()[0].id, child);
+        assert_eq!(store.find_items_by_entity(&doc).await.unwrap().len(), 2);
+
+        let first = store.set_field(&child, &ItemField::number("rating", 3.0)).await.unwrap();
+        let second = store.set_field(&child, &ItemField::number("rating", 5.0)).await.unwrap();
+        assert_eq!(first, second);
+        let fields = store.get_fields(&child).await.unwrap();
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].value_number, Some(5.0));
+
+        store.add_tag(&child, "todo").await.unwrap();
+        store.add_tag(&child, "todo").await.unwrap();
+        assert_eq!(store.get_tags(&child).await.unwrap(), vec!["todo"]);
+        assert_eq!(store.find_by_tag(&collection, "todo").await.unwrap().len(), 1);
+
+        let view = CollectionView::new(collection.clone(), "All", ViewConfig::list()).as_default();
+        let view_id = store.create_view(&collection, "All", &view).await.unwrap();
+        assert_eq!(store.get_default_view(&collection).await.unwrap().unwrap().id, view_id);
+
+        // Removing the parent removes its subtree
+        assert!(store.remove_item(&folder).await.unwrap());
+        assert!(store.get_item(&child).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,571 @@
+//! PostgreSQL implementation of DocumentStore
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+use super::PostgresStore;
+use crate::storage::helper::unix_timestamp;
+use crate::storage::ids::{AssetId, DocumentId, MessageId, RevisionId, TabId, UserId};
+use crate::storage::traits::DocumentStore;
+use crate::storage::types::{stored, stored_editable, Document, DocumentRevision, DocumentSource, DocumentTab, Stored, StoredEditable};
+
+pub(crate) const SCHEMA: &str = r#"
+    -- Documents (Episteme-compatible)
+    CREATE TABLE IF NOT EXISTS documents (
+        id TEXT PRIMARY KEY,
+        user_id TEXT REFERENCES users(id) ON DELETE CASCADE,
+        title TEXT NOT NULL,
+        source TEXT NOT NULL,
+        source_id TEXT,
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL
+    );
+
+    -- Document Tabs (hierarchical structure within documents)
+    CREATE TABLE IF NOT EXISTS document_tabs (
+        id TEXT PRIMARY KEY,
+        document_id TEXT REFERENCES documents(id) ON DELETE CASCADE,
+        parent_tab_id TEXT REFERENCES document_tabs(id) ON DELETE CASCADE,
+        tab_index INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        icon TEXT,
+        content_markdown TEXT,
+        referenced_assets TEXT,
+        source_tab_id TEXT,
+        current_revision_id TEXT,
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL
+    );
+
+    -- Document Revisions (version history for tabs)
+    CREATE TABLE IF NOT EXISTS document_revisions (
+        id TEXT PRIMARY KEY,
+        tab_id TEXT REFERENCES document_tabs(id) ON DELETE CASCADE,
+        revision_number INTEGER NOT NULL,
+        parent_revision_id TEXT REFERENCES document_revisions(id) ON DELETE SET NULL,
+        content_markdown TEXT NOT NULL,
+        content_hash TEXT NOT NULL,
+        referenced_assets TEXT,
+        created_at BIGINT NOT NULL,
+        created_by TEXT NOT NULL DEFAULT 'import',
+        UNIQUE (tab_id, revision_number)
+    );
+
+    -- Indexes for documents
+    CREATE INDEX IF NOT EXISTS idx_documents_user ON documents(user_id);
+    CREATE INDEX IF NOT EXISTS idx_documents_source ON documents(source);
+    CREATE INDEX IF NOT EXISTS idx_documents_user_source_id ON documents(user_id, source, source_id);
+    CREATE INDEX IF NOT EXISTS idx_documents_title_search ON documents USING GIN (to_tsvector('english', title));
+    CREATE INDEX IF NOT EXISTS idx_document_tabs_document ON document_tabs(document_id);
+    CREATE INDEX IF NOT EXISTS idx_document_tabs_parent ON document_tabs(parent_tab_id);
+    CREATE INDEX IF NOT EXISTS idx_document_revisions_tab ON document_revisions(tab_id);
+"#;
+
+const DOCUMENT_COLUMNS: &str = "id, user_id, title, source, source_id, created_at, updated_at";
+
+const TAB_COLUMNS: &str =
+    "id, document_id, parent_tab_id, tab_index, title, icon, content_markdown, referenced_assets, source_tab_id, current_revision_id, created_at, updated_at";
+
+const REVISION_COLUMNS: &str =
+    "id, tab_id, revision_number, parent_revision_id, content_markdown, content_hash, referenced_assets, created_at, created_by";
+
+/// Parse a document from a database row
+fn parse_document(row: &PgRow) -> sqlx::Result<StoredEditable<DocumentId, Document>> {
+    let source_str: String = row.try_get(3)?;
+    let doc = Document {
+        user_id: row.try_get(1)?,
+        title: row.try_get(2)?,
+        source: source_str.parse().unwrap_or(DocumentSource::UserCreated),
+        source_id: row.try_get(4)?,
+    };
+    Ok(stored_editable(row.try_get(0)?, doc, row.try_get(5)?, row.try_get(6)?))
+}
+
+/// Parse referenced assets from JSON
+fn parse_assets(assets_json: Option<String>) -> Vec<AssetId> {
+    assets_json
+        .map(|j| {
+            let strings: Vec<String> = serde_json::from_str(&j).unwrap_or_default();
+            strings.into_iter().map(AssetId::from_string).collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Serialize referenced assets to JSON
+fn assets_to_json(assets: &[AssetId]) -> Result<String> {
+    let strings: Vec<&str> = assets.iter().map(|a| a.as_str()).collect();
+    Ok(serde_json::to_string(&strings)?)
+}
+
+/// Parse a document tab from a database row
+fn parse_document_tab(row: &PgRow) -> sqlx::Result<StoredEditable<TabId, DocumentTab>> {
+    let tab = DocumentTab {
+        document_id: row.try_get(1)?,
+        parent_tab_id: row.try_get(2)?,
+        tab_index: row.try_get(3)?,
+        title: row.try_get(4)?,
+        icon: row.try_get(5)?,
+        content_markdown: row.try_get(6)?,
+        referenced_assets: parse_assets(row.try_get(7)?),
+        source_tab_id: row.try_get(8)?,
+        current_revision_id: row.try_get(9)?,
+    };
+    Ok(stored_editable(row.try_get(0)?, tab, row.try_get(10)?, row.try_get(11)?))
+}
+
+/// Parse a document revision from a database row
+fn parse_document_revision(row: &PgRow) -> sqlx::Result<Stored<RevisionId, DocumentRevision>> {
+    let revision = DocumentRevision {
+        tab_id: row.try_get(1)?,
+        revision_number: row.try_get(2)?,
+        parent_revision_id: row.try_get(3)?,
+        content_markdown: row.try_get(4)?,
+        content_hash: row.try_get(5)?,
+        referenced_assets: parse_assets(row.try_get(6)?),
+        created_by: row.try_get(8)?,
+    };
+    Ok(stored(row.try_get(0)?, revision, row.try_get(7)?))
+}
+
+#[async_trait]
+impl DocumentStore for PostgresStore {
+    // ========== Document Methods ==========
+
+    async fn create_document(
+        &self,
+        user_id: &UserId,
+        title: &str,
+        source: DocumentSource,
+        source_id: Option<&str>,
+    ) -> Result<DocumentId> {
+        let id = DocumentId::new();
+        let now = unix_timestamp();
+
+        sqlx::query(
+            "INSERT INTO documents (id, user_id, title, source, source_id, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(title)
+        .bind(source.as_str())
+        .bind(source_id)
+        .bind(now)
+        .bind(now)
+        .execute(self.pool())
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn get_document(&self, id: &DocumentId) -> Result<Option<StoredEditable<DocumentId, Document>>> {
+        let row = sqlx::query(&format!("SELECT {} FROM documents WHERE id = $1", DOCUMENT_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.pool())
+            .await?;
+        Ok(row.as_ref().map(parse_document).transpose()?)
+    }
+
+    async fn get_document_by_source(
+        &self,
+        user_id: &UserId,
+        source: DocumentSource,
+        source_id: &str,
+    ) -> Result<Option<StoredEditable<DocumentId, Document>>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM documents WHERE user_id = $1 AND source = $2 AND source_id = $3",
+            DOCUMENT_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(source.as_str())
+        .bind(source_id)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(row.as_ref().map(parse_document).transpose()?)
+    }
+
+    async fn list_documents(&self, user_id: &UserId) -> Result<Vec<StoredEditable<DocumentId, Document>>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM documents WHERE user_id = $1 ORDER BY updated_at DESC",
+            DOCUMENT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows.iter().map(parse_document).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn search_documents(
+        &self,
+        user_id: &UserId,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<StoredEditable<DocumentId, Document>>> {
+        // Substring matches as in SQLite, plus stemmed word matches
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM documents
+             WHERE user_id = $1
+               AND (title ILIKE '%' || $2 || '%'
+                    OR to_tsvector('english', title) @@ websearch_to_tsquery('english', $2))
+             ORDER BY updated_at DESC
+             LIMIT $3",
+            DOCUMENT_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(query)
+        .bind(limit as i64)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows.iter().map(parse_document).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn update_document_title(&self, id: &DocumentId, title: &str) -> Result<()> {
+        sqlx::query("UPDATE documents SET title = $1, updated_at = $2 WHERE id = $3")
+            .bind(title)
+            .bind(unix_timestamp())
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_document(&self, id: &DocumentId) -> Result<bool> {
+        // Revisions and tabs will be cascade deleted
+        let result = sqlx::query("DELETE FROM documents WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========== Document Tab Methods ==========
+
+    async fn create_document_tab(
+        &self,
+        document_id: &DocumentId,
+        parent_tab_id: Option<&TabId>,
+        tab_index: i32,
+        title: &str,
+        icon: Option<&str>,
+        content_markdown: Option<&str>,
+        referenced_assets: &[AssetId],
+        source_tab_id: Option<&TabId>,
+    ) -> Result<TabId> {
+        let id = TabId::new();
+        let now = unix_timestamp();
+
+        sqlx::query(
+            "INSERT INTO document_tabs (id, document_id, parent_tab_id, tab_index, title, icon, content_markdown, referenced_assets, source_tab_id, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+        )
+        .bind(&id)
+        .bind(document_id)
+        .bind(parent_tab_id)
+        .bind(tab_index)
+        .bind(title)
+        .bind(icon)
+        .bind(content_markdown)
+        .bind(assets_to_json(referenced_assets)?)
+        .bind(source_tab_id)
+        .bind(now)
+        .bind(now)
+        .execute(self.pool())
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn get_document_tab(&self, id: &TabId) -> Result<Option<StoredEditable<TabId, DocumentTab>>> {
+        let row = sqlx::query(&format!("SELECT {} FROM document_tabs WHERE id = $1", TAB_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.pool())
+            .await?;
+        Ok(row.as_ref().map(parse_document_tab).transpose()?)
+    }
+
+    async fn list_document_tabs(&self, document_id: &DocumentId) -> Result<Vec<StoredEditable<TabId, DocumentTab>>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM document_tabs WHERE document_id = $1 ORDER BY tab_index",
+            TAB_COLUMNS
+        ))
+        .bind(document_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows.iter().map(parse_document_tab).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn update_document_tab_content(
+        &self,
+        id: &TabId,
+        content_markdown: &str,
+        referenced_assets: &[AssetId],
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE document_tabs SET content_markdown = $1, referenced_assets = $2, updated_at = $3 WHERE id = $4",
+        )
+        .bind(content_markdown)
+        .bind(assets_to_json(referenced_assets)?)
+        .bind(unix_timestamp())
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn update_document_tab_parent(
+        &self,
+        id: &TabId,
+        parent_tab_id: Option<&TabId>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE document_tabs SET parent_tab_id = $1, updated_at = $2 WHERE id = $3")
+            .bind(parent_tab_id)
+            .bind(unix_timestamp())
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    async fn set_document_tab_revision(&self, tab_id: &TabId, revision_id: &RevisionId) -> Result<()> {
+        sqlx::query("UPDATE document_tabs SET current_revision_id = $1, updated_at = $2 WHERE id = $3")
+            .bind(revision_id)
+            .bind(unix_timestamp())
+            .bind(tab_id)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_document_tab(&self, id: &TabId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM document_tabs WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========== Document Revision Methods ==========
+
+    async fn create_document_revision(
+        &self,
+        tab_id: &TabId,
+        content_markdown: &str,
+        content_hash: &str,
+        referenced_assets: &[AssetId],
+        created_by: &UserId,
+    ) -> Result<RevisionId> {
+        let id = RevisionId::new();
+        let mut tx = self.pool().begin().await?;
+
+        // Lock the tab so concurrent writers can't both read the same
+        // predecessor; the insert then sees the latest committed revision
+        sqlx::query("SELECT id FROM document_tabs WHERE id = $1 FOR UPDATE")
+            .bind(tab_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO document_revisions (id, tab_id, revision_number, parent_revision_id, content_markdown, content_hash, referenced_assets, created_at, created_by)
+             VALUES (
+                 $1, $2,
+                 (SELECT COALESCE(MAX(revision_number), 0) + 1 FROM document_revisions WHERE tab_id = $2),
+                 (SELECT current_revision_id FROM document_tabs WHERE id = $2),
+                 $3, $4, $5, $6, $7
+             )",
+        )
+        .bind(&id)
+        .bind(tab_id)
+        .bind(content_markdown)
+        .bind(content_hash)
+        .bind(assets_to_json(referenced_assets)?)
+        .bind(unix_timestamp())
+        .bind(created_by)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    async fn get_document_revision(&self, id: &RevisionId) -> Result<Option<Stored<RevisionId, DocumentRevision>>> {
+        let row = sqlx::query(&format!("SELECT {} FROM document_revisions WHERE id = $1", REVISION_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.pool())
+            .await?;
+        Ok(row.as_ref().map(parse_document_revision).transpose()?)
+    }
+
+    async fn list_document_revisions(&self, tab_id: &TabId) -> Result<Vec<Stored<RevisionId, DocumentRevision>>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM document_revisions WHERE tab_id = $1 ORDER BY revision_number DESC",
+            REVISION_COLUMNS
+        ))
+        .bind(tab_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows.iter().map(parse_document_revision).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn promote_from_message(
+        &self,
+        message_id: &MessageId,
+        user_id: &UserId,
+        title: Option<&str>,
+    ) -> Result<DocumentId> {
+        let now = unix_timestamp();
+        let mut tx = self.pool().begin().await?;
+
+        // 1. Get the text of the message's text content, in order
+        let text_parts: Vec<String> = sqlx::query_scalar(
+            "SELECT cb.text FROM message_content mc
+             JOIN content_blocks cb ON cb.id = mc.content_block_id
+             WHERE mc.message_id = $1 AND mc.content_type = 'text'
+             ORDER BY mc.sequence_number",
+        )
+        .bind(message_id)
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to get text from content_block")?;
+
+        if text_parts.is_empty() {
+            anyhow::bail!("Message has no text content to promote");
+        }
+        let content_markdown = text_parts.join("\n\n");
+
+        // 2. Determine title (use provided or first line)
+        let doc_title = title.map(|t| t.to_string()).unwrap_or_else(|| {
+            content_markdown
+                .lines()
+                .next()
+                .map(|l| l.trim_start_matches('#').trim())
+                .filter(|l| !l.is_empty())
+                .unwrap_or("Untitled")
+                .chars()
+                .take(100)
+                .collect()
+        });
+
+        // 3. Create document
+        let doc_id = DocumentId::new();
+        sqlx::query(
+            "INSERT INTO documents (id, user_id, title, source, source_id, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(&doc_id)
+        .bind(user_id)
+        .bind(&doc_title)
+        .bind(DocumentSource::AiGenerated.as_str())
+        .bind(message_id) // Store message_id as source_id for provenance
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        // 4. Create initial tab and revision
+        let tab_id = TabId::new();
+        let revision_id = RevisionId::new();
+        sqlx::query(
+            "INSERT INTO document_tabs (id, document_id, tab_index, title, content_markdown, current_revision_id, created_at, updated_at)
+             VALUES ($1, $2, 0, 'Main', $3, $4, $5, $6)",
+        )
+        .bind(&tab_id)
+        .bind(&doc_id)
+        .bind(&content_markdown)
+        .bind(&revision_id)
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        let content_hash = hex::encode(Sha256::digest(&content_markdown));
+        sqlx::query(
+            "INSERT INTO document_revisions (id, tab_id, revision_number, content_markdown, content_hash, created_at, created_by)
+             VALUES ($1, $2, 1, $3, $4, $5, $6)",
+        )
+        .bind(&revision_id)
+        .bind(&tab_id)
+        .bind(&content_markdown)
+        .bind(&content_hash)
+        .bind(now)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(doc_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::test_store;
+    use super::*;
+    use crate::storage::traits::UserStore;
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_documents_tabs_and_revisions() {
+        let store = test_store().await;
+        let user = store.get_or_create_default_user().await.unwrap();
+
+        let doc = store
+            .create_document(&user.id, "Running notes", DocumentSource::UserCreated, None)
+            .await
+            .unwrap();
+        let tab = store
+            .create_document_tab(&doc, None, 0, "Main", None, Some("# Hi"), &[], None)
+            .await
+            .unwrap();
+
+        let first = store.create_document_revision(&tab, "# Hi", "h1", &[], &user.id).await.unwrap();
+        store.set_document_tab_revision(&tab, &first).await.unwrap();
+        let second = store.create_document_revision(&tab, "# Hello", "h2", &[], &user.id).await.unwrap();
+
+        let revision = store.get_document_revision(&second).await.unwrap().unwrap();
+        assert_eq!(revision.revision_number, 2);
+        assert_eq!(revision.parent_revision_id.as_ref(), Some(&first));
+
+        // Both substring and stemmed word matches are found
+        assert_eq!(store.search_documents(&user.id, "notes", 10).await.unwrap().len(), 1);
+        assert_eq!(store.search_documents(&user.id, "run", 10).await.unwrap().len(), 1);
+        assert!(store.search_documents(&user.id, "walking", 10).await.unwrap().is_empty());
+
+        assert!(store.delete_document(&doc).await.unwrap());
+        assert!(store.get_document_tab(&tab).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_concurrent_revisions_get_distinct_numbers() {
+        let store = test_store().await;
+        let user = store.get_or_create_default_user().await.unwrap();
+        let doc = store
+            .create_document(&user.id, "Notes", DocumentSource::UserCreated, None)
+            .await
+            .unwrap();
+        let tab = store
+            .create_document_tab(&doc, None, 0, "Main", None, None, &[], None)
+            .await
+            .unwrap();
+
+        let hashes: Vec<String> = (0..8).map(|i| format!("h{}", i)).collect();
+        let revisions = futures::future::join_all(
+            hashes.iter().map(|hash| store.create_document_revision(&tab, "# Hi", hash, &[], &user.id)),
+        )
+        .await;
+        assert!(revisions.into_iter().all(|revision| revision.is_ok()));
+
+        let mut numbers: Vec<i32> = store
+            .list_document_revisions(&tab)
+            .await
+            .unwrap()
+            .iter()
+            .map(|revision| revision.revision_number)
+            .collect();
+        numbers.sort();
+        assert_eq!(numbers, (1..=8).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,239 @@
+//! Message embeddings for semantic search
+//!
+//! Vectors are stored as little-endian f32 `BYTEA`, the same encoding as
+//! the SQLite backend, and ranked by cosine similarity in Rust. This keeps
+//! the backend free of extensions such as pgvector.
+
+use anyhow::{Context, Result};
+use llm::EmbeddingModel;
+use sqlx::Row;
+
+use super::PostgresStore;
+use crate::storage::helper::unix_timestamp;
+use crate::storage::ids::{ConversationId, MessageId};
+use crate::storage::types::SearchHit;
+
+pub(crate) const SCHEMA: &str = r#"
+    -- One embedding per message, from the model that produced it
+    CREATE TABLE IF NOT EXISTS message_embeddings (
+        message_id TEXT PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+        model_id TEXT NOT NULL,
+        dimensions INTEGER NOT NULL,
+        vector BYTEA NOT NULL,
+        created_at BIGINT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_message_embeddings_dimensions
+        ON message_embeddings(dimensions);
+"#;
+
+impl PostgresStore {
+    /// Store the embedding of a message, replacing any previous one
+    pub async fn store_embedding(
+        &self,
+        message_id: &MessageId,
+        model_id: &str,
+        vector: &[f32],
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO message_embeddings (message_id, model_id, dimensions, vector, created_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (message_id) DO UPDATE SET
+                 model_id = EXCLUDED.model_id,
+                 dimensions = EXCLUDED.dimensions,
+                 vector = EXCLUDED.vector,
+                 created_at = EXCLUDED.created_at",
+        )
+        .bind(message_id)
+        .bind(model_id)
+        .bind(vector.len() as i32)
+        .bind(encode_vector(vector))
+        .bind(unix_timestamp())
+        .execute(self.pool())
+        .await
+        .context("Failed to store message embedding")?;
+        Ok(())
+    }
+
+    /// Embed every message that has no embedding from `model` yet
+    ///
+    /// Messages embedded by another model are re-embedded, so that all
+    /// stored vectors are comparable. Private content blocks are left out
+    /// of the embedded text. Returns the number of messages embedded.
+    pub async fn reindex_embeddings(
+        &self,
+        model: &(dyn EmbeddingModel + Send + Sync),
+    ) -> Result<usize> {
+        let pending = self.load_unembedded_messages(model.id()).await?;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let (message_ids, texts): (Vec<MessageId>, Vec<String>) = pending.into_iter().unzip();
+        let embeddings = model.embed(&texts).await?;
+        for (message_id, vector) in message_ids.iter().zip(&embeddings.vectors) {
+            self.store_embedding(message_id, model.id(), vector).await?;
+        }
+        Ok(message_ids.len())
+    }
+
+    /// Find the `top_k` messages whose embeddings are closest to `query_embedding`
+    ///
+    /// Only embeddings with the same dimensions as the query are compared,
+    /// and messages in incognito conversations are skipped. Hits are ordered
+    /// by descending cosine similarity.
+    pub async fn semantic_search(
+        &self,
+        query_embedding: &[f32],
+        top_k: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let query_norm = norm(query_embedding);
+        if query_norm == 0.0 || top_k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(
+            "SELECT e.message_id, e.vector,
+                    (SELECT cs.conversation_id FROM conversation_selections cs
+                     WHERE cs.span_id = m.span_id
+                     ORDER BY cs.conversation_id LIMIT 1)
+             FROM message_embeddings e
+             JOIN messages m ON m.id = e.message_id
+             WHERE e.dimensions = $1 AND NOT EXISTS (
+                 SELECT 1 FROM conversation_selections cs
+                 JOIN entities c ON c.id = cs.conversation_id
+                 WHERE cs.span_id = m.span_id AND c.incognito <> 0
+             )",
+        )
+        .bind(query_embedding.len() as i32)
+        .fetch_all(self.pool())
+        .await?;
+
+        let mut hits = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let vector = decode_vector(row.try_get(1)?);
+            let vector_norm = norm(&vector);
+            if vector_norm == 0.0 {
+                continue;
+            }
+            hits.push(SearchHit {
+                message_id: row.try_get(0)?,
+                conversation_id: row.try_get::<Option<ConversationId>, _>(2)?,
+                score: dot(query_embedding, &vector) / (query_norm * vector_norm),
+            });
+        }
+
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(top_k);
+        Ok(hits)
+    }
+
+    /// Load the text of each message lacking an embedding from `model_id`,
+    /// joining its non-private text blocks in order (incognito conversations
+    /// are never indexed)
+    async fn load_unembedded_messages(&self, model_id: &str) -> Result<Vec<(MessageId, String)>> {
+        let rows: Vec<(MessageId, String)> = sqlx::query_as(
+            "SELECT m.id, string_agg(cb.text, E'\\n' ORDER BY mc.sequence_number)
+             FROM messages m
+             JOIN message_content mc ON mc.message_id = m.id AND mc.content_type = 'text'
+             JOIN content_blocks cb ON cb.id = mc.content_block_id
+             LEFT JOIN message_embeddings e ON e.message_id = m.id
+             WHERE NOT cb.is_private AND (e.message_id IS NULL OR e.model_id <> $1)
+               AND NOT EXISTS (
+                   SELECT 1 FROM conversation_selections cs
+                   JOIN entities c ON c.id = cs.conversation_id
+                   WHERE cs.span_id = m.span_id AND c.incognito <> 0
+               )
+             GROUP BY m.id, m.created_at
+             ORDER BY m.created_at, m.id",
+        )
+        .bind(model_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .collect())
+    }
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    dot(vector, vector).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{add_text_message, test_store};
+    use super::*;
+    use crate::storage::traits::EntityStore;
+    use crate::storage::types::{ContentBlock, EntityType, Incognito};
+    use async_trait::async_trait;
+    use llm::Embeddings;
+
+    /// Embeds text by counting occurrences of "cat" and "dog"
+    struct KeywordEmbedder;
+
+    #[async_trait]
+    impl EmbeddingModel for KeywordEmbedder {
+        fn id(&self) -> &str {
+            "keywords"
+        }
+
+        async fn embed(&self, inputs: &[String]) -> anyhow::Result<Embeddings> {
+            let vectors = inputs
+                .iter()
+                .map(|text| {
+                    vec![
+                        text.matches("cat").count() as f32,
+                        text.matches("dog").count() as f32,
+                    ]
+                })
+                .collect();
+            Embeddings::from_vectors(inputs.len(), vectors)
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_reindex_and_search() {
+        let store = test_store().await;
+        let listed = store.create_entity(EntityType::conversation(), None).await.unwrap();
+        let unlisted = store.create_entity(EntityType::conversation(), None).await.unwrap();
+        let mut entity = store.get_entity(&unlisted).await.unwrap().unwrap();
+        entity.incognito = Incognito::Unlisted;
+        store.update_entity(&unlisted, &entity).await.unwrap();
+
+        let cats = add_text_message(&store, &listed, ContentBlock::plain("cat cat cat")).await;
+        let dogs = add_text_message(&store, &listed, ContentBlock::plain("dog dog")).await;
+        add_text_message(&store, &listed, ContentBlock::plain("secret cat").private()).await;
+        add_text_message(&store, &unlisted, ContentBlock::plain("cat cat")).await;
+
+        assert_eq!(store.reindex_embeddings(&KeywordEmbedder).await.unwrap(), 2);
+        // Already embedded messages are skipped
+        assert_eq!(store.reindex_embeddings(&KeywordEmbedder).await.unwrap(), 0);
+
+        let hits = store.semantic_search(&[1.0, 0.1], 5).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].message_id, cats);
+        assert_eq!(hits[0].conversation_id.as_ref(), Some(&listed));
+        assert_eq!(hits[1].message_id, dogs);
+
+        // Queries of a different dimension match nothing
+        assert!(store.semantic_search(&[1.0, 0.0, 0.0], 5).await.unwrap().is_empty());
+    }
+}
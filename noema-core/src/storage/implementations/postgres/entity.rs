@@ -0,0 +1,479 @@
+//! PostgreSQL implementation of EntityStore
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+use super::PostgresStore;
+use crate::storage::helper::unix_timestamp;
+use crate::storage::ids::{ConversationId, EntityId, UserId};
+use crate::storage::traits::normalize_tag;
+use crate::storage::traits::{EntityStore, StoredEntity};
+use crate::storage::types::entity::{ConversationListOptions, Entity, EntityRangeQuery, EntityRelation, EntityType, Incognito, RelationType};
+use crate::storage::types::stored_editable;
+
+/// Entity schema (entities, entity_relations and conversation_tags tables)
+pub(crate) const SCHEMA: &str = r#"
+    -- Entities: unified addressable layer
+    CREATE TABLE IF NOT EXISTS entities (
+        id TEXT PRIMARY KEY,
+        entity_type TEXT NOT NULL,
+        user_id TEXT REFERENCES users(id) ON DELETE CASCADE,
+        name TEXT,
+        slug TEXT UNIQUE,
+        is_private BOOLEAN NOT NULL DEFAULT TRUE,
+        is_archived BOOLEAN NOT NULL DEFAULT FALSE,
+        metadata TEXT,
+        incognito INTEGER NOT NULL DEFAULT 0,
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_entities_user ON entities(user_id);
+    CREATE INDEX IF NOT EXISTS idx_entities_type ON entities(entity_type, user_id);
+    CREATE INDEX IF NOT EXISTS idx_entities_slug ON entities(slug) WHERE slug IS NOT NULL;
+
+    -- Entity relations: relationships between entities
+    CREATE TABLE IF NOT EXISTS entity_relations (
+        from_id TEXT NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+        to_id TEXT NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+        relation TEXT NOT NULL,
+        metadata TEXT,
+        created_at BIGINT NOT NULL,
+        PRIMARY KEY (from_id, to_id, relation)
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_entity_relations_to ON entity_relations(to_id, relation);
+
+    -- Conversation tags
+    CREATE TABLE IF NOT EXISTS conversation_tags (
+        conversation_id TEXT NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        created_at BIGINT NOT NULL,
+        PRIMARY KEY (conversation_id, tag)
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_conversation_tags_tag ON conversation_tags(tag);
+"#;
+
+/// Columns read by `parse_entity`, in order
+const ENTITY_COLUMNS: &str =
+    "e.id, e.entity_type, e.user_id, e.name, e.slug, e.is_private, e.is_archived, e.metadata, e.created_at, e.updated_at, e.incognito";
+
+fn parse_entity(row: &PgRow) -> sqlx::Result<StoredEntity> {
+    let metadata: Option<String> = row.try_get(7)?;
+    let entity = Entity {
+        entity_type: EntityType::new(row.try_get::<String, _>(1)?),
+        user_id: row.try_get(2)?,
+        name: row.try_get(3)?,
+        slug: row.try_get(4)?,
+        is_private: row.try_get(5)?,
+        is_archived: row.try_get(6)?,
+        incognito: Incognito::from_i32(row.try_get(10)?),
+        metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
+    };
+    Ok(stored_editable(row.try_get(0)?, entity, row.try_get(8)?, row.try_get(9)?))
+}
+
+/// Parse a relation row: other entity id, relation, metadata, created_at
+fn parse_relation(row: &PgRow) -> sqlx::Result<(EntityId, EntityRelation)> {
+    let metadata: Option<String> = row.try_get(2)?;
+    let relation = EntityRelation {
+        relation: RelationType::new(row.try_get::<String, _>(1)?),
+        metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
+        created_at: row.try_get(3)?,
+    };
+    Ok((row.try_get(0)?, relation))
+}
+
+// ============================================================================
+// EntityStore Implementation
+// ============================================================================
+
+#[async_trait]
+impl EntityStore for PostgresStore {
+    async fn create_entity(
+        &self,
+        entity_type: EntityType,
+        user_id: Option<&UserId>,
+    ) -> Result<EntityId> {
+        let now = unix_timestamp();
+        let entity_id = EntityId::new();
+
+        sqlx::query(
+            "INSERT INTO entities (id, entity_type, user_id, is_private, is_archived, created_at, updated_at)
+             VALUES ($1, $2, $3, TRUE, FALSE, $4, $5)",
+        )
+        .bind(&entity_id)
+        .bind(entity_type.as_str())
+        .bind(user_id)
+        .bind(now)
+        .bind(now)
+        .execute(self.pool())
+        .await?;
+
+        Ok(entity_id)
+    }
+
+    async fn get_entity(&self, id: &EntityId) -> Result<Option<StoredEntity>> {
+        let row = sqlx::query(&format!("SELECT {} FROM entities e WHERE e.id = $1", ENTITY_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.pool())
+            .await?;
+        Ok(row.as_ref().map(parse_entity).transpose()?)
+    }
+
+    async fn get_entity_by_slug(&self, slug: &str) -> Result<Option<StoredEntity>> {
+        let row = sqlx::query(&format!("SELECT {} FROM entities e WHERE e.slug = $1", ENTITY_COLUMNS))
+            .bind(slug)
+            .fetch_optional(self.pool())
+            .await?;
+        Ok(row.as_ref().map(parse_entity).transpose()?)
+    }
+
+    async fn list_entities(
+        &self,
+        user_id: &UserId,
+        entity_type: Option<&EntityType>,
+    ) -> Result<Vec<StoredEntity>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM entities e
+             WHERE e.user_id = $1 AND ($2::TEXT IS NULL OR e.entity_type = $2) AND NOT e.is_archived
+             ORDER BY e.updated_at DESC",
+            ENTITY_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(entity_type.map(|et| et.as_str()))
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.iter().map(parse_entity).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn list_entities_in_range(
+        &self,
+        user_id: &UserId,
+        query: &EntityRangeQuery,
+    ) -> Result<Vec<StoredEntity>> {
+        let types: Option<Vec<String>> = query
+            .types_slice()
+            .filter(|types| !types.is_empty())
+            .map(|types| types.iter().map(|t| t.as_str().to_string()).collect());
+
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM entities e
+             WHERE e.user_id = $1
+               AND e.updated_at >= $2
+               AND e.updated_at <= $3
+               AND NOT e.is_archived
+               AND e.incognito = 0
+               AND ($4::TEXT[] IS NULL OR e.entity_type = ANY($4))
+             ORDER BY e.updated_at DESC
+             LIMIT $5",
+            ENTITY_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(query.start)
+        .bind(query.end)
+        .bind(types)
+        .bind(query.limit.map(i64::from))
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.iter().map(parse_entity).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn list_conversations(
+        &self,
+        user_id: &UserId,
+        options: &ConversationListOptions,
+    ) -> Result<Vec<StoredEntity>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM entities e
+             WHERE e.user_id = $1 AND e.entity_type = $2 AND ($3 OR NOT e.is_archived) AND ($4 OR e.incognito = 0)
+             ORDER BY e.updated_at DESC",
+            ENTITY_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(EntityType::conversation().as_str())
+        .bind(options.include_archived)
+        .bind(options.include_incognito)
+        .fetch_all(self.pool())
+        .await?;
+
+        let mut entities: Vec<StoredEntity> =
+            rows.iter().map(parse_entity).collect::<sqlx::Result<_>>()?;
+        // Stable sort keeps the recency order within each group
+        entities.sort_by_key(|e| !e.is_pinned());
+
+        Ok(entities)
+    }
+
+    async fn update_entity(&self, id: &EntityId, entity: &Entity) -> Result<()> {
+        sqlx::query(
+            "UPDATE entities SET name = $1, slug = $2, is_private = $3, is_archived = $4, metadata = $5, updated_at = $6, incognito = $7
+             WHERE id = $8",
+        )
+        .bind(&entity.name)
+        .bind(&entity.slug)
+        .bind(entity.is_private)
+        .bind(entity.is_archived)
+        .bind(entity.metadata.as_ref().map(|m| m.to_string()))
+        .bind(unix_timestamp())
+        .bind(entity.incognito.as_i32())
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn archive_entity(&self, id: &EntityId) -> Result<()> {
+        sqlx::query("UPDATE entities SET is_archived = TRUE, updated_at = $1 WHERE id = $2")
+            .bind(unix_timestamp())
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    async fn unarchive_entity(&self, id: &EntityId) -> Result<()> {
+        sqlx::query("UPDATE entities SET is_archived = FALSE, updated_at = $1 WHERE id = $2")
+            .bind(unix_timestamp())
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_entity(&self, id: &EntityId) -> Result<()> {
+        // Relations and tags go with the entity (ON DELETE CASCADE)
+        sqlx::query("DELETE FROM entities WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // Relations
+    // ========================================================================
+
+    async fn add_relation(
+        &self,
+        from_id: &EntityId,
+        to_id: &EntityId,
+        relation: RelationType,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO entity_relations (from_id, to_id, relation, metadata, created_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (from_id, to_id, relation)
+             DO UPDATE SET metadata = EXCLUDED.metadata, created_at = EXCLUDED.created_at",
+        )
+        .bind(from_id)
+        .bind(to_id)
+        .bind(relation.as_str())
+        .bind(metadata.map(|m| m.to_string()))
+        .bind(unix_timestamp())
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_relations_from(
+        &self,
+        id: &EntityId,
+        relation_type: Option<&RelationType>,
+    ) -> Result<Vec<(EntityId, EntityRelation)>> {
+        let rows = sqlx::query(
+            "SELECT to_id, relation, metadata, created_at
+             FROM entity_relations
+             WHERE from_id = $1 AND ($2::TEXT IS NULL OR relation = $2)
+             ORDER BY created_at DESC",
+        )
+        .bind(id)
+        .bind(relation_type.map(|rt| rt.as_str()))
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.iter().map(parse_relation).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn get_relations_to(
+        &self,
+        id: &EntityId,
+        relation_type: Option<&RelationType>,
+    ) -> Result<Vec<(EntityId, EntityRelation)>> {
+        let rows = sqlx::query(
+            "SELECT from_id, relation, metadata, created_at
+             FROM entity_relations
+             WHERE to_id = $1 AND ($2::TEXT IS NULL OR relation = $2)
+             ORDER BY created_at DESC",
+        )
+        .bind(id)
+        .bind(relation_type.map(|rt| rt.as_str()))
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.iter().map(parse_relation).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn remove_relation(
+        &self,
+        from_id: &EntityId,
+        to_id: &EntityId,
+        relation: &RelationType,
+    ) -> Result<()> {
+        sqlx::query("DELETE FROM entity_relations WHERE from_id = $1 AND to_id = $2 AND relation = $3")
+            .bind(from_id)
+            .bind(to_id)
+            .bind(relation.as_str())
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // Tags
+    // ========================================================================
+
+    async fn add_conversation_tag(&self, conversation_id: &ConversationId, tag: &str) -> Result<()> {
+        let tag = normalize_tag(tag)?;
+
+        sqlx::query(
+            "INSERT INTO conversation_tags (conversation_id, tag, created_at)
+             VALUES ($1, $2, $3)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(conversation_id)
+        .bind(tag)
+        .bind(unix_timestamp())
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_conversation_tag(&self, conversation_id: &ConversationId, tag: &str) -> Result<()> {
+        sqlx::query("DELETE FROM conversation_tags WHERE conversation_id = $1 AND tag = $2")
+            .bind(conversation_id)
+            .bind(tag.trim())
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    async fn get_conversation_tags(&self, conversation_id: &ConversationId) -> Result<Vec<String>> {
+        let tags = sqlx::query_scalar(
+            "SELECT tag FROM conversation_tags WHERE conversation_id = $1 ORDER BY tag",
+        )
+        .bind(conversation_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(tags)
+    }
+
+    async fn list_conversations_by_tag(
+        &self,
+        user_id: &UserId,
+        tag: &str,
+    ) -> Result<Vec<StoredEntity>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM entities e
+             JOIN conversation_tags t ON t.conversation_id = e.id
+             WHERE e.user_id = $1 AND e.entity_type = $2 AND t.tag = $3 AND NOT e.is_archived AND e.incognito = 0
+             ORDER BY e.updated_at DESC",
+            ENTITY_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(EntityType::conversation().as_str())
+        .bind(tag.trim())
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.iter().map(parse_entity).collect::<sqlx::Result<_>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::test_store;
+    use super::*;
+    use crate::storage::traits::UserStore;
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_entity_crud_and_listing() {
+        let store = test_store().await;
+        let user = store.get_or_create_default_user().await.unwrap();
+
+        let conv = store.create_entity(EntityType::conversation(), Some(&user.id)).await.unwrap();
+        let doc = store.create_entity(EntityType::document(), Some(&user.id)).await.unwrap();
+
+        let mut entity = store.get_entity(&conv).await.unwrap().unwrap();
+        assert!(entity.is_private);
+        entity.name = Some("Trip planning".to_string());
+        entity.slug = Some("trip".to_string());
+        entity.metadata = Some(serde_json::json!({"pinned": true}));
+        store.update_entity(&conv, &entity).await.unwrap();
+
+        let by_slug = store.get_entity_by_slug("trip").await.unwrap().unwrap();
+        assert_eq!(by_slug.id, conv);
+        assert_eq!(by_slug.name.as_deref(), Some("Trip planning"));
+
+        let conversations = store.list_entities(&user.id, Some(&EntityType::conversation())).await.unwrap();
+        assert_eq!(conversations.len(), 1);
+        assert_eq!(store.list_entities(&user.id, None).await.unwrap().len(), 2);
+
+        let query = EntityRangeQuery::new(0, i64::MAX).with_types(vec![EntityType::document()]);
+        let in_range = store.list_entities_in_range(&user.id, &query).await.unwrap();
+        assert_eq!(in_range.len(), 1);
+        assert_eq!(in_range[0].id, doc);
+
+        store.archive_entity(&conv).await.unwrap();
+        let options = ConversationListOptions::default();
+        assert!(store.list_conversations(&user.id, &options).await.unwrap().is_empty());
+        let archived = store.list_conversations(&user.id, &options.with_archived()).await.unwrap();
+        assert_eq!(archived.len(), 1);
+
+        store.add_relation(&doc, &conv, RelationType::new("mentions"), None).await.unwrap();
+        store
+            .add_relation(&doc, &conv, RelationType::new("mentions"), Some(serde_json::json!({"n": 2})))
+            .await
+            .unwrap();
+        let backlinks = store.get_relations_to(&conv, None).await.unwrap();
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].1.metadata, Some(serde_json::json!({"n": 2})));
+
+        store.delete_entity(&conv).await.unwrap();
+        assert!(store.get_entity(&conv).await.unwrap().is_none());
+        assert!(store.get_relations_from(&doc, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_conversation_tags() {
+        let store = test_store().await;
+        let user = store.get_or_create_default_user().await.unwrap();
+        let conv = store.create_entity(EntityType::conversation(), Some(&user.id)).await.unwrap();
+        let other = store.create_entity(EntityType::conversation(), Some(&user.id)).await.unwrap();
+
+        store.add_conversation_tag(&conv, " work ").await.unwrap();
+        store.add_conversation_tag(&conv, "work").await.unwrap();
+        store.add_conversation_tag(&conv, "rust").await.unwrap();
+        store.add_conversation_tag(&other, "rust").await.unwrap();
+        assert!(store.add_conversation_tag(&conv, "  ").await.is_err());
+
+        assert_eq!(store.get_conversation_tags(&conv).await.unwrap(), vec!["rust", "work"]);
+        assert_eq!(store.list_conversations_by_tag(&user.id, "rust").await.unwrap().len(), 2);
+
+        store.remove_conversation_tag(&conv, "rust ").await.unwrap();
+        let tagged = store.list_conversations_by_tag(&user.id, "rust").await.unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].id, other);
+    }
+}
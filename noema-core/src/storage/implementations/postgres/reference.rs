@@ -0,0 +1,204 @@
+//! PostgreSQL implementation of ReferenceStore
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+use super::PostgresStore;
+use crate::storage::helper::unix_timestamp;
+use crate::storage::ids::{EntityId, ReferenceId};
+use crate::storage::traits::{ReferenceStore, StoredReference};
+use crate::storage::types::{stored, Reference, RelationType};
+
+/// References schema
+pub(crate) const SCHEMA: &str = r#"
+    -- Cross-references between entities
+    CREATE TABLE IF NOT EXISTS entity_references (
+        id TEXT PRIMARY KEY,
+        from_entity_id TEXT NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+        to_entity_id TEXT NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+        relation_type TEXT,
+        context TEXT,
+        created_at BIGINT NOT NULL,
+        UNIQUE(from_entity_id, to_entity_id, relation_type)
+    );
+
+    -- Index for forward lookups (outgoing references from an entity)
+    CREATE INDEX IF NOT EXISTS idx_references_from ON entity_references(from_entity_id);
+
+    -- Index for backward lookups (backlinks to an entity)
+    CREATE INDEX IF NOT EXISTS idx_references_to ON entity_references(to_entity_id);
+
+    -- Index for finding references by type
+    CREATE INDEX IF NOT EXISTS idx_references_type ON entity_references(relation_type) WHERE relation_type IS NOT NULL;
+"#;
+
+const REFERENCE_COLUMNS: &str = "id, from_entity_id, to_entity_id, relation_type, context, created_at";
+
+/// Parse a reference from a database row
+fn parse_reference(row: &PgRow) -> sqlx::Result<StoredReference> {
+    let relation_type: Option<String> = row.try_get(3)?;
+    let reference = Reference {
+        from_entity_id: row.try_get(1)?,
+        to_entity_id: row.try_get(2)?,
+        relation_type: relation_type.map(RelationType::from),
+        context: row.try_get(4)?,
+    };
+    Ok(stored(row.try_get(0)?, reference, row.try_get(5)?))
+}
+
+impl PostgresStore {
+    /// References where `column` is `entity_id`, optionally of one type
+    async fn references_where(
+        &self,
+        column: &str,
+        entity_id: &EntityId,
+        relation_type: Option<&RelationType>,
+    ) -> Result<Vec<StoredReference>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM entity_references
+             WHERE {} = $1 AND ($2::TEXT IS NULL OR relation_type = $2)
+             ORDER BY created_at DESC",
+            REFERENCE_COLUMNS, column
+        ))
+        .bind(entity_id)
+        .bind(relation_type.map(|r| r.as_str()))
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.iter().map(parse_reference).collect::<sqlx::Result<_>>()?)
+    }
+}
+
+// ============================================================================
+// ReferenceStore Implementation
+// ============================================================================
+
+#[async_trait]
+impl ReferenceStore for PostgresStore {
+    async fn create_reference(
+        &self,
+        from_entity_id: &EntityId,
+        to_entity_id: &EntityId,
+        relation_type: Option<&RelationType>,
+        context: Option<&str>,
+    ) -> Result<ReferenceId> {
+        let id = ReferenceId::new();
+
+        sqlx::query(
+            "INSERT INTO entity_references (id, from_entity_id, to_entity_id, relation_type, context, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&id)
+        .bind(from_entity_id)
+        .bind(to_entity_id)
+        .bind(relation_type.map(|r| r.as_str()))
+        .bind(context)
+        .bind(unix_timestamp())
+        .execute(self.pool())
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn delete_reference(&self, id: &ReferenceId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM entity_references WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_references_between(
+        &self,
+        from_entity_id: &EntityId,
+        to_entity_id: &EntityId,
+    ) -> Result<usize> {
+        let result = sqlx::query(
+            "DELETE FROM entity_references WHERE from_entity_id = $1 AND to_entity_id = $2",
+        )
+        .bind(from_entity_id)
+        .bind(to_entity_id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn get_outgoing(&self, entity_id: &EntityId) -> Result<Vec<StoredReference>> {
+        self.references_where("from_entity_id", entity_id, None).await
+    }
+
+    async fn get_outgoing_by_type(
+        &self,
+        entity_id: &EntityId,
+        relation_type: &RelationType,
+    ) -> Result<Vec<StoredReference>> {
+        self.references_where("from_entity_id", entity_id, Some(relation_type)).await
+    }
+
+    async fn get_backlinks(&self, entity_id: &EntityId) -> Result<Vec<StoredReference>> {
+        self.references_where("to_entity_id", entity_id, None).await
+    }
+
+    async fn get_backlinks_by_type(
+        &self,
+        entity_id: &EntityId,
+        relation_type: &RelationType,
+    ) -> Result<Vec<StoredReference>> {
+        self.references_where("to_entity_id", entity_id, Some(relation_type)).await
+    }
+
+    async fn reference_exists(
+        &self,
+        from_entity_id: &EntityId,
+        to_entity_id: &EntityId,
+        relation_type: Option<&RelationType>,
+    ) -> Result<bool> {
+        let exists = sqlx::query_scalar(
+            "SELECT EXISTS(
+                 SELECT 1 FROM entity_references
+                 WHERE from_entity_id = $1 AND to_entity_id = $2 AND relation_type IS NOT DISTINCT FROM $3
+             )",
+        )
+        .bind(from_entity_id)
+        .bind(to_entity_id)
+        .bind(relation_type.map(|r| r.as_str()))
+        .fetch_one(self.pool())
+        .await?;
+        Ok(exists)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::test_store;
+    use super::*;
+    use crate::storage::traits::EntityStore;
+    use crate::storage::types::EntityType;
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_references_and_backlinks() {
+        let store = test_store().await;
+        let a = store.create_entity(EntityType::conversation(), None).await.unwrap();
+        let b = store.create_entity(EntityType::document(), None).await.unwrap();
+        let cites = RelationType::new("cites");
+
+        store.create_reference(&a, &b, Some(&cites), Some("see doc")).await.unwrap();
+        store.create_reference(&a, &b, None, None).await.unwrap();
+        assert!(store.create_reference(&a, &b, Some(&cites), None).await.is_err());
+
+        assert!(store.reference_exists(&a, &b, Some(&cites)).await.unwrap());
+        assert!(store.reference_exists(&a, &b, None).await.unwrap());
+        assert!(!store.reference_exists(&b, &a, None).await.unwrap());
+
+        assert_eq!(store.get_outgoing(&a).await.unwrap().len(), 2);
+        let typed = store.get_backlinks_by_type(&b, &cites).await.unwrap();
+        assert_eq!(typed.len(), 1);
+        assert_eq!(typed[0].context.as_deref(), Some("see doc"));
+
+        assert_eq!(store.delete_references_between(&a, &b).await.unwrap(), 2);
+        assert!(store.get_backlinks(&b).await.unwrap().is_empty());
+    }
+}
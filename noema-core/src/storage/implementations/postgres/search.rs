@@ -0,0 +1,95 @@
+//! Full-text message search
+//!
+//! Uses the generated `search_vector` column on `content_blocks`, so
+//! queries accept the `websearch_to_tsquery` syntax: quoted phrases,
+//! `or`, and `-excluded` words.
+
+use anyhow::Result;
+use sqlx::Row;
+
+use super::PostgresStore;
+use crate::storage::ids::UserId;
+use crate::storage::types::SearchHit;
+
+impl PostgresStore {
+    /// Find the `limit` messages in `user_id`'s conversations that best match `query`
+    ///
+    /// Only text on a conversation's selected path is searched. Private
+    /// content blocks and incognito conversations are skipped. Hits are
+    /// ordered by descending `ts_rank` of their best matching block.
+    pub async fn search_messages(
+        &self,
+        user_id: &UserId,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let rows = sqlx::query(
+            "SELECT m.id, MIN(cs.conversation_id), MAX(ts_rank(cb.search_vector, q))::REAL AS score
+             FROM websearch_to_tsquery('english', $2) q,
+                  content_blocks cb
+             JOIN message_content mc ON mc.content_block_id = cb.id AND mc.content_type = 'text'
+             JOIN messages m ON m.id = mc.message_id
+             JOIN conversation_selections cs ON cs.span_id = m.span_id
+             JOIN entities c ON c.id = cs.conversation_id
+             WHERE cb.search_vector @@ q AND NOT cb.is_private AND c.user_id = $1
+               AND NOT EXISTS (
+                   SELECT 1 FROM conversation_selections other
+                   JOIN entities oc ON oc.id = other.conversation_id
+                   WHERE other.span_id = m.span_id AND oc.incognito <> 0
+               )
+             GROUP BY m.id
+             ORDER BY score DESC, m.id
+             LIMIT $3",
+        )
+        .bind(user_id)
+        .bind(query)
+        .bind(limit as i64)
+        .fetch_all(self.pool())
+        .await?;
+
+        let hits = rows
+            .iter()
+            .map(|row| {
+                Ok(SearchHit {
+                    message_id: row.try_get(0)?,
+                    conversation_id: row.try_get(1)?,
+                    score: row.try_get(2)?,
+                })
+            })
+            .collect::<sqlx::Result<_>>()?;
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{add_text_message, test_store};
+    use crate::storage::traits::{EntityStore, UserStore};
+    use crate::storage::types::{ContentBlock, EntityType, Incognito};
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_search_messages() {
+        let store = test_store().await;
+        let user = store.get_or_create_default_user().await.unwrap();
+        let listed = store.create_entity(EntityType::conversation(), Some(&user.id)).await.unwrap();
+        let unlisted = store.create_entity(EntityType::conversation(), Some(&user.id)).await.unwrap();
+        let mut entity = store.get_entity(&unlisted).await.unwrap().unwrap();
+        entity.incognito = Incognito::Unlisted;
+        store.update_entity(&unlisted, &entity).await.unwrap();
+
+        let running = add_text_message(&store, &listed, ContentBlock::plain("I went running today")).await;
+        add_text_message(&store, &listed, ContentBlock::plain("Cooking pasta")).await;
+        add_text_message(&store, &listed, ContentBlock::plain("secret run").private()).await;
+        add_text_message(&store, &unlisted, ContentBlock::plain("run away")).await;
+
+        // Stemming matches "running" for "runs"
+        let hits = store.search_messages(&user.id, "runs", 10).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].message_id, running);
+        assert_eq!(hits[0].conversation_id.as_ref(), Some(&listed));
+        assert!(hits[0].score > 0.0);
+
+        assert!(store.search_messages(&user.id, "run -today", 10).await.unwrap().is_empty());
+    }
+}
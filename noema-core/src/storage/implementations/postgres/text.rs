@@ -0,0 +1,170 @@
+//! PostgreSQL implementation of TextStore
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+use super::PostgresStore;
+use crate::storage::helper::{content_hash, unix_timestamp};
+use crate::storage::ids::ContentBlockId;
+use crate::storage::traits::{StoredTextBlock, TextStore};
+use crate::storage::types::{stored, ContentBlock, ContentOrigin, ContentType, Hashed, OriginKind};
+
+/// The content_blocks schema
+///
+/// `search_vector` is generated from the text and backs full-text search
+/// (see `PostgresStore::search_messages`).
+pub(crate) const SCHEMA: &str = r#"
+    -- Content blocks (content-addressed text storage)
+    CREATE TABLE IF NOT EXISTS content_blocks (
+        id TEXT PRIMARY KEY,
+        content_hash TEXT NOT NULL,
+        content_type TEXT NOT NULL DEFAULT 'plain',
+        text TEXT NOT NULL,
+        is_private BOOLEAN NOT NULL DEFAULT FALSE,
+        origin_kind TEXT,
+        origin_user_id TEXT,
+        origin_model_id TEXT,
+        origin_source_id TEXT,
+        origin_parent_id TEXT REFERENCES content_blocks(id),
+        created_at BIGINT NOT NULL,
+        search_vector TSVECTOR GENERATED ALWAYS AS (to_tsvector('english', text)) STORED
+    );
+
+    -- Index for deduplication lookups
+    CREATE INDEX IF NOT EXISTS idx_content_blocks_hash
+        ON content_blocks(content_hash);
+
+    -- Index for origin queries
+    CREATE INDEX IF NOT EXISTS idx_content_blocks_origin
+        ON content_blocks(origin_kind, origin_user_id);
+
+    -- Index for privacy filtering
+    CREATE INDEX IF NOT EXISTS idx_content_blocks_private
+        ON content_blocks(is_private) WHERE is_private;
+
+    -- Index for temporal queries
+    CREATE INDEX IF NOT EXISTS idx_content_blocks_created
+        ON content_blocks(created_at);
+
+    -- Index for full-text search
+    CREATE INDEX IF NOT EXISTS idx_content_blocks_search
+        ON content_blocks USING GIN (search_vector);
+"#;
+
+#[async_trait]
+impl TextStore for PostgresStore {
+    async fn store(&self, content: ContentBlock) -> Result<ContentBlockId> {
+        let id = ContentBlockId::new();
+
+        sqlx::query(
+            "INSERT INTO content_blocks (id, content_hash, content_type, text, is_private, origin_kind, origin_user_id, origin_model_id, origin_source_id, origin_parent_id, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+        )
+        .bind(&id)
+        .bind(content_hash(&content.text))
+        .bind(content.content_type.as_str())
+        .bind(&content.text)
+        .bind(content.is_private)
+        .bind(content.origin.kind().as_str())
+        .bind(content.origin.user_id())
+        .bind(content.origin.model_id())
+        .bind(content.origin.source_id())
+        .bind(content.origin.parent_id())
+        .bind(unix_timestamp())
+        .execute(self.pool())
+        .await
+        .context("Failed to insert content block")?;
+
+        Ok(id)
+    }
+
+    async fn get(&self, id: &ContentBlockId) -> Result<Option<StoredTextBlock>> {
+        let row = sqlx::query(
+            "SELECT id, content_hash, content_type, text, is_private, origin_kind, origin_user_id, origin_model_id, origin_source_id, origin_parent_id, created_at
+             FROM content_blocks WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await
+        .context("Failed to get content block")?;
+
+        row.as_ref().map(parse_content_block).transpose()
+    }
+
+    async fn get_text(&self, id: &ContentBlockId) -> Result<Option<String>> {
+        let text = sqlx::query_scalar("SELECT text FROM content_blocks WHERE id = $1")
+            .bind(id)
+            .fetch_optional(self.pool())
+            .await
+            .context("Failed to get content block text")?;
+        Ok(text)
+    }
+
+    async fn exists(&self, id: &ContentBlockId) -> Result<bool> {
+        let exists = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM content_blocks WHERE id = $1)")
+            .bind(id)
+            .fetch_one(self.pool())
+            .await
+            .context("Failed to check content block existence")?;
+        Ok(exists)
+    }
+}
+
+fn parse_content_block(row: &PgRow) -> Result<StoredTextBlock> {
+    let content_type: String = row.try_get(2)?;
+    let content_type = ContentType::from_str(&content_type)
+        .ok_or_else(|| anyhow::anyhow!("Invalid content type: {}", content_type))?;
+
+    let origin_kind: Option<String> = row.try_get(5)?;
+    let origin_kind = origin_kind
+        .as_deref()
+        .map(|s| OriginKind::from_str(s).ok_or_else(|| anyhow::anyhow!("Invalid origin kind: {}", s)))
+        .transpose()?
+        .unwrap_or(OriginKind::System);
+
+    let origin = ContentOrigin::from_db(
+        origin_kind,
+        row.try_get(6)?,
+        row.try_get(7)?,
+        row.try_get(8)?,
+        row.try_get(9)?,
+    );
+
+    Ok(stored(
+        row.try_get(0)?,
+        Hashed::new(
+            row.try_get::<String, _>(1)?,
+            ContentBlock {
+                text: row.try_get(3)?,
+                content_type,
+                is_private: row.try_get(4)?,
+                origin,
+            },
+        ),
+        row.try_get(10)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::test_store;
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_store_and_get() {
+        let store = test_store().await;
+
+        let content = ContentBlock::plain("Hello, world!").with_origin(ContentOrigin::assistant("claude"));
+        let id = store.store(content).await.unwrap();
+
+        let block = store.get(&id).await.unwrap().unwrap();
+        assert_eq!(block.text(), "Hello, world!");
+        assert_eq!(block.origin().model_id(), Some("claude"));
+        assert_eq!(store.get_text(&id).await.unwrap().as_deref(), Some("Hello, world!"));
+        assert!(store.exists(&id).await.unwrap());
+        assert!(!store.exists(&ContentBlockId::new()).await.unwrap());
+    }
+}
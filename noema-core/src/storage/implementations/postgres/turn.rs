@@ -0,0 +1,677 @@
+//! PostgreSQL implementation of TurnStore
+
+use anyhow::Result;
+use async_trait::async_trait;
+use llm::Role;
+use sqlx::postgres::PgRow;
+use sqlx::{PgConnection, Row};
+
+use super::PostgresStore;
+use crate::storage::content::StoredContent;
+use crate::storage::helper::unix_timestamp;
+use crate::storage::ids::{ConversationId, MessageContentId, MessageId, SpanId, TurnId};
+use crate::storage::traits::{StoredMessage, StoredSpan, StoredTurn, TurnStore};
+use crate::storage::types::{stored, Message, MessageWithContent, Span, Turn, TurnWithContent};
+
+/// Turn-related schema (turns, spans, messages, conversation_selections)
+pub(crate) const SCHEMA: &str = r#"
+    -- Turns: structural nodes that can have multiple spans
+    -- Order is determined by conversation_selections.sequence_number
+    CREATE TABLE IF NOT EXISTS turns (
+        id TEXT PRIMARY KEY,
+        role TEXT CHECK(role IN ('user', 'assistant')) NOT NULL,
+        created_at BIGINT NOT NULL
+    );
+
+    -- Spans: alternative responses at a turn
+    CREATE TABLE IF NOT EXISTS spans (
+        id TEXT PRIMARY KEY,
+        turn_id TEXT NOT NULL REFERENCES turns(id) ON DELETE CASCADE,
+        model_id TEXT,
+        created_at BIGINT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_spans_turn ON spans(turn_id);
+
+    -- Messages: individual messages within a span
+    CREATE TABLE IF NOT EXISTS messages (
+        id TEXT PRIMARY KEY,
+        span_id TEXT NOT NULL REFERENCES spans(id) ON DELETE CASCADE,
+        sequence_number INTEGER NOT NULL,
+        role TEXT CHECK(role IN ('user', 'assistant', 'system', 'tool')) NOT NULL,
+        created_at BIGINT NOT NULL,
+        UNIQUE (span_id, sequence_number)
+    );
+
+    -- Message content: individual content items within a message
+    CREATE TABLE IF NOT EXISTS message_content (
+        id TEXT PRIMARY KEY,
+        message_id TEXT NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+        sequence_number INTEGER NOT NULL,
+        content_type TEXT CHECK(content_type IN ('text', 'asset_ref', 'document_ref', 'tool_call', 'tool_result')) NOT NULL,
+        -- For text: reference to content_blocks
+        content_block_id TEXT REFERENCES content_blocks(id),
+        -- For asset_ref: reference to blob storage
+        asset_id TEXT,
+        mime_type TEXT,
+        -- For document_ref: reference to document
+        document_id TEXT,
+        -- For tool_call/tool_result: structured JSON
+        tool_data TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_message_content_message ON message_content(message_id, sequence_number);
+    CREATE INDEX IF NOT EXISTS idx_message_content_block ON message_content(content_block_id);
+
+    -- Conversation selections: which span is selected at each turn for a conversation
+    -- Each conversation has its own linear sequence of turns.
+    -- sequence_number defines the order of turns within the conversation.
+    CREATE TABLE IF NOT EXISTS conversation_selections (
+        conversation_id TEXT NOT NULL,
+        turn_id TEXT NOT NULL REFERENCES turns(id) ON DELETE CASCADE,
+        span_id TEXT NOT NULL REFERENCES spans(id) ON DELETE CASCADE,
+        sequence_number INTEGER NOT NULL,
+        PRIMARY KEY (conversation_id, turn_id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_conv_selections_span ON conversation_selections(span_id);
+    CREATE INDEX IF NOT EXISTS idx_conv_selections_seq ON conversation_selections(conversation_id, sequence_number);
+"#;
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn parse_role(role: &str) -> Result<Role> {
+    role.parse::<Role>()
+        .map_err(|_| anyhow::anyhow!("Invalid role: {}", role))
+}
+
+/// Parse a span row: id, model_id, created_at, message_count
+fn parse_span(row: &PgRow) -> sqlx::Result<StoredSpan> {
+    let message_count: i64 = row.try_get(3)?;
+    let span = Span::new(row.try_get(1)?, message_count as i32);
+    Ok(stored(row.try_get(0)?, span, row.try_get(2)?))
+}
+
+/// Parse a message row: id, span_id, sequence_number, role, created_at
+fn parse_message(row: &PgRow) -> Result<StoredMessage> {
+    let role: String = row.try_get(3)?;
+    let message = Message::new(row.try_get(1)?, row.try_get(2)?, parse_role(&role)?);
+    Ok(stored(row.try_get(0)?, message, row.try_get(4)?))
+}
+
+/// Parse a message_content row starting at column `offset`:
+/// content_type, content_block_id, asset_id, mime_type, document_id, tool_data
+///
+/// Rows with missing fields or unreadable tool data are skipped, as in the
+/// SQLite backend.
+fn parse_content(row: &PgRow, offset: usize) -> sqlx::Result<Option<StoredContent>> {
+    let content_type: String = row.try_get(offset)?;
+    let tool_data: Option<String> = row.try_get(offset + 5)?;
+    let content = match content_type.as_str() {
+        "text" => row
+            .try_get::<Option<_>, _>(offset + 1)?
+            .map(|content_block_id| StoredContent::TextRef { content_block_id }),
+        "asset_ref" => row
+            .try_get::<Option<_>, _>(offset + 2)?
+            .zip(row.try_get::<Option<String>, _>(offset + 3)?)
+            .map(|(asset_id, mime_type)| StoredContent::AssetRef { asset_id, mime_type }),
+        "document_ref" => row
+            .try_get::<Option<_>, _>(offset + 4)?
+            .map(|document_id| StoredContent::DocumentRef { document_id }),
+        "tool_call" => tool_data
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .map(StoredContent::ToolCall),
+        "tool_result" => tool_data
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .map(StoredContent::ToolResult),
+        _ => None,
+    };
+    Ok(content)
+}
+
+async fn insert_message_content(
+    conn: &mut PgConnection,
+    message_id: &MessageId,
+    content: &[StoredContent],
+) -> Result<()> {
+    for (content_seq, stored_content) in content.iter().enumerate() {
+        let query = sqlx::query(
+            "INSERT INTO message_content (id, message_id, sequence_number, content_type, content_block_id, asset_id, mime_type, document_id, tool_data)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(MessageContentId::new())
+        .bind(message_id)
+        .bind(content_seq as i32);
+
+        let query = match stored_content {
+            StoredContent::TextRef { content_block_id } => query
+                .bind("text")
+                .bind(Some(content_block_id))
+                .bind(None::<&str>)
+                .bind(None::<&str>)
+                .bind(None::<&str>)
+                .bind(None::<String>),
+            StoredContent::AssetRef { asset_id, mime_type } => query
+                .bind("asset_ref")
+                .bind(None::<&str>)
+                .bind(Some(asset_id))
+                .bind(Some(mime_type))
+                .bind(None::<&str>)
+                .bind(None::<String>),
+            StoredContent::DocumentRef { document_id } => query
+                .bind("document_ref")
+                .bind(None::<&str>)
+                .bind(None::<&str>)
+                .bind(None::<&str>)
+                .bind(Some(document_id))
+                .bind(None::<String>),
+            StoredContent::ToolCall(call) => query
+                .bind("tool_call")
+                .bind(None::<&str>)
+                .bind(None::<&str>)
+                .bind(None::<&str>)
+                .bind(None::<&str>)
+                .bind(Some(serde_json::to_string(call)?)),
+            StoredContent::ToolResult(result) => query
+                .bind("tool_result")
+                .bind(None::<&str>)
+                .bind(None::<&str>)
+                .bind(None::<&str>)
+                .bind(None::<&str>)
+                .bind(Some(serde_json::to_string(result)?)),
+        };
+        query.execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+impl PostgresStore {
+    /// Sequence number of a turn in a conversation
+    async fn selection_sequence(
+        &self,
+        conversation_id: &ConversationId,
+        turn_id: &TurnId,
+    ) -> Result<i32> {
+        let seq = sqlx::query_scalar(
+            "SELECT sequence_number FROM conversation_selections WHERE conversation_id = $1 AND turn_id = $2",
+        )
+        .bind(conversation_id)
+        .bind(turn_id)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Turn {} is not in conversation {}", turn_id, conversation_id))?;
+        Ok(seq)
+    }
+
+    /// Load the turns selected in a conversation before sequence number `before`
+    async fn load_selected_turns(
+        &self,
+        conversation_id: &ConversationId,
+        before: Option<i32>,
+    ) -> Result<Vec<TurnWithContent>> {
+        let selections: Vec<(TurnId, SpanId)> = sqlx::query_as(
+            "SELECT turn_id, span_id FROM conversation_selections
+             WHERE conversation_id = $1 AND ($2::INTEGER IS NULL OR sequence_number < $2)
+             ORDER BY sequence_number",
+        )
+        .bind(conversation_id)
+        .bind(before)
+        .fetch_all(self.pool())
+        .await?;
+
+        let mut result = Vec::with_capacity(selections.len());
+        for (turn_id, span_id) in selections {
+            let turn = self
+                .get_turn(&turn_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Turn not found: {}", turn_id))?;
+            let span = self
+                .get_span(&span_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Span not found: {}", span_id))?;
+            let messages = self.get_messages(&span_id).await?;
+
+            result.push(TurnWithContent {
+                turn,
+                span,
+                messages,
+            });
+        }
+
+        Ok(result)
+    }
+}
+
+// ============================================================================
+// TurnStore Implementation
+// ============================================================================
+
+#[async_trait]
+impl TurnStore for PostgresStore {
+    // ========== Turn Management ==========
+
+    async fn create_turn(&self, role: Role) -> Result<StoredTurn> {
+        let turn_id = TurnId::new();
+        let now = unix_timestamp();
+
+        sqlx::query("INSERT INTO turns (id, role, created_at) VALUES ($1, $2, $3)")
+            .bind(&turn_id)
+            .bind(role.as_str())
+            .bind(now)
+            .execute(self.pool())
+            .await?;
+
+        Ok(stored(turn_id, Turn::new(role), now))
+    }
+
+    async fn get_turn(&self, turn_id: &TurnId) -> Result<Option<StoredTurn>> {
+        let row = sqlx::query("SELECT id, role, created_at FROM turns WHERE id = $1")
+            .bind(turn_id)
+            .fetch_optional(self.pool())
+            .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let role: String = row.try_get(1)?;
+        Ok(Some(stored(row.try_get(0)?, Turn::new(parse_role(&role)?), row.try_get(2)?)))
+    }
+
+    // ========== Span Management ==========
+
+    async fn create_span(&self, turn_id: &TurnId, model_id: Option<&str>) -> Result<StoredSpan> {
+        let id = SpanId::new();
+        let now = unix_timestamp();
+
+        sqlx::query("INSERT INTO spans (id, turn_id, model_id, created_at) VALUES ($1, $2, $3, $4)")
+            .bind(&id)
+            .bind(turn_id)
+            .bind(model_id)
+            .bind(now)
+            .execute(self.pool())
+            .await?;
+
+        let span = Span::new(model_id.map(|s| s.to_string()), 0);
+        Ok(stored(id, span, now))
+    }
+
+    async fn get_spans(&self, turn_id: &TurnId) -> Result<Vec<StoredSpan>> {
+        let rows = sqlx::query(
+            "SELECT s.id, s.model_id, s.created_at,
+                    (SELECT COUNT(*) FROM messages m WHERE m.span_id = s.id) AS message_count
+             FROM spans s WHERE s.turn_id = $1
+             ORDER BY s.created_at",
+        )
+        .bind(turn_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.iter().map(parse_span).collect::<sqlx::Result<_>>()?)
+    }
+
+    async fn get_span(&self, span_id: &SpanId) -> Result<Option<StoredSpan>> {
+        let row = sqlx::query(
+            "SELECT s.id, s.model_id, s.created_at,
+                    (SELECT COUNT(*) FROM messages m WHERE m.span_id = s.id) AS message_count
+             FROM spans s WHERE s.id = $1",
+        )
+        .bind(span_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row.as_ref().map(parse_span).transpose()?)
+    }
+
+    // ========== Message Management ==========
+
+    async fn add_message(
+        &self,
+        span_id: &SpanId,
+        role: Role,
+        content: &[StoredContent],
+    ) -> Result<StoredMessage> {
+        let message_id = MessageId::new();
+        let now = unix_timestamp();
+        let mut tx = self.pool().begin().await?;
+
+        // Lock the span so concurrent writers number its messages one after
+        // the other instead of reading the same MAX
+        sqlx::query("SELECT id FROM spans WHERE id = $1 FOR UPDATE")
+            .bind(span_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // Get next sequence number for message
+        let sequence_number: i32 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(sequence_number), -1) + 1 FROM messages WHERE span_id = $1",
+        )
+        .bind(span_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO messages (id, span_id, sequence_number, role, created_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&message_id)
+        .bind(span_id)
+        .bind(sequence_number)
+        .bind(role.as_str())
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        insert_message_content(&mut tx, &message_id, content).await?;
+        tx.commit().await?;
+
+        let message = Message::new(span_id.clone(), sequence_number, role);
+        Ok(stored(message_id, message, now))
+    }
+
+    async fn get_messages(&self, span_id: &SpanId) -> Result<Vec<MessageWithContent>> {
+        let rows = sqlx::query(
+            "SELECT id, span_id, sequence_number, role, created_at
+             FROM messages WHERE span_id = $1
+             ORDER BY sequence_number",
+        )
+        .bind(span_id)
+        .fetch_all(self.pool())
+        .await?;
+        let mut messages: Vec<MessageWithContent> = rows
+            .iter()
+            .map(|row| {
+                Ok(MessageWithContent {
+                    message: parse_message(row)?,
+                    content: Vec::new(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        // Content for the whole span in one query, in message order
+        let content_rows = sqlx::query(
+            "SELECT mc.message_id, mc.content_type, mc.content_block_id, mc.asset_id, mc.mime_type, mc.document_id, mc.tool_data
+             FROM message_content mc
+             JOIN messages m ON m.id = mc.message_id
+             WHERE m.span_id = $1
+             ORDER BY m.sequence_number, mc.sequence_number",
+        )
+        .bind(span_id)
+        .fetch_all(self.pool())
+        .await?;
+        for row in &content_rows {
+            let message_id: MessageId = row.try_get(0)?;
+            let Some(content) = parse_content(row, 1)? else {
+                continue;
+            };
+            if let Some(message) = messages.iter_mut().find(|m| m.message.id == message_id) {
+                message.content.push(content);
+            }
+        }
+
+        Ok(messages)
+    }
+
+    async fn get_message(&self, message_id: &MessageId) -> Result<Option<StoredMessage>> {
+        let row = sqlx::query(
+            "SELECT id, span_id, sequence_number, role, created_at
+             FROM messages WHERE id = $1",
+        )
+        .bind(message_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        row.as_ref().map(parse_message).transpose()
+    }
+
+    // ========== Selection Management ==========
+
+    async fn select_span(
+        &self,
+        conversation_id: &ConversationId,
+        turn_id: &TurnId,
+        span_id: &SpanId,
+    ) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+
+        // Conversations have no row of their own to lock, so serialize
+        // selections per conversation with an advisory lock instead
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('conversation_selections:' || $1))")
+            .bind(conversation_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // The sequence number is only used for new insertions
+        sqlx::query(
+            "INSERT INTO conversation_selections (conversation_id, turn_id, span_id, sequence_number)
+             VALUES ($1, $2, $3, (
+                 SELECT COALESCE(MAX(sequence_number), -1) + 1
+                 FROM conversation_selections WHERE conversation_id = $1
+             ))
+             ON CONFLICT (conversation_id, turn_id) DO UPDATE SET span_id = EXCLUDED.span_id",
+        )
+        .bind(conversation_id)
+        .bind(turn_id)
+        .bind(span_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_selected_span(
+        &self,
+        conversation_id: &ConversationId,
+        turn_id: &TurnId,
+    ) -> Result<Option<SpanId>> {
+        let span_id = sqlx::query_scalar(
+            "SELECT span_id FROM conversation_selections WHERE conversation_id = $1 AND turn_id = $2",
+        )
+        .bind(conversation_id)
+        .bind(turn_id)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(span_id)
+    }
+
+    async fn get_conversation_path(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Result<Vec<TurnWithContent>> {
+        self.load_selected_turns(conversation_id, None).await
+    }
+
+    async fn get_context_at(
+        &self,
+        conversation_id: &ConversationId,
+        up_to_turn_id: &TurnId,
+    ) -> Result<Vec<TurnWithContent>> {
+        let up_to_seq = self.selection_sequence(conversation_id, up_to_turn_id).await?;
+        self.load_selected_turns(conversation_id, Some(up_to_seq)).await
+    }
+
+    async fn copy_selections(
+        &self,
+        from_conversation_id: &ConversationId,
+        to_conversation_id: &ConversationId,
+        up_to_turn_id: &TurnId,
+        include_turn: bool,
+    ) -> Result<usize> {
+        let cutoff_seq = self.selection_sequence(from_conversation_id, up_to_turn_id).await?;
+
+        // If include_turn is true, include the turn, otherwise exclude it
+        let cutoff = if include_turn { cutoff_seq + 1 } else { cutoff_seq };
+
+        let copied = sqlx::query(
+            "INSERT INTO conversation_selections (conversation_id, turn_id, span_id, sequence_number)
+             SELECT $1, cs.turn_id, cs.span_id, cs.sequence_number
+             FROM conversation_selections cs
+             WHERE cs.conversation_id = $2
+               AND cs.sequence_number < $3",
+        )
+        .bind(to_conversation_id)
+        .bind(from_conversation_id)
+        .bind(cutoff)
+        .execute(self.pool())
+        .await?;
+
+        Ok(copied.rows_affected() as usize)
+    }
+
+    async fn truncate_selections(
+        &self,
+        conversation_id: &ConversationId,
+        after_turn_id: &TurnId,
+    ) -> Result<usize> {
+        let cutoff_seq = self.selection_sequence(conversation_id, after_turn_id).await?;
+
+        let removed = sqlx::query(
+            "DELETE FROM conversation_selections WHERE conversation_id = $1 AND sequence_number > $2",
+        )
+        .bind(conversation_id)
+        .bind(cutoff_seq)
+        .execute(self.pool())
+        .await?;
+
+        Ok(removed.rows_affected() as usize)
+    }
+
+    async fn get_turn_count(&self, conversation_id: &ConversationId) -> Result<usize> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM conversation_selections WHERE conversation_id = $1",
+        )
+        .bind(conversation_id)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(count as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::test_store;
+    use super::*;
+    use crate::storage::traits::TextStore;
+    use crate::storage::types::ContentBlock;
+
+    async fn text(store: &PostgresStore, text: &str) -> Vec<StoredContent> {
+        let id = store.store(ContentBlock::plain(text)).await.unwrap();
+        vec![StoredContent::text_ref(id)]
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_conversation_path() {
+        let store = test_store().await;
+        let conversation_id = ConversationId::new();
+
+        let turn1 = store.create_turn(Role::User).await.unwrap();
+        let span1 = store.create_span(&turn1.id, None).await.unwrap();
+        let content = text(&store, "Hello").await;
+        store.add_message(&span1.id, Role::User, &content).await.unwrap();
+        store.select_span(&conversation_id, &turn1.id, &span1.id).await.unwrap();
+
+        let turn2 = store.create_turn(Role::Assistant).await.unwrap();
+        let span2 = store.create_span(&turn2.id, Some("claude")).await.unwrap();
+        let call = llm::ToolCall {
+            id: "call-1".to_string(),
+            name: "calculator".to_string(),
+            arguments: serde_json::json!({"expression": "1 + 1"}),
+            extra: serde_json::Value::Null,
+        };
+        let mut content = text(&store, "Let me check").await;
+        content.push(StoredContent::ToolCall(call));
+        store.add_message(&span2.id, Role::Assistant, &content).await.unwrap();
+        store.add_message(&span2.id, Role::Assistant, &text(&store, "Two").await).await.unwrap();
+        store.select_span(&conversation_id, &turn2.id, &span2.id).await.unwrap();
+
+        let path = store.get_conversation_path(&conversation_id).await.unwrap();
+        assert_eq!(path.len(), 2);
+        assert_eq!(path[0].turn.role(), Role::User);
+        assert_eq!(path[1].span.message_count, 2);
+        assert_eq!(path[1].messages[0].content.len(), 2);
+        assert!(matches!(path[1].messages[0].content[1], StoredContent::ToolCall(_)));
+        assert_eq!(path[1].messages[1].message.sequence_number, 1);
+
+        let context = store.get_context_at(&conversation_id, &turn2.id).await.unwrap();
+        assert_eq!(context.len(), 1);
+        assert_eq!(store.get_turn_count(&conversation_id).await.unwrap(), 2);
+
+        // Re-selecting keeps the turn's position
+        let span3 = store.create_span(&turn1.id, None).await.unwrap();
+        store.select_span(&conversation_id, &turn1.id, &span3.id).await.unwrap();
+        let path = store.get_conversation_path(&conversation_id).await.unwrap();
+        assert_eq!(path[0].span.id, span3.id);
+        assert_eq!(store.get_spans(&turn1.id).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_copy_and_truncate_selections() {
+        let store = test_store().await;
+
+        let conv1 = ConversationId::new();
+        let mut turns = Vec::new();
+        for role in [Role::User, Role::Assistant, Role::User] {
+            let turn = store.create_turn(role).await.unwrap();
+            let span = store.create_span(&turn.id, None).await.unwrap();
+            store.select_span(&conv1, &turn.id, &span.id).await.unwrap();
+            turns.push(turn);
+        }
+
+        let conv2 = ConversationId::new();
+        let copied = store.copy_selections(&conv1, &conv2, &turns[1].id, true).await.unwrap();
+        assert_eq!(copied, 2);
+        let conv3 = ConversationId::new();
+        let copied = store.copy_selections(&conv1, &conv3, &turns[1].id, false).await.unwrap();
+        assert_eq!(copied, 1);
+
+        let removed = store.truncate_selections(&conv1, &turns[0].id).await.unwrap();
+        assert_eq!(removed, 2);
+        assert_eq!(store.get_turn_count(&conv1).await.unwrap(), 1);
+        assert_eq!(store.get_turn_count(&conv2).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_concurrent_writers_get_distinct_numbers() {
+        let store = test_store().await;
+        let conversation_id = ConversationId::new();
+        let turn = store.create_turn(Role::Assistant).await.unwrap();
+        let span = store.create_span(&turn.id, None).await.unwrap();
+        let content = text(&store, "Hi").await;
+
+        let messages = futures::future::join_all(
+            (0..8).map(|_| store.add_message(&span.id, Role::Assistant, &content)),
+        )
+        .await;
+        let mut numbers: Vec<i32> = messages
+            .into_iter()
+            .map(|message| message.unwrap().sequence_number)
+            .collect();
+        numbers.sort();
+        assert_eq!(numbers, (0..8).collect::<Vec<_>>());
+
+        let mut turns = Vec::new();
+        for _ in 0..8 {
+            let turn = store.create_turn(Role::User).await.unwrap();
+            let span = store.create_span(&turn.id, None).await.unwrap();
+            turns.push((turn.id, span.id));
+        }
+        let selected = futures::future::join_all(
+            turns
+                .iter()
+                .map(|(turn_id, span_id)| store.select_span(&conversation_id, turn_id, span_id)),
+        )
+        .await;
+        assert!(selected.into_iter().all(|result| result.is_ok()));
+        let sequences: std::collections::HashSet<i32> = sqlx::query_scalar(
+            "SELECT sequence_number FROM conversation_selections WHERE conversation_id = $1",
+        )
+        .bind(&conversation_id)
+        .fetch_all(store.pool())
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+        assert_eq!(sequences.len(), 8);
+    }
+}
@@ -0,0 +1,93 @@
+//! PostgreSQL implementation of UserStore
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+use super::PostgresStore;
+use crate::storage::helper::unix_timestamp;
+use crate::storage::ids::UserId;
+use crate::storage::traits::{StoredUser, UserStore};
+use crate::storage::types::{Keyed, User};
+
+/// Default user email for single-tenant local mode
+pub const DEFAULT_USER_EMAIL: &str = "human@noema";
+
+pub(crate) const SCHEMA: &str = r#"
+    -- Users
+    CREATE TABLE IF NOT EXISTS users (
+        id TEXT PRIMARY KEY,
+        email TEXT UNIQUE NOT NULL,
+        encrypted_anthropic_key TEXT,
+        encrypted_openai_key TEXT,
+        encrypted_gemini_key TEXT,
+        google_oauth_refresh_token TEXT,
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL
+    );
+"#;
+
+fn parse_user(row: &PgRow) -> sqlx::Result<StoredUser> {
+    Ok(Keyed::new(row.try_get::<UserId, _>(0)?, User::new(row.try_get::<String, _>(1)?)))
+}
+
+#[async_trait]
+impl UserStore for PostgresStore {
+    async fn get_or_create_default_user(&self) -> Result<StoredUser> {
+        self.get_or_create_user_by_email(DEFAULT_USER_EMAIL).await
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<StoredUser>> {
+        let row = sqlx::query("SELECT id, email FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_optional(self.pool())
+            .await?;
+        Ok(row.as_ref().map(parse_user).transpose()?)
+    }
+
+    async fn get_or_create_user_by_email(&self, email: &str) -> Result<StoredUser> {
+        let now = unix_timestamp();
+
+        // A concurrent insert of the same email leaves the existing row alone
+        sqlx::query(
+            "INSERT INTO users (id, email, created_at, updated_at) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (email) DO NOTHING",
+        )
+        .bind(UserId::new())
+        .bind(email)
+        .bind(now)
+        .bind(now)
+        .execute(self.pool())
+        .await?;
+
+        self.get_user_by_email(email)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("User not found after insert: {}", email))
+    }
+
+    async fn list_users(&self) -> Result<Vec<StoredUser>> {
+        let rows = sqlx::query("SELECT id, email FROM users ORDER BY created_at")
+            .fetch_all(self.pool())
+            .await?;
+        Ok(rows.iter().map(parse_user).collect::<sqlx::Result<_>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::test_store;
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "requires NOEMA_TEST_POSTGRES_URL"]
+    async fn test_default_user_is_created_once() {
+        let store = test_store().await;
+
+        let first = store.get_or_create_default_user().await.unwrap();
+        let second = store.get_or_create_default_user().await.unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(store.list_users().await.unwrap().len(), 1);
+        assert!(store.get_user_by_email("nobody@noema").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,326 @@
+//! Message embeddings for semantic search
+//!
+//! Vectors are stored as little-endian f32 blobs. SQLite has no vector
+//! operations, so `semantic_search` loads the vectors and ranks them by
+//! cosine similarity in Rust.
+
+use anyhow::{Context, Result};
+use llm::EmbeddingModel;
+use rusqlite::{params, Connection};
+
+use super::SqliteStore;
+use crate::storage::helper::unix_timestamp;
+use crate::storage::ids::{ConversationId, MessageId};
+use crate::storage::types::SearchHit;
+
+/// Initialize the message_embeddings schema
+pub(crate) fn init_schema(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        -- One embedding per message, from the model that produced it
+        CREATE TABLE IF NOT EXISTS message_embeddings (
+            message_id TEXT PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+            model_id TEXT NOT NULL,
+            dimensions INTEGER NOT NULL,
+            vector BLOB NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_message_embeddings_dimensions
+            ON message_embeddings(dimensions);
+        "#,
+    )
+    .context("Failed to initialize message_embeddings schema")?;
+    Ok(())
+}
+
+impl SqliteStore {
+    /// Store the embedding of a message, replacing any previous one
+    pub async fn store_embedding(
+        &self,
+        message_id: &MessageId,
+        model_id: &str,
+        vector: &[f32],
+    ) -> Result<()> {
+        let conn = self.conn().lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO message_embeddings (message_id, model_id, dimensions, vector, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                message_id,
+                model_id,
+                vector.len() as i64,
+                encode_vector(vector),
+                unix_timestamp()
+            ],
+        )
+        .context("Failed to store message embedding")?;
+        Ok(())
+    }
+
+    /// Embed every message that has no embedding from `model` yet
+    ///
+    /// Messages embedded by another model are re-embedded, so that all
+    /// stored vectors are comparable. Private content blocks are left out
+    /// of the embedded text. Returns the number of messages embedded.
+    pub async fn reindex_embeddings(
+        &self,
+        model: &(dyn EmbeddingModel + Send + Sync),
+    ) -> Result<usize> {
+        let pending = {
+            let conn = self.conn().lock().unwrap();
+            load_unembedded_messages(&conn, model.id())?
+        };
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let (message_ids, texts): (Vec<MessageId>, Vec<String>) = pending.into_iter().unzip();
+        let embeddings = model.embed(&texts).await?;
+        for (message_id, vector) in message_ids.iter().zip(&embeddings.vectors) {
+            self.store_embedding(message_id, model.id(), vector).await?;
+        }
+        Ok(message_ids.len())
+    }
+
+    /// Find the `top_k` messages whose embeddings are closest to `query_embedding`
+    ///
+    /// Only embeddings with the same dimensions as the query are compared,
+    /// and messages in incognito conversations are skipped. Hits are ordered
+    /// by descending cosine similarity.
+    pub async fn semantic_search(
+        &self,
+        query_embedding: &[f32],
+        top_k: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let query_norm = norm(query_embedding);
+        if query_norm == 0.0 || top_k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn().lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT e.message_id, e.vector,
+                    (SELECT cs.conversation_id FROM conversation_selections cs
+                     WHERE cs.span_id = m.span_id
+                     ORDER BY cs.conversation_id LIMIT 1)
+             FROM message_embeddings e
+             JOIN messages m ON m.id = e.message_id
+             WHERE e.dimensions = ?1 AND NOT EXISTS (
+                 SELECT 1 FROM conversation_selections cs
+                 JOIN entities c ON c.id = cs.conversation_id
+                 WHERE cs.span_id = m.span_id AND c.incognito != 0
+             )",
+        )?;
+
+        let mut hits: Vec<SearchHit> = stmt
+            .query_map(params![query_embedding.len() as i64], |row| {
+                let message_id: MessageId = row.get(0)?;
+                let vector: Vec<u8> = row.get(1)?;
+                let conversation_id: Option<ConversationId> = row.get(2)?;
+                Ok((message_id, vector, conversation_id))
+            })?
+            .filter_map(|r| r.ok())
+            .filter_map(|(message_id, vector, conversation_id)| {
+                let vector = decode_vector(&vector);
+                let vector_norm = norm(&vector);
+                if vector_norm == 0.0 {
+                    return None;
+                }
+                let score = dot(query_embedding, &vector) / (query_norm * vector_norm);
+                Some(SearchHit {
+                    message_id,
+                    conversation_id,
+                    score,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(top_k);
+        Ok(hits)
+    }
+}
+
+/// Load the text of each message lacking an embedding from `model_id`,
+/// joining its non-private text blocks in order (incognito conversations
+/// are never indexed)
+fn load_unembedded_messages(conn: &Connection, model_id: &str) -> Result<Vec<(MessageId, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT m.id, cb.text
+         FROM messages m
+         JOIN message_content mc ON mc.message_id = m.id AND mc.content_type = 'text'
+         JOIN content_blocks cb ON cb.id = mc.content_block_id
+         LEFT JOIN message_embeddings e ON e.message_id = m.id
+         WHERE cb.is_private = 0 AND (e.message_id IS NULL OR e.model_id != ?1)
+           AND NOT EXISTS (
+               SELECT 1 FROM conversation_selections cs
+               JOIN entities c ON c.id = cs.conversation_id
+               WHERE cs.span_id = m.span_id AND c.incognito != 0
+           )
+         ORDER BY m.created_at, m.id, mc.sequence_number",
+    )?;
+
+    let rows = stmt.query_map(params![model_id], |row| {
+        let message_id: MessageId = row.get(0)?;
+        let text: String = row.get(1)?;
+        Ok((message_id, text))
+    })?;
+
+    let mut messages: Vec<(MessageId, String)> = Vec::new();
+    for row in rows {
+        let (message_id, text) = row?;
+        match messages.last_mut() {
+            Some((last_id, last_text)) if *last_id == message_id => {
+                last_text.push('\n');
+                last_text.push_str(&text);
+            }
+            _ => messages.push((message_id, text)),
+        }
+    }
+    messages.retain(|(_, text)| !text.trim().is_empty());
+    Ok(messages)
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    dot(vector, vector).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::content::StoredContent;
+    use crate::storage::traits::{TextStore, TurnStore};
+    use crate::storage::types::ContentBlock;
+    use async_trait::async_trait;
+    use llm::{Embeddings, Role};
+
+    /// Embeds text by counting occurrences of "cat" and "dog"
+    struct KeywordEmbedder;
+
+    #[async_trait]
+    impl EmbeddingModel for KeywordEmbedder {
+        fn id(&self) -> &str {
+            "keywords"
+        }
+
+        async fn embed(&self, inputs: &[String]) -> anyhow::Result<Embeddings> {
+            let vectors = inputs
+                .iter()
+                .map(|text| {
+                    vec![
+                        text.matches("cat").count() as f32,
+                        text.matches("dog").count() as f32,
+                    ]
+                })
+                .collect();
+            Embeddings::from_vectors(inputs.len(), vectors)
+        }
+    }
+
+    async fn add_text_message(
+        store: &SqliteStore,
+        conversation_id: &ConversationId,
+        block: ContentBlock,
+    ) -> MessageId {
+        let block_id = store.store(block).await.unwrap();
+        let turn = store.create_turn(Role::User).await.unwrap();
+        let span = store.create_span(&turn.id, None).await.unwrap();
+        let message = store
+            .add_message(&span.id, Role::User, &[StoredContent::text_ref(block_id)])
+            .await
+            .unwrap();
+        store
+            .select_span(conversation_id, &turn.id, &span.id)
+            .await
+            .unwrap();
+        message.id
+    }
+
+    #[test]
+    fn test_vector_round_trip() {
+        let vector = vec![0.5, -1.25, 3.0];
+        assert_eq!(encode_vector(&vector).len(), 12);
+        assert_eq!(decode_vector(&encode_vector(&vector)), vector);
+    }
+
+    #[tokio::test]
+    async fn test_reindex_and_search() {
+        let store = SqliteStore::in_memory().unwrap();
+        let conversation_id = ConversationId::new();
+
+        let cats =
+            add_text_message(&store, &conversation_id, ContentBlock::plain("cat cat cat")).await;
+        let dogs = add_text_message(&store, &conversation_id, ContentBlock::plain("dog dog")).await;
+        add_text_message(
+            &store,
+            &conversation_id,
+            ContentBlock::plain("secret cat").private(),
+        )
+        .await;
+
+        assert_eq!(store.reindex_embeddings(&KeywordEmbedder).await.unwrap(), 2);
+        // Already embedded messages are skipped
+        assert_eq!(store.reindex_embeddings(&KeywordEmbedder).await.unwrap(), 0);
+
+        let hits = store.semantic_search(&[1.0, 0.1], 5).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].message_id, cats);
+        assert_eq!(hits[0].conversation_id.as_ref(), Some(&conversation_id));
+        assert_eq!(hits[1].message_id, dogs);
+        assert!(hits[0].score > hits[1].score);
+
+        let hits = store.semantic_search(&[0.0, 1.0], 1).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].message_id, dogs);
+
+        // Queries of a different dimension match nothing
+        assert!(store
+            .semantic_search(&[1.0, 0.0, 0.0], 5)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_incognito_conversations_are_not_searched() {
+        use crate::storage::traits::EntityStore;
+        use crate::storage::types::{EntityType, Incognito};
+
+        let store = SqliteStore::in_memory().unwrap();
+        let listed = store.create_entity(EntityType::conversation(), None).await.unwrap();
+        let unlisted = store.create_entity(EntityType::conversation(), None).await.unwrap();
+        let mut entity = store.get_entity(&unlisted).await.unwrap().unwrap();
+        entity.incognito = Incognito::Unlisted;
+        store.update_entity(&unlisted, &entity).await.unwrap();
+
+        let cats = add_text_message(&store, &listed, ContentBlock::plain("cat")).await;
+        add_text_message(&store, &unlisted, ContentBlock::plain("cat cat")).await;
+
+        // Only the listed conversation is indexed
+        assert_eq!(store.reindex_embeddings(&KeywordEmbedder).await.unwrap(), 1);
+        let hits = store.semantic_search(&[1.0, 0.0], 5).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].message_id, cats);
+
+        // Going incognito hides messages that were indexed earlier
+        let mut entity = store.get_entity(&listed).await.unwrap().unwrap();
+        entity.incognito = Incognito::Ephemeral;
+        store.update_entity(&listed, &entity).await.unwrap();
+        assert!(store.semantic_search(&[1.0, 0.0], 5).await.unwrap().is_empty());
+    }
+}
@@ -6,9 +6,10 @@ use rusqlite::{params, Connection};
 
 use super::SqliteStore;
 use crate::storage::helper::unix_timestamp;
-use crate::storage::ids::{EntityId, UserId};
+use crate::storage::ids::{ConversationId, EntityId, UserId};
+use crate::storage::traits::normalize_tag;
 use crate::storage::traits::{EntityStore, StoredEntity};
-use crate::storage::types::entity::{Entity, EntityRangeQuery, EntityRelation, EntityType, RelationType};
+use crate::storage::types::entity::{ConversationListOptions, Entity, EntityRangeQuery, EntityRelation, EntityType, Incognito, RelationType};
 use crate::storage::types::stored_editable;
 
 /// Initialize entity schema (entities and entity_relations tables)
@@ -25,6 +26,7 @@ pub(crate) fn init_schema(conn: &Connection) -> Result<()> {
             is_private INTEGER NOT NULL DEFAULT 1,
             is_archived INTEGER NOT NULL DEFAULT 0,
             metadata TEXT,
+            incognito INTEGER NOT NULL DEFAULT 0,
             created_at INTEGER NOT NULL,
             updated_at INTEGER NOT NULL
         );
@@ -44,8 +46,28 @@ pub(crate) fn init_schema(conn: &Connection) -> Result<()> {
         );
 
         CREATE INDEX IF NOT EXISTS idx_entity_relations_to ON entity_relations(to_id, relation);
+
+        -- Conversation tags
+        CREATE TABLE IF NOT EXISTS conversation_tags (
+            conversation_id TEXT NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+            tag TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (conversation_id, tag)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_conversation_tags_tag ON conversation_tags(tag);
         "#,
     )?;
+
+    // Databases created before incognito conversations lack the column
+    let has_incognito: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('entities') WHERE name = 'incognito'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_incognito {
+        conn.execute_batch("ALTER TABLE entities ADD COLUMN incognito INTEGER NOT NULL DEFAULT 0;")?;
+    }
     Ok(())
 }
 
@@ -82,7 +104,7 @@ impl EntityStore for SqliteStore {
     async fn get_entity(&self, id: &EntityId) -> Result<Option<StoredEntity>> {
         let conn = self.conn().lock().unwrap();
         let result = conn.query_row(
-            "SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at
+            "SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito
              FROM entities WHERE id = ?1",
             params![id.as_str()],
             |row| {
@@ -96,12 +118,13 @@ impl EntityStore for SqliteStore {
                 let metadata: Option<String> = row.get(7)?;
                 let created_at: i64 = row.get(8)?;
                 let updated_at: i64 = row.get(9)?;
-                Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at))
+                let incognito: i32 = row.get(10)?;
+                Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito))
             },
         );
 
         match result {
-            Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at)) => {
+            Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito)) => {
                 let entity = Entity {
                     entity_type: EntityType::new(entity_type),
                     user_id: user_id.map(UserId::from_string),
@@ -109,6 +132,7 @@ impl EntityStore for SqliteStore {
                     slug,
                     is_private: is_private != 0,
                     is_archived: is_archived != 0,
+                    incognito: Incognito::from_i32(incognito),
                     metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
                 };
                 Ok(Some(stored_editable(EntityId::from_string(id), entity, created_at, updated_at)))
@@ -121,7 +145,7 @@ impl EntityStore for SqliteStore {
     async fn get_entity_by_slug(&self, slug: &str) -> Result<Option<StoredEntity>> {
         let conn = self.conn().lock().unwrap();
         let result = conn.query_row(
-            "SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at
+            "SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito
              FROM entities WHERE slug = ?1",
             params![slug],
             |row| {
@@ -135,12 +159,13 @@ impl EntityStore for SqliteStore {
                 let metadata: Option<String> = row.get(7)?;
                 let created_at: i64 = row.get(8)?;
                 let updated_at: i64 = row.get(9)?;
-                Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at))
+                let incognito: i32 = row.get(10)?;
+                Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito))
             },
         );
 
         match result {
-            Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at)) => {
+            Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito)) => {
                 let entity = Entity {
                     entity_type: EntityType::new(entity_type),
                     user_id: user_id.map(UserId::from_string),
@@ -148,6 +173,7 @@ impl EntityStore for SqliteStore {
                     slug,
                     is_private: is_private != 0,
                     is_archived: is_archived != 0,
+                    incognito: Incognito::from_i32(incognito),
                     metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
                 };
                 Ok(Some(stored_editable(EntityId::from_string(id), entity, created_at, updated_at)))
@@ -169,7 +195,7 @@ impl EntityStore for SqliteStore {
         let entities: Vec<StoredEntity> = match &entity_type_str {
             Some(et_str) => {
                 let mut stmt = conn.prepare(
-                    "SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at
+                    "SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito
                      FROM entities
                      WHERE user_id = ?1 AND entity_type = ?2 AND is_archived = 0
                      ORDER BY updated_at DESC",
@@ -185,10 +211,11 @@ impl EntityStore for SqliteStore {
                     let metadata: Option<String> = row.get(7)?;
                     let created_at: i64 = row.get(8)?;
                     let updated_at: i64 = row.get(9)?;
-                    Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at))
+                    let incognito: i32 = row.get(10)?;
+                    Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito))
                 })?;
                 rows.filter_map(|r| r.ok())
-                    .map(|(id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at)| {
+                    .map(|(id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito)| {
                         let entity = Entity {
                             entity_type: EntityType::new(entity_type),
                             user_id: user_id.map(UserId::from_string),
@@ -196,6 +223,7 @@ impl EntityStore for SqliteStore {
                             slug,
                             is_private: is_private != 0,
                             is_archived: is_archived != 0,
+                            incognito: Incognito::from_i32(incognito),
                             metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
                         };
                         stored_editable(EntityId::from_string(id), entity, created_at, updated_at)
@@ -204,7 +232,7 @@ impl EntityStore for SqliteStore {
             }
             None => {
                 let mut stmt = conn.prepare(
-                    "SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at
+                    "SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito
                      FROM entities
                      WHERE user_id = ?1 AND is_archived = 0
                      ORDER BY updated_at DESC",
@@ -220,10 +248,11 @@ impl EntityStore for SqliteStore {
                     let metadata: Option<String> = row.get(7)?;
                     let created_at: i64 = row.get(8)?;
                     let updated_at: i64 = row.get(9)?;
-                    Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at))
+                    let incognito: i32 = row.get(10)?;
+                    Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito))
                 })?;
                 rows.filter_map(|r| r.ok())
-                    .map(|(id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at)| {
+                    .map(|(id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito)| {
                         let entity = Entity {
                             entity_type: EntityType::new(entity_type),
                             user_id: user_id.map(UserId::from_string),
@@ -231,6 +260,7 @@ impl EntityStore for SqliteStore {
                             slug,
                             is_private: is_private != 0,
                             is_archived: is_archived != 0,
+                            incognito: Incognito::from_i32(incognito),
                             metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
                         };
                         stored_editable(EntityId::from_string(id), entity, created_at, updated_at)
@@ -256,12 +286,13 @@ impl EntityStore for SqliteStore {
                 let type_list = placeholders.join(", ");
                 let sql = format!(
                     r#"
-                    SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at
+                    SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito
                     FROM entities
                     WHERE user_id = ?1
                       AND updated_at >= ?2
                       AND updated_at <= ?3
                       AND is_archived = 0
+                      AND incognito = 0
                       AND entity_type IN ({})
                     ORDER BY updated_at DESC
                     {}
@@ -275,12 +306,13 @@ impl EntityStore for SqliteStore {
             _ => {
                 let sql = format!(
                     r#"
-                    SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at
+                    SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito
                     FROM entities
                     WHERE user_id = ?1
                       AND updated_at >= ?2
                       AND updated_at <= ?3
                       AND is_archived = 0
+                      AND incognito = 0
                     ORDER BY updated_at DESC
                     {}
                     "#,
@@ -292,7 +324,7 @@ impl EntityStore for SqliteStore {
 
         let mut stmt = conn.prepare(&sql)?;
 
-        let rows: Vec<(String, String, Option<String>, Option<String>, Option<String>, i32, i32, Option<String>, i64, i64)> = match &type_filter {
+        let rows: Vec<(String, String, Option<String>, Option<String>, Option<String>, i32, i32, Option<String>, i64, i64, i32)> = match &type_filter {
             Some(types) => {
                 let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![
                     Box::new(user_id.as_str().to_string()),
@@ -309,6 +341,7 @@ impl EntityStore for SqliteStore {
                     Ok((
                         row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?,
                         row.get(5)?, row.get(6)?, row.get(7)?, row.get(8)?, row.get(9)?,
+                        row.get(10)?,
                     ))
                 })?
                 .filter_map(|r| r.ok())
@@ -319,6 +352,7 @@ impl EntityStore for SqliteStore {
                     Ok((
                         row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?,
                         row.get(5)?, row.get(6)?, row.get(7)?, row.get(8)?, row.get(9)?,
+                        row.get(10)?,
                     ))
                 })?
                 .filter_map(|r| r.ok())
@@ -327,7 +361,7 @@ impl EntityStore for SqliteStore {
 
         let entities = rows
             .into_iter()
-            .map(|(id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at)| {
+            .map(|(id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito)| {
                 let entity = Entity {
                     entity_type: EntityType::new(entity_type),
                     user_id: user_id.map(UserId::from_string),
@@ -335,6 +369,7 @@ impl EntityStore for SqliteStore {
                     slug,
                     is_private: is_private != 0,
                     is_archived: is_archived != 0,
+                    incognito: Incognito::from_i32(incognito),
                     metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
                 };
                 stored_editable(EntityId::from_string(id), entity, created_at, updated_at)
@@ -344,13 +379,70 @@ impl EntityStore for SqliteStore {
         Ok(entities)
     }
 
+    async fn list_conversations(
+        &self,
+        user_id: &UserId,
+        options: &ConversationListOptions,
+    ) -> Result<Vec<StoredEntity>> {
+        let conn = self.conn().lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito
+             FROM entities
+             WHERE user_id = ?1 AND entity_type = ?2 AND (?3 OR is_archived = 0) AND (?4 OR incognito = 0)
+             ORDER BY updated_at DESC",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                user_id.as_str(),
+                EntityType::conversation().as_str(),
+                options.include_archived,
+                options.include_incognito
+            ],
+            |row| {
+                let id: String = row.get(0)?;
+                let entity_type: String = row.get(1)?;
+                let user_id: Option<String> = row.get(2)?;
+                let name: Option<String> = row.get(3)?;
+                let slug: Option<String> = row.get(4)?;
+                let is_private: i32 = row.get(5)?;
+                let is_archived: i32 = row.get(6)?;
+                let metadata: Option<String> = row.get(7)?;
+                let created_at: i64 = row.get(8)?;
+                let updated_at: i64 = row.get(9)?;
+                let incognito: i32 = row.get(10)?;
+                Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito))
+            },
+        )?;
+        let mut entities: Vec<StoredEntity> = rows
+            .filter_map(|r| r.ok())
+            .map(|(id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito)| {
+                let entity = Entity {
+                    entity_type: EntityType::new(entity_type),
+                    user_id: user_id.map(UserId::from_string),
+                    name,
+                    slug,
+                    is_private: is_private != 0,
+                    is_archived: is_archived != 0,
+                    incognito: Incognito::from_i32(incognito),
+                    metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
+                };
+                stored_editable(EntityId::from_string(id), entity, created_at, updated_at)
+            })
+            .collect();
+        // Stable sort keeps the recency order within each group
+        entities.sort_by_key(|e| !e.is_pinned());
+
+        Ok(entities)
+    }
+
     async fn update_entity(&self, id: &EntityId, entity: &Entity) -> Result<()> {
         let conn = self.conn().lock().unwrap();
         let now = unix_timestamp();
         let metadata_json = entity.metadata.as_ref().map(|m| m.to_string());
 
         conn.execute(
-            "UPDATE entities SET name = ?1, slug = ?2, is_private = ?3, is_archived = ?4, metadata = ?5, updated_at = ?6
+            "UPDATE entities SET name = ?1, slug = ?2, is_private = ?3, is_archived = ?4, metadata = ?5, updated_at = ?6, incognito = ?8
              WHERE id = ?7",
             params![
                 entity.name,
@@ -359,7 +451,8 @@ impl EntityStore for SqliteStore {
                 entity.is_archived as i32,
                 metadata_json,
                 now,
-                id.as_str()
+                id.as_str(),
+                entity.incognito.as_i32()
             ],
         )?;
 
@@ -378,6 +471,18 @@ impl EntityStore for SqliteStore {
         Ok(())
     }
 
+    async fn unarchive_entity(&self, id: &EntityId) -> Result<()> {
+        let conn = self.conn().lock().unwrap();
+        let now = unix_timestamp();
+
+        conn.execute(
+            "UPDATE entities SET is_archived = 0, updated_at = ?1 WHERE id = ?2",
+            params![now, id.as_str()],
+        )?;
+
+        Ok(())
+    }
+
     async fn delete_entity(&self, id: &EntityId) -> Result<()> {
         let conn = self.conn().lock().unwrap();
 
@@ -386,6 +491,10 @@ impl EntityStore for SqliteStore {
             "DELETE FROM entity_relations WHERE from_id = ?1 OR to_id = ?1",
             params![id.as_str()],
         )?;
+        conn.execute(
+            "DELETE FROM conversation_tags WHERE conversation_id = ?1",
+            params![id.as_str()],
+        )?;
 
         // Delete entity
         conn.execute("DELETE FROM entities WHERE id = ?1", params![id.as_str()])?;
@@ -568,6 +677,98 @@ impl EntityStore for SqliteStore {
 
         Ok(())
     }
+
+    // ========================================================================
+    // Tags
+    // ========================================================================
+
+    async fn add_conversation_tag(&self, conversation_id: &ConversationId, tag: &str) -> Result<()> {
+        let tag = normalize_tag(tag)?;
+        let conn = self.conn().lock().unwrap();
+
+        conn.execute(
+            "INSERT OR IGNORE INTO conversation_tags (conversation_id, tag, created_at)
+             VALUES (?1, ?2, ?3)",
+            params![conversation_id.as_str(), tag, unix_timestamp()],
+        )?;
+
+        Ok(())
+    }
+
+    async fn remove_conversation_tag(&self, conversation_id: &ConversationId, tag: &str) -> Result<()> {
+        let conn = self.conn().lock().unwrap();
+
+        conn.execute(
+            "DELETE FROM conversation_tags WHERE conversation_id = ?1 AND tag = ?2",
+            params![conversation_id.as_str(), tag.trim()],
+        )?;
+
+        Ok(())
+    }
+
+    async fn get_conversation_tags(&self, conversation_id: &ConversationId) -> Result<Vec<String>> {
+        let conn = self.conn().lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT tag FROM conversation_tags WHERE conversation_id = ?1 ORDER BY tag",
+        )?;
+        let tags = stmt
+            .query_map(params![conversation_id.as_str()], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        Ok(tags)
+    }
+
+    async fn list_conversations_by_tag(
+        &self,
+        user_id: &UserId,
+        tag: &str,
+    ) -> Result<Vec<StoredEntity>> {
+        let conn = self.conn().lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT e.id, e.entity_type, e.user_id, e.name, e.slug, e.is_private, e.is_archived, e.metadata, e.created_at, e.updated_at, e.incognito
+             FROM entities e
+             JOIN conversation_tags t ON t.conversation_id = e.id
+             WHERE e.user_id = ?1 AND e.entity_type = ?2 AND t.tag = ?3 AND e.is_archived = 0 AND e.incognito = 0
+             ORDER BY e.updated_at DESC",
+        )?;
+        let rows = stmt.query_map(
+            params![user_id.as_str(), EntityType::conversation().as_str(), tag.trim()],
+            |row| {
+                let id: String = row.get(0)?;
+                let entity_type: String = row.get(1)?;
+                let user_id: Option<String> = row.get(2)?;
+                let name: Option<String> = row.get(3)?;
+                let slug: Option<String> = row.get(4)?;
+                let is_private: i32 = row.get(5)?;
+                let is_archived: i32 = row.get(6)?;
+                let metadata: Option<String> = row.get(7)?;
+                let created_at: i64 = row.get(8)?;
+                let updated_at: i64 = row.get(9)?;
+                let incognito: i32 = row.get(10)?;
+                Ok((id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito))
+            },
+        )?;
+        let entities = rows
+            .filter_map(|r| r.ok())
+            .map(|(id, entity_type, user_id, name, slug, is_private, is_archived, metadata, created_at, updated_at, incognito)| {
+                let entity = Entity {
+                    entity_type: EntityType::new(entity_type),
+                    user_id: user_id.map(UserId::from_string),
+                    name,
+                    slug,
+                    is_private: is_private != 0,
+                    is_archived: is_archived != 0,
+                    incognito: Incognito::from_i32(incognito),
+                    metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
+                };
+                stored_editable(EntityId::from_string(id), entity, created_at, updated_at)
+            })
+            .collect();
+
+        Ok(entities)
+    }
 }
 
 #[cfg(test)]
@@ -864,4 +1065,92 @@ mod tests {
         assert_eq!(to_relations.len(), 1);
         assert_eq!(to_relations[0].0, sub);
     }
+
+    #[tokio::test]
+    async fn test_conversation_tags() {
+        use crate::storage::traits::UserStore;
+
+        let store = SqliteStore::in_memory().unwrap();
+        let user_id = store.get_or_create_default_user().await.unwrap().id.clone();
+
+        let work = store.create_entity(EntityType::conversation(), Some(&user_id)).await.unwrap();
+        let other = store.create_entity(EntityType::conversation(), Some(&user_id)).await.unwrap();
+
+        store.add_conversation_tag(&work, " work ").await.unwrap();
+        store.add_conversation_tag(&work, "rust").await.unwrap();
+        store.add_conversation_tag(&work, "work").await.unwrap();
+        store.add_conversation_tag(&other, "rust").await.unwrap();
+        assert!(store.add_conversation_tag(&work, "  ").await.is_err());
+
+        assert_eq!(store.get_conversation_tags(&work).await.unwrap(), vec!["rust", "work"]);
+
+        let tagged = store.list_conversations_by_tag(&user_id, "work").await.unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].id, work);
+        assert_eq!(store.list_conversations_by_tag(&user_id, "rust").await.unwrap().len(), 2);
+
+        store.remove_conversation_tag(&work, "work").await.unwrap();
+        assert_eq!(store.get_conversation_tags(&work).await.unwrap(), vec!["rust"]);
+        assert!(store.list_conversations_by_tag(&user_id, "work").await.unwrap().is_empty());
+
+        // Deleting a conversation drops its tags
+        store.delete_entity(&other).await.unwrap();
+        assert_eq!(store.list_conversations_by_tag(&user_id, "rust").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_incognito_conversations_are_unlisted() {
+        use crate::storage::traits::UserStore;
+
+        let store = SqliteStore::in_memory().unwrap();
+        let user_id = store.get_or_create_user_by_email("test@example.com").await.unwrap().id.clone();
+
+        let listed = store.create_entity(EntityType::conversation(), Some(&user_id)).await.unwrap();
+        let hidden = store.create_entity(EntityType::conversation(), Some(&user_id)).await.unwrap();
+        store.add_conversation_tag(&hidden, "work").await.unwrap();
+
+        let mut entity = store.get_entity(&hidden).await.unwrap().unwrap();
+        assert_eq!(entity.incognito, Incognito::Off);
+        entity.incognito = Incognito::Unlisted;
+        store.update_entity(&hidden, &entity).await.unwrap();
+        assert_eq!(store.get_entity(&hidden).await.unwrap().unwrap().incognito, Incognito::Unlisted);
+
+        let ids = |entities: Vec<StoredEntity>| entities.into_iter().map(|e| e.id).collect::<Vec<_>>();
+        let options = ConversationListOptions::default();
+        assert_eq!(ids(store.list_conversations(&user_id, &options).await.unwrap()), vec![listed.clone()]);
+        assert_eq!(store.list_conversations(&user_id, &options.with_incognito()).await.unwrap().len(), 2);
+        assert!(store.list_conversations_by_tag(&user_id, "work").await.unwrap().is_empty());
+        let range = EntityRangeQuery::new(0, i64::MAX);
+        assert_eq!(ids(store.list_entities_in_range(&user_id, &range).await.unwrap()), vec![listed]);
+    }
+
+    #[test]
+    fn test_schema_adds_incognito_column() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE entities (
+                id TEXT PRIMARY KEY,
+                entity_type TEXT NOT NULL,
+                user_id TEXT,
+                name TEXT,
+                slug TEXT UNIQUE,
+                is_private INTEGER NOT NULL DEFAULT 1,
+                is_archived INTEGER NOT NULL DEFAULT 0,
+                metadata TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            INSERT INTO entities (id, entity_type, created_at, updated_at) VALUES ('old', 'conversation', 1, 1);",
+        )
+        .unwrap();
+
+        init_schema(&conn).unwrap();
+        // Running it again leaves the migrated table alone
+        init_schema(&conn).unwrap();
+
+        let incognito: i32 = conn
+            .query_row("SELECT incognito FROM entities WHERE id = 'old'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(incognito, 0);
+    }
 }
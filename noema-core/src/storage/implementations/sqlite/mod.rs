@@ -10,17 +10,28 @@
 //! - `turn` - TurnStore impl
 //! - `document` - DocumentStore impl
 //! - `entity` - EntityStore impl
+//! - `embedding` - Message embeddings and semantic search
 //! - `user` - UserStore impl
+//!
+//! With the `sqlcipher` feature, `open_encrypted` and `open_with_passphrase`
+//! open a database encrypted page by page with SQLCipher. Expect reads and
+//! writes to be roughly 5-15% slower than plain SQLite. A passphrase is
+//! stretched with PBKDF2 (256,000 iterations) on every open, adding a few
+//! hundred milliseconds at startup; a raw `StorageKey` skips that step.
 
 use anyhow::Result;
 use rusqlite::Connection;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "sqlcipher")]
+use crate::storage::encryption::StorageKey;
+
 // Submodules with trait implementations
 mod asset;
 mod collection;
 mod document;
+mod embedding;
 mod entity;
 mod reference;
 mod temporal;
@@ -32,6 +43,7 @@ mod user;
 pub(crate) use asset::init_schema as init_asset_schema;
 pub(crate) use collection::init_schema as init_collection_schema;
 pub(crate) use document::init_schema as init_document_schema;
+pub(crate) use embedding::init_schema as init_embedding_schema;
 pub(crate) use entity::init_schema as init_entity_schema;
 pub(crate) use reference::init_schema as init_reference_schema;
 pub(crate) use temporal::init_schema as init_temporal_schema;
@@ -66,6 +78,54 @@ impl SqliteStore {
         Ok(store)
     }
 
+    /// Open or create a SQLCipher-encrypted database with a raw 256-bit key
+    ///
+    /// Fails, rather than returning unreadable data, when the key is wrong
+    /// or the file is an unencrypted database.
+    #[cfg(feature = "sqlcipher")]
+    pub fn open_encrypted(path: impl AsRef<Path>, key: &StorageKey) -> Result<Self> {
+        Self::open_keyed(path.as_ref(), |conn| {
+            conn.execute_batch(&format!("PRAGMA key = \"x'{}'\";", key.to_hex()))
+        })
+    }
+
+    /// Open or create a SQLCipher-encrypted database with a key derived from
+    /// `passphrase`
+    #[cfg(feature = "sqlcipher")]
+    pub fn open_with_passphrase(path: impl AsRef<Path>, passphrase: &str) -> Result<Self> {
+        Self::open_keyed(path.as_ref(), |conn| conn.pragma_update(None, "key", passphrase))
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    fn open_keyed(
+        path: &Path,
+        apply_key: impl FnOnce(&Connection) -> rusqlite::Result<()>,
+    ) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        apply_key(&conn)?;
+        // SQLCipher only checks the key when the first page is read
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Wrong encryption key, or {} is not an encrypted database",
+                    path.display()
+                )
+            })?;
+        let store = Self {
+            conn: Arc::new(Mutex::new(conn)),
+        };
+        store.init_schema()?;
+        Ok(store)
+    }
+
+    /// Re-encrypt an encrypted database with a new key
+    #[cfg(feature = "sqlcipher")]
+    pub fn rekey(&self, key: &StorageKey) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch(&format!("PRAGMA rekey = \"x'{}'\";", key.to_hex()))?;
+        Ok(())
+    }
+
     /// Create an in-memory SQLite database (useful for testing)
     pub fn in_memory() -> Result<Self> {
         let conn = Connection::open_in_memory()?;
@@ -92,6 +152,7 @@ impl SqliteStore {
         init_reference_schema(&conn)?;
         init_collection_schema(&conn)?;
         init_temporal_schema(&conn)?;
+        init_embedding_schema(&conn)?;
         Ok(())
     }
 }
@@ -104,4 +165,37 @@ mod tests {
     fn test_sqlite_store_create() {
         let _store = SqliteStore::in_memory().unwrap();
     }
+
+    #[cfg(feature = "sqlcipher")]
+    #[tokio::test]
+    async fn test_encrypted_database_requires_its_key() {
+        use crate::storage::traits::UserStore;
+
+        let dir = std::env::temp_dir().join(format!("noema_cipher_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("noema.db");
+        let key = StorageKey::generate();
+
+        let user = {
+            let store = SqliteStore::open_encrypted(&path, &key).unwrap();
+            store.get_or_create_default_user().await.unwrap()
+        };
+
+        let err = SqliteStore::open_encrypted(&path, &StorageKey::generate()).err().unwrap();
+        assert!(err.to_string().contains("Wrong encryption key"));
+        assert!(SqliteStore::open(&path).is_err());
+        assert!(!std::fs::read(&path).unwrap().windows(5).any(|w| w == b"noema"));
+
+        let new_key = StorageKey::generate();
+        SqliteStore::open_encrypted(&path, &key).unwrap().rekey(&new_key).unwrap();
+        let store = SqliteStore::open_encrypted(&path, &new_key).unwrap();
+        assert_eq!(store.get_or_create_default_user().await.unwrap().id, user.id);
+
+        let passphrase_path = dir.join("passphrase.db");
+        SqliteStore::open_with_passphrase(&passphrase_path, "correct horse").unwrap();
+        assert!(SqliteStore::open_with_passphrase(&passphrase_path, "wrong horse").is_err());
+        assert!(SqliteStore::open_with_passphrase(&passphrase_path, "correct horse").is_ok());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -530,6 +530,27 @@ impl TurnStore for SqliteStore {
         Ok(copied)
     }
 
+    async fn truncate_selections(
+        &self,
+        conversation_id: &ConversationId,
+        after_turn_id: &TurnId,
+    ) -> Result<usize> {
+        let conn = self.conn().lock().unwrap();
+
+        let cutoff_seq: i32 = conn.query_row(
+            "SELECT sequence_number FROM conversation_selections WHERE conversation_id = ?1 AND turn_id = ?2",
+            params![conversation_id, after_turn_id],
+            |row| row.get(0),
+        )?;
+
+        let removed = conn.execute(
+            "DELETE FROM conversation_selections WHERE conversation_id = ?1 AND sequence_number > ?2",
+            params![conversation_id, cutoff_seq],
+        )?;
+
+        Ok(removed)
+    }
+
     async fn get_turn_count(&self, conversation_id: &ConversationId) -> Result<usize> {
         let conn = self.conn().lock().unwrap();
         let count: usize = conn.query_row(
@@ -752,4 +773,37 @@ mod tests {
         let copied = store.copy_selections(&conv1, &conv3, &turn2.id, false).await.unwrap();
         assert_eq!(copied, 1);
     }
+
+    #[tokio::test]
+    async fn test_truncate_selections() {
+        let store = create_test_store();
+
+        let conv1 = ConversationId::new();
+        let mut turns = Vec::new();
+        for role in [llm::Role::User, llm::Role::Assistant, llm::Role::User] {
+            let turn = store.create_turn(role).await.unwrap();
+            let span = store.create_span(&turn.id, None).await.unwrap();
+            store.select_span(&conv1, &turn.id, &span.id).await.unwrap();
+            turns.push(turn);
+        }
+        let conv2 = ConversationId::new();
+        store.copy_selections(&conv1, &conv2, &turns[2].id, true).await.unwrap();
+
+        // Dropping the turns after the first leaves only it
+        let removed = store.truncate_selections(&conv1, &turns[0].id).await.unwrap();
+        assert_eq!(removed, 2);
+        let path = store.get_conversation_path(&conv1).await.unwrap();
+        assert_eq!(path.len(), 1);
+        assert_eq!(path[0].turn.id, turns[0].id);
+
+        // Other conversations selecting the same turns are untouched
+        assert_eq!(store.get_turn_count(&conv2).await.unwrap(), 3);
+
+        // New turns are appended after the kept one
+        let turn = store.create_turn(llm::Role::Assistant).await.unwrap();
+        let span = store.create_span(&turn.id, None).await.unwrap();
+        store.select_span(&conv1, &turn.id, &span.id).await.unwrap();
+        let path = store.get_conversation_path(&conv1).await.unwrap();
+        assert_eq!(path[1].turn.id, turn.id);
+    }
 }
@@ -37,6 +37,9 @@ pub(crate) mod content;
 // Storage coordinator for asset externalization
 pub mod coordinator;
 
+// At-rest encryption keys
+pub mod encryption;
+
 // Trait definitions
 pub mod traits;
 
@@ -69,11 +72,13 @@ pub use types::{
     // ContentBlock
     ContentBlock, ContentOrigin, ContentType, OriginKind,
     // Conversation structure (turns, spans, messages)
-    Message, MessageWithContent, Span, Turn, TurnWithContent,
+    ContextSummary, Message, MessageWithContent, Span, Turn, TurnWithContent,
     // Document
     Document, DocumentRevision, DocumentSource, DocumentTab,
     // Entity
-    Entity, EntityRelation, EntityType, RelationType,
+    ConversationListOptions, Entity, EntityRelation, EntityType, Incognito, RelationType,
+    // Search
+    SearchHit,
     // Stored wrappers
     Editable, Hashed, Keyed, Stored, StoredEditable, Timestamped,
     // User
@@ -89,9 +94,15 @@ pub use content::InputContent;
 // Implementations (feature-gated)
 #[cfg(feature = "sqlite")]
 pub use implementations::sqlite::SqliteStore;
+#[cfg(feature = "postgres")]
+pub use implementations::postgres::{PostgresStorage, PostgresStore, PostgresStores};
 
 pub use implementations::fs::FsBlobStore;
 
+// Encryption
+pub use encryption::StorageKey;
+pub use implementations::encrypted::EncryptedBlobStore;
+
 // Memory implementations (for testing)
 pub use implementations::memory::{
     MemoryAssetStore, MemoryBlobStore, MemoryCollectionStore, MemoryDocumentStore,
@@ -17,7 +17,7 @@ use crate::storage::content::InputContent;
 use crate::storage::coordinator::StorageCoordinator;
 use crate::storage::ids::{ConversationId, SpanId, TurnId};
 use crate::storage::traits::StorageTypes;
-use crate::storage::types::OriginKind;
+use crate::storage::types::{ContextSummary, Incognito, OriginKind};
 
 use super::types::{ResolvedContent, ResolvedMessage};
 
@@ -60,6 +60,12 @@ pub struct Session<S: StorageTypes> {
     llm_cache_valid: bool,
     /// Pending messages (ChatMessage) not yet committed
     pending: Vec<ChatMessage>,
+    /// System prompt, always sent to the LLM as the first message
+    system_message: Option<String>,
+    /// Summary sent to the LLM in place of the turns it covers
+    context_summary: Option<ContextSummary>,
+    /// Whether (and how) the conversation is kept out of history
+    incognito: Incognito,
 }
 
 impl<S: StorageTypes> Session<S> {
@@ -72,6 +78,9 @@ impl<S: StorageTypes> Session<S> {
         conversation_id: ConversationId,
     ) -> Result<Self> {
         let resolved_cache = coordinator.open_session(&conversation_id).await?;
+        let system_message = coordinator.get_system_prompt(&conversation_id).await?;
+        let context_summary = coordinator.get_context_summary(&conversation_id).await?;
+        let incognito = coordinator.get_incognito(&conversation_id).await?;
 
         Ok(Self {
             coordinator,
@@ -80,6 +89,9 @@ impl<S: StorageTypes> Session<S> {
             llm_cache: Vec::new(),
             llm_cache_valid: false,
             pending: Vec::new(),
+            system_message,
+            context_summary,
+            incognito,
         })
     }
 
@@ -95,6 +107,9 @@ impl<S: StorageTypes> Session<S> {
             llm_cache: Vec::new(),
             llm_cache_valid: false,
             pending: Vec::new(),
+            system_message: None,
+            context_summary: None,
+            incognito: Incognito::Off,
         }
     }
 
@@ -102,6 +117,124 @@ impl<S: StorageTypes> Session<S> {
         &self.conversation_id
     }
 
+    /// Get the system prompt, if set
+    pub fn system_message(&self) -> Option<&str> {
+        self.system_message.as_deref()
+    }
+
+    /// Set or clear the system prompt and persist it.
+    ///
+    /// Replaces any previous system prompt, including system messages added
+    /// to the pending queue, so the LLM only ever sees one.
+    pub async fn set_system_message(&mut self, text: Option<String>) -> Result<()> {
+        let text = text.filter(|t| !t.trim().is_empty());
+        self.coordinator
+            .set_system_prompt(&self.conversation_id, text.as_deref())
+            .await?;
+        self.pending.retain(|msg| msg.role != Role::System);
+        self.system_message = text;
+        self.llm_cache_valid = false;
+        Ok(())
+    }
+
+    /// Get the conversation's incognito mode
+    pub fn incognito(&self) -> Incognito {
+        self.incognito
+    }
+
+    /// Set the incognito mode and persist it.
+    ///
+    /// In an ephemeral conversation `commit` keeps messages in this session
+    /// only; they are gone once it is dropped.
+    pub async fn set_incognito(&mut self, incognito: Incognito) -> Result<()> {
+        self.coordinator
+            .set_incognito(&self.conversation_id, incognito)
+            .await?;
+        self.incognito = incognito;
+        Ok(())
+    }
+
+    /// Get the summary standing in for the start of the history, if compacted
+    pub fn context_summary(&self) -> Option<&ContextSummary> {
+        self.context_summary.as_ref()
+    }
+
+    /// Set or clear the context summary and persist it.
+    ///
+    /// The summarized messages stay in storage and in `messages_for_display`;
+    /// only the LLM sees the summary in their place.
+    pub async fn set_context_summary(&mut self, summary: Option<ContextSummary>) -> Result<()> {
+        self.coordinator
+            .set_context_summary(&self.conversation_id, summary.as_ref())
+            .await?;
+        self.context_summary = summary;
+        self.llm_cache_valid = false;
+        Ok(())
+    }
+
+    /// Collect the committed messages to fold into a new context summary,
+    /// keeping the last `keep_turns` exchanges verbatim.
+    ///
+    /// An exchange starts at each user message that isn't returning tool
+    /// results. The current summary, if any, comes first so the new one
+    /// covers everything before the cut. Returns None when there is nothing
+    /// older than the kept exchanges to summarize.
+    pub fn messages_to_summarize(&self, keep_turns: usize) -> Option<(Vec<ChatMessage>, TurnId)> {
+        let start = self.summarized_len();
+        let unsummarized = &self.resolved_cache[start..];
+        let exchange_starts: Vec<usize> = unsummarized
+            .iter()
+            .enumerate()
+            .filter(|(_, msg)| {
+                msg.role == Role::User
+                    && !msg.content.iter().any(|c| matches!(c, ResolvedContent::ToolResult(_)))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        let cut = match keep_turns {
+            0 => unsummarized.len(),
+            n if exchange_starts.len() > n => exchange_starts[exchange_starts.len() - n],
+            _ => return None,
+        };
+        if cut == 0 {
+            return None;
+        }
+
+        let mut messages = Vec::with_capacity(cut + 1);
+        if start > 0 {
+            messages.extend(self.summary_message());
+        }
+        for msg in &unsummarized[..cut] {
+            let blocks = resolved_message_to_blocks(msg);
+            messages.push(ChatMessage::new(msg.role, ChatPayload::new(blocks)));
+        }
+        Some((messages, unsummarized[cut - 1].turn_id.clone()))
+    }
+
+    /// Number of leading committed messages covered by the context summary.
+    ///
+    /// A summary whose turn is no longer in the history (e.g. after
+    /// truncation) covers nothing.
+    fn summarized_len(&self) -> usize {
+        let Some(summary) = &self.context_summary else {
+            return 0;
+        };
+        self.resolved_cache
+            .iter()
+            .rposition(|msg| msg.turn_id == summary.through_turn_id)
+            .map_or(0, |pos| pos + 1)
+    }
+
+    /// The context summary as it is sent to the LLM
+    fn summary_message(&self) -> Option<ChatMessage> {
+        self.context_summary.as_ref().map(|summary| {
+            ChatMessage::system(ChatPayload::text(format!(
+                "Summary of the earlier conversation:\n{}",
+                summary.text
+            )))
+        })
+    }
+
     /// Get committed messages for display - returns cached ResolvedContent
     pub fn messages_for_display(&self) -> &[ResolvedMessage] {
         &self.resolved_cache
@@ -208,20 +341,34 @@ impl<S: StorageTypes> Session<S> {
 
     /// Commit pending messages to storage.
     ///
+    /// Returns the turn and span of the first committed message, if any.
+    /// Ephemeral conversations are committed to the cache only and have no span.
+    ///
     /// # Arguments
     /// * `model_id` - The model that generated assistant messages
     /// * `commit_mode` - How to commit: NewTurns (create turns) or AtTurn (add span at existing turn)
-    pub async fn commit(&mut self, model_id: Option<&str>, commit_mode: &CommitMode) -> Result<()> {
+    pub async fn commit(
+        &mut self,
+        model_id: Option<&str>,
+        commit_mode: &CommitMode,
+    ) -> Result<Option<(TurnId, Option<SpanId>)>> {
         if self.pending.is_empty() {
-            return Ok(());
+            return Ok(None);
         }
 
         let messages = std::mem::take(&mut self.pending);
 
+        if self.incognito == Incognito::Ephemeral {
+            let first_turn = self.commit_in_memory(messages, commit_mode).await?;
+            self.llm_cache_valid = false;
+            return Ok(first_turn.map(|turn_id| (turn_id, None)));
+        }
+
         // Track current turn and span for adding messages
         let mut current_turn: Option<TurnId> = None;
         let mut current_span: Option<SpanId> = None;
         let mut current_role: Option<Role> = None;
+        let mut first_turn: Option<(TurnId, Option<SpanId>)> = None;
 
         for msg in messages {
             let origin = OriginKind::from(msg.role);
@@ -254,6 +401,9 @@ impl<S: StorageTypes> Session<S> {
             };
             let turn_id = current_turn.as_ref().unwrap();
             let span_id = current_span.as_ref().unwrap();
+            if first_turn.is_none() {
+                first_turn = Some((turn_id.clone(), Some(span_id.clone())));
+            }
 
             let resolved = self.coordinator
                 .add_message(span_id, turn_id, msg.role, msg.payload.content, origin)
@@ -262,7 +412,37 @@ impl<S: StorageTypes> Session<S> {
         }
 
         self.llm_cache_valid = false;
-        Ok(())
+        Ok(first_turn)
+    }
+
+    /// Add committed messages of an ephemeral conversation to the cache
+    /// without storing them, grouping them into turns as `commit` would.
+    /// Returns the turn of the first message.
+    async fn commit_in_memory(
+        &mut self,
+        messages: Vec<ChatMessage>,
+        commit_mode: &CommitMode,
+    ) -> Result<Option<TurnId>> {
+        let mut first_turn = None;
+        let mut current: Option<(Role, TurnId)> = None;
+        for msg in messages {
+            let turn_id = match commit_mode {
+                CommitMode::AtTurn(tid) => tid.clone(),
+                CommitMode::NewTurns => match &current {
+                    Some((role, tid)) if *role == msg.role => tid.clone(),
+                    _ => TurnId::new(),
+                },
+            };
+            current = Some((msg.role, turn_id.clone()));
+            first_turn.get_or_insert_with(|| turn_id.clone());
+
+            let content = self
+                .coordinator
+                .resolve_unstored(msg.payload.content, OriginKind::from(msg.role))
+                .await?;
+            self.resolved_cache.push(ResolvedMessage::new(msg.role, content, turn_id));
+        }
+        Ok(first_turn)
     }
 }
 
@@ -274,15 +454,27 @@ impl<S: StorageTypes> Session<S> {
 impl<S: StorageTypes> ConversationContext for Session<S> {
     async fn messages(&mut self) -> Result<MessagesGuard<'_>> {
         // Rebuild llm_cache if invalid or pending messages changed
-        let needs_rebuild = !self.llm_cache_valid ||
-            self.llm_cache.len() != self.resolved_cache.len() + self.pending.len();
+        let summarized = self.summarized_len();
+        let system_len = usize::from(self.system_message.is_some()) + usize::from(summarized > 0);
+        let expected_len = system_len + self.resolved_cache.len() - summarized + self.pending.len();
+        let needs_rebuild = !self.llm_cache_valid || self.llm_cache.len() != expected_len;
 
         if needs_rebuild {
             self.llm_cache.clear();
-            self.llm_cache.reserve(self.resolved_cache.len() + self.pending.len());
+            self.llm_cache.reserve(expected_len);
+
+            // System prompt always comes first
+            if let Some(text) = &self.system_message {
+                self.llm_cache.push(ChatMessage::system(ChatPayload::text(text.clone())));
+            }
+
+            // The summary stands in for the messages it covers
+            if summarized > 0 {
+                self.llm_cache.extend(self.summary_message());
+            }
 
             // Add resolved (committed) messages
-            for msg in &self.resolved_cache {
+            for msg in &self.resolved_cache[summarized..] {
                 let blocks = resolved_message_to_blocks(msg);
                 let role = msg.role.into();
                 self.llm_cache.push(ChatMessage::new(role, ChatPayload::new(blocks)));
@@ -313,7 +505,8 @@ impl<S: StorageTypes> ConversationContext for Session<S> {
 
     async fn commit(&mut self) -> Result<()> {
         // Delegate to the concrete commit method with default mode
-        Session::commit(self, None, &CommitMode::default()).await
+        Session::commit(self, None, &CommitMode::default()).await?;
+        Ok(())
     }
 }
 
@@ -16,6 +16,7 @@ use crate::storage::implementations::memory::{
     MemoryStorage, MemoryTextStore, MemoryTurnStore,
 };
 use crate::storage::session::Session;
+use crate::storage::types::ContextSummary;
 
 /// Create test coordinator with memory stores
 fn make_test_coordinator() -> Arc<StorageCoordinator<MemoryStorage>> {
@@ -435,6 +436,29 @@ async fn test_session_preserves_message_content() {
     }
 }
 
+#[tokio::test]
+async fn test_session_keeps_message_times_on_reopen() {
+    let coordinator = make_test_coordinator();
+    let conversation_id = create_test_conversation(&coordinator).await;
+
+    let mut session = Session::<MemoryStorage>::new(
+        coordinator.clone(),
+        conversation_id.clone(),
+    );
+    session.add(ChatMessage::user(ChatPayload::text("Hi")));
+    session.commit(None, &CommitMode::NewTurns).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    session.add(ChatMessage::assistant(ChatPayload::text("Hello")));
+    session.commit(None, &CommitMode::NewTurns).await.unwrap();
+
+    let committed: Vec<i64> = session.messages_for_display().iter().map(|m| m.created_at).collect();
+    assert!(committed[0] > 0 && committed[0] < committed[1]);
+
+    let reopened = Session::<MemoryStorage>::open(coordinator, conversation_id).await.unwrap();
+    let stored: Vec<i64> = reopened.messages_for_display().iter().map(|m| m.created_at).collect();
+    assert_eq!(stored, committed);
+}
+
 #[tokio::test]
 async fn test_session_multi_content_message() {
     let coordinator = make_test_coordinator();
@@ -455,3 +479,219 @@ async fn test_session_multi_content_message() {
     let resolved = &session.messages_for_display()[0];
     assert_eq!(resolved.content.len(), 2);
 }
+
+// ============================================================================
+// System Prompt Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_session_system_message_is_first_and_persisted() {
+    let coordinator = make_test_coordinator();
+    let conversation_id = create_test_conversation(&coordinator).await;
+
+    {
+        let mut session = Session::<MemoryStorage>::new(
+            coordinator.clone(),
+            conversation_id.clone(),
+        );
+        session.add(ChatMessage::user(ChatPayload::new(vec![
+            ContentBlock::Text { text: "Hi".to_string() },
+        ])));
+        session.commit(None, &CommitMode::NewTurns).await.unwrap();
+
+        session.set_system_message(Some("Be terse.".to_string())).await.unwrap();
+        let messages = session.messages().await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, Role::System);
+        assert_eq!(messages[0].get_text(), "Be terse.");
+        assert_eq!(messages[1].role, Role::User);
+    }
+
+    // Reopening restores the prompt; it is not part of the turn history
+    let mut session = Session::<MemoryStorage>::open(
+        coordinator,
+        conversation_id,
+    ).await.unwrap();
+    assert_eq!(session.system_message(), Some("Be terse."));
+    assert_eq!(session.messages_for_display().len(), 1);
+    assert_eq!(session.messages().await.unwrap()[0].role, Role::System);
+}
+
+#[tokio::test]
+async fn test_session_set_system_message_replaces_existing() {
+    let coordinator = make_test_coordinator();
+    let conversation_id = create_test_conversation(&coordinator).await;
+
+    let mut session = Session::<MemoryStorage>::new(
+        coordinator,
+        conversation_id,
+    );
+
+    // A system message pushed the old way is replaced too
+    session.add(ChatMessage::system(ChatPayload::text("Old prompt")));
+    session.set_system_message(Some("First".to_string())).await.unwrap();
+    session.set_system_message(Some("Second".to_string())).await.unwrap();
+
+    let messages = session.messages().await.unwrap();
+    let system: Vec<_> = messages.iter().filter(|m| m.role == Role::System).collect();
+    assert_eq!(system.len(), 1);
+    assert_eq!(system[0].get_text(), "Second");
+
+    // Clearing removes it
+    session.set_system_message(None).await.unwrap();
+    assert_eq!(session.system_message(), None);
+    assert!(session.messages().await.unwrap().is_empty());
+}
+
+// ============================================================================
+// Context Summary Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_session_context_summary_replaces_old_turns() {
+    let coordinator = make_test_coordinator();
+    let conversation_id = create_test_conversation(&coordinator).await;
+
+    {
+        let mut session = Session::<MemoryStorage>::new(
+            coordinator.clone(),
+            conversation_id.clone(),
+        );
+        for i in 0..3 {
+            session.add(ChatMessage::user(ChatPayload::text(format!("Question {}", i))));
+            session.add(ChatMessage::assistant(ChatPayload::text(format!("Answer {}", i))));
+            session.commit(None, &CommitMode::NewTurns).await.unwrap();
+        }
+
+        // Nothing to summarize when every exchange is kept
+        assert!(session.messages_to_summarize(3).is_none());
+
+        let (messages, through_turn_id) = session.messages_to_summarize(1).unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[3].get_text(), "Answer 1");
+        session
+            .set_context_summary(Some(ContextSummary {
+                text: "Two questions were answered.".to_string(),
+                through_turn_id,
+            }))
+            .await
+            .unwrap();
+
+        let messages = session.messages().await.unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].role, Role::System);
+        assert!(messages[0].get_text().ends_with("Two questions were answered."));
+        assert_eq!(messages[1].get_text(), "Question 2");
+    }
+
+    // Reopening restores the summary; the summarized turns are still displayed
+    let mut session = Session::<MemoryStorage>::open(
+        coordinator,
+        conversation_id,
+    ).await.unwrap();
+    assert_eq!(session.messages_for_display().len(), 6);
+    assert_eq!(session.messages().await.unwrap().len(), 3);
+
+    // A further summary starts from the current one
+    let (messages, _) = session.messages_to_summarize(0).unwrap();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0].role, Role::System);
+}
+
+// ============================================================================
+// Fork Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_fork_at_message_keeps_messages_up_to_index() {
+    let coordinator = make_test_coordinator();
+    let conversation_id = create_test_conversation(&coordinator).await;
+
+    let mut session = Session::<MemoryStorage>::new(coordinator.clone(), conversation_id.clone());
+    for (role, text) in [
+        (Role::User, "one"),
+        (Role::Assistant, "two"),
+        (Role::User, "three"),
+        (Role::Assistant, "four"),
+    ] {
+        session.add(ChatMessage::new(role, ChatPayload::text(text)));
+    }
+    session.commit(Some("test-model"), &CommitMode::NewTurns).await.unwrap();
+
+    let fork_id = coordinator
+        .fork_conversation_at_message(&conversation_id, Some(1))
+        .await
+        .unwrap();
+    let forked = Session::<MemoryStorage>::open(coordinator.clone(), fork_id.clone()).await.unwrap();
+    let texts: Vec<&str> = forked
+        .messages_for_display()
+        .iter()
+        .flat_map(|m| &m.content)
+        .filter_map(|c| match c {
+            crate::storage::session::ResolvedContent::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(texts, vec!["one", "two"]);
+
+    // The original is untouched and lists the fork
+    let original = Session::<MemoryStorage>::open(coordinator.clone(), conversation_id.clone()).await.unwrap();
+    assert_eq!(original.messages_for_display().len(), 4);
+    let forks = coordinator.get_forked_conversations(&conversation_id).await.unwrap();
+    assert_eq!(forks.len(), 1);
+    assert_eq!(forks[0].0, fork_id);
+
+    // No index forks at the end; out-of-range indices are rejected
+    let full_id = coordinator.fork_conversation_at_message(&conversation_id, None).await.unwrap();
+    let full = Session::<MemoryStorage>::open(coordinator.clone(), full_id).await.unwrap();
+    assert_eq!(full.messages_for_display().len(), 4);
+    assert!(coordinator.fork_conversation_at_message(&conversation_id, Some(4)).await.is_err());
+}
+
+// ============================================================================
+// Edit Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_replace_user_message_drops_later_turns() {
+    use crate::storage::traits::TurnStore;
+
+    let turn_store = Arc::new(MemoryTurnStore::new());
+    let coordinator = Arc::new(StorageCoordinator::<MemoryStorage>::new(
+        Arc::new(MemoryBlobStore::new()),
+        Arc::new(MemoryAssetStore::new()),
+        Arc::new(MemoryTextStore::new()),
+        Arc::new(MemoryEntityStore::new()),
+        turn_store.clone(),
+    ));
+    let conversation_id = create_test_conversation(&coordinator).await;
+
+    let mut session = Session::<MemoryStorage>::new(coordinator.clone(), conversation_id.clone());
+    for (role, text) in [
+        (Role::User, "one"),
+        (Role::Assistant, "two"),
+        (Role::User, "thre"),
+        (Role::Assistant, "four"),
+    ] {
+        session.add(ChatMessage::new(role, ChatPayload::text(text)));
+    }
+    session.commit(Some("test-model"), &CommitMode::NewTurns).await.unwrap();
+    let turn_id = session.messages_for_display()[2].turn_id.clone();
+
+    coordinator
+        .replace_user_message(&conversation_id, &turn_id, vec![ContentBlock::Text { text: "three".to_string() }])
+        .await
+        .unwrap();
+
+    let reopened = Session::<MemoryStorage>::open(coordinator.clone(), conversation_id).await.unwrap();
+    let messages = reopened.messages_for_display();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[2].turn_id, turn_id);
+    assert!(matches!(
+        &messages[2].content[..],
+        [crate::storage::session::ResolvedContent::Text { text }] if text == "three"
+    ));
+
+    // The original text is kept as an alternate at the same turn
+    assert_eq!(turn_store.get_spans(&turn_id).await.unwrap().len(), 2);
+}
@@ -6,6 +6,7 @@
 
 use llm::{ContentBlock, Role, ToolCall, ToolResult};
 
+use crate::storage::helper::unix_timestamp;
 use crate::storage::ids::{AssetId, DocumentId, TurnId};
 use crate::storage::types::{BlobHash};
 
@@ -20,11 +21,20 @@ pub struct ResolvedMessage {
     pub content: Vec<ResolvedContent>,
     /// Turn this message belongs to (for truncation)
     pub turn_id: TurnId,
+    /// When the message was stored (unix millis)
+    pub created_at: i64,
 }
 
 impl ResolvedMessage {
+    /// A message created now
     pub fn new(role: Role, content: Vec<ResolvedContent>, turn_id: TurnId) -> Self {
-        Self { role, content, turn_id }
+        Self { role, content, turn_id, created_at: unix_timestamp() }
+    }
+
+    /// Set when the message was stored
+    pub fn with_created_at(mut self, created_at: i64) -> Self {
+        self.created_at = created_at;
+        self
     }
 }
 
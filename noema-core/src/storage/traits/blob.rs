@@ -1,13 +1,18 @@
 //! BlobStore trait for content-addressable binary storage
 
+use std::collections::HashSet;
+
 use anyhow::Result;
 use async_trait::async_trait;
-use crate::storage::types::BlobHash;
+use crate::storage::types::{BlobHash, GcStats};
 
 /// Content-addressable blob storage trait
 #[async_trait]
 pub trait BlobStore: Send + Sync {
     /// Store binary data and return its SHA-256 hash
+    ///
+    /// Storing is idempotent: if a blob with the same hash already exists it
+    /// is not written again and the existing hash is returned.
     async fn store(&self, data: &[u8]) -> Result<BlobHash>;
 
     /// Retrieve blob data by hash
@@ -20,4 +25,10 @@ pub trait BlobStore: Send + Sync {
     ///
     /// Returns Ok(true) if deleted, Ok(false) if didn't exist
     async fn delete(&self, hash: &BlobHash) -> Result<bool>;
+
+    /// Delete every blob whose hash is not in `referenced`
+    ///
+    /// Implementations backed by persistent storage skip recently written
+    /// blobs, so a blob stored concurrently but not yet referenced survives.
+    async fn gc(&self, referenced: &HashSet<BlobHash>) -> Result<GcStats>;
 }
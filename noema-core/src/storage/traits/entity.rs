@@ -6,8 +6,8 @@
 use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::storage::ids::{EntityId, UserId};
-use crate::storage::types::entity::{Entity, EntityRangeQuery, EntityRelation, EntityType, RelationType};
+use crate::storage::ids::{ConversationId, EntityId, UserId};
+use crate::storage::types::entity::{ConversationListOptions, Entity, EntityRangeQuery, EntityRelation, EntityType, RelationType};
 use crate::storage::types::StoredEditable;
 
 /// Stored representation of an Entity (mutable - can be renamed, archived, etc.)
@@ -41,6 +41,8 @@ pub trait EntityStore: Send + Sync {
     async fn get_entity_by_slug(&self, slug: &str) -> Result<Option<StoredEntity>>;
 
     /// List entities for a user, optionally filtered by type
+    ///
+    /// Excludes archived entities but keeps incognito conversations.
     async fn list_entities(
         &self,
         user_id: &UserId,
@@ -50,21 +52,35 @@ pub trait EntityStore: Send + Sync {
     /// List entities updated within a time range
     ///
     /// Returns entities ordered by `updated_at` descending (most recent first).
-    /// Excludes archived entities.
+    /// Excludes archived entities and incognito conversations.
     async fn list_entities_in_range(
         &self,
         user_id: &UserId,
         query: &EntityRangeQuery,
     ) -> Result<Vec<StoredEntity>>;
 
+    /// List a user's conversations
+    ///
+    /// Returns pinned conversations first, each group ordered by `updated_at`
+    /// descending. Archived and incognito conversations are only included if
+    /// requested.
+    async fn list_conversations(
+        &self,
+        user_id: &UserId,
+        options: &ConversationListOptions,
+    ) -> Result<Vec<StoredEntity>>;
+
     /// Update an entity's mutable fields
     ///
-    /// Updates name, slug, is_private, is_archived. Entity type cannot be changed.
+    /// Updates name, slug, is_private, is_archived, incognito. Entity type cannot be changed.
     async fn update_entity(&self, id: &EntityId, entity: &Entity) -> Result<()>;
 
     /// Archive an entity (soft delete - hidden from default views)
     async fn archive_entity(&self, id: &EntityId) -> Result<()>;
 
+    /// Restore an archived entity to default views
+    async fn unarchive_entity(&self, id: &EntityId) -> Result<()>;
+
     /// Delete an entity permanently
     ///
     /// Also removes all relations involving this entity.
@@ -112,4 +128,36 @@ pub trait EntityStore: Send + Sync {
         to_id: &EntityId,
         relation: &RelationType,
     ) -> Result<()>;
+
+    // ========================================================================
+    // Tags
+    // ========================================================================
+
+    /// Tag a conversation
+    ///
+    /// Surrounding whitespace is trimmed. Adding a tag twice is a no-op.
+    async fn add_conversation_tag(&self, conversation_id: &ConversationId, tag: &str) -> Result<()>;
+
+    /// Remove a tag from a conversation (no-op if not tagged)
+    async fn remove_conversation_tag(&self, conversation_id: &ConversationId, tag: &str) -> Result<()>;
+
+    /// Get a conversation's tags, sorted alphabetically
+    async fn get_conversation_tags(&self, conversation_id: &ConversationId) -> Result<Vec<String>>;
+
+    /// List a user's conversations with the given tag
+    ///
+    /// Returns conversations ordered by `updated_at` descending.
+    /// Excludes archived and incognito conversations.
+    async fn list_conversations_by_tag(
+        &self,
+        user_id: &UserId,
+        tag: &str,
+    ) -> Result<Vec<StoredEntity>>;
+}
+
+/// Trim a tag, rejecting empty ones
+pub(crate) fn normalize_tag(tag: &str) -> Result<&str> {
+    let tag = tag.trim();
+    anyhow::ensure!(!tag.is_empty(), "Tag cannot be empty");
+    Ok(tag)
 }
@@ -17,6 +17,7 @@ pub use asset::{AssetStore, StoredAsset};
 pub use blob::BlobStore;
 pub use collection::{CollectionStore, ItemField, StoredCollection, StoredCollectionItem, StoredCollectionView, StoredItemField};
 pub use document::{DocumentStore, StoredDocument, StoredTab, StoredRevision};
+pub(crate) use entity::normalize_tag;
 pub use entity::{EntityStore, StoredEntity};
 pub use reference::{ReferenceStore, StoredReference};
 pub use text::{TextStore, StoredTextBlock};
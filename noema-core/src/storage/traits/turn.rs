@@ -125,6 +125,17 @@ pub trait TurnStore: Send + Sync {
         include_turn: bool,
     ) -> Result<usize>;
 
+    /// Remove the turns after a specific turn from a conversation's path
+    ///
+    /// Used when editing a message: later turns leave the conversation but
+    /// are kept in storage, since other conversations may still select them.
+    /// Returns the number of turns removed.
+    async fn truncate_selections(
+        &self,
+        conversation_id: &ConversationId,
+        after_turn_id: &TurnId,
+    ) -> Result<usize>;
+
     /// Get the number of turns in a conversation
     async fn get_turn_count(&self, conversation_id: &ConversationId) -> Result<usize>;
 }
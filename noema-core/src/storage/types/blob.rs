@@ -36,4 +36,12 @@ impl BlobHash {
     pub fn as_str(&self) -> &str {
         &self.0
     }
-}
\ No newline at end of file
+}
+/// Result of a blob garbage collection sweep
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GcStats {
+    /// Number of blobs deleted
+    pub deleted: usize,
+    /// Total size of the deleted blobs in bytes
+    pub bytes_reclaimed: u64,
+}
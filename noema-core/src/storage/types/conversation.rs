@@ -151,6 +151,21 @@ pub struct TurnWithContent {
     pub messages: Vec<MessageWithContent>,
 }
 
+// ============================================================================
+// Context Summary
+// ============================================================================
+
+/// Summary standing in for the start of a conversation when it is sent to
+/// the model. Stored in the conversation entity's metadata; the summarized
+/// turns themselves stay in storage and are still displayed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextSummary {
+    /// Summary of every turn up to and including `through_turn_id`
+    pub text: String,
+    /// Last turn covered by the summary
+    pub through_turn_id: TurnId,
+}
+
 // ============================================================================
 // Conversation (Entity-based)
 // ============================================================================
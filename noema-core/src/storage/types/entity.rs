@@ -8,6 +8,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::storage::ids::UserId;
+use crate::storage::types::ContextSummary;
 
 // ============================================================================
 // EntityType
@@ -96,8 +97,12 @@ pub struct Entity {
     pub is_private: bool,
     /// Whether entity is archived (hidden from default views)
     pub is_archived: bool,
+    /// Whether a conversation is kept out of listings, search and (when
+    /// ephemeral) storage
+    pub incognito: Incognito,
     /// Type-specific metadata as JSON
-    /// For conversations: {"main_view_id": "view-123"}
+    /// For conversations: {"main_view_id": "view-123", "last_model": "gemini/gemini-2.5-flash",
+    ///                     "system_prompt": "You are...", "pinned": true}
     /// For documents: {"document_id": "doc-456"}
     /// For assets: {"asset_id": "asset-789"}
     pub metadata: Option<serde_json::Value>,
@@ -113,6 +118,7 @@ impl Entity {
             slug: None,
             is_private: true,
             is_archived: false,
+            incognito: Incognito::Off,
             metadata: None,
         }
     }
@@ -147,11 +153,180 @@ impl Entity {
         self
     }
 
+    /// Set the incognito mode
+    pub fn with_incognito(mut self, incognito: Incognito) -> Self {
+        self.incognito = incognito;
+        self
+    }
+
     /// Set type-specific metadata
     pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
         self.metadata = Some(metadata);
         self
     }
+
+    /// Last model used in a conversation (provider/model format)
+    pub fn last_model(&self) -> Option<&str> {
+        self.metadata.as_ref()?.get("last_model")?.as_str()
+    }
+
+    /// Record the last model used, preserving other metadata keys
+    pub fn set_last_model(&mut self, model_id: impl Into<String>) {
+        let metadata = self
+            .metadata
+            .get_or_insert_with(|| serde_json::json!({}));
+        if !metadata.is_object() {
+            *metadata = serde_json::json!({});
+        }
+        metadata["last_model"] = serde_json::Value::String(model_id.into());
+    }
+
+    /// System prompt of a conversation
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.metadata.as_ref()?.get("system_prompt")?.as_str()
+    }
+
+    /// Set or clear the system prompt, preserving other metadata keys
+    pub fn set_system_prompt(&mut self, prompt: Option<String>) {
+        match prompt {
+            Some(prompt) => {
+                let metadata = self
+                    .metadata
+                    .get_or_insert_with(|| serde_json::json!({}));
+                if !metadata.is_object() {
+                    *metadata = serde_json::json!({});
+                }
+                metadata["system_prompt"] = serde_json::Value::String(prompt);
+            }
+            None => {
+                if let Some(metadata) = self.metadata.as_mut().and_then(|m| m.as_object_mut()) {
+                    metadata.remove("system_prompt");
+                }
+            }
+        }
+    }
+
+    /// Summary replacing the start of a conversation's history, if it was compacted
+    pub fn context_summary(&self) -> Option<ContextSummary> {
+        let summary = self.metadata.as_ref()?.get("context_summary")?;
+        serde_json::from_value(summary.clone()).ok()
+    }
+
+    /// Set or clear the context summary, preserving other metadata keys
+    pub fn set_context_summary(&mut self, summary: Option<&ContextSummary>) {
+        match summary {
+            Some(summary) => {
+                let metadata = self
+                    .metadata
+                    .get_or_insert_with(|| serde_json::json!({}));
+                if !metadata.is_object() {
+                    *metadata = serde_json::json!({});
+                }
+                metadata["context_summary"] = serde_json::json!(summary);
+            }
+            None => {
+                if let Some(metadata) = self.metadata.as_mut().and_then(|m| m.as_object_mut()) {
+                    metadata.remove("context_summary");
+                }
+            }
+        }
+    }
+
+    /// Whether a conversation is pinned to the top of the list
+    pub fn is_pinned(&self) -> bool {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.get("pinned"))
+            .and_then(|p| p.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Pin or unpin a conversation, preserving other metadata keys
+    pub fn set_pinned(&mut self, pinned: bool) {
+        if pinned {
+            let metadata = self
+                .metadata
+                .get_or_insert_with(|| serde_json::json!({}));
+            if !metadata.is_object() {
+                *metadata = serde_json::json!({});
+            }
+            metadata["pinned"] = serde_json::Value::Bool(true);
+        } else if let Some(metadata) = self.metadata.as_mut().and_then(|m| m.as_object_mut()) {
+            metadata.remove("pinned");
+        }
+    }
+}
+
+// ============================================================================
+// Incognito
+// ============================================================================
+
+/// How much of a conversation is kept out of history
+///
+/// Unrelated to `Entity::is_private`, which keeps content away from cloud
+/// models.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Incognito {
+    /// Listed and searchable like any other conversation
+    #[default]
+    Off,
+    /// Left out of default listings, range queries and semantic search
+    Unlisted,
+    /// Unlisted, and its messages are kept in memory instead of being stored
+    Ephemeral,
+}
+
+impl Incognito {
+    /// Value of the `incognito` column
+    pub fn as_i32(self) -> i32 {
+        match self {
+            Incognito::Off => 0,
+            Incognito::Unlisted => 1,
+            Incognito::Ephemeral => 2,
+        }
+    }
+
+    /// Parse the `incognito` column (unknown values count as unlisted)
+    pub fn from_i32(value: i32) -> Self {
+        match value {
+            0 => Incognito::Off,
+            2 => Incognito::Ephemeral,
+            _ => Incognito::Unlisted,
+        }
+    }
+
+    /// Whether the conversation is hidden from listings and search
+    pub fn is_unlisted(self) -> bool {
+        self != Incognito::Off
+    }
+}
+
+// ============================================================================
+// ConversationListOptions
+// ============================================================================
+
+/// Options for `EntityStore::list_conversations`
+#[derive(Clone, Debug, Default)]
+pub struct ConversationListOptions {
+    /// Also return archived conversations
+    pub include_archived: bool,
+    /// Also return incognito conversations
+    pub include_incognito: bool,
+}
+
+impl ConversationListOptions {
+    /// Include archived conversations
+    pub fn with_archived(mut self) -> Self {
+        self.include_archived = true;
+        self
+    }
+
+    /// Include incognito conversations
+    pub fn with_incognito(mut self) -> Self {
+        self.include_incognito = true;
+        self
+    }
 }
 
 // ============================================================================
@@ -308,6 +483,7 @@ impl EntityRelation {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::ids::TurnId;
 
     #[test]
     fn test_entity_type_wellknown() {
@@ -358,6 +534,67 @@ mod tests {
         assert!(entity.user_id.is_none());
     }
 
+    #[test]
+    fn test_entity_last_model() {
+        let mut entity = Entity::new(EntityType::conversation())
+            .with_metadata(serde_json::json!({"main_view_id": "view-1"}));
+        assert_eq!(entity.last_model(), None);
+
+        entity.set_last_model("claude/claude-sonnet-4-5");
+        assert_eq!(entity.last_model(), Some("claude/claude-sonnet-4-5"));
+        assert_eq!(entity.metadata.as_ref().unwrap()["main_view_id"], "view-1");
+
+        let mut bare = Entity::new(EntityType::conversation());
+        bare.set_last_model("ollama/llama3");
+        assert_eq!(bare.last_model(), Some("ollama/llama3"));
+    }
+
+    #[test]
+    fn test_entity_system_prompt() {
+        let mut entity = Entity::new(EntityType::conversation())
+            .with_metadata(serde_json::json!({"main_view_id": "view-1"}));
+        assert_eq!(entity.system_prompt(), None);
+
+        entity.set_system_prompt(Some("Be terse.".to_string()));
+        assert_eq!(entity.system_prompt(), Some("Be terse."));
+
+        entity.set_system_prompt(None);
+        assert_eq!(entity.system_prompt(), None);
+        assert_eq!(entity.metadata.as_ref().unwrap()["main_view_id"], "view-1");
+    }
+
+    #[test]
+    fn test_entity_context_summary() {
+        let mut entity = Entity::new(EntityType::conversation())
+            .with_metadata(serde_json::json!({"main_view_id": "view-1"}));
+        assert_eq!(entity.context_summary(), None);
+
+        let summary = ContextSummary {
+            text: "We picked a name.".to_string(),
+            through_turn_id: TurnId::from_string("turn-1"),
+        };
+        entity.set_context_summary(Some(&summary));
+        assert_eq!(entity.context_summary(), Some(summary));
+
+        entity.set_context_summary(None);
+        assert_eq!(entity.context_summary(), None);
+        assert_eq!(entity.metadata, Some(serde_json::json!({"main_view_id": "view-1"})));
+    }
+
+    #[test]
+    fn test_entity_pinned() {
+        let mut entity = Entity::new(EntityType::conversation())
+            .with_metadata(serde_json::json!({"main_view_id": "view-1"}));
+        assert!(!entity.is_pinned());
+
+        entity.set_pinned(true);
+        assert!(entity.is_pinned());
+
+        entity.set_pinned(false);
+        assert!(!entity.is_pinned());
+        assert_eq!(entity.metadata, Some(serde_json::json!({"main_view_id": "view-1"})));
+    }
+
     #[test]
     fn test_relation_with_metadata() {
         let metadata = serde_json::json!({
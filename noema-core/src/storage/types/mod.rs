@@ -10,22 +10,26 @@ pub mod conversation;
 pub mod document;
 pub mod entity;
 pub mod reference;
+pub mod search;
 pub mod stored;
 pub mod temporal;
 pub mod user;
 
 // Re-exports for convenience
 pub use asset::Asset;
-pub use blob::BlobHash;
+pub use blob::{BlobHash, GcStats};
 pub use content_block::{ContentBlock, ContentOrigin, ContentType, OriginKind};
-pub use conversation::{Message, MessageWithContent, Span, Turn, TurnWithContent};
+pub use conversation::{ContextSummary, Message, MessageWithContent, Span, Turn, TurnWithContent};
 pub use document::{Document, DocumentRevision, DocumentSource, DocumentTab};
-pub use entity::{Entity, EntityRangeQuery, EntityRelation, EntityType, RelationType};
+pub use entity::{
+    ConversationListOptions, Entity, EntityRangeQuery, EntityRelation, EntityType, Incognito, RelationType,
+};
 pub use collection::{
     Collection, CollectionItem, CollectionView, FieldDefinition, FieldType,
     ItemTarget, ViewConfig, ViewType,
 };
 pub use reference::{EntityRef, Reference};
+pub use search::SearchHit;
 pub use stored::{stored, stored_editable, Editable, Hashed, Keyed, Stored, StoredEditable, Timestamped};
 pub use temporal::ActivitySummary;
 pub use user::User;
@@ -0,0 +1,16 @@
+//! Search result types
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::ids::{ConversationId, MessageId};
+
+/// A message matching a search query
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// The matching message
+    pub message_id: MessageId,
+    /// A conversation whose selected path contains the message, if any
+    pub conversation_id: Option<ConversationId>,
+    /// Relevance score; for semantic search this is the cosine similarity
+    pub score: f32,
+}
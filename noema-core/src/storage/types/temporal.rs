@@ -63,7 +63,7 @@ impl ActivitySummary {
 mod tests {
     use super::*;
     use crate::storage::ids::EntityId;
-    use crate::storage::types::{stored_editable, Entity};
+    use crate::storage::types::{stored_editable, Entity, Incognito};
 
     fn make_entity(entity_type: EntityType) -> StoredEntity {
         let entity = Entity {
@@ -73,6 +73,7 @@ mod tests {
             slug: None,
             is_private: true,
             is_archived: false,
+            incognito: Incognito::Off,
             metadata: None,
         };
         stored_editable(EntityId::new(), entity, 1000, 1000)
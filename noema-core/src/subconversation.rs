@@ -0,0 +1,222 @@
+//! Running several subconversations at once
+//!
+//! `SubconversationManager` launches sub-agents concurrently, tracks the
+//! status of each, and collects their results keyed by subconversation ID.
+//! How a sub-agent runs (its session, model and tools) is up to the caller,
+//! which passes each one in as a future producing the sub-agent's result.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+use crate::storage::ids::ConversationId;
+
+/// Where a subconversation's agent is, or how it ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubconversationStatus {
+    Running,
+    /// Finished with this result text
+    Completed(String),
+    /// Stopped with this error
+    Failed(String),
+    /// Stopped because it was cancelled (or a sibling failed)
+    Cancelled,
+}
+
+impl SubconversationStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, Self::Running)
+    }
+}
+
+/// Runs sub-agents concurrently and waits for all of them
+///
+/// By default a failing sub-agent doesn't affect the others; with
+/// `cancel_siblings_on_failure(true)` the first failure cancels every
+/// sub-agent still running. With `max_concurrent(n)` at most `n` sub-agents
+/// run at a time and the rest wait their turn.
+#[derive(Default)]
+pub struct SubconversationManager {
+    cancel_siblings_on_failure: bool,
+    permits: Option<Arc<Semaphore>>,
+    token: CancellationToken,
+    statuses: Arc<Mutex<HashMap<ConversationId, SubconversationStatus>>>,
+    tasks: JoinSet<()>,
+}
+
+impl SubconversationManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the remaining sub-agents as soon as one fails
+    pub fn cancel_siblings_on_failure(mut self, cancel: bool) -> Self {
+        self.cancel_siblings_on_failure = cancel;
+        self
+    }
+
+    /// Run at most `limit` sub-agents at a time
+    pub fn max_concurrent(mut self, limit: usize) -> Self {
+        self.permits = Some(Arc::new(Semaphore::new(limit.max(1))));
+        self
+    }
+
+    /// Start running a sub-agent for subconversation `id`
+    ///
+    /// `run` is dropped, stopping the sub-agent, if the manager is cancelled
+    /// before it finishes. Under `max_concurrent`, `run` isn't polled until
+    /// a slot is free.
+    pub fn spawn<F>(&mut self, id: ConversationId, run: F)
+    where
+        F: Future<Output = anyhow::Result<String>> + Send + 'static,
+    {
+        self.statuses.lock().unwrap().insert(id.clone(), SubconversationStatus::Running);
+
+        let token = self.token.clone();
+        let statuses = Arc::clone(&self.statuses);
+        let cancel_siblings = self.cancel_siblings_on_failure;
+        let permits = self.permits.clone();
+        let run = async move {
+            // Held until the sub-agent finishes; waiting is cancellable too
+            let _permit = match permits {
+                Some(permits) => Some(permits.acquire_owned().await?),
+                None => None,
+            };
+            run.await
+        };
+        self.tasks.spawn(async move {
+            let status = tokio::select! {
+                biased;
+                _ = token.cancelled() => SubconversationStatus::Cancelled,
+                result = run => match result {
+                    Ok(text) => SubconversationStatus::Completed(text),
+                    Err(e) => {
+                        if cancel_siblings {
+                            token.cancel();
+                        }
+                        SubconversationStatus::Failed(e.to_string())
+                    }
+                },
+            };
+            statuses.lock().unwrap().insert(id, status);
+        });
+    }
+
+    /// Get the status of one subconversation
+    pub fn status(&self, id: &ConversationId) -> Option<SubconversationStatus> {
+        self.statuses.lock().unwrap().get(id).cloned()
+    }
+
+    /// Get the status of every subconversation
+    pub fn statuses(&self) -> HashMap<ConversationId, SubconversationStatus> {
+        self.statuses.lock().unwrap().clone()
+    }
+
+    /// Cancel every sub-agent still running
+    pub fn cancel_all(&self) {
+        self.token.cancel();
+    }
+
+    /// Wait for every sub-agent to finish and return how each one ended
+    pub async fn await_all(mut self) -> HashMap<ConversationId, SubconversationStatus> {
+        while let Some(joined) = self.tasks.join_next().await {
+            if joined.is_err() && self.cancel_siblings_on_failure {
+                self.token.cancel();
+            }
+        }
+
+        // A sub-agent that panicked never recorded how it ended
+        let mut statuses = std::mem::take(&mut *self.statuses.lock().unwrap());
+        for status in statuses.values_mut() {
+            if !status.is_finished() {
+                *status = SubconversationStatus::Failed("sub-agent stopped unexpectedly".to_string());
+            }
+        }
+        statuses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::{oneshot, Notify};
+
+    fn id(s: &str) -> ConversationId {
+        ConversationId::from_string(s)
+    }
+
+    /// Spawn "ok", which succeeds at once, "fails", which fails once "ok"
+    /// is done, and "slow", which succeeds once `release` is notified
+    fn spawn_three(manager: &mut SubconversationManager, release: Arc<Notify>) {
+        let (ok_done, ok_finished) = oneshot::channel();
+        manager.spawn(id("ok"), async move {
+            let _ = ok_done.send(());
+            Ok("done".to_string())
+        });
+        manager.spawn(id("fails"), async move {
+            let _ = ok_finished.await;
+            anyhow::bail!("model unavailable")
+        });
+        manager.spawn(id("slow"), async move {
+            release.notified().await;
+            Ok("slow result".to_string())
+        });
+    }
+
+    #[tokio::test]
+    async fn test_await_all_collects_every_result() {
+        let mut manager = SubconversationManager::new();
+        let release = Arc::new(Notify::new());
+        spawn_three(&mut manager, Arc::clone(&release));
+        assert_eq!(manager.status(&id("slow")), Some(SubconversationStatus::Running));
+
+        release.notify_one();
+        let results = manager.await_all().await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[&id("ok")], SubconversationStatus::Completed("done".to_string()));
+        assert_eq!(results[&id("fails")], SubconversationStatus::Failed("model unavailable".to_string()));
+        assert_eq!(results[&id("slow")], SubconversationStatus::Completed("slow result".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_failure_cancels_siblings_when_enabled() {
+        let mut manager = SubconversationManager::new().cancel_siblings_on_failure(true);
+        // "slow" is never released, so only the failure can end it
+        spawn_three(&mut manager, Arc::new(Notify::new()));
+
+        let results = manager.await_all().await;
+        assert_eq!(results[&id("ok")], SubconversationStatus::Completed("done".to_string()));
+        assert_eq!(results[&id("fails")], SubconversationStatus::Failed("model unavailable".to_string()));
+        assert_eq!(results[&id("slow")], SubconversationStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_limits_running_sub_agents() {
+        let mut manager = SubconversationManager::new().max_concurrent(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let most_running = Arc::new(AtomicUsize::new(0));
+        for i in 0..5 {
+            let running = Arc::clone(&running);
+            let most_running = Arc::clone(&most_running);
+            manager.spawn(id(&i.to_string()), async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                most_running.fetch_max(now, Ordering::SeqCst);
+                // Give every other sub-agent a chance to start
+                for _ in 0..10 {
+                    tokio::task::yield_now().await;
+                }
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(String::new())
+            });
+        }
+
+        let results = manager.await_all().await;
+        assert_eq!(results.len(), 5);
+        assert!(results.values().all(|status| matches!(status, SubconversationStatus::Completed(_))));
+        assert_eq!(most_running.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,194 @@
+//! Conversation templates
+//!
+//! A template seeds a new conversation with a system prompt, a model and
+//! sampling settings ("code-reviewer", "translator", ...). A few are built
+//! in; the user's own live in templates.toml (see `PathManager::templates_path`)
+//! and replace a built-in of the same name:
+//!
+//! ```toml
+//! [summarizer]
+//! description = "Condense text to bullet points"
+//! system_message = "Summarize what you are given as terse bullet points."
+//! model_id = "claude/claude-haiku-4-5"
+//!
+//! [summarizer.generation_params]
+//! temperature = 0.2
+//! ```
+
+use crate::manager::ConversationManager;
+use crate::storage::StorageTypes;
+use anyhow::{Context, Result};
+use config::PathManager;
+use llm::GenerationParams;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Settings a new conversation starts with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Template {
+    /// Name used to pick the template, e.g. in "/new code-reviewer"
+    #[serde(skip)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_message: Option<String>,
+    /// Model to use (provider/model); None keeps the current model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default_params")]
+    pub generation_params: GenerationParams,
+}
+
+fn is_default_params(params: &GenerationParams) -> bool {
+    *params == GenerationParams::default()
+}
+
+/// Templates available without any configuration
+pub fn builtin_templates() -> Vec<Template> {
+    vec![
+        Template {
+            name: "code-reviewer".to_string(),
+            description: Some("Review code for bugs, clarity and style".to_string()),
+            system_message: Some(
+                "You are an experienced code reviewer. Point out bugs, edge cases and unclear code \
+                 first, then style issues. Quote the lines you are talking about and suggest concrete \
+                 fixes. Say so when the code looks good."
+                    .to_string(),
+            ),
+            model_id: None,
+            generation_params: GenerationParams { temperature: Some(0.2), ..Default::default() },
+        },
+        Template {
+            name: "translator".to_string(),
+            description: Some("Translate text, keeping tone and formatting".to_string()),
+            system_message: Some(
+                "You are a translator. Translate the user's text into English, or into the language \
+                 they ask for. Keep the tone, formatting and meaning; reply with the translation only."
+                    .to_string(),
+            ),
+            model_id: None,
+            generation_params: GenerationParams { temperature: Some(0.3), ..Default::default() },
+        },
+    ]
+}
+
+/// Read templates from a templates.toml file
+pub fn load_templates(path: &Path) -> Result<Vec<Template>> {
+    let content = std::fs::read_to_string(path)?;
+    let templates: BTreeMap<String, Template> =
+        toml::from_str(&content).with_context(|| format!("Invalid templates file {}", path.display()))?;
+    Ok(templates
+        .into_iter()
+        .map(|(name, template)| Template { name, ..template })
+        .collect())
+}
+
+/// Write templates to a templates.toml file, replacing its contents
+pub fn save_templates(path: &Path, templates: &[Template]) -> Result<()> {
+    let by_name: BTreeMap<&str, &Template> = templates.iter().map(|t| (t.name.as_str(), t)).collect();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, toml::to_string_pretty(&by_name)?)?;
+    Ok(())
+}
+
+/// The user's templates (none if templates.toml is missing)
+fn user_templates() -> Result<Vec<Template>> {
+    match PathManager::templates_path() {
+        Some(path) if path.exists() => load_templates(&path),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// All templates by name: the built-ins, overridden by the user's own
+pub fn list_templates() -> Result<Vec<Template>> {
+    let mut templates: BTreeMap<String, Template> =
+        builtin_templates().into_iter().map(|t| (t.name.clone(), t)).collect();
+    templates.extend(user_templates()?.into_iter().map(|t| (t.name.clone(), t)));
+    Ok(templates.into_values().collect())
+}
+
+/// Look up a template by name
+pub fn get_template(name: &str) -> Result<Template> {
+    list_templates()?
+        .into_iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| anyhow::anyhow!("No template named '{}'", name))
+}
+
+/// Add or replace one of the user's templates
+pub fn save_template(template: Template) -> Result<()> {
+    if template.name.trim().is_empty() {
+        anyhow::bail!("Template name cannot be empty");
+    }
+    let path = PathManager::templates_path().context("Could not determine templates path")?;
+    let mut templates = user_templates()?;
+    templates.retain(|t| t.name != template.name);
+    templates.push(template);
+    save_templates(&path, &templates)
+}
+
+/// Remove one of the user's templates (built-ins can only be overridden)
+///
+/// Returns whether a template was removed.
+pub fn delete_template(name: &str) -> Result<bool> {
+    let Some(path) = PathManager::templates_path() else {
+        return Ok(false);
+    };
+    let mut templates = user_templates()?;
+    let before = templates.len();
+    templates.retain(|t| t.name != name);
+    if templates.len() == before {
+        return Ok(false);
+    }
+    save_templates(&path, &templates)?;
+    Ok(true)
+}
+
+/// Seed a conversation with a template's system prompt and sampling settings
+///
+/// The model isn't switched here: frontends create `template.model_id` with
+/// their own wrappers (retries, rate limits) and hand it to the manager.
+pub async fn apply_template<S: StorageTypes>(manager: &mut ConversationManager<S>, template: &Template) -> Result<()> {
+    manager.set_generation_params(template.generation_params.clone());
+    manager.set_system_message(template.system_message.clone()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_templates_round_trip() {
+        let templates = vec![
+            Template {
+                name: "summarizer".to_string(),
+                system_message: Some("Summarize as bullet points.".to_string()),
+                model_id: Some("claude/claude-haiku-4-5".to_string()),
+                generation_params: GenerationParams { temperature: Some(0.2), ..Default::default() },
+                ..Default::default()
+            },
+            Template { name: "plain".to_string(), ..Default::default() },
+        ];
+        let path = std::env::temp_dir().join(format!("templates_{}.toml", uuid::Uuid::new_v4()));
+        save_templates(&path, &templates).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("[summarizer.generation_params]"), "{}", content);
+        let mut loaded = load_templates(&path).unwrap();
+        loaded.sort_by(|a, b| b.name.cmp(&a.name));
+        assert_eq!(loaded, templates);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_builtin_templates_have_prompts() {
+        for template in builtin_templates() {
+            assert!(!template.name.is_empty());
+            assert!(template.system_message.is_some(), "{}", template.name);
+        }
+    }
+}
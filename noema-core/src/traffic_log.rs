@@ -89,7 +89,7 @@ fn log_traffic(category: &str, event_type: &str, message: &str) {
             .open(&log_path)
         {
             let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-            let _ = writeln!(file, "[{}] [TRAFFIC] [{}] [{}] {}", timestamp, category, event_type, message);
+            let _ = writeln!(file, "[{}] [TRAFFIC] [{}] [{}] {}", timestamp, category, event_type, config::redact(message));
         }
     }
 }
@@ -0,0 +1,340 @@
+//! Built-in web search tool
+//!
+//! `search_web` lets the model look things up without an MCP server. The
+//! search itself goes through a `SearchBackend`: DuckDuckGo's Instant Answer
+//! API (no key) or Google results through SerpAPI. Enable it with a
+//! `[web_search]` section in mcp.toml (see `WebSearchConfig`); the registry
+//! then offers `search_web` to `McpAgent` alongside the MCP servers' tools,
+//! and the model calls it before answering whenever it needs fresh results.
+//!
+//! Only built with the `web-search` feature.
+
+use crate::mcp::{WebSearchBackendKind, WebSearchConfig};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use llm::ToolRegistry;
+use llm_macros::tool_methods;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Results returned when neither the model nor the config asks for a number
+pub const DEFAULT_MAX_RESULTS: usize = 5;
+
+/// Upper bound on results per search, whatever the model asks for
+const MAX_RESULTS_LIMIT: usize = 20;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// One search hit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+/// A web search service
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    /// Search for `query`, returning at most `max_results` hits
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchResult>>;
+}
+
+/// DuckDuckGo's Instant Answer API
+///
+/// Free and keyless, but it returns topic summaries and related pages rather
+/// than a full result page, so obscure queries may come back empty.
+pub struct DuckDuckGoBackend {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl DuckDuckGoBackend {
+    pub fn new() -> Self {
+        Self::with_base_url("https://api.duckduckgo.com")
+    }
+
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        Self { client: http_client(), base_url: base_url.into() }
+    }
+}
+
+impl Default for DuckDuckGoBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchBackend for DuckDuckGoBackend {
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchResult>> {
+        let response: Value = self
+            .client
+            .get(&self.base_url)
+            .query(&[("q", query), ("format", "json"), ("no_html", "1"), ("skip_disambig", "1")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("DuckDuckGo returned an invalid response")?;
+        Ok(parse_duckduckgo(&response, max_results))
+    }
+}
+
+/// Results from an Instant Answer response: the abstract, then direct
+/// results, then related topics (flattening topic groups)
+fn parse_duckduckgo(response: &Value, max_results: usize) -> Vec<SearchResult> {
+    fn topic_result(topic: &Value) -> Option<SearchResult> {
+        let text = topic["Text"].as_str().filter(|t| !t.is_empty())?;
+        let url = topic["FirstURL"].as_str().filter(|u| !u.is_empty())?;
+        let title = text.split(" - ").next().unwrap_or(text);
+        Some(SearchResult { title: title.to_string(), url: url.to_string(), snippet: text.to_string() })
+    }
+
+    let mut results = Vec::new();
+    if let (Some(text), Some(url)) = (
+        response["AbstractText"].as_str().filter(|t| !t.is_empty()),
+        response["AbstractURL"].as_str().filter(|u| !u.is_empty()),
+    ) {
+        let title = response["Heading"].as_str().filter(|h| !h.is_empty()).unwrap_or(url);
+        results.push(SearchResult { title: title.to_string(), url: url.to_string(), snippet: text.to_string() });
+    }
+
+    let as_array = |key: &str| response[key].as_array().cloned().unwrap_or_default();
+    let topics = as_array("Results").into_iter().chain(as_array("RelatedTopics")).flat_map(|topic| {
+        match topic["Topics"].as_array() {
+            Some(group) => group.clone(),
+            None => vec![topic],
+        }
+    });
+    results.extend(topics.filter_map(|topic| topic_result(&topic)));
+    results.truncate(max_results);
+    results
+}
+
+/// Google results through SerpAPI (https://serpapi.com)
+pub struct SerpApiBackend {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl SerpApiBackend {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self::with_base_url(api_key, "https://serpapi.com/search.json")
+    }
+
+    pub fn with_base_url(api_key: impl Into<String>, base_url: impl Into<String>) -> Self {
+        Self { client: http_client(), api_key: api_key.into(), base_url: base_url.into() }
+    }
+}
+
+#[async_trait]
+impl SearchBackend for SerpApiBackend {
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchResult>> {
+        let num = max_results.to_string();
+        let response = self
+            .client
+            .get(&self.base_url)
+            .query(&[("engine", "google"), ("q", query), ("num", &num), ("api_key", &self.api_key)])
+            .send()
+            .await
+            // The URL carries the API key, so keep it out of the error
+            .map_err(|e| e.without_url())?;
+        let status = response.status();
+        let body: Value = response.json().await.context("SerpAPI returned an invalid response")?;
+        if let Some(error) = body["error"].as_str() {
+            anyhow::bail!("SerpAPI search failed: {}", error);
+        }
+        if !status.is_success() {
+            anyhow::bail!("SerpAPI search failed with status {}", status);
+        }
+        Ok(parse_serpapi(&body, max_results))
+    }
+}
+
+/// Organic results from a SerpAPI response
+fn parse_serpapi(response: &Value, max_results: usize) -> Vec<SearchResult> {
+    response["organic_results"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|result| {
+            Some(SearchResult {
+                title: result["title"].as_str()?.to_string(),
+                url: result["link"].as_str()?.to_string(),
+                snippet: result["snippet"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .take(max_results)
+        .collect()
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("noema/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+}
+
+/// The `search_web` tool, backed by a `SearchBackend`
+pub struct WebSearch {
+    backend: Arc<dyn SearchBackend>,
+    default_max_results: usize,
+}
+
+#[tool_methods]
+impl WebSearch {
+    pub fn new(backend: Arc<dyn SearchBackend>) -> Self {
+        Self { backend, default_max_results: DEFAULT_MAX_RESULTS }
+    }
+
+    /// Tool backed by the search service named in `config`
+    ///
+    /// Fails if the backend needs an API key and its variable isn't set.
+    pub fn from_config(config: &WebSearchConfig) -> Result<Self> {
+        let backend: Arc<dyn SearchBackend> = match config.backend {
+            WebSearchBackendKind::DuckDuckGo => Arc::new(DuckDuckGoBackend::new()),
+            WebSearchBackendKind::SerpApi => {
+                let env = config.api_key_env.as_deref().unwrap_or("SERPAPI_API_KEY");
+                let api_key = std::env::var(env)
+                    .with_context(|| format!("Web search needs a SerpAPI key in {}", env))?;
+                Arc::new(SerpApiBackend::new(api_key))
+            }
+        };
+        let mut search = Self::new(backend);
+        if let Some(max_results) = config.max_results {
+            search.default_max_results = max_results.clamp(1, MAX_RESULTS_LIMIT);
+        }
+        Ok(search)
+    }
+
+    /// Search the web and return matching pages with a title, URL and snippet.
+    /// Use this for recent events or facts you are unsure about, and cite the URLs you rely on.
+    #[tool]
+    async fn search_web(&self, query: String, max_results: Option<usize>) -> Result<Vec<SearchResult>, String> {
+        let max_results = max_results.unwrap_or(self.default_max_results).clamp(1, MAX_RESULTS_LIMIT);
+        self.backend.search(&query, max_results).await.map_err(|e| e.to_string())
+    }
+
+    /// Add `search_web` to a tool registry
+    pub fn register(self: Arc<Self>, registry: &mut ToolRegistry) {
+        registry.register(SearchWebArgs::search_web_tool_def(), move |args: Value| {
+            let search = Arc::clone(&self);
+            async move {
+                let args: SearchWebArgs = serde_json::from_value(args)?;
+                let results = search.search_web(args.query, args.max_results).await.map_err(anyhow::Error::msg)?;
+                if results.is_empty() {
+                    return Ok("No results found.".to_string());
+                }
+                Ok(serde_json::to_string(&results)?)
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Backend returning canned results and recording the queries it saw
+    #[derive(Default)]
+    struct FakeBackend {
+        queries: Mutex<Vec<(String, usize)>>,
+    }
+
+    #[async_trait]
+    impl SearchBackend for FakeBackend {
+        async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchResult>> {
+            self.queries.lock().unwrap().push((query.to_string(), max_results));
+            Ok((0..max_results)
+                .map(|i| SearchResult {
+                    title: format!("Result {}", i),
+                    url: format!("https://example.com/{}", i),
+                    snippet: query.to_string(),
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_tool_definition_is_generated() {
+        let def = SearchWebArgs::search_web_tool_def();
+        assert_eq!(def.name, "search_web");
+        assert!(def.description.unwrap().starts_with("Search the web"));
+        let schema = serde_json::to_value(&def.input_schema).unwrap();
+        assert_eq!(schema["required"], json!(["query"]));
+        assert!(schema["properties"]["max_results"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_registered_tool_calls_backend() {
+        let backend = Arc::new(FakeBackend::default());
+        let mut registry = ToolRegistry::new();
+        Arc::new(WebSearch::new(backend.clone())).register(&mut registry);
+
+        let output = registry.call("search_web", json!({"query": "rust 2024 edition"})).await.unwrap();
+        let results: Vec<SearchResult> = serde_json::from_str(&output).unwrap();
+        assert_eq!(results.len(), DEFAULT_MAX_RESULTS);
+        assert_eq!(results[0].snippet, "rust 2024 edition");
+
+        registry.call("search_web", json!({"query": "q", "max_results": 500})).await.unwrap();
+        assert_eq!(backend.queries.lock().unwrap()[1], ("q".to_string(), MAX_RESULTS_LIMIT));
+
+        assert!(registry.call("search_web", json!({})).await.is_err());
+    }
+
+    #[test]
+    fn test_parse_duckduckgo() {
+        let response = json!({
+            "Heading": "Rust (programming language)",
+            "AbstractText": "Rust is a general-purpose programming language.",
+            "AbstractURL": "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+            "Results": [
+                {"Text": "Official site - Rust", "FirstURL": "https://www.rust-lang.org/"}
+            ],
+            "RelatedTopics": [
+                {"Text": "Cargo - Rust's package manager", "FirstURL": "https://duckduckgo.com/Cargo"},
+                {"Name": "See also", "Topics": [
+                    {"Text": "Ferris - the Rust mascot", "FirstURL": "https://duckduckgo.com/Ferris"}
+                ]},
+                {"Text": "", "FirstURL": ""}
+            ]
+        });
+        let results = parse_duckduckgo(&response, 10);
+        let urls: Vec<&str> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+                "https://www.rust-lang.org/",
+                "https://duckduckgo.com/Cargo",
+                "https://duckduckgo.com/Ferris",
+            ]
+        );
+        assert_eq!(results[0].title, "Rust (programming language)");
+        assert_eq!(results[2].title, "Cargo");
+        assert_eq!(parse_duckduckgo(&response, 2).len(), 2);
+    }
+
+    #[test]
+    fn test_parse_serpapi() {
+        let response = json!({
+            "organic_results": [
+                {"title": "Rust", "link": "https://www.rust-lang.org/", "snippet": "A language empowering everyone"},
+                {"title": "No snippet", "link": "https://example.com/"},
+                {"snippet": "missing link"}
+            ]
+        });
+        let results = parse_serpapi(&response, 10);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[1].snippet, "");
+    }
+}
@@ -1,22 +1,71 @@
 //! Chat-related Tauri commands
 
-use llm::{Role, create_model, list_all_models};
-use noema_core::{ConversationManager, ManagerEvent, ToolConfig as CoreToolConfig};
-use noema_core::storage::{DocumentResolver, EntityStore, EntityType, InputContent, Session, StorageTypes, Stores, TurnStore};
+use llm::{ChatModel, ContentBlock, RetryPolicy, RetryingChatModel, Role, create_model};
+use noema_core::{AutoNameConfig, CompactionConfig, ConversationManager, ManagerEvent, ToolConfig as CoreToolConfig, DEFAULT_TEXT_DELTA_INTERVAL};
+use noema_core::storage::{ConversationListOptions, DocumentResolver, EntityStore, InputContent, ResolvedContent, Session, StorageTypes, StoredEntity, Stores, TurnStore};
 use noema_core::storage::ids::{ConversationId, TurnId, SpanId};
 use noema_core::storage::traits::ReferenceStore;
+use noema_core::agents::ToolApproval;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager, State};
 
 use crate::logging::log_message;
-use crate::state::AppState;
+use crate::state::{AppState, AppStores};
 use crate::types::{
-    AlternateInfo, ConversationInfo, DisplayMessage, ErrorEvent, TruncatedEvent, DisplayInputContent,
-    MessageCompleteEvent, ModelChangedEvent, ModelInfo, StreamingMessageEvent, ToolConfig,
-    UserMessageEvent,
+    AlternateInfo, CancelledEvent, ConversationInfo, ConversationTemplate, DisplayMessage, ErrorEvent, GenerationParams, TruncatedEvent, DisplayInputContent,
+    MessageCompleteEvent, ModelChangedEvent, ModelInfo, ProviderModels, StreamingMessageEvent, TextDeltaEvent,
+    ContextCompactedEvent, ConversationRenamedEvent, HistoryTrimmedEvent, ResponseFinishedEvent, ToolApprovalRequestEvent, ToolCallDeltaEvent, ToolConfig, ToolProgressEvent, UsageEvent, UserMessageEvent,
 };
 
+/// Create a conversation model, retrying transient provider errors
+pub(crate) fn create_chat_model(model_id: &str) -> Result<Arc<dyn ChatModel + Send + Sync>, String> {
+    let model = create_model(model_id)
+        .map_err(|e| format!("Failed to create model: {}", e))?;
+    Ok(RetryingChatModel::wrap(model, RetryPolicy::default()))
+}
+
+/// Make the given model (provider/model format) the app's current model,
+/// which also answers MCP sampling requests
+async fn set_current_model(state: &AppState, model_id: &str) {
+    let display_name = model_id.split('/').last().unwrap_or(model_id).to_string();
+    *state.model_id.lock().await = model_id.to_string();
+    *state.model_name.lock().await = display_name;
+    if let (Some(sampler), Ok(model)) = (state.sampler.get(), create_chat_model(model_id)) {
+        sampler.set_model(model);
+    }
+}
+
+/// Conversation naming as set in settings.toml (titles come from the
+/// conversation's own model)
+fn auto_name_config() -> AutoNameConfig {
+    AutoNameConfig {
+        enabled: config::Settings::load().auto_name_conversations(),
+        title_model: None,
+    }
+}
+
+/// History summarization as set in settings.toml. A summary model that
+/// can't be created falls back to the conversation's model.
+pub(crate) fn compaction_config() -> CompactionConfig {
+    let settings = config::Settings::load();
+    let summary_model = settings
+        .compaction_summary_model
+        .as_deref()
+        .and_then(|model_id| match create_chat_model(model_id) {
+            Ok(model) => Some(model),
+            Err(e) => {
+                tracing::warn!("Summarizing with the conversation's model instead of {}: {}", model_id, e);
+                None
+            }
+        });
+    CompactionConfig {
+        threshold_tokens: settings.compaction_threshold_tokens,
+        summary_model,
+        ..CompactionConfig::default()
+    }
+}
+
 /// Enrich messages with alternate span information for each turn
 async fn enrich_with_alternates<S: StorageTypes, T: Stores<S>>(
     messages: Vec<DisplayMessage>,
@@ -150,6 +199,63 @@ pub async fn send_message(
     Ok(())
 }
 
+/// Seed a conversation with an MCP server's prompt template
+///
+/// The prompt's messages are added to the conversation as they are. When the
+/// last of them is from the user it is sent like a typed message instead, so
+/// the model answers it.
+#[tauri::command]
+pub async fn apply_mcp_prompt(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    server_id: String,
+    name: String,
+    arguments: HashMap<String, String>,
+    tool_config: Option<ToolConfig>,
+) -> Result<(), String> {
+    let mut messages = {
+        let mcp_registry = state.get_mcp_registry()?;
+        let registry = mcp_registry.lock().await;
+        registry
+            .get_prompt(&server_id, &name, &arguments)
+            .await
+            .map_err(|e| format!("Failed to get prompt {}: {}", name, e))?
+    };
+    let last_user_message = match messages.last() {
+        Some(message) if message.role == Role::User => messages.pop(),
+        _ => None,
+    };
+
+    let core_tool_config = match tool_config {
+        Some(tc) => CoreToolConfig {
+            enabled: tc.enabled,
+            server_ids: tc.server_ids,
+            tool_names: tc.tool_names,
+        },
+        None => CoreToolConfig::all_enabled(),
+    };
+
+    let managers = state.managers.lock().await;
+    let manager = managers.get(&conversation_id).ok_or("Conversation not loaded")?;
+    manager.add_messages(messages);
+    if let Some(message) = last_user_message {
+        let input_content = message
+            .payload
+            .content
+            .into_iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(InputContent::Text { text }),
+                ContentBlock::Image { data, mime_type } => Some(InputContent::Image { data, mime_type }),
+                ContentBlock::Audio { data, mime_type } => Some(InputContent::Audio { data, mime_type }),
+                _ => None,
+            })
+            .collect();
+        manager.send_message(input_content, core_tool_config);
+    }
+
+    Ok(())
+}
+
 /// Start the shared event receiver loop - runs continuously from app init
 /// Receives events from the shared channel that all managers send to
 pub async fn start_event_receiver_loop(app: AppHandle, state: Arc<AppState>) {
@@ -171,6 +277,12 @@ pub async fn start_event_receiver_loop(app: AppHandle, state: Arc<AppState>) {
                         message: DisplayMessage::from(&msg),
                     });
                 }
+                ManagerEvent::TextDelta(text) => {
+                    let _ = app.emit("text_delta", TextDeltaEvent {
+                        conversation_id: conversation_id.clone(),
+                        text,
+                    });
+                }
                 ManagerEvent::StreamingMessage(msg) => {
                     state.set_processing(&conversation_id, true).await;
                     let _ = app.emit("streaming_message", StreamingMessageEvent {
@@ -179,6 +291,23 @@ pub async fn start_event_receiver_loop(app: AppHandle, state: Arc<AppState>) {
                     });
                 }
                 ManagerEvent::Complete(resolved_messages) => {
+                    let response = resolved_messages
+                        .iter()
+                        .rev()
+                        .find(|msg| msg.role == Role::Assistant)
+                        .map(|msg| {
+                            msg.content
+                                .iter()
+                                .filter_map(|c| match c {
+                                    ResolvedContent::Text { text } => Some(text.as_str()),
+                                    _ => None,
+                                })
+                                .collect::<Vec<_>>()
+                                .join("")
+                        });
+                    if let Some(response) = response {
+                        state.speak_response(&conversation_id, &response).await;
+                    }
                     let messages: Vec<DisplayMessage> = resolved_messages
                         .iter()
                         .map(DisplayMessage::from)
@@ -189,12 +318,65 @@ pub async fn start_event_receiver_loop(app: AppHandle, state: Arc<AppState>) {
                     });
                     state.set_processing(&conversation_id, false).await;
                 }
-                ManagerEvent::Error(err) => {
-                    log_message(&format!("MANAGER ERROR [{}]: {}", conversation_id.as_str(), err));
-                    let _ = app.emit("error", ErrorEvent {
+                ManagerEvent::ToolCallDelta { tool_call_id, name, args_fragment } => {
+                    let _ = app.emit("tool_call_delta", ToolCallDeltaEvent {
+                        conversation_id: conversation_id.clone(),
+                        tool_call_id,
+                        name,
+                        args_fragment,
+                    });
+                }
+                ManagerEvent::ToolProgress { tool_call_id, message } => {
+                    let _ = app.emit("tool_progress", ToolProgressEvent {
                         conversation_id: conversation_id.clone(),
-                        error: err,
+                        tool_call_id,
+                        message,
                     });
+                }
+                ManagerEvent::ToolApprovalRequest(call) => {
+                    let _ = app.emit("tool_approval_request", ToolApprovalRequestEvent {
+                        conversation_id: conversation_id.clone(),
+                        tool_call_id: call.id,
+                        name: call.name,
+                        arguments: call.arguments.to_string(),
+                    });
+                }
+                ManagerEvent::Usage(usage) => {
+                    let _ = app.emit("usage", UsageEvent {
+                        conversation_id: conversation_id.clone(),
+                        prompt_tokens: usage.prompt_tokens,
+                        completion_tokens: usage.completion_tokens,
+                        total_tokens: usage.total_tokens,
+                        cached_tokens: usage.cached_tokens,
+                    });
+                }
+                ManagerEvent::Finished(finish_reason) => {
+                    let _ = app.emit("response_finished", ResponseFinishedEvent {
+                        conversation_id: conversation_id.clone(),
+                        finish_reason: finish_reason.into(),
+                    });
+                }
+                ManagerEvent::Compacted(summarized_messages) => {
+                    let _ = app.emit("context_compacted", ContextCompactedEvent {
+                        conversation_id: conversation_id.clone(),
+                        summarized_messages: summarized_messages as u32,
+                    });
+                }
+                ManagerEvent::Renamed(name) => {
+                    let _ = app.emit("conversation_renamed", ConversationRenamedEvent {
+                        conversation_id: conversation_id.clone(),
+                        name,
+                    });
+                }
+                ManagerEvent::HistoryTrimmed(dropped_messages) => {
+                    let _ = app.emit("history_trimmed", HistoryTrimmedEvent {
+                        conversation_id: conversation_id.clone(),
+                        dropped_messages: dropped_messages as u32,
+                    });
+                }
+                ManagerEvent::Error(err) => {
+                    log_message(&format!("MANAGER ERROR [{}]: {}", conversation_id.as_str(), err));
+                    let _ = app.emit("error", ErrorEvent::new(conversation_id.clone(), &err));
                     state.set_processing(&conversation_id, false).await;
                 }
                 ManagerEvent::ModelChanged(name) => {
@@ -209,6 +391,17 @@ pub async fn start_event_receiver_loop(app: AppHandle, state: Arc<AppState>) {
                         turn_id,
                     });
                 }
+                ManagerEvent::Cancelled(resolved_messages) => {
+                    let messages: Vec<DisplayMessage> = resolved_messages
+                        .iter()
+                        .map(DisplayMessage::from)
+                        .collect();
+                    let _ = app.emit("cancelled", CancelledEvent {
+                        conversation_id: conversation_id.clone(),
+                        messages,
+                    });
+                    state.set_processing(&conversation_id, false).await;
+                }
             }
         }
     });
@@ -226,6 +419,104 @@ pub async fn clear_history(
     Ok(())
 }
 
+/// Cancel the in-flight request for a conversation
+#[tauri::command]
+pub async fn cancel_request(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    keep_partial: bool,
+) -> Result<(), String> {
+    let managers = state.managers.lock().await;
+    let manager = managers.get(&conversation_id).ok_or("Conversation not loaded")?;
+    manager.cancel(keep_partial);
+    Ok(())
+}
+
+/// Make tool calls in a conversation wait for the user's approval
+#[tauri::command]
+pub async fn set_require_tool_approval(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    required: bool,
+) -> Result<(), String> {
+    let mut managers = state.managers.lock().await;
+    let manager = managers.get_mut(&conversation_id).ok_or("Conversation not loaded")?;
+    manager.set_require_tool_approval(required);
+    Ok(())
+}
+
+/// Answer a tool_approval_request event
+#[tauri::command]
+pub async fn respond_tool_approval(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    tool_call_id: String,
+    approved: bool,
+    message: Option<String>,
+) -> Result<(), String> {
+    let approval = match (approved, message) {
+        (true, _) => ToolApproval::Approve,
+        (false, Some(message)) => ToolApproval::DenyWithMessage(message),
+        (false, None) => ToolApproval::Deny,
+    };
+    let managers = state.managers.lock().await;
+    let manager = managers.get(&conversation_id).ok_or("Conversation not loaded")?;
+    manager
+        .respond_to_tool_approval(&tool_call_id, approval)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the system prompt for a conversation
+#[tauri::command]
+pub async fn get_system_prompt(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+) -> Result<Option<String>, String> {
+    let managers = state.managers.lock().await;
+    let manager = managers.get(&conversation_id).ok_or("Conversation not loaded")?;
+    Ok(manager.system_message().await)
+}
+
+/// Set or clear (None/empty) the system prompt for a conversation
+#[tauri::command]
+pub async fn set_system_prompt(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    prompt: Option<String>,
+) -> Result<(), String> {
+    let managers = state.managers.lock().await;
+    let manager = managers.get(&conversation_id).ok_or("Conversation not loaded")?;
+    manager
+        .set_system_message(prompt)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Set the sampling settings (temperature, top_p, ...) for a conversation
+#[tauri::command]
+pub async fn set_generation_params(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    params: GenerationParams,
+) -> Result<(), String> {
+    let mut managers = state.managers.lock().await;
+    let manager = managers.get_mut(&conversation_id).ok_or("Conversation not loaded")?;
+    manager.set_generation_params(params.into());
+    Ok(())
+}
+
+/// Summarize the older part of a conversation so less context is sent to the model
+#[tauri::command]
+pub async fn compact_conversation(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+) -> Result<(), String> {
+    let managers = state.managers.lock().await;
+    let manager = managers.get(&conversation_id).ok_or("Conversation not loaded")?;
+    manager.compact();
+    Ok(())
+}
+
 /// Set the model for a conversation
 #[tauri::command]
 pub async fn set_model(
@@ -236,8 +527,7 @@ pub async fn set_model(
 ) -> Result<String, String> {
     let full_model_id = format!("{}/{}", provider, model_id);
 
-    let new_model = create_model(&full_model_id)
-        .map_err(|e| format!("Failed to create model: {}", e))?;
+    let new_model = create_chat_model(&full_model_id)?;
 
     let display_name = model_id
         .split('/')
@@ -251,8 +541,7 @@ pub async fn set_model(
         manager.set_model(new_model, full_model_id.clone());
     }
 
-    *state.model_id.lock().await = full_model_id.clone();
-    *state.model_name.lock().await = display_name.clone();
+    set_current_model(&state, &full_model_id).await;
 
     // Save as default model in settings
     let mut settings = config::Settings::load();
@@ -264,33 +553,35 @@ pub async fn set_model(
     Ok(display_name)
 }
 
+/// Frontend view of a provider's model
+fn model_info(provider_name: &str, model: &llm::ModelInfo) -> ModelInfo {
+    ModelInfo {
+        id: model.definition.id.clone(),
+        display_name: model.definition.name().to_string(),
+        provider: provider_name.to_string(),
+        capabilities: model.definition.capabilities.iter().map(|c| format!("{:?}", c)).collect(),
+        context_window: model.definition.context_window,
+        max_output_tokens: model.definition.max_output_tokens,
+    }
+}
+
 /// List available models from all providers
+///
+/// Lists come from the model catalog's cache when it has them, so this
+/// doesn't wait on the network once the providers have been listed.
 #[tauri::command]
-pub async fn list_models(_state: State<'_, Arc<AppState>>) -> Result<Vec<ModelInfo>, String> {
+pub async fn list_models(state: State<'_, Arc<AppState>>) -> Result<Vec<ModelInfo>, String> {
     use llm::ModelCapability;
 
     let mut all_models = Vec::new();
 
-    for (provider_name, result) in list_all_models().await {
+    for (provider_name, result) in state.model_catalog.list_all_models().await {
         if let Ok(models) = result {
             for m in models {
                 if !m.definition.has_capability(&ModelCapability::Text) {
                     continue;
                 }
-
-                let capabilities: Vec<String> = m
-                    .definition
-                    .capabilities
-                    .iter()
-                    .map(|c| format!("{:?}", c))
-                    .collect();
-                all_models.push(ModelInfo {
-                    id: m.definition.id.clone(),
-                    display_name: m.definition.name().to_string(),
-                    provider: provider_name.clone(),
-                    capabilities,
-                    context_window: m.definition.context_window,
-                });
+                all_models.push(model_info(&provider_name, &m));
             }
         }
     }
@@ -298,18 +589,77 @@ pub async fn list_models(_state: State<'_, Arc<AppState>>) -> Result<Vec<ModelIn
     Ok(all_models)
 }
 
-/// List all conversations for the current user
+/// List every model of one provider (or of all providers when None), for
+/// `/models [provider]`
+///
+/// Unlike list_models this keeps non-text models and reports providers that
+/// failed to list (e.g. no API key) instead of leaving them out. The lists
+/// are fetched afresh, updating the model catalog.
+#[tauri::command]
+pub async fn list_provider_models(
+    state: State<'_, Arc<AppState>>,
+    provider: Option<String>,
+) -> Result<Vec<ProviderModels>, String> {
+    let results = state.model_catalog.refresh(provider.as_deref()).await;
+
+    Ok(results
+        .into_iter()
+        .map(|(provider, result)| match result {
+            Ok(models) => ProviderModels {
+                models: models.iter().map(|m| model_info(&provider, m)).collect(),
+                provider,
+                error: None,
+            },
+            Err(e) => ProviderModels { provider, models: Vec::new(), error: Some(e.to_string()) },
+        })
+        .collect())
+}
+
+/// List the current user's conversations, pinned first.
+/// Archived conversations are only included when `include_archived` is set.
 #[tauri::command]
-pub async fn list_conversations(state: State<'_, Arc<AppState>>) -> Result<Vec<ConversationInfo>, String> {
+pub async fn list_conversations(
+    state: State<'_, Arc<AppState>>,
+    include_archived: Option<bool>,
+) -> Result<Vec<ConversationInfo>, String> {
     let stores = state.get_stores()?;
     let user_id = state.user_id.lock().await.clone();
 
+    let options = ConversationListOptions {
+        include_archived: include_archived.unwrap_or(false),
+        ..Default::default()
+    };
     let entities = stores
         .entity()
-        .list_entities(&user_id, Some(&EntityType::conversation()))
+        .list_conversations(&user_id, &options)
         .await
         .map_err(|e| format!("Failed to list conversations: {}", e))?;
 
+    conversation_infos(&stores, entities).await
+}
+
+/// List the user's conversations with a given tag
+#[tauri::command]
+pub async fn list_conversations_by_tag(
+    state: State<'_, Arc<AppState>>,
+    tag: String,
+) -> Result<Vec<ConversationInfo>, String> {
+    let stores = state.get_stores()?;
+    let user_id = state.user_id.lock().await.clone();
+
+    let entities = stores
+        .entity()
+        .list_conversations_by_tag(&user_id, &tag)
+        .await
+        .map_err(|e| format!("Failed to list conversations: {}", e))?;
+
+    conversation_infos(&stores, entities).await
+}
+
+async fn conversation_infos(
+    stores: &AppStores,
+    entities: Vec<StoredEntity>,
+) -> Result<Vec<ConversationInfo>, String> {
     let mut result = Vec::with_capacity(entities.len());
     for entity in entities {
         // Get turn count for this conversation
@@ -318,7 +668,12 @@ pub async fn list_conversations(state: State<'_, Arc<AppState>>) -> Result<Vec<C
             .get_turn_count(&entity.id)
             .await
             .unwrap_or(0);
-        result.push(ConversationInfo::from_entity(&entity, turn_count));
+        let tags = stores
+            .entity()
+            .get_conversation_tags(&entity.id)
+            .await
+            .unwrap_or_default();
+        result.push(ConversationInfo::from_entity(&entity, turn_count, tags));
     }
 
     Ok(result)
@@ -333,12 +688,12 @@ pub async fn load_conversation(
 ) -> Result<Vec<DisplayMessage>, String> {
     let stores = state.get_stores()?;
     let coordinator = state.get_coordinator()?;
-    let model_id_str = state.model_id.lock().await.clone();
 
     // Check if already loaded
     {
         let managers = state.managers.lock().await;
         if let Some(manager) = managers.get(&conversation_id) {
+            set_current_model(&state, manager.model_id()).await;
             // Use messages_for_display to preserve turn_id for alternates enrichment
             let messages: Vec<DisplayMessage> = manager
                 .messages_for_display()
@@ -365,15 +720,20 @@ pub async fn load_conversation(
 
     let mcp_registry = state.get_mcp_registry()?;
 
-    let model = create_model(&model_id_str)
-        .map_err(|e| format!("Failed to create model: {}", e))?;
+    // Restore the conversation's last model, falling back to the current default
+    let model_id_str = match coordinator.get_last_model(&conversation_id).await {
+        Ok(Some(last_model)) => last_model,
+        _ => state.model_id.lock().await.clone(),
+    };
+    let model = create_chat_model(&model_id_str)?;
+    set_current_model(&state, &model_id_str).await;
 
     let document_resolver: Arc<dyn DocumentResolver> = stores.document();
     let event_tx = state.event_sender();
     let user_id = state.user_id.lock().await.clone();
 
     // Create manager (context is injected via enricher in McpAgent)
-    let manager = ConversationManager::new(
+    let mut manager = ConversationManager::new(
         session,
         coordinator,
         model,
@@ -383,6 +743,9 @@ pub async fn load_conversation(
         user_id,
         event_tx,
     );
+    manager.set_text_delta_interval(Some(DEFAULT_TEXT_DELTA_INTERVAL));
+    manager.set_auto_name(auto_name_config());
+    manager.set_compaction(compaction_config());
     state.managers.lock().await.insert(conversation_id.clone(), manager);
 
     // Enrich with alternates
@@ -390,13 +753,20 @@ pub async fn load_conversation(
     Ok(messages)
 }
 
-/// Create a new conversation and load its manager
+/// Create a new conversation and load its manager, optionally seeded from
+/// a template (system prompt, model and sampling settings)
 #[tauri::command]
 pub async fn new_conversation(
     app: AppHandle,
     state: State<'_, Arc<AppState>>,
     name: Option<String>,
+    template: Option<String>,
 ) -> Result<String, String> {
+    let template = template
+        .map(|name| noema_core::templates::get_template(&name))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
     let stores = state.get_stores()?;
     let coordinator = state.get_coordinator()?;
     let user_id = state.user_id.lock().await.clone();
@@ -414,17 +784,20 @@ pub async fn new_conversation(
 
     let session = Session::new(coordinator.clone(), conv_id.clone());
 
-    let model_id_str = state.model_id.lock().await.clone();
+    let model_id_str = match template.as_ref().and_then(|t| t.model_id.clone()) {
+        Some(model_id) => model_id,
+        None => state.model_id.lock().await.clone(),
+    };
     let mcp_registry = state.get_mcp_registry()?;
 
-    let model = create_model(&model_id_str)
-        .map_err(|e| format!("Failed to create model: {}", e))?;
+    let model = create_chat_model(&model_id_str)?;
+    set_current_model(&state, &model_id_str).await;
 
     let document_resolver: Arc<dyn DocumentResolver> = stores.document();
     let event_tx = state.event_sender();
 
     // Create manager (context is injected via enricher in McpAgent)
-    let manager = ConversationManager::new(
+    let mut manager = ConversationManager::new(
         session,
         coordinator,
         model,
@@ -434,11 +807,39 @@ pub async fn new_conversation(
         user_id,
         event_tx,
     );
+    manager.set_text_delta_interval(Some(DEFAULT_TEXT_DELTA_INTERVAL));
+    manager.set_auto_name(auto_name_config());
+    manager.set_compaction(compaction_config());
+    if let Some(template) = &template {
+        noema_core::templates::apply_template(&mut manager, template)
+            .await
+            .map_err(|e| format!("Failed to apply template: {}", e))?;
+    }
     state.managers.lock().await.insert(conv_id.clone(), manager);
 
     Ok(conv_id.as_str().to_string())
 }
 
+/// List conversation templates (built-in and user-defined)
+#[tauri::command]
+pub fn list_templates() -> Result<Vec<ConversationTemplate>, String> {
+    noema_core::templates::list_templates()
+        .map(|templates| templates.into_iter().map(ConversationTemplate::from).collect())
+        .map_err(|e| e.to_string())
+}
+
+/// Add or replace a user-defined conversation template
+#[tauri::command]
+pub fn save_template(template: ConversationTemplate) -> Result<(), String> {
+    noema_core::templates::save_template(template.into()).map_err(|e| e.to_string())
+}
+
+/// Delete a user-defined conversation template
+#[tauri::command]
+pub fn delete_template(name: String) -> Result<bool, String> {
+    noema_core::templates::delete_template(&name).map_err(|e| e.to_string())
+}
+
 /// Delete a conversation
 #[tauri::command]
 pub async fn delete_conversation(
@@ -480,6 +881,36 @@ pub async fn rename_conversation(
         .map_err(|e| format!("Failed to rename conversation: {}", e))
 }
 
+/// Tag a conversation
+#[tauri::command]
+pub async fn add_conversation_tag(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    tag: String,
+) -> Result<(), String> {
+    let stores = state.get_stores()?;
+    stores
+        .entity()
+        .add_conversation_tag(&conversation_id, &tag)
+        .await
+        .map_err(|e| format!("Failed to tag conversation: {}", e))
+}
+
+/// Remove a tag from a conversation
+#[tauri::command]
+pub async fn remove_conversation_tag(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    tag: String,
+) -> Result<(), String> {
+    let stores = state.get_stores()?;
+    stores
+        .entity()
+        .remove_conversation_tag(&conversation_id, &tag)
+        .await
+        .map_err(|e| format!("Failed to untag conversation: {}", e))
+}
+
 /// Get whether the current conversation is marked as private
 #[tauri::command]
 pub async fn get_conversation_private(
@@ -521,6 +952,47 @@ pub async fn set_conversation_private(
         .map_err(|e| format!("Failed to set conversation privacy: {}", e))
 }
 
+/// Pin or unpin a conversation
+#[tauri::command]
+pub async fn set_conversation_pinned(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    pinned: bool,
+) -> Result<(), String> {
+    let stores = state.get_stores()?;
+
+    let mut entity = stores
+        .entity()
+        .get_entity(&conversation_id)
+        .await
+        .map_err(|e| format!("Failed to get conversation: {}", e))?
+        .ok_or_else(|| "Conversation not found".to_string())?;
+
+    entity.set_pinned(pinned);
+
+    stores
+        .entity()
+        .update_entity(&conversation_id, &entity)
+        .await
+        .map_err(|e| format!("Failed to pin conversation: {}", e))
+}
+
+/// Archive or restore a conversation
+#[tauri::command]
+pub async fn set_conversation_archived(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    archived: bool,
+) -> Result<(), String> {
+    let stores = state.get_stores()?;
+    let result = if archived {
+        stores.entity().archive_entity(&conversation_id).await
+    } else {
+        stores.entity().unarchive_entity(&conversation_id).await
+    };
+    result.map_err(|e| format!("Failed to archive conversation: {}", e))
+}
+
 /// Get current model name
 #[tauri::command]
 pub async fn get_model_name(state: State<'_, Arc<AppState>>) -> Result<String, String> {
@@ -615,6 +1087,7 @@ pub async fn get_span_messages(
             turn_id: None,
             span_id: Some(span_id.clone()),
             alternates: None,
+            created_at: Some(m.message.created_at),
         });
     }
 
@@ -683,6 +1156,69 @@ pub async fn regenerate_response(
     Ok(())
 }
 
+/// Regenerate the response to the last user message
+#[tauri::command]
+pub async fn regenerate_last_response(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    tool_config: Option<ToolConfig>,
+) -> Result<(), String> {
+    let core_tool_config = match tool_config {
+        Some(tc) => CoreToolConfig {
+            enabled: tc.enabled,
+            server_ids: tc.server_ids,
+            tool_names: tc.tool_names,
+        },
+        None => CoreToolConfig::all_enabled(),
+    };
+
+    let managers = state.managers.lock().await;
+    let manager = managers.get(&conversation_id).ok_or("Conversation not loaded")?;
+    manager
+        .regenerate_last(core_tool_config)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Continue the last response where the output token limit cut it off
+#[tauri::command]
+pub async fn continue_response(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+) -> Result<(), String> {
+    let managers = state.managers.lock().await;
+    let manager = managers.get(&conversation_id).ok_or("Conversation not loaded")?;
+    manager.continue_response().await.map_err(|e| e.to_string())
+}
+
+/// Replace the text of the last user message and regenerate the response
+#[tauri::command]
+pub async fn edit_and_resend(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    text: String,
+    tool_config: Option<ToolConfig>,
+) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("Message must have text".to_string());
+    }
+
+    let core_tool_config = match tool_config {
+        Some(tc) => CoreToolConfig {
+            enabled: tc.enabled,
+            server_ids: tc.server_ids,
+            tool_names: tc.tool_names,
+        },
+        None => CoreToolConfig::all_enabled(),
+    };
+
+    let managers = state.managers.lock().await;
+    let manager = managers.get(&conversation_id).ok_or("Conversation not loaded")?;
+    manager
+        .edit_and_resend(text, core_tool_config)
+        .map_err(|e| e.to_string())
+}
+
 /// Fork a conversation at a specific turn
 ///
 /// Creates a new conversation entity with copied selections and links them
@@ -706,6 +1242,60 @@ pub async fn fork_conversation(
     Ok(new_conversation_id.as_str().to_string())
 }
 
+/// Fork a conversation after the message at `message_index` on its current
+/// path (0-based), or after the last message when None
+///
+/// Returns the new conversation ID.
+#[tauri::command]
+pub async fn fork_conversation_at_message(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+    message_index: Option<u32>,
+) -> Result<String, String> {
+    let coordinator = state.get_coordinator()?;
+
+    let new_conversation_id = coordinator
+        .fork_conversation_at_message(&conversation_id, message_index.map(|i| i as usize))
+        .await
+        .map_err(|e| format!("Failed to fork conversation: {}", e))?;
+
+    Ok(new_conversation_id.as_str().to_string())
+}
+
+/// Export a conversation as JSON (a list of chat messages)
+#[tauri::command]
+pub async fn export_conversation(
+    state: State<'_, Arc<AppState>>,
+    conversation_id: ConversationId,
+) -> Result<String, String> {
+    let coordinator = state.get_coordinator()?;
+    coordinator
+        .export_conversation(&conversation_id)
+        .await
+        .map_err(|e| format!("Failed to export conversation: {}", e))
+}
+
+/// Import a conversation from JSON produced by `export_conversation`.
+///
+/// # Returns
+/// The new conversation's ID as a string.
+#[tauri::command]
+pub async fn import_conversation(
+    state: State<'_, Arc<AppState>>,
+    json: String,
+    name: Option<String>,
+) -> Result<String, String> {
+    let coordinator = state.get_coordinator()?;
+    let user_id = state.user_id.lock().await.clone();
+
+    let conversation_id = coordinator
+        .import_conversation(&user_id, name.as_deref(), &json)
+        .await
+        .map_err(|e| format!("Failed to import conversation: {}", e))?;
+
+    Ok(conversation_id.as_str().to_string())
+}
+
 /// Select a specific span at a turn
 #[tauri::command]
 pub async fn select_span(
@@ -998,15 +1588,14 @@ pub async fn edit_message(
 
     let model_id_str = state.model_id.lock().await.clone();
     let mcp_registry = state.get_mcp_registry()?;
-    let model = create_model(&model_id_str)
-        .map_err(|e| format!("Failed to create model: {}", e))?;
+    let model = create_chat_model(&model_id_str)?;
 
     let document_resolver: Arc<dyn DocumentResolver> = stores.document();
     let event_tx = state.event_sender();
     let user_id = state.user_id.lock().await.clone();
 
     // Create manager (context is injected via enricher in McpAgent)
-    let manager = ConversationManager::new(
+    let mut manager = ConversationManager::new(
         session,
         coordinator,
         model,
@@ -1016,6 +1605,9 @@ pub async fn edit_message(
         user_id,
         event_tx,
     );
+    manager.set_text_delta_interval(Some(DEFAULT_TEXT_DELTA_INTERVAL));
+    manager.set_auto_name(auto_name_config());
+    manager.set_compaction(compaction_config());
 
     // Trigger AI to respond to the edited message
     let core_tool_config = match tool_config {
@@ -1,12 +1,16 @@
 //! File-related Tauri commands
 
+use serde::Serialize;
 use tauri::{AppHandle, State};
 use tauri_plugin_dialog::DialogExt;
 use std::sync::Arc;
+use ts_rs::TS;
 
+use llm::ContentBlock;
 use noema_core::storage::ids::AssetId;
 use crate::logging::log_message;
 use crate::state::AppState;
+use crate::types::DisplayInputContent;
 
 /// Save binary data to a file using the system save dialog
 #[tauri::command]
@@ -70,19 +74,88 @@ pub async fn save_file(
 // which enables proper HTTP caching. See lib.rs for the protocol handler.
 // Frontend fetches assets at: noema-asset://localhost/{asset_id}?mime_type={mime}
 
+/// Result of storing an asset
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct StoredAssetResponse {
+    #[ts(type = "string")]
+    pub asset_id: AssetId,
+    /// SHA-256 hash of the content; identical bytes always yield the same hash
+    pub blob_hash: String,
+}
+
 /// Store an asset in blob storage
 ///
-/// Returns the asset ID (UUID) for referencing in messages.
+/// Returns the asset ID (UUID) for referencing in messages, along with the
+/// content hash. Blob storage is content-addressed, so uploading bytes that
+/// are already stored does not write them again.
 #[tauri::command]
 pub async fn store_asset(
     state: State<'_, Arc<AppState>>,
     data: String,      // base64 encoded
     mime_type: String,
-) -> Result<AssetId, String> {
+) -> Result<StoredAssetResponse, String> {
     let coordinator = state.get_coordinator()?;
 
-    coordinator
-        .store_asset(&data, &mime_type)
+    let (asset_id, blob_hash) = coordinator
+        .store_asset_with_hash(&data, &mime_type)
         .await
-        .map_err(|e| format!("Failed to store asset: {}", e))
+        .map_err(|e| format!("Failed to store asset: {}", e))?;
+
+    Ok(StoredAssetResponse {
+        asset_id,
+        blob_hash: blob_hash.as_str().to_string(),
+    })
+}
+
+/// A file read from disk and turned into message content
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct AttachedFile {
+    /// File name, for showing the attachment before it is sent
+    pub name: String,
+    /// Content blocks to include with the next message
+    pub content: Vec<DisplayInputContent>,
+}
+
+/// Read an image or PDF from a path and process it into content blocks
+///
+/// PDFs become their extracted text plus page or embedded images.
+#[tauri::command]
+pub async fn attach_file(path: String) -> Result<AttachedFile, String> {
+    log_message(&format!("attach_file called: path={}", path));
+
+    let path = std::path::PathBuf::from(path);
+    let (name, blocks) = tokio::task::spawn_blocking(move || {
+        let attachment = noema_ext::Attachment::from_path(&path)?;
+        let blocks = noema_ext::process_attachment(&attachment)?;
+        Ok::<_, String>((attachment.name.unwrap_or_default(), blocks))
+    })
+    .await
+    .map_err(|e| format!("Failed to process attachment: {}", e))??;
+
+    let content = blocks
+        .into_iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text { text } => Some(DisplayInputContent::Text { text }),
+            ContentBlock::Image { data, mime_type } => Some(DisplayInputContent::Image { data, mime_type }),
+            ContentBlock::Audio { data, mime_type } => Some(DisplayInputContent::Audio { data, mime_type }),
+            _ => None,
+        })
+        .collect();
+
+    Ok(AttachedFile { name, content })
+}
+
+#[cfg(test)]
+mod ts_export {
+    use super::*;
+
+    #[test]
+    fn export_types() {
+        StoredAssetResponse::export_all().expect("Failed to export StoredAssetResponse");
+        AttachedFile::export_all().expect("Failed to export AttachedFile");
+    }
 }
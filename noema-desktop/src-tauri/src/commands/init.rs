@@ -1,16 +1,20 @@
 //! Application initialization command
 
 use config::PathManager;
-use noema_core::mcp::{start_auto_connect, ServerStatus};
+use noema_core::mcp::{start_auto_connect, start_health_monitor, Sampler};
 use noema_core::storage::coordinator::StorageCoordinator;
 use noema_core::storage::traits::UserStore;
 use noema_core::storage::{FsBlobStore, SqliteStore, Stores};
 use crate::state::AppStorage;
 use noema_core::McpRegistry;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::broadcast;
 
 use crate::commands::chat::start_event_receiver_loop;
+use crate::commands::chat::create_chat_model;
+use crate::commands::mcp::{status_string, EventSamplingApprover};
 use crate::core_server::{self, CoreServerState};
 use crate::logging::log_message;
 use crate::state::AppState;
@@ -80,7 +84,7 @@ async fn do_init(app: AppHandle, state: Arc<AppState>) -> Result<String, String>
     log_message(&format!("Default model set: {}", model_name));
 
     // Initialize MCP registry (global, not per-conversation)
-    let mcp_registry = init_mcp(&state)?;
+    let mcp_registry = init_mcp(&app, &state).await?;
     log_message("MCP registry loaded");
 
     // Start embedded Noema Core MCP server (provides spawn_agent tool)
@@ -138,34 +142,38 @@ async fn start_core_server(
     }
 }
 
-/// Start auto-connect for all configured MCP servers
-async fn start_mcp_auto_connect(app: AppHandle, mcp_registry: Arc<tokio::sync::Mutex<McpRegistry>>) {
+/// Interval between MCP server health checks
+const MCP_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
 
-    // Create callback that emits events to frontend
-    let app_handle = app.clone();
-    let on_status_change: Arc<dyn Fn(&str, &ServerStatus) + Send + Sync> =
-        Arc::new(move |server_id: &str, status: &ServerStatus| {
-            let status_str = match status {
-                ServerStatus::Disconnected => "disconnected".to_string(),
-                ServerStatus::Connected => "connected".to_string(),
-                ServerStatus::Retrying { attempt } => format!("retrying:{}", attempt),
-                ServerStatus::RetryStopped { last_error } => format!("stopped:{}", last_error),
+/// Start auto-connect and health monitoring for configured MCP servers
+async fn start_mcp_auto_connect(app: AppHandle, mcp_registry: Arc<tokio::sync::Mutex<McpRegistry>>) {
+    // Forward status changes to the frontend
+    let mut status_rx = mcp_registry.lock().await.subscribe_status();
+    tokio::spawn(async move {
+        loop {
+            let (server_id, status) = match status_rx.recv().await {
+                Ok(change) => change,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
             };
+            let status_str = status_string(&status);
 
             log_message(&format!("MCP server '{}' status: {}", server_id, status_str));
 
-            // Emit event to frontend
-            let _ = app_handle.emit(
+            let _ = app.emit(
                 "mcp_server_status",
                 serde_json::json!({
                     "server_id": server_id,
                     "status": status_str,
                 }),
             );
-        });
+        }
+    });
 
-    let count = start_auto_connect(mcp_registry, Some(on_status_change)).await;
+    let count = start_auto_connect(Arc::clone(&mcp_registry), None).await;
     log_message(&format!("Started auto-connect for {} MCP servers", count));
+
+    start_health_monitor(mcp_registry, MCP_HEALTH_CHECK_INTERVAL);
 }
 
 async fn init_storage(state: &AppState) -> Result<(), String> {
@@ -250,8 +258,27 @@ async fn init_user(state: &AppState) -> Result<(), String> {
 }
 
 /// Initialize MCP registry (global, shared across all engines)
-fn init_mcp(state: &AppState) -> Result<Arc<tokio::sync::Mutex<McpRegistry>>, String> {
-    let registry = McpRegistry::load().unwrap_or_else(|_| McpRegistry::new(Default::default()));
+///
+/// Servers allowed to sample run completions on the current model (or one
+/// listed in `sampling_models`), each after the user agrees.
+async fn init_mcp(app: &AppHandle, state: &AppState) -> Result<Arc<tokio::sync::Mutex<McpRegistry>>, String> {
+    let mut registry = McpRegistry::load().unwrap_or_else(|_| McpRegistry::new(Default::default()));
+    let model_id = state.model_id.lock().await.clone();
+    match create_chat_model(&model_id) {
+        Ok(model) => {
+            let approver = EventSamplingApprover {
+                app: app.clone(),
+                pending: Arc::clone(&state.pending_sampling),
+            };
+            let sampler = Sampler::new(model)
+                .with_approver(Arc::new(approver))
+                .with_allowed_models(config::Settings::load().sampling_models);
+            let sampler = Arc::new(sampler);
+            let _ = state.sampler.set(Arc::clone(&sampler));
+            registry.set_sampler(Some(sampler));
+        }
+        Err(e) => log_message(&format!("MCP sampling disabled: {}", e)),
+    }
     let registry_arc = Arc::new(tokio::sync::Mutex::new(registry));
 
     let _ = state.mcp_registry.set(registry_arc.clone());
@@ -1,14 +1,38 @@
 //! MCP (Model Context Protocol) server commands
 
-use noema_core::mcp::{spawn_retry_task, ServerStatus};
-use noema_core::{AuthMethod, ServerConfig};
+use noema_core::mcp::{spawn_retry_task, OAuthTokens, Pkce, SamplingApprover, SamplingRequest, ServerStatus};
+use noema_core::{AuthMethod, ServerConfig, Transport};
+use rmcp::model::ResourceContents;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager, State};
 
 use crate::logging::log_message;
 use crate::oauth_callback;
-use crate::state::{save_pending_oauth_states, AppState};
-use crate::types::{AddMcpServerRequest, McpServerInfo, McpToolInfo};
+use crate::state::{save_pending_oauth_states, AppState, PendingOAuth, PendingSampling};
+use crate::commands::files::AttachedFile;
+use crate::types::{
+    AddMcpServerRequest, DisplayInputContent, McpPromptArgument, McpPromptInfo, McpResourceInfo,
+    McpSamplingRequestEvent, McpServerInfo, McpToolInfo,
+};
+
+/// HTTP URL of a server, for OAuth and .well-known discovery
+fn server_url(config: &ServerConfig) -> Result<&str, String> {
+    config
+        .transport
+        .url()
+        .ok_or_else(|| "OAuth is only supported for HTTP servers".to_string())
+}
+
+/// Format a server status for the frontend (e.g. "connected", "retrying:2")
+pub(crate) fn status_string(status: &ServerStatus) -> String {
+    match status {
+        ServerStatus::Disconnected => "disconnected".to_string(),
+        ServerStatus::Connected => "connected".to_string(),
+        ServerStatus::Retrying { attempt } => format!("retrying:{}", attempt),
+        ServerStatus::RetryStopped { last_error } => format!("stopped:{}", last_error),
+    }
+}
 
 /// List all configured MCP servers
 #[tauri::command]
@@ -32,18 +56,12 @@ pub async fn list_mcp_servers(state: State<'_, Arc<AppState>>) -> Result<Vec<Mcp
         };
 
         // Get server status
-        let server_status = registry.get_status(id);
-        let status = match &server_status {
-            ServerStatus::Disconnected => "disconnected".to_string(),
-            ServerStatus::Connected => "connected".to_string(),
-            ServerStatus::Retrying { attempt } => format!("retrying:{}", attempt),
-            ServerStatus::RetryStopped { last_error } => format!("stopped:{}", last_error),
-        };
+        let status = status_string(&registry.get_status(id));
 
         servers.push(McpServerInfo {
             id: id.to_string(),
             name: config.name.clone(),
-            url: config.url.clone(),
+            url: config.transport.to_string(),
             auth_type: auth_type.to_string(),
             is_connected,
             needs_oauth_login: config.auth.needs_oauth_login(),
@@ -51,6 +69,7 @@ pub async fn list_mcp_servers(state: State<'_, Arc<AppState>>) -> Result<Vec<Mcp
             status,
             auto_connect: config.auto_connect,
             auto_retry: config.auto_retry,
+            allow_sampling: config.allow_sampling,
         });
     }
 
@@ -116,12 +135,16 @@ pub async fn add_mcp_server(
 
     let config = ServerConfig {
         name: request.name,
-        url: request.url,
+        transport: Transport::Http { url: request.url },
         auth,
         use_well_known,
         auth_token: None,
         auto_connect: true,
         auto_retry: true,
+        tool_filter: None,
+        tool_timeouts: HashMap::new(),
+        max_tool_result_bytes: None,
+        allow_sampling: false,
     };
 
     let mcp_registry = state.get_mcp_registry()?;
@@ -196,12 +219,119 @@ pub async fn get_mcp_server_tools(
             name: tool.name.to_string(),
             description: tool.description.as_ref().map(|d| d.to_string()),
             server_id: server_id.clone(),
+            filtered_out: !server.config.is_tool_allowed(&tool.name),
         })
         .collect();
 
     Ok(tools)
 }
 
+/// Get resources (documents, files, ...) offered by a connected MCP server
+#[tauri::command]
+pub async fn get_mcp_server_resources(
+    state: State<'_, Arc<AppState>>,
+    server_id: String,
+) -> Result<Vec<McpResourceInfo>, String> {
+    let mcp_registry = state.get_mcp_registry()?;
+    let registry = mcp_registry.lock().await;
+
+    let server = registry
+        .get_connection(&server_id)
+        .ok_or("Server not connected")?;
+
+    let resources = server
+        .resources
+        .iter()
+        .map(|resource| McpResourceInfo {
+            uri: resource.uri.clone(),
+            name: resource.name.clone(),
+            description: resource.description.clone(),
+            mime_type: resource.mime_type.clone(),
+            server_id: server_id.clone(),
+        })
+        .collect();
+
+    Ok(resources)
+}
+
+/// Get prompt templates offered by a connected MCP server
+#[tauri::command]
+pub async fn get_mcp_server_prompts(
+    state: State<'_, Arc<AppState>>,
+    server_id: String,
+) -> Result<Vec<McpPromptInfo>, String> {
+    let mcp_registry = state.get_mcp_registry()?;
+    let registry = mcp_registry.lock().await;
+
+    let server = registry
+        .get_connection(&server_id)
+        .ok_or("Server not connected")?;
+
+    let prompts = server
+        .prompts
+        .iter()
+        .map(|prompt| McpPromptInfo {
+            name: prompt.name.clone(),
+            description: prompt.description.clone(),
+            arguments: prompt
+                .arguments
+                .iter()
+                .map(|argument| McpPromptArgument {
+                    name: argument.name.clone(),
+                    description: argument.description.clone(),
+                    required: argument.required,
+                })
+                .collect(),
+            server_id: server_id.clone(),
+        })
+        .collect();
+
+    Ok(prompts)
+}
+
+/// Read a resource from a connected MCP server, to attach to the next message
+///
+/// Text becomes text content; binary images and audio are kept, other binary
+/// contents are skipped.
+#[tauri::command]
+pub async fn read_mcp_resource(
+    state: State<'_, Arc<AppState>>,
+    server_id: String,
+    uri: String,
+) -> Result<AttachedFile, String> {
+    let mcp_registry = state.get_mcp_registry()?;
+    let registry = mcp_registry.lock().await;
+
+    let contents = registry
+        .read_resource(&server_id, &uri)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", uri, e))?;
+    let name = registry
+        .get_connection(&server_id)
+        .and_then(|server| server.resources.iter().find(|resource| resource.uri == uri))
+        .map(|resource| resource.name.clone())
+        .unwrap_or_else(|| uri.clone());
+
+    let content = contents
+        .into_iter()
+        .filter_map(|contents| match contents {
+            ResourceContents::TextResourceContents { text, .. } => Some(DisplayInputContent::Text { text }),
+            ResourceContents::BlobResourceContents { blob, mime_type, .. } => {
+                let mime_type = mime_type?;
+                if mime_type.starts_with("image/") {
+                    Some(DisplayInputContent::Image { data: blob, mime_type })
+                } else if mime_type.starts_with("audio/") {
+                    Some(DisplayInputContent::Audio { data: blob, mime_type })
+                } else {
+                    None
+                }
+            }
+        })
+        .collect();
+
+    Ok(AttachedFile { name, content })
+}
+
 /// Test connection to an MCP server (connect and immediately disconnect)
 #[tauri::command]
 pub async fn test_mcp_server(state: State<'_, Arc<AppState>>, server_id: String) -> Result<usize, String> {
@@ -268,7 +398,7 @@ async fn register_oauth_client(
 
     if !resp.status().is_success() {
         let error_text = resp.text().await.unwrap_or_default();
-        return Err(format!("Client registration failed: {}", error_text));
+        return Err(format!("Client registration failed: {}", config::redact(&error_text)));
     }
 
     let registration_response: serde_json::Value = resp
@@ -329,7 +459,7 @@ pub async fn start_mcp_oauth(
 
             // Fetch .well-known config if needed
             let well_known = if config.use_well_known {
-                Some(fetch_well_known(&config.url).await?)
+                Some(fetch_well_known(server_url(&config)?).await?)
             } else {
                 None
             };
@@ -370,13 +500,22 @@ pub async fn start_mcp_oauth(
             let final_client_id = client_id.clone();
             let final_client_secret = client_secret.clone();
 
-            // Build authorization URL with state parameter
+            // Build authorization URL with state parameter and PKCE challenge
             let state_param = uuid::Uuid::new_v4().to_string();
+            let pkce = Pkce::new();
 
-            // Store the state -> server_id mapping
+            // Store the state -> pending flow mapping, replacing any flow
+            // for this server that was abandoned or restarted
             {
                 let mut pending_states = state.pending_oauth_states.lock().await;
-                pending_states.insert(state_param.clone(), server_id.clone());
+                pending_states.retain(|_, pending| pending.server_id != server_id);
+                pending_states.insert(
+                    state_param.clone(),
+                    PendingOAuth {
+                        server_id: server_id.clone(),
+                        code_verifier: Some(pkce.verifier.clone()),
+                    },
+                );
                 if let Err(e) = save_pending_oauth_states(&pending_states) {
                     log_message(&format!("Warning: Failed to persist OAuth state: {}", e));
                 }
@@ -397,6 +536,8 @@ pub async fn start_mcp_oauth(
                 .append_pair("redirect_uri", &redirect_uri)
                 .append_pair("state", &state_param)
                 .append_pair("scope", &scope_str)
+                .append_pair("code_challenge", &pkce.challenge)
+                .append_pair("code_challenge_method", Pkce::METHOD)
                 .append_pair("access_type", "offline") // Request refresh token
                 .append_pair("prompt", "consent"); // Force consent to get refresh token
 
@@ -409,12 +550,17 @@ pub async fn start_mcp_oauth(
             let redirect_uri_clone = redirect_uri.clone();
             let scopes_clone = scopes.clone();
             let config_clone = config.clone();
+            let code_verifier = pkce.verifier;
 
             // Spawn background task to handle the callback
             tokio::spawn(async move {
                 log_message("Waiting for OAuth callback...");
 
-                match callback_server.wait_for_callback().await {
+                let callback = callback_server.wait_for_callback().await;
+                // The flow ends here whatever the outcome
+                take_pending_oauth(&app_clone.state::<Arc<AppState>>(), &state_param_clone).await;
+
+                match callback {
                     Ok((code, received_state)) => {
                         log_message(&format!("Received OAuth callback with state: {}", received_state));
 
@@ -426,16 +572,17 @@ pub async fn start_mcp_oauth(
                         }
 
                         // Exchange code for tokens
-                        match exchange_code_for_tokens(
+                        match noema_core::mcp::exchange_code(
                             &tok_url,
                             &code,
                             &redirect_uri_clone,
                             &final_client_id,
                             final_client_secret.as_deref(),
+                            Some(&code_verifier),
                         )
                         .await
                         {
-                            Ok((access_token, refresh_token, expires_in)) => {
+                            Ok(tokens) => {
                                 log_message("Successfully exchanged code for tokens");
 
                                 // Update the MCP config with the new tokens
@@ -443,9 +590,8 @@ pub async fn start_mcp_oauth(
                                     &app_clone,
                                     &server_id_clone,
                                     &config_clone,
-                                    access_token,
-                                    refresh_token,
-                                    expires_in,
+                                    &tok_url,
+                                    tokens,
                                     &scopes_clone,
                                 )
                                 .await
@@ -460,7 +606,7 @@ pub async fn start_mcp_oauth(
                             }
                             Err(e) => {
                                 log_message(&format!("Token exchange failed: {}", e));
-                                let _ = app_clone.emit("oauth_error", e);
+                                let _ = app_clone.emit("oauth_error", e.to_string());
                             }
                         }
                     }
@@ -477,102 +623,39 @@ pub async fn start_mcp_oauth(
     }
 }
 
-/// Exchange authorization code for access and refresh tokens
-async fn exchange_code_for_tokens(
-    token_url: &str,
-    code: &str,
-    redirect_uri: &str,
-    client_id: &str,
-    client_secret: Option<&str>,
-) -> Result<(String, Option<String>, Option<i64>), String> {
-    let http_client = reqwest::Client::new();
-
-    let mut params = vec![
-        ("grant_type", "authorization_code"),
-        ("code", code),
-        ("redirect_uri", redirect_uri),
-        ("client_id", client_id),
-    ];
-
-    if let Some(secret) = client_secret {
-        params.push(("client_secret", secret));
-    }
-
-    let resp = http_client
-        .post(token_url)
-        .form(&params)
-        .send()
-        .await
-        .map_err(|e| format!("Token exchange request failed: {}", e))?;
-
-    if !resp.status().is_success() {
-        let error_text = resp.text().await.unwrap_or_default();
-        return Err(format!("Token exchange failed: {}", error_text));
-    }
-
-    let token_response: serde_json::Value = resp
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse token response: {}", e))?;
-
-    let access_token = token_response["access_token"]
-        .as_str()
-        .ok_or("No access_token in response")?
-        .to_string();
-
-    let refresh_token = token_response["refresh_token"].as_str().map(String::from);
-
-    let expires_in = token_response["expires_in"].as_i64();
-
-    Ok((access_token, refresh_token, expires_in))
-}
-
 /// Save OAuth tokens to the MCP registry
 async fn save_oauth_tokens(
     app: &AppHandle,
     server_id: &str,
     config: &ServerConfig,
-    access_token: String,
-    refresh_token: Option<String>,
-    expires_in: Option<i64>,
+    tok_url: &str,
+    tokens: OAuthTokens,
     scopes: &[String],
 ) -> Result<(), String> {
     let state = app.state::<Arc<AppState>>();
     let mcp_registry = state.get_mcp_registry()?;
     let mut registry = mcp_registry.lock().await;
 
-    // Calculate expiration timestamp
-    let expires_at = expires_in.map(|secs| chrono::Utc::now().timestamp() + secs);
-
     // Get existing OAuth config and update with new tokens
-    let updated_auth = match &config.auth {
-        AuthMethod::OAuth {
-            client_id,
-            client_secret,
-            authorization_url,
-            token_url,
-            ..
-        } => AuthMethod::OAuth {
-            client_id: client_id.clone(),
-            client_secret: client_secret.clone(),
-            authorization_url: authorization_url.clone(),
-            token_url: token_url.clone(),
-            scopes: scopes.to_vec(),
-            access_token: Some(access_token),
-            refresh_token,
-            expires_at,
-        },
+    let mut updated_auth = config.auth.clone();
+    match &mut updated_auth {
+        AuthMethod::OAuth { scopes: stored, .. } => *stored = scopes.to_vec(),
         _ => return Err("Server is not configured for OAuth".to_string()),
-    };
+    }
+    updated_auth.set_tokens(tok_url, tokens);
 
     let updated_config = ServerConfig {
         name: config.name.clone(),
-        url: config.url.clone(),
+        transport: config.transport.clone(),
         auth: updated_auth,
         use_well_known: config.use_well_known,
         auth_token: None,
         auto_connect: config.auto_connect,
         auto_retry: config.auto_retry,
+        tool_filter: config.tool_filter.clone(),
+        tool_timeouts: config.tool_timeouts.clone(),
+        max_tool_result_bytes: config.max_tool_result_bytes,
+        allow_sampling: config.allow_sampling,
     };
 
     registry.add_server(server_id.to_string(), updated_config);
@@ -595,6 +678,7 @@ pub async fn complete_mcp_oauth(
     server_id: String,
     code: String,
 ) -> Result<(), String> {
+    let code_verifier = take_pending_verifier(&state, &server_id).await;
     let mcp_registry = state.get_mcp_registry()?;
     let mut registry = mcp_registry.lock().await;
 
@@ -609,15 +693,13 @@ pub async fn complete_mcp_oauth(
             client_id,
             client_secret,
             token_url,
-            authorization_url,
-            scopes,
             ..
         } => {
             // Get token URL
             let tok_url = if let Some(url) = token_url {
                 url.clone()
             } else if config.use_well_known {
-                let well_known = fetch_well_known(&config.url).await?;
+                let well_known = fetch_well_known(server_url(&config)?).await?;
                 well_known["token_endpoint"]
                     .as_str()
                     .ok_or("No token_endpoint in well-known config")?
@@ -628,74 +710,33 @@ pub async fn complete_mcp_oauth(
 
             // Use same redirect_uri as in start_mcp_oauth
             let redirect_uri = "noema://oauth/callback";
-            let http_client = reqwest::Client::new();
-
-            let mut params = vec![
-                ("grant_type", "authorization_code"),
-                ("code", &code),
-                ("redirect_uri", redirect_uri),
-                ("client_id", client_id.as_str()),
-            ];
-
-            let client_secret_str;
-            if let Some(secret) = client_secret {
-                client_secret_str = secret.clone();
-                params.push(("client_secret", &client_secret_str));
-            }
-
-            let resp = http_client
-                .post(&tok_url)
-                .form(&params)
-                .send()
-                .await
-                .map_err(|e| format!("Token exchange failed: {}", e))?;
-
-            if !resp.status().is_success() {
-                let error_text = resp.text().await.unwrap_or_default();
-                return Err(format!("Token exchange failed: {}", error_text));
-            }
-
-            let token_response: serde_json::Value = resp
-                .json()
-                .await
-                .map_err(|e| format!("Failed to parse token response: {}", e))?;
-
-            let access_token = token_response["access_token"]
-                .as_str()
-                .ok_or("No access_token in response")?
-                .to_string();
-
-            let refresh_token = token_response["refresh_token"].as_str().map(String::from);
-
-            let expires_in = token_response["expires_in"].as_i64();
-            let expires_at = expires_in.map(|exp| {
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs() as i64
-                    + exp
-            });
+            let tokens = noema_core::mcp::exchange_code(
+                &tok_url,
+                &code,
+                redirect_uri,
+                client_id,
+                client_secret.as_deref(),
+                code_verifier.as_deref(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
 
             // Update the server config with tokens
-            let updated_auth = AuthMethod::OAuth {
-                client_id: client_id.clone(),
-                client_secret: client_secret.clone(),
-                authorization_url: authorization_url.clone(),
-                token_url: Some(tok_url),
-                scopes: scopes.clone(),
-                access_token: Some(access_token),
-                refresh_token,
-                expires_at,
-            };
+            let mut updated_auth = config.auth.clone();
+            updated_auth.set_tokens(&tok_url, tokens);
 
             let updated_config = ServerConfig {
                 name: config.name.clone(),
-                url: config.url.clone(),
+                transport: config.transport.clone(),
                 auth: updated_auth,
                 use_well_known: config.use_well_known,
                 auth_token: None,
                 auto_connect: config.auto_connect,
                 auto_retry: config.auto_retry,
+                tool_filter: config.tool_filter.clone(),
+                tool_timeouts: config.tool_timeouts.clone(),
+                max_tool_result_bytes: config.max_tool_result_bytes,
+                allow_sampling: config.allow_sampling,
             };
 
             registry.add_server(server_id.clone(), updated_config);
@@ -716,11 +757,37 @@ pub async fn complete_mcp_oauth(
     }
 }
 
+/// Remove the pending OAuth flow started for a server, returning its PKCE verifier
+///
+/// Starting a flow replaces earlier ones for the same server, so there is at
+/// most one to find.
+async fn take_pending_verifier(state: &AppState, server_id: &str) -> Option<String> {
+    let state_param = state
+        .pending_oauth_states
+        .lock()
+        .await
+        .iter()
+        .find(|(_, pending)| pending.server_id == server_id)
+        .map(|(state_param, _)| state_param.clone())?;
+    take_pending_oauth(state, &state_param).await?.code_verifier
+}
+
+/// Remove the pending OAuth flow with the given state parameter
+async fn take_pending_oauth(state: &AppState, state_param: &str) -> Option<PendingOAuth> {
+    let mut pending_states = state.pending_oauth_states.lock().await;
+    let pending = pending_states.remove(state_param)?;
+    if let Err(e) = save_pending_oauth_states(&pending_states) {
+        log_message(&format!("Warning: Failed to update persisted OAuth state: {}", e));
+    }
+    Some(pending)
+}
+
 /// Internal function to complete OAuth (shared by command and deep link handler)
 pub async fn complete_oauth_internal(
     app: &AppHandle,
     server_id: &str,
     code: &str,
+    code_verifier: Option<&str>,
 ) -> Result<(), String> {
     let state = app.state::<Arc<AppState>>();
     let mcp_registry = state.get_mcp_registry()?;
@@ -737,15 +804,13 @@ pub async fn complete_oauth_internal(
             client_id,
             client_secret,
             token_url,
-            authorization_url,
-            scopes,
             ..
         } => {
             // Get token URL
             let tok_url = if let Some(url) = token_url {
                 url.clone()
             } else if config.use_well_known {
-                let well_known = fetch_well_known(&config.url).await?;
+                let well_known = fetch_well_known(server_url(&config)?).await?;
                 well_known["token_endpoint"]
                     .as_str()
                     .ok_or("No token_endpoint in well-known config")?
@@ -754,75 +819,35 @@ pub async fn complete_oauth_internal(
                 return Err("OAuth requires token_url or use_well_known".to_string());
             };
 
+            // Use same redirect_uri as in start_mcp_oauth
             let redirect_uri = "noema://oauth/callback";
-            let http_client = reqwest::Client::new();
-
-            let mut params = vec![
-                ("grant_type", "authorization_code"),
-                ("code", code),
-                ("redirect_uri", redirect_uri),
-                ("client_id", client_id.as_str()),
-            ];
-
-            let client_secret_str;
-            if let Some(secret) = client_secret {
-                client_secret_str = secret.clone();
-                params.push(("client_secret", &client_secret_str));
-            }
-
-            let resp = http_client
-                .post(&tok_url)
-                .form(&params)
-                .send()
-                .await
-                .map_err(|e| format!("Token exchange failed: {}", e))?;
-
-            if !resp.status().is_success() {
-                let error_text = resp.text().await.unwrap_or_default();
-                return Err(format!("Token exchange failed: {}", error_text));
-            }
-
-            let token_response: serde_json::Value = resp
-                .json()
-                .await
-                .map_err(|e| format!("Failed to parse token response: {}", e))?;
-
-            let access_token = token_response["access_token"]
-                .as_str()
-                .ok_or("No access_token in response")?
-                .to_string();
-
-            let refresh_token = token_response["refresh_token"].as_str().map(String::from);
-
-            let expires_in = token_response["expires_in"].as_i64();
-            let expires_at = expires_in.map(|exp| {
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs() as i64
-                    + exp
-            });
+            let tokens = noema_core::mcp::exchange_code(
+                &tok_url,
+                code,
+                redirect_uri,
+                client_id,
+                client_secret.as_deref(),
+                code_verifier,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
 
             // Update the server config with tokens
-            let updated_auth = AuthMethod::OAuth {
-                client_id: client_id.clone(),
-                client_secret: client_secret.clone(),
-                authorization_url: authorization_url.clone(),
-                token_url: Some(tok_url),
-                scopes: scopes.clone(),
-                access_token: Some(access_token),
-                refresh_token,
-                expires_at,
-            };
+            let mut updated_auth = config.auth.clone();
+            updated_auth.set_tokens(&tok_url, tokens);
 
             let updated_config = ServerConfig {
                 name: config.name.clone(),
-                url: config.url.clone(),
+                transport: config.transport.clone(),
                 auth: updated_auth,
                 use_well_known: config.use_well_known,
                 auth_token: None,
                 auto_connect: config.auto_connect,
                 auto_retry: config.auto_retry,
+                tool_filter: config.tool_filter.clone(),
+                tool_timeouts: config.tool_timeouts.clone(),
+                max_tool_result_bytes: config.max_tool_result_bytes,
+                allow_sampling: config.allow_sampling,
             };
 
             registry.add_server(server_id.to_string(), updated_config);
@@ -843,14 +868,70 @@ pub async fn complete_oauth_internal(
     }
 }
 
-/// Update auto-connect and auto-retry settings for an MCP server
+/// Approver that asks the UI with an `mcp_sampling_request` event and waits
+/// for `respond_mcp_sampling`
+pub struct EventSamplingApprover {
+    pub app: AppHandle,
+    pub pending: Arc<PendingSampling>,
+}
+
+#[async_trait::async_trait]
+impl SamplingApprover for EventSamplingApprover {
+    async fn approve(&self, request: &SamplingRequest) -> bool {
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.pending.lock().unwrap().insert(request_id.clone(), tx);
+
+        let messages = request
+            .messages
+            .iter()
+            .map(|message| format!("{:?}: {}", message.role, message.get_text()))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let event = McpSamplingRequestEvent {
+            request_id: request_id.clone(),
+            server: request.server.clone(),
+            model_id: request.model_id.clone(),
+            system_prompt: request.system_prompt.clone(),
+            messages,
+            max_tokens: request.params.max_tokens,
+        };
+        if self.app.emit("mcp_sampling_request", event).is_err() {
+            self.pending.lock().unwrap().remove(&request_id);
+            return false;
+        }
+        // Dropped unanswered counts as declined
+        rx.await.unwrap_or(false)
+    }
+}
+
+/// Answer an mcp_sampling_request event
+#[tauri::command]
+pub async fn respond_mcp_sampling(
+    state: State<'_, Arc<AppState>>,
+    request_id: String,
+    approved: bool,
+) -> Result<(), String> {
+    let sender = state
+        .pending_sampling
+        .lock()
+        .unwrap()
+        .remove(&request_id)
+        .ok_or("No sampling request waiting with that ID")?;
+    let _ = sender.send(approved);
+    Ok(())
+}
+
+/// Update auto-connect, auto-retry and sampling settings for an MCP server
+///
+/// `allow_sampling` (None keeps the current setting) applies from the next connection.
 #[tauri::command]
 pub async fn update_mcp_server_settings(
-    app: AppHandle,
     state: State<'_, Arc<AppState>>,
     server_id: String,
     auto_connect: bool,
     auto_retry: bool,
+    allow_sampling: Option<bool>,
 ) -> Result<(), String> {
     let mcp_registry = state.get_mcp_registry()?;
     let mut registry = mcp_registry.lock().await;
@@ -865,12 +946,16 @@ pub async fn update_mcp_server_settings(
     // Update config with new settings
     let updated_config = ServerConfig {
         name: config.name,
-        url: config.url.clone(),
+        transport: config.transport.clone(),
         auth: config.auth,
         use_well_known: config.use_well_known,
         auth_token: config.auth_token,
         auto_connect,
         auto_retry,
+        tool_filter: config.tool_filter,
+        tool_timeouts: config.tool_timeouts,
+        max_tool_result_bytes: config.max_tool_result_bytes,
+        allow_sampling: allow_sampling.unwrap_or(config.allow_sampling),
     };
 
     registry.add_server(server_id.clone(), updated_config.clone());
@@ -883,33 +968,12 @@ pub async fn update_mcp_server_settings(
 
     // If auto_retry was enabled and server is not connected, start retry
     if auto_retry && !registry.is_connected(&server_id) && !registry.is_retry_active(&server_id) {
-        // Create callback for status updates
-        let app_handle = app.clone();
-        let cb: Option<Box<dyn Fn(&str, &ServerStatus) + Send + Sync>> =
-            Some(Box::new(move |server_id: &str, status: &ServerStatus| {
-                let status_str = match status {
-                    ServerStatus::Disconnected => "disconnected".to_string(),
-                    ServerStatus::Connected => "connected".to_string(),
-                    ServerStatus::Retrying { attempt } => format!("retrying:{}", attempt),
-                    ServerStatus::RetryStopped { last_error } => format!("stopped:{}", last_error),
-                };
-
-                log_message(&format!("MCP server '{}' status: {}", server_id, status_str));
-
-                let _ = app_handle.emit(
-                    "mcp_server_status",
-                    serde_json::json!({
-                        "server_id": server_id,
-                        "status": status_str,
-                    }),
-                );
-            }));
 
         let token = spawn_retry_task(
             Arc::clone(&mcp_registry),
             server_id.clone(),
             updated_config,
-            cb,
+            None,
         );
         registry.set_retry_token(&server_id, token);
     }
@@ -937,7 +1001,6 @@ pub async fn stop_mcp_retry(state: State<'_, Arc<AppState>>, server_id: String)
 /// Start retry attempts for an MCP server
 #[tauri::command]
 pub async fn start_mcp_retry(
-    app: AppHandle,
     state: State<'_, Arc<AppState>>,
     server_id: String,
 ) -> Result<(), String> {
@@ -958,29 +1021,7 @@ pub async fn start_mcp_retry(
         .ok_or("Server not found")?
         .clone();
 
-    // Create callback for status updates
-    let app_handle = app.clone();
-    let cb: Option<Box<dyn Fn(&str, &ServerStatus) + Send + Sync>> =
-        Some(Box::new(move |server_id: &str, status: &ServerStatus| {
-            let status_str = match status {
-                ServerStatus::Disconnected => "disconnected".to_string(),
-                ServerStatus::Connected => "connected".to_string(),
-                ServerStatus::Retrying { attempt } => format!("retrying:{}", attempt),
-                ServerStatus::RetryStopped { last_error } => format!("stopped:{}", last_error),
-            };
-
-            log_message(&format!("MCP server '{}' status: {}", server_id, status_str));
-
-            let _ = app_handle.emit(
-                "mcp_server_status",
-                serde_json::json!({
-                    "server_id": server_id,
-                    "status": status_str,
-                }),
-            );
-        }));
-
-    let token = spawn_retry_task(Arc::clone(&mcp_registry), server_id.clone(), config, cb);
+    let token = spawn_retry_task(Arc::clone(&mcp_registry), server_id.clone(), config, None);
     registry.set_retry_token(&server_id, token);
 
     Ok(())
@@ -1012,29 +1053,19 @@ pub async fn handle_deep_link(app: &AppHandle, urls: Vec<url::Url>) {
             if let (Some(auth_code), Some(oauth_state)) = (code.as_ref(), state_param.as_ref()) {
                 let app_state = app.state::<AppState>();
 
-                // Look up server ID from state parameter
-                let server_id = {
-                    let mut pending_states = app_state.pending_oauth_states.lock().await;
-                    let server_id = pending_states.remove(oauth_state);
+                // Look up the pending flow from state parameter
+                let pending = take_pending_oauth(&app_state, oauth_state).await;
 
-                    // Update persisted state
-                    if server_id.is_some() {
-                        if let Err(e) = save_pending_oauth_states(&pending_states) {
-                            log_message(&format!(
-                                "Warning: Failed to update persisted OAuth state: {}",
-                                e
-                            ));
-                        }
-                    }
-
-                    server_id
-                };
-
-                log_message(&format!("Found server_id for state: {:?}", server_id));
+                log_message(&format!(
+                    "Found server_id for state: {:?}",
+                    pending.as_ref().map(|p| &p.server_id)
+                ));
 
-                if let Some(server_id) = server_id {
+                if let Some(PendingOAuth { server_id, code_verifier }) = pending {
                     // Complete OAuth flow
-                    match complete_oauth_internal(app, &server_id, auth_code).await {
+                    match complete_oauth_internal(app, &server_id, auth_code, code_verifier.as_deref())
+                        .await
+                    {
                         Ok(()) => {
                             log_message(&format!(
                                 "OAuth completed successfully for server: {}",
@@ -1,10 +1,17 @@
 //! Settings commands
 
-use config::Settings;
-use llm::registry::list_providers;
+use config::{ApiKeyStorage, InputHistory, Settings};
+use llm::registry::{list_compatible_providers, list_providers};
+use noema_core::storage::ids::ConversationId;
+use noema_core::storage::{EntityStore, Incognito, Stores};
 use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::State;
 use ts_rs::TS;
 
+use crate::commands::chat::compaction_config;
+use crate::state::AppState;
+
 /// Get the current user email setting
 #[tauri::command]
 pub fn get_user_email() -> Option<String> {
@@ -25,37 +32,141 @@ pub fn get_api_key_status() -> HashMap<String, bool> {
     let settings = Settings::load();
     list_providers()
         .iter()
-        .map(|info| (info.name.to_string(), settings.has_api_key(info.name)))
+        .map(|info| info.name.to_string())
+        .chain(list_compatible_providers(&settings))
+        .map(|name| {
+            let has_key = settings.has_api_key(&name);
+            (name, has_key)
+        })
         .collect()
 }
 
-/// Set an API key for a provider (encrypts and saves)
+/// Set an API key for a provider, in the system keychain when available.
+/// Returns a warning when it had to be stored encrypted in settings.toml.
 #[tauri::command]
-pub fn set_api_key(provider: String, api_key: String) -> Result<(), String> {
+pub fn set_api_key(provider: String, api_key: String) -> Result<Option<String>, String> {
     let mut settings = Settings::load();
-    settings.set_api_key(&provider, &api_key)?;
-    settings.save()
+    let storage = settings.set_api_key(&provider, &api_key)?;
+    settings.save()?;
+    Ok(match storage {
+        ApiKeyStorage::Keychain => None,
+        ApiKeyStorage::EncryptedFile => {
+            let warning = format!(
+                "No system keychain is available, so the {} API key was stored encrypted in settings.toml instead. \
+                 Install and unlock a Secret Service provider (e.g. GNOME Keyring) to keep keys in the keychain.",
+                provider
+            );
+            tracing::warn!("{}", warning);
+            Some(warning)
+        }
+    })
 }
 
 /// Remove an API key for a provider
 #[tauri::command]
 pub fn remove_api_key(provider: String) -> Result<(), String> {
     let mut settings = Settings::load();
-    settings.remove_api_key(&provider);
-    settings.save()
+    let removed = settings.remove_api_key(&provider);
+    settings.save()?;
+    removed
 }
 
-/// Get provider info (name, whether it requires API key, env var name)
+/// Get provider info (name, whether it requires API key, env var name).
+/// Includes the OpenAI-compatible providers defined in settings.
 #[tauri::command]
 pub fn get_provider_info() -> Vec<ProviderInfoResponse> {
-    list_providers()
-        .iter()
-        .map(|info| ProviderInfoResponse {
-            name: info.name.to_string(),
-            requires_api_key: info.api_key_env.is_some(),
-            api_key_env: info.api_key_env.map(|s| s.to_string()),
-        })
-        .collect()
+    let settings = Settings::load();
+    let builtin = list_providers().iter().map(|info| ProviderInfoResponse {
+        name: info.name.to_string(),
+        requires_api_key: info.api_key_env.is_some(),
+        api_key_env: info.api_key_env.map(|s| s.to_string()),
+    });
+    let compatible = list_compatible_providers(&settings).into_iter().map(|name| {
+        let api_key_env = settings
+            .get_compatible_provider(&name)
+            .and_then(|p| p.api_key_env.clone());
+        ProviderInfoResponse {
+            name,
+            requires_api_key: api_key_env.is_some(),
+            api_key_env,
+        }
+    });
+    builtin.chain(compatible).collect()
+}
+
+/// Get previously submitted chat input, oldest first
+#[tauri::command]
+pub fn get_input_history() -> Vec<String> {
+    InputHistory::load().entries().to_vec()
+}
+
+/// Record submitted chat input so it can be recalled after a restart.
+/// Input sent to private or ephemeral conversations is not recorded.
+#[tauri::command]
+pub async fn push_input_history(
+    state: State<'_, Arc<AppState>>,
+    entry: String,
+    conversation_id: Option<ConversationId>,
+) -> Result<(), String> {
+    if let Some(conversation_id) = conversation_id {
+        let stores = state.get_stores()?;
+        let entity = stores
+            .entity()
+            .get_entity(&conversation_id)
+            .await
+            .map_err(|e| format!("Failed to get conversation: {}", e))?;
+        if entity.is_some_and(|e| e.is_private || e.incognito == Incognito::Ephemeral) {
+            return Ok(());
+        }
+    }
+    InputHistory::load().push(&entry)
+}
+
+/// Get when and with which model old history is summarized
+#[tauri::command]
+pub fn get_compaction_settings() -> CompactionSettings {
+    let settings = Settings::load();
+    CompactionSettings {
+        threshold_tokens: settings
+            .compaction_threshold_tokens
+            .map(|tokens| u32::try_from(tokens).unwrap_or(u32::MAX)),
+        summary_model: settings.compaction_summary_model,
+    }
+}
+
+/// Set when and with which model old history is summarized, for new and
+/// open conversations alike
+#[tauri::command]
+pub async fn set_compaction_settings(
+    state: State<'_, Arc<AppState>>,
+    compaction: CompactionSettings,
+) -> Result<(), String> {
+    let mut settings = Settings::load();
+    settings.compaction_threshold_tokens = compaction
+        .threshold_tokens
+        .filter(|&tokens| tokens > 0)
+        .map(|tokens| tokens as usize);
+    settings.compaction_summary_model = compaction.summary_model.filter(|model| !model.trim().is_empty());
+    settings.save()?;
+
+    let config = compaction_config();
+    for manager in state.managers.lock().await.values_mut() {
+        manager.set_compaction(config.clone());
+    }
+    Ok(())
+}
+
+/// History summarization settings
+#[derive(serde::Serialize, serde::Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct CompactionSettings {
+    /// Summarize once a conversation is estimated above this many tokens
+    /// (None = only on /compact)
+    pub threshold_tokens: Option<u32>,
+    /// Model (provider/model) that writes summaries (None = the
+    /// conversation's model)
+    pub summary_model: Option<String>,
 }
 
 #[derive(serde::Serialize, TS)]
@@ -74,5 +185,6 @@ mod ts_export {
     #[test]
     fn export_types() {
         ProviderInfoResponse::export_all().expect("Failed to export ProviderInfoResponse");
+        CompactionSettings::export_all().expect("Failed to export CompactionSettings");
     }
 }
@@ -1,7 +1,9 @@
 //! Voice-related Tauri commands
 
+use config::Settings;
 use noema_audio::{
-    create_browser_backend, VoiceAgent, VoiceCoordinator,
+    create_browser_backend_with_vad, download_whisper_model, list_input_devices,
+    whisper_model_size, SystemTts, VadConfig, VoiceAgent, VoiceCoordinator, WhisperConfig,
 };
 
 #[cfg(feature = "native-audio")]
@@ -10,71 +12,121 @@ use noema_audio::StreamingAudioCapture;
 use tauri::{AppHandle, Emitter, Manager, State};
 use std::path::PathBuf;
 use std::sync::Arc;
-
-use tokio::io::AsyncWriteExt;
+use std::time::Duration;
 
 use crate::logging::log_message;
 use crate::state::AppState;
+use crate::types::{DecodedAudioResponse, InputDevice};
 
 /// Check if voice is available (Whisper model exists)
+///
+/// `model` is a model filename such as "ggml-base.bin"; the configured model is
+/// checked when omitted.
 #[tauri::command]
-pub async fn is_voice_available(app: AppHandle) -> Result<bool, String> {
-    let model_path = get_whisper_model_path(&app).ok_or("Could not determine model path")?;
+pub async fn is_voice_available(app: AppHandle, model: Option<String>) -> Result<bool, String> {
+    let model = model.unwrap_or_else(|| Settings::load().whisper_model().to_string());
+    let model_path = get_whisper_model_path(&app, &model).ok_or("Could not determine model path")?;
     Ok(model_path.exists())
 }
 
 /// Get the Whisper model path using AppHandle for proper mobile resolution
-fn get_whisper_model_path(_app: &AppHandle) -> Option<PathBuf> {
+fn get_whisper_model_path(_app: &AppHandle, model: &str) -> Option<PathBuf> {
     use config::PathManager;
-    PathManager::whisper_model_path()
+    PathManager::whisper_model_path_for(model)
 }
 
-/// Download the Whisper model
-#[tauri::command]
-pub async fn download_voice_model(app: AppHandle, url: String) -> Result<(), String> {
-    let model_path = get_whisper_model_path(&app)
-        .ok_or("Could not determine model path")?;
+/// Whisper config for the configured model and language
+fn whisper_config(app: &AppHandle) -> Result<WhisperConfig, String> {
+    let settings = Settings::load();
+    let model_path = get_whisper_model_path(app, settings.whisper_model()).ok_or(
+        "Whisper model not found. Please download it first.",
+    )?;
 
-    if model_path.exists() {
-        return Ok(());
+    if !model_path.exists() {
+        return Err("Model file not found. Please download it.".to_string());
     }
 
-    if let Some(parent) = model_path.parent() {
-        tokio::fs::create_dir_all(parent).await
-            .map_err(|e| format!("Failed to create model directory: {}", e))?;
+    Ok(WhisperConfig::new(model_path).with_language(settings.whisper_language))
+}
+
+/// Speech detection settings for the configured sensitivity
+fn vad_config() -> VadConfig {
+    let settings = Settings::load();
+    let config = match settings.voice_sensitivity.as_deref() {
+        Some(name) => VadConfig::preset(name).unwrap_or_else(|| {
+            log_message(&format!("Unknown voice sensitivity '{}', using normal", name));
+            VadConfig::default()
+        }),
+        None => VadConfig::default(),
+    };
+    match settings.voice_vad_aggressiveness {
+        Some(aggressiveness) => config.with_aggressiveness(aggressiveness),
+        None => config,
     }
+}
 
-    log_message(&format!("Downloading model from {}", url));
-    app.emit("download_progress", "starting").ok();
+/// Coordinator for the agent, gated by the configured wake word if any and
+/// reading responses aloud when `speak_responses` is set
+fn voice_coordinator(agent: VoiceAgent, speak_responses: bool) -> VoiceCoordinator {
+    let settings = Settings::load();
+    let mut coordinator = match settings.voice_silence_timeout_ms {
+        Some(0) => VoiceCoordinator::new(agent).with_silence_timeout(None),
+        Some(millis) => {
+            VoiceCoordinator::new(agent).with_silence_timeout(Some(Duration::from_millis(millis)))
+        }
+        None => VoiceCoordinator::new(agent),
+    };
+    match SystemTts::new() {
+        Ok(tts) => coordinator = coordinator.with_tts(tts),
+        Err(e) => log_message(&format!("Responses can't be spoken: {}", e)),
+    }
+    coordinator.set_speak_responses(speak_responses);
+    match settings.voice_wake_word {
+        Some(word) if !word.trim().is_empty() => coordinator.with_wake_word(&word),
+        _ => coordinator,
+    }
+}
 
-    let client = reqwest::Client::new();
-    let response = client.get(&url).send().await
-        .map_err(|e| format!("Failed to fetch model: {}", e))?;
+/// Download a Whisper model (the configured one when `model` is omitted)
+#[tauri::command]
+pub async fn download_voice_model(app: AppHandle, model: Option<String>) -> Result<(), String> {
+    let model = model.unwrap_or_else(|| Settings::load().whisper_model().to_string());
+    let size = whisper_model_size(&model)
+        .ok_or_else(|| format!("Unknown Whisper model '{}'", model))?;
+    let model_path = get_whisper_model_path(&app, &model)
+        .ok_or("Could not determine model path")?;
 
-    let total_size = response.content_length().unwrap_or(0);
-    let mut stream = response.bytes_stream();
-    let mut file = tokio::fs::File::create(&model_path).await
-        .map_err(|e| format!("Failed to create model file: {}", e))?;
+    log_message(&format!("Downloading Whisper model {}", model));
+    app.emit("download_progress", "starting").ok();
 
-    let mut downloaded: u64 = 0;
-    
-    use futures::StreamExt;
-    while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| format!("Download error: {}", e))?;
-        file.write_all(&chunk).await.map_err(|e| format!("Write error: {}", e))?;
-        
-        downloaded += chunk.len() as u64;
-        if total_size > 0 {
-            let progress = (downloaded as f64 / total_size as f64 * 100.0) as u8;
-            app.emit("download_progress", progress).ok();
+    download_whisper_model(size, &model_path, |progress| {
+        if let Some(percent) = progress.percent() {
+            app.emit("download_progress", percent).ok();
         }
-    }
+    })
+    .await
+    .map_err(|e| format!("Failed to download model: {:#}", e))?;
 
     log_message("Model download complete");
     app.emit("download_progress", "complete").ok();
     Ok(())
 }
 
+/// Decode an audio content block the webview can't (e.g. raw PCM) to samples
+#[tauri::command]
+pub async fn decode_audio(data: String, mime_type: String) -> Result<DecodedAudioResponse, String> {
+    use base64::Engine;
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&data)
+        .map_err(|e| format!("Failed to decode data: {}", e))?;
+    let audio = noema_audio::decode_audio(&bytes, &mime_type).map_err(|e| format!("{:#}", e))?;
+    Ok(DecodedAudioResponse {
+        samples: audio.samples,
+        sample_rate: audio.sample_rate,
+    })
+}
+
 /// Spawn the event polling loop for the voice coordinator
 fn spawn_voice_loop(app: AppHandle) {
     tokio::spawn(async move {
@@ -85,7 +137,7 @@ fn spawn_voice_loop(app: AppHandle) {
             // Check if the voice conversation is processing - if so, buffer voice input
             let is_processing = state.is_voice_conversation_processing().await;
 
-            let (message, errors, is_listening, is_transcribing, buffered_count) = {
+            let (message, partial, errors, is_listening, is_transcribing, buffered_count) = {
                 let mut coordinator_guard = state.voice_coordinator.lock().await;
                 if let Some(coordinator) = coordinator_guard.as_mut() {
                     let is_listening = coordinator.is_listening();
@@ -93,7 +145,8 @@ fn spawn_voice_loop(app: AppHandle) {
                     let buffered_count = coordinator.buffered_count();
                     // Buffer messages while processing, release when not processing
                     let (msg, errs) = coordinator.process(is_processing);
-                    (msg, errs, is_listening, is_transcribing, buffered_count)
+                    let partial = coordinator.take_partial();
+                    (msg, partial, errs, is_listening, is_transcribing, buffered_count)
                 } else {
                     // Voice was disabled or session ended
                     break;
@@ -117,6 +170,11 @@ fn spawn_voice_loop(app: AppHandle) {
                 last_status = Some(current_status);
             }
 
+            // Provisional text for the utterance in progress
+            if let Some(partial) = partial {
+                app.emit("voice_partial", &partial).ok();
+            }
+
             // Send transcribed message (buffered messages are concatenated)
             if let Some(msg) = message {
                 app.emit("voice_transcription", &msg).ok();
@@ -134,35 +192,44 @@ fn spawn_voice_loop(app: AppHandle) {
     });
 }
 
+/// Shut a voice coordinator down on a blocking thread
+///
+/// Joining the transcription thread waits for an in-progress decode to
+/// abort, so it must not run on the async runtime or under a lock.
+async fn shutdown_coordinator(mut coordinator: VoiceCoordinator) {
+    if let Err(e) = tokio::task::spawn_blocking(move || coordinator.shutdown()).await {
+        log_message(&format!("Voice shutdown failed: {}", e));
+    }
+}
+
 /// Toggle voice input on/off (Native)
 #[tauri::command]
 pub async fn toggle_voice(app: AppHandle, state: State<'_, Arc<AppState>>) -> Result<bool, String> {
     let mut coordinator_guard = state.voice_coordinator.lock().await;
 
-    if coordinator_guard.is_some() {
-        // Disable voice - just set to None
-        *coordinator_guard = None;
+    if let Some(coordinator) = coordinator_guard.take() {
+        drop(coordinator_guard);
+        // Disable voice - abort any in-flight transcription so nothing arrives late
+        shutdown_coordinator(coordinator).await;
         // Loop will exit automatically
         Ok(false)
     } else {
         // Enable voice
         #[cfg(feature = "native-audio")]
         {
-            let model_path = get_whisper_model_path(&app).ok_or(
-                "Whisper model not found. Please download it first.",
-            )?;
+            let config = whisper_config(&app)?;
 
-            if !model_path.exists() {
-                return Err("Model file not found. Please download it.".to_string());
+            let streamer = match Settings::load().voice_input_device {
+                Some(device) => StreamingAudioCapture::with_device(&device),
+                None => StreamingAudioCapture::new(),
             }
+            .map_err(|e| format!("Failed to initialize audio capture: {}", e))?
+                .with_vad_config(vad_config());
 
-            let streamer = StreamingAudioCapture::new()
-                .map_err(|e| format!("Failed to initialize audio capture: {}", e))?;
-
-            let agent = VoiceAgent::new(Box::new(streamer), &model_path)
+            let agent = VoiceAgent::new(Box::new(streamer), config)
                 .map_err(|e| format!("Failed to start voice agent: {}", e))?;
 
-            let coordinator = VoiceCoordinator::new(agent);
+            let coordinator = voice_coordinator(agent, *state.speak_responses.lock().await);
             *coordinator_guard = Some(coordinator);
             drop(coordinator_guard); // Release lock before spawning
 
@@ -180,6 +247,39 @@ pub async fn toggle_voice(app: AppHandle, state: State<'_, Arc<AppState>>) -> Re
     }
 }
 
+/// Toggle reading responses in the voice conversation aloud; returns whether
+/// they are now spoken
+#[tauri::command]
+pub async fn toggle_speak_responses(state: State<'_, Arc<AppState>>) -> Result<bool, String> {
+    let mut speak_responses = state.speak_responses.lock().await;
+    *speak_responses = !*speak_responses;
+    if let Some(coordinator) = state.voice_coordinator.lock().await.as_mut() {
+        coordinator.set_speak_responses(*speak_responses);
+    }
+    Ok(*speak_responses)
+}
+
+/// Microphones voice input can capture from
+#[tauri::command]
+pub fn list_voice_input_devices() -> Vec<InputDevice> {
+    list_input_devices()
+        .into_iter()
+        .map(|device| InputDevice {
+            name: device.name,
+            is_default: device.is_default,
+        })
+        .collect()
+}
+
+/// Capture voice from the microphone called `name` (the system default when
+/// None); takes effect the next time voice is turned on
+#[tauri::command]
+pub fn set_voice_input_device(name: Option<String>) -> Result<(), String> {
+    let mut settings = Settings::load();
+    settings.voice_input_device = name;
+    settings.save()
+}
+
 /// Get current voice status
 #[tauri::command]
 pub async fn get_voice_status(state: State<'_, Arc<AppState>>) -> Result<String, String> {
@@ -215,28 +315,25 @@ pub async fn start_voice_session(app: AppHandle, state: State<'_, Arc<AppState>>
         *state.browser_audio_controller.lock().await = None;
     }
 
-    let model_path = get_whisper_model_path(&app).ok_or(
-        "Whisper model not found. Please download it first.",
-    )?;
-
-    if !model_path.exists() {
-        return Err("Model file not found. Please download it.".to_string());
-    }
+    let config = whisper_config(&app)?;
 
     // Create browser backend (controller + streamer)
     // Assuming browser sends 16kHz or we handle resampling. 
     // For now, let's assume 16000, but we might need to parameterize this if browser sends 44100.
     // Ideally, we'd pass the sample rate from the frontend.
-    let (controller, streamer) = create_browser_backend(16000);
+    let (controller, streamer) = create_browser_backend_with_vad(16000, vad_config());
 
-    let agent = VoiceAgent::new(Box::new(streamer), &model_path)
+    let agent = VoiceAgent::new(Box::new(streamer), config)
         .map_err(|e| format!("Failed to start voice session: {}", e))?;
 
-    let coordinator = VoiceCoordinator::new(agent);
+    let coordinator = voice_coordinator(agent, *state.speak_responses.lock().await);
 
     // Store state
     *state.browser_audio_controller.lock().await = Some(controller);
-    *state.voice_coordinator.lock().await = Some(coordinator);
+    let previous = state.voice_coordinator.lock().await.replace(coordinator);
+    if let Some(previous) = previous {
+        shutdown_coordinator(previous).await;
+    }
 
     // Start loop
     spawn_voice_loop(app.clone());
@@ -283,10 +380,10 @@ pub async fn stop_voice_session(
     // But we can't easily "wait" for the agent to finish transcribing unless we change VoiceAgent API.
     // For now, we just stop. 
     
-    // Drop coordinator to stop the loop and agent
-    {
-        let mut coordinator = state.voice_coordinator.lock().await;
-        *coordinator = None;
+    // Shut down the agent and drop the coordinator to stop the loop
+    let coordinator = state.voice_coordinator.lock().await.take();
+    if let Some(coordinator) = coordinator {
+        shutdown_coordinator(coordinator).await;
     }
     {
         let mut controller = state.browser_audio_controller.lock().await;
@@ -10,7 +10,7 @@ use crate::logging::log_message;
 use crate::state::AppCoordinator;
 use noema_core::storage::DocumentResolver;
 use noema_core::McpRegistry;
-use noema_mcp_core::{NoemaCoreServer, ServerHandle};
+use noema_mcp_core::{NoemaCoreServer, ServerHandle, DEFAULT_DRAIN_TIMEOUT};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -35,7 +35,7 @@ impl CoreServerState {
 
     /// Get the port of the running server
     pub async fn port(&self) -> Option<u16> {
-        self.handle.lock().await.as_ref().map(|h| h.port())
+        self.handle.lock().await.as_ref().and_then(|h| h.port())
     }
 }
 
@@ -68,9 +68,7 @@ pub async fn start_core_server(
         .map_err(|e| format!("Failed to start Noema Core server: {}", e))?;
 
     let url = handle.url();
-    let port = handle.port();
-
-    log_message(&format!("Noema Core MCP server started on port {}", port));
+    log_message(&format!("Noema Core MCP server started at {}", url));
 
     // Store the handle
     *server_state.handle.lock().await = Some(handle);
@@ -109,7 +107,7 @@ pub async fn stop_core_server(
     // Stop the server
     if let Some(handle) = server_state.handle.lock().await.take() {
         log_message("Stopping Noema Core MCP server");
-        handle.stop();
+        handle.stop_and_wait(DEFAULT_DRAIN_TIMEOUT).await;
     }
 
     Ok(())
@@ -166,6 +166,19 @@ pub fn run() {
                     commands::mcp::handle_deep_link(&handle, urls).await;
                 });
             });
+
+            // Show downloads of Ollama models pulled on first use
+            let handle = app.handle().clone();
+            llm::set_pull_progress_handler(Arc::new(move |progress: &llm::PullProgress| {
+                use tauri::Emitter;
+                handle
+                    .emit("model_pull_progress", types::ModelPullProgressEvent {
+                        model: progress.model.clone(),
+                        status: progress.status.clone(),
+                        percent: progress.percent(),
+                    })
+                    .ok();
+            }));
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -173,16 +186,33 @@ pub fn run() {
             commands::init::init_app,
             commands::chat::get_messages,
             commands::chat::send_message,
+            commands::chat::apply_mcp_prompt,
             commands::chat::clear_history,
+            commands::chat::cancel_request,
+            commands::chat::set_require_tool_approval,
+            commands::chat::respond_tool_approval,
+            commands::chat::get_system_prompt,
+            commands::chat::set_system_prompt,
+            commands::chat::set_generation_params,
+            commands::chat::compact_conversation,
             commands::chat::set_model,
             commands::chat::list_models,
+            commands::chat::list_provider_models,
             commands::chat::list_conversations,
             commands::chat::load_conversation,
             commands::chat::new_conversation,
+            commands::chat::list_templates,
+            commands::chat::save_template,
+            commands::chat::delete_template,
             commands::chat::delete_conversation,
             commands::chat::rename_conversation,
+            commands::chat::add_conversation_tag,
+            commands::chat::remove_conversation_tag,
+            commands::chat::list_conversations_by_tag,
             commands::chat::get_conversation_private,
             commands::chat::set_conversation_private,
+            commands::chat::set_conversation_pinned,
+            commands::chat::set_conversation_archived,
             commands::chat::get_model_name,
             commands::chat::get_favorite_models,
             commands::chat::toggle_favorite_model,
@@ -191,7 +221,13 @@ pub fn run() {
             commands::chat::get_span_messages,
             commands::chat::list_conversation_views, // Returns forks of this conversation
             commands::chat::regenerate_response,
+            commands::chat::regenerate_last_response,
+            commands::chat::continue_response,
+            commands::chat::edit_and_resend,
             commands::chat::fork_conversation,
+            commands::chat::fork_conversation_at_message,
+            commands::chat::export_conversation,
+            commands::chat::import_conversation,
             commands::chat::select_span,
             commands::chat::edit_message,
             // Subconversation commands
@@ -210,13 +246,19 @@ pub fn run() {
             // Voice commands
             commands::voice::is_voice_available,
             commands::voice::download_voice_model,
+            commands::voice::decode_audio,
             commands::voice::toggle_voice,
             commands::voice::get_voice_status,
+            commands::voice::toggle_speak_responses,
+            commands::voice::list_voice_input_devices,
+            commands::voice::set_voice_input_device,
             commands::voice::start_voice_session,
             commands::voice::process_audio_chunk,
             commands::voice::stop_voice_session,
             // File/Asset commands
             commands::files::save_file,
+            commands::files::store_asset,
+            commands::files::attach_file,
             // Logging
             logging::log_debug,
             // MCP server commands
@@ -226,6 +268,10 @@ pub fn run() {
             commands::mcp::connect_mcp_server,
             commands::mcp::disconnect_mcp_server,
             commands::mcp::get_mcp_server_tools,
+            commands::mcp::get_mcp_server_resources,
+            commands::mcp::read_mcp_resource,
+            commands::mcp::get_mcp_server_prompts,
+            commands::mcp::respond_mcp_sampling,
             commands::mcp::test_mcp_server,
             commands::mcp::start_mcp_oauth,
             commands::mcp::complete_mcp_oauth,
@@ -239,6 +285,10 @@ pub fn run() {
             commands::settings::set_api_key,
             commands::settings::remove_api_key,
             commands::settings::get_provider_info,
+            commands::settings::get_input_history,
+            commands::settings::push_input_history,
+            commands::settings::get_compaction_settings,
+            commands::settings::set_compaction_settings,
             // Document commands (episteme-compatible)
             commands::gdocs::list_documents,
             commands::gdocs::get_document,
@@ -2,11 +2,19 @@
 //!
 //! All logs (backend tracing + frontend log_debug) go to:
 //! ~/.local/share/noema/logs/noema.log (or platform equivalent)
+//!
+//! Every line is passed through `config::redact` before it is written, so
+//! tokens and secrets never reach the log file.
+//!
+//! Spans are logged when they close, with how long they took; with
+//! `RUST_LOG=noema_core=debug` that gives a timeline of each turn (the
+//! `turn`, `model_call` and `tool_call` spans).
 
 use config::PathManager;
+use std::io::{self, Write};
 use std::sync::Once;
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use tracing_subscriber::{fmt, fmt::format::FmtSpan, prelude::*, EnvFilter};
 
 static INIT: Once = Once::new();
 static mut LOG_GUARD: Option<WorkerGuard> = None;
@@ -53,12 +61,13 @@ pub fn init_logging() {
                         .with(filter)
                         .with(
                             fmt::layer()
-                                .with_writer(non_blocking)
+                                .with_writer(move || RedactingWriter(non_blocking.clone()))
                                 .with_ansi(false)
                                 .with_target(true)
                                 .with_thread_ids(false)
                                 .with_file(true)
-                                .with_line_number(true),
+                                .with_line_number(true)
+                                .with_span_events(FmtSpan::CLOSE),
                         );
 
                     match tracing::subscriber::set_global_default(subscriber) {
@@ -89,14 +98,30 @@ fn init_stderr_logging() {
 
     let subscriber = tracing_subscriber::registry().with(filter).with(
         fmt::layer()
-            .with_writer(std::io::stderr)
+            .with_writer(|| RedactingWriter(io::stderr()))
             .with_ansi(true)
-            .with_target(true),
+            .with_target(true)
+            .with_span_events(FmtSpan::CLOSE),
     );
 
     let _ = tracing::subscriber::set_global_default(subscriber);
 }
 
+/// Writer masking credentials in each formatted log event
+struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let line = String::from_utf8_lossy(buf);
+        self.0.write_all(config::redact(&line).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
 /// Log a message to the log file (legacy function for compatibility)
 /// New code should use tracing macros directly
 pub fn log_message(msg: &str) {
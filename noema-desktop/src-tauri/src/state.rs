@@ -1,15 +1,21 @@
 //! Application state management
 
+use llm::ModelCatalog;
 use noema_audio::BrowserAudioController;
 use noema_audio::VoiceCoordinator;
 use noema_core::storage::coordinator::StorageCoordinator;
 use noema_core::storage::ids::{ConversationId, UserId};
 use noema_core::storage::traits::StorageTypes;
 use noema_core::storage::{FsBlobStore, SqliteStore, Stores};
-use noema_core::{ConversationManager, ManagerEvent, McpRegistry};
+use noema_core::mcp::Sampler;
+use noema_core::{event_channel, ConversationManager, EventReceiver, McpRegistry, SharedEventSender, DEFAULT_EVENT_CHANNEL_CAPACITY};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex, OnceCell};
+use tokio::sync::{oneshot, Mutex, OnceCell};
+
+/// MCP sampling requests waiting for the user's answer, keyed by request ID
+pub type PendingSampling = std::sync::Mutex<HashMap<String, oneshot::Sender<bool>>>;
 
 // ============================================================================
 // App Storage Types - Define once via StorageTypes
@@ -79,10 +85,7 @@ impl Stores<AppStorage> for AppStores {
 pub type AppCoordinator = StorageCoordinator<AppStorage>;
 pub type AppManager = ConversationManager<AppStorage>;
 
-/// Tagged event for routing to UI - includes conversation ID for dispatch
-pub type TaggedEvent = (ConversationId, ManagerEvent);
-pub type EventSender = mpsc::UnboundedSender<TaggedEvent>;
-pub type EventReceiver = mpsc::UnboundedReceiver<TaggedEvent>;
+pub type EventSender = SharedEventSender;
 
 pub struct AppState {
     /// All stores - initialized once at startup
@@ -93,6 +96,10 @@ pub struct AppState {
     pub managers: Mutex<HashMap<ConversationId, AppManager>>,
     /// MCP registry (shared across all conversations) - initialized once at startup
     pub mcp_registry: OnceCell<Arc<Mutex<McpRegistry>>>,
+    /// Runs MCP servers' sampling requests on the current model - initialized once at startup
+    pub sampler: OnceCell<Arc<Sampler>>,
+    /// MCP sampling requests waiting for the user's answer
+    pub pending_sampling: Arc<PendingSampling>,
     /// Shared event sender - managers send events here tagged with conversation ID
     pub event_tx: EventSender,
     /// Shared event receiver - single consumer dispatches to UI
@@ -103,13 +110,17 @@ pub struct AppState {
     pub model_id: Mutex<String>,
     /// Display name for the model
     pub model_name: Mutex<String>,
+    /// Cached model lists of the providers, for the model picker
+    pub model_catalog: Arc<ModelCatalog>,
     pub voice_coordinator: Mutex<Option<VoiceCoordinator>>,
     /// Which conversation voice input is currently associated with
     pub voice_conversation: Mutex<Option<ConversationId>>,
+    /// Read responses in the voice conversation aloud ("/speak")
+    pub speak_responses: Mutex<bool>,
     /// Maps conversation ID to processing state
     pub processing: Mutex<HashMap<ConversationId, bool>>,
-    /// Maps OAuth state parameter to server ID for pending OAuth flows
-    pub pending_oauth_states: Mutex<HashMap<String, String>>,
+    /// Maps OAuth state parameter to the pending OAuth flow it belongs to
+    pub pending_oauth_states: Mutex<HashMap<String, PendingOAuth>>,
     /// Browser voice controller for WebAudio-based input
     pub browser_audio_controller: Mutex<Option<BrowserAudioController>>,
     /// Lock to prevent concurrent initialization (React StrictMode calls init twice)
@@ -121,21 +132,25 @@ impl AppState {
         // Load pending OAuth states from disk
         let pending_states = load_pending_oauth_states().unwrap_or_default();
 
-        // Create shared event channel
-        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        // Create shared event channel (bounded so a stalled UI can't grow it without limit)
+        let (event_tx, event_rx) = event_channel(DEFAULT_EVENT_CHANNEL_CAPACITY);
 
         Self {
             stores: OnceCell::new(),
             coordinator: OnceCell::new(),
             managers: Mutex::new(HashMap::new()),
             mcp_registry: OnceCell::new(),
+            sampler: OnceCell::new(),
+            pending_sampling: Arc::new(std::sync::Mutex::new(HashMap::new())),
             event_tx,
             event_rx: Mutex::new(Some(event_rx)),
             user_id: Mutex::new(UserId::new()),
             model_id: Mutex::new(String::new()),
             model_name: Mutex::new(String::new()),
+            model_catalog: Arc::new(ModelCatalog::open_default()),
             voice_coordinator: Mutex::new(None),
             voice_conversation: Mutex::new(None),
+            speak_responses: Mutex::new(false),
             processing: Mutex::new(HashMap::new()),
             pending_oauth_states: Mutex::new(pending_states),
             browser_audio_controller: Mutex::new(None),
@@ -215,6 +230,17 @@ impl AppState {
         }
     }
 
+    /// Read a completed response aloud if it belongs to the voice
+    /// conversation and "/speak" is on
+    pub async fn speak_response(&self, conversation_id: &ConversationId, text: &str) {
+        if self.voice_conversation.lock().await.as_ref() != Some(conversation_id) {
+            return;
+        }
+        if let Some(coordinator) = self.voice_coordinator.lock().await.as_mut() {
+            coordinator.speak_response(text);
+        }
+    }
+
     /// Set which conversation voice input is associated with
     pub async fn set_voice_conversation(&self, conversation_id: Option<ConversationId>) {
         *self.voice_conversation.lock().await = conversation_id;
@@ -227,6 +253,15 @@ impl Default for AppState {
     }
 }
 
+/// An OAuth authorization waiting for its callback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingOAuth {
+    pub server_id: String,
+    /// PKCE verifier to send with the token exchange
+    #[serde(default)]
+    pub code_verifier: Option<String>,
+}
+
 /// Get the path to the pending OAuth states file
 pub fn get_oauth_states_path() -> Option<std::path::PathBuf> {
     use config::PathManager;
@@ -234,14 +269,25 @@ pub fn get_oauth_states_path() -> Option<std::path::PathBuf> {
 }
 
 /// Load pending OAuth states from disk
-pub fn load_pending_oauth_states() -> Option<HashMap<String, String>> {
+pub fn load_pending_oauth_states() -> Option<HashMap<String, PendingOAuth>> {
     let path = get_oauth_states_path()?;
     let content = std::fs::read_to_string(&path).ok()?;
-    serde_json::from_str(&content).ok()
+    serde_json::from_str(&content).ok().or_else(|| {
+        // Older files map the state straight to a server ID
+        let legacy: HashMap<String, String> = serde_json::from_str(&content).ok()?;
+        Some(
+            legacy
+                .into_iter()
+                .map(|(state, server_id)| {
+                    (state, PendingOAuth { server_id, code_verifier: None })
+                })
+                .collect(),
+        )
+    })
 }
 
 /// Save pending OAuth states to disk
-pub fn save_pending_oauth_states(states: &HashMap<String, String>) -> Result<(), String> {
+pub fn save_pending_oauth_states(states: &HashMap<String, PendingOAuth>) -> Result<(), String> {
     let path = get_oauth_states_path().ok_or("Could not determine data directory")?;
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
@@ -2,6 +2,7 @@
 
 use llm::{ChatMessage, ContentBlock, Role, ToolResultContent};
 use noema_core::storage::ids::{AssetId, ConversationId, DocumentId, SpanId, TurnId};
+use noema_core::ManagerError;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
@@ -14,6 +15,18 @@ pub struct ModelInfo {
     pub provider: String,
     pub capabilities: Vec<String>,
     pub context_window: Option<u32>,
+    pub max_output_tokens: Option<u32>,
+}
+
+/// Models offered by one provider, or why they couldn't be listed (e.g. no
+/// API key)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct ProviderModels {
+    pub provider: String,
+    pub models: Vec<ModelInfo>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -27,13 +40,22 @@ pub struct ConversationInfo {
     /// Whether this conversation is marked as private (warns before using cloud models)
     pub is_private: bool,
     pub created_at: i64,
+    /// Model last used in this conversation (provider/model format)
+    pub last_model: Option<String>,
+    /// Tags, sorted alphabetically
+    pub tags: Vec<String>,
+    /// Whether this conversation is pinned to the top of the list
+    pub is_pinned: bool,
+    /// Whether this conversation is archived (hidden unless requested)
+    pub is_archived: bool,
 }
 
 impl ConversationInfo {
-    /// Create from StoredEntity with turn count
+    /// Create from StoredEntity with turn count and tags
     pub fn from_entity(
         entity: &noema_core::storage::StoredEntity,
         turn_count: usize,
+        tags: Vec<String>,
     ) -> Self {
         Self {
             id: entity.id.clone(),
@@ -41,6 +63,10 @@ impl ConversationInfo {
             message_count: turn_count,
             is_private: entity.is_private,
             created_at: entity.created_at,
+            last_model: entity.last_model().map(str::to_string),
+            tags,
+            is_pinned: entity.is_pinned(),
+            is_archived: entity.is_archived,
         }
     }
 }
@@ -134,6 +160,10 @@ pub struct DisplayMessage {
     /// Available alternates for this message's turn (only populated for assistant messages with alternatives)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub alternates: Option<Vec<AlternateInfo>>,
+    /// When the message was stored (epoch ms); unset while it is streaming
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(type = "number | undefined")]
+    pub created_at: Option<i64>,
 }
 
 impl From<&ChatMessage> for DisplayMessage {
@@ -151,6 +181,7 @@ impl From<&ChatMessage> for DisplayMessage {
             turn_id: None,
             span_id: None,
             alternates: None,
+            created_at: None,
         }
     }
 }
@@ -170,6 +201,7 @@ impl DisplayMessage {
             turn_id: Some(turn_id),
             span_id: Some(span_id),
             alternates: if alternates.len() > 1 { Some(alternates) } else { None },
+            created_at: None,
         }
     }
 }
@@ -268,6 +300,7 @@ impl From<&noema_core::storage::ResolvedMessage> for DisplayMessage {
             turn_id: Some(msg.turn_id.clone()),
             span_id: None,
             alternates: None,
+            created_at: Some(msg.created_at),
         }
     }
 }
@@ -279,6 +312,7 @@ impl From<&noema_core::storage::ResolvedMessage> for DisplayMessage {
 pub struct McpServerInfo {
     pub id: String,
     pub name: String,
+    /// HTTP endpoint, or the command line for stdio servers
     pub url: String,
     pub auth_type: String,
     pub is_connected: bool,
@@ -290,6 +324,8 @@ pub struct McpServerInfo {
     pub auto_connect: bool,
     /// Whether to auto-retry with exponential backoff
     pub auto_retry: bool,
+    /// Whether the server may ask for completions from the user's model
+    pub allow_sampling: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -299,6 +335,38 @@ pub struct McpToolInfo {
     pub name: String,
     pub description: Option<String>,
     pub server_id: String,
+    /// Hidden from the model by the server's tool filter
+    pub filtered_out: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct McpResourceInfo {
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+    pub server_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct McpPromptInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<McpPromptArgument>,
+    pub server_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct McpPromptArgument {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -334,6 +402,11 @@ pub struct Attachment {
     pub data: String,      // base64 encoded
 
     pub mime_type: String, // e.g., "image/png", "audio/mp3"
+
+    /// Original filename, e.g. "notes.md"
+    #[serde(default)]
+    #[ts(optional)]
+    pub name: Option<String>,
 }
 
 impl Into<noema_ext::Attachment> for Attachment {
@@ -341,6 +414,7 @@ impl Into<noema_ext::Attachment> for Attachment {
         noema_ext::Attachment {
             data: self.data,
             mime_type: self.mime_type,
+            name: self.name,
         }
     }
 }
@@ -358,7 +432,7 @@ pub struct ReferencedDocument {
 /// This preserves the exact position of document references and attachments inline with text.
 ///
 /// This is the TypeScript-facing type that mirrors `noema_core::storage::InputContent`.
-#[derive(Debug, Clone, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "camelCase")]
 #[ts(export, export_to = "../../src/generated/")]
 pub enum DisplayInputContent {
@@ -457,6 +531,21 @@ pub struct MessageCompleteEvent {
     pub messages: Vec<DisplayMessage>,
 }
 
+/// Kind of failure reported by an error event
+#[derive(Debug, Clone, Copy, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub enum ErrorKind {
+    Network,
+    RateLimited,
+    Auth,
+    ModelNotFound,
+    ContentFiltered,
+    Provider,
+    Storage,
+    Other,
+}
+
 /// Payload for error event
 #[derive(Debug, Clone, Serialize, TS)]
 #[serde(rename_all = "camelCase")]
@@ -465,6 +554,213 @@ pub struct ErrorEvent {
     #[ts(type = "string")]
     pub conversation_id: ConversationId,
     pub error: String,
+    pub kind: ErrorKind,
+    /// Seconds to wait before retrying, when rate limited and the provider said
+    pub retry_after_secs: Option<u32>,
+}
+
+impl ErrorEvent {
+    pub fn new(conversation_id: ConversationId, error: &ManagerError) -> Self {
+        let kind = match error {
+            ManagerError::Network(_) => ErrorKind::Network,
+            ManagerError::RateLimited { .. } => ErrorKind::RateLimited,
+            ManagerError::Auth(_) => ErrorKind::Auth,
+            ManagerError::ModelNotFound(_) => ErrorKind::ModelNotFound,
+            ManagerError::ContentFiltered { .. } => ErrorKind::ContentFiltered,
+            ManagerError::Provider(_) => ErrorKind::Provider,
+            ManagerError::Storage(_) => ErrorKind::Storage,
+            ManagerError::Other(_) => ErrorKind::Other,
+        };
+        let retry_after_secs = match error {
+            ManagerError::RateLimited { retry_after } => retry_after.map(|d| d.as_secs() as u32),
+            _ => None,
+        };
+        Self {
+            conversation_id,
+            error: error.to_string(),
+            kind,
+            retry_after_secs,
+        }
+    }
+}
+
+/// Payload for text_delta event (newly streamed assistant text, to append to the in-progress message)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct TextDeltaEvent {
+    #[ts(type = "string")]
+    pub conversation_id: ConversationId,
+    pub text: String,
+}
+
+/// Payload for tool_progress event (progress reported by an MCP server for a running tool call)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct ToolProgressEvent {
+    #[ts(type = "string")]
+    pub conversation_id: ConversationId,
+    pub tool_call_id: String,
+    pub message: String,
+}
+
+/// Payload for tool_call_delta event (the next fragment of a tool call's
+/// arguments while the model is still writing them)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct ToolCallDeltaEvent {
+    #[ts(type = "string")]
+    pub conversation_id: ConversationId,
+    pub tool_call_id: String,
+    pub name: String,
+    pub args_fragment: String,
+}
+
+/// Mono samples of a decoded audio content block
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct DecodedAudioResponse {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+/// A microphone voice input can capture from
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct InputDevice {
+    pub name: String,
+    /// Whether this is the system's default input
+    pub is_default: bool,
+}
+
+/// Payload for model_pull_progress event (an Ollama model being downloaded
+/// because a request needed it and the server didn't have it)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct ModelPullProgressEvent {
+    pub model: String,
+    pub status: String,
+    /// Percentage of the current layer downloaded, while downloading
+    pub percent: Option<u8>,
+}
+
+/// Payload for tool_approval_request event (a tool call waiting for the
+/// user's approval; answer with respond_tool_approval)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct ToolApprovalRequestEvent {
+    #[ts(type = "string")]
+    pub conversation_id: ConversationId,
+    pub tool_call_id: String,
+    pub name: String,
+    /// The call's arguments as JSON text
+    pub arguments: String,
+}
+
+/// Payload for mcp_sampling_request event (an MCP server asks to run a
+/// completion on the user's model; answer with respond_mcp_sampling)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct McpSamplingRequestEvent {
+    pub request_id: String,
+    /// Name of the server asking
+    pub server: String,
+    /// Model that will answer (provider's model ID)
+    pub model_id: String,
+    pub system_prompt: Option<String>,
+    /// The conversation to complete, one "Role: text" paragraph per message
+    pub messages: String,
+    /// Most tokens the completion may produce
+    pub max_tokens: Option<u32>,
+}
+
+/// Payload for usage event (token counts for the last completed request)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct UsageEvent {
+    #[ts(type = "string")]
+    pub conversation_id: ConversationId,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    /// Prompt tokens served from the provider's prompt cache
+    pub cached_tokens: u32,
+}
+
+/// Payload for history_trimmed event (oldest messages left out of the last
+/// request to fit the model's context window)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct HistoryTrimmedEvent {
+    #[ts(type = "string")]
+    pub conversation_id: ConversationId,
+    pub dropped_messages: u32,
+}
+
+/// Why the model stopped a response
+#[derive(Debug, Clone, Copy, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub enum FinishReason {
+    Stop,
+    Length,
+    ToolUse,
+    ContentFilter,
+    Other,
+}
+
+impl From<llm::FinishReason> for FinishReason {
+    fn from(reason: llm::FinishReason) -> Self {
+        match reason {
+            llm::FinishReason::Stop => FinishReason::Stop,
+            llm::FinishReason::Length => FinishReason::Length,
+            llm::FinishReason::ToolUse => FinishReason::ToolUse,
+            llm::FinishReason::ContentFilter { .. } => FinishReason::ContentFilter,
+            llm::FinishReason::Other => FinishReason::Other,
+        }
+    }
+}
+
+/// Payload for response_finished event (sent after message_complete when
+/// the provider said why the response ended; "length" means it was cut off)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct ResponseFinishedEvent {
+    #[ts(type = "string")]
+    pub conversation_id: ConversationId,
+    pub finish_reason: FinishReason,
+}
+
+/// Payload for context_compacted event (older messages summarized; 0 if
+/// there was nothing old enough to summarize)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct ContextCompactedEvent {
+    #[ts(type = "string")]
+    pub conversation_id: ConversationId,
+    pub summarized_messages: u32,
+}
+
+/// Payload for conversation_renamed event (an untitled conversation was named
+/// after its first exchange)
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct ConversationRenamedEvent {
+    #[ts(type = "string")]
+    pub conversation_id: ConversationId,
+    pub name: String,
 }
 
 /// Payload for model_changed event
@@ -489,6 +785,16 @@ pub struct TruncatedEvent {
     pub turn_id: Option<TurnId>,
 }
 
+/// Payload for cancelled event
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct CancelledEvent {
+    #[ts(type = "string")]
+    pub conversation_id: ConversationId,
+    pub messages: Vec<DisplayMessage>,
+}
+
 /// Configuration for which tools to enable for a message.
 /// Designed to be extensible for future tool set selection.
 #[derive(Debug, Clone, Default, Deserialize, TS)]
@@ -516,6 +822,93 @@ fn default_true() -> bool {
     true
 }
 
+/// Sampling settings for a conversation; unset fields use the provider's defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct GenerationParams {
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    /// Maximum number of tokens to generate
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Sequences that end generation when produced
+    #[serde(default)]
+    pub stop: Option<Vec<String>>,
+    /// Sampling seed for reproducible runs (best-effort; ignored by
+    /// providers without seed support)
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub seed: Option<u64>,
+}
+
+impl From<GenerationParams> for llm::GenerationParams {
+    fn from(params: GenerationParams) -> Self {
+        llm::GenerationParams {
+            temperature: params.temperature,
+            top_p: params.top_p,
+            max_tokens: params.max_tokens,
+            stop: params.stop,
+            seed: params.seed,
+        }
+    }
+}
+
+impl From<llm::GenerationParams> for GenerationParams {
+    fn from(params: llm::GenerationParams) -> Self {
+        GenerationParams {
+            temperature: params.temperature,
+            top_p: params.top_p,
+            max_tokens: params.max_tokens,
+            stop: params.stop,
+            seed: params.seed,
+        }
+    }
+}
+
+/// Preset system prompt, model and sampling settings for new conversations
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../src/generated/")]
+pub struct ConversationTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub system_message: Option<String>,
+    /// Model to use (provider/model); null keeps the current model
+    #[serde(default)]
+    pub model_id: Option<String>,
+    #[serde(default)]
+    pub generation_params: GenerationParams,
+}
+
+impl From<noema_core::Template> for ConversationTemplate {
+    fn from(template: noema_core::Template) -> Self {
+        ConversationTemplate {
+            name: template.name,
+            description: template.description,
+            system_message: template.system_message,
+            model_id: template.model_id,
+            generation_params: template.generation_params.into(),
+        }
+    }
+}
+
+impl From<ConversationTemplate> for noema_core::Template {
+    fn from(template: ConversationTemplate) -> Self {
+        noema_core::Template {
+            name: template.name,
+            description: template.description,
+            system_message: template.system_message,
+            model_id: template.model_id,
+            generation_params: template.generation_params.into(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod ts_export {
     use super::*;
@@ -523,6 +916,7 @@ mod ts_export {
     #[test]
     fn export_types() {
         ModelInfo::export_all().expect("Failed to export ModelInfo");
+        ProviderModels::export_all().expect("Failed to export ProviderModels");
         ConversationInfo::export_all().expect("Failed to export ConversationInfo");
         DisplayContent::export_all().expect("Failed to export DisplayContent");
         DisplayToolResultContent::export_all().expect("Failed to export DisplayToolResultContent");
@@ -530,17 +924,37 @@ mod ts_export {
         DisplayMessage::export_all().expect("Failed to export DisplayMessage");
         McpServerInfo::export_all().expect("Failed to export McpServerInfo");
         McpToolInfo::export_all().expect("Failed to export McpToolInfo");
+        McpResourceInfo::export_all().expect("Failed to export McpResourceInfo");
+        McpPromptInfo::export_all().expect("Failed to export McpPromptInfo");
         AddMcpServerRequest::export_all().expect("Failed to export AddMcpServerRequest");
         Attachment::export_all().expect("Failed to export Attachment");
         UserMessageEvent::export_all().expect("Failed to export UserMessageEvent");
         StreamingMessageEvent::export_all().expect("Failed to export StreamingMessageEvent");
         MessageCompleteEvent::export_all().expect("Failed to export MessageCompleteEvent");
+        ErrorKind::export_all().expect("Failed to export ErrorKind");
         ErrorEvent::export_all().expect("Failed to export ErrorEvent");
+        TextDeltaEvent::export_all().expect("Failed to export TextDeltaEvent");
+        ToolProgressEvent::export_all().expect("Failed to export ToolProgressEvent");
+        ToolCallDeltaEvent::export_all().expect("Failed to export ToolCallDeltaEvent");
+        ModelPullProgressEvent::export_all().expect("Failed to export ModelPullProgressEvent");
+        DecodedAudioResponse::export_all().expect("Failed to export DecodedAudioResponse");
+        InputDevice::export_all().expect("Failed to export InputDevice");
+        ToolApprovalRequestEvent::export_all().expect("Failed to export ToolApprovalRequestEvent");
+        McpSamplingRequestEvent::export_all().expect("Failed to export McpSamplingRequestEvent");
+        UsageEvent::export_all().expect("Failed to export UsageEvent");
+        HistoryTrimmedEvent::export_all().expect("Failed to export HistoryTrimmedEvent");
+        FinishReason::export_all().expect("Failed to export FinishReason");
+        ResponseFinishedEvent::export_all().expect("Failed to export ResponseFinishedEvent");
+        ContextCompactedEvent::export_all().expect("Failed to export ContextCompactedEvent");
+        ConversationRenamedEvent::export_all().expect("Failed to export ConversationRenamedEvent");
         ModelChangedEvent::export_all().expect("Failed to export ModelChangedEvent");
         TruncatedEvent::export_all().expect("Failed to export TruncatedEvent");
+        CancelledEvent::export_all().expect("Failed to export CancelledEvent");
         ReferencedDocument::export_all().expect("Failed to export ReferencedDocument");
         DisplayInputContent::export_all().expect("Failed to export DisplayInputContent");
         ForkInfoResponse::export_all().expect("Failed to export ForkInfoResponse");
         ToolConfig::export_all().expect("Failed to export ToolConfig");
+        GenerationParams::export_all().expect("Failed to export GenerationParams");
+        ConversationTemplate::export_all().expect("Failed to export ConversationTemplate");
     }
 }
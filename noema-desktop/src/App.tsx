@@ -9,17 +9,51 @@ import { Settings } from "./components/Settings";
 import { DocumentPanel } from "./components/DocumentPanel";
 import { ViewSelector } from "./components/ViewSelector";
 import { EditMessageModal } from "./components/EditMessageModal";
-import type { DisplayMessage, ModelInfo, ConversationInfo, InputContentBlock, ToolConfig } from "./generated";
+import type { AttachedFile, DisplayMessage, GenerationParams, ModelInfo, ConversationInfo, InputContentBlock, ToolConfig } from "./generated";
 import * as tauri from "./tauri";
 import { useVoiceInput } from "./hooks/useVoiceInput";
 import { appLog } from "./utils/log";
+import { applySetCommand, EMPTY_GENERATION_PARAMS } from "./utils/generationParams";
+
+/** Index of the last message the user typed (tool results don't count), or -1 */
+function lastUserIndex(messages: DisplayMessage[]): number {
+  for (let i = messages.length - 1; i >= 0; i--) {
+    const message = messages[i];
+    if (message.role === "user" && message.content.some((block) => "text" in block)) {
+      return i;
+    }
+  }
+  return -1;
+}
+
+/** Text of the last message the user typed */
+function lastUserText(messages: DisplayMessage[]): string | undefined {
+  const index = lastUserIndex(messages);
+  if (index < 0) return undefined;
+  return messages[index].content
+    .map((block) => ("text" in block ? block.text : ""))
+    .filter(Boolean)
+    .join("\n");
+}
 
 function App() {
   const [messages, setMessages] = useState<DisplayMessage[]>([]);
   const [streamingMessage, setStreamingMessage] = useState<DisplayMessage | null>(null);
   const [isLoading, setIsLoading] = useState(false);
+  // Name of the tool the model is writing a call for, while its arguments stream in
+  const [preparingTool, setPreparingTool] = useState<string | null>(null);
+  // Ollama model being downloaded before the request can run
+  const [modelPull, setModelPull] = useState<string | null>(null);
   const [error, setError] = useState<string | null>(null);
+  // Whether the output token limit cut the last response off
+  const [responseTruncated, setResponseTruncated] = useState(false);
+  // Output of slash commands that print something (e.g. "/models")
+  const [commandOutput, setCommandOutput] = useState<string | null>(null);
   const [conversations, setConversations] = useState<ConversationInfo[]>([]);
+  // Whether the conversation list includes archived conversations; the ref
+  // lets event listeners registered once refresh the list the same way
+  const [showArchived, setShowArchived] = useState(false);
+  const showArchivedRef = useRef(false);
   const [currentConversationId, setCurrentConversationId] = useState("");
   const [models, setModels] = useState<ModelInfo[]>([]);
   const [currentModel, setCurrentModel] = useState("");
@@ -48,8 +82,14 @@ function App() {
   const [forks, setForks] = useState<tauri.ForkInfo[]>([]);
   // Prefilled input text (used when forking from a user message to let them edit and resend)
   const [prefilledInput, setPrefilledInput] = useState<string>("");
+  // Whether the prefilled input is an edit of the last user message (Ctrl/Cmd+E)
+  const [editingLast, setEditingLast] = useState(false);
+  // Files attached with "/attach <path>", sent with the next message
+  const [pendingAttachments, setPendingAttachments] = useState<AttachedFile[]>([]);
   // Tools enabled state - controls whether MCP tools are sent to the model
   const [toolsEnabled, setToolsEnabled] = useState<boolean>(true);
+  // Sampling settings set with "/set", per conversation
+  const generationParamsRef = useRef<Map<string, GenerationParams>>(new Map());
   // Conversation privacy state - warns before using cloud models with private conversations
   const [isConversationPrivate, setIsConversationPrivate] = useState<boolean>(false);
   // Privacy warning dialog state
@@ -89,7 +129,7 @@ function App() {
       await tauri.setConversationPrivate(currentConversationId, newPrivate);
       setIsConversationPrivate(newPrivate);
       // Refresh conversation list to show updated privacy status
-      const convos = await tauri.listConversations();
+      const convos = await tauri.listConversations(showArchivedRef.current);
       setConversations(convos);
     } catch (err) {
       appLog.error("Toggle private error", String(err));
@@ -126,6 +166,48 @@ function App() {
     onError: handleVoiceError,
   });
 
+  // Ctrl/Cmd+Shift+Y copies the text of the last assistant message
+  useEffect(() => {
+    const handleKeyDown = async (e: KeyboardEvent) => {
+      if (!(e.ctrlKey || e.metaKey) || !e.shiftKey || e.key.toLowerCase() !== "y") return;
+      e.preventDefault();
+      const lastAssistant = [...messages].reverse().find((m) => m.role === "assistant");
+      const text = lastAssistant?.content
+        .map((block) => ("text" in block ? block.text : ""))
+        .filter(Boolean)
+        .join("\n\n");
+      if (!text) return;
+      try {
+        await navigator.clipboard.writeText(text);
+      } catch (err) {
+        setError(`Clipboard unavailable: ${err}`);
+      }
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, [messages]);
+
+  // Ctrl/Cmd+E puts the last user message in the input; sending it replaces
+  // that message and regenerates the response
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (!(e.ctrlKey || e.metaKey) || e.shiftKey || e.key.toLowerCase() !== "e") return;
+      e.preventDefault();
+      if (isLoading) return;
+      const text = lastUserText(messages);
+      if (!text) return;
+      setPrefilledInput(text);
+      setEditingLast(true);
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, [messages, isLoading]);
+
+  // The "continue" offer belongs to the conversation whose response was cut off
+  useEffect(() => {
+    setResponseTruncated(false);
+  }, [currentConversationId]);
+
   // Auto-scroll to bottom when new messages arrive
   const prevMessagesLengthRef = useRef(0);
 
@@ -152,7 +234,7 @@ function App() {
         }
         setCurrentModel(modelName);
 
-        const convos = await tauri.listConversations();
+        const convos = await tauri.listConversations(showArchivedRef.current);
         setConversations(convos);
 
         // Pick the most recent conversation, or create a new one
@@ -244,11 +326,51 @@ function App() {
       setCurrentConversationId((currentId) => {
         if (currentId === conversationId) {
           setStreamingMessage(msg);
+          setPreparingTool(null);
+          setModelPull(null);
+        }
+        return currentId;
+      });
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    tauri.onTextDelta(({ conversationId, text }) => {
+      setCurrentConversationId((currentId) => {
+        if (currentId === conversationId) {
+          setPreparingTool(null);
+          setModelPull(null);
+          setStreamingMessage((prev) => {
+            const last = prev?.content[prev.content.length - 1];
+            if (prev && last && "text" in last) {
+              const content = [...prev.content.slice(0, -1), { text: last.text + text }];
+              return { ...prev, content };
+            }
+            return {
+              role: "assistant",
+              content: [...(prev?.content ?? []), { text }],
+              turnId: undefined,
+              spanId: undefined,
+              alternates: null,
+              createdAt: undefined,
+            };
+          });
+        }
+        return currentId;
+      });
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    tauri.onToolCallDelta(({ conversationId, name }) => {
+      setCurrentConversationId((currentId) => {
+        if (currentId === conversationId) {
+          setPreparingTool(name);
         }
         return currentId;
       });
     }).then((unlisten) => unlisteners.push(unlisten));
 
+    tauri.onModelPullProgress(({ model, status, percent }) => {
+      setModelPull(`Downloading ${model}: ${status}${percent !== null ? ` (${percent}%)` : ""}`);
+    }).then((unlisten) => unlisteners.push(unlisten));
+
     tauri.onMessageComplete(({ conversationId }) => {
       // Only update if this event is for the current conversation
       setCurrentConversationId((currentId) => {
@@ -258,26 +380,77 @@ function App() {
             setMessages(Array.isArray(msgs) ? msgs : []);
           }).catch(console.error);
           setStreamingMessage(null);
+          setPreparingTool(null);
+          setModelPull(null);
           setIsLoading(false);
         }
         return currentId;
       });
       // Refresh conversations
-      tauri.listConversations().then(setConversations).catch(console.error);
+      tauri.listConversations(showArchivedRef.current).then(setConversations).catch(console.error);
     }).then((unlisten) => unlisteners.push(unlisten));
 
-    tauri.onError(({ conversationId, error }) => {
-      appLog.error("Backend error received", error);
+    tauri.onError(({ conversationId, error, kind }) => {
+      appLog.error(`Backend error received (${kind})`, error);
       setCurrentConversationId((currentId) => {
         if (currentId === conversationId) {
-          setError(error);
+          setError(kind === "auth" ? `${error} - check the provider's API key` : error);
           setIsLoading(false);
           setStreamingMessage(null);
+          setPreparingTool(null);
+          setModelPull(null);
+        }
+        return currentId;
+      });
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // Tool calls wait for an answer when "/approve on" is set
+    tauri.onToolApprovalRequest(({ conversationId, toolCallId, name, arguments: args }) => {
+      const approved = confirm(`Allow the model to run ${name}?\n\n${args}`);
+      tauri.respondToolApproval(conversationId, toolCallId, approved).catch((err) =>
+        appLog.error("Tool approval error", String(err))
+      );
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    // MCP servers with allow_sampling ask before using the model
+    tauri.onMcpSamplingRequest(({ requestId, server, modelId, systemPrompt, messages, maxTokens }) => {
+      const system = systemPrompt ? `System: ${systemPrompt}\n\n` : "";
+      const limit = maxTokens !== null ? ` (up to ${maxTokens} tokens)` : "";
+      const approved = confirm(
+        `Allow ${server} to run a completion on ${modelId}${limit}?\n\n${system}${messages}`
+      );
+      tauri.respondMcpSampling(requestId, approved).catch((err) =>
+        appLog.error("Sampling approval error", String(err))
+      );
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    tauri.onHistoryTrimmed(({ conversationId, droppedMessages }) => {
+      appLog.info(
+        `Left ${droppedMessages} oldest messages of ${conversationId} out of the request to fit the context window`
+      );
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    tauri.onResponseFinished(({ conversationId, finishReason }) => {
+      setCurrentConversationId((currentId) => {
+        if (currentId === conversationId) {
+          setResponseTruncated(finishReason === "length");
         }
         return currentId;
       });
     }).then((unlisten) => unlisteners.push(unlisten));
 
+    tauri.onContextCompacted(({ conversationId, summarizedMessages }) => {
+      appLog.info(
+        summarizedMessages > 0
+          ? `Summarized ${summarizedMessages} older messages of ${conversationId}`
+          : `Nothing old enough to summarize in ${conversationId}`
+      );
+    }).then((unlisten) => unlisteners.push(unlisten));
+
+    tauri.onConversationRenamed(() => {
+      tauri.listConversations(showArchivedRef.current).then(setConversations).catch(console.error);
+    }).then((unlisten) => unlisteners.push(unlisten));
+
     tauri.onModelChanged(({ conversationId, model }) => {
       setCurrentConversationId((currentId) => {
         if (currentId === conversationId) {
@@ -361,7 +534,7 @@ function App() {
         return currentId;
       });
       // Refresh conversations
-      tauri.listConversations().then(setConversations).catch(console.error);
+      tauri.listConversations(showArchivedRef.current).then(setConversations).catch(console.error);
     }).then((unlisten) => unlisteners.push(unlisten));
 
     tauri.onParallelModelError(({ modelId, error: modelError }) => {
@@ -379,6 +552,122 @@ function App() {
   const handleSendMessage = async (content: InputContentBlock[], toolConfig?: ToolConfig, skipPrivacyCheck?: boolean) => {
     try {
       setError(null);
+      setResponseTruncated(false);
+
+      // "/compact" summarizes older history, "/fork [n]" branches off after
+      // the n-th message (default: the last), "/attach <path>" queues a file
+      // for the next message, "/resource <server> <uri>" queues an MCP
+      // server's resource the same way, "/prompt <server> <name> [key=value
+      // ...]" starts from an MCP server's prompt template, "/approve on|off" toggles asking before each
+      // tool call, "/set temperature 0.2" changes sampling settings and
+      // "/new <template>" starts a conversation from a template,
+      // "/models [provider]" lists available models, "/voice download
+      // <size>" fetches a Whisper model (e.g. base.en), "/voice device
+      // [name]" lists or picks the microphone and "/speak" toggles
+      // reading voice responses aloud instead of sending
+      const first = content.length === 1 ? content[0] : null;
+      const attach = first?.type === "text" ? first.text.trim().match(/^\/attach\s+(.+)$/) : null;
+      if (attach) {
+        const attached = await tauri.attachFile(attach[1]);
+        appLog.info(`Attached ${attached.name} (${attached.content.length} blocks)`);
+        setPendingAttachments((prev) => [...prev, attached]);
+        return;
+      }
+      const resource = first?.type === "text" ? first.text.trim().match(/^\/resource\s+(\S+)\s+(\S+)$/) : null;
+      if (resource) {
+        const attached = await tauri.readMcpResource(resource[1], resource[2]);
+        appLog.info(`Attached ${attached.name} from ${resource[1]} (${attached.content.length} blocks)`);
+        setPendingAttachments((prev) => [...prev, attached]);
+        return;
+      }
+      const approve = first?.type === "text" ? first.text.trim().match(/^\/approve\s+(on|off)$/) : null;
+      if (approve) {
+        await tauri.setRequireToolApproval(currentConversationId, approve[1] === "on");
+        appLog.info(`Tool approval ${approve[1]} for ${currentConversationId}`);
+        return;
+      }
+      const newFromTemplate = first?.type === "text" ? first.text.trim().match(/^\/new\s+(\S+)$/) : null;
+      if (newFromTemplate) {
+        await handleNewConversation(newFromTemplate[1]);
+        return;
+      }
+      const listModels = first?.type === "text" ? first.text.trim().match(/^\/models(?:\s+(\S+))?$/) : null;
+      if (listModels) {
+        const results = await tauri.listProviderModels(listModels[1]);
+        setCommandOutput(
+          results
+            .map(({ provider, models, error }) =>
+              error !== null
+                ? `${provider}: unavailable (${error})`
+                : [`${provider}:`, ...models.map((m) => `  ${provider}/${m.id}  [${m.capabilities.join(", ")}]`)].join("\n")
+            )
+            .join("\n\n")
+        );
+        return;
+      }
+      const voiceDownload = first?.type === "text" ? first.text.trim().match(/^\/voice\s+download(?:\s+(\S+))?$/) : null;
+      if (voiceDownload) {
+        const model = voiceDownload[1] ? `ggml-${voiceDownload[1]}.bin` : undefined;
+        const label = model ?? "the configured Whisper model";
+        const unlisten = await tauri.onDownloadProgress((progress) => {
+          if (typeof progress === "number") {
+            setCommandOutput(`Downloading ${label}: ${progress}%`);
+          }
+        });
+        setCommandOutput(`Downloading ${label}…`);
+        try {
+          await tauri.downloadVoiceModel(model);
+          setCommandOutput(`Downloaded ${label}`);
+        } finally {
+          unlisten();
+        }
+        return;
+      }
+      const voiceDevice = first?.type === "text" ? first.text.trim().match(/^\/voice\s+device(?:\s+(.+))?$/) : null;
+      if (voiceDevice) {
+        const devices = await tauri.listVoiceInputDevices();
+        if (!voiceDevice[1]) {
+          setCommandOutput(
+            devices.length === 0
+              ? "No microphones found"
+              : devices.map((d) => `${d.name}${d.isDefault ? "  (default)" : ""}`).join("\n")
+          );
+          return;
+        }
+        const device = devices.find((d) => d.name === voiceDevice[1]);
+        await tauri.setVoiceInputDevice(device && !device.isDefault ? device.name : undefined);
+        setCommandOutput(
+          device
+            ? `Voice input from ${device.name} (from the next time voice is turned on)`
+            : `No microphone named "${voiceDevice[1]}", using the default`
+        );
+        return;
+      }
+      if (first?.type === "text" && first.text.trim() === "/speak") {
+        const speaking = await tauri.toggleSpeakResponses();
+        appLog.info(`Speaking voice responses ${speaking ? "on" : "off"}`);
+        return;
+      }
+      if (first?.type === "text" && first.text.trim() === "/compact") {
+        await tauri.compactConversation(currentConversationId);
+        return;
+      }
+      const fork = first?.type === "text" ? first.text.trim().match(/^\/fork(?:\s+(\d+))?$/) : null;
+      if (fork) {
+        const messageIndex = fork[1] ? Number(fork[1]) - 1 : undefined;
+        const newConversationId = await tauri.forkConversationAtMessage(currentConversationId, messageIndex);
+        await showFork(newConversationId);
+        return;
+      }
+      if (first?.type === "text") {
+        const current = generationParamsRef.current.get(currentConversationId) ?? EMPTY_GENERATION_PARAMS;
+        const params = applySetCommand(first.text, current);
+        if (params) {
+          await tauri.setGenerationParams(currentConversationId, params);
+          generationParamsRef.current.set(currentConversationId, params);
+          return;
+        }
+      }
 
       // Check if we need to show privacy warning (private conversation + cloud model)
       if (!skipPrivacyCheck && isConversationPrivate && !isCurrentModelPrivate()) {
@@ -390,11 +679,41 @@ function App() {
         return;
       }
 
+      // After the privacy check: the prompt's last message is sent to the model
+      const prompt = first?.type === "text" ? first.text.trim().match(/^\/prompt\s+(\S+)\s+(\S+)(.*)$/) : null;
+      if (prompt) {
+        // Values with spaces are quoted: topic="release notes"
+        const args: Record<string, string> = {};
+        for (const [, key, quoted, bare] of prompt[3].matchAll(/(\w+)=(?:"([^"]*)"|(\S+))/g)) {
+          args[key] = quoted ?? bare;
+        }
+        await tauri.applyMcpPrompt(currentConversationId, prompt[1], prompt[2], args, toolConfig);
+        return;
+      }
+
       // Clear any prefilled text after sending
       if (prefilledInput) {
         setPrefilledInput("");
       }
 
+      if (editingLast) {
+        setEditingLast(false);
+        const text = content
+          .map((block) => (block.type === "text" ? block.text : ""))
+          .filter(Boolean)
+          .join("\n");
+        // Drop the edited message and everything after it; the new one
+        // arrives with the user_message event
+        setMessages((prev) => prev.slice(0, lastUserIndex(prev)));
+        await tauri.editAndResend(currentConversationId, text, toolConfig);
+        return;
+      }
+
+      if (pendingAttachments.length > 0) {
+        content = [...content, ...pendingAttachments.flatMap((file) => file.content)];
+        setPendingAttachments([]);
+      }
+
       // Check if we have multiple models selected for parallel comparison
       if (selectedModelsForComparison.length >= 2) {
         // Clear any previous comparison results
@@ -414,13 +733,37 @@ function App() {
     }
   };
 
-  const handleNewConversation = async () => {
+  const handleContinueResponse = async () => {
+    setError(null);
+    setResponseTruncated(false);
+    setIsLoading(true);
+    try {
+      await tauri.continueResponse(currentConversationId);
+    } catch (err) {
+      appLog.error("Continue response error", String(err));
+      setError(String(err));
+      setIsLoading(false);
+    }
+  };
+
+  const handleNewConversation = async (templateName?: string) => {
     try {
-      const id = await tauri.newConversation();
+      const id = await tauri.newConversation(undefined, templateName);
+      if (templateName) {
+        // Keep "/set" working from the template's sampling settings
+        const template = (await tauri.listTemplates()).find((t) => t.name === templateName);
+        if (template) {
+          generationParamsRef.current.set(id, template.generationParams);
+          if (template.modelId) {
+            setCurrentModelId(template.modelId);
+            setCurrentModel(template.modelId.split("/").pop() ?? template.modelId);
+          }
+        }
+      }
       setCurrentConversationId(id);
       setMessages([]);
       setIsConversationPrivate(false); // New conversations start as non-private
-      const convos = await tauri.listConversations();
+      const convos = await tauri.listConversations(showArchivedRef.current);
       setConversations(convos);
     } catch (err) {
       appLog.error("New conversation error", String(err));
@@ -467,7 +810,7 @@ function App() {
 
       // Now delete the conversation
       await tauri.deleteConversation(id);
-      const convos = await tauri.listConversations();
+      const convos = await tauri.listConversations(showArchivedRef.current);
       setConversations(convos);
     } catch (err) {
       appLog.error("Delete conversation error", String(err));
@@ -478,7 +821,7 @@ function App() {
   const handleRenameConversation = async (id: string, name: string) => {
     try {
       await tauri.renameConversation(id, name);
-      const convos = await tauri.listConversations();
+      const convos = await tauri.listConversations(showArchivedRef.current);
       setConversations(convos);
     } catch (err) {
       appLog.error("Rename conversation error", String(err));
@@ -486,6 +829,40 @@ function App() {
     }
   };
 
+  const handlePinConversation = async (id: string, pinned: boolean) => {
+    try {
+      await tauri.setConversationPinned(id, pinned);
+      const convos = await tauri.listConversations(showArchivedRef.current);
+      setConversations(convos);
+    } catch (err) {
+      appLog.error("Pin conversation error", String(err));
+      setError(String(err));
+    }
+  };
+
+  const handleToggleShowArchived = async () => {
+    const next = !showArchivedRef.current;
+    showArchivedRef.current = next;
+    setShowArchived(next);
+    try {
+      setConversations(await tauri.listConversations(next));
+    } catch (err) {
+      appLog.error("List conversations error", String(err));
+      setError(String(err));
+    }
+  };
+
+  const handleArchiveConversation = async (id: string, archived: boolean) => {
+    try {
+      await tauri.setConversationArchived(id, archived);
+      const convos = await tauri.listConversations(showArchivedRef.current);
+      setConversations(convos);
+    } catch (err) {
+      appLog.error("Archive conversation error", String(err));
+      setError(String(err));
+    }
+  };
+
   const handleSelectModel = async (modelId: string, provider: string) => {
     try {
       await tauri.setModel(currentConversationId, modelId, provider);
@@ -533,23 +910,28 @@ function App() {
     }
   };
 
+  // Switch to a just-created fork
+  const showFork = async (newConversationId: string) => {
+    appLog.info(`Created fork: conversationId=${newConversationId}`);
+    // Load the forked conversation
+    const msgs = await tauri.loadConversation(newConversationId);
+    setCurrentConversationId(newConversationId);
+    setMessages(Array.isArray(msgs) ? msgs : []);
+    // Load forks for the new conversation
+    const convForks = await tauri.listConversationForks(newConversationId);
+    setForks(convForks);
+    // Refresh conversation list to include the fork
+    const convos = await tauri.listConversations(showArchivedRef.current);
+    setConversations(convos);
+  };
+
   // Fork handler - creates a new conversation forked at the given turn
   const handleFork = async (turnId: string, role: "user" | "assistant", userText?: string) => {
     appLog.info(`Forking at turn: turnId=${turnId}, role=${role}`);
     try {
       setError(null);
       const newConversationId = await tauri.forkConversation(currentConversationId, turnId);
-      appLog.info(`Created fork: conversationId=${newConversationId}`);
-      // Load the forked conversation
-      const msgs = await tauri.loadConversation(newConversationId);
-      setCurrentConversationId(newConversationId);
-      setMessages(Array.isArray(msgs) ? msgs : []);
-      // Load forks for the new conversation
-      const convForks = await tauri.listConversationForks(newConversationId);
-      setForks(convForks);
-      // Refresh conversation list to include the fork
-      const convos = await tauri.listConversations();
-      setConversations(convos);
+      await showFork(newConversationId);
       // For user messages, prefill the input with their original text so they can edit and resend
       if (role === "user" && userText) {
         setPrefilledInput(userText);
@@ -569,6 +951,7 @@ function App() {
   // Clear prefilled input (used after forking when user wants to cancel the edit)
   const handleClearPrefill = () => {
     setPrefilledInput("");
+    setEditingLast(false);
   };
 
   // Regenerate response at a specific turn
@@ -610,7 +993,7 @@ function App() {
       setForks(convForks);
 
       // Refresh conversation list to include the fork
-      const convos = await tauri.listConversations();
+      const convos = await tauri.listConversations(showArchivedRef.current);
       setConversations(convos);
 
       // Close the modal (AI response will stream in via events)
@@ -631,7 +1014,7 @@ function App() {
       if (!modelName) return; // Duplicate call
 
       setCurrentModel(modelName);
-      const convos = await tauri.listConversations();
+      const convos = await tauri.listConversations(showArchivedRef.current);
       setConversations(convos);
 
       // Pick the most recent conversation, or create a new one
@@ -725,10 +1108,14 @@ function App() {
         activeActivity={activeActivity}
         conversations={conversations}
         currentConversationId={currentConversationId}
-        onNewConversation={handleNewConversation}
+        onNewConversation={() => handleNewConversation()}
         onSelectConversation={handleSelectConversation}
         onDeleteConversation={handleDeleteConversation}
         onRenameConversation={handleRenameConversation}
+        onPinConversation={handlePinConversation}
+        onArchiveConversation={handleArchiveConversation}
+        showArchived={showArchived}
+        onToggleShowArchived={handleToggleShowArchived}
         selectedDocumentId={selectedDocumentId}
         onSelectDocument={setSelectedDocumentId}
       />
@@ -863,6 +1250,20 @@ function App() {
           </div>
         )}
 
+        {commandOutput && (
+          <div className="bg-surface text-foreground px-4 py-2 flex items-start justify-between gap-4">
+            <pre className="text-xs font-mono whitespace-pre-wrap max-h-64 overflow-y-auto flex-1">{commandOutput}</pre>
+            <button
+              onClick={() => setCommandOutput(null)}
+              className="text-muted hover:text-foreground"
+            >
+              <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M6 18L18 6M6 6l12 12" />
+              </svg>
+            </button>
+          </div>
+        )}
+
         {/* Content based on activity */}
         {activeActivity === "conversations" ? (
           <>
@@ -1004,6 +1405,25 @@ function App() {
                         </div>
                       </div>
                     )}
+                    {isLoading && preparingTool && !isParallelMode && (
+                      <div className="flex justify-start mb-4 text-sm text-muted italic">
+                        Preparing to call {preparingTool}…
+                      </div>
+                    )}
+                    {isLoading && modelPull && (
+                      <div className="flex justify-start mb-4 text-sm text-muted italic">
+                        {modelPull}…
+                      </div>
+                    )}
+                    {responseTruncated && !isLoading && (
+                      <div className="flex justify-start mb-4 text-sm text-muted italic">
+                        (truncated —&nbsp;
+                        <button onClick={handleContinueResponse} className="underline hover:text-foreground">
+                          continue
+                        </button>
+                        )
+                      </div>
+                    )}
                   </>
                 )}
                 <div ref={messagesEndRef} />
@@ -1020,13 +1440,31 @@ function App() {
               onSendToModels={handleSendToMultipleModels}
             />
 
+            {/* Files queued with /attach */}
+            {pendingAttachments.length > 0 && (
+              <div className="flex items-center gap-2 px-4 py-1 text-xs text-muted">
+                <span>
+                  Attached: {pendingAttachments.map((file) => file.name).join(", ")}
+                </span>
+                <button
+                  className="hover:text-foreground"
+                  onClick={() => setPendingAttachments([])}
+                  title="Remove attachments"
+                >
+                  ×
+                </button>
+              </div>
+            )}
+
             {/* Input area */}
             <ChatInput
               onSend={handleSendMessage}
+              conversationId={currentConversationId}
               disabled={isLoading}
               voiceAvailable={voice.isAvailable}
               voiceStatus={voice.status}
               voiceBufferedCount={voice.bufferedCount}
+              voicePartial={voice.partialText}
               onToggleVoice={voice.toggle}
               prefilledText={prefilledInput}
               onClearPrefill={handleClearPrefill}
@@ -7,6 +7,7 @@ export function ApiKeySettings() {
   const [keyStatus, setKeyStatus] = useState<Record<string, boolean>>({});
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
+  const [warning, setWarning] = useState<string | null>(null);
   const [editingProvider, setEditingProvider] = useState<string | null>(null);
   const [apiKeyInput, setApiKeyInput] = useState("");
   const [saving, setSaving] = useState(false);
@@ -37,7 +38,7 @@ export function ApiKeySettings() {
     try {
       setSaving(true);
       setError(null);
-      await tauri.setApiKey(provider, apiKeyInput.trim());
+      setWarning(await tauri.setApiKey(provider, apiKeyInput.trim()));
       setKeyStatus((prev) => ({ ...prev, [provider]: true }));
       setEditingProvider(null);
       setApiKeyInput("");
@@ -90,6 +91,16 @@ export function ApiKeySettings() {
         </div>
       )}
 
+      {/* Warning banner (e.g. no system keychain) */}
+      {warning && (
+        <div className="px-4 py-2 bg-yellow-900/50 text-yellow-200 text-sm rounded-lg">
+          {warning}
+          <button onClick={() => setWarning(null)} className="ml-2 underline">
+            dismiss
+          </button>
+        </div>
+      )}
+
       {/* Providers requiring API keys */}
       <div>
         <h3 className="text-sm font-medium text-gray-300 mb-3">
@@ -184,7 +195,8 @@ export function ApiKeySettings() {
                       </button>
                     </div>
                     <p className="text-xs text-muted mt-2">
-                      Your API key will be encrypted and stored locally.
+                      Your API key will be stored in the system keychain, or
+                      encrypted locally if no keychain is available.
                     </p>
                   </div>
                 )}
@@ -224,8 +236,8 @@ export function ApiKeySettings() {
       {/* Info section */}
       <div className="text-sm text-muted bg-elevated rounded-lg p-4">
         <p className="mb-2">
-          <strong>Priority:</strong> Settings API keys take priority over
-          environment variables.
+          <strong>Priority:</strong> API keys saved here (in the system
+          keychain) take priority over environment variables.
         </p>
         <p>
           If no API key is configured here, the app will fall back to
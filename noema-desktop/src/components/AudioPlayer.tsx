@@ -11,6 +11,30 @@ interface AudioPlayerProps {
 // Number of bars in the waveform visualization
 const WAVEFORM_BARS = 50;
 
+function toBase64(bytes: ArrayBuffer): string {
+  const view = new Uint8Array(bytes);
+  let binary = "";
+  for (let i = 0; i < view.length; i++) {
+    binary += String.fromCharCode(view[i]);
+  }
+  return btoa(binary);
+}
+
+// The webview decodes WAV/MP3/OGG itself; anything else (e.g. raw PCM from
+// Gemini) is decoded by the backend
+async function decodeAudio(audioContext: AudioContext, bytes: ArrayBuffer, mimeType: string): Promise<AudioBuffer> {
+  try {
+    // decodeAudioData detaches the buffer it is given
+    return await audioContext.decodeAudioData(bytes.slice(0));
+  } catch (err) {
+    audioLog.debug("Webview can't decode audio, decoding in backend", { mimeType, err });
+    const { samples, sampleRate } = await tauri.decodeAudio(toBase64(bytes), mimeType);
+    const buffer = audioContext.createBuffer(1, Math.max(samples.length, 1), sampleRate);
+    buffer.copyToChannel(Float32Array.from(samples), 0);
+    return buffer;
+  }
+}
+
 export function AudioPlayer({ data, src, mimeType }: AudioPlayerProps) {
   const [isPlaying, setIsPlaying] = useState(false);
   const [error, setError] = useState<string | null>(null);
@@ -52,7 +76,7 @@ export function AudioPlayer({ data, src, mimeType }: AudioPlayerProps) {
         const bytes = await getAudioBytes();
 
         // Decode audio data
-        const audioBuffer = await audioContext.decodeAudioData(bytes);
+        const audioBuffer = await decodeAudio(audioContext, bytes, mimeType);
         audioBufferRef.current = audioBuffer;
         setDuration(audioBuffer.duration);
 
@@ -87,7 +111,7 @@ export function AudioPlayer({ data, src, mimeType }: AudioPlayerProps) {
     };
 
     extractWaveform();
-  }, [data, src, getAudioBytes]);
+  }, [data, src, mimeType, getAudioBytes]);
 
   const stopPlayback = useCallback(() => {
     if (sourceRef.current) {
@@ -141,7 +165,7 @@ export function AudioPlayer({ data, src, mimeType }: AudioPlayerProps) {
       const bytes = await getAudioBytes();
 
       // Decode audio data
-      const audioBuffer = await audioContext.decodeAudioData(bytes);
+      const audioBuffer = await decodeAudio(audioContext, bytes, mimeType);
 
       // Create and configure source node
       const source = audioContext.createBufferSource();
@@ -175,7 +199,7 @@ export function AudioPlayer({ data, src, mimeType }: AudioPlayerProps) {
       setError(`Failed to play audio: ${message}`);
       console.error("Audio playback error:", err);
     }
-  }, [getAudioBytes, stopPlayback, updateProgress]);
+  }, [getAudioBytes, mimeType, stopPlayback, updateProgress]);
 
   const toggle = useCallback(() => {
     if (isPlaying) {
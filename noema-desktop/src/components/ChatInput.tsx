@@ -22,10 +22,14 @@ type EditorBlock =
 
 interface ChatInputProps {
   onSend: (content: InputContentBlock[], toolConfig?: ToolConfig) => void;
+  /** Conversation the input is sent to, so private ones stay out of input history */
+  conversationId?: string;
   disabled?: boolean;
   voiceAvailable?: boolean;
   voiceStatus?: VoiceStatus;
   voiceBufferedCount?: number;
+  /** Provisional transcription of the utterance in progress */
+  voicePartial?: string;
   onToggleVoice?: () => void;
   /** Prefilled text (e.g., when forking from a user message to let them edit) */
   prefilledText?: string;
@@ -76,6 +80,7 @@ async function fileToAttachment(file: File): Promise<Attachment | null> {
       resolve({
         data: base64,
         mimeType: file.type,
+        name: file.name,
       });
     };
     reader.onerror = () => resolve(null);
@@ -97,7 +102,7 @@ async function filePathToAttachment(filePath: string): Promise<Attachment | null
         .map((byte) => String.fromCharCode(byte))
         .join("")
     );
-    return { data: base64, mimeType };
+    return { data: base64, mimeType, name: filePath.split(/[\\/]/).pop() };
   } catch (err) {
     console.error("Failed to read file:", filePath, err);
     return null;
@@ -119,10 +124,12 @@ function getReferencedDocs(blocks: EditorBlock[]): { id: string; title: string }
 
 export function ChatInput({
   onSend,
+  conversationId,
   disabled = false,
   voiceAvailable = false,
   voiceStatus = "disabled",
   voiceBufferedCount = 0,
+  voicePartial = "",
   onToggleVoice,
   prefilledText = "",
   onClearPrefill,
@@ -138,22 +145,40 @@ export function ChatInput({
   const editorRef = useRef<HTMLDivElement>(null);
   const containerRef = useRef<HTMLDivElement>(null);
 
+  // Replace the input with plain text and put the cursor at the end
+  const setInputText = useCallback((text: string) => {
+    needsDomSyncRef.current = true;
+    setBlocks([{ type: "text", text }]);
+    setTimeout(() => {
+      editorRef.current?.focus();
+      // Move cursor to end
+      const selection = window.getSelection();
+      if (selection && editorRef.current) {
+        selection.selectAllChildren(editorRef.current);
+        selection.collapseToEnd();
+      }
+    }, 0);
+  }, []);
+
   // When prefilledText changes (fork from user message), update the input
   useEffect(() => {
     if (prefilledText) {
-      needsDomSyncRef.current = true;
-      setBlocks([{ type: "text", text: prefilledText }]);
-      setTimeout(() => {
-        editorRef.current?.focus();
-        // Move cursor to end
-        const selection = window.getSelection();
-        if (selection && editorRef.current) {
-          selection.selectAllChildren(editorRef.current);
-          selection.collapseToEnd();
-        }
-      }, 0);
+      setInputText(prefilledText);
     }
-  }, [prefilledText]);
+  }, [prefilledText, setInputText]);
+
+  // Previously sent messages for Up/Down recall, persisted across restarts
+  const historyRef = useRef<string[]>([]);
+  // Position while stepping through history; null when not browsing
+  const historyIndexRef = useRef<number | null>(null);
+
+  useEffect(() => {
+    tauri.getInputHistory()
+      .then((entries) => {
+        historyRef.current = entries;
+      })
+      .catch((err) => console.error("Failed to load input history:", err));
+  }, []);
 
   // @ mention autocomplete state
   const [mentionState, setMentionState] = useState<MentionState>({
@@ -256,6 +281,9 @@ export function ChatInput({
     const editor = editorRef.current;
     if (!editor) return;
 
+    // Editing a recalled entry ends history browsing
+    historyIndexRef.current = null;
+
     // Parse the DOM back into blocks
     const newBlocks: EditorBlock[] = [];
     const children = Array.from(editor.childNodes);
@@ -530,6 +558,19 @@ export function ChatInput({
       }
     }
 
+    const text = blocks
+      .map((block) => (block.type === "text" ? block.text : ""))
+      .join("")
+      .trim();
+    if (text) {
+      const history = historyRef.current;
+      if (history[history.length - 1] !== text) {
+        historyRef.current = [...history, text];
+      }
+      tauri.pushInputHistory(text, conversationId).catch((err) => console.error("Failed to save input history:", err));
+    }
+    historyIndexRef.current = null;
+
     if (contentBlocks.length > 0) {
       // Build tool config based on current toggle state
       const toolConfig: ToolConfig = { enabled: toolsEnabled, serverIds: null, toolNames: null };
@@ -538,7 +579,7 @@ export function ChatInput({
       setBlocks([{ type: "text", text: "" }]);
       setAttachments([]);
     }
-  }, [blocks, attachments, disabled, onSend, toolsEnabled]);
+  }, [blocks, attachments, disabled, onSend, toolsEnabled, conversationId]);
 
   const handleKeyDown = useCallback(
     (e: React.KeyboardEvent) => {
@@ -598,13 +639,32 @@ export function ChatInput({
         }
       }
 
+      // Up/Down step through history when the input is empty or showing a recalled entry
+      if (e.key === "ArrowUp" || e.key === "ArrowDown") {
+        const history = historyRef.current;
+        const browsing = historyIndexRef.current !== null;
+        if (history.length > 0 && (browsing || (e.key === "ArrowUp" && !hasContent(blocks)))) {
+          e.preventDefault();
+          const current = historyIndexRef.current ?? history.length;
+          const next = e.key === "ArrowUp" ? Math.max(current - 1, 0) : current + 1;
+          if (next >= history.length) {
+            historyIndexRef.current = null;
+            setInputText("");
+          } else {
+            historyIndexRef.current = next;
+            setInputText(history[next]);
+          }
+          return;
+        }
+      }
+
       // Submit on Enter (without Shift)
       if (e.key === "Enter" && !e.shiftKey) {
         e.preventDefault();
         handleSubmit();
       }
     },
-    [mentionState, mentionResults, insertMention, handleSubmit, removeDocRef]
+    [mentionState, mentionResults, insertMention, handleSubmit, removeDocRef, blocks, setInputText]
   );
 
   const handleRemoveAttachment = (index: number) => {
@@ -898,15 +958,17 @@ export function ChatInput({
             {/* Placeholder */}
             {isEmpty && (
               <div className="absolute left-4 top-3 text-muted pointer-events-none">
-                {voiceStatus === "listening"
-                  ? "Listening... speak now"
-                  : voiceStatus === "transcribing"
-                    ? "Transcribing..."
-                    : voiceStatus === "buffering"
-                      ? `${voiceBufferedCount} message${voiceBufferedCount !== 1 ? "s" : ""} queued while thinking...`
-                      : attachments.length > 0 || referencedDocs.length > 0
-                        ? "Add a message..."
-                        : "Type a message, @ to reference docs..."}
+                {voicePartial && (voiceStatus === "listening" || voiceStatus === "transcribing")
+                  ? `${voicePartial}...`
+                  : voiceStatus === "listening"
+                    ? "Listening... speak now"
+                    : voiceStatus === "transcribing"
+                      ? "Transcribing..."
+                      : voiceStatus === "buffering"
+                        ? `${voiceBufferedCount} message${voiceBufferedCount !== 1 ? "s" : ""} queued while thinking...`
+                        : attachments.length > 0 || referencedDocs.length > 0
+                          ? "Add a message..."
+                          : "Type a message, @ to reference docs..."}
               </div>
             )}
           </div>
@@ -0,0 +1,123 @@
+import { useState, useEffect } from "react";
+import * as tauri from "../tauri";
+
+export function CompactionSettings() {
+  const [thresholdInput, setThresholdInput] = useState("");
+  const [summaryModel, setSummaryModel] = useState("");
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [success, setSuccess] = useState<string | null>(null);
+
+  useEffect(() => {
+    tauri
+      .getCompactionSettings()
+      .then((settings) => {
+        setThresholdInput(settings.thresholdTokens?.toString() ?? "");
+        setSummaryModel(settings.summaryModel ?? "");
+      })
+      .catch((err) => setError(String(err)))
+      .finally(() => setLoading(false));
+  }, []);
+
+  const handleSave = async () => {
+    const threshold = thresholdInput.trim() ? Number(thresholdInput.trim()) : null;
+    if (threshold !== null && (!Number.isInteger(threshold) || threshold < 0)) {
+      setError("The threshold must be a whole number of tokens");
+      return;
+    }
+
+    try {
+      setSaving(true);
+      setError(null);
+      setSuccess(null);
+      await tauri.setCompactionSettings({
+        thresholdTokens: threshold || null,
+        summaryModel: summaryModel.trim() || null,
+      });
+      setSuccess("Saved");
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return <div className="text-center py-8 text-muted">Loading settings...</div>;
+  }
+
+  return (
+    <div className="space-y-6">
+      <div>
+        <h3 className="text-lg font-medium text-foreground mb-2">
+          History Summarization
+        </h3>
+        <p className="text-sm text-muted">
+          Long conversations can have their older messages summarized so they
+          fit the model's context. The messages themselves are kept. Type
+          /compact in a conversation to summarize it right away.
+        </p>
+      </div>
+
+      {error && (
+        <div className="px-4 py-2 bg-red-900/50 text-red-200 text-sm rounded-lg">
+          {error}
+          <button onClick={() => setError(null)} className="ml-2 underline">
+            dismiss
+          </button>
+        </div>
+      )}
+
+      {success && (
+        <div className="px-4 py-2 bg-teal-900/50 text-teal-200 text-sm rounded-lg">
+          {success}
+        </div>
+      )}
+
+      <div className="space-y-3">
+        <div>
+          <label
+            htmlFor="compactionThreshold"
+            className="block text-sm font-medium text-foreground mb-1"
+          >
+            Summarize automatically above (tokens)
+          </label>
+          <input
+            id="compactionThreshold"
+            type="number"
+            min={0}
+            value={thresholdInput}
+            onChange={(e) => setThresholdInput(e.target.value)}
+            placeholder="Only on /compact"
+            className="w-full px-3 py-2 bg-surface border border-gray-700 rounded-md text-foreground placeholder-muted focus:outline-none focus:ring-2 focus:ring-teal-500"
+          />
+        </div>
+        <div>
+          <label
+            htmlFor="summaryModel"
+            className="block text-sm font-medium text-foreground mb-1"
+          >
+            Summary model
+          </label>
+          <input
+            id="summaryModel"
+            type="text"
+            value={summaryModel}
+            onChange={(e) => setSummaryModel(e.target.value)}
+            placeholder="The conversation's model (e.g. claude/claude-haiku-4-5)"
+            className="w-full px-3 py-2 bg-surface border border-gray-700 rounded-md text-foreground placeholder-muted focus:outline-none focus:ring-2 focus:ring-teal-500"
+          />
+        </div>
+      </div>
+
+      <button
+        onClick={handleSave}
+        disabled={saving}
+        className="px-4 py-2 bg-teal-600 hover:bg-teal-700 disabled:bg-gray-600 text-white rounded"
+      >
+        {saving ? "Saving..." : "Save"}
+      </button>
+    </div>
+  );
+}
@@ -1,6 +1,6 @@
 import { useState, useEffect, useCallback } from "react";
 import { open } from "@tauri-apps/plugin-shell";
-import type { McpServerInfo, McpToolInfo } from "../types";
+import type { McpPromptInfo, McpResourceInfo, McpServerInfo, McpToolInfo } from "../types";
 import * as tauri from "../tauri";
 
 export function McpSettingsContent() {
@@ -12,6 +12,12 @@ export function McpSettingsContent() {
   const [serverTools, setServerTools] = useState<Record<string, McpToolInfo[]>>(
     {}
   );
+  const [serverResources, setServerResources] = useState<Record<string, McpResourceInfo[]>>(
+    {}
+  );
+  const [serverPrompts, setServerPrompts] = useState<Record<string, McpPromptInfo[]>>(
+    {}
+  );
   const [oauthPending, setOauthPending] = useState<string | null>(null);
   const [addingServer, setAddingServer] = useState(false);
 
@@ -174,8 +180,14 @@ export function McpSettingsContent() {
     const server = servers.find((s) => s.id === serverId);
     if (server?.isConnected && !serverTools[serverId]) {
       try {
-        const tools = await tauri.getMcpServerTools(serverId);
+        const [tools, resources, prompts] = await Promise.all([
+          tauri.getMcpServerTools(serverId),
+          tauri.getMcpServerResources(serverId),
+          tauri.getMcpServerPrompts(serverId),
+        ]);
         setServerTools((prev) => ({ ...prev, [serverId]: tools }));
+        setServerResources((prev) => ({ ...prev, [serverId]: resources }));
+        setServerPrompts((prev) => ({ ...prev, [serverId]: prompts }));
       } catch (err) {
         setError(String(err));
       }
@@ -319,6 +331,55 @@ export function McpSettingsContent() {
                       </ul>
                     </div>
                   )}
+
+                  {/* Resources list; "/resource <server> <uri>" attaches one */}
+                  {server.isConnected && serverResources[server.id]?.length > 0 && (
+                    <div>
+                      <h4 className="text-sm font-medium text-gray-300 mb-2">
+                        Resources:
+                      </h4>
+                      <ul className="text-sm space-y-1">
+                        {serverResources[server.id].map((resource) => (
+                          <li key={resource.uri} className="text-muted">
+                            <span className="font-mono">{resource.uri}</span>
+                            {resource.description && (
+                              <span className="text-gray-500">
+                                {" "}
+                                - {resource.description}
+                              </span>
+                            )}
+                          </li>
+                        ))}
+                      </ul>
+                    </div>
+                  )}
+
+                  {/* Prompts list; "/prompt <server> <name> key=value" applies one */}
+                  {server.isConnected && serverPrompts[server.id]?.length > 0 && (
+                    <div>
+                      <h4 className="text-sm font-medium text-gray-300 mb-2">
+                        Prompts:
+                      </h4>
+                      <ul className="text-sm space-y-1">
+                        {serverPrompts[server.id].map((prompt) => (
+                          <li key={prompt.name} className="text-muted">
+                            <span className="font-mono">
+                              {prompt.name}
+                              {prompt.arguments.map((arg) =>
+                                arg.required ? ` ${arg.name}=…` : ` [${arg.name}=…]`
+                              )}
+                            </span>
+                            {prompt.description && (
+                              <span className="text-gray-500">
+                                {" "}
+                                - {prompt.description}
+                              </span>
+                            )}
+                          </li>
+                        ))}
+                      </ul>
+                    </div>
+                  )}
                 </div>
               )}
             </li>
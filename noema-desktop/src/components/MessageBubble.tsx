@@ -25,6 +25,18 @@ function extractRawMarkdown(content: DisplayContent[]): string {
     .join("\n\n");
 }
 
+// Relative time a message was sent, e.g. "5 min ago", with the full date after it
+function formatMessageTime(timestamp: number): string {
+  const date = new Date(timestamp);
+  const minutes = Math.floor((Date.now() - timestamp) / (1000 * 60));
+  let relative: string;
+  if (minutes < 1) relative = "Just now";
+  else if (minutes < 60) relative = `${minutes} min ago`;
+  else if (minutes < 60 * 24) relative = `${Math.floor(minutes / 60)} h ago`;
+  else relative = `${Math.floor(minutes / (60 * 24))} days ago`;
+  return `${relative} (${date.toLocaleString()})`;
+}
+
 // Copy icon component
 const CopyIcon = () => (
   <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
@@ -162,6 +174,7 @@ export function MessageBubble({ message, onDocumentClick, onSwitchAlternate, onF
       className={`flex ${isUser ? "justify-end" : "justify-start"} mb-4 group`}
     >
       <div
+        title={message.createdAt !== undefined ? formatMessageTime(message.createdAt) : undefined}
         className={`max-w-[85%] px-4 py-3 rounded-2xl relative ${isUser ? "bg-teal-600 text-white" : isSystem ? "bg-amber-500/20 text-amber-100" : "bg-surface text-foreground"}`}
       >
         {/* Show alternates selector for assistant messages with multiple responses */}
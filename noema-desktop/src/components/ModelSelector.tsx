@@ -17,6 +17,12 @@ function formatContextWindow(tokens: number | null): string {
   return String(tokens);
 }
 
+function contextWindowTitle(model: ModelInfo): string {
+  const title = `Context window: ${model.contextWindow?.toLocaleString()} tokens`;
+  if (model.maxOutputTokens === null) return title;
+  return `${title}\nMax output: ${model.maxOutputTokens.toLocaleString()} tokens`;
+}
+
 // Format model name for display - strip provider prefix, truncate middle if needed
 function formatModelName(name: string, maxLen = 28): string {
   // Strip provider prefix (e.g., "ollama/llama3" -> "llama3")
@@ -235,7 +241,7 @@ export function ModelSelector({
               {/* Context window */}
               {contextWindow && (
                 <span
-                  title={`Context window: ${model.contextWindow?.toLocaleString()} tokens`}
+                  title={contextWindowTitle(model)}
                   className="text-[10px] text-gray-500 bg-gray-800 px-1.5 py-0.5 rounded"
                 >
                   {contextWindow}
@@ -280,7 +286,7 @@ export function ModelSelector({
               {/* Context window badge */}
               {currentModelObj?.contextWindow && (
                 <span
-                  title={`Context window: ${currentModelObj.contextWindow.toLocaleString()} tokens`}
+                  title={contextWindowTitle(currentModelObj)}
                   className="text-[10px] text-gray-500 bg-gray-800 px-1.5 py-0.5 rounded shrink-0"
                 >
                   {formatContextWindow(currentModelObj.contextWindow)}
@@ -2,8 +2,9 @@ import { useState } from "react";
 import { McpSettingsContent } from "./McpSettingsContent";
 import { ApiKeySettings } from "./ApiKeySettings";
 import { GoogleDocsSettings } from "./GoogleDocsSettings";
+import { CompactionSettings } from "./CompactionSettings";
 
-type TabId = "mcp" | "apikeys" | "gdocs";
+type TabId = "mcp" | "apikeys" | "gdocs" | "history";
 
 interface SettingsProps {
   onClose: () => void;
@@ -19,6 +20,7 @@ const tabs: TabConfig[] = [
   { id: "mcp", label: "MCP Servers" },
   { id: "apikeys", label: "API Keys" },
   { id: "gdocs", label: "Google Docs" },
+  { id: "history", label: "History" },
 ];
 
 export function Settings({ onClose, initialTab = "mcp" }: SettingsProps) {
@@ -74,6 +76,7 @@ export function Settings({ onClose, initialTab = "mcp" }: SettingsProps) {
           {activeTab === "mcp" && <McpSettingsContent />}
           {activeTab === "apikeys" && <ApiKeySettings />}
           {activeTab === "gdocs" && <GoogleDocsSettings />}
+          {activeTab === "history" && <CompactionSettings />}
         </div>
       </div>
     </div>
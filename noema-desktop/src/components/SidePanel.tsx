@@ -11,6 +11,10 @@ interface SidePanelProps {
   onSelectConversation: (id: string) => void;
   onDeleteConversation: (id: string) => void;
   onRenameConversation: (id: string, name: string) => void;
+  onPinConversation: (id: string, pinned: boolean) => void;
+  onArchiveConversation: (id: string, archived: boolean) => void;
+  showArchived: boolean;
+  onToggleShowArchived: () => void;
   // Document props
   selectedDocumentId?: string | null;
   onSelectDocument?: (docId: string) => void;
@@ -24,6 +28,10 @@ export function SidePanel({
   onSelectConversation,
   onDeleteConversation,
   onRenameConversation,
+  onPinConversation,
+  onArchiveConversation,
+  showArchived,
+  onToggleShowArchived,
   selectedDocumentId,
   onSelectDocument,
 }: SidePanelProps) {
@@ -37,6 +45,10 @@ export function SidePanel({
           onSelectConversation={onSelectConversation}
           onDeleteConversation={onDeleteConversation}
           onRenameConversation={onRenameConversation}
+          onPinConversation={onPinConversation}
+          onArchiveConversation={onArchiveConversation}
+          showArchived={showArchived}
+          onToggleShowArchived={onToggleShowArchived}
         />
       )}
       {activeActivity === "documents" && (
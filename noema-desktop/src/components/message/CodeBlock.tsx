@@ -0,0 +1,120 @@
+import { useState } from "react";
+
+type TokenKind = "comment" | "string" | "number" | "keyword" | "plain";
+
+interface Token {
+  kind: TokenKind;
+  text: string;
+}
+
+const KEYWORDS = new Set([
+  // Shared by most C-like languages
+  "break", "case", "class", "const", "continue", "default", "do", "else", "enum",
+  "export", "false", "for", "if", "import", "in", "interface", "let", "match",
+  "new", "null", "return", "static", "struct", "switch", "this", "true", "type",
+  "var", "while",
+  // Rust
+  "as", "async", "await", "crate", "dyn", "fn", "impl", "loop", "mod", "move",
+  "mut", "pub", "ref", "self", "Self", "super", "trait", "unsafe", "use", "where",
+  // Python
+  "and", "def", "del", "elif", "except", "from", "lambda", "None", "not", "or",
+  "pass", "raise", "True", "False", "try", "with", "yield",
+  // JavaScript / TypeScript
+  "catch", "extends", "finally", "function", "instanceof", "of", "throw",
+  "typeof", "undefined",
+]);
+
+// Languages that use `#` for line comments instead of `//`
+const HASH_COMMENT_LANGUAGES = new Set([
+  "bash", "python", "py", "ruby", "rb", "sh", "shell", "toml", "yaml", "yml", "zsh",
+]);
+
+// Alternation order matters: comments and strings win over anything inside them
+const SLASH_PATTERN =
+  /(\/\/.*|\/\*[\s\S]*?\*\/)|("(?:\\.|[^"\\])*"|'(?:\\.|[^'\\\n])*'|`(?:\\.|[^`\\])*`)|(\b\d[\d_]*(?:\.\d+)?\b)|([A-Za-z_]\w*)/g;
+const HASH_PATTERN =
+  /(#.*)|("(?:\\.|[^"\\])*"|'(?:\\.|[^'\\\n])*')|(\b\d[\d_]*(?:\.\d+)?\b)|([A-Za-z_]\w*)/g;
+
+/** Split code into tokens for lightweight syntax highlighting */
+export function highlightCode(code: string, language: string): Token[] {
+  const pattern = new RegExp(
+    HASH_COMMENT_LANGUAGES.has(language) ? HASH_PATTERN : SLASH_PATTERN
+  );
+  const tokens: Token[] = [];
+  let last = 0;
+  for (const match of code.matchAll(pattern)) {
+    const [text, comment, string, number, word] = match;
+    const index = match.index ?? 0;
+    if (index > last) {
+      tokens.push({ kind: "plain", text: code.slice(last, index) });
+    }
+    let kind: TokenKind = "plain";
+    if (comment !== undefined) kind = "comment";
+    else if (string !== undefined) kind = "string";
+    else if (number !== undefined) kind = "number";
+    else if (word !== undefined && KEYWORDS.has(word)) kind = "keyword";
+    tokens.push({ kind, text });
+    last = index + text.length;
+  }
+  if (last < code.length) {
+    tokens.push({ kind: "plain", text: code.slice(last) });
+  }
+  return tokens;
+}
+
+const TOKEN_CLASSES: Record<TokenKind, string | undefined> = {
+  comment: "text-gray-500 italic",
+  string: "text-amber-300",
+  number: "text-purple-300",
+  keyword: "text-teal-400",
+  plain: undefined,
+};
+
+interface CodeBlockProps {
+  code: string;
+  language?: string;
+}
+
+/** Fenced code block with a language label and syntax highlighting */
+export function CodeBlock({ code, language }: CodeBlockProps) {
+  const [copyLabel, setCopyLabel] = useState("Copy");
+  const lang = language?.toLowerCase() ?? "";
+  // Plain text and unlabeled blocks are shown as-is
+  const tokens: Token[] =
+    lang && lang !== "text" ? highlightCode(code, lang) : [{ kind: "plain", text: code }];
+
+  const handleCopy = async () => {
+    try {
+      await navigator.clipboard.writeText(code);
+      setCopyLabel("Copied!");
+    } catch (err) {
+      console.error("Failed to copy:", err);
+      setCopyLabel("Copy failed");
+    }
+    setTimeout(() => setCopyLabel("Copy"), 2000);
+  };
+
+  return (
+    <div className="not-prose my-2 rounded-lg bg-background text-sm overflow-hidden">
+      <div className="flex items-center justify-between px-3 py-1 text-xs text-muted border-b border-gray-700">
+        <span>{lang}</span>
+        <button onClick={handleCopy} className="hover:text-foreground transition-colors">
+          {copyLabel}
+        </button>
+      </div>
+      <pre className="p-3 overflow-x-auto text-gray-100">
+        <code>
+          {tokens.map((token, i) =>
+            TOKEN_CLASSES[token.kind] ? (
+              <span key={i} className={TOKEN_CLASSES[token.kind]}>
+                {token.text}
+              </span>
+            ) : (
+              token.text
+            )
+          )}
+        </code>
+      </pre>
+    </div>
+  );
+}
@@ -2,6 +2,7 @@ import ReactMarkdown from "react-markdown";
 import remarkGfm from "remark-gfm";
 import remarkMath from "remark-math";
 import rehypeKatex from "rehype-katex";
+import { CodeBlock } from "./CodeBlock";
 
 interface MarkdownTextProps {
   text: string;
@@ -42,23 +43,28 @@ export function MarkdownText({ text, onDocumentClick }: MarkdownTextProps) {
             </a>
           );
         },
-        code(props) {
-          const { children, className } = props;
-          const isInline = !className;
-          return isInline ? (
+        code({ children }) {
+          // Fenced blocks are rendered by `pre` below, so this is inline code
+          return (
             <code className="bg-elevated text-gray-100 px-1 py-0.5 rounded text-sm">
               {children}
             </code>
-          ) : (
-            <code className={className}>{children}</code>
           );
         },
-        pre(props) {
-          return (
-            <pre className="bg-background text-gray-100 p-3 rounded-lg overflow-x-auto text-sm">
-              {props.children}
-            </pre>
-          );
+        pre({ node, children }) {
+          const codeNode = node?.children[0];
+          if (codeNode?.type !== "element" || codeNode.tagName !== "code") {
+            return <pre>{children}</pre>;
+          }
+          const classes = codeNode.properties.className;
+          const languageClass = Array.isArray(classes)
+            ? classes.map(String).find((c) => c.startsWith("language-"))
+            : undefined;
+          const code = codeNode.children
+            .map((child) => (child.type === "text" ? child.value : ""))
+            .join("")
+            .replace(/\n$/, "");
+          return <CodeBlock code={code} language={languageClass?.slice("language-".length)} />;
         },
       }}
     >
@@ -8,6 +8,11 @@ interface ConversationsPanelProps {
   onSelectConversation: (id: string) => void;
   onDeleteConversation: (id: string) => void;
   onRenameConversation: (id: string, name: string) => void;
+  onPinConversation: (id: string, pinned: boolean) => void;
+  onArchiveConversation: (id: string, archived: boolean) => void;
+  /** Whether archived conversations are listed */
+  showArchived: boolean;
+  onToggleShowArchived: () => void;
 }
 
 function formatDate(timestamp: number | bigint): string {
@@ -31,6 +36,10 @@ export function ConversationsPanel({
   onSelectConversation,
   onDeleteConversation,
   onRenameConversation,
+  onPinConversation,
+  onArchiveConversation,
+  showArchived,
+  onToggleShowArchived,
 }: ConversationsPanelProps) {
   const [editingId, setEditingId] = useState<string | null>(null);
   const [editName, setEditName] = useState("");
@@ -57,6 +66,15 @@ export function ConversationsPanel({
         >
           + New Chat
         </button>
+        <label className="mt-3 flex items-center gap-2 text-xs text-muted cursor-pointer">
+          <input
+            type="checkbox"
+            checked={showArchived}
+            onChange={onToggleShowArchived}
+            className="accent-teal-600"
+          />
+          Show archived
+        </label>
       </div>
 
       {/* Conversations list */}
@@ -100,9 +118,61 @@ export function ConversationsPanel({
                     >
                       <div className="flex items-center justify-between">
                         <span className="truncate font-medium text-sm">
+                          {conv.isPinned && (
+                            <span className="mr-1 text-teal-400" title="Pinned">
+                              •
+                            </span>
+                          )}
                           {displayName}
+                          {conv.isArchived && (
+                            <span className="ml-1 text-xs text-muted">(archived)</span>
+                          )}
                         </span>
                         <div className="opacity-0 group-hover:opacity-100 flex gap-1 flex-shrink-0">
+                          <button
+                            onClick={(e) => {
+                              e.stopPropagation();
+                              onPinConversation(conv.id, !conv.isPinned);
+                            }}
+                            className="p-1 text-muted hover:text-foreground"
+                            title={conv.isPinned ? "Unpin" : "Pin"}
+                          >
+                            <svg
+                              className="w-4 h-4"
+                              fill={conv.isPinned ? "currentColor" : "none"}
+                              stroke="currentColor"
+                              viewBox="0 0 24 24"
+                            >
+                              <path
+                                strokeLinecap="round"
+                                strokeLinejoin="round"
+                                strokeWidth={2}
+                                d="M5 5a2 2 0 012-2h10a2 2 0 012 2v16l-7-3.5L5 21V5z"
+                              />
+                            </svg>
+                          </button>
+                          <button
+                            onClick={(e) => {
+                              e.stopPropagation();
+                              onArchiveConversation(conv.id, !conv.isArchived);
+                            }}
+                            className="p-1 text-muted hover:text-foreground"
+                            title={conv.isArchived ? "Unarchive" : "Archive"}
+                          >
+                            <svg
+                              className="w-4 h-4"
+                              fill="none"
+                              stroke="currentColor"
+                              viewBox="0 0 24 24"
+                            >
+                              <path
+                                strokeLinecap="round"
+                                strokeLinejoin="round"
+                                strokeWidth={2}
+                                d="M5 8h14M5 8a2 2 0 110-4h14a2 2 0 110 4M5 8v10a2 2 0 002 2h10a2 2 0 002-2V8m-9 4h4"
+                              />
+                            </svg>
+                          </button>
                           <button
                             onClick={(e) => {
                               e.stopPropagation();
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DisplayInputContent } from "./DisplayInputContent";
+
+/**
+ * A file read from disk and turned into message content
+ */
+export type AttachedFile = { 
+/**
+ * File name, for showing the attachment before it is sent
+ */
+name: string, 
+/**
+ * Content blocks to include with the next message
+ */
+content: Array<DisplayInputContent>, };
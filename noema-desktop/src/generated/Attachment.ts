@@ -3,4 +3,8 @@
 /**
  * Attachment from frontend for message sending
  */
-export type Attachment = { data: string, mimeType: string, };
+export type Attachment = { data: string, mimeType: string, 
+/**
+ * Original filename, e.g. "notes.md"
+ */
+name?: string, };
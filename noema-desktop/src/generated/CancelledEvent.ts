@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DisplayMessage } from "./DisplayMessage";
+
+/**
+ * Payload for cancelled event
+ */
+export type CancelledEvent = { conversationId: string, messages: Array<DisplayMessage>, };
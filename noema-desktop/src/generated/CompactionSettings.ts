@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * History summarization settings
+ */
+export type CompactionSettings = { 
+/**
+ * Summarize once a conversation is estimated above this many tokens
+ * (None = only on /compact)
+ */
+thresholdTokens: number | null, 
+/**
+ * Model (provider/model) that writes summaries (None = the
+ * conversation's model)
+ */
+summaryModel: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for context_compacted event (older messages summarized; 0 if
+ * there was nothing old enough to summarize)
+ */
+export type ContextCompactedEvent = { conversationId: string, summarizedMessages: number, };
@@ -4,4 +4,20 @@ export type ConversationInfo = { id: string, name: string | null, messageCount:
 /**
  * Whether this conversation is marked as private (warns before using cloud models)
  */
-isPrivate: boolean, createdAt: bigint, };
+isPrivate: boolean, createdAt: bigint, 
+/**
+ * Model last used in this conversation (provider/model format)
+ */
+lastModel: string | null, 
+/**
+ * Tags, sorted alphabetically
+ */
+tags: Array<string>, 
+/**
+ * Whether this conversation is pinned to the top of the list
+ */
+isPinned: boolean, 
+/**
+ * Whether this conversation is archived (hidden unless requested)
+ */
+isArchived: boolean, };
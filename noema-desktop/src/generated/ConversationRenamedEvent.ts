@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for conversation_renamed event (an untitled conversation was named
+ * after its first exchange)
+ */
+export type ConversationRenamedEvent = { conversationId: string, name: string, };
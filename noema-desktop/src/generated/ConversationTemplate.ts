@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GenerationParams } from "./GenerationParams";
+
+/**
+ * Preset system prompt, model and sampling settings for new conversations
+ */
+export type ConversationTemplate = { name: string, description: string | null, systemMessage: string | null, 
+/**
+ * Model to use (provider/model); null keeps the current model
+ */
+modelId: string | null, generationParams: GenerationParams, };
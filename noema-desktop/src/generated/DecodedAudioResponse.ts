@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Mono samples of a decoded audio content block
+ */
+export type DecodedAudioResponse = { samples: Array<number>, sampleRate: number, };
@@ -14,4 +14,8 @@ spanId: string | undefined,
 /**
  * Available alternates for this message's turn (only populated for assistant messages with alternatives)
  */
-alternates: Array<AlternateInfo> | null, };
+alternates: Array<AlternateInfo> | null, 
+/**
+ * When the message was stored (epoch ms); unset while it is streaming
+ */
+createdAt: number | undefined, };
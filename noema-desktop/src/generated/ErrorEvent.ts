@@ -1,6 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrorKind } from "./ErrorKind";
 
 /**
  * Payload for error event
  */
-export type ErrorEvent = { conversationId: string, error: string, };
+export type ErrorEvent = { conversationId: string, error: string, kind: ErrorKind, 
+/**
+ * Seconds to wait before retrying, when rate limited and the provider said
+ */
+retryAfterSecs: number | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Kind of failure reported by an error event
+ */
+export type ErrorKind = "network" | "rateLimited" | "auth" | "modelNotFound" | "contentFiltered" | "provider" | "storage" | "other";
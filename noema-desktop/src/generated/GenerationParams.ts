@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Sampling settings for a conversation; unset fields use the provider's defaults
+ */
+export type GenerationParams = { temperature: number | null, topP: number | null, 
+/**
+ * Maximum number of tokens to generate
+ */
+maxTokens: number | null, 
+/**
+ * Sequences that end generation when produced
+ */
+stop: Array<string> | null, 
+/**
+ * Sampling seed for reproducible runs (best-effort; ignored by
+ * providers without seed support)
+ */
+seed: number | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for history_trimmed event (oldest messages left out of the last
+ * request to fit the model's context window)
+ */
+export type HistoryTrimmedEvent = { conversationId: string, droppedMessages: number, };
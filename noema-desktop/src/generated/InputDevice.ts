@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A microphone voice input can capture from
+ */
+export type InputDevice = { name: string, 
+/**
+ * Whether this is the system's default input
+ */
+isDefault: boolean, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { McpPromptArgument } from "./McpPromptArgument";
+
+export type McpPromptInfo = { name: string, description: string | null, arguments: Array<McpPromptArgument>, serverId: string, };
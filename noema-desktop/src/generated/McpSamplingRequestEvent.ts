@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for mcp_sampling_request event (an MCP server asks to run a
+ * completion on the user's model; answer with respond_mcp_sampling)
+ */
+export type McpSamplingRequestEvent = { requestId: string, 
+/**
+ * Name of the server asking
+ */
+server: string, 
+/**
+ * Model that will answer (provider's model ID)
+ */
+modelId: string, systemPrompt: string | null, 
+/**
+ * The conversation to complete, one "Role: text" paragraph per message
+ */
+messages: string, 
+/**
+ * Most tokens the completion may produce
+ */
+maxTokens: number | null, };
@@ -1,6 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type McpServerInfo = { id: string, name: string, url: string, authType: string, isConnected: boolean, needsOauthLogin: boolean, toolCount: number, 
+export type McpServerInfo = { id: string, name: string, 
+/**
+ * HTTP endpoint, or the command line for stdio servers
+ */
+url: string, authType: string, isConnected: boolean, needsOauthLogin: boolean, toolCount: number, 
 /**
  * Connection status: "disconnected", "connected", "retrying:N", "stopped:error"
  */
@@ -12,4 +16,8 @@ autoConnect: boolean,
 /**
  * Whether to auto-retry with exponential backoff
  */
-autoRetry: boolean, };
+autoRetry: boolean, 
+/**
+ * Whether the server may ask for completions from the user's model
+ */
+allowSampling: boolean, };
@@ -1,3 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type McpToolInfo = { name: string, description: string | null, serverId: string, };
+export type McpToolInfo = { name: string, description: string | null, serverId: string, 
+/**
+ * Hidden from the model by the server's tool filter
+ */
+filteredOut: boolean, };
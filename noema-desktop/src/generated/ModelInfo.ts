@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type ModelInfo = { id: string, displayName: string, provider: string, capabilities: Array<string>, contextWindow: number | null, };
+export type ModelInfo = { id: string, displayName: string, provider: string, capabilities: Array<string>, contextWindow: number | null, maxOutputTokens: number | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for model_pull_progress event (an Ollama model being downloaded
+ * because a request needed it and the server didn't have it)
+ */
+export type ModelPullProgressEvent = { model: string, status: string, 
+/**
+ * Percentage of the current layer downloaded, while downloading
+ */
+percent: number | null, };
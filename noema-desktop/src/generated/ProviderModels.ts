@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelInfo } from "./ModelInfo";
+
+/**
+ * Models offered by one provider, or why they couldn't be listed (e.g. no
+ * API key)
+ */
+export type ProviderModels = { provider: string, models: Array<ModelInfo>, error: string | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FinishReason } from "./FinishReason";
+
+/**
+ * Payload for response_finished event (sent after message_complete when
+ * the provider said why the response ended; "length" means it was cut off)
+ */
+export type ResponseFinishedEvent = { conversationId: string, finishReason: FinishReason, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of storing an asset
+ */
+export type StoredAssetResponse = { assetId: string, 
+/**
+ * SHA-256 hash of the content; identical bytes always yield the same hash
+ */
+blobHash: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for text_delta event (newly streamed assistant text, to append to the in-progress message)
+ */
+export type TextDeltaEvent = { conversationId: string, text: string, };
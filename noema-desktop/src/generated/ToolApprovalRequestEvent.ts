@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for tool_approval_request event (a tool call waiting for the
+ * user's approval; answer with respond_tool_approval)
+ */
+export type ToolApprovalRequestEvent = { conversationId: string, toolCallId: string, name: string, 
+/**
+ * The call's arguments as JSON text
+ */
+arguments: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for tool_call_delta event (the next fragment of a tool call's
+ * arguments while the model is still writing them)
+ */
+export type ToolCallDeltaEvent = { conversationId: string, toolCallId: string, name: string, argsFragment: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for tool_progress event (progress reported by an MCP server for a running tool call)
+ */
+export type ToolProgressEvent = { conversationId: string, toolCallId: string, message: string, };
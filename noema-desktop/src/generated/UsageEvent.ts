@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for usage event (token counts for the last completed request)
+ */
+export type UsageEvent = { conversationId: string, promptTokens: number, completionTokens: number, totalTokens: number, 
+/**
+ * Prompt tokens served from the provider's prompt cache
+ */
+cachedTokens: number, };
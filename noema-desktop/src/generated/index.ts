@@ -4,6 +4,7 @@
 export type { AddMcpServerRequest } from "./AddMcpServerRequest";
 export type { AlternateInfo } from "./AlternateInfo";
 export type { Attachment } from "./Attachment";
+export type { AttachedFile } from "./AttachedFile";
 export type { ConversationInfo } from "./ConversationInfo";
 export type { DisplayContent } from "./DisplayContent";
 export type { DisplayMessage } from "./DisplayMessage";
@@ -12,18 +13,42 @@ export type { DocumentContentResponse } from "./DocumentContentResponse";
 export type { DocumentInfoResponse } from "./DocumentInfoResponse";
 export type { DocumentTabResponse } from "./DocumentTabResponse";
 export type { InputContentBlock } from "./InputContentBlock";
+export type { McpPromptArgument } from "./McpPromptArgument";
+export type { McpPromptInfo } from "./McpPromptInfo";
+export type { McpResourceInfo } from "./McpResourceInfo";
+export type { McpSamplingRequestEvent } from "./McpSamplingRequestEvent";
 export type { McpServerInfo } from "./McpServerInfo";
 export type { McpToolInfo } from "./McpToolInfo";
 export type { ModelInfo } from "./ModelInfo";
+export type { ProviderModels } from "./ProviderModels";
 export type { ProviderInfoResponse as ProviderInfo } from "./ProviderInfoResponse";
+export type { CompactionSettings } from "./CompactionSettings";
 export type { ReferencedDocument } from "./ReferencedDocument";
+export type { StoredAssetResponse } from "./StoredAssetResponse";
 export type { ToolConfig } from "./ToolConfig";
+export type { GenerationParams } from "./GenerationParams";
+export type { ConversationTemplate } from "./ConversationTemplate";
 export type { ThreadInfoResponse } from "./ThreadInfoResponse";
 
 // Event payload types
 export type { UserMessageEvent } from "./UserMessageEvent";
 export type { StreamingMessageEvent } from "./StreamingMessageEvent";
+export type { TextDeltaEvent } from "./TextDeltaEvent";
 export type { MessageCompleteEvent } from "./MessageCompleteEvent";
 export type { ErrorEvent } from "./ErrorEvent";
+export type { ErrorKind } from "./ErrorKind";
+export type { ToolProgressEvent } from "./ToolProgressEvent";
+export type { ToolCallDeltaEvent } from "./ToolCallDeltaEvent";
+export type { ModelPullProgressEvent } from "./ModelPullProgressEvent";
+export type { DecodedAudioResponse } from "./DecodedAudioResponse";
+export type { InputDevice } from "./InputDevice";
+export type { ToolApprovalRequestEvent } from "./ToolApprovalRequestEvent";
+export type { UsageEvent } from "./UsageEvent";
+export type { HistoryTrimmedEvent } from "./HistoryTrimmedEvent";
+export type { FinishReason } from "./FinishReason";
+export type { ResponseFinishedEvent } from "./ResponseFinishedEvent";
+export type { ContextCompactedEvent } from "./ContextCompactedEvent";
+export type { ConversationRenamedEvent } from "./ConversationRenamedEvent";
+export type { CancelledEvent } from "./CancelledEvent";
 export type { ModelChangedEvent } from "./ModelChangedEvent";
 export type { HistoryClearedEvent } from "./HistoryClearedEvent";
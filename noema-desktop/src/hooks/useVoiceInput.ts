@@ -12,6 +12,7 @@ interface UseVoiceInputOptions {
 export function useVoiceInput(options: UseVoiceInputOptions = {}) {
   const [status, setStatus] = useState<VoiceStatus>("disabled");
   const [bufferedCount, setBufferedCount] = useState(0);
+  const [partialText, setPartialText] = useState("");
   const [isAvailable, setIsAvailable] = useState(false);
   const audioContextRef = useRef<AudioContext | null>(null);
   const workletNodeRef = useRef<AudioWorkletNode | null>(null);
@@ -52,12 +53,18 @@ export function useVoiceInput(options: UseVoiceInputOptions = {}) {
       }
       lastTranscriptionRef.current = text;
       voiceLog.info("Transcription received", { text });
+      setPartialText("");
       setStatus("disabled");
       onTranscriptionRef.current?.(text);
     }).then((unlisten) => unlisteners.push(unlisten));
 
+    tauri.onVoicePartial((text) => {
+      setPartialText(text);
+    }).then((unlisten) => unlisteners.push(unlisten));
+
     tauri.onVoiceError((error) => {
       voiceLog.error("Voice error", { error });
+      setPartialText("");
       setStatus("disabled");
       onErrorRef.current?.(error);
     }).then((unlisten) => unlisteners.push(unlisten));
@@ -72,6 +79,7 @@ export function useVoiceInput(options: UseVoiceInputOptions = {}) {
       } else if (newStatus === "disabled") {
         setStatus("disabled");
         setBufferedCount(0);
+        setPartialText("");
       } else if (newStatus.startsWith("buffering:")) {
         const count = parseInt(newStatus.split(":")[1], 10) || 0;
         setStatus("buffering");
@@ -199,6 +207,7 @@ export function useVoiceInput(options: UseVoiceInputOptions = {}) {
   return {
     status,
     bufferedCount,
+    partialText,
     isAvailable,
     toggle,
     startRecording,
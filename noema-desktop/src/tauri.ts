@@ -2,27 +2,49 @@ import { invoke } from "@tauri-apps/api/core";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
 import type {
   AddMcpServerRequest,
+  McpPromptInfo,
+  McpResourceInfo,
   McpServerInfo,
   McpToolInfo,
   ModelInfo,
+  ProviderModels,
+  DecodedAudioResponse,
+  InputDevice,
   ConversationInfo,
+  ConversationTemplate,
   DocumentInfoResponse,
   DocumentContentResponse,
   DocumentTabResponse,
   DisplayMessage,
   InputContentBlock,
+  StoredAssetResponse,
+  AttachedFile,
   ToolConfig,
+  GenerationParams,
+  CompactionSettings,
   UserMessageEvent,
   StreamingMessageEvent,
+  TextDeltaEvent,
   MessageCompleteEvent,
   ErrorEvent,
+  ToolProgressEvent,
+  ToolCallDeltaEvent,
+  ModelPullProgressEvent,
+  ToolApprovalRequestEvent,
+  McpSamplingRequestEvent,
+  UsageEvent,
+  HistoryTrimmedEvent,
+  ResponseFinishedEvent,
+  ContextCompactedEvent,
+  ConversationRenamedEvent,
   ModelChangedEvent,
   HistoryClearedEvent,
 } from "./generated";
 import type { TruncatedEvent } from "./generated/TruncatedEvent";
+import type { CancelledEvent } from "./generated/CancelledEvent";
 
 // Re-export event payload types for consumers
-export type { UserMessageEvent, StreamingMessageEvent, MessageCompleteEvent, ErrorEvent, ModelChangedEvent, HistoryClearedEvent } from "./generated";
+export type { UserMessageEvent, StreamingMessageEvent, TextDeltaEvent, MessageCompleteEvent, ErrorEvent, ToolProgressEvent, ToolCallDeltaEvent, ModelPullProgressEvent, ToolApprovalRequestEvent, McpSamplingRequestEvent, UsageEvent, HistoryTrimmedEvent, ResponseFinishedEvent, ContextCompactedEvent, ConversationRenamedEvent, ModelChangedEvent, HistoryClearedEvent } from "./generated";
 
 // Tauri commands
 export async function initApp(): Promise<string> {
@@ -41,11 +63,75 @@ export async function sendMessage(
   return invoke<void>("send_message", { conversationId, content, toolConfig });
 }
 
+export async function applyMcpPrompt(
+  conversationId: string,
+  serverId: string,
+  name: string,
+  args: Record<string, string>,
+  toolConfig?: ToolConfig
+): Promise<void> {
+  return invoke<void>("apply_mcp_prompt", { conversationId, serverId, name, arguments: args, toolConfig });
+}
+
 
 export async function clearHistory(): Promise<void> {
   return invoke<void>("clear_history");
 }
 
+export async function cancelRequest(
+  conversationId: string,
+  keepPartial: boolean
+): Promise<void> {
+  return invoke<void>("cancel_request", { conversationId, keepPartial });
+}
+
+export async function setRequireToolApproval(
+  conversationId: string,
+  required: boolean
+): Promise<void> {
+  return invoke<void>("set_require_tool_approval", { conversationId, required });
+}
+
+export async function respondToolApproval(
+  conversationId: string,
+  toolCallId: string,
+  approved: boolean,
+  message?: string
+): Promise<void> {
+  return invoke<void>("respond_tool_approval", {
+    conversationId,
+    toolCallId,
+    approved,
+    message: message ?? null,
+  });
+}
+
+export async function respondMcpSampling(requestId: string, approved: boolean): Promise<void> {
+  return invoke<void>("respond_mcp_sampling", { requestId, approved });
+}
+
+export async function getSystemPrompt(conversationId: string): Promise<string | null> {
+  return invoke<string | null>("get_system_prompt", { conversationId });
+}
+
+export async function setSystemPrompt(
+  conversationId: string,
+  prompt: string | null
+): Promise<void> {
+  return invoke<void>("set_system_prompt", { conversationId, prompt });
+}
+
+export async function setGenerationParams(
+  conversationId: string,
+  params: GenerationParams
+): Promise<void> {
+  return invoke<void>("set_generation_params", { conversationId, params });
+}
+
+export async function compactConversation(conversationId: string): Promise<void> {
+  return invoke<void>("compact_conversation", { conversationId });
+}
+
 export async function setModel(
   conversationId: string,
   modelId: string,
@@ -58,8 +144,15 @@ export async function listModels(): Promise<ModelInfo[]> {
   return invoke<ModelInfo[]>("list_models");
 }
 
-export async function listConversations(): Promise<ConversationInfo[]> {
-  return invoke<ConversationInfo[]>("list_conversations");
+/** Every model of one provider (or all providers), noting those that failed to list */
+export async function listProviderModels(provider?: string): Promise<ProviderModels[]> {
+  return invoke<ProviderModels[]>("list_provider_models", { provider: provider ?? null });
+}
+
+export async function listConversations(
+  includeArchived = false
+): Promise<ConversationInfo[]> {
+  return invoke<ConversationInfo[]>("list_conversations", { includeArchived });
 }
 
 export async function loadConversation(
@@ -68,8 +161,22 @@ export async function loadConversation(
   return invoke<DisplayMessage[]>("load_conversation", { conversationId });
 }
 
-export async function newConversation(name?: string): Promise<string> {
-  return invoke<string>("new_conversation", { name });
+/** Create a conversation, seeded from the named template if given */
+export async function newConversation(name?: string, template?: string): Promise<string> {
+  return invoke<string>("new_conversation", { name, template });
+}
+
+export async function listTemplates(): Promise<ConversationTemplate[]> {
+  return invoke<ConversationTemplate[]>("list_templates");
+}
+
+export async function saveTemplate(template: ConversationTemplate): Promise<void> {
+  return invoke<void>("save_template", { template });
+}
+
+/** Returns whether a user-defined template was removed */
+export async function deleteTemplate(name: string): Promise<boolean> {
+  return invoke<boolean>("delete_template", { name });
 }
 
 export async function deleteConversation(
@@ -85,6 +192,26 @@ export async function renameConversation(
   return invoke<void>("rename_conversation", { conversationId, name });
 }
 
+export async function addConversationTag(
+  conversationId: string,
+  tag: string
+): Promise<void> {
+  return invoke<void>("add_conversation_tag", { conversationId, tag });
+}
+
+export async function removeConversationTag(
+  conversationId: string,
+  tag: string
+): Promise<void> {
+  return invoke<void>("remove_conversation_tag", { conversationId, tag });
+}
+
+export async function listConversationsByTag(
+  tag: string
+): Promise<ConversationInfo[]> {
+  return invoke<ConversationInfo[]>("list_conversations_by_tag", { tag });
+}
+
 export async function getConversationPrivate(
   conversationId: string
 ): Promise<boolean> {
@@ -98,6 +225,20 @@ export async function setConversationPrivate(
   return invoke<void>("set_conversation_private", { conversationId, isPrivate });
 }
 
+export async function setConversationPinned(
+  conversationId: string,
+  pinned: boolean
+): Promise<void> {
+  return invoke<void>("set_conversation_pinned", { conversationId, pinned });
+}
+
+export async function setConversationArchived(
+  conversationId: string,
+  archived: boolean
+): Promise<void> {
+  return invoke<void>("set_conversation_archived", { conversationId, archived });
+}
+
 export async function getModelName(): Promise<string> {
   return invoke<string>("get_model_name");
 }
@@ -111,6 +252,16 @@ export async function toggleFavoriteModel(modelId: string): Promise<string[]> {
   return invoke<string[]>("toggle_favorite_model", { modelId });
 }
 
+// Input history (persisted across restarts, oldest first)
+export async function getInputHistory(): Promise<string[]> {
+  return invoke<string[]>("get_input_history");
+}
+
+// Not recorded when the conversation is private or ephemeral
+export async function pushInputHistory(entry: string, conversationId?: string): Promise<void> {
+  return invoke<void>("push_input_history", { entry, conversationId: conversationId || null });
+}
+
 // Event listeners
 export function onUserMessage(
   callback: (payload: UserMessageEvent) => void
@@ -128,6 +279,16 @@ export function onStreamingMessage(
   );
 }
 
+/**
+ * Newly streamed assistant text. Append `text` to the in-progress message;
+ * `streaming_message` and `message_complete` still carry the final state.
+ */
+export function onTextDelta(
+  callback: (payload: TextDeltaEvent) => void
+): Promise<UnlistenFn> {
+  return listen<TextDeltaEvent>("text_delta", (event) => callback(event.payload));
+}
+
 export function onMessageComplete(
   callback: (payload: MessageCompleteEvent) => void
 ): Promise<UnlistenFn> {
@@ -140,6 +301,68 @@ export function onError(callback: (payload: ErrorEvent) => void): Promise<Unlist
   return listen<ErrorEvent>("error", (event) => callback(event.payload));
 }
 
+export function onToolCallDelta(
+  callback: (payload: ToolCallDeltaEvent) => void
+): Promise<UnlistenFn> {
+  return listen<ToolCallDeltaEvent>("tool_call_delta", (event) => callback(event.payload));
+}
+
+export function onModelPullProgress(
+  callback: (payload: ModelPullProgressEvent) => void
+): Promise<UnlistenFn> {
+  return listen<ModelPullProgressEvent>("model_pull_progress", (event) => callback(event.payload));
+}
+
+export function onToolProgress(
+  callback: (payload: ToolProgressEvent) => void
+): Promise<UnlistenFn> {
+  return listen<ToolProgressEvent>("tool_progress", (event) => callback(event.payload));
+}
+
+export function onToolApprovalRequest(
+  callback: (payload: ToolApprovalRequestEvent) => void
+): Promise<UnlistenFn> {
+  return listen<ToolApprovalRequestEvent>("tool_approval_request", (event) =>
+    callback(event.payload)
+  );
+}
+
+export function onMcpSamplingRequest(
+  callback: (payload: McpSamplingRequestEvent) => void
+): Promise<UnlistenFn> {
+  return listen<McpSamplingRequestEvent>("mcp_sampling_request", (event) =>
+    callback(event.payload)
+  );
+}
+
+export function onUsage(callback: (payload: UsageEvent) => void): Promise<UnlistenFn> {
+  return listen<UsageEvent>("usage", (event) => callback(event.payload));
+}
+
+export function onHistoryTrimmed(
+  callback: (payload: HistoryTrimmedEvent) => void
+): Promise<UnlistenFn> {
+  return listen<HistoryTrimmedEvent>("history_trimmed", (event) => callback(event.payload));
+}
+
+export function onResponseFinished(
+  callback: (payload: ResponseFinishedEvent) => void
+): Promise<UnlistenFn> {
+  return listen<ResponseFinishedEvent>("response_finished", (event) => callback(event.payload));
+}
+
+export function onContextCompacted(
+  callback: (payload: ContextCompactedEvent) => void
+): Promise<UnlistenFn> {
+  return listen<ContextCompactedEvent>("context_compacted", (event) => callback(event.payload));
+}
+
+export function onConversationRenamed(
+  callback: (payload: ConversationRenamedEvent) => void
+): Promise<UnlistenFn> {
+  return listen<ConversationRenamedEvent>("conversation_renamed", (event) => callback(event.payload));
+}
+
 export function onModelChanged(
   callback: (payload: ModelChangedEvent) => void
 ): Promise<UnlistenFn> {
@@ -154,6 +377,10 @@ export function onTruncated(callback: (payload: TruncatedEvent) => void): Promis
   return listen<TruncatedEvent>("truncated", (event) => callback(event.payload));
 }
 
+export function onCancelled(callback: (payload: CancelledEvent) => void): Promise<UnlistenFn> {
+  return listen<CancelledEvent>("cancelled", (event) => callback(event.payload));
+}
+
 // Parallel execution events
 export interface ParallelStreamingPayload {
   modelId: string;
@@ -216,18 +443,59 @@ export function onParallelModelError(
 }
 
 // Voice commands
-export async function isVoiceAvailable(): Promise<boolean> {
-  return invoke<boolean>("is_voice_available");
+/**
+ * Check whether a Whisper model file (e.g. "ggml-base.bin") has been downloaded.
+ * Defaults to the model configured in settings.
+ */
+export async function isVoiceAvailable(model?: string): Promise<boolean> {
+  return invoke<boolean>("is_voice_available", { model });
+}
+
+/**
+ * Download a Whisper model (e.g. "ggml-base.en.bin"), defaulting to the
+ * configured one. Progress arrives via onDownloadProgress.
+ */
+export async function downloadVoiceModel(model?: string): Promise<void> {
+  return invoke("download_voice_model", { model });
+}
+
+// Percentage downloaded, framed by "starting" and "complete"
+export type DownloadProgress = number | "starting" | "complete";
+
+export function onDownloadProgress(
+  callback: (progress: DownloadProgress) => void
+): Promise<UnlistenFn> {
+  return listen<DownloadProgress>("download_progress", (event) => callback(event.payload));
 }
 
 export async function toggleVoice(): Promise<boolean> {
   return invoke<boolean>("toggle_voice");
 }
 
+/** Toggle reading responses in the voice conversation aloud ("/speak") */
+export async function toggleSpeakResponses(): Promise<boolean> {
+  return invoke<boolean>("toggle_speak_responses");
+}
+
+/** Microphones voice input can capture from ("/voice device") */
+export async function listVoiceInputDevices(): Promise<InputDevice[]> {
+  return invoke<InputDevice[]>("list_voice_input_devices");
+}
+
+/** Choose the microphone for voice input; omit for the system default */
+export async function setVoiceInputDevice(name?: string): Promise<void> {
+  return invoke("set_voice_input_device", { name });
+}
+
 export async function getVoiceStatus(): Promise<string> {
   return invoke<string>("get_voice_status");
 }
 
+/** Decode audio the webview can't, such as raw PCM ("audio/L16;rate=24000") */
+export async function decodeAudio(data: string, mimeType: string): Promise<DecodedAudioResponse> {
+  return invoke<DecodedAudioResponse>("decode_audio", { data, mimeType });
+}
+
 // Voice events - status can also be "buffering:N" where N is count of queued messages
 export type VoiceStatus = string;
 
@@ -243,6 +511,14 @@ export function onVoiceTranscription(
   return listen<string>("voice_transcription", (event) => callback(event.payload));
 }
 
+// Provisional text while the user is still speaking; each partial replaces the
+// previous one until the final text arrives via voice_transcription
+export function onVoicePartial(
+  callback: (text: string) => void
+): Promise<UnlistenFn> {
+  return listen<string>("voice_partial", (event) => callback(event.payload));
+}
+
 export function onVoiceError(
   callback: (error: string) => void
 ): Promise<UnlistenFn> {
@@ -274,6 +550,18 @@ export async function getMcpServerTools(serverId: string): Promise<McpToolInfo[]
   return invoke<McpToolInfo[]>("get_mcp_server_tools", { serverId });
 }
 
+export async function getMcpServerResources(serverId: string): Promise<McpResourceInfo[]> {
+  return invoke<McpResourceInfo[]>("get_mcp_server_resources", { serverId });
+}
+
+export async function getMcpServerPrompts(serverId: string): Promise<McpPromptInfo[]> {
+  return invoke<McpPromptInfo[]>("get_mcp_server_prompts", { serverId });
+}
+
+export async function readMcpResource(serverId: string, uri: string): Promise<AttachedFile> {
+  return invoke<AttachedFile>("read_mcp_resource", { serverId, uri });
+}
+
 export async function testMcpServer(serverId: string): Promise<number> {
   return invoke<number>("test_mcp_server", { serverId });
 }
@@ -321,6 +609,25 @@ export async function saveFile(
   return invoke<boolean>("save_file", { data, filename, mimeType });
 }
 
+/**
+ * Store base64 data as an asset. Identical bytes are stored only once;
+ * the returned asset ID can be sent as an `assetRef` content block.
+ */
+/**
+ * Read an image or PDF from disk and process it into content blocks
+ * to include with the next message (/attach).
+ */
+export async function attachFile(path: string): Promise<AttachedFile> {
+  return invoke<AttachedFile>("attach_file", { path });
+}
+
+export async function storeAsset(
+  data: string,
+  mimeType: string
+): Promise<StoredAssetResponse> {
+  return invoke<StoredAssetResponse>("store_asset", { data, mimeType });
+}
+
 // Logging
 export async function logDebug(
   level: string,
@@ -350,8 +657,9 @@ export async function getApiKeyStatus(): Promise<Record<string, boolean>> {
   return invoke<Record<string, boolean>>("get_api_key_status");
 }
 
-export async function setApiKey(provider: string, apiKey: string): Promise<void> {
-  return invoke<void>("set_api_key", { provider, apiKey });
+/** Returns a warning when the key couldn't go into the system keychain */
+export async function setApiKey(provider: string, apiKey: string): Promise<string | null> {
+  return invoke<string | null>("set_api_key", { provider, apiKey });
 }
 
 export async function removeApiKey(provider: string): Promise<void> {
@@ -362,6 +670,16 @@ export async function getProviderInfo(): Promise<ProviderInfo[]> {
   return invoke<ProviderInfo[]>("get_provider_info");
 }
 
+// History summarization settings
+export async function getCompactionSettings(): Promise<CompactionSettings> {
+  return invoke<CompactionSettings>("get_compaction_settings");
+}
+
+/** Also applies to conversations that are already open */
+export async function setCompactionSettings(compaction: CompactionSettings): Promise<void> {
+  return invoke<void>("set_compaction_settings", { compaction });
+}
+
 // Document commands (episteme-compatible)
 export async function listDocuments(): Promise<DocumentInfoResponse[]> {
   return invoke<DocumentInfoResponse[]>("list_documents");
@@ -512,6 +830,39 @@ export async function regenerateResponse(
   return invoke<void>("regenerate_response", { conversationId, turnId, toolConfig });
 }
 
+/**
+ * Regenerate the response to the last user message (/retry)
+ * Fails if the conversation has no response to regenerate.
+ */
+export async function regenerateLastResponse(
+  conversationId: string,
+  toolConfig?: ToolConfig
+): Promise<void> {
+  return invoke<void>("regenerate_last_response", { conversationId, toolConfig });
+}
+
+/**
+ * Continue the last response where the output token limit cut it off.
+ * The continuation streams in as text deltas and is appended to the last
+ * assistant message. Fails if the conversation does not end in a response.
+ */
+export async function continueResponse(conversationId: string): Promise<void> {
+  return invoke<void>("continue_response", { conversationId });
+}
+
+/**
+ * Replace the text of the last user message and regenerate the response.
+ * Everything after that message is dropped. Fails while a response is
+ * being generated.
+ */
+export async function editAndResend(
+  conversationId: string,
+  text: string,
+  toolConfig?: ToolConfig
+): Promise<void> {
+  return invoke<void>("edit_and_resend", { conversationId, text, toolConfig });
+}
+
 /**
  * Fork a conversation at a specific turn
  * Creates a new conversation that shares history up to but not including the specified turn.
@@ -525,6 +876,33 @@ export async function forkConversation(
   return invoke<string>("fork_conversation", { conversationId, atTurnId, name });
 }
 
+/**
+ * Fork a conversation after the message at the given 0-based index on its
+ * current path, or after the last message when no index is given.
+ * Returns the new conversation's ID.
+ */
+export async function forkConversationAtMessage(
+  conversationId: string,
+  messageIndex?: number
+): Promise<string> {
+  return invoke<string>("fork_conversation_at_message", { conversationId, messageIndex });
+}
+
+/**
+ * Export a conversation's current path as JSON (a list of chat messages).
+ */
+export async function exportConversation(conversationId: string): Promise<string> {
+  return invoke<string>("export_conversation", { conversationId });
+}
+
+/**
+ * Import a conversation from JSON produced by exportConversation.
+ * Returns the new conversation's ID.
+ */
+export async function importConversation(json: string, name?: string): Promise<string> {
+  return invoke<string>("import_conversation", { json, name });
+}
+
 /**
  * Select a specific span at a turn
  * Updates the conversation selection to use the specified span at the given turn.
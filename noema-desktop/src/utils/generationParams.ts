@@ -0,0 +1,85 @@
+import type { GenerationParams } from "../generated";
+
+export const EMPTY_GENERATION_PARAMS: GenerationParams = {
+  temperature: null,
+  topP: null,
+  maxTokens: null,
+  stop: null,
+  seed: null,
+};
+
+/**
+ * Parse the value of `/set stop`: whitespace-separated sequences, where a
+ * sequence containing spaces can be double-quoted and `\n`, `\t`, `\"` and
+ * `\\` are unescaped (e.g. `/set stop "END OF" \n\n`).
+ */
+function parseStopSequences(value: string): string[] {
+  const escapes: Record<string, string> = { n: "\n", t: "\t", '"': '"', "\\": "\\" };
+  const sequences: string[] = [];
+  for (const [, quoted, bare] of value.matchAll(/"((?:[^"\\]|\\.)*)"|(\S+)/g)) {
+    const raw = quoted ?? bare;
+    const sequence = raw.replace(/\\(.)/g, (escape, c: string) => escapes[c] ?? escape);
+    if (sequence) {
+      sequences.push(sequence);
+    }
+  }
+  if (sequences.length === 0) {
+    throw new Error("stop needs at least one non-empty sequence");
+  }
+  return sequences;
+}
+
+/**
+ * Apply a `/set <param> [value]` command to the current settings.
+ *
+ * Returns null if the text is not a `/set` command. Omitting the value
+ * resets the parameter to the provider default. Throws on unknown
+ * parameters or invalid values.
+ */
+export function applySetCommand(
+  text: string,
+  current: GenerationParams
+): GenerationParams | null {
+  const match = text.trim().match(/^\/set\s+(\S+)(?:\s+(.+))?$/);
+  if (!match) {
+    return null;
+  }
+  const [, name, rawValue] = match;
+  const value = rawValue?.trim();
+
+  const number = (min: number, max: number): number | null => {
+    if (value === undefined) return null;
+    const parsed = Number(value);
+    if (!Number.isFinite(parsed) || parsed < min || parsed > max) {
+      throw new Error(`${name} must be a number between ${min} and ${max}`);
+    }
+    return parsed;
+  };
+
+  switch (name) {
+    case "temperature":
+      return { ...current, temperature: number(0, 2) };
+    case "top_p":
+      return { ...current, topP: number(0, 1) };
+    case "max_tokens": {
+      const maxTokens = number(1, Number.MAX_SAFE_INTEGER);
+      if (maxTokens !== null && !Number.isInteger(maxTokens)) {
+        throw new Error("max_tokens must be a whole number");
+      }
+      return { ...current, maxTokens };
+    }
+    case "seed": {
+      const seed = number(0, Number.MAX_SAFE_INTEGER);
+      if (seed !== null && !Number.isInteger(seed)) {
+        throw new Error("seed must be a whole number");
+      }
+      return { ...current, seed };
+    }
+    case "stop":
+      return { ...current, stop: value === undefined ? null : parseStopSequences(value) };
+    default:
+      throw new Error(
+        `Unknown parameter "${name}" (expected temperature, top_p, max_tokens, stop or seed)`
+      );
+  }
+}
@@ -1,16 +1,96 @@
+use crate::docx::extract_docx_text;
 use crate::pdf::process_pdf;
 use base64::Engine;
 use llm::ContentBlock;
+use std::path::Path;
+
+/// Text attachments longer than this many characters are truncated
+pub const MAX_TEXT_ATTACHMENT_CHARS: usize = 100_000;
+
+const DOCX_MIME_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+/// Extensions treated as plain text when the mime type is missing or generic
+const TEXT_EXTENSIONS: &[&str] = &["txt", "md", "markdown", "csv", "tsv"];
+
+/// Mime types of the files that can be attached by path
+const PATH_MIME_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("pdf", "application/pdf"),
+];
+
+/// Detect the mime type of an image or PDF from its file extension
+pub fn mime_type_for_path(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    PATH_MIME_TYPES
+        .iter()
+        .find(|(known, _)| *known == ext)
+        .map(|(_, mime_type)| *mime_type)
+}
 
 #[derive(Debug, Clone)]
 pub struct Attachment {
     pub mime_type: String,
     pub data: String, // base64 encoded data
+    /// Original filename, used to detect the type and label extracted text
+    pub name: Option<String>,
+}
+
+impl Attachment {
+    /// Read an image or PDF from disk
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let mime_type = mime_type_for_path(path)
+            .ok_or_else(|| format!("Unsupported attachment: {} (images and PDFs only)", path.display()))?;
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(Self {
+            mime_type: mime_type.to_string(),
+            data: base64::engine::general_purpose::STANDARD.encode(bytes),
+            name: path.file_name().map(|name| name.to_string_lossy().into_owned()),
+        })
+    }
+
+    fn extension(&self) -> Option<String> {
+        let name = self.name.as_deref()?;
+        let (_, ext) = name.rsplit_once('.')?;
+        Some(ext.to_lowercase())
+    }
+
+    fn decode(&self) -> Result<Vec<u8>, String> {
+        base64::engine::general_purpose::STANDARD
+            .decode(&self.data)
+            .map_err(|e| format!("Failed to decode base64: {}", e))
+    }
+
+    /// Text block with a header naming the file
+    fn text_block(&self, text: &str) -> ContentBlock {
+        let header = match &self.name {
+            Some(name) => format!("[File: {}]", name),
+            None => "[Attached file]".to_string(),
+        };
+        ContentBlock::Text {
+            text: format!("{}\n{}", header, truncate_text(text, MAX_TEXT_ATTACHMENT_CHARS)),
+        }
+    }
+}
+
+/// Cut `text` to at most `max_chars` characters, noting how much was dropped
+fn truncate_text(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    format!("{}\n[... truncated: showing {} of {} characters]", kept, max_chars, total)
 }
 
 pub fn process_attachment(attachment: &Attachment) -> Result<Vec<ContentBlock>, String> {
     let mut blocks = Vec::new();
     let mime_lower = attachment.mime_type.to_lowercase();
+    let extension = attachment.extension();
 
     if mime_lower.starts_with("image/") {
         blocks.push(ContentBlock::Image {
@@ -22,22 +102,18 @@ pub fn process_attachment(attachment: &Attachment) -> Result<Vec<ContentBlock>,
             data: attachment.data.clone(),
             mime_type: attachment.mime_type.clone(),
         });
-    } else if mime_lower.starts_with("text/") {
-        // Text/markdown files: decode and add as text content
-        match base64::engine::general_purpose::STANDARD.decode(&attachment.data) {
-            Ok(bytes) => match String::from_utf8(bytes) {
-                Ok(text) => {
-                    blocks.push(ContentBlock::Text { text });
-                }
-                Err(e) => {
-                    return Err(format!("Failed to decode text file as UTF-8: {}", e));
-                }
-            },
-            Err(e) => {
-                return Err(format!("Failed to decode base64: {}", e));
-            }
-        }
-    } else if mime_lower == "application/pdf" {
+    } else if mime_lower.starts_with("text/")
+        || extension.as_deref().is_some_and(|ext| TEXT_EXTENSIONS.contains(&ext))
+    {
+        // Text/markdown/CSV files: decode and add as text content
+        let text = String::from_utf8(attachment.decode()?)
+            .map_err(|e| format!("Failed to decode text file as UTF-8: {}", e))?;
+        blocks.push(attachment.text_block(&text));
+    } else if mime_lower == DOCX_MIME_TYPE || extension.as_deref() == Some("docx") {
+        let text = extract_docx_text(&attachment.decode()?)
+            .map_err(|e| format!("Failed to process Word document: {}", e))?;
+        blocks.push(attachment.text_block(&text));
+    } else if mime_lower == "application/pdf" || extension.as_deref() == Some("pdf") {
         // PDF files: extract text and images
         match process_pdf_attachment(&attachment.data) {
             Ok(pdf_blocks) => {
@@ -47,11 +123,13 @@ pub fn process_attachment(attachment: &Attachment) -> Result<Vec<ContentBlock>,
                 return Err(format!("Failed to process PDF: {}", e));
             }
         }
-    }
-
-    if blocks.is_empty() {
-        // Maybe warning or error? For now let's assume unsupported types return empty or error
-        // If empty, caller might decide.
+    } else {
+        let kind = match (&attachment.name, mime_lower.as_str()) {
+            (Some(name), _) => name.clone(),
+            (None, "") => "unknown type".to_string(),
+            (None, mime) => mime.to_string(),
+        };
+        return Err(format!("Unsupported attachment: {}", kind));
     }
 
     Ok(blocks)
@@ -70,7 +148,27 @@ fn process_pdf_attachment(base64_data: &str) -> Result<Vec<ContentBlock>, String
     // Add text content
     if let Some(text) = extracted.text {
         blocks.push(ContentBlock::Text {
-            text: format!("[PDF Content]\n{}", text),
+            text: format!("[PDF Content]\n{}", truncate_text(&text, MAX_TEXT_ATTACHMENT_CHARS)),
+        });
+    }
+
+    // Add rendered pages (scanned PDFs)
+    if !extracted.pages.is_empty() {
+        let note = if extracted.pages.len() < extracted.page_count {
+            format!(
+                "[PDF Pages] First {} of {} pages as images",
+                extracted.pages.len(),
+                extracted.page_count
+            )
+        } else {
+            format!("[PDF Pages] {} pages as images", extracted.pages.len())
+        };
+        blocks.push(ContentBlock::Text { text: note });
+    }
+    for page in extracted.pages {
+        blocks.push(ContentBlock::Image {
+            data: page.data,
+            mime_type: page.mime_type,
         });
     }
 
@@ -88,3 +186,80 @@ fn process_pdf_attachment(base64_data: &str) -> Result<Vec<ContentBlock>, String
 
     Ok(blocks)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(mime_type: &str, name: Option<&str>, content: &[u8]) -> Attachment {
+        Attachment {
+            mime_type: mime_type.to_string(),
+            data: base64::engine::general_purpose::STANDARD.encode(content),
+            name: name.map(String::from),
+        }
+    }
+
+    fn text_of(blocks: Vec<ContentBlock>) -> String {
+        match blocks.as_slice() {
+            [ContentBlock::Text { text }] => text.clone(),
+            other => panic!("expected one text block, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_text_attachments_by_mime_or_extension() {
+        let csv = attachment("text/csv", Some("data.csv"), b"a,b\n1,2");
+        assert_eq!(text_of(process_attachment(&csv).unwrap()), "[File: data.csv]\na,b\n1,2");
+
+        // Browsers often send no type for markdown files
+        let md = attachment("", Some("notes.MD"), b"# Notes");
+        assert_eq!(text_of(process_attachment(&md).unwrap()), "[File: notes.MD]\n# Notes");
+
+        let unnamed = attachment("text/plain", None, b"hi");
+        assert_eq!(text_of(process_attachment(&unnamed).unwrap()), "[Attached file]\nhi");
+    }
+
+    #[test]
+    fn test_unsupported_attachment_is_an_error() {
+        let exe = attachment("application/octet-stream", Some("tool.exe"), &[0x4d, 0x5a, 0x00]);
+        assert_eq!(process_attachment(&exe).unwrap_err(), "Unsupported attachment: tool.exe");
+
+        let unknown = attachment("application/zip", None, &[0x50, 0x4b]);
+        assert_eq!(
+            process_attachment(&unknown).unwrap_err(),
+            "Unsupported attachment: application/zip"
+        );
+    }
+
+    #[test]
+    fn test_truncate_text() {
+        assert_eq!(truncate_text("short", 10), "short");
+        assert_eq!(
+            truncate_text("héllo wörld", 5),
+            "héllo\n[... truncated: showing 5 of 11 characters]"
+        );
+    }
+
+    #[test]
+    fn test_attachment_from_path() {
+        assert_eq!(mime_type_for_path(Path::new("photo.JPG")), Some("image/jpeg"));
+        assert_eq!(mime_type_for_path(Path::new("paper.pdf")), Some("application/pdf"));
+        assert_eq!(mime_type_for_path(Path::new("notes.txt")), None);
+        assert_eq!(mime_type_for_path(Path::new("README")), None);
+
+        let path = std::env::temp_dir().join(format!("noema-attach-{}.png", std::process::id()));
+        std::fs::write(&path, [0x89, b'P', b'N', b'G']).unwrap();
+        let attachment = Attachment::from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(attachment.mime_type, "image/png");
+        assert_eq!(attachment.name.as_deref(), path.file_name().and_then(|n| n.to_str()));
+        assert!(matches!(
+            process_attachment(&attachment).unwrap().as_slice(),
+            [ContentBlock::Image { mime_type, .. }] if mime_type == "image/png"
+        ));
+
+        assert!(Attachment::from_path(Path::new("missing.png")).is_err());
+        assert!(Attachment::from_path(Path::new("notes.txt")).unwrap_err().starts_with("Unsupported"));
+    }
+}
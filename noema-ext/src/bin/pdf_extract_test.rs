@@ -49,6 +49,15 @@ fn main() {
                 // Save to file for inspection
                 save_image(image, i);
             }
+
+            println!(
+                "\nPages rendered: {} of {}",
+                extracted.pages.len(),
+                extracted.page_count
+            );
+            for (i, page) in extracted.pages.iter().enumerate() {
+                save_image(page, extracted.images.len() + i);
+            }
         }
         Err(e) => {
             eprintln!("Failed to extract PDF content: {}", e);
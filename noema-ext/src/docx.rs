@@ -0,0 +1,132 @@
+//! Text extraction from Word (.docx) documents
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::io::{Cursor, Read};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+/// Upper bound for the unpacked `word/document.xml`, to reject zip bombs
+const MAX_DOCUMENT_XML_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Extract the paragraph text of a .docx file
+pub fn extract_docx_text(bytes: &[u8]) -> Result<String, String> {
+    let xml = read_zip_entry(bytes, "word/document.xml", MAX_DOCUMENT_XML_BYTES)?;
+    let xml = String::from_utf8(xml).map_err(|e| format!("document.xml is not UTF-8: {}", e))?;
+    document_text(&xml)
+}
+
+/// Collect the text runs of WordprocessingML, one line per paragraph
+fn document_text(xml: &str) -> Result<String, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut text = String::new();
+    let mut in_text_run = false;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) if e.local_name().as_ref() == b"t" => in_text_run = true,
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"t" => in_text_run = false,
+                b"p" => text.push('\n'),
+                _ => {}
+            },
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"tab" => text.push('\t'),
+                b"br" | b"cr" => text.push('\n'),
+                _ => {}
+            },
+            Ok(Event::Text(e)) if in_text_run => {
+                let run = e.unescape().map_err(|e| format!("Invalid document.xml: {}", e))?;
+                text.push_str(&run);
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => return Err(format!("Invalid document.xml: {}", e)),
+        }
+    }
+
+    Ok(text.trim().to_string())
+}
+
+/// Read one file out of a zip archive. An entry that unpacks to more
+/// than `max_bytes` is an error rather than being cut short.
+fn read_zip_entry(bytes: &[u8], name: &str, max_bytes: u64) -> Result<Vec<u8>, String> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|_| "Not a valid .docx (zip) file".to_string())?;
+    let entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(ZipError::FileNotFound) => {
+            return Err(format!("{} not found; is this a Word document?", name))
+        }
+        Err(e) => return Err(format!("Failed to read {}: {}", name, e)),
+    };
+    let too_large = || format!("{} is larger than {} bytes", name, max_bytes);
+    if entry.size() > max_bytes {
+        return Err(too_large());
+    }
+
+    // The declared size may lie, so read one byte past the limit to tell
+    let mut out = Vec::new();
+    entry
+        .take(max_bytes + 1)
+        .read_to_end(&mut out)
+        .map_err(|e| format!("Failed to decompress {}: {}", name, e))?;
+    if out.len() as u64 > max_bytes {
+        return Err(too_large());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    const DOCUMENT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:body>
+    <w:p><w:r><w:t>Quarterly</w:t></w:r><w:r><w:t xml:space="preserve"> report</w:t></w:r></w:p>
+    <w:p><w:r><w:t>Q&amp;A</w:t><w:tab/><w:t>done</w:t></w:r></w:p>
+  </w:body>
+</w:document>"#;
+
+    /// Build a zip with one entry, stored or deflated
+    fn zip_with(name: &str, content: &[u8], deflate: bool) -> Vec<u8> {
+        let method = if deflate { CompressionMethod::Deflated } else { CompressionMethod::Stored };
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(name, SimpleFileOptions::default().compression_method(method))
+            .unwrap();
+        zip.write_all(content).unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_docx_text() {
+        for deflate in [false, true] {
+            let docx = zip_with("word/document.xml", DOCUMENT_XML.as_bytes(), deflate);
+            assert_eq!(
+                extract_docx_text(&docx).unwrap(),
+                "Quarterly report\nQ&A\tdone"
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_docx_rejects_other_files() {
+        assert!(extract_docx_text(b"plain text").is_err());
+
+        let zip = zip_with("content.xml", b"<x/>", false);
+        let err = extract_docx_text(&zip).unwrap_err();
+        assert!(err.contains("word/document.xml not found"), "{}", err);
+    }
+
+    #[test]
+    fn test_oversized_entry_is_an_error() {
+        let zip = zip_with("word/document.xml", &[b'x'; 100], true);
+        let err = read_zip_entry(&zip, "word/document.xml", 99).unwrap_err();
+        assert!(err.contains("larger than 99 bytes"), "{}", err);
+        assert_eq!(read_zip_entry(&zip, "word/document.xml", 100).unwrap().len(), 100);
+    }
+}
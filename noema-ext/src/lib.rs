@@ -1,7 +1,9 @@
-//! Extension utilities for Noema - PDF processing, etc.
+//! Extension utilities for Noema - PDF, Word and text attachment processing
 
 pub mod attachments;
+pub mod docx;
 pub mod pdf;
 
-pub use attachments::{process_attachment, Attachment};
-pub use pdf::{process_pdf, ExtractedImage, ExtractedPdf};
\ No newline at end of file
+pub use attachments::{mime_type_for_path, process_attachment, Attachment, MAX_TEXT_ATTACHMENT_CHARS};
+pub use docx::extract_docx_text;
+pub use pdf::{process_pdf, process_pdf_with_options, ExtractedImage, ExtractedPdf, PageRendering, PdfOptions};
\ No newline at end of file
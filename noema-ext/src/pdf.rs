@@ -10,6 +10,10 @@ use std::io::Read;
 pub struct ExtractedPdf {
     pub text: Option<String>,
     pub images: Vec<ExtractedImage>,
+    /// Rasterized pages, in page order (see [`PageRendering`])
+    pub pages: Vec<ExtractedImage>,
+    /// Number of pages in the document, which may exceed `pages.len()`
+    pub page_count: usize,
 }
 
 /// An extracted image with its data and mime type
@@ -18,11 +22,51 @@ pub struct ExtractedImage {
     pub mime_type: String,
 }
 
-/// Process PDF bytes and extract text and images
+/// When to rasterize whole pages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageRendering {
+    Never,
+    /// Only for PDFs without a text layer, e.g. scans
+    WhenNoText,
+    Always,
+}
+
+/// Options for [`process_pdf_with_options`]
+#[derive(Debug, Clone)]
+pub struct PdfOptions {
+    pub render_pages: PageRendering,
+    /// Resolution of rendered pages
+    pub dpi: u32,
+    /// Pages beyond this are not rendered, to keep payloads bounded
+    pub max_pages: usize,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            render_pages: PageRendering::WhenNoText,
+            dpi: 150,
+            max_pages: 20,
+        }
+    }
+}
+
+/// Process PDF bytes and extract text and images, using default options
 pub fn process_pdf(pdf_bytes: &[u8]) -> Result<ExtractedPdf, String> {
+    process_pdf_with_options(pdf_bytes, &PdfOptions::default())
+}
+
+/// Process PDF bytes and extract text and images, rendering pages as
+/// configured in `options`
+pub fn process_pdf_with_options(
+    pdf_bytes: &[u8],
+    options: &PdfOptions,
+) -> Result<ExtractedPdf, String> {
     let mut result = ExtractedPdf {
         text: None,
         images: Vec::new(),
+        pages: Vec::new(),
+        page_count: 0,
     };
 
     // Extract text from PDF
@@ -34,12 +78,35 @@ pub fn process_pdf(pdf_bytes: &[u8]) -> Result<ExtractedPdf, String> {
             }
         }
         Err(e) => {
-            eprintln!("PDF text extraction failed: {}", e);
+            tracing::warn!("PDF text extraction failed: {}", e);
         }
     }
 
+    let render = match options.render_pages {
+        PageRendering::Never => false,
+        PageRendering::WhenNoText => result.text.is_none(),
+        PageRendering::Always => true,
+    };
+    if render {
+        match render_pages(pdf_bytes, options) {
+            Ok((pages, page_count)) => {
+                result.pages = pages;
+                result.page_count = page_count;
+            }
+            Err(e) => {
+                tracing::warn!("PDF page rendering failed: {}", e);
+            }
+        }
+    }
+
+    // Rendered pages already show everything embedded in them
+    if !result.pages.is_empty() {
+        return Ok(result);
+    }
+
     // Extract embedded images by scanning all document objects
     if let Ok(doc) = lopdf::Document::load_mem(pdf_bytes) {
+        result.page_count = doc.get_pages().len();
         for (_obj_id, obj) in doc.objects.iter() {
             if let lopdf::Object::Stream(stream) = obj {
                 if let Some(image) = extract_image_from_stream(stream) {
@@ -221,18 +288,66 @@ fn convert_raw_to_png(
     })
 }
 
+/// Bind to the pdfium library next to the executable or installed system-wide
+fn load_pdfium() -> Result<pdfium_render::prelude::Pdfium, String> {
+    use pdfium_render::prelude::*;
+
+    Ok(Pdfium::new(
+        Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path("./"))
+            .or_else(|_| Pdfium::bind_to_system_library())
+            .map_err(|e| format!("Failed to load pdfium: {}", e))?,
+    ))
+}
+
+/// Pixel size of a page edge given in points (1/72 inch)
+fn page_pixels(points: f32, dpi: u32) -> i32 {
+    ((points * dpi as f32 / 72.0).round() as i32).max(1)
+}
+
+/// Rasterize up to `options.max_pages` pages to PNG.
+/// Returns the rendered pages and the document's total page count.
+fn render_pages(
+    pdf_bytes: &[u8],
+    options: &PdfOptions,
+) -> Result<(Vec<ExtractedImage>, usize), String> {
+    use pdfium_render::prelude::*;
+
+    let pdfium = load_pdfium()?;
+    let document = pdfium
+        .load_pdf_from_byte_slice(pdf_bytes, None)
+        .map_err(|e| format!("Failed to load PDF with pdfium: {}", e))?;
+
+    let page_count = document.pages().len() as usize;
+    let mut pages = Vec::new();
+    for page in document.pages().iter().take(options.max_pages) {
+        let render_config = PdfRenderConfig::new()
+            .set_target_width(page_pixels(page.width().value, options.dpi))
+            .set_target_height(page_pixels(page.height().value, options.dpi));
+        let bitmap = page
+            .render_with_config(&render_config)
+            .map_err(|e| format!("Failed to render page: {}", e))?;
+
+        let mut png_bytes = std::io::Cursor::new(Vec::new());
+        bitmap
+            .as_image()
+            .write_to(&mut png_bytes, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode page: {}", e))?;
+        pages.push(ExtractedImage {
+            data: base64::engine::general_purpose::STANDARD.encode(png_bytes.into_inner()),
+            mime_type: "image/png".to_string(),
+        });
+    }
+
+    Ok((pages, page_count))
+}
+
 /// Extract Form XObjects (diagrams, charts) as rendered images using pdfium
 fn extract_form_xobjects(pdf_bytes: &[u8]) -> Result<Vec<ExtractedImage>, String> {
     use pdfium_render::prelude::*;
 
     let mut images = Vec::new();
 
-    // Try to bind to pdfium library
-    let pdfium = Pdfium::new(
-        Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path("./"))
-            .or_else(|_| Pdfium::bind_to_system_library())
-            .map_err(|e| format!("Failed to load pdfium: {}", e))?,
-    );
+    let pdfium = load_pdfium()?;
 
     let document = pdfium
         .load_pdf_from_byte_slice(pdf_bytes, None)
@@ -280,3 +395,16 @@ fn extract_form_xobjects(pdf_bytes: &[u8]) -> Result<Vec<ExtractedImage>, String
 
     Ok(images)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_pixels() {
+        // US Letter width at 150 DPI
+        assert_eq!(page_pixels(612.0, 150), 1275);
+        assert_eq!(page_pixels(612.0, 72), 612);
+        assert_eq!(page_pixels(0.1, 72), 1);
+    }
+}
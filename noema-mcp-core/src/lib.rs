@@ -2,6 +2,10 @@
 //!
 //! Exposes noema's internal capabilities as standard MCP tools:
 //! - `spawn_agent` - spawn subconversations for complex subtasks
+//! - `spawn_agent_streaming` - same, reporting the sub-agent's messages as progress
+//! - `spawn_agents` - run several sub-agents concurrently and collect their results
+//! - `list_conversations` - list the user's prior conversations
+//! - `get_conversation` - read one of the user's prior conversations
 //!
 //! This server is stateless - agents enrich tool calls with context
 //! (conversation_id, turn_id, etc) before forwarding to this server.
@@ -10,39 +14,13 @@ pub mod tools;
 
 pub use tools::NoemaCoreServer;
 
-use hyper::server::conn::http1;
-use hyper_util::rt::TokioIo;
-use rmcp::transport::streamable_http_server::{
-    session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
-};
-use std::net::SocketAddr;
-use std::sync::Arc;
-use tokio::net::TcpListener;
-use tokio::sync::oneshot;
-use tower_service::Service;
-use tracing::info;
+pub use noema_mcp_server::{ServerHandle, DEFAULT_DRAIN_TIMEOUT};
 
-/// Handle to a running server that can be used to stop it
-pub struct ServerHandle {
-    shutdown_tx: oneshot::Sender<()>,
-    port: u16,
-}
-
-impl ServerHandle {
-    /// Get the port the server is running on
-    pub fn port(&self) -> u16 {
-        self.port
-    }
-
-    /// Get the URL for the MCP endpoint
-    pub fn url(&self) -> String {
-        format!("http://127.0.0.1:{}/mcp", self.port)
-    }
+use noema_mcp_server::McpHttpServer;
+use std::path::Path;
 
-    /// Stop the server
-    pub fn stop(self) {
-        let _ = self.shutdown_tx.send(());
-    }
+fn http_server(server: NoemaCoreServer) -> McpHttpServer<NoemaCoreServer> {
+    McpHttpServer::new("Noema Core", move || server.clone())
 }
 
 /// Start the noema-core MCP server on a random port
@@ -56,68 +34,19 @@ pub async fn start_server_on(
     port: u16,
     server: NoemaCoreServer,
 ) -> anyhow::Result<ServerHandle> {
-    let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
-
-    let listener = TcpListener::bind(addr).await?;
-    let local_addr = listener.local_addr()?;
-    let actual_port = local_addr.port();
-
-    info!("Starting Noema Core MCP server on {}", local_addr);
-
-    let (shutdown_tx, shutdown_rx) = oneshot::channel();
-
-    tokio::spawn(async move {
-        let config = StreamableHttpServerConfig::default();
-        let session_manager = Arc::new(LocalSessionManager::default());
-
-        let mcp_service = StreamableHttpService::new(
-            move || Ok(server.clone()),
-            session_manager,
-            config,
-        );
-
-        let mut shutdown_rx = shutdown_rx;
-
-        loop {
-            tokio::select! {
-                _ = &mut shutdown_rx => {
-                    info!("Shutting down Noema Core MCP server");
-                    break;
-                }
-                result = listener.accept() => {
-                    match result {
-                        Ok((stream, _)) => {
-                            let io = TokioIo::new(stream);
-                            let service = mcp_service.clone();
-
-                            tokio::spawn(async move {
-                                if let Err(err) = http1::Builder::new()
-                                    .serve_connection(
-                                        io,
-                                        hyper::service::service_fn(move |req| {
-                                            let mut svc = service.clone();
-                                            async move {
-                                                svc.call(req).await
-                                            }
-                                        }),
-                                    )
-                                    .await
-                                {
-                                    tracing::error!("Error serving connection: {:?}", err);
-                                }
-                            });
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to accept connection: {}", e);
-                        }
-                    }
-                }
-            }
-        }
-    });
+    http_server(server).serve(host, port).await
+}
 
-    Ok(ServerHandle {
-        shutdown_tx,
-        port: actual_port,
-    })
+/// Start the noema-core MCP server on a Unix domain socket
+///
+/// Serves the same endpoint as `start_server_on` without opening a TCP
+/// port. A socket file left at `path` by an earlier run is replaced, and
+/// the file is removed when the server stops. Unix sockets are not
+/// available on Windows, where this returns an error; use
+/// `start_server_on` there.
+pub async fn start_server_uds(
+    path: impl AsRef<Path>,
+    server: NoemaCoreServer,
+) -> anyhow::Result<ServerHandle> {
+    http_server(server).serve_uds(path).await
 }
@@ -2,21 +2,31 @@
 //!
 //! This server is stateless - the agent enriches tool calls with context
 //! (conversation_id, turn_id, etc) before forwarding to this server.
+//!
+//! `list_conversations` and `get_conversation` read stored conversations of
+//! the user named in the injected context. The server trusts that context:
+//! callers must only inject the user they act for, and only forward
+//! conversation IDs that user owns. Conversations of other users are
+//! reported as not found.
 
-use noema_core::agents::{ExecutionContext, McpAgent, ToolEnricher};
+use noema_core::agents::{ExecutionContext, MaxIterationsExceeded, McpAgent, ToolEnricher};
 use noema_core::mcp::{McpRegistry, McpToolRegistry};
 use noema_core::storage::coordinator::StorageCoordinator;
 use noema_core::storage::document_resolver::DocumentResolver;
 use noema_core::storage::ids::{ConversationId, SpanId, TurnId, UserId};
-use noema_core::storage::session::Session;
-use noema_core::storage::traits::StorageTypes;
+use noema_core::storage::session::{ResolvedContent, ResolvedMessage, Session};
+use noema_core::storage::traits::{StorageTypes, StoredEntity};
+use noema_core::storage::types::ConversationListOptions;
 use noema_core::manager::CommitMode;
-use noema_core::{Agent, ConversationContext};
+use noema_core::{Agent, ConversationContext, MessagesGuard, SubconversationManager, SubconversationStatus};
 
 /// Create an enricher that injects execution context for noema-core tools.
 fn create_noema_core_enricher() -> ToolEnricher {
     Arc::new(|tool_name, args, context| {
-        if tool_name == "spawn_agent" {
+        if matches!(
+            tool_name,
+            "spawn_agent" | "spawn_agent_streaming" | "spawn_agents" | "list_conversations" | "get_conversation"
+        ) {
             match args {
                 serde_json::Value::Object(map) => serde_json::Value::Object(context.inject_into(map)),
                 other => other,
@@ -27,14 +37,14 @@ fn create_noema_core_enricher() -> ToolEnricher {
     })
 }
 
-use llm::{ChatMessage, ChatPayload, ContentBlock, create_model};
+use llm::{ChatMessage, ChatPayload, ContentBlock, Role, create_model};
 use rmcp::{
     handler::server::ServerHandler,
     model::*,
     service::{RequestContext, RoleServer},
     ErrorData as McpError,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -56,6 +66,193 @@ struct SpawnAgentArgs {
     context: ExecutionContext,
 }
 
+/// Most tasks one spawn_agents call may ask for
+const MAX_SPAWN_AGENTS_TASKS: usize = 16;
+
+/// Most sub-agents of one spawn_agents call that run at the same time
+const MAX_CONCURRENT_AGENTS: usize = 4;
+
+/// One subtask of a spawn_agents call
+#[derive(Debug, Deserialize)]
+struct AgentTask {
+    /// The task/prompt for the spawned agent
+    prompt: String,
+    /// Optional system prompt for the spawned agent
+    system_prompt: Option<String>,
+    /// Optional name for the subconversation
+    name: Option<String>,
+}
+
+/// Arguments for spawn_agents tool
+#[derive(Debug, Deserialize)]
+struct SpawnAgentsArgs {
+    /// The subtasks, each run by its own agent
+    tasks: Vec<AgentTask>,
+    /// Cancel the remaining agents as soon as one fails
+    #[serde(default)]
+    cancel_on_failure: bool,
+    /// Execution context (injected by agent)
+    #[serde(rename = "_context")]
+    context: ExecutionContext,
+}
+
+/// The turn that called a spawn tool, taken from its execution context
+struct SpawnParent {
+    conversation_id: ConversationId,
+    user_id: UserId,
+    turn_id: TurnId,
+    span_id: Option<SpanId>,
+    model_id: String,
+}
+
+impl SpawnParent {
+    fn from_context(tool: &str, ctx: &ExecutionContext) -> Result<Self, CallToolResult> {
+        if !ctx.is_ready() {
+            error!("{}: execution context incomplete: {:?}", tool, ctx);
+            return Err(CallToolResult::error(vec![Content::text(format!(
+                "{}: execution context not ready. The agent should inject _context.",
+                tool
+            ))]));
+        }
+
+        Ok(Self {
+            conversation_id: ConversationId::from_string(ctx.conversation_id.as_ref().unwrap()),
+            user_id: UserId::from_string(ctx.user_id.as_ref().unwrap()),
+            turn_id: TurnId::from_string(ctx.turn_id.as_ref().unwrap()),
+            span_id: ctx.span_id.as_ref().map(SpanId::from_string),
+            model_id: ctx.model_id.clone().unwrap(),
+        })
+    }
+
+    fn create_model(&self) -> Result<Arc<dyn llm::ChatModel + Send + Sync>, CallToolResult> {
+        create_model(&self.model_id).map_err(|e| {
+            CallToolResult::error(vec![Content::text(format!(
+                "Failed to create model '{}': {}",
+                self.model_id, e
+            ))])
+        })
+    }
+}
+
+/// Arguments for list_conversations tool
+#[derive(Debug, Deserialize)]
+struct ListConversationsArgs {
+    /// Also list archived conversations
+    #[serde(default)]
+    include_archived: bool,
+    /// Maximum number of conversations to return
+    limit: Option<usize>,
+    /// Execution context (injected by agent)
+    #[serde(rename = "_context")]
+    context: ExecutionContext,
+}
+
+/// Arguments for get_conversation tool
+#[derive(Debug, Deserialize)]
+struct GetConversationArgs {
+    /// The conversation to read
+    conversation_id: String,
+    /// Execution context (injected by agent)
+    #[serde(rename = "_context")]
+    context: ExecutionContext,
+}
+
+/// A conversation as returned by list_conversations and get_conversation
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConversationInfo {
+    id: String,
+    name: Option<String>,
+    message_count: usize,
+    is_private: bool,
+    created_at: i64,
+    last_model: Option<String>,
+    tags: Vec<String>,
+    is_pinned: bool,
+    is_archived: bool,
+}
+
+/// A conversation with its messages, as returned by get_conversation
+#[derive(Debug, Serialize)]
+struct ConversationTranscript {
+    #[serde(flatten)]
+    info: ConversationInfo,
+    messages: Vec<TranscriptMessage>,
+}
+
+/// The text of one message on a conversation's selected path
+#[derive(Debug, Serialize)]
+struct TranscriptMessage {
+    role: Role,
+    text: String,
+}
+
+impl TranscriptMessage {
+    /// Keep the text of a message, noting non-text content by kind
+    fn from_resolved(message: &ResolvedMessage) -> Self {
+        let text = message
+            .content
+            .iter()
+            .map(|content| match content {
+                ResolvedContent::Text { text } => text.clone(),
+                ResolvedContent::Asset { mime_type, .. } => format!("[attachment: {}]", mime_type),
+                ResolvedContent::Document { document_id, .. } => format!("[document: {}]", document_id),
+                ResolvedContent::ToolCall(call) => format!("[called {}]", call.name),
+                ResolvedContent::ToolResult(result) => format!("[tool result: {}]", result.get_text()),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            role: message.role,
+            text,
+        }
+    }
+}
+
+/// Receives each assistant message a spawned agent produces
+type MessageObserver = Arc<dyn Fn(&ChatMessage) + Send + Sync>;
+
+/// What a spawned agent is asked to do in its subconversation
+struct SubagentRun {
+    prompt: String,
+    system_prompt: Option<String>,
+    model: Arc<dyn llm::ChatModel + Send + Sync>,
+    execution_context: ExecutionContext,
+    on_message: Option<MessageObserver>,
+}
+
+/// Context that reports assistant messages to an observer as the agent adds them
+struct ObservedContext<'a> {
+    inner: &'a mut dyn ConversationContext,
+    on_message: MessageObserver,
+}
+
+#[async_trait::async_trait]
+impl ConversationContext for ObservedContext<'_> {
+    async fn messages(&mut self) -> anyhow::Result<MessagesGuard<'_>> {
+        self.inner.messages().await
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn add(&mut self, message: ChatMessage) {
+        if message.role == Role::Assistant {
+            (self.on_message)(&message);
+        }
+        self.inner.add(message);
+    }
+
+    fn pending(&self) -> &[ChatMessage] {
+        self.inner.pending()
+    }
+
+    async fn commit(&mut self) -> anyhow::Result<()> {
+        self.inner.commit().await
+    }
+}
+
 /// MCP Server exposing Noema's core capabilities.
 /// Stateless - context is passed in tool arguments by the agent.
 #[derive(Clone)]
@@ -83,15 +280,25 @@ trait CoordinatorOps: Send + Sync {
 
     async fn get_subconversation_result(&self, sub_id: &ConversationId) -> anyhow::Result<Option<String>>;
 
+    async fn list_conversations(
+        &self,
+        user_id: &UserId,
+        include_archived: bool,
+    ) -> anyhow::Result<Vec<ConversationInfo>>;
+
+    /// Get a conversation and its messages, or None if `user_id` doesn't own it
+    async fn get_conversation(
+        &self,
+        user_id: &UserId,
+        conversation_id: &ConversationId,
+    ) -> anyhow::Result<Option<ConversationTranscript>>;
+
     async fn run_agent_in_subconversation(
         &self,
         sub_id: &ConversationId,
-        prompt: String,
-        system_prompt: Option<String>,
-        model: Arc<dyn llm::ChatModel + Send + Sync>,
+        run: SubagentRun,
         mcp_registry: Arc<Mutex<McpRegistry>>,
         document_resolver: Arc<dyn DocumentResolver>,
-        execution_context: ExecutionContext,
     ) -> anyhow::Result<()>;
 }
 
@@ -100,6 +307,22 @@ struct ConcreteCoordinator<S: StorageTypes> {
     coordinator: Arc<StorageCoordinator<S>>,
 }
 
+impl<S: StorageTypes> ConcreteCoordinator<S> {
+    async fn conversation_info(&self, entity: &StoredEntity) -> anyhow::Result<ConversationInfo> {
+        Ok(ConversationInfo {
+            id: entity.id.as_str().to_string(),
+            name: entity.name.clone(),
+            message_count: self.coordinator.get_turn_count(&entity.id).await?,
+            is_private: entity.is_private,
+            created_at: entity.created_at,
+            last_model: entity.last_model().map(str::to_string),
+            tags: self.coordinator.get_conversation_tags(&entity.id).await?,
+            is_pinned: entity.is_pinned(),
+            is_archived: entity.is_archived,
+        })
+    }
+}
+
 #[async_trait::async_trait]
 impl<S: StorageTypes> CoordinatorOps for ConcreteCoordinator<S> {
     async fn spawn_subconversation(
@@ -119,16 +342,54 @@ impl<S: StorageTypes> CoordinatorOps for ConcreteCoordinator<S> {
         self.coordinator.get_subconversation_result(sub_id).await
     }
 
+    async fn list_conversations(
+        &self,
+        user_id: &UserId,
+        include_archived: bool,
+    ) -> anyhow::Result<Vec<ConversationInfo>> {
+        let options = ConversationListOptions { include_archived, ..Default::default() };
+        let entities = self.coordinator.list_conversations(user_id, &options).await?;
+        let mut infos = Vec::with_capacity(entities.len());
+        for entity in &entities {
+            infos.push(self.conversation_info(entity).await?);
+        }
+        Ok(infos)
+    }
+
+    async fn get_conversation(
+        &self,
+        user_id: &UserId,
+        conversation_id: &ConversationId,
+    ) -> anyhow::Result<Option<ConversationTranscript>> {
+        let Some(entity) = self.coordinator.get_conversation(conversation_id).await? else {
+            return Ok(None);
+        };
+        if entity.user_id.as_ref() != Some(user_id) {
+            return Ok(None);
+        }
+
+        let messages = self.coordinator.open_session(conversation_id).await?;
+        Ok(Some(ConversationTranscript {
+            info: self.conversation_info(&entity).await?,
+            messages: messages.iter().map(TranscriptMessage::from_resolved).collect(),
+        }))
+    }
+
     async fn run_agent_in_subconversation(
         &self,
         sub_id: &ConversationId,
-        prompt: String,
-        system_prompt: Option<String>,
-        model: Arc<dyn llm::ChatModel + Send + Sync>,
+        run: SubagentRun,
         mcp_registry: Arc<Mutex<McpRegistry>>,
         document_resolver: Arc<dyn DocumentResolver>,
-        execution_context: ExecutionContext,
     ) -> anyhow::Result<()> {
+        let SubagentRun {
+            prompt,
+            system_prompt,
+            model,
+            execution_context,
+            on_message,
+        } = run;
+
         // Open session
         let resolved_messages = self.coordinator.open_session(sub_id).await?;
         let mut session = Session::new(self.coordinator.clone(), sub_id.clone());
@@ -136,12 +397,9 @@ impl<S: StorageTypes> CoordinatorOps for ConcreteCoordinator<S> {
             session.add_resolved(msg);
         }
 
-        // Add system prompt if provided
-        if let Some(system) = system_prompt {
-            let system_message = ChatMessage::system(ChatPayload::new(vec![ContentBlock::Text {
-                text: system,
-            }]));
-            session.add(system_message);
+        // Set system prompt if provided
+        if system_prompt.is_some() {
+            session.set_system_message(system_prompt).await?;
         }
 
         // Add user prompt
@@ -163,11 +421,26 @@ impl<S: StorageTypes> CoordinatorOps for ConcreteCoordinator<S> {
         // Save model id before move
         let model_id = model.id().to_string();
 
-        // Run agent
-        agent.execute(&mut session, model).await?;
+        // Run agent; a sub-agent that runs out of rounds still reports what it has
+        let result = match on_message {
+            Some(on_message) => {
+                let mut observed = ObservedContext {
+                    inner: &mut session,
+                    on_message,
+                };
+                agent.execute(&mut observed, model).await
+            }
+            None => agent.execute(&mut session, model).await,
+        };
+        if let Err(e) = result {
+            if e.downcast_ref::<MaxIterationsExceeded>().is_none() {
+                return Err(e);
+            }
+        }
 
         // Commit messages to storage so get_subconversation_result can find them
-        session.commit(Some(&model_id), &CommitMode::NewTurns).await
+        session.commit(Some(&model_id), &CommitMode::NewTurns).await?;
+        Ok(())
     }
 }
 
@@ -197,165 +470,517 @@ impl NoemaCoreServer {
 
         // Note: The schema only shows what the LLM provides.
         // The agent enriches calls with conversation_id, user_id, etc.
-        vec![Tool {
-            name: "spawn_agent".into(),
-            title: None,
-            description: Some(
-                "Spawn a subconversation to handle a complex subtask. The spawned agent runs \
-                 independently and returns its result. Use this for tasks that require focused \
-                 attention or multiple tool calls that are separate from the main conversation flow."
-                    .into(),
-            ),
-            input_schema: make_schema(json!({
-                "type": "object",
-                "properties": {
-                    "prompt": {
-                        "type": "string",
-                        "description": "The task/prompt for the spawned agent"
-                    },
-                    "system_prompt": {
-                        "type": "string",
-                        "description": "Optional system prompt for the spawned agent"
-                    },
-                    "name": {
-                        "type": "string",
-                        "description": "Optional name for the subconversation (e.g., 'Research API docs')"
-                    }
+        let spawn_schema = make_schema(json!({
+            "type": "object",
+            "properties": {
+                "prompt": {
+                    "type": "string",
+                    "description": "The task/prompt for the spawned agent"
+                },
+                "system_prompt": {
+                    "type": "string",
+                    "description": "Optional system prompt for the spawned agent"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Optional name for the subconversation (e.g., 'Research API docs')"
+                }
+            },
+            "required": ["prompt"]
+        }));
+
+        let list_schema = make_schema(json!({
+            "type": "object",
+            "properties": {
+                "include_archived": {
+                    "type": "boolean",
+                    "description": "Also list archived conversations (default false)"
                 },
-                "required": ["prompt"]
-            })),
-            annotations: None,
-            output_schema: None,
-            icons: None,
-            meta: None,
-        }]
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Maximum number of conversations to return"
+                }
+            }
+        }));
+
+        let spawn_many_schema = make_schema(json!({
+            "type": "object",
+            "properties": {
+                "tasks": {
+                    "type": "array",
+                    "minItems": 1,
+                    "maxItems": MAX_SPAWN_AGENTS_TASKS,
+                    "description": "The subtasks, each run by its own agent, a few at a time",
+                    "items": spawn_schema.as_ref().clone()
+                },
+                "cancel_on_failure": {
+                    "type": "boolean",
+                    "description": "Cancel the remaining agents as soon as one fails (default false)"
+                }
+            },
+            "required": ["tasks"]
+        }));
+
+        let get_schema = make_schema(json!({
+            "type": "object",
+            "properties": {
+                "conversation_id": {
+                    "type": "string",
+                    "description": "ID of the conversation, as returned by list_conversations"
+                }
+            },
+            "required": ["conversation_id"]
+        }));
+
+        vec![
+            Tool {
+                name: "spawn_agent".into(),
+                title: None,
+                description: Some(
+                    "Spawn a subconversation to handle a complex subtask. The spawned agent runs \
+                     independently and returns its result. Use this for tasks that require focused \
+                     attention or multiple tool calls that are separate from the main conversation flow."
+                        .into(),
+                ),
+                input_schema: spawn_schema.clone(),
+                annotations: None,
+                output_schema: None,
+                icons: None,
+                meta: None,
+            },
+            Tool {
+                name: "spawn_agent_streaming".into(),
+                title: None,
+                description: Some(
+                    "Like spawn_agent, but reports each message of the spawned agent as progress \
+                     while it runs and returns the full transcript. Use this for long subtasks."
+                        .into(),
+                ),
+                input_schema: spawn_schema,
+                annotations: None,
+                output_schema: None,
+                icons: None,
+                meta: None,
+            },
+            Tool {
+                name: "spawn_agents".into(),
+                title: None,
+                description: Some(
+                    "Spawn several subconversations, one per task (at most 16), and wait for all \
+                     of them. Returns each agent's result. Use this for independent subtasks that \
+                     can be worked on in parallel."
+                        .into(),
+                ),
+                input_schema: spawn_many_schema,
+                annotations: None,
+                output_schema: None,
+                icons: None,
+                meta: None,
+            },
+            Tool {
+                name: "list_conversations".into(),
+                title: None,
+                description: Some(
+                    "List the user's prior conversations with their IDs, names, tags and \
+                     message counts."
+                        .into(),
+                ),
+                input_schema: list_schema,
+                annotations: None,
+                output_schema: None,
+                icons: None,
+                meta: None,
+            },
+            Tool {
+                name: "get_conversation".into(),
+                title: None,
+                description: Some(
+                    "Read one of the user's prior conversations: its details and the text of \
+                     each message on its current path."
+                        .into(),
+                ),
+                input_schema: get_schema,
+                annotations: None,
+                output_schema: None,
+                icons: None,
+                meta: None,
+            },
+        ]
     }
 
     async fn handle_spawn_agent(
         &self,
         args: serde_json::Map<String, serde_json::Value>,
     ) -> CallToolResult {
-        let args: SpawnAgentArgs = match serde_json::from_value(serde_json::Value::Object(args)) {
-            Ok(a) => a,
+        let sub_id = match self.run_spawn_agent(args, None).await {
+            Ok(id) => id,
+            Err(result) => return result,
+        };
+
+        let result = match self.inner.coordinator.get_subconversation_result(&sub_id).await {
+            Ok(Some(r)) => r,
+            Ok(None) => "(no result)".to_string(),
             Err(e) => {
-                error!("spawn_agent: invalid arguments: {}", e);
+                error!("Failed to get subconversation result: {}", e);
                 return CallToolResult::error(vec![Content::text(format!(
-                    "Invalid arguments: {}. Make sure the agent is enriching tool calls with context.",
+                    "Failed to get result: {}",
                     e
                 ))]);
             }
         };
 
-        // Extract context (injected by agent)
-        let ctx = &args.context;
+        CallToolResult::success(vec![Content::text(format!(
+            "Subconversation completed.\n\nSubconversation ID: {}\n\nResult:\n{}",
+            sub_id.as_str(),
+            result
+        ))])
+    }
+
+    /// Like `handle_spawn_agent`, but sends each assistant message of the
+    /// sub-agent as an MCP progress notification (if the caller asked for
+    /// progress) and returns the whole transcript.
+    async fn handle_spawn_agent_streaming(
+        &self,
+        args: serde_json::Map<String, serde_json::Value>,
+        context: RequestContext<RoleServer>,
+    ) -> CallToolResult {
+        let transcript = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+
+        // Notifications are async, the observer is not: forward through a channel
+        let progress_token = context.meta.get_progress_token();
+        let peer = context.peer.clone();
+        let notifier = tokio::spawn(async move {
+            let mut progress = 0.0;
+            while let Some(message) = rx.recv().await {
+                progress += 1.0;
+                let Some(progress_token) = progress_token.clone() else {
+                    continue;
+                };
+                if let Err(e) = peer
+                    .notify_progress(ProgressNotificationParam {
+                        progress_token,
+                        progress,
+                        total: None,
+                        message: Some(message),
+                    })
+                    .await
+                {
+                    error!("spawn_agent_streaming: failed to send progress: {}", e);
+                }
+            }
+        });
+
+        let on_message: MessageObserver = {
+            let transcript = Arc::clone(&transcript);
+            Arc::new(move |message: &ChatMessage| {
+                let text = message.get_text();
+                if text.trim().is_empty() {
+                    return;
+                }
+                transcript.lock().unwrap().push(text.clone());
+                let _ = tx.send(text);
+            })
+        };
+
+        // The observer (and with it the sender) is dropped once the agent is done
+        let result = self.run_spawn_agent(args, Some(on_message)).await;
+        let _ = notifier.await;
 
-        // Validate required context fields
-        if !ctx.is_ready() {
-            error!("spawn_agent: execution context incomplete: {:?}", ctx);
-            return CallToolResult::error(vec![Content::text(
-                "spawn_agent: execution context not ready. The agent should inject _context."
-            )]);
+        let sub_id = match result {
+            Ok(id) => id,
+            Err(result) => return result,
+        };
+
+        let transcript = transcript.lock().unwrap().join("\n\n");
+        let transcript = if transcript.is_empty() {
+            "(no result)".to_string()
+        } else {
+            transcript
+        };
+        CallToolResult::success(vec![Content::text(format!(
+            "Subconversation completed.\n\nSubconversation ID: {}\n\nTranscript:\n{}",
+            sub_id.as_str(),
+            transcript
+        ))])
+    }
+
+    /// Run one agent per task, up to `MAX_CONCURRENT_AGENTS` at a time, and
+    /// report every result
+    async fn handle_spawn_agents(
+        &self,
+        args: serde_json::Map<String, serde_json::Value>,
+    ) -> CallToolResult {
+        let args: SpawnAgentsArgs = match serde_json::from_value(serde_json::Value::Object(args)) {
+            Ok(a) => a,
+            Err(e) => return invalid_arguments("spawn_agents", e),
+        };
+        if args.tasks.is_empty() {
+            return CallToolResult::error(vec![Content::text("spawn_agents: no tasks given")]);
+        }
+        if args.tasks.len() > MAX_SPAWN_AGENTS_TASKS {
+            return CallToolResult::error(vec![Content::text(format!(
+                "spawn_agents: {} tasks given, at most {} are allowed",
+                args.tasks.len(),
+                MAX_SPAWN_AGENTS_TASKS
+            ))]);
+        }
+        let parent = match SpawnParent::from_context("spawn_agents", &args.context) {
+            Ok(parent) => parent,
+            Err(result) => return result,
+        };
+        let model = match parent.create_model() {
+            Ok(model) => model,
+            Err(result) => return result,
+        };
+
+        // Create every subconversation before starting any agent, so a
+        // storage failure doesn't leave agents running unreported
+        let mut subconversations = Vec::with_capacity(args.tasks.len());
+        for task in &args.tasks {
+            match self.create_subconversation(&parent, task.name.as_deref()).await {
+                Ok(sub) => subconversations.push(sub),
+                Err(e) => {
+                    error!("Failed to spawn subconversation: {}", e);
+                    return CallToolResult::error(vec![Content::text(format!(
+                        "Failed to spawn subconversation: {}",
+                        e
+                    ))]);
+                }
+            }
+        }
+
+        let mut manager = SubconversationManager::new()
+            .cancel_siblings_on_failure(args.cancel_on_failure)
+            .max_concurrent(MAX_CONCURRENT_AGENTS);
+        let mut order = Vec::with_capacity(args.tasks.len());
+        for (task, (sub_id, execution_context)) in args.tasks.into_iter().zip(subconversations) {
+            let server = self.clone();
+            let model = Arc::clone(&model);
+            let id = sub_id.clone();
+            manager.spawn(sub_id.clone(), async move {
+                let run = SubagentRun {
+                    prompt: task.prompt,
+                    system_prompt: task.system_prompt,
+                    model,
+                    execution_context,
+                    on_message: None,
+                };
+                server.run_subagent(&id, run).await?;
+                let result = server.inner.coordinator.get_subconversation_result(&id).await?;
+                Ok(result.unwrap_or_else(|| "(no result)".to_string()))
+            });
+            order.push(sub_id);
         }
 
-        let conversation_id = ConversationId::from_string(ctx.conversation_id.as_ref().unwrap());
-        let user_id = UserId::from_string(ctx.user_id.as_ref().unwrap());
-        let turn_id = TurnId::from_string(ctx.turn_id.as_ref().unwrap());
-        let span_id = ctx.span_id.as_ref().map(|s| SpanId::from_string(s));
-        let model_id = ctx.model_id.as_ref().unwrap();
+        let mut results = manager.await_all().await;
+        let report = order
+            .iter()
+            .map(|sub_id| {
+                let (status, result) = match results.remove(sub_id) {
+                    Some(SubconversationStatus::Completed(text)) => ("completed", text),
+                    Some(SubconversationStatus::Failed(e)) => ("failed", e),
+                    _ => ("cancelled", "(no result)".to_string()),
+                };
+                format!(
+                    "Subconversation ID: {}\nStatus: {}\n\nResult:\n{}",
+                    sub_id.as_str(),
+                    status,
+                    result
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n---\n\n");
 
-        // Create model from ID
-        let model = match create_model(model_id) {
-            Ok(m) => m,
+        info!("spawn_agents: {} subconversations finished", order.len());
+        CallToolResult::success(vec![Content::text(format!(
+            "All subconversations finished.\n\n{}",
+            report
+        ))])
+    }
+
+    async fn handle_list_conversations(
+        &self,
+        args: serde_json::Map<String, serde_json::Value>,
+    ) -> CallToolResult {
+        let args: ListConversationsArgs = match serde_json::from_value(serde_json::Value::Object(args)) {
+            Ok(a) => a,
+            Err(e) => return invalid_arguments("list_conversations", e),
+        };
+        let Some(user_id) = args.context.user_id.as_deref().map(UserId::from_string) else {
+            return missing_user("list_conversations");
+        };
+
+        match self.inner.coordinator.list_conversations(&user_id, args.include_archived).await {
+            Ok(mut conversations) => {
+                if let Some(limit) = args.limit {
+                    conversations.truncate(limit);
+                }
+                json_result(&conversations)
+            }
             Err(e) => {
-                return CallToolResult::error(vec![Content::text(format!(
-                    "Failed to create model '{}': {}",
-                    model_id, e
-                ))]);
+                error!("Failed to list conversations: {}", e);
+                CallToolResult::error(vec![Content::text(format!(
+                    "Failed to list conversations: {}",
+                    e
+                ))])
+            }
+        }
+    }
+
+    async fn handle_get_conversation(
+        &self,
+        args: serde_json::Map<String, serde_json::Value>,
+    ) -> CallToolResult {
+        let args: GetConversationArgs = match serde_json::from_value(serde_json::Value::Object(args)) {
+            Ok(a) => a,
+            Err(e) => return invalid_arguments("get_conversation", e),
+        };
+        let Some(user_id) = args.context.user_id.as_deref().map(UserId::from_string) else {
+            return missing_user("get_conversation");
+        };
+        let conversation_id = ConversationId::from_string(&args.conversation_id);
+
+        match self.inner.coordinator.get_conversation(&user_id, &conversation_id).await {
+            Ok(Some(conversation)) => json_result(&conversation),
+            Ok(None) => CallToolResult::error(vec![Content::text(format!(
+                "Conversation not found: {}",
+                args.conversation_id
+            ))]),
+            Err(e) => {
+                error!("Failed to get conversation {}: {}", args.conversation_id, e);
+                CallToolResult::error(vec![Content::text(format!(
+                    "Failed to get conversation: {}",
+                    e
+                ))])
+            }
+        }
+    }
+
+    /// Create a subconversation from the tool call and run the agent in it.
+    /// Returns the subconversation ID, or the error result for the tool call.
+    async fn run_spawn_agent(
+        &self,
+        args: serde_json::Map<String, serde_json::Value>,
+        on_message: Option<MessageObserver>,
+    ) -> Result<ConversationId, CallToolResult> {
+        let args: SpawnAgentArgs = match serde_json::from_value(serde_json::Value::Object(args)) {
+            Ok(a) => a,
+            Err(e) => {
+                error!("spawn_agent: invalid arguments: {}", e);
+                return Err(CallToolResult::error(vec![Content::text(format!(
+                    "Invalid arguments: {}. Make sure the agent is enriching tool calls with context.",
+                    e
+                ))]));
             }
         };
 
+        let parent = SpawnParent::from_context("spawn_agent", &args.context)?;
+        let model = parent.create_model()?;
+
+        // 1. Create subconversation
+        let (sub_id, sub_execution_context) = self
+            .create_subconversation(&parent, args.name.as_deref())
+            .await
+            .map_err(|e| {
+                error!("Failed to spawn subconversation: {}", e);
+                CallToolResult::error(vec![Content::text(format!(
+                    "Failed to spawn subconversation: {}",
+                    e
+                ))])
+            })?;
+
+        // 2. Run agent in subconversation
+        let run = SubagentRun {
+            prompt: args.prompt,
+            system_prompt: args.system_prompt,
+            model,
+            execution_context: sub_execution_context,
+            on_message,
+        };
+        if let Err(e) = self.run_subagent(&sub_id, run).await {
+            error!("Failed to run agent in subconversation: {}", e);
+            return Err(CallToolResult::error(vec![Content::text(format!(
+                "Failed to run agent: {}",
+                e
+            ))]));
+        }
+
+        info!("spawn_agent: subconversation {} completed", sub_id.as_str());
+        Ok(sub_id)
+    }
+
+    /// Create a subconversation under the calling turn, along with the
+    /// execution context its agent runs in
+    async fn create_subconversation(
+        &self,
+        parent: &SpawnParent,
+        name: Option<&str>,
+    ) -> anyhow::Result<(ConversationId, ExecutionContext)> {
         info!(
             "spawn_agent: creating subconversation from turn {} in conversation {}",
-            turn_id.as_str(), conversation_id.as_str()
+            parent.turn_id.as_str(), parent.conversation_id.as_str()
         );
 
-        // 1. Create subconversation
-        let sub_id = match self
+        let sub_id = self
             .inner
             .coordinator
             .spawn_subconversation(
-                &conversation_id,
-                &user_id,
-                &turn_id,
-                span_id.as_ref(),
-                args.name.as_deref(),
+                &parent.conversation_id,
+                &parent.user_id,
+                &parent.turn_id,
+                parent.span_id.as_ref(),
+                name,
             )
-            .await
-        {
-            Ok(id) => id,
-            Err(e) => {
-                error!("Failed to spawn subconversation: {}", e);
-                return CallToolResult::error(vec![Content::text(format!(
-                    "Failed to spawn subconversation: {}",
-                    e
-                ))]);
-            }
-        };
+            .await?;
 
         info!("spawn_agent: created subconversation {}", sub_id.as_str());
 
         // Build execution context for the subconversation's agent
         // (so nested spawn calls also get proper context)
         let sub_execution_context = ExecutionContext {
-            user_id: Some(user_id.as_str().to_string()),
+            user_id: Some(parent.user_id.as_str().to_string()),
             conversation_id: Some(sub_id.as_str().to_string()),
-            turn_id: Some(turn_id.as_str().to_string()), // Will be updated when subconversation creates its first turn
-            span_id: span_id.as_ref().map(|s| s.as_str().to_string()),
-            model_id: Some(model_id.clone()),
+            turn_id: Some(parent.turn_id.as_str().to_string()), // Will be updated when subconversation creates its first turn
+            span_id: parent.span_id.as_ref().map(|s| s.as_str().to_string()),
+            model_id: Some(parent.model_id.clone()),
         };
+        Ok((sub_id, sub_execution_context))
+    }
 
-        // 2. Run agent in subconversation
-        if let Err(e) = self
-            .inner
+    /// Run an agent on `prompt` in an existing subconversation
+    async fn run_subagent(&self, sub_id: &ConversationId, run: SubagentRun) -> anyhow::Result<()> {
+        self.inner
             .coordinator
             .run_agent_in_subconversation(
-                &sub_id,
-                args.prompt,
-                args.system_prompt,
-                model,
+                sub_id,
+                run,
                 Arc::clone(&self.inner.mcp_registry),
                 Arc::clone(&self.inner.document_resolver),
-                sub_execution_context,
             )
             .await
-        {
-            error!("Failed to run agent in subconversation: {}", e);
-            return CallToolResult::error(vec![Content::text(format!(
-                "Failed to run agent: {}",
-                e
-            ))]);
-        }
+    }
+}
 
-        // 3. Get result
-        let result = match self.inner.coordinator.get_subconversation_result(&sub_id).await {
-            Ok(Some(r)) => r,
-            Ok(None) => "(no result)".to_string(),
-            Err(e) => {
-                error!("Failed to get subconversation result: {}", e);
-                return CallToolResult::error(vec![Content::text(format!(
-                    "Failed to get result: {}",
-                    e
-                ))]);
-            }
-        };
+fn invalid_arguments(tool: &str, e: serde_json::Error) -> CallToolResult {
+    error!("{}: invalid arguments: {}", tool, e);
+    CallToolResult::error(vec![Content::text(format!("Invalid arguments: {}", e))])
+}
 
-        info!("spawn_agent: subconversation {} completed", sub_id.as_str());
+fn missing_user(tool: &str) -> CallToolResult {
+    error!("{}: execution context has no user", tool);
+    CallToolResult::error(vec![Content::text(format!(
+        "{}: execution context has no user. The agent should inject _context.",
+        tool
+    ))])
+}
 
-        CallToolResult::success(vec![Content::text(format!(
-            "Subconversation completed.\n\nSubconversation ID: {}\n\nResult:\n{}",
-            sub_id.as_str(),
-            result
-        ))])
+/// Return a value as pretty-printed JSON text
+fn json_result(value: &impl Serialize) -> CallToolResult {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => CallToolResult::success(vec![Content::text(json)]),
+        Err(e) => CallToolResult::error(vec![Content::text(format!("Failed to encode result: {}", e))]),
     }
 }
 
@@ -366,7 +991,9 @@ impl ServerHandler for NoemaCoreServer {
             capabilities: ServerCapabilities::builder().enable_tools().build(),
             server_info: Implementation::from_build_env(),
             instructions: Some(
-                "Noema Core MCP server. Provides spawn_agent for creating subconversations."
+                "Noema Core MCP server. Provides spawn_agent, spawn_agent_streaming and \
+                 spawn_agents for creating subconversations, and list_conversations and get_conversation for \
+                 reading the user's prior conversations."
                     .into(),
             ),
         }
@@ -386,7 +1013,7 @@ impl ServerHandler for NoemaCoreServer {
     fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<CallToolResult, McpError>> + Send + '_ {
         async move {
             let name = request.name.as_ref();
@@ -396,6 +1023,12 @@ impl ServerHandler for NoemaCoreServer {
 
             match name {
                 "spawn_agent" => Ok(self.handle_spawn_agent(arguments).await),
+                "spawn_agent_streaming" => {
+                    Ok(self.handle_spawn_agent_streaming(arguments, context).await)
+                }
+                "spawn_agents" => Ok(self.handle_spawn_agents(arguments).await),
+                "list_conversations" => Ok(self.handle_list_conversations(arguments).await),
+                "get_conversation" => Ok(self.handle_get_conversation(arguments).await),
                 _ => Ok(CallToolResult::error(vec![Content::text(format!(
                     "Unknown tool: {}",
                     name
@@ -404,3 +1037,107 @@ impl ServerHandler for NoemaCoreServer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm::mock::MockChatModel;
+    use noema_core::mcp::McpConfig;
+    use noema_core::storage::implementations::memory::{
+        MemoryAssetStore, MemoryBlobStore, MemoryDocumentStore, MemoryEntityStore, MemoryStorage,
+        MemoryTextStore, MemoryTurnStore,
+    };
+
+    fn memory_coordinator() -> Arc<StorageCoordinator<MemoryStorage>> {
+        Arc::new(StorageCoordinator::new(
+            Arc::new(MemoryBlobStore::new()),
+            Arc::new(MemoryAssetStore::new()),
+            Arc::new(MemoryTextStore::new()),
+            Arc::new(MemoryEntityStore::new()),
+            Arc::new(MemoryTurnStore::new()),
+        ))
+    }
+
+    fn server(coordinator: Arc<StorageCoordinator<MemoryStorage>>) -> NoemaCoreServer {
+        NoemaCoreServer::new(
+            coordinator,
+            Arc::new(Mutex::new(McpRegistry::new(McpConfig::default()))),
+            Arc::new(MemoryDocumentStore::new()),
+        )
+    }
+
+    /// Observer that keeps the text of every message it is shown
+    fn recording_observer() -> (MessageObserver, Arc<std::sync::Mutex<Vec<String>>>) {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let observer: MessageObserver = {
+            let seen = Arc::clone(&seen);
+            Arc::new(move |message: &ChatMessage| seen.lock().unwrap().push(message.get_text()))
+        };
+        (observer, seen)
+    }
+
+    #[tokio::test]
+    async fn test_observed_context_reports_only_assistant_messages() {
+        let coordinator = memory_coordinator();
+        let conversation_id = coordinator.create_conversation(&UserId::new(), None).await.unwrap();
+        let mut session = Session::new(coordinator, conversation_id);
+        let (on_message, seen) = recording_observer();
+
+        let mut observed = ObservedContext {
+            inner: &mut session,
+            on_message,
+        };
+        observed.add(ChatMessage::user(ChatPayload::text("question")));
+        observed.add(ChatMessage::assistant(ChatPayload::text("answer")));
+
+        assert_eq!(*seen.lock().unwrap(), vec!["answer"]);
+        assert_eq!(session.pending().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_subagent_reports_messages_and_commits_transcript() {
+        let coordinator = memory_coordinator();
+        let user_id = UserId::new();
+        let parent_id = coordinator.create_conversation(&user_id, None).await.unwrap();
+        let server = server(Arc::clone(&coordinator));
+
+        let parent = SpawnParent {
+            conversation_id: parent_id,
+            user_id,
+            turn_id: TurnId::new(),
+            span_id: None,
+            model_id: "mock/child".to_string(),
+        };
+        let (sub_id, execution_context) = server.create_subconversation(&parent, Some("Research")).await.unwrap();
+
+        let (on_message, seen) = recording_observer();
+        let run = SubagentRun {
+            prompt: "Look it up".to_string(),
+            system_prompt: None,
+            model: Arc::new(MockChatModel::builder("child").text("Found it").build()),
+            execution_context,
+            on_message: Some(on_message),
+        };
+        server.run_subagent(&sub_id, run).await.unwrap();
+
+        // The child's reply reached the parent's observer...
+        assert_eq!(*seen.lock().unwrap(), vec!["Found it"]);
+        // ...and was committed to the subconversation
+        let result = coordinator.get_subconversation_result(&sub_id).await.unwrap();
+        assert_eq!(result.as_deref(), Some("Found it"));
+        let transcript = coordinator.open_session(&sub_id).await.unwrap();
+        assert_eq!(transcript.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_agents_rejects_too_many_tasks() {
+        let server = server(memory_coordinator());
+        let tasks = vec![json!({"prompt": "look it up"}); MAX_SPAWN_AGENTS_TASKS + 1];
+        let args = json!({"tasks": tasks, "_context": ExecutionContext::new()});
+
+        let result = server.handle_spawn_agents(args.as_object().unwrap().clone()).await;
+        assert_eq!(result.is_error, Some(true));
+        let text = result.content[0].as_text().unwrap().text.clone();
+        assert!(text.contains("at most 16"), "{}", text);
+    }
+}
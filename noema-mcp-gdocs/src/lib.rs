@@ -16,62 +16,25 @@ mod well_known;
 pub use google_api::{ExtractedDocument, ExtractedImage, ExtractedTab, GoogleDocsClient};
 pub use tools::GoogleDocsServer;
 
-use http_body_util::{BodyExt, Full};
-use hyper::body::Bytes;
-use hyper::server::conn::http1;
-use hyper::{Request, Response, StatusCode};
-use hyper_util::rt::TokioIo;
-use rmcp::transport::streamable_http_server::{
-    session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
-};
-use std::convert::Infallible;
-use std::net::SocketAddr;
-use std::sync::Arc;
-use tokio::net::TcpListener;
-use tokio::sync::oneshot;
-use tower_service::Service;
-use tracing::info;
+pub use noema_mcp_server::{ServerHandle, DEFAULT_DRAIN_TIMEOUT};
 
-type BoxBody = http_body_util::combinators::BoxBody<Bytes, Infallible>;
+use noema_mcp_server::{json_response, BoxBody, McpHttpServer};
+use std::path::Path;
 
 /// Handle the .well-known/oauth-authorization-server endpoint
-fn handle_well_known() -> Result<Response<BoxBody>, Infallible> {
+fn handle_well_known() -> http::Response<BoxBody> {
     let metadata = well_known::google_oauth_metadata();
-    let json = serde_json::to_string(&metadata).unwrap_or_default();
-
-    let body = Full::new(Bytes::from(json)).map_err(|_| -> Infallible { unreachable!() }).boxed();
-
-    let response = Response::builder()
-        .status(StatusCode::OK)
-        .header("Content-Type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
-        .body(body)
-        .unwrap();
-
-    Ok(response)
+    let mut response = json_response(serde_json::to_string(&metadata).unwrap_or_default());
+    response.headers_mut().insert(
+        http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        http::HeaderValue::from_static("*"),
+    );
+    response
 }
 
-/// Handle to a running server that can be used to stop it
-pub struct ServerHandle {
-    shutdown_tx: oneshot::Sender<()>,
-    port: u16,
-}
-
-impl ServerHandle {
-    /// Get the port the server is running on
-    pub fn port(&self) -> u16 {
-        self.port
-    }
-
-    /// Get the URL for the MCP endpoint
-    pub fn url(&self) -> String {
-        format!("http://127.0.0.1:{}/mcp", self.port)
-    }
-
-    /// Stop the server
-    pub fn stop(self) {
-        let _ = self.shutdown_tx.send(());
-    }
+fn http_server() -> McpHttpServer<GoogleDocsServer> {
+    McpHttpServer::new("Google Docs", GoogleDocsServer::new)
+        .with_route("/.well-known/oauth-authorization-server", handle_well_known)
 }
 
 /// Start the MCP server on a random port
@@ -88,74 +51,46 @@ pub async fn start_server() -> anyhow::Result<ServerHandle> {
 /// The server runs as a spawned task on the current runtime.
 /// We rely on the caller to release any locks before awaiting MCP responses.
 pub async fn start_server_on(host: &str, port: u16) -> anyhow::Result<ServerHandle> {
-    let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
-
-    let listener = TcpListener::bind(addr).await?;
-    let local_addr = listener.local_addr()?;
-    let actual_port = local_addr.port();
-
-    info!("Starting Google Docs MCP server on {}", local_addr);
-
-    let (shutdown_tx, shutdown_rx) = oneshot::channel();
-
-    // Spawn the server loop as a task on the current runtime
-    tokio::spawn(async move {
-        let config = StreamableHttpServerConfig::default();
-        let session_manager = Arc::new(LocalSessionManager::default());
-
-        let mcp_service = StreamableHttpService::new(
-            || Ok(GoogleDocsServer::new()),
-            session_manager,
-            config,
-        );
-
-        // Convert oneshot receiver for use in select
-        let mut shutdown_rx = shutdown_rx;
-
-        loop {
-            tokio::select! {
-                _ = &mut shutdown_rx => {
-                    info!("Shutting down Google Docs MCP server");
-                    break;
-                }
-                result = listener.accept() => {
-                    match result {
-                        Ok((stream, _)) => {
-                            let io = TokioIo::new(stream);
-                            let service = mcp_service.clone();
+    http_server().serve(host, port).await
+}
 
-                            tokio::spawn(async move {
-                                if let Err(err) = http1::Builder::new()
-                                    .serve_connection(
-                                        io,
-                                        hyper::service::service_fn(move |req: Request<hyper::body::Incoming>| {
-                                            let mut svc = service.clone();
-                                            async move {
-                                                // Handle .well-known OAuth discovery endpoint
-                                                if req.uri().path() == "/.well-known/oauth-authorization-server" {
-                                                    return handle_well_known();
-                                                }
-                                                svc.call(req).await
-                                            }
-                                        }),
-                                    )
-                                    .await
-                                {
-                                    tracing::error!("Error serving connection: {:?}", err);
-                                }
-                            });
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to accept connection: {}", e);
-                        }
-                    }
-                }
-            }
-        }
-    });
+/// Start the MCP server on a Unix domain socket
+///
+/// Serves the same endpoints as `start_server_on` without opening a TCP
+/// port. A socket file left at `path` by an earlier run is replaced, and
+/// the file is removed when the server stops. Unix sockets are not
+/// available on Windows, where this returns an error; use
+/// `start_server_on` there.
+pub async fn start_server_uds(path: impl AsRef<Path>) -> anyhow::Result<ServerHandle> {
+    http_server().serve_uds(path).await
+}
 
-    Ok(ServerHandle {
-        shutdown_tx,
-        port: actual_port,
-    })
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    #[tokio::test]
+    async fn test_well_known_endpoint() {
+        let handle = start_server().await.unwrap();
+        let port = handle.port().unwrap();
+
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        stream
+            .write_all(b"GET /.well-known/oauth-authorization-server HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.to_lowercase().contains("access-control-allow-origin: *"));
+
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let metadata: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(metadata["issuer"], "https://accounts.google.com");
+
+        handle.stop_and_wait(Duration::from_secs(2)).await;
+    }
 }
@@ -18,6 +18,10 @@ struct Args {
     #[arg(short = 'H', long, default_value = "127.0.0.1")]
     host: String,
 
+    /// Listen on this Unix socket instead of a TCP port
+    #[arg(short, long, conflicts_with_all = ["port", "host"])]
+    socket: Option<std::path::PathBuf>,
+
     /// Log level
     #[arg(short, long, default_value = "info")]
     log_level: String,
@@ -45,7 +49,10 @@ async fn main() -> anyhow::Result<()> {
     tracing::subscriber::set_global_default(subscriber)?;
 
     // Start the server
-    let handle = noema_mcp_gdocs::start_server_on(&args.host, args.port).await?;
+    let handle = match &args.socket {
+        Some(path) => noema_mcp_gdocs::start_server_uds(path).await?,
+        None => noema_mcp_gdocs::start_server_on(&args.host, args.port).await?,
+    };
 
     println!("Google Docs MCP server running at {}", handle.url());
     println!("Press Ctrl+C to stop");
@@ -53,6 +60,6 @@ async fn main() -> anyhow::Result<()> {
     // Wait for shutdown signal
     tokio::signal::ctrl_c().await?;
 
-    handle.stop();
+    handle.stop_and_wait(noema_mcp_gdocs::DEFAULT_DRAIN_TIMEOUT).await;
     Ok(())
 }
@@ -0,0 +1,465 @@
+//! HTTP hosting for Noema's MCP servers
+//!
+//! Serves an MCP service over streamable HTTP on a TCP port or a Unix
+//! domain socket, next to a `/health` endpoint and any plain HTTP routes
+//! the server adds (e.g. OAuth discovery). Stopping a server drains its
+//! open connections before aborting them.
+//!
+//! ```ignore
+//! let handle = McpHttpServer::new("Google Docs", GoogleDocsServer::new)
+//!     .with_route("/.well-known/oauth-authorization-server", handle_well_known)
+//!     .serve("127.0.0.1", 0)
+//!     .await?;
+//! ```
+
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper::server::conn::http1;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use rmcp::transport::streamable_http_server::{
+    session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
+};
+use rmcp::RoleServer;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, watch};
+use tokio::task::{JoinHandle, JoinSet};
+use tower_service::Service;
+use tracing::info;
+
+pub type BoxBody = http_body_util::combinators::BoxBody<Bytes, Infallible>;
+
+/// Answers a plain HTTP endpoint
+pub type RouteHandler = fn() -> Response<BoxBody>;
+
+/// A 200 response with a JSON body
+pub fn json_response(json: String) -> Response<BoxBody> {
+    let body = Full::new(Bytes::from(json)).map_err(|_| -> Infallible { unreachable!() }).boxed();
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .unwrap()
+}
+
+/// Handle the /health endpoint
+///
+/// Answered before the request reaches the MCP service, so it never
+/// creates or touches an MCP session.
+fn handle_health(started: Instant, port: Option<u16>) -> Response<BoxBody> {
+    let health = serde_json::json!({
+        "status": "ok",
+        "uptime_secs": started.elapsed().as_secs(),
+        "port": port,
+    });
+    json_response(health.to_string())
+}
+
+/// How long `ServerHandle::stop` lets in-flight connections finish before
+/// abandoning them
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Handle to a running server that can be used to stop it
+pub struct ServerHandle {
+    shutdown_tx: oneshot::Sender<Duration>,
+    task: JoinHandle<()>,
+    address: Address,
+}
+
+/// Where a running server listens
+enum Address {
+    Tcp(u16),
+    #[cfg_attr(not(unix), allow(dead_code))]
+    Unix(PathBuf),
+}
+
+impl ServerHandle {
+    /// Get the TCP port the server is running on (None for a Unix socket)
+    pub fn port(&self) -> Option<u16> {
+        match &self.address {
+            Address::Tcp(port) => Some(*port),
+            Address::Unix(_) => None,
+        }
+    }
+
+    /// Get the Unix socket the server is listening on (None for TCP)
+    pub fn socket_path(&self) -> Option<&Path> {
+        match &self.address {
+            Address::Tcp(_) => None,
+            Address::Unix(path) => Some(path),
+        }
+    }
+
+    /// Get the URL for the MCP endpoint
+    ///
+    /// For a Unix socket this is an `http+unix://` URL whose host is the
+    /// percent-encoded socket path.
+    pub fn url(&self) -> String {
+        match &self.address {
+            Address::Tcp(port) => format!("http://127.0.0.1:{}/mcp", port),
+            Address::Unix(path) => format!(
+                "http+unix://{}/mcp",
+                path.to_string_lossy().replace('%', "%25").replace('/', "%2F")
+            ),
+        }
+    }
+
+    /// Stop accepting connections without waiting for in-flight ones.
+    ///
+    /// Active connections are asked to finish and are aborted after
+    /// `DEFAULT_DRAIN_TIMEOUT`.
+    pub fn stop(self) {
+        let _ = self.shutdown_tx.send(DEFAULT_DRAIN_TIMEOUT);
+    }
+
+    /// Stop accepting connections and wait for in-flight ones to finish.
+    ///
+    /// Connections still open after `timeout` (e.g. long-lived event
+    /// streams) are aborted before this returns.
+    pub async fn stop_and_wait(self, timeout: Duration) {
+        let _ = self.shutdown_tx.send(timeout);
+        let _ = self.task.await;
+    }
+}
+
+/// An MCP service to serve over HTTP, along with its plain HTTP routes
+pub struct McpHttpServer<S> {
+    /// Used in log messages, e.g. "Google Docs"
+    name: &'static str,
+    make_service: Arc<dyn Fn() -> S + Send + Sync>,
+    routes: Vec<(&'static str, RouteHandler)>,
+}
+
+impl<S> McpHttpServer<S>
+where
+    S: rmcp::Service<RoleServer> + Send + 'static,
+{
+    /// Serve the MCP service built by `make_service` for each session
+    pub fn new(name: &'static str, make_service: impl Fn() -> S + Send + Sync + 'static) -> Self {
+        Self {
+            name,
+            make_service: Arc::new(make_service),
+            routes: Vec::new(),
+        }
+    }
+
+    /// Answer requests to `path` with `handler` instead of the MCP service
+    pub fn with_route(mut self, path: &'static str, handler: RouteHandler) -> Self {
+        self.routes.push((path, handler));
+        self
+    }
+
+    /// Start the server on the specified host and port
+    ///
+    /// Use port 0 to get a random available port.
+    ///
+    /// The server runs as a spawned task on the current runtime.
+    /// We rely on the caller to release any locks before awaiting MCP responses.
+    pub async fn serve(self, host: &str, port: u16) -> anyhow::Result<ServerHandle> {
+        let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
+
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+
+        info!("Starting {} MCP server on {}", self.name, local_addr);
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let task = self.spawn(listener, Some(local_addr.port()), shutdown_rx);
+
+        Ok(ServerHandle {
+            shutdown_tx,
+            task,
+            address: Address::Tcp(local_addr.port()),
+        })
+    }
+
+    /// Start the server on a Unix domain socket
+    ///
+    /// Serves the same endpoints as `serve` without opening a TCP port. A
+    /// socket file left at `path` by an earlier run is replaced, and the
+    /// file is removed when the server stops. Unix sockets are not
+    /// available on Windows, where this returns an error; use `serve`
+    /// there.
+    pub async fn serve_uds(self, path: impl AsRef<Path>) -> anyhow::Result<ServerHandle> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+
+            let path = path.as_ref().to_path_buf();
+            if std::fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_socket()) {
+                std::fs::remove_file(&path)?;
+            }
+            let listener = UnixSocketListener {
+                listener: tokio::net::UnixListener::bind(&path)?,
+                path: path.clone(),
+            };
+
+            info!("Starting {} MCP server on {}", self.name, path.display());
+
+            let (shutdown_tx, shutdown_rx) = oneshot::channel();
+            let task = self.spawn(listener, None, shutdown_rx);
+
+            Ok(ServerHandle {
+                shutdown_tx,
+                task,
+                address: Address::Unix(path),
+            })
+        }
+
+        #[cfg(not(unix))]
+        {
+            anyhow::bail!(
+                "Cannot listen on {}: Unix domain sockets are not supported on this platform, use serve to listen on TCP",
+                path.as_ref().display()
+            )
+        }
+    }
+
+    /// Spawn the server loop as a task on the current runtime
+    ///
+    /// `port` is reported by the health endpoint (None for a Unix socket).
+    fn spawn<L: Listener>(
+        self,
+        listener: L,
+        port: Option<u16>,
+        shutdown_rx: oneshot::Receiver<Duration>,
+    ) -> JoinHandle<()> {
+        let started = Instant::now();
+        let Self { name, make_service, routes } = self;
+        let routes: Arc<[(&'static str, RouteHandler)]> = routes.into();
+
+        tokio::spawn(async move {
+            let config = StreamableHttpServerConfig::default();
+            let session_manager = Arc::new(LocalSessionManager::default());
+
+            let mcp_service = StreamableHttpService::new(
+                move || Ok(make_service()),
+                session_manager,
+                config,
+            );
+
+            let mut shutdown_rx = shutdown_rx;
+
+            // Flipped to true to ask open connections to finish
+            let (closing_tx, closing_rx) = watch::channel(false);
+            let mut connections = JoinSet::new();
+
+            let drain_timeout = loop {
+                tokio::select! {
+                    timeout = &mut shutdown_rx => {
+                        info!("Shutting down {} MCP server", name);
+                        break timeout.unwrap_or(DEFAULT_DRAIN_TIMEOUT);
+                    }
+                    Some(_) = connections.join_next(), if !connections.is_empty() => {}
+                    result = listener.accept() => {
+                        match result {
+                            Ok(stream) => {
+                                let io = TokioIo::new(stream);
+                                let service = mcp_service.clone();
+                                let routes = Arc::clone(&routes);
+                                let mut closing = closing_rx.clone();
+
+                                connections.spawn(async move {
+                                    let conn = http1::Builder::new()
+                                        .serve_connection(
+                                            io,
+                                            hyper::service::service_fn(move |req: Request<hyper::body::Incoming>| {
+                                                let mut svc = service.clone();
+                                                let route = routes
+                                                    .iter()
+                                                    .find(|(path, _)| req.uri().path() == *path)
+                                                    .map(|(_, handler)| *handler);
+                                                async move {
+                                                    if let Some(handler) = route {
+                                                        return Ok(handler());
+                                                    }
+                                                    if req.method() == Method::GET && req.uri().path() == "/health" {
+                                                        return Ok(handle_health(started, port));
+                                                    }
+                                                    svc.call(req).await
+                                                }
+                                            }),
+                                        );
+                                    tokio::pin!(conn);
+
+                                    let result = tokio::select! {
+                                        result = conn.as_mut() => result,
+                                        _ = closing.changed() => {
+                                            conn.as_mut().graceful_shutdown();
+                                            conn.await
+                                        }
+                                    };
+                                    if let Err(err) = result {
+                                        tracing::error!("Error serving connection: {:?}", err);
+                                    }
+                                });
+                            }
+                            Err(e) => {
+                                tracing::error!("Failed to accept connection: {}", e);
+                            }
+                        }
+                    }
+                }
+            };
+
+            drop(listener);
+            drain_connections(connections, &closing_tx, drain_timeout).await;
+        })
+    }
+}
+
+/// A socket the server accepts connections on
+trait Listener: Send + 'static {
+    type Io: AsyncRead + AsyncWrite + Unpin + Send + 'static;
+
+    fn accept(&self) -> impl Future<Output = std::io::Result<Self::Io>> + Send;
+}
+
+impl Listener for TcpListener {
+    type Io = TcpStream;
+
+    async fn accept(&self) -> std::io::Result<TcpStream> {
+        TcpListener::accept(self).await.map(|(stream, _)| stream)
+    }
+}
+
+/// Unix socket listener that removes its socket file when dropped
+#[cfg(unix)]
+struct UnixSocketListener {
+    listener: tokio::net::UnixListener,
+    path: PathBuf,
+}
+
+#[cfg(unix)]
+impl Listener for UnixSocketListener {
+    type Io = tokio::net::UnixStream;
+
+    async fn accept(&self) -> std::io::Result<tokio::net::UnixStream> {
+        self.listener.accept().await.map(|(stream, _)| stream)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for UnixSocketListener {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Ask open connections to finish their current request, waiting up to
+/// `timeout` before aborting the rest
+async fn drain_connections(
+    mut connections: JoinSet<()>,
+    closing_tx: &watch::Sender<bool>,
+    timeout: Duration,
+) {
+    let _ = closing_tx.send(true);
+    let drained = tokio::time::timeout(timeout, async {
+        while connections.join_next().await.is_some() {}
+    })
+    .await;
+    if drained.is_err() {
+        tracing::warn!(
+            "Aborting {} connections still open after {:?}",
+            connections.len(),
+            timeout
+        );
+        connections.shutdown().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// MCP server without any tools
+    struct EmptyServer;
+
+    impl rmcp::ServerHandler for EmptyServer {}
+
+    fn handle_ping() -> Response<BoxBody> {
+        json_response("\"pong\"".to_string())
+    }
+
+    fn server() -> McpHttpServer<EmptyServer> {
+        McpHttpServer::new("Test", || EmptyServer).with_route("/ping", handle_ping)
+    }
+
+    #[tokio::test]
+    async fn test_stop_and_wait_closes_open_connections() {
+        let handle = server().serve("127.0.0.1", 0).await.unwrap();
+        let port = handle.port().unwrap();
+
+        // A keep-alive connection that stays open after its request
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        stream
+            .write_all(b"GET /ping HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = [0u8; 1024];
+        let n = stream.read(&mut buf).await.unwrap();
+        assert!(buf[..n].starts_with(b"HTTP/1.1 200"));
+
+        // The idle connection finishes right away instead of running into the timeout
+        tokio::time::timeout(Duration::from_secs(2), handle.stop_and_wait(Duration::from_secs(10)))
+            .await
+            .expect("server did not drain its connections");
+        assert_eq!(stream.read(&mut buf).await.unwrap(), 0);
+        assert!(TcpStream::connect(("127.0.0.1", port)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint() {
+        let handle = server().serve("127.0.0.1", 0).await.unwrap();
+        let port = handle.port().unwrap();
+
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let health: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(health["status"], "ok");
+        assert_eq!(health["port"], port);
+        assert!(health["uptime_secs"].is_u64());
+
+        handle.stop_and_wait(Duration::from_secs(2)).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_server() {
+        let path = std::env::temp_dir().join(format!("noema-mcp-server-{}.sock", std::process::id()));
+        let handle = server().serve_uds(&path).await.unwrap();
+        assert_eq!(handle.port(), None);
+        assert_eq!(handle.socket_path(), Some(path.as_path()));
+        assert!(handle.url().starts_with("http+unix://%2F"));
+
+        let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET /ping HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = [0u8; 1024];
+        let n = stream.read(&mut buf).await.unwrap();
+        assert!(buf[..n].starts_with(b"HTTP/1.1 200"));
+
+        // Stopping removes the socket file
+        handle.stop_and_wait(Duration::from_secs(2)).await;
+        assert!(!path.exists());
+    }
+}